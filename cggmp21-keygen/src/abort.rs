@@ -0,0 +1,78 @@
+//! Graceful abort
+//!
+//! A party that has to stop the protocol (e.g. due to operator intervention or policy denial)
+//! can notify other parties by broadcasting [`MsgAbort`]. Every protocol message has a dedicated
+//! variant for it. Once abort message is received, other parties stop executing the protocol
+//! and return an error that identifies the party who aborted it, instead of waiting for the
+//! remaining messages until timeout.
+//!
+//! Abort message is not part of any round, it can be sent at any point of the protocol
+//! via the same delivery channel as other protocol messages:
+//! ```rust,no_run
+//! # async fn abort<M, S>(mut outgoings: S) -> Result<(), S::Error>
+//! # where
+//! #     M: round_based::rounds_router::RoundMessage<cggmp21_keygen::abort::MsgAbort>,
+//! #     S: futures::Sink<round_based::Outgoing<M>> + Unpin,
+//! # {
+//! use cggmp21_keygen::abort::MsgAbort;
+//! use futures::SinkExt;
+//!
+//! outgoings.send(MsgAbort::broadcast()).await
+//! # }
+//! ```
+
+use futures::{Stream, StreamExt};
+use round_based::{
+    rounds_router::{ProtocolMessage, RoundMessage},
+    Incoming, Outgoing, PartyIndex,
+};
+use thiserror::Error;
+
+/// Message indicating that sender aborts the protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MsgAbort;
+
+impl MsgAbort {
+    /// Constructs a message that notifies all other parties that protocol is aborted
+    pub fn broadcast<M: RoundMessage<MsgAbort>>() -> Outgoing<M> {
+        Outgoing::broadcast(M::to_protocol_message(MsgAbort))
+    }
+}
+
+/// Protocol was aborted by another party who sent [`MsgAbort`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("party {0} aborted the protocol")]
+pub struct PeerAborted(pub PartyIndex);
+
+/// Error of receiving messages
+#[derive(Debug, Error)]
+pub enum ReceiveError {
+    /// Delivery layer failed to receive a message
+    #[error("receive message")]
+    Io(#[source] Box<dyn std::error::Error + Send + Sync>),
+    /// Another party aborted the protocol
+    #[error(transparent)]
+    PeerAborted(PeerAborted),
+}
+
+/// Intercepts abort messages in the stream of incoming messages
+///
+/// Returned stream yields [`ReceiveError::PeerAborted`] as soon as abort message is received, so
+/// the protocol stops waiting for the round to complete. Other messages are passed through.
+pub fn intercept<M, S, E>(
+    incomings: S,
+) -> impl Stream<Item = Result<Incoming<M>, ReceiveError>> + Unpin
+where
+    M: RoundMessage<MsgAbort>,
+    S: Stream<Item = Result<Incoming<M>, E>> + Unpin,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    incomings.map(|incoming| match incoming {
+        Ok(incoming) if incoming.msg.round() == <M as RoundMessage<MsgAbort>>::ROUND => {
+            Err(ReceiveError::PeerAborted(PeerAborted(incoming.sender)))
+        }
+        Ok(incoming) => Ok(incoming),
+        Err(err) => Err(ReceiveError::Io(Box::new(err))),
+    })
+}
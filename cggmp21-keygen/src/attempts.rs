@@ -0,0 +1,107 @@
+//! Attempts management
+//!
+//! If protocol execution fails (e.g. one of the parties went offline), it may be retried, but
+//! each attempt must use a fresh execution ID. This module helps to keep track of attempts
+//! and derive execution ID for each of them.
+//!
+//! Execution ID is derived deterministically from [structured components](ExecutionIdBuilder)
+//! and attempt number, so all parties obtain the same execution ID as long as they agree on
+//! the components and number of failed attempts.
+//!
+//! ## Example
+//! ```rust
+//! use cggmp21_keygen::attempts::Attempts;
+//! use cggmp21_keygen::execution_id::{ExecutionId, ProtocolKind};
+//!
+//! let mut attempts = Attempts::new();
+//! let operation = "sign tx #1";
+//! let components = ExecutionId::builder(b"my-wallet-app").protocol(ProtocolKind::Signing);
+//!
+//! let eid1 = attempts.execution_id(&operation, components);
+//! // protocol failed, we need to retry
+//! attempts.record_failure(operation);
+//! let eid2 = attempts.execution_id(&operation, components);
+//! assert_ne!(eid1, eid2);
+//!
+//! // protocol succeeded
+//! attempts.record_success(&operation);
+//! assert_eq!(attempts.current(&operation), 0);
+//! ```
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::execution_id::{DerivedExecutionId, ExecutionIdBuilder};
+
+/// Tracks attempt counters per logical operation
+///
+/// `K` identifies a logical operation, e.g. it could be an ID of transaction being signed.
+#[derive(Debug, Clone)]
+pub struct Attempts<K> {
+    counters: HashMap<K, u64>,
+    max_attempts: Option<u64>,
+}
+
+impl<K: Hash + Eq> Attempts<K> {
+    /// Constructs an empty tracker
+    pub fn new() -> Self {
+        Self {
+            counters: HashMap::new(),
+            max_attempts: None,
+        }
+    }
+
+    /// Limits amount of attempts per logical operation
+    ///
+    /// When limit is reached, [`record_failure`](Self::record_failure) returns `None`. Not
+    /// limited by default.
+    pub fn with_max_attempts(self, max_attempts: u64) -> Self {
+        Self {
+            max_attempts: Some(max_attempts),
+            ..self
+        }
+    }
+
+    /// Returns number of the current attempt (starting from 0)
+    pub fn current(&self, operation: &K) -> u64 {
+        self.counters.get(operation).copied().unwrap_or(0)
+    }
+
+    /// Derives execution ID for current attempt
+    ///
+    /// Attempt number set in `components` is overwritten.
+    pub fn execution_id(
+        &self,
+        operation: &K,
+        components: ExecutionIdBuilder,
+    ) -> DerivedExecutionId {
+        components.attempt(self.current(operation)).derive()
+    }
+
+    /// Records that current attempt failed
+    ///
+    /// Returns number of the next attempt, or `None` if attempts limit is reached. All parties
+    /// must record failure, otherwise they won't agree on execution ID.
+    pub fn record_failure(&mut self, operation: K) -> Option<u64> {
+        let counter = self.counters.entry(operation).or_insert(0);
+        let next = counter.checked_add(1)?;
+        if matches!(self.max_attempts, Some(max) if next >= max) {
+            return None;
+        }
+        *counter = next;
+        Some(next)
+    }
+
+    /// Records that operation completed
+    ///
+    /// Attempt counter of the operation is dropped.
+    pub fn record_success(&mut self, operation: &K) {
+        self.counters.remove(operation);
+    }
+}
+
+impl<K: Hash + Eq> Default for Attempts<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
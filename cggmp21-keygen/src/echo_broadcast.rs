@@ -0,0 +1,65 @@
+//! Echo broadcast
+//!
+//! Generic layer that makes any broadcast round reliable. After receiving all messages
+//! of a broadcast round, each party hashes them (including its own message) and sends the
+//! hash to everyone in an extra _echo_ round. If any party received a different hash, it
+//! means that someone sent different messages to different parties, and protocol must be
+//! aborted.
+//!
+//! Echo round is registered in the rounds router just like any other round, so enabling
+//! echo broadcast simply adds one extra round after every broadcast round of the protocol.
+
+use digest::Digest;
+use round_based::{rounds_router::simple_store::RoundMsgs, MsgId, PartyIndex};
+
+/// Echo message
+///
+/// Contains hash of all messages received at broadcast round `ROUND`. Const parameter
+/// is used to distinguish echo messages of different rounds within one protocol.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct MsgEcho<D: Digest, const ROUND: u16>(pub digest::Output<D>);
+
+#[derive(udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.echo_broadcast")]
+struct Tag<'a> {
+    #[udigest(as_bytes)]
+    sid: &'a [u8],
+    round: u16,
+}
+
+impl<D: Digest, const ROUND: u16> MsgEcho<D, ROUND> {
+    /// Hashes all messages received at broadcast round
+    ///
+    /// `msgs` must include message sent by local party, i.e. it's expected to be
+    /// obtained via [`RoundMsgs::iter_including_me`].
+    pub fn new(sid: &[u8], msgs: impl Iterator<Item = impl udigest::Digestable>) -> Self {
+        Self::new_with_digest::<D>(sid, msgs)
+    }
+
+    /// Hashes all messages received at broadcast round using digest `H`
+    ///
+    /// Same as [`new`](Self::new), but allows hashing messages with a digest different from
+    /// the one used in the protocol. All parties must use the same digest `H`.
+    pub fn new_with_digest<H>(
+        sid: &[u8],
+        msgs: impl Iterator<Item = impl udigest::Digestable>,
+    ) -> Self
+    where
+        H: Digest<OutputSize = D::OutputSize>,
+    {
+        Self(udigest::Tag::<H>::new_structured(Tag { sid, round: ROUND }).digest_iter(msgs))
+    }
+
+    /// Returns list of parties who echoed a different hash
+    ///
+    /// Empty list means that broadcast round was reliable.
+    pub fn find_mismatched(&self, echoes: RoundMsgs<Self>) -> Vec<(PartyIndex, MsgId)> {
+        echoes
+            .into_iter_indexed()
+            .filter(|(_j, _msg_id, echo)| echo.0 != self.0)
+            .map(|(j, msg_id, _)| (j, msg_id))
+            .collect()
+    }
+}
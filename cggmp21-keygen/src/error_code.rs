@@ -0,0 +1,207 @@
+//! Stable error codes
+//!
+//! Errors returned by the protocols are opaque: their internal structure is not exposed and may
+//! change between versions. Each protocol error provides [`ErrorCode`] that identifies the reason
+//! of failure and is kept stable across versions, so applications (e.g. FFI bindings or log
+//! pipelines) can branch on it without matching the error message. Codes are grouped by ranges:
+//!
+//! * `1000..2000` — failures not caused by other parties: i/o errors, invalid arguments, bugs
+//! * `2000..3000` — other party deviated from the protocol, [`ErrorReport::parties`] lists the
+//!   parties to blame
+//! * `3000..4000` — protocol was not allowed to proceed by local configuration (e.g. policy,
+//!   approval hook or rate limiter)
+//!
+//! Numeric value and string name of existing codes never change. New codes may be added in
+//! minor versions, so applications should handle unknown codes.
+//!
+//! [`ErrorReport`] bundles the code with human-readable message and blamed parties, and can be
+//! serialized, e.g. to be sent over FFI boundary or written to the logs.
+
+use round_based::PartyIndex;
+
+macro_rules! error_codes {
+    ($($(#[$doc:meta])* $variant:ident = $code:literal, $name:literal;)+) => {
+        /// Stable code identifying reason of the failure
+        ///
+        /// Serialized as a string name of the code, e.g. `"invalid_schnorr_proof"`.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[repr(u16)]
+        #[non_exhaustive]
+        pub enum ErrorCode {$(
+            $(#[$doc])*
+            #[cfg_attr(feature = "serde", serde(rename = $name))]
+            $variant = $code,
+        )+}
+
+        impl ErrorCode {
+            /// List of all error codes
+            pub const ALL: &'static [ErrorCode] = &[$(Self::$variant),+];
+
+            /// Returns string name of the code
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $name,)+
+                }
+            }
+
+            /// Parses error code from its numeric value
+            ///
+            /// Returns `None` if code is unknown
+            pub fn from_code(code: u16) -> Option<Self> {
+                match code {
+                    $($code => Some(Self::$variant),)+
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// Delivery layer failed to send a message
+    SendMessage = 1000, "send_message";
+    /// Delivery layer failed to receive a message
+    ReceiveMessage = 1001, "receive_message";
+    /// Delivery layer was closed before the protocol completed
+    UnexpectedEof = 1002, "unexpected_eof";
+    /// Received message couldn't be routed: it was sent to wrong round, or sender sent more than
+    /// one message at the round
+    MalformedMessage = 1003, "malformed_message";
+    /// Another party aborted the protocol
+    PeerAborted = 1004, "peer_aborted";
+    /// Execution ID was rejected by the registry
+    ExecutionIdRejected = 1005, "execution_id_rejected";
+    /// Protocol was started with invalid arguments
+    InvalidArgs = 1006, "invalid_args";
+    /// Provided key share is not valid
+    InvalidKeyShare = 1007, "invalid_key_share";
+    /// Pregenerated primes are Blum primes, but they're not allowed
+    BlumPrimesNotAllowed = 1008, "blum_primes_not_allowed";
+    /// Local Paillier decryption backend failed
+    PaillierBackend = 1009, "paillier_backend";
+    /// Message to sign was not provided
+    MessageNotProvided = 1010, "message_not_provided";
+    /// Party runs incompatible version of the protocol
+    IncompatibleVersion = 1011, "incompatible_version";
+    /// Internal error, indicates a bug
+    InternalError = 1999, "internal_error";
+
+    /// Party decommitment doesn't match its commitment
+    InvalidDecommitment = 2000, "invalid_decommitment";
+    /// Party sent invalid Schnorr proof
+    InvalidSchnorrProof = 2001, "invalid_schnorr_proof";
+    /// Party sent secret share inconsistent with its Feldman VSS commitment
+    FeldmanVerificationFailed = 2002, "feldman_verification_failed";
+    /// Party sent a message with missing data or data of wrong size
+    InvalidDataSize = 2003, "invalid_data_size";
+    /// Parties received different messages over broadcast channel
+    BroadcastNotReliable = 2004, "broadcast_not_reliable";
+    /// Party didn't contribute to the chain code
+    MissingChainCode = 2005, "missing_chain_code";
+    /// Party didn't commit to proof of possession nonce
+    MissingPopCommitment = 2006, "missing_pop_commitment";
+    /// Party sent invalid share of proof of possession
+    InvalidPopShare = 2007, "invalid_pop_share";
+    /// Party polynomial doesn't share its original key share
+    PolynomialNotBoundToShare = 2008, "polynomial_not_bound_to_share";
+    /// Party sent invalid $\Pi^\text{mod}$ proof
+    InvalidModProof = 2009, "invalid_mod_proof";
+    /// Party sent invalid $\Pi^\text{fac}$ proof
+    InvalidFacProof = 2010, "invalid_fac_proof";
+    /// Party sent invalid ring-Pedersen parameters
+    InvalidRingPedersenParameters = 2011, "invalid_ring_pedersen_parameters";
+    /// Party Paillier modulus is equal to or shares a factor with modulus of another party
+    RelatedPaillierModuli = 2012, "related_paillier_moduli";
+    /// Party sent malformed public share
+    InvalidPublicShare = 2013, "invalid_public_share";
+    /// Party sent secret share that doesn't match its public share
+    InvalidSecretShare = 2014, "invalid_secret_share";
+    /// Message of the party could not be decrypted
+    UndecryptableMessage = 2015, "undecryptable_message";
+    /// Party sent Paillier ciphertext out of range $[1, N^2)$
+    MalformedCiphertext = 2016, "malformed_ciphertext";
+    /// Party sent invalid $\Pi^\text{enc}$ proof
+    InvalidEncProof = 2017, "invalid_enc_proof";
+    /// Party sent invalid $\psi$, $\hat \psi$ or $\psi'$ proofs at presigning
+    InvalidPsiProofs = 2018, "invalid_psi_proofs";
+    /// Party sent invalid $\psi''$ proof at presigning
+    InvalidPsiPrimePrimeProof = 2019, "invalid_psi_prime_prime_proof";
+    /// Sum of $\Delta_j$ doesn't match $\delta \cdot G$
+    MismatchedDelta = 2020, "mismatched_delta";
+    /// Resulting signature is not valid
+    InvalidSignature = 2021, "invalid_signature";
+
+    /// Key usage policy is violated
+    PolicyViolation = 3000, "policy_violation";
+    /// Signing was rejected by approval hook
+    Rejected = 3001, "rejected";
+    /// Signing was not allowed by rate limiter
+    RateLimited = 3002, "rate_limited";
+}
+
+impl ErrorCode {
+    /// Returns numeric value of the code
+    pub fn code(&self) -> u16 {
+        *self as u16
+    }
+
+    /// Indicates that failure was caused by other party deviating from the protocol
+    pub fn is_malicious(&self) -> bool {
+        (2000..3000).contains(&self.code())
+    }
+}
+
+impl core::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Serializable report of protocol failure
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ErrorReport {
+    /// Error code
+    pub code: ErrorCode,
+    /// Numeric value of the [error code](Self::code)
+    pub numeric_code: u16,
+    /// Human-readable description of the error, including all its sources
+    ///
+    /// Message is not stable and must not be used to distinguish errors
+    pub message: String,
+    /// Parties the failure is attributed to
+    ///
+    /// Lists parties who deviated from the protocol if [`ErrorCode::is_malicious`], or the party
+    /// who aborted the protocol if code is [`ErrorCode::PeerAborted`]. Empty otherwise.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub parties: Vec<PartyIndex>,
+}
+
+impl ErrorReport {
+    /// Builds a report for the error
+    pub fn new(
+        code: ErrorCode,
+        error: &(dyn std::error::Error + 'static),
+        mut parties: Vec<PartyIndex>,
+    ) -> Self {
+        let mut message = error.to_string();
+        let mut source = error.source();
+        while let Some(err) = source {
+            message += ": ";
+            message += &err.to_string();
+            source = err.source();
+        }
+        parties.sort_unstable();
+        parties.dedup();
+        Self {
+            code,
+            numeric_code: code.code(),
+            message,
+            parties,
+        }
+    }
+}
@@ -6,6 +6,9 @@ use round_based::rounds_router::{
 };
 use thiserror::Error;
 
+use crate::abort::{PeerAborted, ReceiveError};
+use crate::error_code::ErrorCode;
+
 pub type BoxedError = Box<dyn std::error::Error + Send + Sync>;
 
 #[derive(Debug, Error)]
@@ -16,6 +19,8 @@ pub enum IoError {
     ReceiveMessage(#[source] BoxedError),
     #[error("got eof while recieving messages")]
     ReceiveMessageEof,
+    #[error(transparent)]
+    PeerAborted(PeerAborted),
     #[error("route received message (possibly malicious behavior)")]
     RouteReceivedError(router_error::CompleteRoundError<RoundInputError, Infallible>),
 }
@@ -25,12 +30,13 @@ impl IoError {
         Self::SendMessage(Box::new(err))
     }
 
-    pub fn receive_message<E: std::error::Error + Send + Sync + 'static>(
-        err: CompleteRoundError<RoundInputError, E>,
-    ) -> Self {
+    pub fn receive_message(err: CompleteRoundError<RoundInputError, ReceiveError>) -> Self {
         match err {
-            CompleteRoundError::Io(router_error::IoError::Io(e)) => {
-                Self::ReceiveMessage(Box::new(e))
+            CompleteRoundError::Io(router_error::IoError::Io(ReceiveError::Io(e))) => {
+                Self::ReceiveMessage(e)
+            }
+            CompleteRoundError::Io(router_error::IoError::Io(ReceiveError::PeerAborted(e))) => {
+                Self::PeerAborted(e)
             }
             CompleteRoundError::Io(router_error::IoError::UnexpectedEof) => Self::ReceiveMessageEof,
 
@@ -40,6 +46,16 @@ impl IoError {
             CompleteRoundError::Other(e) => Self::RouteReceivedError(CompleteRoundError::Other(e)),
         }
     }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::SendMessage(_) => ErrorCode::SendMessage,
+            Self::ReceiveMessage(_) => ErrorCode::ReceiveMessage,
+            Self::ReceiveMessageEof => ErrorCode::UnexpectedEof,
+            Self::PeerAborted(_) => ErrorCode::PeerAborted,
+            Self::RouteReceivedError(_) => ErrorCode::MalformedMessage,
+        }
+    }
 }
 
 macro_rules! impl_from {
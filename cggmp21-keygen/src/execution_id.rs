@@ -1,3 +1,5 @@
+//! Execution ID and related utilities
+
 /// Protocol execution ID
 ///
 /// Each protocol execution must have unique execution ID. All signers taking part in the protocol
@@ -18,4 +20,312 @@ impl<'id> ExecutionId<'id> {
     pub fn as_bytes(&self) -> &'id [u8] {
         self.id
     }
+
+    /// Derives an execution ID from structured components
+    ///
+    /// Takes an application ID, which must distinguish your application from any other
+    /// application that may use the same keys. Other components can be set via builder
+    /// methods. All parties must derive execution ID from the same components.
+    ///
+    /// ## Example
+    /// ```rust
+    /// use cggmp21_keygen::execution_id::{ExecutionId, ProtocolKind};
+    ///
+    /// # let key_fingerprint = [0u8; 33];
+    /// let eid = ExecutionId::builder(b"my-wallet-app")
+    ///     .key_fingerprint(&key_fingerprint)
+    ///     .protocol(ProtocolKind::Signing)
+    ///     .attempt(0)
+    ///     .derive();
+    /// let eid = eid.as_execution_id();
+    /// ```
+    pub fn builder(application_id: &[u8]) -> ExecutionIdBuilder {
+        ExecutionIdBuilder::new(application_id)
+    }
+}
+
+/// Derives execution ID from structured components
+///
+/// Obtained via [`ExecutionId::builder`]
+#[derive(Debug, Clone, Copy, udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.execution_id")]
+pub struct ExecutionIdBuilder<'a> {
+    #[udigest(as_bytes)]
+    application_id: &'a [u8],
+    #[udigest(with = crate::utils::encoding::maybe_bytes)]
+    key_fingerprint: Option<&'a [u8]>,
+    #[udigest(with = crate::utils::encoding::maybe_bytes)]
+    protocol: Option<&'a str>,
+    attempt: u64,
+}
+
+impl<'a> ExecutionIdBuilder<'a> {
+    /// Constructs a builder
+    ///
+    /// Same as [`ExecutionId::builder`]
+    pub fn new(application_id: &'a [u8]) -> Self {
+        Self {
+            application_id,
+            key_fingerprint: None,
+            protocol: None,
+            attempt: 0,
+        }
+    }
+
+    /// Specifies fingerprint of the key that's being used
+    ///
+    /// Can be, for instance, the shared public key. Not set by default.
+    pub fn key_fingerprint(self, fingerprint: &'a [u8]) -> Self {
+        Self {
+            key_fingerprint: Some(fingerprint),
+            ..self
+        }
+    }
+
+    /// Specifies which protocol is being carried out
+    ///
+    /// Not set by default.
+    pub fn protocol(self, protocol: ProtocolKind<'a>) -> Self {
+        Self {
+            protocol: Some(protocol.as_str()),
+            ..self
+        }
+    }
+
+    /// Specifies an attempt counter
+    ///
+    /// If protocol execution failed and needs to be retried, fresh execution ID must be used
+    /// for each attempt. Default: `0`.
+    pub fn attempt(self, attempt: u64) -> Self {
+        Self { attempt, ..self }
+    }
+
+    /// Derives execution ID
+    pub fn derive(&self) -> DerivedExecutionId {
+        let tag = udigest::Tag::<sha2::Sha256>::new_structured(Tag { version: 1 });
+        DerivedExecutionId(tag.digest(self).into())
+    }
+}
+
+#[derive(udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.execution_id.tag")]
+struct Tag {
+    version: u16,
 }
+
+/// Protocol which execution ID is derived for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolKind<'a> {
+    /// Non-threshold key generation
+    Keygen,
+    /// Threshold key generation
+    ThresholdKeygen,
+    /// Auxiliary info generation
+    AuxInfoGen,
+    /// Key refresh
+    KeyRefresh,
+    /// Presignature generation
+    Presigning,
+    /// Signing
+    Signing,
+    /// Any other protocol, identified by its name
+    Custom(&'a str),
+}
+
+impl<'a> ProtocolKind<'a> {
+    /// Returns a name of the protocol
+    pub fn as_str(&self) -> &'a str {
+        match self {
+            Self::Keygen => "keygen",
+            Self::ThresholdKeygen => "threshold-keygen",
+            Self::AuxInfoGen => "aux-info-gen",
+            Self::KeyRefresh => "key-refresh",
+            Self::Presigning => "presigning",
+            Self::Signing => "signing",
+            Self::Custom(name) => name,
+        }
+    }
+}
+
+/// Execution ID derived via [`ExecutionIdBuilder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DerivedExecutionId([u8; 32]);
+
+impl DerivedExecutionId {
+    /// Returns [`ExecutionId`] borrowing derived bytes
+    pub fn as_execution_id(&self) -> ExecutionId {
+        ExecutionId::new(&self.0)
+    }
+
+    /// Returns derived bytes
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+/// Application-specific domain separation tag
+///
+/// Execution ID distinguishes protocol executions, whereas domain tag distinguishes applications
+/// embedding the library. When it's set (e.g. via
+/// [`set_domain_tag`](crate::GenericKeygenBuilder::set_domain_tag)), all hashes computed in the
+/// protocol are bound to it, so transcripts of different applications can never be
+/// cross-interpreted, even if they happen to use the same execution ID.
+///
+/// Tag must be 1 to 64 bytes long and may contain only ASCII alphanumeric characters
+/// and `.`, `-`, `_`, `/`. Tags starting with `dfns.cggmp21` are reserved for the library.
+///
+/// ## Example
+/// ```rust
+/// use cggmp21_keygen::execution_id::DomainTag;
+///
+/// let tag = DomainTag::new("com.example.wallet/v1")?;
+/// # Ok::<_, cggmp21_keygen::execution_id::InvalidDomainTag>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DomainTag<'a>(&'a str);
+
+impl<'a> DomainTag<'a> {
+    /// Maximum length of the tag in bytes
+    pub const MAX_LEN: usize = 64;
+    /// Prefix reserved for the tags used by the library
+    pub const RESERVED_PREFIX: &'static str = "dfns.cggmp21";
+
+    /// Validates and constructs a domain tag
+    pub fn new(tag: &'a str) -> Result<Self, InvalidDomainTag> {
+        if tag.is_empty() || tag.len() > Self::MAX_LEN {
+            return Err(InvalidDomainTag(InvalidDomainTagReason::Length(tag.len())));
+        }
+        if let Some(c) = tag
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '/')))
+        {
+            return Err(InvalidDomainTag(InvalidDomainTagReason::Character(c)));
+        }
+        if tag.starts_with(Self::RESERVED_PREFIX) {
+            return Err(InvalidDomainTag(InvalidDomainTagReason::Reserved));
+        }
+        Ok(Self(tag))
+    }
+
+    /// Returns the tag
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+}
+
+/// Domain tag doesn't satisfy [constraints](DomainTag)
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct InvalidDomainTag(InvalidDomainTagReason);
+
+#[derive(Debug, thiserror::Error)]
+enum InvalidDomainTagReason {
+    #[error(
+        "domain tag must be 1 to {} bytes long, got {0} bytes",
+        DomainTag::MAX_LEN
+    )]
+    Length(usize),
+    #[error("domain tag contains not allowed character {0:?}")]
+    Character(char),
+    #[error(
+        "domain tag starts with reserved prefix `{}`",
+        DomainTag::RESERVED_PREFIX
+    )]
+    Reserved,
+}
+
+/// Registry of execution IDs that were already used
+///
+/// Reusing the same execution ID across several protocol executions is catastrophic for
+/// security. Builders may be provided with a registry which is consulted before the protocol
+/// starts: if the execution ID was used before, the protocol is not started.
+///
+/// [`InMemoryEidRegistry`] is provided out of box. Persistent back-ends can be implemented
+/// on top of a database, in which case [`EidRegistryError::backend`] can be used to report
+/// a storage failure.
+pub trait EidRegistry {
+    /// Marks execution ID as used
+    ///
+    /// Returns an error if execution ID was already registered ([`EidRegistryError::is_reused`]
+    /// returns `true` for such error).
+    /// Checking and marking must be done atomically, i.e. if two calls are made concurrently
+    /// with the same `eid`, at most one of them may succeed.
+    fn register(&self, eid: ExecutionId) -> Result<(), EidRegistryError>;
+}
+
+impl<R: EidRegistry + ?Sized> EidRegistry for &R {
+    fn register(&self, eid: ExecutionId) -> Result<(), EidRegistryError> {
+        (**self).register(eid)
+    }
+}
+
+/// In-memory registry of used execution IDs
+///
+/// Keeps all registered IDs in RAM, so registry is cleared when program exits.
+#[derive(Debug, Default)]
+pub struct InMemoryEidRegistry {
+    used: std::sync::Mutex<std::collections::HashSet<Vec<u8>>>,
+}
+
+impl InMemoryEidRegistry {
+    /// Constructs an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks whether execution ID was already registered
+    pub fn contains(&self, eid: ExecutionId) -> bool {
+        self.used
+            .lock()
+            .map(|used| used.contains(eid.as_bytes()))
+            .unwrap_or(true)
+    }
+}
+
+impl EidRegistry for InMemoryEidRegistry {
+    fn register(&self, eid: ExecutionId) -> Result<(), EidRegistryError> {
+        let mut used = self
+            .used
+            .lock()
+            .map_err(|_| EidRegistryError::backend(PoisonedLock))?;
+        if used.insert(eid.as_bytes().to_vec()) {
+            Ok(())
+        } else {
+            Err(EidRegistryError::reused())
+        }
+    }
+}
+
+/// Error returned by [`EidRegistry`]
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct EidRegistryError(EidRegistryReason);
+
+#[derive(Debug, thiserror::Error)]
+enum EidRegistryReason {
+    #[error("execution id was already used")]
+    Reused,
+    #[error("registry back-end failed")]
+    Backend(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl EidRegistryError {
+    /// Constructs an error indicating that execution ID was already used
+    pub fn reused() -> Self {
+        Self(EidRegistryReason::Reused)
+    }
+
+    /// Constructs an error indicating that registry back-end failed
+    pub fn backend(err: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self(EidRegistryReason::Backend(Box::new(err)))
+    }
+
+    /// Indicates whether execution ID was already used
+    pub fn is_reused(&self) -> bool {
+        matches!(self.0, EidRegistryReason::Reused)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("lock is poisoned")]
+struct PoisonedLock;
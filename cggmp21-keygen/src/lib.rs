@@ -1,21 +1,33 @@
 //! Threshold and non-threshold CGGMP21 DKG
 #![allow(non_snake_case, clippy::too_many_arguments)]
 
+pub mod abort;
+pub mod attempts;
+pub mod echo_broadcast;
+pub mod error_code;
+pub mod pop;
 pub mod progress;
 pub mod security_level;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+pub mod to_threshold;
+pub mod transcript;
+pub mod version;
 
 /// Non-threshold DKG specific types
 mod non_threshold;
 /// Threshold DKG specific types
 mod threshold;
+/// Two-round non-threshold DKG specific types
+mod two_round;
 
 mod errors;
-mod execution_id;
+pub mod execution_id;
 mod rng;
 mod utils;
 
 use digest::Digest;
-use generic_ec::Curve;
+use generic_ec::{Curve, NonZero, Scalar};
 use rand_core::{CryptoRng, RngCore};
 use round_based::{Mpc, MsgId, PartyIndex};
 use thiserror::Error;
@@ -25,14 +37,21 @@ pub use key_share;
 
 use crate::progress::Tracer;
 use crate::{
+    abort::PeerAborted,
+    error_code::{ErrorCode, ErrorReport},
     errors::IoError,
     key_share::{CoreKeyShare, InvalidCoreShare},
     security_level::SecurityLevel,
 };
 
-pub use self::execution_id::ExecutionId;
+pub use self::execution_id::{
+    DomainTag, EidRegistry, EidRegistryError, ExecutionId, InMemoryEidRegistry, InvalidDomainTag,
+};
 #[doc(no_inline)]
-pub use self::msg::{non_threshold::Msg as NonThresholdMsg, threshold::Msg as ThresholdMsg};
+pub use self::msg::{
+    non_threshold::Msg as NonThresholdMsg, threshold::Msg as ThresholdMsg,
+    to_threshold::Msg as ToThresholdMsg, two_round::Msg as TwoRoundMsg,
+};
 
 /// Defines default choice for digest and security level used across the crate
 mod default_choice {
@@ -44,14 +63,30 @@ mod default_choice {
 pub mod msg {
     /// Messages types related to non threshold DKG protocol
     pub mod non_threshold {
+        pub use crate::abort::MsgAbort;
+        pub use crate::echo_broadcast::MsgEcho;
         pub use crate::non_threshold::{Msg, MsgReliabilityCheck, MsgRound1, MsgRound2, MsgRound3};
     }
     /// Messages types related to threshold DKG protocol
     pub mod threshold {
+        pub use crate::abort::MsgAbort;
+        pub use crate::echo_broadcast::MsgEcho;
         pub use crate::threshold::{
             Msg, MsgReliabilityCheck, MsgRound1, MsgRound2Broad, MsgRound2Uni, MsgRound3,
         };
     }
+    /// Messages types related to two-round non threshold DKG protocol
+    pub mod two_round {
+        pub use crate::abort::MsgAbort;
+        pub use crate::echo_broadcast::MsgEcho;
+        pub use crate::two_round::{Decommitment, Msg, MsgReliabilityCheck, MsgRound1, MsgRound2};
+    }
+    /// Messages types related to conversion of non-threshold key shares into threshold ones
+    pub mod to_threshold {
+        pub use crate::abort::MsgAbort;
+        pub use crate::echo_broadcast::MsgEcho;
+        pub use crate::to_threshold::{Msg, MsgRound1Broad, MsgRound1Uni};
+    }
 }
 
 /// Key generation entry point. You can call [`set_threshold`] to make it into a
@@ -75,16 +110,23 @@ pub type ThresholdKeygenBuilder<
 
 /// Key generation entry point with choice for threshold or non-threshold
 /// variant
-pub struct GenericKeygenBuilder<'a, E: Curve, M, L: SecurityLevel, D: Digest> {
+pub struct GenericKeygenBuilder<'a, E: Curve, M, L: SecurityLevel, D: Digest, H = D> {
     i: u16,
     n: u16,
     reliable_broadcast_enforced: bool,
+    echo_broadcast_enforced: bool,
     optional_t: M,
     execution_id: ExecutionId<'a>,
     tracer: Option<&'a mut dyn Tracer>,
+    eid_registry: Option<&'a dyn EidRegistry>,
+    randomness_beacon: Option<&'a [u8]>,
+    domain_tag: Option<DomainTag<'a>>,
+    share_indexes: Option<&'a [NonZero<Scalar<E>>]>,
+    weights: Option<&'a [u16]>,
+    batch_verification: bool,
     #[cfg(feature = "hd-wallets")]
     hd_enabled: bool,
-    _params: std::marker::PhantomData<(E, L, D)>,
+    _params: std::marker::PhantomData<(E, L, D, H)>,
 }
 
 /// Indicates non-threshold DKG
@@ -107,8 +149,15 @@ where
             n,
             optional_t: NonThreshold,
             reliable_broadcast_enforced: true,
+            echo_broadcast_enforced: false,
             execution_id: eid,
             tracer: None,
+            eid_registry: None,
+            randomness_beacon: None,
+            domain_tag: None,
+            share_indexes: None,
+            weights: None,
+            batch_verification: false,
             #[cfg(feature = "hd-wallets")]
             hd_enabled: true,
             _params: std::marker::PhantomData,
@@ -116,27 +165,38 @@ where
     }
 }
 
-impl<'a, E, L, D, M> GenericKeygenBuilder<'a, E, M, L, D>
+impl<'a, E, L, D, H, M> GenericKeygenBuilder<'a, E, M, L, D, H>
 where
     E: Curve,
     L: SecurityLevel,
     D: Digest + Clone + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
 {
     /// Specifies to generate key shares for a threshold scheme
-    pub fn set_threshold(self, t: u16) -> GenericKeygenBuilder<'a, E, WithThreshold, L, D> {
+    pub fn set_threshold(self, t: u16) -> GenericKeygenBuilder<'a, E, WithThreshold, L, D, H> {
         GenericKeygenBuilder {
             i: self.i,
             n: self.n,
             optional_t: WithThreshold(t),
             reliable_broadcast_enforced: self.reliable_broadcast_enforced,
+            echo_broadcast_enforced: self.echo_broadcast_enforced,
             execution_id: self.execution_id,
             tracer: self.tracer,
+            eid_registry: self.eid_registry,
+            randomness_beacon: self.randomness_beacon,
+            domain_tag: self.domain_tag,
+            share_indexes: self.share_indexes,
+            weights: self.weights,
+            batch_verification: self.batch_verification,
             #[cfg(feature = "hd-wallets")]
             hd_enabled: self.hd_enabled,
             _params: std::marker::PhantomData,
         }
     }
     /// Specifies another hash function to use
+    ///
+    /// Also resets digest used in reliability checks to `D2`, see
+    /// [`set_reliability_digest`](Self::set_reliability_digest).
     pub fn set_digest<D2>(self) -> GenericKeygenBuilder<'a, E, M, L, D2>
     where
         D2: Digest + Clone + 'static,
@@ -146,8 +206,15 @@ where
             n: self.n,
             optional_t: self.optional_t,
             reliable_broadcast_enforced: self.reliable_broadcast_enforced,
+            echo_broadcast_enforced: self.echo_broadcast_enforced,
             execution_id: self.execution_id,
             tracer: self.tracer,
+            eid_registry: self.eid_registry,
+            randomness_beacon: self.randomness_beacon,
+            domain_tag: self.domain_tag,
+            share_indexes: self.share_indexes,
+            weights: self.weights,
+            batch_verification: self.batch_verification,
             #[cfg(feature = "hd-wallets")]
             hd_enabled: self.hd_enabled,
             _params: std::marker::PhantomData,
@@ -155,7 +222,7 @@ where
     }
 
     /// Specifies [security level](crate::security_level)
-    pub fn set_security_level<L2>(self) -> GenericKeygenBuilder<'a, E, M, L2, D>
+    pub fn set_security_level<L2>(self) -> GenericKeygenBuilder<'a, E, M, L2, D, H>
     where
         L2: SecurityLevel,
     {
@@ -164,8 +231,40 @@ where
             n: self.n,
             optional_t: self.optional_t,
             reliable_broadcast_enforced: self.reliable_broadcast_enforced,
+            echo_broadcast_enforced: self.echo_broadcast_enforced,
+            execution_id: self.execution_id,
+            tracer: self.tracer,
+            eid_registry: self.eid_registry,
+            randomness_beacon: self.randomness_beacon,
+            domain_tag: self.domain_tag,
+            share_indexes: self.share_indexes,
+            weights: self.weights,
+            batch_verification: self.batch_verification,
+            #[cfg(feature = "hd-wallets")]
+            hd_enabled: self.hd_enabled,
+            _params: std::marker::PhantomData,
+        }
+    }
+
+    #[doc = include_str!("../docs/set_reliability_digest.md")]
+    pub fn set_reliability_digest<H2>(self) -> GenericKeygenBuilder<'a, E, M, L, D, H2>
+    where
+        H2: Digest<OutputSize = D::OutputSize>,
+    {
+        GenericKeygenBuilder {
+            i: self.i,
+            n: self.n,
+            optional_t: self.optional_t,
+            reliable_broadcast_enforced: self.reliable_broadcast_enforced,
+            echo_broadcast_enforced: self.echo_broadcast_enforced,
             execution_id: self.execution_id,
             tracer: self.tracer,
+            eid_registry: self.eid_registry,
+            randomness_beacon: self.randomness_beacon,
+            domain_tag: self.domain_tag,
+            share_indexes: self.share_indexes,
+            weights: self.weights,
+            batch_verification: self.batch_verification,
             #[cfg(feature = "hd-wallets")]
             hd_enabled: self.hd_enabled,
             _params: std::marker::PhantomData,
@@ -178,6 +277,41 @@ where
         self
     }
 
+    /// Sets a registry of used execution IDs
+    ///
+    /// Before starting the protocol, execution ID is registered in the registry. If it was
+    /// already used before, protocol won't start and returns an error.
+    pub fn set_eid_registry(mut self, registry: &'a dyn EidRegistry) -> Self {
+        self.eid_registry = Some(registry);
+        self
+    }
+
+    /// Sets output of an external randomness beacon (e.g. drand) to be mixed into the protocol
+    ///
+    /// Beacon output is mixed into the collectively sampled `rid` which Schnorr proofs challenges
+    /// are derived from, so randomness of the ceremony doesn't rely solely on local RNGs of the
+    /// parties. All parties must set the same beacon output, otherwise the protocol aborts.
+    ///
+    /// Beacon output should not be known in advance, e.g. it can be output of the beacon round
+    /// that follows scheduling of the ceremony.
+    pub fn set_randomness_beacon(mut self, beacon: &'a [u8]) -> Self {
+        self.randomness_beacon = Some(beacon);
+        self
+    }
+
+    /// Sets application-specific domain separation tag
+    ///
+    /// Tag is mixed into the session identifier the protocol is bound to, so transcripts, commitments
+    /// and ZK proofs produced by one application can't be replayed in another application
+    /// even if they happen to use the same execution ID. All parties must set the same tag,
+    /// otherwise the protocol aborts.
+    ///
+    /// Tag is not included into the key share.
+    pub fn set_domain_tag(mut self, tag: DomainTag<'a>) -> Self {
+        self.domain_tag = Some(tag);
+        self
+    }
+
     #[doc = include_str!("../docs/enforce_reliable_broadcast.md")]
     pub fn enforce_reliable_broadcast(self, enforce: bool) -> Self {
         Self {
@@ -186,19 +320,39 @@ where
         }
     }
 
+    #[doc = include_str!("../docs/enforce_echo_broadcast.md")]
+    pub fn enforce_echo_broadcast(self, enforce: bool) -> Self {
+        Self {
+            echo_broadcast_enforced: enforce,
+            ..self
+        }
+    }
+
     #[cfg(feature = "hd-wallets")]
     /// Specifies whether HD derivation is enabled for a key
     pub fn hd_wallet(mut self, v: bool) -> Self {
         self.hd_enabled = v;
         self
     }
+
+    /// Returns total amount of rounds the protocol will go through with the current settings
+    ///
+    /// Matches amount of [`Event::RoundBegins`](progress::Event::RoundBegins) events emitted
+    /// to the tracer, so it can be used with [`ProgressTracer`](progress::ProgressTracer)
+    /// to report progress to the end user. Two-round variant goes through different amount of
+    /// rounds, see `two_round_rounds_count`.
+    pub fn rounds_count(&self) -> u16 {
+        4 + u16::from(self.reliable_broadcast_enforced)
+            + 2 * u16::from(self.echo_broadcast_enforced)
+    }
 }
 
-impl<'a, E, L, D> GenericKeygenBuilder<'a, E, NonThreshold, L, D>
+impl<'a, E, L, D, H> GenericKeygenBuilder<'a, E, NonThreshold, L, D, H>
 where
     E: Curve,
     L: SecurityLevel,
     D: Digest + Clone + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
 {
     /// Starts key generation
     pub async fn start<R, M>(self, rng: &mut R, party: M) -> Result<CoreKeyShare<E>, KeygenError>
@@ -206,12 +360,134 @@ where
         R: RngCore + CryptoRng,
         M: Mpc<ProtocolMessage = non_threshold::Msg<E, L, D>>,
     {
-        non_threshold::run_keygen(
+        self.start_with_transcript(rng, party)
+            .await
+            .map(|(key_share, _transcript)| key_share)
+    }
+
+    /// Starts key generation, outputs key share along with [transcript](transcript::Transcript)
+    /// of all broadcast messages
+    pub async fn start_with_transcript<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+    ) -> Result<
+        (
+            CoreKeyShare<E>,
+            transcript::Transcript<transcript::NonThresholdRounds<E, L, D>>,
+        ),
+        KeygenError,
+    >
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = non_threshold::Msg<E, L, D>>,
+    {
+        self.run(rng, party, None)
+            .await
+            .map(|(key_share, transcript, _pop)| (key_share, transcript))
+    }
+
+    /// Starts key generation, outputs key share along with [proof of possession](pop) of
+    /// the shared secret key
+    ///
+    /// Proof is a Schnorr signature over `challenge` jointly produced by the parties, it doesn't
+    /// require additional communication rounds. All parties must provide the same challenge,
+    /// otherwise protocol aborts.
+    pub async fn start_with_proof_of_possession<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+        challenge: &[u8],
+    ) -> Result<(CoreKeyShare<E>, pop::ProofOfPossession<E>), KeygenError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = non_threshold::Msg<E, L, D>>,
+    {
+        let (key_share, _transcript, pop) = self.run(rng, party, Some(challenge)).await?;
+        Ok((key_share, pop.ok_or(Bug::NoPop)?))
+    }
+
+    /// Returns total amount of rounds the [two-round variant](Self::start_two_round) will go
+    /// through with the current settings
+    ///
+    /// Counterpart of [`rounds_count`](Self::rounds_count): matches amount of
+    /// [`Event::RoundBegins`](progress::Event::RoundBegins) events emitted by the two-round
+    /// variant, including the output round.
+    pub fn two_round_rounds_count(&self) -> u16 {
+        3 + u16::from(self.reliable_broadcast_enforced) + u16::from(self.echo_broadcast_enforced)
+    }
+
+    /// Starts two-round variant of key generation
+    ///
+    /// Parties send only two messages instead of three: Schnorr proofs are sent along with
+    /// decommitments. Challenge of the proofs is derived from commitments of all parties instead
+    /// of jointly sampled randomness, which is secure in the random oracle model. That saves
+    /// one round trip, which dominates latency of keygen for small committees (e.g. 2-of-2 or
+    /// 3-of-3).
+    ///
+    /// Protocol goes through `2 + enforce_reliable_broadcast + enforce_echo_broadcast` rounds.
+    /// It's not compatible with regular keygen: all parties must use two-round variant. Use
+    /// [`two_round_rounds_count`](Self::two_round_rounds_count) instead of
+    /// [`rounds_count`](Self::rounds_count) to track its progress.
+    pub async fn start_two_round<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+    ) -> Result<CoreKeyShare<E>, KeygenError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = two_round::Msg<E, L, D>>,
+    {
+        if let Some(registry) = self.eid_registry {
+            registry.register(self.execution_id)?;
+        }
+        two_round::run_keygen::<_, _, _, _, _, H>(
             self.tracer,
             self.i,
             self.n,
             self.reliable_broadcast_enforced,
+            self.echo_broadcast_enforced,
             self.execution_id,
+            self.domain_tag,
+            self.randomness_beacon,
+            rng,
+            party,
+            #[cfg(feature = "hd-wallets")]
+            self.hd_enabled,
+        )
+        .await
+    }
+
+    async fn run<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+        pop_challenge: Option<&[u8]>,
+    ) -> Result<
+        (
+            CoreKeyShare<E>,
+            transcript::Transcript<transcript::NonThresholdRounds<E, L, D>>,
+            Option<pop::ProofOfPossession<E>>,
+        ),
+        KeygenError,
+    >
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = non_threshold::Msg<E, L, D>>,
+    {
+        if let Some(registry) = self.eid_registry {
+            registry.register(self.execution_id)?;
+        }
+        non_threshold::run_keygen::<_, _, _, _, _, H>(
+            self.tracer,
+            self.i,
+            self.n,
+            self.reliable_broadcast_enforced,
+            self.echo_broadcast_enforced,
+            self.execution_id,
+            self.domain_tag,
+            self.randomness_beacon,
+            pop_challenge,
             rng,
             party,
             #[cfg(feature = "hd-wallets")]
@@ -221,25 +497,208 @@ where
     }
 }
 
-impl<'a, E, L, D> GenericKeygenBuilder<'a, E, WithThreshold, L, D>
+impl<'a, E, L, D, H> GenericKeygenBuilder<'a, E, WithThreshold, L, D, H>
 where
     E: Curve,
     L: SecurityLevel,
     D: Digest + Clone + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
 {
+    /// Specifies share indexes $I_j$ of the parties
+    ///
+    /// `I[j]` is the point at which the shared polynomial is evaluated to obtain secret share of
+    /// $j$-th party. Indexes must be distinct, and there must be exactly $n$ of them. By default,
+    /// $I_j = j + 1$.
+    ///
+    /// If key is [weighted](Self::set_weights), there must be one index per share, i.e. as many as
+    /// total weight of the parties. Indexes of the shares of $j$-th party go in a row, after
+    /// indexes of the shares of $(j-1)$-th party.
+    ///
+    /// Custom indexes make key shares compatible with externally defined indexing schemes. All
+    /// parties must specify the same indexes, otherwise protocol aborts.
+    pub fn set_share_indexes(mut self, I: &'a [NonZero<Scalar<E>>]) -> Self {
+        self.share_indexes = Some(I);
+        self
+    }
+
+    /// Specifies weights of the parties, making the key weighted
+    ///
+    /// Party with weight $w_j$ receives $w_j$ shares of the key. Any set of parties with total
+    /// weight at least $t$ (specified in [`set_threshold`](GenericKeygenBuilder::set_threshold))
+    /// can sign. Weights must be non-zero, and there must be exactly $n$ of them. All parties must
+    /// specify the same weights, otherwise protocol aborts.
+    pub fn set_weights(mut self, weights: &'a [u16]) -> Self {
+        self.weights = Some(weights);
+        self
+    }
+
+    /// Enables batch verification of received secret shares
+    ///
+    /// Designed for large committees (with $n$ in hundreds), where verification of received
+    /// shares dominates the computation: instead of checking each share against committed
+    /// polynomial of its dealer, which takes $O(n \cdot t)$ point multiplications, all shares
+    /// are checked at once against the sum of polynomials, which takes $O(n \cdot t)$ point
+    /// additions and only $O(t)$ multiplications. Shares are checked one by one only if batch
+    /// check fails, so misbehaving party is identified as usual.
+    ///
+    /// Shares received from malicious parties may pass the batch check if their errors
+    /// cancel each other out. In that case, resulting key share is still consistent with the
+    /// shared public key, but such parties aren't blamed. Disabled by default.
+    ///
+    /// Effect of batch verification for given committee size can be measured with `measure_perf`
+    /// tool from the repository: compare duration of "Validate Feldmann VSS" stage reported by
+    /// ```text
+    /// cargo run --release -p cggmp21-tests --bin measure_perf -- -n 50,100,200 \
+    ///     --no-bench-primes-gen --no-bench-non-threshold-keygen --no-bench-aux-data-gen \
+    ///     --no-bench-signing
+    /// ```
+    /// with duration of "Validate Feldmann VSS (batched)" stage reported when
+    /// `--batch-verification` flag is added.
+    pub fn batch_verification(mut self, enabled: bool) -> Self {
+        self.batch_verification = enabled;
+        self
+    }
+
     /// Starts threshold key generation
     pub async fn start<R, M>(self, rng: &mut R, party: M) -> Result<CoreKeyShare<E>, KeygenError>
     where
         R: RngCore + CryptoRng,
         M: Mpc<ProtocolMessage = threshold::Msg<E, L, D>>,
     {
-        threshold::run_threshold_keygen(
+        self.start_with_transcript(rng, party)
+            .await
+            .map(|(key_share, _transcript)| key_share)
+    }
+
+    /// Starts threshold key generation, outputs key share along with
+    /// [transcript](transcript::Transcript) of all broadcast messages
+    pub async fn start_with_transcript<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+    ) -> Result<
+        (
+            CoreKeyShare<E>,
+            transcript::Transcript<transcript::ThresholdRounds<E, L, D>>,
+        ),
+        KeygenError,
+    >
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = threshold::Msg<E, L, D>>,
+    {
+        self.run(rng, party, None)
+            .await
+            .map(|(key_share, transcript, _pop)| (key_share, transcript))
+    }
+
+    /// Starts threshold key generation, outputs key share along with [proof of possession](pop)
+    /// of the shared secret key
+    ///
+    /// Proof is a Schnorr signature over `challenge` jointly produced by the parties, it doesn't
+    /// require additional communication rounds. All parties must provide the same challenge,
+    /// otherwise protocol aborts.
+    pub async fn start_with_proof_of_possession<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+        challenge: &[u8],
+    ) -> Result<(CoreKeyShare<E>, pop::ProofOfPossession<E>), KeygenError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = threshold::Msg<E, L, D>>,
+    {
+        let (key_share, _transcript, pop) = self.run(rng, party, Some(challenge)).await?;
+        Ok((key_share, pop.ok_or(Bug::NoPop)?))
+    }
+
+    async fn run<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+        pop_challenge: Option<&[u8]>,
+    ) -> Result<
+        (
+            CoreKeyShare<E>,
+            transcript::Transcript<transcript::ThresholdRounds<E, L, D>>,
+            Option<pop::ProofOfPossession<E>>,
+        ),
+        KeygenError,
+    >
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = threshold::Msg<E, L, D>>,
+    {
+        let total_weight = match self.weights {
+            Some(weights) => {
+                if weights.len() != usize::from(self.n) {
+                    return Err(InvalidArgs::WeightsSize {
+                        expected: self.n,
+                        actual: weights.len(),
+                    }
+                    .into());
+                }
+                if weights.contains(&0) {
+                    return Err(InvalidArgs::ZeroWeight.into());
+                }
+                weights
+                    .iter()
+                    .try_fold(0u16, |acc, w| acc.checked_add(*w))
+                    .ok_or(InvalidArgs::TotalWeightOverflow)?
+            }
+            None => self.n,
+        };
+        let all_indexes = match self.share_indexes {
+            Some(I) => {
+                if I.len() != usize::from(total_weight) {
+                    return Err(InvalidArgs::ShareIndexesSize {
+                        expected: total_weight,
+                        actual: I.len(),
+                    }
+                    .into());
+                }
+                if (1..I.len()).any(|j| I[..j].contains(&I[j])) {
+                    return Err(InvalidArgs::DuplicatedShareIndex.into());
+                }
+                I.to_vec()
+            }
+            None => (1..=total_weight)
+                .map(|j| NonZero::from_scalar(Scalar::from(j)))
+                .collect::<Option<Vec<_>>>()
+                .ok_or(Bug::NonZeroScalar)?,
+        };
+        let (share_indexes, extra_indexes) = match self.weights {
+            Some(weights) => {
+                let mut share_indexes = Vec::with_capacity(weights.len());
+                let mut extra_indexes = Vec::with_capacity(weights.len());
+                let mut offset = 0;
+                for &w_j in weights {
+                    let w_j = usize::from(w_j);
+                    share_indexes.push(all_indexes[offset]);
+                    extra_indexes.push(all_indexes[offset + 1..offset + w_j].to_vec());
+                    offset += w_j;
+                }
+                (share_indexes, Some(extra_indexes))
+            }
+            None => (all_indexes, None),
+        };
+        if let Some(registry) = self.eid_registry {
+            registry.register(self.execution_id)?;
+        }
+        threshold::run_threshold_keygen::<_, _, _, _, _, H>(
             self.tracer,
             self.i,
             self.optional_t.0,
             self.n,
+            share_indexes,
+            extra_indexes,
             self.reliable_broadcast_enforced,
+            self.echo_broadcast_enforced,
             self.execution_id,
+            self.domain_tag,
+            self.randomness_beacon,
+            pop_challenge,
+            self.batch_verification,
             rng,
             party,
             #[cfg(feature = "hd-wallets")]
@@ -254,10 +713,48 @@ where
 #[error("keygen protocol is failed to complete")]
 pub struct KeygenError(#[source] Reason);
 
+impl KeygenError {
+    /// Returns index of the party who aborted the protocol by sending
+    /// [`MsgAbort`](crate::abort::MsgAbort), if protocol failed for this reason
+    pub fn aborted_by_peer(&self) -> Option<PartyIndex> {
+        match &self.0 {
+            Reason::PeerAborted(PeerAborted(j)) => Some(*j),
+            _ => None,
+        }
+    }
+
+    /// Returns stable code of the error
+    pub fn code(&self) -> ErrorCode {
+        match &self.0 {
+            Reason::Aborted(err) => err.code(),
+            Reason::IoError(err) => err.code(),
+            Reason::PeerAborted(_) => ErrorCode::PeerAborted,
+            Reason::EidRegistry(_) => ErrorCode::ExecutionIdRejected,
+            Reason::InvalidArgs(_) => ErrorCode::InvalidArgs,
+            Reason::Bug(_) => ErrorCode::InternalError,
+        }
+    }
+
+    /// Returns serializable report of the error
+    pub fn report(&self) -> ErrorReport {
+        let parties = match &self.0 {
+            Reason::Aborted(err) => err.blamed_parties(),
+            Reason::PeerAborted(PeerAborted(j)) => vec![*j],
+            _ => vec![],
+        };
+        ErrorReport::new(self.code(), self, parties)
+    }
+}
+
 crate::errors::impl_from! {
     impl From for KeygenError {
         err: KeygenAborted => KeygenError(Reason::Aborted(err)),
-        err: IoError => KeygenError(Reason::IoError(err)),
+        err: IoError => match err {
+            IoError::PeerAborted(err) => KeygenError(Reason::PeerAborted(err)),
+            err => KeygenError(Reason::IoError(err)),
+        },
+        err: EidRegistryError => KeygenError(Reason::EidRegistry(err)),
+        err: InvalidArgs => KeygenError(Reason::InvalidArgs(err)),
         err: Bug => KeygenError(Reason::Bug(err)),
     }
 }
@@ -273,6 +770,12 @@ enum Reason {
     ),
     #[error("i/o error")]
     IoError(#[source] IoError),
+    #[error("protocol was aborted by another party")]
+    PeerAborted(#[source] PeerAborted),
+    #[error("execution id was rejected by registry")]
+    EidRegistry(#[source] EidRegistryError),
+    #[error("invalid arguments")]
+    InvalidArgs(#[source] InvalidArgs),
     /// Bug occurred
     #[error("bug occurred")]
     Bug(Bug),
@@ -291,11 +794,70 @@ enum KeygenAborted {
     FeldmanVerificationFailed { parties: Vec<u16> },
     #[error("party data size is not suitable for threshold parameters: {parties:?}")]
     InvalidDataSize { parties: Vec<u16> },
-    #[error("round1 wasn't reliable")]
-    Round1NotReliable(Vec<(PartyIndex, MsgId)>),
+    #[error("broadcast at round {round} wasn't reliable: {parties:?}")]
+    BroadcastNotReliable {
+        round: u16,
+        parties: Vec<(PartyIndex, MsgId)>,
+    },
     #[cfg(feature = "hd-wallets")]
     #[error("party did not generate chain code: {0:?}")]
     MissingChainCode(Vec<utils::AbortBlame>),
+    #[error("party did not commit to proof of possession nonce: {0:?}")]
+    MissingPopCommitment(Vec<utils::AbortBlame>),
+    #[error("party provided invalid share of proof of possession: {0:?}")]
+    InvalidPopShare(Vec<utils::AbortBlame>),
+    #[error("party polynomial doesn't share its original key share: {parties:?}")]
+    PolynomialNotBoundToShare { parties: Vec<u16> },
+}
+
+impl KeygenAborted {
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::InvalidDecommitment(_) => ErrorCode::InvalidDecommitment,
+            Self::InvalidSchnorrProof(_) => ErrorCode::InvalidSchnorrProof,
+            Self::FeldmanVerificationFailed { .. } => ErrorCode::FeldmanVerificationFailed,
+            Self::InvalidDataSize { .. } => ErrorCode::InvalidDataSize,
+            Self::BroadcastNotReliable { .. } => ErrorCode::BroadcastNotReliable,
+            #[cfg(feature = "hd-wallets")]
+            Self::MissingChainCode(_) => ErrorCode::MissingChainCode,
+            Self::MissingPopCommitment(_) => ErrorCode::MissingPopCommitment,
+            Self::InvalidPopShare(_) => ErrorCode::InvalidPopShare,
+            Self::PolynomialNotBoundToShare { .. } => ErrorCode::PolynomialNotBoundToShare,
+        }
+    }
+
+    fn blamed_parties(&self) -> Vec<PartyIndex> {
+        match self {
+            Self::InvalidDecommitment(blame)
+            | Self::InvalidSchnorrProof(blame)
+            | Self::MissingPopCommitment(blame)
+            | Self::InvalidPopShare(blame) => blame.iter().map(|b| b.faulty_party).collect(),
+            #[cfg(feature = "hd-wallets")]
+            Self::MissingChainCode(blame) => blame.iter().map(|b| b.faulty_party).collect(),
+            Self::FeldmanVerificationFailed { parties }
+            | Self::InvalidDataSize { parties }
+            | Self::PolynomialNotBoundToShare { parties } => parties.clone(),
+            Self::BroadcastNotReliable { parties, .. } => parties.iter().map(|(j, _)| *j).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+enum InvalidArgs {
+    #[error("expected {expected} share indexes, got {actual}")]
+    ShareIndexesSize { expected: u16, actual: usize },
+    #[error("share indexes are not distinct")]
+    DuplicatedShareIndex,
+    #[error("expected {expected} weights, got {actual}")]
+    WeightsSize { expected: u16, actual: usize },
+    #[error("weight of a party is zero")]
+    ZeroWeight,
+    #[error("total weight of parties overflows u16")]
+    TotalWeightOverflow,
+    #[error("key share is already a threshold one")]
+    AlreadyThreshold,
+    #[error("invalid threshold: t={t}, n={n}")]
+    InvalidThreshold { t: u16, n: u16 },
 }
 
 #[derive(Debug, Error)]
@@ -311,6 +873,14 @@ enum Bug {
     ZeroShare,
     #[error("shared public key is zero - probability of that is negligible")]
     ZeroPk,
+    #[error(
+        "proof of possession commitment is missing although we checked that it should be present"
+    )]
+    NoPopCommitment,
+    #[error("proof of possession share is missing although we checked that it should be present")]
+    NoPopShare,
+    #[error("proof of possession was requested but not produced")]
+    NoPop,
 }
 
 /// Distributed key generation protocol
@@ -320,3 +890,15 @@ enum Bug {
 pub fn keygen<E: Curve>(eid: ExecutionId, i: u16, n: u16) -> KeygenBuilder<E> {
     KeygenBuilder::new(eid, i, n)
 }
+
+/// Conversion of non-threshold key shares into threshold ones
+///
+/// Takes non-threshold key share of local party and threshold $t$ of resulting key, $2 \le t \le n$.
+/// See [module level docs](mod@to_threshold) for details.
+pub fn to_threshold<'a, E: Curve>(
+    eid: ExecutionId<'a>,
+    key_share: &'a key_share::CoreKeyShare<E>,
+    t: u16,
+) -> to_threshold::ToThresholdBuilder<'a, E> {
+    to_threshold::ToThresholdBuilder::new(eid, key_share, t)
+}
@@ -7,11 +7,17 @@ use round_based::{
     rounds_router::simple_store::RoundInput, rounds_router::RoundsRouter, Delivery, Mpc, MpcParty,
     Outgoing, ProtocolMessage,
 };
-use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use serde_with::As;
 
+use crate::abort::{self, MsgAbort};
+use crate::echo_broadcast::MsgEcho;
+use crate::pop::{self, ProofOfPossession};
 use crate::progress::Tracer;
+use crate::transcript::{NonThresholdRounds, Transcript};
 use crate::{
     errors::IoError,
+    execution_id::DomainTag,
     key_share::{CoreKeyShare, DirtyCoreKeyShare, DirtyKeyInfo, Validate},
     security_level::SecurityLevel,
     utils, ExecutionId,
@@ -20,8 +26,9 @@ use crate::{
 use super::{Bug, KeygenAborted, KeygenError};
 
 /// Message of key generation protocol
-#[derive(ProtocolMessage, Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(ProtocolMessage, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub enum Msg<E: Curve, L: SecurityLevel, D: Digest> {
     /// Round 1 message
     Round1(MsgRound1<D>),
@@ -31,11 +38,18 @@ pub enum Msg<E: Curve, L: SecurityLevel, D: Digest> {
     Round2(MsgRound2<E, L>),
     /// Round 3 message
     Round3(MsgRound3<E>),
+    /// Echo of round 2 messages (optional additional round)
+    Round2Echo(MsgEcho<D, 2>),
+    /// Echo of round 3 messages (optional additional round)
+    Round3Echo(MsgEcho<D, 3>),
+    /// Party aborts the protocol (not part of any round)
+    Abort(MsgAbort),
 }
 
 /// Message from round 1
-#[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
-#[serde(bound = "")]
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 #[udigest(bound = "")]
 #[udigest(tag = "dfns.cggmp21.keygen.non_threshold.round1")]
 pub struct MsgRound1<D: Digest> {
@@ -44,14 +58,14 @@ pub struct MsgRound1<D: Digest> {
     pub commitment: digest::Output<D>,
 }
 /// Message from round 2
-#[serde_with::serde_as]
-#[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
-#[serde(bound = "")]
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 #[udigest(bound = "")]
 #[udigest(tag = "dfns.cggmp21.keygen.non_threshold.round2")]
 pub struct MsgRound2<E: Curve, L: SecurityLevel> {
     /// `rid_i`
-    #[serde_as(as = "utils::HexOrBin")]
+    #[cfg_attr(feature = "serde", serde(with = "As::<utils::HexOrBin>"))]
     #[udigest(as_bytes)]
     pub rid: L::Rid,
     /// $X_i$
@@ -60,24 +74,43 @@ pub struct MsgRound2<E: Curve, L: SecurityLevel> {
     pub sch_commit: schnorr_pok::Commit<E>,
     /// Party contribution to chain code
     #[cfg(feature = "hd-wallets")]
-    #[serde_as(as = "Option<utils::HexOrBin>")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "As::<Option<utils::HexOrBin>>")
+    )]
     #[udigest(with = utils::encoding::maybe_bytes)]
     pub chain_code: Option<slip_10::ChainCode>,
+    /// Commitment to nonce of [proof of possession](crate::pop), if it was requested
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub pop_commit: Option<Point<E>>,
     /// $u_i$
-    #[serde(with = "hex::serde")]
+    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))]
     #[udigest(as_bytes)]
     pub decommit: L::Rid,
 }
 /// Message from round 3
-#[derive(Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+#[udigest(bound = "")]
+#[udigest(tag = "dfns.cggmp21.keygen.non_threshold.round3")]
 pub struct MsgRound3<E: Curve> {
     /// $\psi_i$
     pub sch_proof: schnorr_pok::Proof<E>,
+    /// Share of [proof of possession](crate::pop), if it was requested
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub pop_share: Option<Scalar<E>>,
 }
 /// Message parties exchange to ensure reliability of broadcast channel
-#[derive(Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct MsgReliabilityCheck<D: Digest>(pub digest::Output<D>);
 
 #[derive(udigest::Digestable)]
@@ -96,20 +129,32 @@ enum Tag<'a> {
     },
 }
 
-pub async fn run_keygen<E, R, M, L, D>(
+pub async fn run_keygen<E, R, M, L, D, H>(
     mut tracer: Option<&mut dyn Tracer>,
     i: u16,
     n: u16,
     reliable_broadcast_enforced: bool,
+    echo_broadcast_enforced: bool,
     execution_id: ExecutionId<'_>,
+    domain_tag: Option<DomainTag<'_>>,
+    randomness_beacon: Option<&[u8]>,
+    pop_challenge: Option<&[u8]>,
     rng: &mut R,
     party: M,
     #[cfg(feature = "hd-wallets")] hd_enabled: bool,
-) -> Result<CoreKeyShare<E>, KeygenError>
+) -> Result<
+    (
+        CoreKeyShare<E>,
+        Transcript<NonThresholdRounds<E, L, D>>,
+        Option<ProofOfPossession<E>>,
+    ),
+    KeygenError,
+>
 where
     E: Curve,
     L: SecurityLevel,
     D: Digest + Clone + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
     R: RngCore + CryptoRng,
     M: Mpc<ProtocolMessage = Msg<E, L, D>>,
 {
@@ -124,13 +169,16 @@ where
     let round1_sync = rounds.add_round(RoundInput::<MsgReliabilityCheck<D>>::broadcast(i, n));
     let round2 = rounds.add_round(RoundInput::<MsgRound2<E, L>>::broadcast(i, n));
     let round3 = rounds.add_round(RoundInput::<MsgRound3<E>>::broadcast(i, n));
-    let mut rounds = rounds.listen(incomings);
+    let round2_echo = rounds.add_round(RoundInput::<MsgEcho<D, 2>>::broadcast(i, n));
+    let round3_echo = rounds.add_round(RoundInput::<MsgEcho<D, 3>>::broadcast(i, n));
+    let mut rounds = rounds.listen(abort::intercept(incomings));
 
     // Round 1
     tracer.round_begins();
 
     tracer.stage("Compute execution id");
-    let sid = execution_id.as_bytes();
+    let sid = utils::domain_separated_sid::<D>(execution_id.as_bytes(), domain_tag);
+    let sid = &*sid;
     let tag = |j| {
         udigest::Tag::<D>::new_structured(Tag::Indexed {
             party_index: j,
@@ -157,6 +205,7 @@ where
 
     tracer.stage("Sample schnorr commitment");
     let (sch_secret, sch_commit) = schnorr_pok::prover_commits_ephemeral_secret::<E, _>(rng);
+    let pop_nonce = pop_challenge.map(|_| SecretScalar::<E>::random(rng));
 
     tracer.stage("Commit to public data");
     let my_decommitment = MsgRound2 {
@@ -165,6 +214,7 @@ where
         sch_commit,
         #[cfg(feature = "hd-wallets")]
         chain_code: chain_code_local,
+        pop_commit: pop_nonce.as_ref().map(|r| Point::generator() * r),
         decommit: {
             let mut nonce = L::Rid::default();
             rng.fill_bytes(nonce.as_mut());
@@ -191,12 +241,12 @@ where
         .complete(round1)
         .await
         .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
+    tracer.msgs_received_count(usize::from(n) - 1);
 
     // Optional reliability check
     if reliable_broadcast_enforced {
         tracer.stage("Hash received msgs (reliability check)");
-        let h_i = udigest::Tag::<D>::new_structured(Tag::Unindexed { sid })
+        let h_i = udigest::Tag::<H>::new_structured(Tag::Unindexed { sid })
             .digest_iter(commitments.iter_including_me(&my_commitment));
 
         tracer.send_msg();
@@ -215,16 +265,16 @@ where
             .complete(round1_sync)
             .await
             .map_err(IoError::receive_message)?;
-        tracer.msgs_received();
+        tracer.msgs_received_count(usize::from(n) - 1);
 
         tracer.stage("Assert other parties hashed messages (reliability check)");
-        let parties_have_different_hashes = round1_hashes
+        let parties = round1_hashes
             .into_iter_indexed()
             .filter(|(_j, _msg_id, hash_j)| hash_j.0 != h_i)
             .map(|(j, msg_id, _)| (j, msg_id))
             .collect::<Vec<_>>();
-        if !parties_have_different_hashes.is_empty() {
-            return Err(KeygenAborted::Round1NotReliable(parties_have_different_hashes).into());
+        if !parties.is_empty() {
+            return Err(KeygenAborted::BroadcastNotReliable { round: 1, parties }.into());
         }
     }
 
@@ -243,7 +293,38 @@ where
         .complete(round2)
         .await
         .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
+    tracer.msgs_received_count(usize::from(n) - 1);
+
+    // Optional echo broadcast
+    if echo_broadcast_enforced {
+        tracer.stage("Hash received msgs (echo broadcast)");
+        let echo = MsgEcho::<D, 2>::new_with_digest::<H>(
+            sid,
+            decommitments.iter_including_me(&my_decommitment),
+        );
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round2Echo(echo.clone())))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.round_begins();
+
+        tracer.receive_msgs();
+        let echoes = rounds
+            .complete(round2_echo)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received_count(usize::from(n) - 1);
+
+        tracer.stage("Assert other parties echoed the same msgs (echo broadcast)");
+        let parties = echo.find_mismatched(echoes);
+        if !parties.is_empty() {
+            return Err(KeygenAborted::BroadcastNotReliable { round: 2, parties }.into());
+        }
+    }
 
     tracer.stage("Validate decommitments");
     let blame = utils::collect_blame(&commitments, &decommitments, |j, com, decom| {
@@ -279,6 +360,7 @@ where
         .iter_including_me(&my_decommitment)
         .map(|d| &d.rid)
         .fold(L::Rid::default(), utils::xor_array);
+    let rid = utils::mix_randomness_beacon::<D, _>(rid, sid, randomness_beacon);
     let challenge = {
         let hash = |d: D| {
             d.chain_update(sid)
@@ -294,8 +376,33 @@ where
     tracer.stage("Prove knowledge of `x_i`");
     let sch_proof = schnorr_pok::prove(&sch_secret, &challenge, &x_i);
 
+    let shared_public_key = decommitments
+        .iter_including_me(&my_decommitment)
+        .map(|d| d.X)
+        .sum::<Point<E>>();
+    let pop_setup = match (pop_challenge, &pop_nonce) {
+        (Some(pop_challenge), Some(pop_nonce)) => {
+            tracer.stage("Compute share of proof of possession");
+            let blame =
+                utils::collect_simple_blame(&decommitments, |decom| decom.pop_commit.is_none());
+            if !blame.is_empty() {
+                return Err(KeygenAborted::MissingPopCommitment(blame).into());
+            }
+            let R = decommitments
+                .iter_including_me(&my_decommitment)
+                .map(|d| d.pop_commit.ok_or(Bug::NoPopCommitment))
+                .sum::<Result<Point<E>, _>>()?;
+            let e = pop::hash_challenge::<E, D>(&shared_public_key, &R, pop_challenge);
+            Some((R, e, pop_nonce + e * &x_i))
+        }
+        _ => None,
+    };
+
     tracer.send_msg();
-    let my_sch_proof = MsgRound3 { sch_proof };
+    let my_sch_proof = MsgRound3 {
+        sch_proof,
+        pop_share: pop_setup.map(|(_, _, z_i)| z_i),
+    };
     outgoings
         .send(Outgoing::broadcast(Msg::Round3(my_sch_proof.clone())))
         .await
@@ -310,10 +417,40 @@ where
         .complete(round3)
         .await
         .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
+    tracer.msgs_received_count(usize::from(n) - 1);
+
+    // Optional echo broadcast
+    if echo_broadcast_enforced {
+        tracer.stage("Hash received msgs (echo broadcast)");
+        let echo =
+            MsgEcho::<D, 3>::new_with_digest::<H>(sid, sch_proofs.iter_including_me(&my_sch_proof));
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round3Echo(echo.clone())))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.round_begins();
+
+        tracer.receive_msgs();
+        let echoes = rounds
+            .complete(round3_echo)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received_count(usize::from(n) - 1);
+
+        tracer.stage("Assert other parties echoed the same msgs (echo broadcast)");
+        let parties = echo.find_mismatched(echoes);
+        if !parties.is_empty() {
+            return Err(KeygenAborted::BroadcastNotReliable { round: 3, parties }.into());
+        }
+    }
 
     tracer.stage("Validate schnorr proofs");
     let blame = utils::collect_blame(&decommitments, &sch_proofs, |j, decom, sch_proof| {
+        tracer.process_peer(j);
         let challenge = {
             let hash = |d: D| {
                 d.chain_update(sid)
@@ -334,29 +471,61 @@ where
         return Err(KeygenAborted::InvalidSchnorrProof(blame).into());
     }
 
+    let pop = match pop_setup {
+        Some((R, e, _)) => {
+            tracer.stage("Validate shares of proof of possession");
+            let blame =
+                utils::collect_blame(&decommitments, &sch_proofs, |_j, decom, proof| {
+                    match (decom.pop_commit, proof.pop_share) {
+                        (Some(R_j), Some(z_j)) => Point::generator() * z_j != R_j + e * decom.X,
+                        _ => true,
+                    }
+                });
+            if !blame.is_empty() {
+                return Err(KeygenAborted::InvalidPopShare(blame).into());
+            }
+            let z = sch_proofs
+                .iter_including_me(&my_sch_proof)
+                .map(|proof| proof.pop_share.ok_or(Bug::NoPopShare))
+                .sum::<Result<Scalar<E>, _>>()?;
+            Some(ProofOfPossession { R, z })
+        }
+        None => None,
+    };
+
+    let public_shares = decommitments
+        .iter_including_me(&my_decommitment)
+        .map(|d| d.X)
+        .collect();
+
+    tracer.stage("Record transcript");
+    let transcript = Transcript {
+        eid: execution_id.as_bytes().to_vec(),
+        i,
+        rounds: NonThresholdRounds {
+            round1: commitments.into_vec_including_me(my_commitment),
+            round2: decommitments.into_vec_including_me(my_decommitment),
+            round3: sch_proofs.into_vec_including_me(my_sch_proof),
+        },
+    };
+
     tracer.protocol_ends();
 
-    Ok(DirtyCoreKeyShare {
+    let key_share = DirtyCoreKeyShare {
         i,
         key_info: DirtyKeyInfo {
             curve: Default::default(),
-            shared_public_key: NonZero::from_point(
-                decommitments
-                    .iter_including_me(&my_decommitment)
-                    .map(|d| d.X)
-                    .sum(),
-            )
-            .ok_or(Bug::ZeroPk)?,
-            public_shares: decommitments
-                .iter_including_me(&my_decommitment)
-                .map(|d| d.X)
-                .collect(),
+            shared_public_key: NonZero::from_point(shared_public_key).ok_or(Bug::ZeroPk)?,
+            public_shares,
             vss_setup: None,
             #[cfg(feature = "hd-wallets")]
             chain_code,
         },
         x: x_i,
+        extra_x: Vec::new(),
     }
     .validate()
-    .map_err(|e| Bug::InvalidKeyShare(e.into_error()))?)
+    .map_err(|e| Bug::InvalidKeyShare(e.into_error()))?;
+
+    Ok((key_share, transcript, pop))
 }
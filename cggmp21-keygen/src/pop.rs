@@ -0,0 +1,75 @@
+//! Proof of possession of the shared secret key
+//!
+//! Some systems (e.g. validators registries or certificate authorities) require a proof of
+//! possession (PoP) of the secret key when the public key is being enrolled. Keygen can optionally
+//! output such proof: parties jointly produce a Schnorr signature over a challenge provided by
+//! the system, without any additional communication rounds (see
+//! [`start_with_proof_of_possession`](crate::GenericKeygenBuilder::start_with_proof_of_possession)).
+//!
+//! ## Proof format
+//! Proof is a pair $(R, z)$ such that $z \cdot G = R + e \cdot X$, where $X$ is the shared public
+//! key and $e$ is a scalar derived by interpreting as big-endian integer (reduced modulo curve
+//! order) the digest
+//!
+//! $$e = H(\texttt{"dfns.cggmp21.keygen.pop"} \parallel X \parallel R \parallel \text{challenge})$$
+//!
+//! where points are encoded in compressed form. Hash function $H$ is the one that was used
+//! to carry out keygen (SHA-256 by default).
+
+use digest::Digest;
+use generic_ec::{Curve, Point, Scalar};
+use thiserror::Error;
+
+/// Domain separation tag of the challenge hash
+const TAG: &[u8] = b"dfns.cggmp21.keygen.pop";
+
+/// Proof of possession of the shared secret key
+///
+/// Schnorr signature over the challenge, see [module level docs](self) for the format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct ProofOfPossession<E: Curve> {
+    /// $R$
+    pub R: Point<E>,
+    /// $z$
+    pub z: Scalar<E>,
+}
+
+impl<E: Curve> ProofOfPossession<E> {
+    /// Verifies the proof of possession of the secret key corresponding to `public_key`
+    ///
+    /// `D` must be the same hash function that was used to carry out keygen.
+    pub fn verify<D: Digest>(
+        &self,
+        public_key: &Point<E>,
+        challenge: &[u8],
+    ) -> Result<(), InvalidProofOfPossession> {
+        let e = hash_challenge::<E, D>(public_key, &self.R, challenge);
+        if Point::generator() * self.z == self.R + e * public_key {
+            Ok(())
+        } else {
+            Err(InvalidProofOfPossession)
+        }
+    }
+}
+
+/// Computes challenge $e$ of Schnorr signature
+pub(crate) fn hash_challenge<E: Curve, D: Digest>(
+    public_key: &Point<E>,
+    R: &Point<E>,
+    challenge: &[u8],
+) -> Scalar<E> {
+    let hash = D::new()
+        .chain_update(TAG)
+        .chain_update(public_key.to_bytes(true))
+        .chain_update(R.to_bytes(true))
+        .chain_update(challenge)
+        .finalize();
+    Scalar::from_be_bytes_mod_order(hash)
+}
+
+/// Proof of possession is not valid
+#[derive(Debug, Error)]
+#[error("proof of possession is not valid")]
+pub struct InvalidProofOfPossession;
@@ -3,9 +3,17 @@
 //! Provides [`Tracer`] trait that can be used to trace progress of ongoing MPC protocol execution.
 //! For instance, it can be implemented to report progress to the end user.
 //!
-//! Out of box, there's [`PerfProfiler`] which can be used to bechmark a protocol.
+//! Out of box, there's [`PerfProfiler`] which can be used to bechmark a protocol, and [`EventLog`]
+//! which records all events with their context (e.g. index of the round), [`SinkTracer`] which
+//! streams these events into an async channel, and [`BandwidthTracer`]
+//! which attributes network traffic to rounds and peers. With `serde` feature enabled, events and
+//! reports implement `serde` traits, so they can be streamed to dashboards or persisted for later
+//! analysis.
 
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::fmt;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use thiserror::Error;
@@ -28,12 +36,14 @@ pub trait Tracer: Send + Sync {
     /// Traces [`Event::RoundBegins`] event
     fn named_round_begins(&mut self, round_name: &'static str) {
         self.trace_event(Event::RoundBegins {
-            name: Some(round_name),
+            name: Some(Cow::Borrowed(round_name)),
         })
     }
     /// Traces [`Event::Stage`] event
     fn stage(&mut self, stage: &'static str) {
-        self.trace_event(Event::Stage { name: stage })
+        self.trace_event(Event::Stage {
+            name: Cow::Borrowed(stage),
+        })
     }
     /// Traces [`Event::ReceiveMsgs`] event
     fn receive_msgs(&mut self) {
@@ -41,7 +51,15 @@ pub trait Tracer: Send + Sync {
     }
     /// Traces [`Event::MsgsReceived`] event
     fn msgs_received(&mut self) {
-        self.trace_event(Event::MsgsReceived)
+        self.trace_event(Event::MsgsReceived { count: None })
+    }
+    /// Traces [`Event::MsgsReceived`] event specifying amount of received messages
+    fn msgs_received_count(&mut self, count: usize) {
+        self.trace_event(Event::MsgsReceived { count: Some(count) })
+    }
+    /// Traces [`Event::ProcessPeer`] event
+    fn process_peer(&mut self, peer: u16) {
+        self.trace_event(Event::ProcessPeer { peer })
     }
     /// Traces [`Event::SendMsg`] event
     fn send_msg(&mut self) {
@@ -58,7 +76,9 @@ pub trait Tracer: Send + Sync {
 }
 
 /// Event occurred during the protocol execution
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
 pub enum Event {
     /// Protocol begins
     ///
@@ -68,24 +88,36 @@ pub enum Event {
     /// Round begins
     RoundBegins {
         /// Optional name of the round
-        name: Option<&'static str>,
+        name: Option<Cow<'static, str>>,
     },
     /// Stage begins
     Stage {
         /// Name of the stage
-        name: &'static str,
+        name: Cow<'static, str>,
     },
 
     /// Protocol waits for some messages to be received
     ReceiveMsgs,
     /// Protocol received messages, round continues
-    MsgsReceived,
+    MsgsReceived {
+        /// Amount of received messages, if known
+        count: Option<usize>,
+    },
 
     /// Protocol starts sending a message
     SendMsg,
     /// Protocol sent a message, round continues
     MsgSent,
 
+    /// Protocol starts processing data related to specified peer
+    ///
+    /// This event doesn't finish ongoing stage: processing the peer is normally done within
+    /// a stage.
+    ProcessPeer {
+        /// Index of the peer
+        peer: u16,
+    },
+
     /// Protocol completed
     ProtocolEnds,
 }
@@ -102,6 +134,13 @@ impl<T: Tracer> Tracer for &mut T {
     }
 }
 
+impl<A: Tracer, B: Tracer> Tracer for (A, B) {
+    fn trace_event(&mut self, event: Event) {
+        self.0.trace_event(event.clone());
+        self.1.trace_event(event);
+    }
+}
+
 impl<T: Tracer> Tracer for Option<T> {
     fn trace_event(&mut self, event: Event) {
         match self {
@@ -113,6 +152,106 @@ impl<T: Tracer> Tracer for Option<T> {
     }
 }
 
+/// Reports progress of protocol execution in percents
+///
+/// Adapter that turns protocol events into determinate progress: it's given amount of rounds
+/// the protocol goes through, and calls `on_progress` every time a new round begins. Amount
+/// of rounds can be obtained from the protocol builder, e.g. `KeygenBuilder::rounds_count`.
+///
+/// Progress is measured in completed rounds, so it reaches 100% only when protocol ends.
+///
+/// ## Example
+/// ```rust
+/// use cggmp21_keygen::progress::{Progress, ProgressTracer, Tracer};
+///
+/// let mut tracer = ProgressTracer::new(2, |progress: Progress| {
+///     println!("{:.0}%", progress.percent());
+/// });
+/// tracer.protocol_begins(); // 0%
+/// tracer.round_begins(); // 0%
+/// tracer.round_begins(); // 50%
+/// tracer.protocol_ends(); // 100%
+/// ```
+pub struct ProgressTracer<F> {
+    total_rounds: u16,
+    rounds_begun: u16,
+    on_progress: F,
+}
+
+/// Progress of protocol execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Progress {
+    /// Amount of completed rounds
+    pub completed_rounds: u16,
+    /// Total amount of rounds
+    pub total_rounds: u16,
+}
+
+impl Progress {
+    /// Returns progress in percents, a number within `[0, 100]`
+    pub fn percent(&self) -> f64 {
+        if self.total_rounds == 0 {
+            return 100.;
+        }
+        let completed = self.completed_rounds.min(self.total_rounds);
+        f64::from(completed) * 100. / f64::from(self.total_rounds)
+    }
+
+    /// Indicates whether protocol is completed
+    pub fn is_completed(&self) -> bool {
+        self.completed_rounds >= self.total_rounds
+    }
+}
+
+impl<F> ProgressTracer<F>
+where
+    F: FnMut(Progress) + Send + Sync,
+{
+    /// Constructs a tracer
+    ///
+    /// `total_rounds` is amount of rounds the protocol goes through, `on_progress` is called
+    /// every time progress changes.
+    pub fn new(total_rounds: u16, on_progress: F) -> Self {
+        Self {
+            total_rounds,
+            rounds_begun: 0,
+            on_progress,
+        }
+    }
+
+    /// Returns current progress
+    pub fn progress(&self) -> Progress {
+        Progress {
+            completed_rounds: self.rounds_begun.saturating_sub(1).min(self.total_rounds),
+            total_rounds: self.total_rounds,
+        }
+    }
+}
+
+impl<F> Tracer for ProgressTracer<F>
+where
+    F: FnMut(Progress) + Send + Sync,
+{
+    fn trace_event(&mut self, event: Event) {
+        match event {
+            Event::ProtocolBegins => self.rounds_begun = 0,
+            Event::RoundBegins { .. } => self.rounds_begun = self.rounds_begun.saturating_add(1),
+            Event::ProtocolEnds => {
+                let progress = Progress {
+                    completed_rounds: self.total_rounds,
+                    total_rounds: self.total_rounds,
+                };
+                (self.on_progress)(progress);
+                return;
+            }
+            _ => return,
+        }
+        let progress = self.progress();
+        (self.on_progress)(progress)
+    }
+}
+
 /// Profiles performance of the protocol
 ///
 /// Implements [`Tracer`] trait so it can be embedded into protocol execution. `PerfProfiler` keeps track of time
@@ -124,10 +263,38 @@ pub struct PerfProfiler {
     protocol_began: Option<Instant>,
     report: PerfReport,
     error: Option<ProfileError>,
+    arrivals: Option<MsgArrivals>,
+}
+
+/// Records time when messages from other parties arrive
+///
+/// Obtained via [`PerfProfiler::track_msg_arrivals`]. Protocol doesn't observe individual
+/// messages, so arrivals must be reported by the transport layer by calling
+/// [`record`](Self::record) each time a message is received.
+#[derive(Debug, Clone, Default)]
+pub struct MsgArrivals(Arc<Mutex<Vec<(u16, Instant)>>>);
+
+impl MsgArrivals {
+    /// Records that message from `sender` has just arrived
+    pub fn record(&self, sender: u16) {
+        let now = Instant::now();
+        match self.0.lock() {
+            Ok(mut arrivals) => arrivals.push((sender, now)),
+            Err(poisoned) => poisoned.into_inner().push((sender, now)),
+        }
+    }
+
+    fn take(&self) -> Vec<(u16, Instant)> {
+        match self.0.lock() {
+            Ok(mut arrivals) => std::mem::take(&mut *arrivals),
+            Err(poisoned) => std::mem::take(&mut *poisoned.into_inner()),
+        }
+    }
 }
 
 /// Performance report generated by [`PerfProfiler`]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PerfReport {
     /// Duration of setup phase (time after protocol began and before first round started)
     pub setup: Duration,
@@ -135,14 +302,21 @@ pub struct PerfReport {
     pub setup_stages: Vec<StageDuration>,
     /// Performance report for each round
     pub rounds: Vec<RoundDuration>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "display_io_default"))]
     display_io: bool,
 }
 
+#[cfg(feature = "serde")]
+fn display_io_default() -> bool {
+    true
+}
+
 /// Performance of specific round (part of [`PerfReport`])
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RoundDuration {
     /// Round name (if provided)
-    pub round_name: Option<&'static str>,
+    pub round_name: Option<Cow<'static, str>>,
     /// Stages of the round
     pub stages: Vec<StageDuration>,
     /// Total duration of pure computation performed during the round
@@ -151,13 +325,25 @@ pub struct RoundDuration {
     pub sending: Duration,
     /// Total time we spent during this round on receiving messages
     pub receiving: Duration,
+    /// Time we were waiting for each peer during this round
+    ///
+    /// Only populated if [arrivals tracking](PerfProfiler::track_msg_arrivals) is enabled. Peers
+    /// whose messages arrived before we started waiting for them are not listed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub peers_wait: BTreeMap<u16, Duration>,
+    /// Peer whose message arrived last, i.e. the one who we waited for the longest
+    ///
+    /// Only populated if [arrivals tracking](PerfProfiler::track_msg_arrivals) is enabled.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub slowest_peer: Option<u16>,
 }
 
 /// Performance of specific stage (part of [`PerfReport`])
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StageDuration {
     /// Stage name
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
     /// Duration of the stage
     pub duration: Duration,
 }
@@ -205,9 +391,22 @@ impl PerfProfiler {
                 display_io: true,
             },
             error: None,
+            arrivals: None,
         }
     }
 
+    /// Enables tracking of time we wait for each peer
+    ///
+    /// Returns a handle that must be given to the transport layer which reports each received
+    /// message via [`MsgArrivals::record`]. Wait time is then attributed to the peers and
+    /// surfaced in [`RoundDuration::peers_wait`] and [`RoundDuration::slowest_peer`], so
+    /// consistently slow committee members can be identified.
+    pub fn track_msg_arrivals(&mut self) -> MsgArrivals {
+        self.arrivals
+            .get_or_insert_with(MsgArrivals::default)
+            .clone()
+    }
+
     /// Obtains a report
     ///
     /// Returns error if protocol behaved unexpectedly
@@ -220,6 +419,10 @@ impl PerfProfiler {
     }
 
     fn try_trace_event(&mut self, event: Event) -> Result<(), ProfileError> {
+        if let Event::ProcessPeer { .. } = event {
+            // Doesn't affect measurements
+            return Ok(());
+        }
         let now = Instant::now();
 
         if Self::event_can_finish_ongoing_stage(&event) {
@@ -252,6 +455,8 @@ impl PerfProfiler {
                     computation: Duration::ZERO,
                     sending: Duration::ZERO,
                     receiving: Duration::ZERO,
+                    peers_wait: BTreeMap::new(),
+                    slowest_peer: None,
                 })
             }
             Event::Stage { name } => {
@@ -284,11 +489,32 @@ impl PerfProfiler {
                 let last_timestamp = self.last_timestamp()?;
                 let last_round = self.last_round_mut()?;
                 last_round.computation += now - last_timestamp;
+                // Messages arrived before we started waiting are not attributed
+                if let Some(arrivals) = &self.arrivals {
+                    arrivals.take();
+                }
             }
-            Event::MsgsReceived => {
+            Event::MsgsReceived { .. } => {
                 let last_timestamp = self.last_timestamp()?;
+                let arrivals = self
+                    .arrivals
+                    .as_ref()
+                    .map(|arrivals| arrivals.take())
+                    .unwrap_or_default();
                 let last_round = self.last_round_mut()?;
                 last_round.receiving += now - last_timestamp;
+
+                let mut slowest: Option<(u16, Duration)> = None;
+                for (sender, arrived_at) in arrivals {
+                    let wait = arrived_at.saturating_duration_since(last_timestamp);
+                    *last_round.peers_wait.entry(sender).or_default() += wait;
+                    if slowest.map_or(true, |(_, longest)| wait >= longest) {
+                        slowest = Some((sender, wait));
+                    }
+                }
+                if let Some((sender, _)) = slowest {
+                    last_round.slowest_peer = Some(sender);
+                }
             }
             Event::SendMsg => {
                 let last_timestamp = self.last_timestamp()?;
@@ -305,6 +531,9 @@ impl PerfProfiler {
                 let last_round = self.last_round_mut()?;
                 last_round.computation += now - last_timestamp;
             }
+            Event::ProcessPeer { .. } => {
+                // Handled above
+            }
         }
 
         self.last_timestamp = Some(now);
@@ -352,6 +581,214 @@ impl PerfReport {
     }
 }
 
+impl PerfReport {
+    /// Total duration of the protocol, including time spent on i/o
+    pub fn total(&self) -> Duration {
+        self.setup
+            + self
+                .rounds
+                .iter()
+                .map(|r| r.computation + r.sending + r.receiving)
+                .sum::<Duration>()
+    }
+
+    /// Total time we were waiting for each peer during the protocol
+    ///
+    /// Only populated if [arrivals tracking](PerfProfiler::track_msg_arrivals) is enabled
+    pub fn peers_wait(&self) -> BTreeMap<u16, Duration> {
+        let mut total = BTreeMap::new();
+        for (j, wait) in self.rounds.iter().flat_map(|r| &r.peers_wait) {
+            *total.entry(*j).or_default() += *wait;
+        }
+        total
+    }
+
+    /// Exports report in CSV format
+    ///
+    /// Each line corresponds to a stage or a part of the round (computation, sending, receiving).
+    /// Columns: `round,round_name,kind,name,duration_ns`. Setup phase has round index `0`.
+    ///
+    /// Report can also be exported to JSON (or any other format) via `serde` traits.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("round,round_name,kind,name,duration_ns\n");
+        let mut write_line =
+            |round: usize, round_name: &str, kind: &str, name: &str, d: Duration| {
+                csv += &format!(
+                    "{round},{},{kind},{},{}\n",
+                    csv_escape(round_name),
+                    csv_escape(name),
+                    d.as_nanos()
+                );
+            };
+
+        write_line(0, "setup", "total", "", self.setup);
+        for stage in &self.setup_stages {
+            write_line(0, "setup", "stage", &stage.name, stage.duration);
+        }
+        for (i, round) in self.rounds.iter().enumerate() {
+            let round_name = round.round_name.as_deref().unwrap_or("");
+            write_line(i + 1, round_name, "computation", "", round.computation);
+            write_line(i + 1, round_name, "sending", "", round.sending);
+            write_line(i + 1, round_name, "receiving", "", round.receiving);
+            for stage in &round.stages {
+                write_line(i + 1, round_name, "stage", &stage.name, stage.duration);
+            }
+        }
+        csv
+    }
+
+    /// Merges reports of multiple runs into statistics
+    ///
+    /// All reports must be obtained from the same protocol (i.e. they must have the same amount
+    /// of rounds), otherwise `None` is returned. Also returns `None` if `reports` are empty.
+    pub fn merge<'r>(reports: impl IntoIterator<Item = &'r PerfReport>) -> Option<PerfStats> {
+        let reports = reports.into_iter().collect::<Vec<_>>();
+        let first = reports.first()?;
+        if reports.iter().any(|r| r.rounds.len() != first.rounds.len()) {
+            return None;
+        }
+
+        let stats = |f: &dyn Fn(&PerfReport) -> Duration| {
+            DurationStats::new(reports.iter().map(|r| f(r)).collect())
+        };
+        let rounds = (0..first.rounds.len())
+            .map(|i| RoundStats {
+                round_name: first.rounds[i].round_name.clone(),
+                computation: stats(&|r| r.rounds[i].computation),
+                sending: stats(&|r| r.rounds[i].sending),
+                receiving: stats(&|r| r.rounds[i].receiving),
+            })
+            .collect();
+
+        Some(PerfStats {
+            runs: reports.len(),
+            total: stats(&PerfReport::total),
+            setup: stats(&|r| r.setup),
+            rounds,
+        })
+    }
+}
+
+/// Statistics over multiple [`PerfReport`]s
+///
+/// Obtained via [`PerfReport::merge`]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PerfStats {
+    /// Amount of runs
+    pub runs: usize,
+    /// Total duration of the protocol
+    pub total: DurationStats,
+    /// Duration of setup phase
+    pub setup: DurationStats,
+    /// Statistics for each round
+    pub rounds: Vec<RoundStats>,
+}
+
+/// Statistics of specific round (part of [`PerfStats`])
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoundStats {
+    /// Round name (if provided)
+    pub round_name: Option<Cow<'static, str>>,
+    /// Duration of pure computation
+    pub computation: DurationStats,
+    /// Time spent on sending messages
+    pub sending: DurationStats,
+    /// Time spent on receiving messages
+    pub receiving: DurationStats,
+}
+
+/// Statistics of a duration measured across multiple runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DurationStats {
+    /// Mean
+    pub mean: Duration,
+    /// Minimal value
+    pub min: Duration,
+    /// Maximal value
+    pub max: Duration,
+    /// Median (50th percentile)
+    pub p50: Duration,
+    /// 90th percentile
+    pub p90: Duration,
+    /// 99th percentile
+    pub p99: Duration,
+}
+
+impl DurationStats {
+    /// Computes statistics of measurements
+    ///
+    /// Returns all zeroes if `measurements` are empty
+    pub fn new(mut measurements: Vec<Duration>) -> Self {
+        measurements.sort_unstable();
+        let percentile = |p: usize| {
+            if measurements.is_empty() {
+                Duration::ZERO
+            } else {
+                // nearest-rank method
+                let rank = (p * measurements.len() + 99) / 100;
+                measurements[rank.clamp(1, measurements.len()) - 1]
+            }
+        };
+        let mean = match u32::try_from(measurements.len()) {
+            Ok(0) | Err(_) => Duration::ZERO,
+            Ok(len) => measurements.iter().sum::<Duration>() / len,
+        };
+        Self {
+            mean,
+            min: measurements.first().copied().unwrap_or_default(),
+            max: measurements.last().copied().unwrap_or_default(),
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+        }
+    }
+}
+
+impl PerfStats {
+    /// Exports statistics in CSV format
+    ///
+    /// Columns: `round,round_name,kind,mean_ns,min_ns,max_ns,p50_ns,p90_ns,p99_ns`. Setup phase has
+    /// round index `0`, and whole protocol is reported as round `total`.
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            String::from("round,round_name,kind,mean_ns,min_ns,max_ns,p50_ns,p90_ns,p99_ns\n");
+        let mut write_line =
+            |round: &dyn fmt::Display, round_name: &str, kind: &str, s: &DurationStats| {
+                csv += &format!(
+                    "{round},{},{kind},{},{},{},{},{},{}\n",
+                    csv_escape(round_name),
+                    s.mean.as_nanos(),
+                    s.min.as_nanos(),
+                    s.max.as_nanos(),
+                    s.p50.as_nanos(),
+                    s.p90.as_nanos(),
+                    s.p99.as_nanos(),
+                );
+            };
+
+        write_line(&"total", "", "total", &self.total);
+        write_line(&0, "setup", "computation", &self.setup);
+        for (i, round) in self.rounds.iter().enumerate() {
+            let round_name = round.round_name.as_deref().unwrap_or("");
+            write_line(&(i + 1), round_name, "computation", &round.computation);
+            write_line(&(i + 1), round_name, "sending", &round.sending);
+            write_line(&(i + 1), round_name, "receiving", &round.receiving);
+        }
+        csv
+    }
+}
+
+fn csv_escape(s: &str) -> Cow<str> {
+    if s.contains([',', '"', '\n']) {
+        Cow::Owned(format!("\"{}\"", s.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
 impl fmt::Display for PerfReport {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let total_computation =
@@ -387,13 +824,21 @@ impl fmt::Display for PerfReport {
         }
 
         writeln!(f, "In particular:")?;
-        Self::fmt_round(f, 0, Some("Stage"), &self.setup_stages, self.setup, None)?;
+        Self::fmt_round(
+            f,
+            0,
+            Some("Stage"),
+            &self.setup_stages,
+            self.setup,
+            None,
+            None,
+        )?;
 
         for (i, round) in self.rounds.iter().enumerate() {
             Self::fmt_round(
                 f,
                 i + 1,
-                round.round_name,
+                round.round_name.as_deref(),
                 &round.stages,
                 round.computation,
                 if self.display_io {
@@ -401,6 +846,10 @@ impl fmt::Display for PerfReport {
                 } else {
                     None
                 },
+                round.slowest_peer.map(|peer| {
+                    let wait = round.peers_wait.get(&peer).copied().unwrap_or_default();
+                    (peer, wait)
+                }),
             )?;
         }
 
@@ -416,6 +865,7 @@ impl PerfReport {
         stages: &[StageDuration],
         computation: Duration,
         io: Option<(Duration, Duration)>, // (sending, receiving)
+        slowest_peer: Option<(u16, Duration)>,
     ) -> fmt::Result {
         let total_duration = computation + io.map(|(s, r)| s + r).unwrap_or_default();
         if let Some(round_name) = round_name {
@@ -436,6 +886,9 @@ impl PerfReport {
             )?;
             writeln!(f, "      - Send: {:.2?}", sending)?;
             writeln!(f, "      - Recv: {:.2?}", receiving)?;
+            if let Some((peer, wait)) = slowest_peer {
+                writeln!(f, "        - Slowest peer: {peer} (waited {wait:.2?})")?;
+            }
         }
 
         if !stages.is_empty() || io.is_some() {
@@ -478,3 +931,400 @@ fn percent(part: Duration, total: Duration) -> impl fmt::Display {
 
     Percentage(part, total)
 }
+
+/// Records all traced events along with their context
+///
+/// Each event is annotated with index of the round it occurred in and time elapsed since
+/// protocol began. Recorded events can be serialized and persisted for later analysis.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventLog {
+    events: Vec<TracedEvent>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    context: EventContext,
+}
+
+/// Keeps track of context in which events occur
+#[derive(Debug, Clone, Copy, Default)]
+struct EventContext {
+    protocol_began: Option<Instant>,
+    round: Option<u16>,
+}
+
+impl EventContext {
+    fn annotate(&mut self, event: Event) -> TracedEvent {
+        let now = Instant::now();
+        match event {
+            Event::ProtocolBegins => {
+                self.protocol_began = Some(now);
+                self.round = None;
+            }
+            Event::RoundBegins { .. } => {
+                self.round = Some(self.round.map(|r| r.saturating_add(1)).unwrap_or(1));
+            }
+            _ => (),
+        }
+        let elapsed = self
+            .protocol_began
+            .map(|began| now - began)
+            .unwrap_or_default();
+        TracedEvent {
+            round: self.round,
+            elapsed,
+            event,
+        }
+    }
+}
+
+/// Event with context (part of [`EventLog`] and [`SinkTracer`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TracedEvent {
+    /// Index of the round (starting from 1) in which event occurred, or `None`
+    /// if event occurred before the first round
+    pub round: Option<u16>,
+    /// Time elapsed since protocol began
+    pub elapsed: Duration,
+    /// The event
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub event: Event,
+}
+
+impl EventLog {
+    /// Constructs an empty log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns all recorded events
+    pub fn events(&self) -> &[TracedEvent] {
+        &self.events
+    }
+
+    /// Takes recorded events out of the log
+    pub fn take_events(&mut self) -> Vec<TracedEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+impl Tracer for EventLog {
+    fn trace_event(&mut self, event: Event) {
+        let event = self.context.annotate(event);
+        self.events.push(event)
+    }
+}
+
+/// Streams traced events into an async channel
+///
+/// Pushes every event, annotated with its context, into a [`Sink`](futures::Sink), so progress
+/// can be consumed live, concurrently with the running protocol.
+///
+/// Tracer never blocks the protocol: if the sink is not ready to accept an event (e.g. bounded
+/// channel is full) or it's closed, the event is discarded. Amount of discarded events can be
+/// obtained via [`dropped_events`](Self::dropped_events). Use an unbounded channel (see
+/// [`SinkTracer::unbounded`]) to make sure that no events are lost.
+///
+/// ## Example
+/// ```rust
+/// use cggmp21_keygen::progress::{SinkTracer, Tracer};
+/// use futures::StreamExt;
+///
+/// # futures::executor::block_on(async {
+/// let (mut tracer, events) = SinkTracer::unbounded();
+/// tracer.protocol_begins();
+/// tracer.round_begins();
+/// tracer.protocol_ends();
+/// drop(tracer);
+///
+/// let events = events.collect::<Vec<_>>().await;
+/// assert_eq!(events.len(), 3);
+/// assert_eq!(events[2].round, Some(1));
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct SinkTracer<S> {
+    sink: S,
+    context: EventContext,
+    dropped: u64,
+}
+
+impl SinkTracer<futures::channel::mpsc::UnboundedSender<TracedEvent>> {
+    /// Constructs a tracer that streams events into an unbounded channel
+    ///
+    /// Returns a tracer and a receiving end of the channel
+    pub fn unbounded() -> (Self, futures::channel::mpsc::UnboundedReceiver<TracedEvent>) {
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        (Self::new(tx), rx)
+    }
+}
+
+impl<S> SinkTracer<S>
+where
+    S: futures::Sink<TracedEvent> + Unpin + Send + Sync,
+{
+    /// Constructs a tracer that streams events into the sink
+    pub fn new(sink: S) -> Self {
+        Self {
+            sink,
+            context: EventContext::default(),
+            dropped: 0,
+        }
+    }
+
+    /// Amount of events that were discarded as the sink wasn't ready to accept them
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Returns the underlying sink
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+}
+
+impl<S> Tracer for SinkTracer<S>
+where
+    S: futures::Sink<TracedEvent> + Unpin + Send + Sync,
+{
+    fn trace_event(&mut self, event: Event) {
+        use std::pin::Pin;
+        use std::task::{Context, Poll};
+
+        let event = self.context.annotate(event);
+
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+        let mut sink = Pin::new(&mut self.sink);
+        let sent = match sink.as_mut().poll_ready(&mut cx) {
+            Poll::Ready(Ok(())) => sink.as_mut().start_send(event).is_ok(),
+            Poll::Ready(Err(_)) | Poll::Pending => false,
+        };
+        if sent {
+            // Flushing is best-effort: if sink is not flushed now, event will be flushed
+            // along with the next one
+            let _ = sink.poll_flush(&mut cx);
+        } else {
+            self.dropped = self.dropped.saturating_add(1);
+        }
+    }
+}
+
+/// Accounts network traffic per round and per peer
+///
+/// Protocol itself doesn't know how messages are serialized, so sizes of sent and received messages
+/// must be reported by the transport layer via [`record_sent`](Self::record_sent) and
+/// [`record_received`](Self::record_received). Tracer is cheaply clonable: one copy can be given
+/// to the protocol as a [`Tracer`], and another one to the transport layer. Traffic is attributed to
+/// the round which was ongoing at the moment it was recorded.
+#[derive(Debug, Clone, Default)]
+pub struct BandwidthTracer {
+    report: Arc<Mutex<BandwidthReport>>,
+}
+
+/// Report generated by [`BandwidthTracer`]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BandwidthReport {
+    /// Traffic occurred before the first round began
+    pub setup: RoundBandwidth,
+    /// Traffic of each round
+    pub rounds: Vec<RoundBandwidth>,
+}
+
+/// Traffic of specific round (part of [`BandwidthReport`])
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoundBandwidth {
+    /// Round name (if provided)
+    pub round_name: Option<Cow<'static, str>>,
+    /// Bytes sent over broadcast channel
+    pub sent_broadcast: u64,
+    /// Bytes sent to each peer over p2p channel
+    pub sent_p2p: BTreeMap<u16, u64>,
+    /// Bytes received from each peer
+    pub received: BTreeMap<u16, u64>,
+}
+
+impl BandwidthTracer {
+    /// Constructs a new tracer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a sent message of given size
+    ///
+    /// `recipient` is `None` if message was broadcasted
+    pub fn record_sent(&self, recipient: Option<u16>, bytes: usize) {
+        self.with_current_round(|round| match recipient {
+            Some(j) => *round.sent_p2p.entry(j).or_default() += bytes as u64,
+            None => round.sent_broadcast += bytes as u64,
+        })
+    }
+
+    /// Records a received message of given size
+    pub fn record_received(&self, sender: u16, bytes: usize) {
+        self.with_current_round(|round| *round.received.entry(sender).or_default() += bytes as u64)
+    }
+
+    /// Obtains a report
+    pub fn get_report(&self) -> BandwidthReport {
+        match self.report.lock() {
+            Ok(report) => report.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    fn with_current_round(&self, f: impl FnOnce(&mut RoundBandwidth)) {
+        let mut report = match self.report.lock() {
+            Ok(report) => report,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let report = &mut *report;
+        f(report.rounds.last_mut().unwrap_or(&mut report.setup))
+    }
+}
+
+impl Tracer for BandwidthTracer {
+    fn trace_event(&mut self, event: Event) {
+        let mut report = match self.report.lock() {
+            Ok(report) => report,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        match event {
+            Event::ProtocolBegins => *report = BandwidthReport::default(),
+            Event::RoundBegins { name } => report.rounds.push(RoundBandwidth {
+                round_name: name,
+                ..Default::default()
+            }),
+            _ => (),
+        }
+    }
+}
+
+impl BandwidthReport {
+    /// Total amount of bytes sent by local party
+    ///
+    /// Broadcast messages are counted once
+    pub fn total_sent(&self) -> u64 {
+        self.all_rounds().map(RoundBandwidth::total_sent).sum()
+    }
+
+    /// Total amount of bytes received by local party
+    pub fn total_received(&self) -> u64 {
+        self.all_rounds().map(RoundBandwidth::total_received).sum()
+    }
+
+    /// Total amount of bytes received from each peer
+    pub fn received_per_peer(&self) -> BTreeMap<u16, u64> {
+        let mut total = BTreeMap::new();
+        for (j, bytes) in self.all_rounds().flat_map(|r| &r.received) {
+            *total.entry(*j).or_default() += bytes;
+        }
+        total
+    }
+
+    fn all_rounds(&self) -> impl Iterator<Item = &RoundBandwidth> {
+        std::iter::once(&self.setup).chain(&self.rounds)
+    }
+}
+
+impl RoundBandwidth {
+    /// Amount of bytes sent during the round
+    pub fn total_sent(&self) -> u64 {
+        self.sent_broadcast + self.sent_p2p.values().sum::<u64>()
+    }
+
+    /// Amount of bytes received during the round
+    pub fn total_received(&self) -> u64 {
+        self.received.values().sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::StreamExt;
+
+    use super::{Event, SinkTracer, Tracer};
+
+    fn trace_protocol(tracer: &mut impl Tracer) {
+        tracer.protocol_begins();
+        tracer.stage("prepare");
+        tracer.named_round_begins("commit");
+        tracer.send_msg();
+        tracer.msg_sent();
+        tracer.receive_msgs();
+        tracer.msgs_received_count(2);
+        tracer.process_peer(1);
+        tracer.round_begins();
+        tracer.protocol_ends();
+    }
+
+    #[test]
+    fn sink_tracer_streams_events_in_order() {
+        let (mut tracer, events) = SinkTracer::unbounded();
+        trace_protocol(&mut tracer);
+        assert_eq!(tracer.dropped_events(), 0);
+        drop(tracer);
+
+        let events = futures::executor::block_on(events.collect::<Vec<_>>());
+        let expected = [
+            (None, Event::ProtocolBegins),
+            (
+                None,
+                Event::Stage {
+                    name: "prepare".into(),
+                },
+            ),
+            (
+                Some(1),
+                Event::RoundBegins {
+                    name: Some("commit".into()),
+                },
+            ),
+            (Some(1), Event::SendMsg),
+            (Some(1), Event::MsgSent),
+            (Some(1), Event::ReceiveMsgs),
+            (Some(1), Event::MsgsReceived { count: Some(2) }),
+            (Some(1), Event::ProcessPeer { peer: 1 }),
+            (Some(2), Event::RoundBegins { name: None }),
+            (Some(2), Event::ProtocolEnds),
+        ];
+        let actual = events
+            .iter()
+            .map(|e| (e.round, e.event.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+        assert!(events.windows(2).all(|w| w[0].elapsed <= w[1].elapsed));
+    }
+
+    #[test]
+    fn sink_tracer_drops_events_when_sink_is_full() {
+        let (tx, events) = futures::channel::mpsc::channel(1);
+        let mut tracer = SinkTracer::new(tx);
+        trace_protocol(&mut tracer);
+        let dropped = tracer.dropped_events();
+        drop(tracer);
+
+        let events = futures::executor::block_on(events.collect::<Vec<_>>());
+        assert!(dropped > 0);
+        assert_eq!(events.len() as u64 + dropped, 10);
+        // Events that fit into the channel are delivered in order
+        assert_eq!(events[0].event, Event::ProtocolBegins);
+        assert_eq!(
+            events[1].event,
+            Event::Stage {
+                name: "prepare".into()
+            }
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn event_log_serialization_roundtrip() {
+        let mut log = super::EventLog::new();
+        trace_protocol(&mut log);
+
+        let serialized = serde_json::to_string(&log).unwrap();
+        let deserialized: super::EventLog = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.events(), log.events());
+    }
+}
@@ -0,0 +1,100 @@
+//! Utilities for reproducible tests
+//!
+//! Allows deriving all randomness of the protocol from a single seed, so multi-party test
+//! fixtures and test vectors can be regenerated bit-by-bit. Available only with `test-utils`
+//! feature.
+//!
+//! **Never use it in production.** Anyone who knows the seed learns all the secrets generated
+//! with it, including the key shares.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # use cggmp21_keygen::{security_level::SecurityLevel128, ThresholdMsg};
+//! # async fn doc<E: generic_ec::Curve>(
+//! #     party: impl round_based::Mpc<ProtocolMessage = ThresholdMsg<E, SecurityLevel128, sha2::Sha256>>,
+//! # ) -> Result<(), cggmp21_keygen::KeygenError> {
+//! use cggmp21_keygen::test_utils::SeededRng;
+//!
+//! let seed = b"test fixture seed";
+//! let eid = cggmp21_keygen::ExecutionId::new(b"execution id");
+//! let (i, n, t) = (0, 3, 2);
+//!
+//! // Each party derives its randomness from the same seed and its own index
+//! let mut rng = SeededRng::for_party(seed, i);
+//! let key_share = cggmp21_keygen::keygen::<E>(eid, i, n)
+//!     .set_threshold(t)
+//!     .start(&mut rng, party)
+//!     .await?;
+//! # Ok(()) }
+//! ```
+
+use digest::Digest;
+
+use crate::rng::HashRng;
+
+type Hasher = Box<dyn Fn(sha2::Sha256) -> digest::Output<sha2::Sha256> + Send + Sync>;
+
+/// Deterministic RNG derived from a seed
+///
+/// Outputs the same sequence of bytes for the same seed (and party index, if it's
+/// [derived for a party](Self::for_party)).
+pub struct SeededRng(HashRng<Hasher, sha2::Sha256>);
+
+#[derive(udigest::Digestable)]
+struct SeedInput<'a> {
+    #[udigest(as_bytes)]
+    seed: &'a [u8],
+    party_index: Option<u16>,
+}
+
+impl SeededRng {
+    /// Constructs RNG from the seed
+    ///
+    /// Useful for the code that's executed by a single entity, like a trusted dealer.
+    pub fn new(seed: &[u8]) -> Self {
+        Self::from_input(SeedInput {
+            seed,
+            party_index: None,
+        })
+    }
+
+    /// Constructs RNG of $i$-th party from the seed
+    ///
+    /// Different parties get independent sequences of bytes, so the same seed can be shared
+    /// between all parties of the protocol.
+    pub fn for_party(seed: &[u8], i: u16) -> Self {
+        Self::from_input(SeedInput {
+            seed,
+            party_index: Some(i),
+        })
+    }
+
+    fn from_input(input: SeedInput) -> Self {
+        let seed = udigest::Tag::<sha2::Sha256>::new("dfns.cggmp21.keygen.test_utils.seeded_rng")
+            .digest(input);
+        let hasher: Hasher = Box::new(move |d: sha2::Sha256| d.chain_update(seed).finalize());
+        Self(HashRng::new(hasher))
+    }
+}
+
+impl rand_core::RngCore for SeededRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+/// Output of the RNG is as unpredictable as the seed. That's sufficient for tests; for production,
+/// seed is never appropriate.
+impl rand_core::CryptoRng for SeededRng {}
@@ -7,13 +7,20 @@ use round_based::{
     rounds_router::simple_store::RoundInput, rounds_router::RoundsRouter, Delivery, Mpc, MpcParty,
     Outgoing, ProtocolMessage,
 };
-use serde::{Deserialize, Serialize};
-use serde_with::serde_as;
+#[cfg(feature = "serde")]
+use serde_with::As;
 
+use crate::abort::{self, MsgAbort};
+use crate::echo_broadcast::MsgEcho;
+use crate::pop::{self, ProofOfPossession};
 use crate::progress::Tracer;
+use crate::transcript::{ThresholdRounds, Transcript};
 use crate::{
     errors::IoError,
-    key_share::{CoreKeyShare, DirtyCoreKeyShare, DirtyKeyInfo, Validate, VssSetup},
+    execution_id::DomainTag,
+    key_share::{
+        CoreKeyShare, DirtyCoreKeyShare, DirtyKeyInfo, Validate, VssSetup, WeightedShares,
+    },
     security_level::SecurityLevel,
     utils, ExecutionId,
 };
@@ -21,8 +28,9 @@ use crate::{
 use super::{Bug, KeygenAborted, KeygenError};
 
 /// Message of key generation protocol
-#[derive(ProtocolMessage, Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(ProtocolMessage, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub enum Msg<E: Curve, L: SecurityLevel, D: Digest> {
     /// Round 1 message
     Round1(MsgRound1<D>),
@@ -34,11 +42,18 @@ pub enum Msg<E: Curve, L: SecurityLevel, D: Digest> {
     Round3(MsgRound3<E>),
     /// Reliability check message (optional additional round)
     ReliabilityCheck(MsgReliabilityCheck<D>),
+    /// Echo of round 2a messages (optional additional round)
+    Round2Echo(MsgEcho<D, 2>),
+    /// Echo of round 3 messages (optional additional round)
+    Round3Echo(MsgEcho<D, 3>),
+    /// Party aborts the protocol (not part of any round)
+    Abort(MsgAbort),
 }
 
 /// Message from round 1
-#[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
-#[serde(bound = "")]
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 #[udigest(bound = "")]
 #[udigest(tag = "dfns.cggmp21.keygen.threshold.round1")]
 pub struct MsgRound1<D: Digest> {
@@ -47,14 +62,14 @@ pub struct MsgRound1<D: Digest> {
     pub commitment: digest::Output<D>,
 }
 /// Message from round 2 broadcasted to everyone
-#[serde_as]
-#[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
-#[serde(bound = "")]
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 #[udigest(bound = "")]
 #[udigest(tag = "dfns.cggmp21.keygen.threshold.round1")]
 pub struct MsgRound2Broad<E: Curve, L: SecurityLevel> {
     /// `rid_i`
-    #[serde_as(as = "utils::HexOrBin")]
+    #[cfg_attr(feature = "serde", serde(with = "As::<utils::HexOrBin>"))]
     #[udigest(as_bytes)]
     pub rid: L::Rid,
     /// $\vec S_i$
@@ -63,31 +78,57 @@ pub struct MsgRound2Broad<E: Curve, L: SecurityLevel> {
     pub sch_commit: schnorr_pok::Commit<E>,
     /// Party contribution to chain code
     #[cfg(feature = "hd-wallets")]
-    #[serde_as(as = "Option<utils::HexOrBin>")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "As::<Option<utils::HexOrBin>>")
+    )]
     #[udigest(with = utils::encoding::maybe_bytes)]
     pub chain_code: Option<slip_10::ChainCode>,
+    /// Commitment to nonce of [proof of possession](crate::pop), if it was requested
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub pop_commit: Option<Point<E>>,
     /// $u_i$
-    #[serde(with = "hex::serde")]
+    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))]
     #[udigest(as_bytes)]
     pub decommit: L::Rid,
 }
 /// Message from round 2 unicasted to each party
-#[derive(Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct MsgRound2Uni<E: Curve> {
     /// $\sigma_{i,j}$
     pub sigma: Scalar<E>,
+    /// Additional shares of recipient, if key is weighted
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra_sigmas: Vec<Scalar<E>>,
 }
 /// Message from round 3
-#[derive(Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+#[udigest(bound = "")]
+#[udigest(tag = "dfns.cggmp21.keygen.threshold.round3")]
 pub struct MsgRound3<E: Curve> {
     /// $\psi_i$
     pub sch_proof: schnorr_pok::Proof<E>,
+    /// Share of [proof of possession](crate::pop), if it was requested
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub pop_share: Option<Scalar<E>>,
 }
 /// Message parties exchange to ensure reliability of broadcast channel
-#[derive(Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct MsgReliabilityCheck<D: Digest>(pub digest::Output<D>);
 
 #[derive(udigest::Digestable)]
@@ -106,21 +147,45 @@ enum Tag<'a> {
     },
 }
 
-pub async fn run_threshold_keygen<E, R, M, L, D>(
+/// Indexes of all shares of weighted key
+#[derive(udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.keygen.threshold.weighted_setup")]
+#[udigest(bound = "")]
+struct WeightedSetup<'a, E: Curve> {
+    I: &'a Vec<NonZero<Scalar<E>>>,
+    extra_I: &'a Vec<Vec<NonZero<Scalar<E>>>>,
+}
+
+pub async fn run_threshold_keygen<E, R, M, L, D, H>(
     mut tracer: Option<&mut dyn Tracer>,
     i: u16,
     t: u16,
     n: u16,
+    key_shares_indexes: Vec<NonZero<Scalar<E>>>,
+    extra_indexes: Option<Vec<Vec<NonZero<Scalar<E>>>>>,
     reliable_broadcast_enforced: bool,
+    echo_broadcast_enforced: bool,
     execution_id: ExecutionId<'_>,
+    domain_tag: Option<DomainTag<'_>>,
+    randomness_beacon: Option<&[u8]>,
+    pop_challenge: Option<&[u8]>,
+    batch_verification: bool,
     rng: &mut R,
     party: M,
     #[cfg(feature = "hd-wallets")] hd_enabled: bool,
-) -> Result<CoreKeyShare<E>, KeygenError>
+) -> Result<
+    (
+        CoreKeyShare<E>,
+        Transcript<ThresholdRounds<E, L, D>>,
+        Option<ProofOfPossession<E>>,
+    ),
+    KeygenError,
+>
 where
     E: Curve,
     L: SecurityLevel,
     D: Digest + Clone + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
     R: RngCore + CryptoRng,
     M: Mpc<ProtocolMessage = Msg<E, L, D>>,
 {
@@ -136,13 +201,16 @@ where
     let round2_broad = rounds.add_round(RoundInput::<MsgRound2Broad<E, L>>::broadcast(i, n));
     let round2_uni = rounds.add_round(RoundInput::<MsgRound2Uni<E>>::p2p(i, n));
     let round3 = rounds.add_round(RoundInput::<MsgRound3<E>>::broadcast(i, n));
-    let mut rounds = rounds.listen(incomings);
+    let round2_echo = rounds.add_round(RoundInput::<MsgEcho<D, 2>>::broadcast(i, n));
+    let round3_echo = rounds.add_round(RoundInput::<MsgEcho<D, 3>>::broadcast(i, n));
+    let mut rounds = rounds.listen(abort::intercept(incomings));
 
     // Round 1
     tracer.round_begins();
 
     tracer.stage("Compute execution id");
-    let sid = execution_id.as_bytes();
+    let sid = utils::domain_separated_sid::<D>(execution_id.as_bytes(), domain_tag);
+    let sid = &*sid;
     let tag = |j| {
         udigest::Tag::<D>::new_structured(Tag::Indexed {
             party_index: j,
@@ -151,21 +219,37 @@ where
     };
     let tag_i = tag(i);
 
+    // Each party holds one share unless key is weighted
+    let weighted = extra_indexes.is_some();
+    let extra_indexes = extra_indexes.unwrap_or_else(|| vec![vec![]; usize::from(n)]);
+    let weighted_setup_hash = weighted.then(|| {
+        udigest::Tag::<D>::new_structured(Tag::Unindexed { sid }).digest(WeightedSetup {
+            I: &key_shares_indexes,
+            extra_I: &extra_indexes,
+        })
+    });
+
     tracer.stage("Sample rid_i, schnorr commitment, polynomial, chain_code");
     let mut rid = L::Rid::default();
     rng.fill_bytes(rid.as_mut());
 
     let (r, h) = schnorr_pok::prover_commits_ephemeral_secret::<E, _>(rng);
+    let pop_nonce = pop_challenge.map(|_| SecretScalar::<E>::random(rng));
 
     let f = Polynomial::<SecretScalar<E>>::sample(rng, usize::from(t) - 1);
     let F = &f * &Point::generator();
     let sigmas = (0..n)
-        .map(|j| {
-            let x = Scalar::from(j + 1);
-            f.value(&x)
-        })
+        .map(|j| f.value(&*key_shares_indexes[usize::from(j)]))
         .collect::<Vec<_>>();
     debug_assert_eq!(sigmas.len(), usize::from(n));
+    let extra_sigmas = extra_indexes
+        .iter()
+        .map(|I_j| {
+            I_j.iter()
+                .map(|I_jk| f.value(&**I_jk))
+                .collect::<Vec<Scalar<E>>>()
+        })
+        .collect::<Vec<_>>();
 
     #[cfg(feature = "hd-wallets")]
     let chain_code_local = if hd_enabled {
@@ -183,6 +267,7 @@ where
         sch_commit: h,
         #[cfg(feature = "hd-wallets")]
         chain_code: chain_code_local,
+        pop_commit: pop_nonce.as_ref().map(|r| Point::generator() * r),
         decommit: {
             let mut nonce = L::Rid::default();
             rng.fill_bytes(nonce.as_mut());
@@ -209,12 +294,12 @@ where
         .complete(round1)
         .await
         .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
+    tracer.msgs_received_count(usize::from(n) - 1);
 
     // Optional reliability check
     if reliable_broadcast_enforced {
         tracer.stage("Hash received msgs (reliability check)");
-        let h_i = udigest::Tag::<D>::new_structured(Tag::Unindexed { sid })
+        let h_i = udigest::Tag::<H>::new_structured(Tag::Unindexed { sid })
             .digest_iter(commitments.iter_including_me(&my_commitment));
 
         tracer.send_msg();
@@ -233,16 +318,16 @@ where
             .complete(round1_sync)
             .await
             .map_err(IoError::receive_message)?;
-        tracer.msgs_received();
+        tracer.msgs_received_count(usize::from(n) - 1);
 
         tracer.stage("Assert other parties hashed messages (reliability check)");
-        let parties_have_different_hashes = hashes
+        let parties = hashes
             .into_iter_indexed()
             .filter(|(_j, _msg_id, h_j)| h_i != h_j.0)
             .map(|(j, msg_id, _)| (j, msg_id))
             .collect::<Vec<_>>();
-        if !parties_have_different_hashes.is_empty() {
-            return Err(KeygenAborted::Round1NotReliable(parties_have_different_hashes).into());
+        if !parties.is_empty() {
+            return Err(KeygenAborted::BroadcastNotReliable { round: 1, parties }.into());
         }
     }
 
@@ -257,6 +342,7 @@ where
     for j in utils::iter_peers(i, n) {
         let message = MsgRound2Uni {
             sigma: sigmas[usize::from(j)],
+            extra_sigmas: extra_sigmas[usize::from(j)].clone(),
         };
         outgoings
             .send(Outgoing::p2p(j, Msg::Round2Uni(message)))
@@ -277,7 +363,38 @@ where
         .complete(round2_uni)
         .await
         .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
+    tracer.msgs_received_count(usize::from(n) - 1);
+
+    // Optional echo broadcast
+    if echo_broadcast_enforced {
+        tracer.stage("Hash received msgs (echo broadcast)");
+        let echo = MsgEcho::<D, 2>::new_with_digest::<H>(
+            sid,
+            decommitments.iter_including_me(&my_decommitment),
+        );
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round2Echo(echo.clone())))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.round_begins();
+
+        tracer.receive_msgs();
+        let echoes = rounds
+            .complete(round2_echo)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received_count(usize::from(n) - 1);
+
+        tracer.stage("Assert other parties echoed the same msgs (echo broadcast)");
+        let parties = echo.find_mismatched(echoes);
+        if !parties.is_empty() {
+            return Err(KeygenAborted::BroadcastNotReliable { round: 2, parties }.into());
+        }
+    }
 
     tracer.stage("Validate decommitments");
     let blame = utils::collect_blame(&commitments, &decommitments, |j, com, decom| {
@@ -289,26 +406,66 @@ where
     }
 
     tracer.stage("Validate data size");
-    let blame = decommitments
-        .iter_indexed()
-        .filter(|(_, _, d)| d.F.degree() + 1 != usize::from(t))
-        .map(|t| t.0)
-        .collect::<Vec<_>>();
-    if !blame.is_empty() {
-        return Err(KeygenAborted::InvalidDataSize { parties: blame }.into());
-    }
-
-    tracer.stage("Validate Feldmann VSS");
     let blame = decommitments
         .iter_indexed()
         .zip(sigmas_msg.iter())
         .filter(|((_, _, d), s)| {
-            d.F.value::<_, Point<_>>(&Scalar::from(i + 1)) != Point::generator() * s.sigma
+            d.F.degree() + 1 != usize::from(t)
+                || s.extra_sigmas.len() != extra_indexes[usize::from(i)].len()
         })
         .map(|t| t.0 .0)
         .collect::<Vec<_>>();
     if !blame.is_empty() {
-        return Err(KeygenAborted::FeldmanVerificationFailed { parties: blame }.into());
+        return Err(KeygenAborted::InvalidDataSize { parties: blame }.into());
+    }
+
+    tracer.stage("Sum up polynomials");
+    let polynomial_sum = decommitments
+        .iter_including_me(&my_decommitment)
+        .map(|d| &d.F)
+        .sum::<Polynomial<_>>();
+
+    // In batch mode, we first check that all received shares are consistent with the sum of
+    // polynomials, which only requires point additions. Parties are checked one by one only
+    // if the batch check fails, to find out who is to blame.
+    let batch_verified = batch_verification && {
+        tracer.stage("Validate Feldmann VSS (batched)");
+        let sigma_sum =
+            sigmas_msg.iter().map(|msg| msg.sigma).sum::<Scalar<E>>() + sigmas[usize::from(i)];
+        polynomial_sum.value::<_, Point<_>>(&*key_shares_indexes[usize::from(i)])
+            == Point::generator() * sigma_sum
+            && extra_indexes[usize::from(i)]
+                .iter()
+                .enumerate()
+                .all(|(k, I_ik)| {
+                    let sigma_sum = sigmas_msg
+                        .iter()
+                        .map(|msg| msg.extra_sigmas[k])
+                        .sum::<Scalar<E>>()
+                        + extra_sigmas[usize::from(i)][k];
+                    polynomial_sum.value::<_, Point<_>>(&**I_ik) == Point::generator() * sigma_sum
+                })
+    };
+    if !batch_verified {
+        tracer.stage("Validate Feldmann VSS");
+        let blame = decommitments
+            .iter_indexed()
+            .zip(sigmas_msg.iter())
+            .filter(|((_, _, d), s)| {
+                d.F.value::<_, Point<_>>(&*key_shares_indexes[usize::from(i)])
+                    != Point::generator() * s.sigma
+                    || extra_indexes[usize::from(i)]
+                        .iter()
+                        .zip(&s.extra_sigmas)
+                        .any(|(I_ik, sigma_k)| {
+                            d.F.value::<_, Point<_>>(&**I_ik) != Point::generator() * sigma_k
+                        })
+            })
+            .map(|t| t.0 .0)
+            .collect::<Vec<_>>();
+        if !blame.is_empty() {
+            return Err(KeygenAborted::FeldmanVerificationFailed { parties: blame }.into());
+        }
     }
 
     tracer.stage("Compute rid");
@@ -316,6 +473,7 @@ where
         .iter_including_me(&my_decommitment)
         .map(|d| &d.rid)
         .fold(L::Rid::default(), utils::xor_array);
+    let rid = utils::mix_randomness_beacon::<D, _>(rid, sid, randomness_beacon);
     #[cfg(feature = "hd-wallets")]
     let chain_code = if hd_enabled {
         tracer.stage("Compute chain_code");
@@ -336,19 +494,43 @@ where
         None
     };
     tracer.stage("Compute Ys");
-    let polynomial_sum = decommitments
-        .iter_including_me(&my_decommitment)
-        .map(|d| &d.F)
-        .sum::<Polynomial<_>>();
-    let ys = (0..n)
-        .map(|l| polynomial_sum.value(&Scalar::from(l + 1)))
+    let default_indexes =
+        (0..n).all(|l| *key_shares_indexes[usize::from(l)] == Scalar::from(l) + Scalar::one());
+    let ys = if default_indexes && !weighted {
+        // Shares are evaluated at 1, 2, ..., n which can be done much faster
+        key_share::poly::evaluate_at_consecutive_points(&polynomial_sum, n)
+    } else {
+        (0..n)
+            .map(|l| polynomial_sum.value(&*key_shares_indexes[usize::from(l)]))
+            .collect()
+    };
+    let ys = ys
+        .into_iter()
         .map(|y_j: Point<E>| NonZero::from_point(y_j).ok_or(Bug::ZeroShare))
         .collect::<Result<Vec<_>, _>>()?;
+    let extra_ys = extra_indexes
+        .iter()
+        .map(|I_j| {
+            I_j.iter()
+                .map(|I_jk| polynomial_sum.value::<_, Point<E>>(&**I_jk))
+                .map(|y_jk| NonZero::from_point(y_jk).ok_or(Bug::ZeroShare))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
     tracer.stage("Compute sigma");
     let sigma: Scalar<E> = sigmas_msg.iter().map(|msg| msg.sigma).sum();
     let mut sigma = sigma + sigmas[usize::from(i)];
     let sigma = NonZero::from_secret_scalar(SecretScalar::new(&mut sigma)).ok_or(Bug::ZeroShare)?;
     debug_assert_eq!(Point::generator() * &sigma, ys[usize::from(i)]);
+    let extra_x = extra_sigmas[usize::from(i)]
+        .iter()
+        .enumerate()
+        .map(|(k, my_sigma)| {
+            let sigma: Scalar<E> = sigmas_msg.iter().map(|msg| msg.extra_sigmas[k]).sum();
+            let mut sigma = sigma + my_sigma;
+            NonZero::from_secret_scalar(SecretScalar::new(&mut sigma)).ok_or(Bug::ZeroShare)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
     tracer.stage("Calculate challenge");
     let challenge = {
@@ -358,6 +540,7 @@ where
                 .chain_update(rid.as_ref())
                 .chain_update(&ys[usize::from(i)].to_bytes(true)) // y_i
                 .chain_update(&my_decommitment.sch_commit.0.to_bytes(false)) // h
+                .chain_update(weighted_setup_hash.as_deref().unwrap_or_default())
                 .finalize()
         };
         let mut rng = crate::rng::HashRng::new(hash);
@@ -368,8 +551,35 @@ where
     tracer.stage("Prove knowledge of `sigma_i`");
     let z = schnorr_pok::prove(&r, &challenge, &sigma);
 
+    let y: Point<E> = decommitments
+        .iter_including_me(&my_decommitment)
+        .map(|d| d.F.coefs()[0])
+        .sum();
+    let pop_setup = match (pop_challenge, &pop_nonce) {
+        (Some(pop_challenge), Some(pop_nonce)) => {
+            tracer.stage("Compute share of proof of possession");
+            let blame =
+                utils::collect_simple_blame(&decommitments, |decom| decom.pop_commit.is_none());
+            if !blame.is_empty() {
+                return Err(KeygenAborted::MissingPopCommitment(blame).into());
+            }
+            let R = decommitments
+                .iter_including_me(&my_decommitment)
+                .map(|d| d.pop_commit.ok_or(Bug::NoPopCommitment))
+                .sum::<Result<Point<E>, _>>()?;
+            let e = pop::hash_challenge::<E, D>(&y, &R, pop_challenge);
+            // Proof of possession is made with additive share of the key which is the free
+            // coefficient of the polynomial
+            Some((R, e, pop_nonce + e * &f.coefs()[0]))
+        }
+        _ => None,
+    };
+
     tracer.send_msg();
-    let my_sch_proof = MsgRound3 { sch_proof: z };
+    let my_sch_proof = MsgRound3 {
+        sch_proof: z,
+        pop_share: pop_setup.map(|(_, _, z_i)| z_i),
+    };
     outgoings
         .send(Outgoing::broadcast(Msg::Round3(my_sch_proof.clone())))
         .await
@@ -384,10 +594,40 @@ where
         .complete(round3)
         .await
         .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
+    tracer.msgs_received_count(usize::from(n) - 1);
+
+    // Optional echo broadcast
+    if echo_broadcast_enforced {
+        tracer.stage("Hash received msgs (echo broadcast)");
+        let echo =
+            MsgEcho::<D, 3>::new_with_digest::<H>(sid, sch_proofs.iter_including_me(&my_sch_proof));
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round3Echo(echo.clone())))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.round_begins();
+
+        tracer.receive_msgs();
+        let echoes = rounds
+            .complete(round3_echo)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received_count(usize::from(n) - 1);
+
+        tracer.stage("Assert other parties echoed the same msgs (echo broadcast)");
+        let parties = echo.find_mismatched(echoes);
+        if !parties.is_empty() {
+            return Err(KeygenAborted::BroadcastNotReliable { round: 3, parties }.into());
+        }
+    }
 
     tracer.stage("Validate schnorr proofs");
     let blame = utils::collect_blame(&decommitments, &sch_proofs, |j, decom, sch_proof| {
+        tracer.process_peer(j);
         let challenge = {
             let hash = |d: D| {
                 d.chain_update(sid)
@@ -395,6 +635,7 @@ where
                     .chain_update(rid.as_ref())
                     .chain_update(&ys[usize::from(j)].to_bytes(true)) // y_i
                     .chain_update(&decom.sch_commit.0.to_bytes(false)) // h
+                    .chain_update(weighted_setup_hash.as_deref().unwrap_or_default())
                     .finalize()
             };
             let mut rng = crate::rng::HashRng::new(hash);
@@ -410,19 +651,44 @@ where
         return Err(KeygenAborted::InvalidSchnorrProof(blame).into());
     }
 
-    tracer.stage("Derive resulting public key and other data");
-    let y: Point<E> = decommitments
-        .iter_including_me(&my_decommitment)
-        .map(|d| d.F.coefs()[0])
-        .sum();
-    let key_shares_indexes = (1..=n)
-        .map(|i| NonZero::from_scalar(Scalar::from(i)))
-        .collect::<Option<Vec<_>>>()
-        .ok_or(Bug::NonZeroScalar)?;
+    let pop = match pop_setup {
+        Some((R, e, _)) => {
+            tracer.stage("Validate shares of proof of possession");
+            let blame =
+                utils::collect_blame(&decommitments, &sch_proofs, |_j, decom, proof| {
+                    match (decom.pop_commit, proof.pop_share) {
+                        (Some(R_j), Some(z_j)) => {
+                            Point::generator() * z_j != R_j + e * decom.F.coefs()[0]
+                        }
+                        _ => true,
+                    }
+                });
+            if !blame.is_empty() {
+                return Err(KeygenAborted::InvalidPopShare(blame).into());
+            }
+            let z = sch_proofs
+                .iter_including_me(&my_sch_proof)
+                .map(|proof| proof.pop_share.ok_or(Bug::NoPopShare))
+                .sum::<Result<Scalar<E>, _>>()?;
+            Some(ProofOfPossession { R, z })
+        }
+        None => None,
+    };
+
+    tracer.stage("Record transcript");
+    let transcript = Transcript {
+        eid: execution_id.as_bytes().to_vec(),
+        i,
+        rounds: ThresholdRounds {
+            round1: commitments.into_vec_including_me(my_commitment),
+            round2: decommitments.into_vec_including_me(my_decommitment),
+            round3: sch_proofs.into_vec_including_me(my_sch_proof),
+        },
+    };
 
     tracer.protocol_ends();
 
-    Ok(DirtyCoreKeyShare {
+    let key_share = DirtyCoreKeyShare {
         i,
         key_info: DirtyKeyInfo {
             curve: Default::default(),
@@ -431,12 +697,20 @@ where
             vss_setup: Some(VssSetup {
                 min_signers: t,
                 I: key_shares_indexes,
+                weighted: weighted.then_some(WeightedShares {
+                    I: extra_indexes,
+                    public_shares: extra_ys,
+                }),
+                commitments: Some(polynomial_sum.into_coefs()),
             }),
             #[cfg(feature = "hd-wallets")]
             chain_code,
         },
         x: sigma,
+        extra_x,
     }
     .validate()
-    .map_err(|err| Bug::InvalidKeyShare(err.into_error()))?)
+    .map_err(|err| Bug::InvalidKeyShare(err.into_error()))?;
+
+    Ok((key_share, transcript, pop))
 }
@@ -0,0 +1,402 @@
+//! Conversion of non-threshold key shares into threshold ones
+//!
+//! Allows n-of-n deployments to adopt threshold signing without generating a new key. Parties
+//! holding additive (non-threshold) shares $x_j$ of the key run a one-round protocol:
+//!
+//! 1. Each party samples a random polynomial $f_j$ of degree $t-1$ such that $f_j(0) = x_j$,
+//!    broadcasts commitment $F_j = f_j \cdot G$ and sends $f_j(I_k)$ to $k$-th party.
+//! 2. Each party checks that $F_j(0)$ matches public share $X_j$ of the original key, and that
+//!    received shares are consistent with $F_j$ (Feldman VSS). Resulting key share is the sum of
+//!    received shares.
+//!
+//! Shared public key is preserved, as $\sum_j f_j(0) = \sum_j x_j = x$. Converted key shares are
+//! evaluations of polynomial $f = \sum_j f_j$ at points $I_k = k + 1$, and, as any threshold key,
+//! they can be used with any $t$ out of $n$ signers. If original key supports HD derivation,
+//! converted key has the same chain code, so derived child keys are preserved as well.
+//!
+//! Commitments must be delivered reliably, so echo broadcast is enforced by default. Once all
+//! parties have obtained converted key shares, original shares must be erased: all $n$ of
+//! them are still sufficient to recover the key.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # async fn doc(
+//! #     rng: &mut (impl rand_core::RngCore + rand_core::CryptoRng),
+//! #     key_share: cggmp21_keygen::key_share::CoreKeyShare<generic_ec::curves::Secp256k1>,
+//! #     party: impl round_based::Mpc<ProtocolMessage = cggmp21_keygen::ToThresholdMsg<generic_ec::curves::Secp256k1, sha2::Sha256>>,
+//! # ) -> Result<(), cggmp21_keygen::KeygenError> {
+//! let eid = cggmp21_keygen::ExecutionId::new(b"execution id, unique per protocol execution");
+//! let t = 2;
+//!
+//! let threshold_key_share = cggmp21_keygen::to_threshold(eid, &key_share, t)
+//!     .start(rng, party)
+//!     .await?;
+//! # Ok(()) }
+//! ```
+
+use digest::Digest;
+use futures::SinkExt;
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use generic_ec_zkp::polynomial::Polynomial;
+use rand_core::{CryptoRng, RngCore};
+use round_based::{
+    rounds_router::simple_store::RoundInput, rounds_router::RoundsRouter, Delivery, Mpc, MpcParty,
+    Outgoing, ProtocolMessage,
+};
+
+use crate::abort::{self, MsgAbort};
+use crate::echo_broadcast::MsgEcho;
+use crate::progress::Tracer;
+use crate::{
+    errors::IoError,
+    execution_id::DomainTag,
+    key_share::{CoreKeyShare, DirtyCoreKeyShare, DirtyKeyInfo, Validate, VssSetup},
+    utils, EidRegistry, ExecutionId,
+};
+
+use super::{Bug, InvalidArgs, KeygenAborted, KeygenError};
+
+/// Message of conversion protocol
+#[derive(ProtocolMessage, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub enum Msg<E: Curve, D: Digest> {
+    /// Round 1 message broadcasted to everyone
+    Round1Broad(MsgRound1Broad<E>),
+    /// Round 1 message unicasted to each party
+    Round1Uni(MsgRound1Uni<E>),
+    /// Echo of round 1 broadcast messages (optional additional round)
+    Round1Echo(MsgEcho<D, 1>),
+    /// Party aborts the protocol (not part of any round)
+    Abort(MsgAbort),
+}
+
+/// Message from round 1 broadcasted to everyone
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+#[udigest(bound = "")]
+#[udigest(tag = "dfns.cggmp21.keygen.to_threshold.round1")]
+pub struct MsgRound1Broad<E: Curve> {
+    /// $F_j$
+    pub F: Polynomial<Point<E>>,
+}
+
+/// Message from round 1 unicasted to each party
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct MsgRound1Uni<E: Curve> {
+    /// $\sigma_{j,k} = f_j(I_k)$
+    pub sigma: Scalar<E>,
+}
+
+/// Builder of the conversion protocol
+///
+/// Can be obtained via [`to_threshold`](fn@crate::to_threshold) function.
+pub struct ToThresholdBuilder<'a, E: Curve, D: Digest = crate::default_choice::Digest> {
+    key_share: &'a CoreKeyShare<E>,
+    t: u16,
+    execution_id: ExecutionId<'a>,
+    echo_broadcast_enforced: bool,
+    tracer: Option<&'a mut dyn Tracer>,
+    eid_registry: Option<&'a dyn EidRegistry>,
+    domain_tag: Option<DomainTag<'a>>,
+    _digest: std::marker::PhantomData<D>,
+}
+
+impl<'a, E, D> ToThresholdBuilder<'a, E, D>
+where
+    E: Curve,
+    D: Digest + Clone + 'static,
+{
+    /// Constructs a builder
+    ///
+    /// Takes non-threshold key share of local party, and threshold $t$ of resulting key
+    pub fn new(eid: ExecutionId<'a>, key_share: &'a CoreKeyShare<E>, t: u16) -> Self {
+        Self {
+            key_share,
+            t,
+            execution_id: eid,
+            echo_broadcast_enforced: true,
+            tracer: None,
+            eid_registry: None,
+            domain_tag: None,
+            _digest: std::marker::PhantomData,
+        }
+    }
+
+    /// Specifies another hash function to use
+    pub fn set_digest<D2>(self) -> ToThresholdBuilder<'a, E, D2>
+    where
+        D2: Digest + Clone + 'static,
+    {
+        ToThresholdBuilder {
+            key_share: self.key_share,
+            t: self.t,
+            execution_id: self.execution_id,
+            echo_broadcast_enforced: self.echo_broadcast_enforced,
+            tracer: self.tracer,
+            eid_registry: self.eid_registry,
+            domain_tag: self.domain_tag,
+            _digest: std::marker::PhantomData,
+        }
+    }
+
+    /// Sets a tracer that tracks progress of protocol execution
+    pub fn set_progress_tracer(mut self, tracer: &'a mut dyn Tracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
+    /// Sets a registry of used execution IDs
+    ///
+    /// Before starting the protocol, execution ID is registered in the registry. If it was
+    /// already used before, protocol won't start and returns an error.
+    pub fn set_eid_registry(mut self, registry: &'a dyn EidRegistry) -> Self {
+        self.eid_registry = Some(registry);
+        self
+    }
+
+    /// Sets application-specific domain separation tag
+    ///
+    /// See [`GenericKeygenBuilder::set_domain_tag`](crate::GenericKeygenBuilder::set_domain_tag).
+    pub fn set_domain_tag(mut self, tag: DomainTag<'a>) -> Self {
+        self.domain_tag = Some(tag);
+        self
+    }
+
+    /// Specifies whether commitments must be delivered via echo broadcast
+    ///
+    /// Enabled by default. Can only be disabled if the transport guarantees reliable broadcast
+    /// on its own, otherwise parties may end up with key shares of different polynomials.
+    pub fn enforce_echo_broadcast(self, enforce: bool) -> Self {
+        Self {
+            echo_broadcast_enforced: enforce,
+            ..self
+        }
+    }
+
+    /// Returns total amount of rounds the protocol will go through with the current settings
+    pub fn rounds_count(&self) -> u16 {
+        1 + u16::from(self.echo_broadcast_enforced)
+    }
+
+    /// Starts the conversion, outputs threshold key share
+    pub async fn start<R, M>(self, rng: &mut R, party: M) -> Result<CoreKeyShare<E>, KeygenError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = Msg<E, D>>,
+    {
+        if self.key_share.vss_setup.is_some() {
+            return Err(InvalidArgs::AlreadyThreshold.into());
+        }
+        let n = self.key_share.n();
+        // Threshold key share must require at least 2 signers
+        if !(2..=n).contains(&self.t) {
+            return Err(InvalidArgs::InvalidThreshold { t: self.t, n }.into());
+        }
+        if let Some(registry) = self.eid_registry {
+            registry.register(self.execution_id)?;
+        }
+        run_conversion(
+            self.tracer,
+            self.key_share,
+            self.t,
+            n,
+            self.echo_broadcast_enforced,
+            self.execution_id,
+            self.domain_tag,
+            rng,
+            party,
+        )
+        .await
+    }
+}
+
+async fn run_conversion<E, R, M, D>(
+    mut tracer: Option<&mut dyn Tracer>,
+    key_share: &CoreKeyShare<E>,
+    t: u16,
+    n: u16,
+    echo_broadcast_enforced: bool,
+    execution_id: ExecutionId<'_>,
+    domain_tag: Option<DomainTag<'_>>,
+    rng: &mut R,
+    party: M,
+) -> Result<CoreKeyShare<E>, KeygenError>
+where
+    E: Curve,
+    D: Digest + Clone + 'static,
+    R: RngCore + CryptoRng,
+    M: Mpc<ProtocolMessage = Msg<E, D>>,
+{
+    tracer.protocol_begins();
+
+    tracer.stage("Setup networking");
+    let MpcParty { delivery, .. } = party.into_party();
+    let (incomings, mut outgoings) = delivery.split();
+
+    let i = key_share.i;
+
+    let mut rounds = RoundsRouter::<Msg<E, D>>::builder();
+    let round1_broad = rounds.add_round(RoundInput::<MsgRound1Broad<E>>::broadcast(i, n));
+    let round1_uni = rounds.add_round(RoundInput::<MsgRound1Uni<E>>::p2p(i, n));
+    let round1_echo = rounds.add_round(RoundInput::<MsgEcho<D, 1>>::broadcast(i, n));
+    let mut rounds = rounds.listen(abort::intercept(incomings));
+
+    // Round 1
+    tracer.round_begins();
+
+    tracer.stage("Compute execution id");
+    let sid = utils::domain_separated_sid::<D>(execution_id.as_bytes(), domain_tag);
+    let sid = &*sid;
+
+    tracer.stage("Sample polynomial");
+    // Free coefficient of the polynomial is additive share of the key, other coefficients are
+    // random
+    let f = Polynomial::<SecretScalar<E>>::sample_with_const_term(
+        rng,
+        usize::from(t) - 1,
+        SecretScalar::from(key_share.x.clone()),
+    );
+    let F = &f * &Point::generator();
+    let I = |j: u16| Scalar::<E>::from(j) + Scalar::one();
+    let sigmas = (0..n).map(|j| f.value(&I(j))).collect::<Vec<Scalar<E>>>();
+
+    tracer.send_msg();
+    let my_commitment = MsgRound1Broad { F };
+    outgoings
+        .send(Outgoing::broadcast(Msg::Round1Broad(my_commitment.clone())))
+        .await
+        .map_err(IoError::send_message)?;
+    for j in utils::iter_peers(i, n) {
+        outgoings
+            .send(Outgoing::p2p(
+                j,
+                Msg::Round1Uni(MsgRound1Uni {
+                    sigma: sigmas[usize::from(j)],
+                }),
+            ))
+            .await
+            .map_err(IoError::send_message)?;
+    }
+    tracer.msg_sent();
+
+    // Output round
+    tracer.round_begins();
+
+    tracer.receive_msgs();
+    let commitments = rounds
+        .complete(round1_broad)
+        .await
+        .map_err(IoError::receive_message)?;
+    let sigmas_msg = rounds
+        .complete(round1_uni)
+        .await
+        .map_err(IoError::receive_message)?;
+    tracer.msgs_received_count(usize::from(n) - 1);
+
+    // Optional echo broadcast
+    if echo_broadcast_enforced {
+        tracer.stage("Hash received msgs (echo broadcast)");
+        let echo = MsgEcho::<D, 1>::new(sid, commitments.iter_including_me(&my_commitment));
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round1Echo(echo.clone())))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.round_begins();
+
+        tracer.receive_msgs();
+        let echoes = rounds
+            .complete(round1_echo)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received_count(usize::from(n) - 1);
+
+        tracer.stage("Assert other parties echoed the same msgs (echo broadcast)");
+        let parties = echo.find_mismatched(echoes);
+        if !parties.is_empty() {
+            return Err(KeygenAborted::BroadcastNotReliable { round: 1, parties }.into());
+        }
+    }
+
+    tracer.stage("Validate data size");
+    let blame = commitments
+        .iter_indexed()
+        .filter(|(_, _, c)| c.F.degree() + 1 != usize::from(t))
+        .map(|(j, _, _)| j)
+        .collect::<Vec<_>>();
+    if !blame.is_empty() {
+        return Err(KeygenAborted::InvalidDataSize { parties: blame }.into());
+    }
+
+    tracer.stage("Validate that polynomials share original key");
+    let blame = commitments
+        .iter_indexed()
+        .filter(|(j, _, c)| c.F.coefs()[0] != *key_share.public_shares[usize::from(*j)])
+        .map(|(j, _, _)| j)
+        .collect::<Vec<_>>();
+    if !blame.is_empty() {
+        return Err(KeygenAborted::PolynomialNotBoundToShare { parties: blame }.into());
+    }
+
+    tracer.stage("Validate Feldmann VSS");
+    let blame = commitments
+        .iter_indexed()
+        .zip(sigmas_msg.iter())
+        .filter(|((_, _, c), s)| c.F.value::<_, Point<_>>(&I(i)) != Point::generator() * s.sigma)
+        .map(|t| t.0 .0)
+        .collect::<Vec<_>>();
+    if !blame.is_empty() {
+        return Err(KeygenAborted::FeldmanVerificationFailed { parties: blame }.into());
+    }
+
+    tracer.stage("Compute Ys");
+    let polynomial_sum = commitments
+        .iter_including_me(&my_commitment)
+        .map(|c| &c.F)
+        .sum::<Polynomial<_>>();
+    let ys = key_share::poly::evaluate_at_consecutive_points(&polynomial_sum, n)
+        .into_iter()
+        .map(|y_j: Point<E>| NonZero::from_point(y_j).ok_or(Bug::ZeroShare))
+        .collect::<Result<Vec<_>, _>>()?;
+    debug_assert_eq!(polynomial_sum.coefs()[0], *key_share.shared_public_key);
+
+    tracer.stage("Compute sigma");
+    let sigma: Scalar<E> = sigmas_msg.iter().map(|msg| msg.sigma).sum();
+    let mut sigma = sigma + sigmas[usize::from(i)];
+    let sigma = NonZero::from_secret_scalar(SecretScalar::new(&mut sigma)).ok_or(Bug::ZeroShare)?;
+    debug_assert_eq!(Point::generator() * &sigma, ys[usize::from(i)]);
+
+    tracer.protocol_ends();
+
+    let I = (0..n)
+        .map(|j| NonZero::from_scalar(I(j)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Bug::NonZeroScalar)?;
+    Ok(DirtyCoreKeyShare {
+        i,
+        key_info: DirtyKeyInfo {
+            curve: Default::default(),
+            shared_public_key: key_share.shared_public_key,
+            public_shares: ys,
+            vss_setup: Some(VssSetup {
+                min_signers: t,
+                I,
+                weighted: None,
+                commitments: Some(polynomial_sum.coefs().to_vec()),
+            }),
+            #[cfg(feature = "hd-wallets")]
+            chain_code: key_share.chain_code,
+        },
+        x: sigma,
+        extra_x: vec![],
+    }
+    .validate()
+    .map_err(|err| Bug::InvalidKeyShare(err.into_error()))?)
+}
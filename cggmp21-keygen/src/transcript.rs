@@ -0,0 +1,78 @@
+//! Transcript of broadcast messages
+//!
+//! Protocols can output a [`Transcript`] of all messages that were broadcast during the execution
+//! (e.g. via [`start_with_transcript`](crate::GenericKeygenBuilder::start_with_transcript)).
+//! Transcript can be archived along with the ceremony and re-verified later. It's identified by
+//! its [hash](Transcript::hash): parties that observed the same broadcast messages output the same
+//! hash, so comparing hashes of transcripts recorded by different parties shows that they all
+//! observed the same ceremony.
+//!
+//! Transcript doesn't contain secret data: p2p messages are not recorded.
+
+use digest::Digest;
+
+use crate::security_level::SecurityLevel;
+
+/// Transcript of broadcast messages of the protocol
+///
+/// `R` contains messages of each round, specific to the protocol.
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[udigest(tag = "dfns.cggmp21.transcript")]
+pub struct Transcript<R> {
+    /// Execution ID of the protocol
+    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))]
+    #[udigest(as_bytes)]
+    pub eid: Vec<u8>,
+    /// Index of the party who recorded the transcript
+    ///
+    /// Not included into the [hash](Self::hash)
+    #[udigest(skip)]
+    pub i: u16,
+    /// Broadcast messages of all parties
+    pub rounds: R,
+}
+
+impl<R: udigest::Digestable> Transcript<R> {
+    /// Hashes the transcript
+    ///
+    /// All honest parties of the same protocol execution output transcripts with the same hash.
+    pub fn hash<D: Digest>(&self) -> digest::Output<D> {
+        udigest::Tag::<D>::new("dfns.cggmp21.transcript").digest(self)
+    }
+}
+
+/// Broadcast messages of non-threshold keygen
+///
+/// `roundN[j]` is the message that $j$-th party sent in round `N`
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+#[udigest(bound = "")]
+#[udigest(tag = "dfns.cggmp21.keygen.non_threshold.transcript")]
+pub struct NonThresholdRounds<E: generic_ec::Curve, L: SecurityLevel, D: Digest> {
+    /// Commitments
+    pub round1: Vec<crate::msg::non_threshold::MsgRound1<D>>,
+    /// Decommitments
+    pub round2: Vec<crate::msg::non_threshold::MsgRound2<E, L>>,
+    /// Schnorr proofs
+    pub round3: Vec<crate::msg::non_threshold::MsgRound3<E>>,
+}
+
+/// Broadcast messages of threshold keygen
+///
+/// `roundN[j]` is the message that $j$-th party sent in round `N`. Shares sent over p2p channels
+/// in round 2 are not recorded.
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+#[udigest(bound = "")]
+#[udigest(tag = "dfns.cggmp21.keygen.threshold.transcript")]
+pub struct ThresholdRounds<E: generic_ec::Curve, L: SecurityLevel, D: Digest> {
+    /// Commitments
+    pub round1: Vec<crate::msg::threshold::MsgRound1<D>>,
+    /// Decommitments
+    pub round2: Vec<crate::msg::threshold::MsgRound2Broad<E, L>>,
+    /// Schnorr proofs
+    pub round3: Vec<crate::msg::threshold::MsgRound3<E>>,
+}
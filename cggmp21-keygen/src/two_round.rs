@@ -0,0 +1,410 @@
+use digest::Digest;
+use futures::SinkExt;
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use generic_ec_zkp::schnorr_pok;
+use rand_core::{CryptoRng, RngCore};
+use round_based::{
+    rounds_router::simple_store::RoundInput, rounds_router::RoundsRouter, Delivery, Mpc, MpcParty,
+    Outgoing, ProtocolMessage,
+};
+#[cfg(feature = "serde")]
+use serde_with::As;
+
+use crate::abort::{self, MsgAbort};
+use crate::echo_broadcast::MsgEcho;
+use crate::progress::Tracer;
+use crate::{
+    errors::IoError,
+    execution_id::DomainTag,
+    key_share::{CoreKeyShare, DirtyCoreKeyShare, DirtyKeyInfo, Validate},
+    security_level::SecurityLevel,
+    utils, ExecutionId,
+};
+
+use super::{Bug, KeygenAborted, KeygenError};
+
+/// Message of two-round key generation protocol
+#[derive(ProtocolMessage, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub enum Msg<E: Curve, L: SecurityLevel, D: Digest> {
+    /// Round 1 message
+    Round1(MsgRound1<D>),
+    /// Reliability check message (optional additional round)
+    ReliabilityCheck(MsgReliabilityCheck<D>),
+    /// Round 2 message
+    Round2(MsgRound2<E, L>),
+    /// Echo of round 2 messages (optional additional round)
+    Round2Echo(MsgEcho<D, 2>),
+    /// Party aborts the protocol (not part of any round)
+    Abort(MsgAbort),
+}
+
+/// Message from round 1
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+#[udigest(bound = "")]
+#[udigest(tag = "dfns.cggmp21.keygen.two_round.round1")]
+pub struct MsgRound1<D: Digest> {
+    /// $V_i$
+    #[udigest(as_bytes)]
+    pub commitment: digest::Output<D>,
+}
+/// Message from round 2
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+#[udigest(bound = "")]
+#[udigest(tag = "dfns.cggmp21.keygen.two_round.round2")]
+pub struct MsgRound2<E: Curve, L: SecurityLevel> {
+    /// Data committed in round 1
+    pub decommitment: Decommitment<E, L>,
+    /// $\psi_i$
+    pub sch_proof: schnorr_pok::Proof<E>,
+}
+/// Data that party commits to in round 1 and reveals in round 2
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+#[udigest(bound = "")]
+#[udigest(tag = "dfns.cggmp21.keygen.two_round.decommitment")]
+pub struct Decommitment<E: Curve, L: SecurityLevel> {
+    /// $X_i$
+    pub X: NonZero<Point<E>>,
+    /// $A_i$
+    pub sch_commit: schnorr_pok::Commit<E>,
+    /// Party contribution to chain code
+    #[cfg(feature = "hd-wallets")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "As::<Option<utils::HexOrBin>>")
+    )]
+    #[udigest(with = utils::encoding::maybe_bytes)]
+    pub chain_code: Option<slip_10::ChainCode>,
+    /// $u_i$
+    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))]
+    #[udigest(as_bytes)]
+    pub decommit: L::Rid,
+}
+/// Message parties exchange to ensure reliability of broadcast channel
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct MsgReliabilityCheck<D: Digest>(pub digest::Output<D>);
+
+#[derive(udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.keygen.two_round.tag")]
+enum Tag<'a> {
+    /// Tag that includes the prover index
+    Indexed {
+        party_index: u16,
+        #[udigest(as_bytes)]
+        sid: &'a [u8],
+    },
+    /// Tag w/o party index
+    Unindexed {
+        #[udigest(as_bytes)]
+        sid: &'a [u8],
+    },
+}
+
+/// Statement of Schnorr proof of $j$-th party
+///
+/// Challenge is derived from commitments of all parties which are fixed before anyone reveals
+/// its data, so nobody can bias the challenge of another party.
+#[derive(udigest::Digestable)]
+#[udigest(bound = "")]
+struct Challenge<'a, E: Curve> {
+    #[udigest(as_bytes)]
+    sid: &'a [u8],
+    prover: u16,
+    #[udigest(as_bytes)]
+    commitments: &'a [u8],
+    #[udigest(with = utils::encoding::maybe_bytes)]
+    randomness_beacon: Option<&'a [u8]>,
+    X: &'a Point<E>,
+    sch_commit: &'a Point<E>,
+}
+
+pub async fn run_keygen<E, R, M, L, D, H>(
+    mut tracer: Option<&mut dyn Tracer>,
+    i: u16,
+    n: u16,
+    reliable_broadcast_enforced: bool,
+    echo_broadcast_enforced: bool,
+    execution_id: ExecutionId<'_>,
+    domain_tag: Option<DomainTag<'_>>,
+    randomness_beacon: Option<&[u8]>,
+    rng: &mut R,
+    party: M,
+    #[cfg(feature = "hd-wallets")] hd_enabled: bool,
+) -> Result<CoreKeyShare<E>, KeygenError>
+where
+    E: Curve,
+    L: SecurityLevel,
+    D: Digest + Clone + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
+    R: RngCore + CryptoRng,
+    M: Mpc<ProtocolMessage = Msg<E, L, D>>,
+{
+    tracer.protocol_begins();
+
+    tracer.stage("Setup networking");
+    let MpcParty { delivery, .. } = party.into_party();
+    let (incomings, mut outgoings) = delivery.split();
+
+    let mut rounds = RoundsRouter::<Msg<E, L, D>>::builder();
+    let round1 = rounds.add_round(RoundInput::<MsgRound1<D>>::broadcast(i, n));
+    let round1_sync = rounds.add_round(RoundInput::<MsgReliabilityCheck<D>>::broadcast(i, n));
+    let round2 = rounds.add_round(RoundInput::<MsgRound2<E, L>>::broadcast(i, n));
+    let round2_echo = rounds.add_round(RoundInput::<MsgEcho<D, 2>>::broadcast(i, n));
+    let mut rounds = rounds.listen(abort::intercept(incomings));
+
+    // Round 1
+    tracer.round_begins();
+
+    tracer.stage("Compute execution id");
+    let sid = utils::domain_separated_sid::<D>(execution_id.as_bytes(), domain_tag);
+    let sid = &*sid;
+    let tag = |j| {
+        udigest::Tag::<D>::new_structured(Tag::Indexed {
+            party_index: j,
+            sid,
+        })
+    };
+    let tag_i = tag(i);
+
+    tracer.stage("Sample x_i, chain_code");
+    let x_i = NonZero::<SecretScalar<E>>::random(rng);
+    let X_i = Point::generator() * &x_i;
+
+    #[cfg(feature = "hd-wallets")]
+    let chain_code_local = if hd_enabled {
+        let mut chain_code = slip_10::ChainCode::default();
+        rng.fill_bytes(&mut chain_code);
+        Some(chain_code)
+    } else {
+        None
+    };
+
+    tracer.stage("Sample schnorr commitment");
+    let (sch_secret, sch_commit) = schnorr_pok::prover_commits_ephemeral_secret::<E, _>(rng);
+
+    tracer.stage("Commit to public data");
+    let my_decommitment = Decommitment {
+        X: X_i,
+        sch_commit,
+        #[cfg(feature = "hd-wallets")]
+        chain_code: chain_code_local,
+        decommit: {
+            let mut nonce = L::Rid::default();
+            rng.fill_bytes(nonce.as_mut());
+            nonce
+        },
+    };
+    let hash_commit = tag_i.clone().digest(&my_decommitment);
+    let my_commitment = MsgRound1 {
+        commitment: hash_commit,
+    };
+
+    tracer.send_msg();
+    outgoings
+        .send(Outgoing::broadcast(Msg::Round1(my_commitment.clone())))
+        .await
+        .map_err(IoError::send_message)?;
+    tracer.msg_sent();
+
+    // Round 2
+    tracer.round_begins();
+
+    tracer.receive_msgs();
+    let commitments = rounds
+        .complete(round1)
+        .await
+        .map_err(IoError::receive_message)?;
+    tracer.msgs_received_count(usize::from(n) - 1);
+
+    tracer.stage("Hash commitments of all parties");
+    let commitments_hash = udigest::Tag::<D>::new_structured(Tag::Unindexed { sid })
+        .digest_iter(commitments.iter_including_me(&my_commitment));
+
+    // Optional reliability check
+    if reliable_broadcast_enforced {
+        tracer.stage("Hash received msgs (reliability check)");
+        let h_i = udigest::Tag::<H>::new_structured(Tag::Unindexed { sid })
+            .digest_iter(commitments.iter_including_me(&my_commitment));
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::ReliabilityCheck(
+                MsgReliabilityCheck(h_i.clone()),
+            )))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.round_begins();
+
+        tracer.receive_msgs();
+        let round1_hashes = rounds
+            .complete(round1_sync)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received_count(usize::from(n) - 1);
+
+        tracer.stage("Assert other parties hashed messages (reliability check)");
+        let parties = round1_hashes
+            .into_iter_indexed()
+            .filter(|(_j, _msg_id, hash_j)| hash_j.0 != h_i)
+            .map(|(j, msg_id, _)| (j, msg_id))
+            .collect::<Vec<_>>();
+        if !parties.is_empty() {
+            return Err(KeygenAborted::BroadcastNotReliable { round: 1, parties }.into());
+        }
+    }
+
+    let challenge = |j: u16, decom: &Decommitment<E, L>| {
+        let seed =
+            udigest::Tag::<D>::new("dfns.cggmp21.keygen.two_round.challenge").digest(Challenge {
+                sid,
+                prover: j,
+                commitments: &commitments_hash,
+                randomness_beacon,
+                X: &decom.X,
+                sch_commit: &decom.sch_commit.0,
+            });
+        let mut rng = crate::rng::HashRng::new(|d: D| d.chain_update(&seed).finalize());
+        schnorr_pok::Challenge {
+            nonce: Scalar::random(&mut rng),
+        }
+    };
+
+    tracer.stage("Prove knowledge of `x_i`");
+    let sch_proof = schnorr_pok::prove(&sch_secret, &challenge(i, &my_decommitment), &x_i);
+    let my_decommitment = MsgRound2 {
+        decommitment: my_decommitment,
+        sch_proof,
+    };
+
+    tracer.send_msg();
+    outgoings
+        .send(Outgoing::broadcast(Msg::Round2(my_decommitment.clone())))
+        .await
+        .map_err(IoError::send_message)?;
+    tracer.msg_sent();
+
+    // Output round
+    tracer.round_begins();
+
+    tracer.receive_msgs();
+    let decommitments = rounds
+        .complete(round2)
+        .await
+        .map_err(IoError::receive_message)?;
+    tracer.msgs_received_count(usize::from(n) - 1);
+
+    // Optional echo broadcast
+    if echo_broadcast_enforced {
+        tracer.stage("Hash received msgs (echo broadcast)");
+        let echo = MsgEcho::<D, 2>::new_with_digest::<H>(
+            sid,
+            decommitments.iter_including_me(&my_decommitment),
+        );
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round2Echo(echo.clone())))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.round_begins();
+
+        tracer.receive_msgs();
+        let echoes = rounds
+            .complete(round2_echo)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received_count(usize::from(n) - 1);
+
+        tracer.stage("Assert other parties echoed the same msgs (echo broadcast)");
+        let parties = echo.find_mismatched(echoes);
+        if !parties.is_empty() {
+            return Err(KeygenAborted::BroadcastNotReliable { round: 2, parties }.into());
+        }
+    }
+
+    tracer.stage("Validate decommitments");
+    let blame = utils::collect_blame(&commitments, &decommitments, |j, com, decom| {
+        let com_expected = tag(j).digest(&decom.decommitment);
+        com.commitment != com_expected
+    });
+    if !blame.is_empty() {
+        return Err(KeygenAborted::InvalidDecommitment(blame).into());
+    }
+
+    tracer.stage("Validate schnorr proofs");
+    let blame = decommitments
+        .iter_indexed()
+        .filter(|(j, _, decom)| {
+            tracer.process_peer(*j);
+            let decom_j = &decom.decommitment;
+            decom
+                .sch_proof
+                .verify(&decom_j.sch_commit, &challenge(*j, decom_j), &decom_j.X)
+                .is_err()
+        })
+        .map(|(j, msg_id, _)| utils::AbortBlame::new(j, msg_id, msg_id))
+        .collect::<Vec<_>>();
+    if !blame.is_empty() {
+        return Err(KeygenAborted::InvalidSchnorrProof(blame).into());
+    }
+
+    #[cfg(feature = "hd-wallets")]
+    let chain_code = if hd_enabled {
+        tracer.stage("Calculate chain_code");
+        let blame = utils::collect_simple_blame(&decommitments, |decom| {
+            decom.decommitment.chain_code.is_none()
+        });
+        if !blame.is_empty() {
+            return Err(KeygenAborted::MissingChainCode(blame).into());
+        }
+        Some(decommitments.iter_including_me(&my_decommitment).try_fold(
+            slip_10::ChainCode::default(),
+            |acc, decom| {
+                Ok::<_, Bug>(utils::xor_array(
+                    acc,
+                    decom.decommitment.chain_code.ok_or(Bug::NoChainCode)?,
+                ))
+            },
+        )?)
+    } else {
+        None
+    };
+
+    tracer.protocol_ends();
+
+    let public_shares = decommitments
+        .iter_including_me(&my_decommitment)
+        .map(|d| d.decommitment.X)
+        .collect::<Vec<_>>();
+    let key_share = DirtyCoreKeyShare {
+        i,
+        key_info: DirtyKeyInfo {
+            curve: Default::default(),
+            shared_public_key: NonZero::from_point(public_shares.iter().sum::<Point<E>>())
+                .ok_or(Bug::ZeroPk)?,
+            public_shares,
+            vss_setup: None,
+            #[cfg(feature = "hd-wallets")]
+            chain_code,
+        },
+        x: x_i,
+        extra_x: Vec::new(),
+    }
+    .validate()
+    .map_err(|e| Bug::InvalidKeyShare(e.into_error()))?;
+
+    Ok(key_share)
+}
@@ -1,7 +1,15 @@
+use std::borrow::Cow;
+
+use digest::Digest;
+use rand_core::RngCore;
 use round_based::rounds_router::simple_store::RoundMsgs;
 use round_based::{MsgId, PartyIndex};
 
+use crate::execution_id::DomainTag;
+
+#[cfg(feature = "serde")]
 mod hex_or_bin;
+#[cfg(feature = "serde")]
 pub use hex_or_bin::HexOrBin;
 
 pub fn xor_array<A, B>(mut a: A, b: B) -> A
@@ -16,6 +24,65 @@ where
     a
 }
 
+/// Mixes output of an external randomness beacon into collectively sampled `rid`
+///
+/// Returns `rid` unchanged if beacon is not provided
+pub fn mix_randomness_beacon<D, Rid>(mut rid: Rid, sid: &[u8], beacon: Option<&[u8]>) -> Rid
+where
+    D: Digest,
+    Rid: AsRef<[u8]> + AsMut<[u8]>,
+{
+    #[derive(udigest::Digestable)]
+    struct BeaconInput<'a> {
+        #[udigest(as_bytes)]
+        sid: &'a [u8],
+        #[udigest(as_bytes)]
+        rid: &'a [u8],
+        #[udigest(as_bytes)]
+        beacon: &'a [u8],
+    }
+
+    let Some(beacon) = beacon else {
+        return rid;
+    };
+    let seed =
+        udigest::Tag::<D>::new("dfns.cggmp21.keygen.randomness_beacon").digest(BeaconInput {
+            sid,
+            rid: rid.as_ref(),
+            beacon,
+        });
+    let mut rng = crate::rng::HashRng::new(|d: D| d.chain_update(&seed).finalize());
+    rng.fill_bytes(rid.as_mut());
+    rid
+}
+
+/// Binds execution id to application-specific domain tag
+///
+/// Returns execution id unchanged if domain tag is not provided
+pub fn domain_separated_sid<'a, D: Digest>(
+    eid: &'a [u8],
+    domain_tag: Option<DomainTag>,
+) -> Cow<'a, [u8]> {
+    #[derive(udigest::Digestable)]
+    struct DomainSid<'a> {
+        #[udigest(as_bytes)]
+        domain_tag: &'a [u8],
+        #[udigest(as_bytes)]
+        eid: &'a [u8],
+    }
+
+    match domain_tag {
+        None => Cow::Borrowed(eid),
+        Some(domain_tag) => {
+            let sid = udigest::Tag::<D>::new("dfns.cggmp21.keygen.domain_tag").digest(DomainSid {
+                domain_tag: domain_tag.as_str().as_bytes(),
+                eid,
+            });
+            Cow::Owned(sid.to_vec())
+        }
+    }
+}
+
 /// For some messages it is possible to precisely identify where the fault
 /// happened and which party is to blame. Use this struct to collect present the
 /// blame.
@@ -66,7 +133,6 @@ where
 
 /// Filter returns `true` for every __faulty__ message. Data and proof are set
 /// to the same message.
-#[cfg(feature = "hd-wallets")]
 pub fn collect_simple_blame<D, F>(messages: &RoundMsgs<D>, mut filter: F) -> Vec<AbortBlame>
 where
     F: FnMut(&D) -> bool,
@@ -90,7 +156,6 @@ pub fn iter_peers(i: u16, n: u16) -> impl Iterator<Item = u16> {
 
 /// Unambiguous encoding for different types for which it was not defined
 pub mod encoding {
-    #[cfg(feature = "hd-wallets")]
     pub fn maybe_bytes<B: udigest::Buffer>(
         m: &Option<impl AsRef<[u8]>>,
         encoder: udigest::encoding::EncodeValue<B>,
@@ -0,0 +1,307 @@
+//! Protocol version negotiation
+//!
+//! Parties running incompatible versions of the library can't complete the protocol: their
+//! messages can't be parsed or proofs don't verify, and such failures are indistinguishable from
+//! malicious behavior. To fail fast with a clear error instead, every message can be wrapped into
+//! a versioned [`Envelope`], and before the protocol starts parties exchange their versions in
+//! an extra _round 0_ via [`negotiate`]:
+//!
+//! ```rust,no_run
+//! # use cggmp21_keygen::{msg::non_threshold::Msg, security_level::SecurityLevel128};
+//! # type E = generic_ec::curves::Secp256k1;
+//! # async fn keygen<M>(
+//! #     rng: &mut (impl rand_core::RngCore + rand_core::CryptoRng),
+//! #     party: M,
+//! #     eid: cggmp21_keygen::ExecutionId<'_>,
+//! #     i: u16,
+//! #     n: u16,
+//! # ) -> Result<(), Box<dyn std::error::Error>>
+//! # where
+//! #     M: round_based::Mpc<
+//! #         ProtocolMessage = cggmp21_keygen::version::Envelope<Msg<E, SecurityLevel128, sha2::Sha256>>,
+//! #     >,
+//! # {
+//! use cggmp21_keygen::version;
+//!
+//! // `party` sends and receives `Envelope<Msg>`
+//! let (party, _version) = version::negotiate(party, i, n).await?;
+//! // `party` now sends and receives `Msg`, envelopes are handled transparently
+//! let _key_share = cggmp21_keygen::keygen::<E>(eid, i, n)
+//!     .start(rng, party)
+//!     .await?;
+//! # Ok(()) }
+//! ```
+//!
+//! Negotiation fails with [`VersionError`] if any party runs version that's not
+//! [compatible](ProtocolVersion::is_compatible_with) with the local one. All parties must use
+//! negotiation, otherwise they don't understand each other's messages.
+
+use futures::{future, SinkExt, StreamExt};
+use round_based::{Delivery, Incoming, Mpc, MpcParty, Outgoing, PartyIndex};
+use thiserror::Error;
+
+use crate::error_code::{ErrorCode, ErrorReport};
+
+/// Version of the protocol messages
+///
+/// Version is not the same as version of the crate: it only changes when format of messages or
+/// the protocol itself changes. Major version is bumped on breaking changes, minor version is
+/// bumped when change is backwards compatible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProtocolVersion {
+    /// Major version
+    pub major: u16,
+    /// Minor version
+    pub minor: u16,
+}
+
+impl ProtocolVersion {
+    /// Version of the protocol implemented by this crate
+    pub const CURRENT: Self = Self { major: 1, minor: 0 };
+
+    /// Checks whether parties running versions `self` and `other` can complete the protocol
+    ///
+    /// Versions are compatible if they have the same major version.
+    pub fn is_compatible_with(&self, other: &Self) -> bool {
+        self.major == other.major
+    }
+}
+
+impl core::fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Versioned envelope of protocol message
+///
+/// Format of the envelope and of [`Payload::Hello`] never changes, so parties can always learn
+/// each other's versions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Envelope<M> {
+    /// Version of the sender
+    pub version: ProtocolVersion,
+    /// Envelope content
+    pub payload: Payload<M>,
+}
+
+/// Content of [`Envelope`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Payload<M> {
+    /// Round 0 message announcing version of the sender
+    Hello,
+    /// Protocol message
+    Msg(M),
+}
+
+impl<M> Envelope<M> {
+    /// Constructs round 0 message announcing the [current](ProtocolVersion::CURRENT) version
+    pub fn hello() -> Self {
+        Self {
+            version: ProtocolVersion::CURRENT,
+            payload: Payload::Hello,
+        }
+    }
+
+    /// Wraps protocol message into envelope of the [current](ProtocolVersion::CURRENT) version
+    pub fn new(msg: M) -> Self {
+        Self {
+            version: ProtocolVersion::CURRENT,
+            payload: Payload::Msg(msg),
+        }
+    }
+}
+
+/// Performs version negotiation
+///
+/// Broadcasts [hello message](Envelope::hello) and waits for the hello messages from all other
+/// parties. Returns error if any of them runs an incompatible version. Otherwise, returns the
+/// party which sends and receives unwrapped protocol messages, and the negotiated version: the
+/// lowest version among all parties.
+///
+/// `i` is index of local party, and `n` is amount of parties participating in the protocol,
+/// which is the same as the ones provided to the protocol (e.g., in signing `n` is the number
+/// of signers).
+///
+/// Protocol messages received during negotiation are kept and delivered to the protocol once it
+/// starts. If after negotiation a party sends an envelope of incompatible version, receiving it
+/// results into error.
+pub async fn negotiate<M, P>(
+    party: P,
+    i: PartyIndex,
+    n: u16,
+) -> Result<(MpcParty<M, impl Delivery<M>, P::Runtime>, ProtocolVersion), VersionError>
+where
+    P: Mpc<ProtocolMessage = Envelope<M>>,
+    M: Send + 'static,
+{
+    if i >= n {
+        return Err(Reason::InvalidIndex { i, n }.into());
+    }
+
+    let MpcParty {
+        delivery, runtime, ..
+    } = party.into_party();
+    let (mut incomings, mut outgoings) = delivery.split();
+
+    outgoings
+        .send(Outgoing::broadcast(Envelope::hello()))
+        .await
+        .map_err(|err| Reason::Send(Box::new(err)))?;
+
+    let mut versions: Vec<Option<ProtocolVersion>> = vec![None; usize::from(n)];
+    versions[usize::from(i)] = Some(ProtocolVersion::CURRENT);
+    let mut received = vec![];
+    while versions.iter().any(Option::is_none) {
+        let Incoming {
+            id,
+            sender: j,
+            msg_type,
+            msg: Envelope { version, payload },
+        } = incomings
+            .next()
+            .await
+            .ok_or(Reason::UnexpectedEof)?
+            .map_err(|err| Reason::Receive(Box::new(err)))?;
+        if j == i {
+            // Some delivery implementations send broadcast messages back to the sender
+            continue;
+        }
+        let Some(version_j) = versions.get_mut(usize::from(j)) else {
+            return Err(Reason::UnknownSender(j).into());
+        };
+        if !ProtocolVersion::CURRENT.is_compatible_with(&version) {
+            return Err(Reason::Incompatible { party: j, version }.into());
+        }
+        match payload {
+            Payload::Hello if version_j.is_some() => return Err(Reason::UnexpectedHello(j).into()),
+            Payload::Hello => *version_j = Some(version),
+            Payload::Msg(msg) => received.push(Ok(Incoming {
+                id,
+                sender: j,
+                msg_type,
+                msg,
+            })),
+        }
+    }
+    let negotiated = versions
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(ProtocolVersion::CURRENT);
+
+    let incomings = futures::stream::iter(received)
+        .chain(incomings.filter_map(move |incoming| future::ready(open_envelope(i, incoming))));
+    let outgoings = outgoings
+        .sink_map_err(|err| VersionError(Reason::Send(Box::new(err))))
+        .with(|outgoing: Outgoing<M>| {
+            future::ready(Ok::<_, VersionError>(outgoing.map(Envelope::new)))
+        });
+
+    let party = MpcParty::connected((incomings, outgoings)).set_runtime(runtime);
+    Ok((party, negotiated))
+}
+
+/// Unwraps incoming envelope received after negotiation is completed
+///
+/// Returns `None` if message must be skipped
+fn open_envelope<M, E>(
+    i: PartyIndex,
+    incoming: Result<Incoming<Envelope<M>>, E>,
+) -> Option<Result<Incoming<M>, VersionError>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let Incoming {
+        id,
+        sender: j,
+        msg_type,
+        msg: Envelope { version, payload },
+    } = match incoming {
+        Ok(incoming) => incoming,
+        Err(err) => return Some(Err(Reason::Receive(Box::new(err)).into())),
+    };
+    if !ProtocolVersion::CURRENT.is_compatible_with(&version) {
+        return Some(Err(Reason::Incompatible { party: j, version }.into()));
+    }
+    match payload {
+        Payload::Hello if j == i => None,
+        Payload::Hello => Some(Err(Reason::UnexpectedHello(j).into())),
+        Payload::Msg(msg) => Some(Ok(Incoming {
+            id,
+            sender: j,
+            msg_type,
+            msg,
+        })),
+    }
+}
+
+/// Version negotiation error
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct VersionError(Reason);
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error(
+        "party {party} runs incompatible protocol version {version} (local version is {})",
+        ProtocolVersion::CURRENT
+    )]
+    Incompatible {
+        party: PartyIndex,
+        version: ProtocolVersion,
+    },
+    #[error("party index {i} is out of bounds (must be < n = {n})")]
+    InvalidIndex { i: PartyIndex, n: u16 },
+    #[error("party {0} sent hello message more than once")]
+    UnexpectedHello(PartyIndex),
+    #[error("received message from unknown party {0}")]
+    UnknownSender(PartyIndex),
+    #[error("send message")]
+    Send(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("receive message")]
+    Receive(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("got eof while receiving hello messages")]
+    UnexpectedEof,
+}
+
+impl VersionError {
+    /// Returns index and version of the party running incompatible version of the protocol,
+    /// if negotiation failed for this reason
+    pub fn incompatible_party(&self) -> Option<(PartyIndex, ProtocolVersion)> {
+        match &self.0 {
+            Reason::Incompatible { party, version } => Some((*party, *version)),
+            _ => None,
+        }
+    }
+
+    /// Returns stable code of the error
+    pub fn code(&self) -> ErrorCode {
+        match &self.0 {
+            Reason::Incompatible { .. } => ErrorCode::IncompatibleVersion,
+            Reason::InvalidIndex { .. } => ErrorCode::InvalidArgs,
+            Reason::UnexpectedHello(_) | Reason::UnknownSender(_) => ErrorCode::MalformedMessage,
+            Reason::Send(_) => ErrorCode::SendMessage,
+            Reason::Receive(_) => ErrorCode::ReceiveMessage,
+            Reason::UnexpectedEof => ErrorCode::UnexpectedEof,
+        }
+    }
+
+    /// Returns serializable report of the error
+    pub fn report(&self) -> ErrorReport {
+        let parties = match &self.0 {
+            Reason::Incompatible { party, .. } => vec![*party],
+            _ => vec![],
+        };
+        ErrorReport::new(self.code(), self, parties)
+    }
+}
+
+impl From<Reason> for VersionError {
+    fn from(err: Reason) -> Self {
+        Self(err)
+    }
+}
@@ -0,0 +1,496 @@
+//! Adversarial message-mutation harness
+//!
+//! Harness takes a transcript of messages received by an honest party in a successful protocol
+//! execution, and replays it to the party many times, each time with one mutation applied:
+//!
+//! * [`Mutation::FlipBit`]: flips a bit in one field of one message (first and last character of
+//!   strings, lowest bit of numbers, booleans)
+//! * [`Mutation::SwapFields`]: swaps two adjacent fields (or array elements) of one message
+//! * [`Mutation::ReplayStale`]: replaces a message with the message sent by the same party in an
+//!   earlier round
+//!
+//! Every mutation must be rejected, i.e. the party must terminate with an error. If any mutated
+//! transcript was accepted, it's reported in [`Report::accepted`].
+//!
+//! Mutations are applied to messages in their serialized form (JSON), so the harness works with
+//! any protocol of this crate. Mutated message that can't be deserialized is not delivered to the
+//! party and counted as [unparseable](Report::unparseable).
+//!
+//! ## Running against your transport
+//! Harness doesn't deliver the messages by itself: it calls a closure with the mutated transcript,
+//! which is supposed to run the party and return its output. The closure can deliver messages via
+//! [`replay`], which feeds them to the party directly, or push them through the transport stack
+//! used in production (serialization, authentication, message routing, etc.) to make sure the
+//! stack doesn't accidentally hide the mutation from the protocol.
+//!
+//! Party must be run with exactly the same inputs as in the recorded execution, including the
+//! randomness: messages of other parties depend on the messages sent by the party, so replaying
+//! the transcript to the party with different randomness fails regardless of mutations. Harness
+//! checks that by running the party with the original transcript first.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # async fn f<M: serde::Serialize + serde::de::DeserializeOwned>(
+//! #     transcript: Vec<round_based::Incoming<serde_json::Value>>,
+//! #     run_party: impl FnMut(Vec<round_based::Incoming<M>>) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), std::convert::Infallible>>>>,
+//! # ) -> Result<(), cggmp21::adversarial::HarnessError> {
+//! use cggmp21::adversarial;
+//!
+//! // `transcript` is obtained via `adversarial::record`
+//! let mutations = adversarial::mutations(&transcript);
+//! let report = adversarial::run(&transcript, mutations, run_party).await?;
+//! report.ensure_all_rejected()?;
+//! # Ok(()) }
+//! ```
+
+use std::convert::Infallible;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use futures::StreamExt;
+use round_based::{Delivery, Incoming, Mpc, MpcParty, Outgoing};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+/// Transcript of messages received by the party, in serialized form
+pub type Transcript = Vec<Incoming<Value>>;
+
+/// Mutation applied to the transcript
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mutation {
+    /// Flips a bit in the field
+    FlipBit {
+        /// Index of the message in the transcript
+        msg: usize,
+        /// [JSON pointer](https://www.rfc-editor.org/rfc/rfc6901) to the field within the message
+        pointer: String,
+        /// Index of character in which the bit is flipped, if field is a string
+        index: usize,
+    },
+    /// Swaps two fields
+    SwapFields {
+        /// Index of the message in the transcript
+        msg: usize,
+        /// JSON pointer to the first field
+        a: String,
+        /// JSON pointer to the second field
+        b: String,
+    },
+    /// Replaces the message with the message from an earlier round
+    ReplayStale {
+        /// Index of the replaced message in the transcript
+        msg: usize,
+        /// Index of the replayed message in the transcript
+        replayed: usize,
+    },
+}
+
+impl fmt::Display for Mutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FlipBit {
+                msg,
+                pointer,
+                index,
+            } => write!(f, "flip bit in msg {msg} at `{pointer}` (index {index})"),
+            Self::SwapFields { msg, a, b } => write!(f, "swap `{a}` and `{b}` in msg {msg}"),
+            Self::ReplayStale { msg, replayed } => {
+                write!(f, "replace msg {msg} with stale msg {replayed}")
+            }
+        }
+    }
+}
+
+/// Records messages received by the party
+///
+/// Returns the party which should be used to run the protocol, and a [`Recording`] which can be
+/// turned into the [`Transcript`] once protocol completes.
+pub fn record<P>(
+    party: P,
+) -> (
+    MpcParty<P::ProtocolMessage, impl Delivery<P::ProtocolMessage>, P::Runtime>,
+    Recording,
+)
+where
+    P: Mpc,
+    P::ProtocolMessage: Serialize,
+{
+    let MpcParty {
+        delivery, runtime, ..
+    } = party.into_party();
+    let (incomings, outgoings) = delivery.split();
+
+    let recording = Recording::default();
+    let recorded = recording.clone();
+    let incomings = incomings.inspect(move |incoming| {
+        if let Ok(incoming) = incoming {
+            recorded.push(incoming)
+        }
+    });
+
+    let party = MpcParty::connected((incomings, outgoings)).set_runtime(runtime);
+    (party, recording)
+}
+
+/// Messages recorded by [`record`]
+#[derive(Clone)]
+pub struct Recording(Arc<Mutex<Result<Transcript, String>>>);
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(Ok(vec![]))))
+    }
+}
+
+impl Recording {
+    fn push<M: Serialize>(&self, incoming: &Incoming<M>) {
+        let Ok(mut transcript) = self.0.lock() else {
+            return;
+        };
+        let Ok(messages) = transcript.as_mut() else {
+            return;
+        };
+        match serde_json::to_value(&incoming.msg) {
+            Ok(msg) => messages.push(Incoming {
+                id: incoming.id,
+                sender: incoming.sender,
+                msg_type: incoming.msg_type,
+                msg,
+            }),
+            Err(err) => *transcript = Err(err.to_string()),
+        }
+    }
+
+    /// Returns the recorded transcript
+    pub fn transcript(&self) -> Result<Transcript, HarnessError> {
+        self.0
+            .lock()
+            .map_err(|_| Reason::PoisonedLock)?
+            .clone()
+            .map_err(|err| Reason::Record(err).into())
+    }
+}
+
+/// Serializes messages into a [`Transcript`]
+pub fn encode_transcript<M: Serialize>(
+    messages: &[Incoming<M>],
+) -> Result<Transcript, HarnessError> {
+    messages
+        .iter()
+        .map(|incoming| {
+            Ok(Incoming {
+                id: incoming.id,
+                sender: incoming.sender,
+                msg_type: incoming.msg_type,
+                msg: serde_json::to_value(&incoming.msg).map_err(Reason::Encode)?,
+            })
+        })
+        .collect()
+}
+
+/// Enumerates mutations of the transcript
+pub fn mutations(transcript: &[Incoming<Value>]) -> Vec<Mutation> {
+    let mut mutations = vec![];
+    for (k, incoming) in transcript.iter().enumerate() {
+        let mut leaves = vec![];
+        collect_leaves(&incoming.msg, String::new(), &mut leaves);
+        for (pointer, leaf) in leaves {
+            let last_char = match leaf {
+                Value::String(s) if !s.is_empty() => s.chars().count() - 1,
+                Value::Number(_) | Value::Bool(_) => 0,
+                _ => continue,
+            };
+            mutations.push(Mutation::FlipBit {
+                msg: k,
+                pointer: pointer.clone(),
+                index: last_char,
+            });
+            if last_char > 0 {
+                mutations.push(Mutation::FlipBit {
+                    msg: k,
+                    pointer,
+                    index: 0,
+                });
+            }
+        }
+
+        collect_swaps(&incoming.msg, String::new(), k, &mut mutations);
+
+        if let Some(round) = round_of(&incoming.msg) {
+            for (j, earlier) in transcript[..k].iter().enumerate() {
+                if earlier.sender == incoming.sender
+                    && round_of(&earlier.msg).is_some_and(|r| r != round)
+                {
+                    mutations.push(Mutation::ReplayStale {
+                        msg: k,
+                        replayed: j,
+                    });
+                }
+            }
+        }
+    }
+    mutations
+}
+
+/// Applies mutation to the transcript
+///
+/// Returns `None` if mutated message can't be deserialized as `M`.
+pub fn apply<M: DeserializeOwned>(
+    transcript: &[Incoming<Value>],
+    mutation: &Mutation,
+) -> Result<Option<Vec<Incoming<M>>>, HarnessError> {
+    let mut transcript = transcript.to_vec();
+    let mutated = match mutation {
+        Mutation::FlipBit {
+            msg,
+            pointer,
+            index,
+        } => {
+            let field = transcript
+                .get_mut(*msg)
+                .and_then(|incoming| incoming.msg.pointer_mut(pointer))
+                .ok_or(Reason::InvalidMutation)?;
+            flip_bit(field, *index).ok_or(Reason::InvalidMutation)?;
+            *msg
+        }
+        Mutation::SwapFields { msg, a, b } => {
+            let message = &mut transcript.get_mut(*msg).ok_or(Reason::InvalidMutation)?.msg;
+            let value_a = message.pointer(a).ok_or(Reason::InvalidMutation)?.clone();
+            let value_b = message.pointer_mut(b).ok_or(Reason::InvalidMutation)?;
+            let value_b = std::mem::replace(value_b, value_a);
+            *message.pointer_mut(a).ok_or(Reason::InvalidMutation)? = value_b;
+            *msg
+        }
+        Mutation::ReplayStale { msg, replayed } => {
+            let stale = transcript
+                .get(*replayed)
+                .ok_or(Reason::InvalidMutation)?
+                .clone();
+            let incoming = transcript.get_mut(*msg).ok_or(Reason::InvalidMutation)?;
+            incoming.msg_type = stale.msg_type;
+            incoming.msg = stale.msg;
+            *msg
+        }
+    };
+
+    let mut decoded = Vec::with_capacity(transcript.len());
+    for (k, incoming) in transcript.into_iter().enumerate() {
+        let msg = match serde_json::from_value(incoming.msg) {
+            Ok(m) => m,
+            Err(_) if k == mutated => return Ok(None),
+            Err(err) => return Err(Reason::Decode(err).into()),
+        };
+        decoded.push(Incoming {
+            id: incoming.id,
+            sender: incoming.sender,
+            msg_type: incoming.msg_type,
+            msg,
+        });
+    }
+    Ok(Some(decoded))
+}
+
+/// Runs the party against every mutated transcript
+///
+/// `run_party` takes messages that need to be delivered to the party, runs the protocol and
+/// returns its output. Before applying mutations, party is run with the original transcript, and
+/// it must succeed.
+pub async fn run<M, F, Fut, T, E>(
+    transcript: &[Incoming<Value>],
+    mutations: impl IntoIterator<Item = Mutation>,
+    mut run_party: F,
+) -> Result<Report, HarnessError>
+where
+    M: DeserializeOwned,
+    F: FnMut(Vec<Incoming<M>>) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let honest = transcript
+        .iter()
+        .map(|incoming| {
+            Ok(Incoming {
+                id: incoming.id,
+                sender: incoming.sender,
+                msg_type: incoming.msg_type,
+                msg: serde_json::from_value(incoming.msg.clone()).map_err(Reason::Decode)?,
+            })
+        })
+        .collect::<Result<Vec<_>, HarnessError>>()?;
+    if run_party(honest).await.is_err() {
+        return Err(Reason::HonestRunFailed.into());
+    }
+
+    let mut report = Report::default();
+    for mutation in mutations {
+        report.total += 1;
+        let Some(messages) = apply(transcript, &mutation)? else {
+            report.unparseable += 1;
+            continue;
+        };
+        match run_party(messages).await {
+            Ok(_) => report.accepted.push(mutation),
+            Err(_) => report.rejected += 1,
+        }
+    }
+    Ok(report)
+}
+
+/// Constructs a party which receives given messages, and discards all messages it sends
+///
+/// Once all messages are delivered, party receives EOF.
+pub fn replay<M>(messages: Vec<Incoming<M>>) -> MpcParty<M, impl Delivery<M>> {
+    let incomings = futures::stream::iter(messages.into_iter().map(Ok::<_, Infallible>));
+    MpcParty::connected((incomings, futures::sink::drain::<Outgoing<M>>()))
+}
+
+/// Results of running the harness
+#[derive(Debug, Default, Clone)]
+pub struct Report {
+    /// Amount of applied mutations
+    pub total: usize,
+    /// Amount of mutations which were rejected by the party
+    pub rejected: usize,
+    /// Amount of mutations which produced a message that can't be deserialized
+    pub unparseable: usize,
+    /// Mutations which were accepted by the party
+    pub accepted: Vec<Mutation>,
+}
+
+impl Report {
+    /// Returns error if any of mutations was accepted
+    pub fn ensure_all_rejected(&self) -> Result<(), HarnessError> {
+        match self.accepted.first() {
+            Some(mutation) => Err(Reason::MutationAccepted {
+                mutation: mutation.clone(),
+                total: self.accepted.len(),
+            }
+            .into()),
+            None => Ok(()),
+        }
+    }
+}
+
+fn collect_leaves<'v>(value: &'v Value, pointer: String, out: &mut Vec<(String, &'v Value)>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                collect_leaves(field, child_pointer(&pointer, key), out)
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect_leaves(item, child_pointer(&pointer, &i.to_string()), out)
+            }
+        }
+        _ => out.push((pointer, value)),
+    }
+}
+
+fn collect_swaps(value: &Value, pointer: String, msg: usize, out: &mut Vec<Mutation>) {
+    let children: Vec<(String, &Value)> = match value {
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, field)| (child_pointer(&pointer, key), field))
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (child_pointer(&pointer, &i.to_string()), item))
+            .collect(),
+        _ => return,
+    };
+    for pair in children.windows(2) {
+        let ((a, value_a), (b, value_b)) = (&pair[0], &pair[1]);
+        if same_kind(value_a, value_b) && value_a != value_b {
+            out.push(Mutation::SwapFields {
+                msg,
+                a: a.clone(),
+                b: b.clone(),
+            })
+        }
+    }
+    for (child, value) in children {
+        collect_swaps(value, child, msg, out)
+    }
+}
+
+fn child_pointer(parent: &str, key: &str) -> String {
+    format!("{parent}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn same_kind(a: &Value, b: &Value) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+}
+
+/// Messages are enums serialized in externally tagged representation, so the tag identifies the
+/// round
+fn round_of(msg: &Value) -> Option<&str> {
+    match msg {
+        Value::Object(fields) if fields.len() == 1 => fields.keys().next().map(String::as_str),
+        _ => None,
+    }
+}
+
+fn flip_bit(value: &mut Value, index: usize) -> Option<()> {
+    match value {
+        Value::String(s) => {
+            let mut chars = s.chars().collect::<Vec<_>>();
+            let c = chars.get_mut(index)?;
+            // Hex digits are flipped within the hex alphabet, so the field stays parseable
+            *c = match c.to_digit(16) {
+                Some(digit) => {
+                    let flipped = std::char::from_digit(digit ^ 1, 16)?;
+                    if c.is_ascii_uppercase() {
+                        flipped.to_ascii_uppercase()
+                    } else {
+                        flipped
+                    }
+                }
+                None => char::from_u32(u32::from(*c) ^ 1)?,
+            };
+            *s = chars.into_iter().collect();
+        }
+        Value::Number(n) => {
+            *n = if let Some(x) = n.as_u64() {
+                (x ^ 1).into()
+            } else if let Some(x) = n.as_i64() {
+                (x ^ 1).into()
+            } else {
+                return None;
+            }
+        }
+        Value::Bool(b) => *b = !*b,
+        _ => return None,
+    }
+    Some(())
+}
+
+/// Error returned by the harness
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct HarnessError(Reason);
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("party failed on the original transcript")]
+    HonestRunFailed,
+    #[error("{total} mutation(s) were accepted, first one: {mutation}")]
+    MutationAccepted { mutation: Mutation, total: usize },
+    #[error("mutation doesn't match the transcript")]
+    InvalidMutation,
+    #[error("serialize message")]
+    Encode(#[source] serde_json::Error),
+    #[error("deserialize message")]
+    Decode(#[source] serde_json::Error),
+    #[error("serialize recorded message: {0}")]
+    Record(String),
+    #[error("lock is poisoned")]
+    PoisonedLock,
+}
+
+crate::errors::impl_from! {
+    impl From for HarnessError {
+        err: Reason => HarnessError(err),
+    }
+}
@@ -0,0 +1,434 @@
+//! Inspects, validates, and migrates serialized key shares
+//!
+//! Supports both core key shares ([`IncompleteKeyShare`]) and complete key shares ([`KeyShare`])
+//! on all supported curves, serialized via `serde_json` or `ciborium`. Encoding and curve are
+//! detected automatically.
+//!
+//! * `inspect` prints public information about the key share: curve, threshold, share indexes,
+//!   security level, sizes of aux data
+//! * `validate` checks that key share is valid, `--deep` runs a [health check](health) which
+//!   takes longer but detects more issues
+//! * `migrate` rewrites key share in the format of the current version of the library, optionally
+//!   converting it to another encoding. Key shares produced by older versions are accepted.
+//!
+//! Key shares contain secret data: the tool never prints secret shares, but make sure that
+//! migrated key shares are stored as securely as original ones.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use cggmp21::{
+    key_share::{health, AnyKeyShare, IncompleteKeyShare, KeyShare},
+    security_level::{SecurityLevel, SecurityLevel128},
+    supported_curves::{Secp256k1, Secp256r1, Stark},
+};
+use generic_ec::Curve;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+enum Command {
+    Inspect {
+        input: PathBuf,
+        format: Format,
+    },
+    Validate {
+        input: PathBuf,
+        deep: bool,
+    },
+    Migrate {
+        input: PathBuf,
+        output: PathBuf,
+        encoding: Option<Encoding>,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("unknown format `{s}`, expected one of: text, json")),
+        }
+    }
+}
+
+/// Encoding of serialized key share
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Json,
+    Cbor,
+    /// Hex-encoded CBOR
+    CborHex,
+}
+
+impl std::str::FromStr for Encoding {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "cbor" => Ok(Self::Cbor),
+            "cbor-hex" => Ok(Self::CborHex),
+            _ => Err(format!(
+                "unknown encoding `{s}`, expected one of: json, cbor, cbor-hex"
+            )),
+        }
+    }
+}
+
+impl Encoding {
+    fn detect(bytes: &[u8]) -> Self {
+        let trimmed = trim(bytes);
+        if trimmed.starts_with(b"{") {
+            Self::Json
+        } else if !trimmed.is_empty() && trimmed.iter().all(u8::is_ascii_hexdigit) {
+            Self::CborHex
+        } else {
+            Self::Cbor
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T> {
+        match self {
+            Self::Json => serde_json::from_slice(bytes).context("decode json"),
+            Self::Cbor => ciborium::from_reader(bytes).context("decode cbor"),
+            Self::CborHex => {
+                let bytes = hex::decode(trim(bytes)).context("decode hex")?;
+                ciborium::from_reader(bytes.as_slice()).context("decode cbor")
+            }
+        }
+    }
+
+    fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>> {
+        match self {
+            Self::Json => serde_json::to_vec_pretty(value).context("encode json"),
+            Self::Cbor | Self::CborHex => {
+                let mut bytes = vec![];
+                ciborium::into_writer(value, &mut bytes).context("encode cbor")?;
+                if self == Self::CborHex {
+                    bytes = hex::encode(bytes).into_bytes();
+                }
+                Ok(bytes)
+            }
+        }
+    }
+}
+
+/// Strips leading and trailing whitespaces
+fn trim(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |end| end + 1);
+    &bytes[start..end]
+}
+
+fn args() -> Command {
+    use bpaf::Parser;
+    let input = || bpaf::positional::<PathBuf>("KEY_SHARE").help("Path to the key share");
+
+    let format = bpaf::long("format")
+        .help("Output format: text or json")
+        .argument::<Format>("FORMAT")
+        .fallback(Format::Text);
+    let inspect = bpaf::construct!(Command::Inspect { format, input() })
+        .to_options()
+        .descr("Prints public information about the key share")
+        .command("inspect");
+
+    let deep = bpaf::long("deep")
+        .help("Runs a health check: primality tests, Paillier encryption round-trip, etc.")
+        .switch();
+    let validate = bpaf::construct!(Command::Validate { deep, input() })
+        .to_options()
+        .descr("Checks that key share is valid")
+        .command("validate");
+
+    let output = bpaf::long("output")
+        .short('o')
+        .help("Where to write migrated key share")
+        .argument::<PathBuf>("PATH");
+    let encoding = bpaf::long("encoding")
+        .help("Encoding of migrated key share: json, cbor or cbor-hex. Same as input by default")
+        .argument::<Encoding>("ENCODING")
+        .optional();
+    let migrate = bpaf::construct!(Command::Migrate {
+        output,
+        encoding,
+        input()
+    })
+    .to_options()
+    .descr("Rewrites key share in the format of the current version of the library")
+    .command("migrate");
+
+    bpaf::construct!([inspect, validate, migrate])
+        .to_options()
+        .descr("Inspects, validates, and migrates cggmp21 key shares")
+        .run()
+}
+
+/// Fields that are enough to figure out the curve and kind of key share
+#[derive(Deserialize)]
+struct Probe {
+    curve: Option<String>,
+    core: Option<CoreProbe>,
+}
+
+#[derive(Deserialize)]
+struct CoreProbe {
+    curve: String,
+}
+
+/// Key share read from the file
+struct Input {
+    bytes: Vec<u8>,
+    encoding: Encoding,
+    curve: String,
+    /// Whether it's a complete key share
+    complete: bool,
+}
+
+impl Input {
+    fn read(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path).with_context(|| format!("read {}", path.display()))?;
+        let encoding = Encoding::detect(&bytes);
+        let probe: Probe = encoding.decode(&bytes).context("not a key share")?;
+        let (curve, complete) = match probe {
+            Probe {
+                core: Some(core), ..
+            } => (core.curve, true),
+            Probe {
+                curve: Some(curve), ..
+            } => (curve, false),
+            _ => bail!("not a key share: curve is not specified"),
+        };
+        Ok(Self {
+            bytes,
+            encoding,
+            curve,
+            complete,
+        })
+    }
+}
+
+fn main() -> Result<()> {
+    let command = args();
+    let input = match &command {
+        Command::Inspect { input, .. }
+        | Command::Validate { input, .. }
+        | Command::Migrate { input, .. } => Input::read(input)?,
+    };
+
+    match input.curve.as_str() {
+        "secp256k1" => run::<Secp256k1>(&command, &input),
+        "secp256r1" => run::<Secp256r1>(&command, &input),
+        "stark" => run::<Stark>(&command, &input),
+        curve => bail!("unsupported curve `{curve}`"),
+    }
+}
+
+fn run<E: Curve>(command: &Command, input: &Input) -> Result<()> {
+    if input.complete {
+        let key_share: KeyShare<E, SecurityLevel128> = input
+            .encoding
+            .decode(&input.bytes)
+            .context("invalid key share")?;
+        match command {
+            Command::Inspect { format, .. } => print_info(&Info::with_aux(&key_share), *format),
+            Command::Validate { deep, .. } => {
+                if *deep {
+                    let report = key_share.health_check();
+                    print_health_report(&report);
+                    if !report.is_healthy() {
+                        bail!("key share is not healthy")
+                    }
+                }
+                println!("key share is valid");
+                Ok(())
+            }
+            Command::Migrate {
+                output, encoding, ..
+            } => migrate(&key_share, input.encoding, *encoding, output),
+        }
+    } else {
+        let key_share: IncompleteKeyShare<E> = input
+            .encoding
+            .decode(&input.bytes)
+            .context("invalid key share")?;
+        match command {
+            Command::Inspect { format, .. } => print_info(&Info::new(&key_share), *format),
+            Command::Validate { deep, .. } => {
+                if *deep {
+                    println!("core key share doesn't have aux data, only cheap validation is done");
+                }
+                println!("key share is valid");
+                Ok(())
+            }
+            Command::Migrate {
+                output, encoding, ..
+            } => migrate(&key_share, input.encoding, *encoding, output),
+        }
+    }
+}
+
+fn migrate<T: Serialize>(
+    key_share: &T,
+    input_encoding: Encoding,
+    output_encoding: Option<Encoding>,
+    output: &Path,
+) -> Result<()> {
+    let bytes = output_encoding
+        .unwrap_or(input_encoding)
+        .encode(key_share)?;
+    std::fs::write(output, bytes).with_context(|| format!("write {}", output.display()))?;
+    println!("migrated key share is written to {}", output.display());
+    Ok(())
+}
+
+/// Public information about the key share
+#[derive(Serialize)]
+struct Info {
+    curve: &'static str,
+    /// Index of the party holding the key share
+    i: u16,
+    n: u16,
+    threshold: Option<u16>,
+    #[serde(with = "hex")]
+    shared_public_key: Vec<u8>,
+    /// Share preimages `I_j`, hex-encoded
+    indexes: Option<Vec<String>>,
+    /// Weights of the parties, if key is weighted
+    weights: Option<Vec<u16>>,
+    vss_commitments: bool,
+    hd_wallet: bool,
+    aux: Option<AuxSummary>,
+    policy: bool,
+}
+
+#[derive(Serialize)]
+struct AuxSummary {
+    security_level: u32,
+    /// Size of own Paillier primes `p` and `q` in bits
+    primes_bits: (u32, u32),
+    /// Size of Paillier modulus `N_j` of each party in bits
+    moduli_bits: Vec<u32>,
+    multiexp_tables: bool,
+    crt: bool,
+}
+
+impl Info {
+    fn new<E: Curve>(key_share: &impl AnyKeyShare<E>) -> Self {
+        let core: &IncompleteKeyShare<E> = key_share.as_ref();
+        let vss = core.vss_setup.as_ref();
+        let n = key_share.n();
+        Self {
+            curve: E::CURVE_NAME,
+            i: core.i,
+            n,
+            threshold: vss.map(|vss| vss.min_signers),
+            shared_public_key: core.shared_public_key.to_bytes(true).to_vec(),
+            indexes: vss.map(|vss| {
+                vss.I
+                    .iter()
+                    .map(|I_j| hex::encode(I_j.to_be_bytes()))
+                    .collect()
+            }),
+            weights: vss
+                .filter(|vss| vss.weighted.is_some())
+                .map(|vss| (0..n).filter_map(|j| vss.weight(j)).collect()),
+            vss_commitments: vss.map_or(false, |vss| vss.commitments.is_some()),
+            hd_wallet: core.is_hd_wallet(),
+            aux: None,
+            policy: false,
+        }
+    }
+
+    fn with_aux<E: Curve, L: SecurityLevel>(key_share: &KeyShare<E, L>) -> Self {
+        let aux = &key_share.aux;
+        Self {
+            aux: Some(AuxSummary {
+                security_level: L::SECURITY_BITS,
+                primes_bits: (aux.p.significant_bits(), aux.q.significant_bits()),
+                moduli_bits: aux
+                    .parties
+                    .iter()
+                    .map(|party| party.N.significant_bits())
+                    .collect(),
+                multiexp_tables: aux.parties.iter().any(|party| party.multiexp.is_some()),
+                crt: aux.parties.iter().any(|party| party.crt.is_some()),
+            }),
+            policy: key_share.policy.is_some(),
+            ..Self::new(key_share)
+        }
+    }
+}
+
+fn print_info(info: &Info, format: Format) -> Result<()> {
+    match format {
+        Format::Json => {
+            let json = serde_json::to_string_pretty(info).context("serialize info")?;
+            println!("{json}");
+        }
+        Format::Text => {
+            let kind = if info.aux.is_some() {
+                "complete"
+            } else {
+                "core"
+            };
+            println!("kind:              {kind}");
+            println!("curve:             {}", info.curve);
+            println!("party index:       {}", info.i);
+            println!("parties:           {}", info.n);
+            match info.threshold {
+                Some(t) => println!("threshold:         {t}"),
+                None => println!("threshold:         none (non-threshold key)"),
+            }
+            println!(
+                "shared public key: {}",
+                hex::encode(&info.shared_public_key)
+            );
+            if let Some(indexes) = &info.indexes {
+                println!("share preimages:");
+                for (j, I_j) in indexes.iter().enumerate() {
+                    println!("  {j}: {I_j}");
+                }
+            }
+            if let Some(weights) = &info.weights {
+                println!("weights:           {weights:?}");
+            }
+            println!("vss commitments:   {}", info.vss_commitments);
+            println!("hd wallet:         {}", info.hd_wallet);
+            if let Some(aux) = &info.aux {
+                println!("security level:    {} bits", aux.security_level);
+                println!(
+                    "paillier primes:   {} and {} bits",
+                    aux.primes_bits.0, aux.primes_bits.1
+                );
+                println!("paillier moduli:   {:?} bits", aux.moduli_bits);
+                println!("multiexp tables:   {}", aux.multiexp_tables);
+                println!("crt:               {}", aux.crt);
+                println!("policy:            {}", info.policy);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_health_report(report: &health::HealthReport) {
+    for (check, status) in &report.checks {
+        match status {
+            health::CheckStatus::Passed => println!("{check:?}: passed"),
+            health::CheckStatus::Failed(reason) => println!("{check:?}: FAILED: {reason}"),
+            health::CheckStatus::Skipped(reason) => println!("{check:?}: skipped: {reason}"),
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Blocking API
+//!
+//! Protocols are implemented as async functions and normally require an async runtime to be
+//! driven. Applications that don't have one (CLI tools, plugins, FFI) can use `run_*_blocking`
+//! functions instead: they run the protocol to completion on the current thread, using a minimal
+//! built-in executor ([`block_on`]), and deliver messages via [`Transport`] that sends and
+//! receives messages in a blocking manner.
+//!
+//! Each function takes a builder configured as usual, so all the options of the async API are
+//! available. Functions block the calling thread until the protocol completes, and thread is
+//! parked while waiting for something other than incoming messages (e.g. for the deferred message
+//! in [`SigningBuilder::sign_with_deferred_message`], which can be used with [`block_on`]
+//! directly).
+//!
+//! ## Example
+//! ```rust,no_run
+//! # type Msg = cggmp21::signing::msg::Msg<cggmp21::supported_curves::Secp256k1, sha2::Sha256>;
+//! # fn doc<T>(transport: T) -> Result<(), cggmp21::SigningError>
+//! # where T: cggmp21::blocking::Transport<Msg>
+//! # {
+//! # let key_share: cggmp21::KeyShare<cggmp21::supported_curves::Secp256k1> = unimplemented!();
+//! # let data_to_sign = cggmp21::DataToSign::digest::<sha2::Sha256>(b"data to be signed");
+//! let eid = cggmp21::ExecutionId::new(b"execution id, unique per protocol execution");
+//! let signing = cggmp21::signing(eid, 0, &[0, 1], &key_share);
+//! let signature = cggmp21::blocking::run_signing_blocking(
+//!     signing,
+//!     &mut rand::rngs::OsRng,
+//!     transport,
+//!     data_to_sign,
+//! )?;
+//! # Ok(()) }
+//! ```
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use digest::Digest;
+use futures::{Sink, Stream};
+use generic_ec::{coords::AlwaysHasAffineX, Curve, NonZero, Point};
+use rand_core::{CryptoRng, RngCore};
+use round_based::{Incoming, MpcParty, Outgoing};
+
+use crate::key_refresh::{self, AuxOnly, GenericKeyRefreshBuilder, KeyRefreshError, RefreshShare};
+use crate::keygen::{self, GenericKeygenBuilder, KeygenError, NonThreshold, WithThreshold};
+use crate::security_level::{KeygenSecurityLevel, SecurityLevel};
+use crate::signing::{msg::Msg as SigningMsg, DataToSign, Presignature, SigningBuilder};
+use crate::{AuxInfo, IncompleteKeyShare, KeyShare, Signature, SigningError};
+
+/// Blocking transport
+///
+/// Delivers messages of the protocol to other parties and receives messages from them. Unlike
+/// [`Delivery`](round_based::Delivery), methods block the thread until the operation completes.
+pub trait Transport<M> {
+    /// Error of sending a message
+    type SendError: std::error::Error + Send + Sync + 'static;
+    /// Error of receiving a message
+    type ReceiveError: std::error::Error + Send + Sync + 'static;
+
+    /// Sends a message
+    ///
+    /// Transport may buffer the message, in that case it must be sent out on
+    /// [`flush`](Self::flush).
+    fn send(&mut self, outgoing: Outgoing<M>) -> Result<(), Self::SendError>;
+
+    /// Flushes buffered messages
+    fn flush(&mut self) -> Result<(), Self::SendError> {
+        Ok(())
+    }
+
+    /// Blocks until next message is received
+    ///
+    /// Returns `None` if connection is closed and no more messages will be received.
+    fn receive(&mut self) -> Option<Result<Incoming<M>, Self::ReceiveError>>;
+}
+
+/// Incoming messages received from [`Transport`]
+///
+/// Obtained via [`connect`]. Shares the transport with [`BlockingOutgoings`], so resulting party
+/// can only be driven on the current thread, e.g. via [`block_on`].
+pub struct BlockingIncomings<T, M> {
+    transport: Rc<RefCell<T>>,
+    _msg: PhantomData<fn() -> M>,
+}
+
+/// Outgoing messages sent to [`Transport`]
+///
+/// Obtained via [`connect`]
+pub struct BlockingOutgoings<T>(Rc<RefCell<T>>);
+
+impl<M, T: Transport<M>> Stream for BlockingIncomings<T, M> {
+    type Item = Result<Incoming<M>, T::ReceiveError>;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.transport.borrow_mut().receive())
+    }
+}
+
+impl<M, T: Transport<M>> Sink<Outgoing<M>> for BlockingOutgoings<T> {
+    type Error = T::SendError;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Outgoing<M>) -> Result<(), Self::Error> {
+        self.0.borrow_mut().send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(self.0.borrow_mut().flush())
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(self.0.borrow_mut().flush())
+    }
+}
+
+/// Constructs a party that communicates via blocking transport
+///
+/// Party can be passed to any protocol, which then needs to be driven by [`block_on`].
+pub fn connect<M, T: Transport<M>>(
+    transport: T,
+) -> MpcParty<M, (BlockingIncomings<T, M>, BlockingOutgoings<T>)> {
+    let transport = Rc::new(RefCell::new(transport));
+    let incomings = BlockingIncomings {
+        transport: transport.clone(),
+        _msg: PhantomData,
+    };
+    MpcParty::connected((incomings, BlockingOutgoings(transport)))
+}
+
+/// Runs the future to completion on the current thread
+///
+/// Minimal executor: the future is polled in a loop, and the thread is parked while the future
+/// is pending until it's woken up.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(std::thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark()
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+/// Runs non-threshold key generation, see [`GenericKeygenBuilder::start`]
+pub fn run_keygen_blocking<E, L, D, H, R, T>(
+    builder: GenericKeygenBuilder<'_, E, NonThreshold, L, D, H>,
+    rng: &mut R,
+    transport: T,
+) -> Result<IncompleteKeyShare<E>, KeygenError>
+where
+    E: Curve,
+    L: KeygenSecurityLevel,
+    D: Digest + Clone + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
+    R: RngCore + CryptoRng,
+    T: Transport<keygen::NonThresholdMsg<E, L, D>>,
+{
+    block_on(builder.start(rng, connect(transport)))
+}
+
+/// Runs threshold key generation, see [`GenericKeygenBuilder::start`]
+pub fn run_threshold_keygen_blocking<E, L, D, H, R, T>(
+    builder: GenericKeygenBuilder<'_, E, WithThreshold, L, D, H>,
+    rng: &mut R,
+    transport: T,
+) -> Result<IncompleteKeyShare<E>, KeygenError>
+where
+    E: Curve,
+    L: KeygenSecurityLevel,
+    D: Digest + Clone + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
+    R: RngCore + CryptoRng,
+    T: Transport<keygen::ThresholdMsg<E, L, D>>,
+{
+    block_on(builder.start(rng, connect(transport)))
+}
+
+/// Runs auxiliary info generation, see [`GenericKeyRefreshBuilder::start`]
+pub fn run_aux_info_gen_blocking<L, D, H, R, T>(
+    builder: GenericKeyRefreshBuilder<'_, AuxOnly, L, D, H>,
+    rng: &mut R,
+    transport: T,
+) -> Result<AuxInfo<L>, KeyRefreshError>
+where
+    L: SecurityLevel,
+    D: Digest + Clone + Sync + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
+    R: RngCore + CryptoRng,
+    T: Transport<key_refresh::AuxOnlyMsg<D, L>>,
+{
+    block_on(builder.start(rng, connect(transport)))
+}
+
+/// Runs key refresh, see [`GenericKeyRefreshBuilder::start`]
+pub fn run_key_refresh_blocking<'a, E, L, D, H, R, T>(
+    builder: GenericKeyRefreshBuilder<'a, RefreshShare<'a, E>, L, D, H>,
+    rng: &mut R,
+    transport: T,
+) -> Result<KeyShare<E, L>, KeyRefreshError>
+where
+    E: Curve,
+    L: SecurityLevel,
+    D: Digest + Clone + Sync + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
+    R: RngCore + CryptoRng,
+    T: Transport<key_refresh::NonThresholdMsg<E, D, L>>,
+{
+    block_on(builder.start(rng, connect(transport)))
+}
+
+/// Runs signing, see [`SigningBuilder::sign`]
+pub fn run_signing_blocking<E, L, D, H, R, T>(
+    builder: SigningBuilder<'_, E, L, D, H>,
+    rng: &mut R,
+    transport: T,
+    message_to_sign: DataToSign<E>,
+) -> Result<Signature<E>, SigningError>
+where
+    E: Curve,
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+    L: SecurityLevel,
+    D: Digest + Clone + Sync + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
+    R: RngCore + CryptoRng,
+    T: Transport<SigningMsg<E, D>>,
+{
+    block_on(builder.sign(rng, connect(transport), message_to_sign))
+}
+
+/// Runs presignature generation, see [`SigningBuilder::generate_presignature`]
+pub fn run_presignature_generation_blocking<E, L, D, H, R, T>(
+    builder: SigningBuilder<'_, E, L, D, H>,
+    rng: &mut R,
+    transport: T,
+) -> Result<Presignature<E>, SigningError>
+where
+    E: Curve,
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+    L: SecurityLevel,
+    D: Digest + Clone + Sync + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
+    R: RngCore + CryptoRng,
+    T: Transport<SigningMsg<E, D>>,
+{
+    block_on(builder.generate_presignature(rng, connect(transport)))
+}
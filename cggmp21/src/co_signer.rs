@@ -0,0 +1,215 @@
+//! Hardware and air-gapped co-signers
+//!
+//! Allows a party of the committee to be a hardware wallet or an air-gapped device which doesn't
+//! have access to the network. Device only needs to implement [`CoSigner`] trait: it receives
+//! one inbound message at a time (as bytes) and replies with the messages it wants to send.
+//! On the host side, [`relay`] plugs the device into the protocol as one [`Mpc`] party: it
+//! forwards messages received from other parties to the device, and sends device replies to the
+//! network.
+//!
+//! Messages are encoded as JSON. Device written in Rust can use [`Inbound::decode`] and
+//! [`Reply::encode`] to process them.
+//!
+//! Host never learns secrets of the device: it sees exactly the same messages as the network.
+//! Output of the protocol (e.g. signature or key share) is obtained by the device.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # async fn host<E: generic_ec::Curve, M, C>(party: M, device: C) -> Result<(), cggmp21::co_signer::RelayError>
+//! # where
+//! #     M: round_based::Mpc<ProtocolMessage = cggmp21::signing::msg::Msg<E, sha2::Sha256>>,
+//! #     C: cggmp21::co_signer::CoSigner,
+//! # {
+//! // Device takes part in the signing as one of the signers
+//! let mut device = device;
+//! cggmp21::co_signer::relay(&mut device, party).await?;
+//! # Ok(()) }
+//! ```
+
+use futures::{SinkExt, StreamExt};
+use round_based::{
+    Delivery, Incoming, MessageDestination, MessageType, Mpc, MpcParty, MsgId, Outgoing, PartyIndex,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Co-signer device
+///
+/// Device runs the protocol one step at a time. Each step takes encoded [`Inbound`] message and
+/// outputs encoded [`Reply`] containing messages produced by the device in response.
+pub trait CoSigner {
+    /// Error returned by the device
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Starts the protocol
+    ///
+    /// Returns encoded [`Reply`] with messages of the first round.
+    fn start(&mut self) -> Result<Vec<u8>, Self::Error>;
+    /// Handles encoded [`Inbound`] message
+    ///
+    /// Returns encoded [`Reply`] with messages produced in response (may be empty if the device
+    /// waits for more messages to complete the round).
+    fn handle(&mut self, inbound: &[u8]) -> Result<Vec<u8>, Self::Error>;
+}
+
+impl<C: CoSigner + ?Sized> CoSigner for &mut C {
+    type Error = C::Error;
+
+    fn start(&mut self) -> Result<Vec<u8>, Self::Error> {
+        (**self).start()
+    }
+    fn handle(&mut self, inbound: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        (**self).handle(inbound)
+    }
+}
+
+/// Message delivered to the device
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Inbound<M> {
+    /// Index of the message
+    pub id: MsgId,
+    /// Index of the party who sent the message
+    pub sender: PartyIndex,
+    /// Indicates whether message was broadcasted
+    pub broadcast: bool,
+    /// The message
+    pub msg: M,
+}
+
+/// Message sent by the device
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Outbound<M> {
+    /// Recipient of the message, `None` if message is broadcasted
+    pub recipient: Option<PartyIndex>,
+    /// The message
+    pub msg: M,
+}
+
+/// Reply of the device
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "M: Serialize", deserialize = "M: Deserialize<'de>"))]
+pub struct Reply<M> {
+    /// Messages to be sent
+    pub outgoing: Vec<Outbound<M>>,
+    /// Indicates whether the device completed the protocol
+    ///
+    /// Once set, host stops relaying messages.
+    pub finished: bool,
+}
+
+impl<M: Serialize> Inbound<M> {
+    /// Encodes the message
+    pub fn encode(&self) -> Result<Vec<u8>, serde_json::Error> {
+        serde_json::to_vec(self)
+    }
+}
+
+impl<M: DeserializeOwned> Inbound<M> {
+    /// Decodes the message
+    pub fn decode(bytes: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(bytes)
+    }
+}
+
+impl<M: Serialize> Reply<M> {
+    /// Encodes the reply
+    pub fn encode(&self) -> Result<Vec<u8>, serde_json::Error> {
+        serde_json::to_vec(self)
+    }
+}
+
+impl<M: DeserializeOwned> Reply<M> {
+    /// Decodes the reply
+    pub fn decode(bytes: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(bytes)
+    }
+}
+
+/// Relays messages between the network and the co-signer device
+///
+/// `party` is a network connection of the party which the device takes part as. Returns once
+/// the device [finished](Reply::finished) the protocol.
+pub async fn relay<C, M>(co_signer: &mut C, party: M) -> Result<(), RelayError>
+where
+    C: CoSigner + ?Sized,
+    M: Mpc,
+    M::ProtocolMessage: Serialize + DeserializeOwned,
+{
+    let MpcParty { delivery, .. } = party.into_party();
+    let (mut incomings, mut outgoings) = delivery.split();
+
+    let mut reply = co_signer.start().map_err(RelayError::co_signer)?;
+    loop {
+        let Reply { outgoing, finished } =
+            Reply::<M::ProtocolMessage>::decode(&reply).map_err(Reason::DecodeReply)?;
+        for Outbound { recipient, msg } in outgoing {
+            let recipient = match recipient {
+                Some(j) => MessageDestination::OneParty(j),
+                None => MessageDestination::AllParties,
+            };
+            outgoings
+                .feed(Outgoing { recipient, msg })
+                .await
+                .map_err(|err| Reason::Send(Box::new(err)))?;
+        }
+        outgoings
+            .flush()
+            .await
+            .map_err(|err| Reason::Send(Box::new(err)))?;
+        if finished {
+            return Ok(());
+        }
+
+        let Incoming {
+            id,
+            sender,
+            msg_type,
+            msg,
+        } = incomings
+            .next()
+            .await
+            .ok_or(Reason::UnexpectedEof)?
+            .map_err(|err| Reason::Receive(Box::new(err)))?;
+        let inbound = Inbound {
+            id,
+            sender,
+            broadcast: msg_type == MessageType::Broadcast,
+            msg,
+        }
+        .encode()
+        .map_err(Reason::EncodeInbound)?;
+        reply = co_signer.handle(&inbound).map_err(RelayError::co_signer)?;
+    }
+}
+
+/// Error returned by [`relay`]
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct RelayError(Reason);
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("co-signer returned error")]
+    CoSigner(#[source] crate::errors::BoxedError),
+    #[error("decode reply of co-signer")]
+    DecodeReply(#[source] serde_json::Error),
+    #[error("encode inbound message")]
+    EncodeInbound(#[source] serde_json::Error),
+    #[error("send message")]
+    Send(#[source] crate::errors::BoxedError),
+    #[error("receive message")]
+    Receive(#[source] crate::errors::BoxedError),
+    #[error("got eof while receiving messages")]
+    UnexpectedEof,
+}
+
+impl RelayError {
+    fn co_signer(err: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self(Reason::CoSigner(Box::new(err)))
+    }
+}
+
+crate::errors::impl_from! {
+    impl From for RelayError {
+        err: Reason => RelayError(err),
+    }
+}
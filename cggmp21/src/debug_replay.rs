@@ -0,0 +1,497 @@
+//! Deterministic record-and-replay of the protocol execution
+//!
+//! When protocol aborts in production, error alone is often not enough to figure out what went
+//! wrong. This module allows recording everything that affects execution of the local party:
+//! messages it received, messages it sent and randomness it drew from the RNG. The recorded
+//! [`Log`] can be saved to the file, and later the party can be re-executed from it
+//! deterministically, e.g. in a debugger, with all the tracing enabled.
+//!
+//! 1. Wrap the party and the RNG with [`record`] and run the protocol as usual
+//! 2. Once protocol terminates (successfully or not), obtain the [`Log`] via [`Recorder::log`] and
+//!    save it (it implements `serde` traits)
+//! 3. To debug, construct the party and the RNG from the log via [`replay`], and run the protocol
+//!    with exactly the same inputs (key share, list of signers, message to sign, etc.)
+//! 4. [`Replay::verify`] reports whether the replayed execution diverged from the recorded one
+//!
+//! ## Security
+//! The log contains all randomness used by the party, which makes it possible to derive its secret
+//! key share and ephemeral secrets (e.g. nonces). It must be handled as securely as the key share
+//! itself. Never enable recording by default.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+//! # type Msg = cggmp21::signing::msg::Msg<cggmp21::supported_curves::Secp256k1, sha2::Sha256>;
+//! # let incoming = futures::stream::pending::<Result<round_based::Incoming<Msg>, std::convert::Infallible>>();
+//! # let outgoing = futures::sink::drain::<round_based::Outgoing<Msg>>();
+//! # let party = round_based::MpcParty::connected((incoming, outgoing));
+//! # let eid = cggmp21::ExecutionId::new(b"execution id");
+//! # let key_share: cggmp21::KeyShare<cggmp21::supported_curves::Secp256k1> = unimplemented!();
+//! # let data_to_sign = cggmp21::DataToSign::digest::<sha2::Sha256>(b"data to be signed");
+//! use cggmp21::debug_replay;
+//!
+//! // In production: record the execution, save the log if it fails
+//! let (party, mut rng, recorder) = debug_replay::record(party, rand_core::OsRng);
+//! let result = cggmp21::signing(eid, 0, &[0, 1], &key_share)
+//!     .sign(&mut rng, party, data_to_sign)
+//!     .await;
+//! let log = serde_json::to_vec(&recorder.log()?)?;
+//!
+//! // When debugging: re-execute the party from the log with the same inputs
+//! let log: debug_replay::Log = serde_json::from_slice(&log)?;
+//! let (party, mut rng, replay) = debug_replay::replay::<Msg>(&log)?;
+//! let replayed = cggmp21::signing(eid, 0, &[0, 1], &key_share)
+//!     .sign(&mut rng, party, data_to_sign)
+//!     .await;
+//! replay.verify()?;
+//! # Ok(()) }
+//! ```
+
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use futures::{Sink, StreamExt};
+use rand_core::{CryptoRng, RngCore};
+use round_based::{
+    Delivery, Incoming, MessageDestination, MessageType, Mpc, MpcParty, MsgId, Outgoing, PartyIndex,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+/// Recorded execution of the local party
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Log {
+    /// Messages received by the party, in order they were received
+    pub incomings: Vec<RecordedIncoming>,
+    /// Messages sent by the party, in order they were sent
+    pub outgoings: Vec<RecordedOutgoing>,
+    /// Randomness drawn from the RNG, in order it was drawn
+    #[serde(with = "hex")]
+    pub randomness: Vec<u8>,
+}
+
+/// Message received by the party
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedIncoming {
+    /// Index of the message
+    pub id: MsgId,
+    /// Index of the party who sent the message
+    pub sender: PartyIndex,
+    /// Indicates whether message was broadcasted
+    pub broadcast: bool,
+    /// The message
+    pub msg: Value,
+}
+
+/// Message sent by the party
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedOutgoing {
+    /// Recipient of the message, `None` if message is broadcasted
+    pub recipient: Option<PartyIndex>,
+    /// The message
+    pub msg: Value,
+}
+
+/// Records execution of the party
+///
+/// Returns the party and the RNG which should be used to run the protocol, and a [`Recorder`]
+/// which collects the [`Log`].
+pub fn record<P, R>(
+    party: P,
+    rng: R,
+) -> (
+    MpcParty<P::ProtocolMessage, impl Delivery<P::ProtocolMessage>, P::Runtime>,
+    RecordingRng<R>,
+    Recorder,
+)
+where
+    P: Mpc,
+    P::ProtocolMessage: Serialize,
+    R: RngCore + CryptoRng,
+{
+    let MpcParty {
+        delivery, runtime, ..
+    } = party.into_party();
+    let (incomings, outgoings) = delivery.split();
+
+    let recorder = Recorder::default();
+    let recorded = recorder.clone();
+    let incomings = incomings.inspect(move |incoming| {
+        if let Ok(incoming) = incoming {
+            recorded.record_incoming(incoming)
+        }
+    });
+    let outgoings = RecordingOutgoings {
+        inner: outgoings,
+        recorder: recorder.clone(),
+    };
+
+    let party = MpcParty::connected((incomings, outgoings)).set_runtime(runtime);
+    let rng = RecordingRng {
+        inner: rng,
+        recorder: recorder.clone(),
+    };
+    (party, rng, recorder)
+}
+
+/// Collects the [`Log`] of the party wrapped via [`record`]
+#[derive(Clone)]
+pub struct Recorder(Arc<Mutex<Result<Log, String>>>);
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(Ok(Log::default()))))
+    }
+}
+
+impl Recorder {
+    /// Returns the log recorded so far
+    pub fn log(&self) -> Result<Log, ReplayError> {
+        self.0
+            .lock()
+            .map_err(|_| Reason::PoisonedLock)?
+            .clone()
+            .map_err(|err| Reason::Record(err).into())
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Log) -> Result<(), serde_json::Error>) {
+        let Ok(mut log) = self.0.lock() else {
+            return;
+        };
+        let Ok(recorded) = log.as_mut() else {
+            return;
+        };
+        if let Err(err) = f(recorded) {
+            *log = Err(err.to_string())
+        }
+    }
+
+    fn record_incoming<M: Serialize>(&self, incoming: &Incoming<M>) {
+        self.update(|log| {
+            log.incomings.push(RecordedIncoming {
+                id: incoming.id,
+                sender: incoming.sender,
+                broadcast: incoming.msg_type == MessageType::Broadcast,
+                msg: serde_json::to_value(&incoming.msg)?,
+            });
+            Ok(())
+        })
+    }
+
+    fn record_outgoing<M: Serialize>(&self, outgoing: &Outgoing<M>) {
+        self.update(|log| {
+            log.outgoings.push(encode_outgoing(outgoing)?);
+            Ok(())
+        })
+    }
+
+    fn record_randomness(&self, bytes: &[u8]) {
+        self.update(|log| {
+            log.randomness.extend_from_slice(bytes);
+            Ok(())
+        })
+    }
+}
+
+/// RNG that records all randomness drawn from the inner RNG
+pub struct RecordingRng<R> {
+    inner: R,
+    recorder: Recorder,
+}
+
+impl<R: RngCore> RngCore for RecordingRng<R> {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.inner.fill_bytes(dest);
+        self.recorder.record_randomness(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.inner.try_fill_bytes(dest)?;
+        self.recorder.record_randomness(dest);
+        Ok(())
+    }
+}
+
+impl<R: CryptoRng> CryptoRng for RecordingRng<R> {}
+
+struct RecordingOutgoings<O> {
+    inner: O,
+    recorder: Recorder,
+}
+
+impl<O, M> Sink<Outgoing<M>> for RecordingOutgoings<O>
+where
+    O: Sink<Outgoing<M>> + Unpin,
+    M: Serialize,
+{
+    type Error = O::Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner).poll_ready(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Outgoing<M>) -> Result<(), Self::Error> {
+        self.recorder.record_outgoing(&item);
+        Pin::new(&mut self.inner).start_send(item)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+/// Re-executes the party from the log
+///
+/// Returns the party which receives recorded messages (followed by EOF) and the RNG which
+/// outputs recorded randomness. Messages sent by the party are compared against the recorded
+/// ones, results can be obtained via [`Replay::verify`] once protocol terminates.
+pub fn replay<M>(
+    log: &Log,
+) -> Result<(MpcParty<M, impl Delivery<M>>, ReplayRng, Replay), ReplayError>
+where
+    M: Serialize + DeserializeOwned,
+{
+    let incomings = log
+        .incomings
+        .iter()
+        .map(|incoming| {
+            Ok(Ok::<_, Infallible>(Incoming {
+                id: incoming.id,
+                sender: incoming.sender,
+                msg_type: if incoming.broadcast {
+                    MessageType::Broadcast
+                } else {
+                    MessageType::P2P
+                },
+                msg: serde_json::from_value(incoming.msg.clone()).map_err(Reason::Decode)?,
+            }))
+        })
+        .collect::<Result<Vec<_>, ReplayError>>()?;
+
+    let replay = Replay(Arc::new(Mutex::new(ReplayState {
+        expected: log.outgoings.clone(),
+        sent: 0,
+        divergence: None,
+        randomness_exhausted: false,
+    })));
+    let outgoings = ReplayOutgoings {
+        replay: replay.clone(),
+    };
+    let party = MpcParty::connected((futures::stream::iter(incomings), outgoings));
+    let rng = ReplayRng {
+        randomness: log.randomness.clone(),
+        position: 0,
+        replay: replay.clone(),
+    };
+    Ok((party, rng, replay))
+}
+
+/// Tracks whether replayed execution matches the recorded one
+#[derive(Clone)]
+pub struct Replay(Arc<Mutex<ReplayState>>);
+
+struct ReplayState {
+    expected: Vec<RecordedOutgoing>,
+    sent: usize,
+    divergence: Option<Divergence>,
+    randomness_exhausted: bool,
+}
+
+impl Replay {
+    /// Returns error if replayed execution diverged from the recorded one
+    ///
+    /// Execution diverges if the party sent a message different from the recorded one, sent less
+    /// or more messages, or drew more randomness than was recorded. Such divergence means that
+    /// the party was run with different inputs, or that the protocol implementation has changed
+    /// since the log was recorded.
+    pub fn verify(&self) -> Result<(), ReplayError> {
+        let state = self.0.lock().map_err(|_| Reason::PoisonedLock)?;
+        if let Some(divergence) = &state.divergence {
+            return Err(Reason::Diverged(divergence.clone()).into());
+        }
+        if state.randomness_exhausted {
+            return Err(Reason::RandomnessExhausted.into());
+        }
+        if state.sent < state.expected.len() {
+            return Err(Reason::MissingOutgoing {
+                sent: state.sent,
+                recorded: state.expected.len(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    fn check_outgoing(&self, outgoing: Result<RecordedOutgoing, serde_json::Error>) {
+        let Ok(mut state) = self.0.lock() else {
+            return;
+        };
+        let k = state.sent;
+        state.sent += 1;
+        if state.divergence.is_some() {
+            return;
+        }
+        state.divergence = match outgoing {
+            Err(err) => Some(Divergence {
+                msg: k,
+                reason: format!("serialize message: {err}"),
+            }),
+            Ok(outgoing) => match state.expected.get(k) {
+                None => Some(Divergence {
+                    msg: k,
+                    reason: "message wasn't sent in recorded execution".into(),
+                }),
+                Some(expected) if expected.recipient != outgoing.recipient => Some(Divergence {
+                    msg: k,
+                    reason: format!(
+                        "recipient mismatch: recorded {:?}, sent {:?}",
+                        expected.recipient, outgoing.recipient
+                    ),
+                }),
+                Some(expected) if expected.msg != outgoing.msg => Some(Divergence {
+                    msg: k,
+                    reason: "message mismatch".into(),
+                }),
+                Some(_) => None,
+            },
+        }
+    }
+}
+
+struct ReplayOutgoings {
+    replay: Replay,
+}
+
+impl<M: Serialize> Sink<Outgoing<M>> for ReplayOutgoings {
+    type Error = Infallible;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Outgoing<M>) -> Result<(), Self::Error> {
+        self.replay.check_outgoing(encode_outgoing(&item));
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// RNG which outputs randomness recorded in the [`Log`]
+///
+/// Once recorded randomness is exhausted, it outputs zeroes, and [`Replay::verify`] reports an
+/// error.
+pub struct ReplayRng {
+    randomness: Vec<u8>,
+    position: usize,
+    replay: Replay,
+}
+
+impl RngCore for ReplayRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let available = self.randomness.len() - self.position;
+        let len = dest.len().min(available);
+        dest[..len].copy_from_slice(&self.randomness[self.position..self.position + len]);
+        dest[len..].fill(0);
+        self.position += len;
+        if len < dest.len() {
+            if let Ok(mut state) = self.replay.0.lock() {
+                state.randomness_exhausted = true
+            }
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Replayed RNG is only used to reproduce an execution that used a cryptographically secure RNG
+impl CryptoRng for ReplayRng {}
+
+fn encode_outgoing<M: Serialize>(
+    outgoing: &Outgoing<M>,
+) -> Result<RecordedOutgoing, serde_json::Error> {
+    Ok(RecordedOutgoing {
+        recipient: match outgoing.recipient {
+            MessageDestination::AllParties => None,
+            MessageDestination::OneParty(j) => Some(j),
+        },
+        msg: serde_json::to_value(&outgoing.msg)?,
+    })
+}
+
+/// Point where replayed execution diverged from the recorded one
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    /// Index of the sent message at which execution diverged
+    pub msg: usize,
+    /// Description of the divergence
+    pub reason: String,
+}
+
+/// Error of recording or replaying the execution
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct ReplayError(Reason);
+
+impl ReplayError {
+    /// If replayed execution diverged from the recorded one, returns where it happened
+    pub fn divergence(&self) -> Option<&Divergence> {
+        match &self.0 {
+            Reason::Diverged(divergence) => Some(divergence),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("record message: {0}")]
+    Record(String),
+    #[error("decode recorded message")]
+    Decode(#[source] serde_json::Error),
+    #[error("execution diverged at sent message {}: {}", .0.msg, .0.reason)]
+    Diverged(Divergence),
+    #[error("party drew more randomness than was recorded")]
+    RandomnessExhausted,
+    #[error("party sent {sent} messages, but {recorded} were recorded")]
+    MissingOutgoing { sent: usize, recorded: usize },
+    #[error("lock is poisoned")]
+    PoisonedLock,
+}
+
+crate::errors::impl_from! {
+    impl From for ReplayError {
+        err: Reason => ReplayError(err),
+    }
+}
@@ -0,0 +1,62 @@
+//! Digests the protocols can be instantiated with
+//!
+//! All protocols are generic over digest `D` which is used to derive commitments, Fiat-Shamir
+//! challenges, and reliability checks. SHA2-256 is used by default, and another digest can be
+//! chosen via `set_digest` method of the protocol builders, e.g.
+//! [`SigningBuilder::set_digest`](crate::signing::SigningBuilder::set_digest). Any digest with
+//! at least 256 bits output is supported.
+//!
+//! This module defines configurations which are tested end-to-end. Each configuration exposes
+//! the digest and types of protocol messages instantiated with it:
+//!
+//! * [`sha2_256`] — SHA2-256, the default one
+//! * [`sha3_256`] — SHA3-256, requires `sha3` feature
+//! * [`blake2s_256`] — BLAKE2s-256, requires `blake2` feature
+//!
+//! All parties must use the same digest, otherwise the protocol fails.
+
+/// Defines protocol messages for the `Digest`
+macro_rules! configuration {
+    ($digest:ty) => {
+        /// The digest
+        pub type Digest = $digest;
+
+        /// Message of threshold keygen protocol
+        pub type KeygenMsg<E, L = crate::security_level::SecurityLevel128> =
+            crate::keygen::ThresholdMsg<E, L, Digest>;
+        /// Message of non-threshold keygen protocol
+        pub type NonThresholdKeygenMsg<E, L = crate::security_level::SecurityLevel128> =
+            crate::keygen::NonThresholdMsg<E, L, Digest>;
+        /// Message of aux info generation protocol
+        pub type AuxGenMsg<L = crate::security_level::SecurityLevel128> =
+            crate::key_refresh::AuxOnlyMsg<Digest, L>;
+        /// Message of key refresh protocol
+        pub type KeyRefreshMsg<E, L = crate::security_level::SecurityLevel128> =
+            crate::key_refresh::NonThresholdMsg<E, Digest, L>;
+        /// Message of signing protocol
+        pub type SigningMsg<E> = crate::signing::msg::Msg<E, Digest>;
+
+        /// Constructs [`DataToSign`](crate::signing::DataToSign) by hashing `data` with the
+        /// [`Digest`]
+        pub fn data_to_sign<E: generic_ec::Curve>(data: &[u8]) -> crate::signing::DataToSign<E> {
+            crate::signing::DataToSign::digest::<Digest>(data)
+        }
+    };
+}
+
+/// SHA2-256 configuration
+pub mod sha2_256 {
+    configuration!(sha2::Sha256);
+}
+
+/// SHA3-256 configuration
+#[cfg(feature = "sha3")]
+pub mod sha3_256 {
+    configuration!(sha3::Sha3_256);
+}
+
+/// BLAKE2s-256 configuration
+#[cfg(feature = "blake2")]
+pub mod blake2s_256 {
+    configuration!(blake2::Blake2s256);
+}
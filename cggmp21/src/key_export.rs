@@ -0,0 +1,152 @@
+//! Export of reconstructed secret key to standard wallet formats
+//!
+//! Once secret key is [reconstructed](crate::key_share::reconstruct_secret_key) (e.g. as part of
+//! disaster recovery), it can be exported in a format accepted by other wallets:
+//!
+//! * [`raw`] — 32 bytes big-endian encoding of the scalar
+//! * [`wif`] — Wallet Import Format (Bitcoin, secp256k1 only)
+//! * [`xprv`] — BIP-32 extended private key (Bitcoin, secp256k1 only, requires key to be
+//!   HD-capable)
+//!
+//! Exported key is a single point of failure, same as reconstructed one. Output should be handed
+//! over to the wallet right away, and never stored or logged.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn f(
+//! #     key_shares: &[cggmp21::KeyShare<cggmp21::supported_curves::Secp256k1>],
+//! # ) -> Result<(), Box<dyn std::error::Error>> {
+//! use cggmp21::key_export::{self, NetworkKind};
+//!
+//! let sk = cggmp21::key_share::reconstruct_secret_key(key_shares)?;
+//! let wif = key_export::wif(&sk, NetworkKind::Main)?;
+//! # Ok(()) }
+//! ```
+
+use generic_ec::{Curve, SecretScalar};
+
+#[cfg(feature = "bitcoin")]
+pub use ::bitcoin::NetworkKind;
+
+#[cfg(feature = "bitcoin")]
+use crate::supported_curves::Secp256k1;
+
+/// Exports secret key as 32 bytes big-endian integer
+///
+/// Returns error if scalars of the curve are not 32 bytes long. All curves
+/// [supported](crate::supported_curves) out of box have 32 bytes scalars, except for
+/// `Secp384r1`.
+pub fn raw<E: Curve>(sk: &SecretScalar<E>) -> Result<[u8; 32], ExportError> {
+    let bytes = sk.as_ref().to_be_bytes();
+    bytes
+        .as_bytes()
+        .try_into()
+        .map_err(|_| Reason::ScalarSize(bytes.len()).into())
+}
+
+/// Exports secret key in Wallet Import Format
+///
+/// Key is marked as corresponding to compressed public key, which is what modern wallets expect.
+#[cfg(feature = "bitcoin")]
+pub fn wif(sk: &SecretScalar<Secp256k1>, network: NetworkKind) -> Result<String, ExportError> {
+    Ok(::bitcoin::PrivateKey {
+        compressed: true,
+        network,
+        inner: secret_key(sk)?,
+    }
+    .to_wif())
+}
+
+/// Exports secret key as BIP-32 extended private key
+///
+/// Exported key is the root of the wallet (depth 0), `chain_code` is
+/// [chain code](crate::key_share::DirtyKeyInfo::chain_code) of the key share. Child keys
+/// derived by other wallets via non-hardened derivation match the ones derived from key shares.
+#[cfg(all(feature = "bitcoin", feature = "hd-wallets"))]
+pub fn xprv(
+    sk: &SecretScalar<Secp256k1>,
+    chain_code: slip_10::ChainCode,
+    network: NetworkKind,
+) -> Result<String, ExportError> {
+    use ::bitcoin::bip32;
+    Ok(bip32::Xpriv {
+        network,
+        depth: 0,
+        parent_fingerprint: bip32::Fingerprint::default(),
+        child_number: bip32::ChildNumber::Normal { index: 0 },
+        private_key: secret_key(sk)?,
+        chain_code: bip32::ChainCode::from(chain_code),
+    }
+    .to_string())
+}
+
+#[cfg(feature = "bitcoin")]
+fn secret_key(
+    sk: &SecretScalar<Secp256k1>,
+) -> Result<::bitcoin::secp256k1::SecretKey, ExportError> {
+    ::bitcoin::secp256k1::SecretKey::from_slice(&raw(sk)?).map_err(|_| Reason::ZeroKey.into())
+}
+
+/// Error indicating that key can't be exported
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct ExportError(Reason);
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("scalar is {0} bytes long, expected 32 bytes")]
+    ScalarSize(usize),
+    #[cfg(feature = "bitcoin")]
+    #[error("secret key is zero")]
+    ZeroKey,
+}
+
+crate::errors::impl_from! {
+    impl From for ExportError {
+        err: Reason => ExportError(err),
+    }
+}
+
+#[cfg(all(test, feature = "bitcoin"))]
+mod test {
+    use generic_ec::{Scalar, SecretScalar};
+
+    use super::NetworkKind;
+    use crate::supported_curves::Secp256k1;
+
+    fn bytes(hex: &str) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(hex, &mut bytes).unwrap();
+        bytes
+    }
+
+    fn secret_key(hex: &str) -> SecretScalar<Secp256k1> {
+        SecretScalar::new(&mut Scalar::from_be_bytes(bytes(hex)).unwrap())
+    }
+
+    #[test]
+    fn exports_wif() {
+        let sk = secret_key("0c28fca386c7a227600b2fe50b7cae11ec86d3bf1fbe471be89827e19d72aa1d");
+        assert_eq!(
+            super::wif(&sk, NetworkKind::Main).unwrap(),
+            "KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98617"
+        );
+
+        let testnet_wif = super::wif(&sk, NetworkKind::Test).unwrap();
+        let parsed = ::bitcoin::PrivateKey::from_wif(&testnet_wif).unwrap();
+        assert_eq!(parsed.network, NetworkKind::Test);
+        assert_eq!(parsed.inner.secret_bytes(), super::raw(&sk).unwrap());
+    }
+
+    /// Test vector 1 from BIP-32
+    #[cfg(feature = "hd-wallets")]
+    #[test]
+    fn exports_xprv() {
+        let sk = secret_key("e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35");
+        let chain_code = bytes("873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508");
+        assert_eq!(
+            super::xprv(&sk, chain_code, NetworkKind::Main).unwrap(),
+            "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi"
+        );
+    }
+}
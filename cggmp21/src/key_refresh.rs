@@ -2,25 +2,51 @@
 
 /// Auxiliary info (re)generation protocol specific types
 mod aux_only;
+/// Offline verification of aux data proofs
+mod aux_proofs;
+/// Attestations of key share erasure
+mod erasure;
 /// Non-threshold key refresh specific types
 mod non_threshold;
+/// Background primes generation
+mod primes_pool;
+/// Regeneration of ring-Pedersen parameters
+mod ring_pedersen;
 
 use digest::Digest;
 use generic_ec::Curve;
 use rand_core::{CryptoRng, RngCore};
-use round_based::Mpc;
+use round_based::{Mpc, PartyIndex};
 use thiserror::Error;
 
+use cggmp21_keygen::{abort::PeerAborted, transcript::Transcript};
+
 use crate::{
+    error_code::{ErrorCode, ErrorReport},
     errors::IoError,
-    key_share::{AnyKeyShare, AuxInfo, DirtyIncompleteKeyShare, KeyShare},
+    key_share::{AuxInfo, DirtyIncompleteKeyShare, IncompleteKeyShare, KeyShare, KeyShareRef},
+    policy::KeyUsagePolicy,
     progress::Tracer,
     security_level::SecurityLevel,
-    utils::AbortBlame,
-    ExecutionId,
+    utils::{self, AbortBlame},
+    EidRegistry, EidRegistryError, ExecutionId,
 };
 use crate::{fast_paillier, rug::Integer};
 
+pub use self::{
+    aux_only::AuxGenRounds,
+    aux_proofs::{verify_aux_proofs, AuxProofs, InvalidAuxProofs, PartyAuxProofs},
+    erasure::{
+        attest_erasure, verify_erasure_attestations, ErasureAttestation, ErasureError,
+        ErasureStatement, InvalidErasureAttestations,
+    },
+    primes_pool::PrimesPool,
+    ring_pedersen::{
+        apply_ring_pedersen_update, regenerate_ring_pedersen_params, RingPedersenError,
+        RingPedersenParams, RingPedersenUpdate,
+    },
+};
+
 #[doc(no_inline)]
 pub use self::msg::{aux_only::Msg as AuxOnlyMsg, non_threshold::Msg as NonThresholdMsg};
 
@@ -29,29 +55,50 @@ pub mod msg {
     /// Messages types related to aux information generation protocol
     pub mod aux_only {
         pub use crate::key_refresh::aux_only::{
-            Msg, MsgReliabilityCheck, MsgRound1, MsgRound2, MsgRound3,
+            Msg, MsgAbort, MsgEcho, MsgReliabilityCheck, MsgRound1, MsgRound2, MsgRound3,
         };
     }
     /// Messages types related to non threshold key refresh protocol
     pub mod non_threshold {
         pub use crate::key_refresh::non_threshold::{
-            Msg, MsgReliabilityCheck, MsgRound1, MsgRound2, MsgRound3,
+            Msg, MsgAbort, MsgEcho, MsgReliabilityCheck, MsgRound1, MsgRound2, MsgRound3,
         };
     }
 }
 
 /// To speed up computations, it's possible to supply data to the algorithm
 /// generated ahead of time
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PregeneratedPrimes<L = crate::default_choice::SecurityLevel> {
     p: Integer,
     q: Integer,
+    #[cfg_attr(feature = "serde", serde(default))]
+    kind: PrimesKind,
     _phantom: std::marker::PhantomData<L>,
 }
 
+/// Kind of [`PregeneratedPrimes`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PrimesKind {
+    /// Safe primes $p = 2p' + 1$ where $p'$ is prime, as required by the spec
+    #[default]
+    Safe,
+    /// Blum primes $p = 3 \mod 4$
+    ///
+    /// Much faster to generate, but security of the protocol is not proven for them. Must only
+    /// be used in tests.
+    Blum,
+}
+
 impl<L: SecurityLevel> PregeneratedPrimes<L> {
     /// Constructs pregenerated primes from two big numbers
     ///
+    /// Numbers are assumed to be [safe primes](PrimesKind::Safe). Protocols check that they're
+    /// actually safe before start, unless [Blum primes are allowed](GenericKeyRefreshBuilder::dangerous_allow_blum_primes).
+    ///
     /// Returns `None` if big numbers are smaller than 4 * [L::SECURITY_BITS](crate::security_level::KeygenSecurityLevel::SECURITY_BITS)
     ///
     /// Function doesn't validate that provided numbers are primes. If they're not,
@@ -63,6 +110,7 @@ impl<L: SecurityLevel> PregeneratedPrimes<L> {
             Some(Self {
                 p,
                 q,
+                kind: PrimesKind::Safe,
                 _phantom: std::marker::PhantomData,
             })
         }
@@ -78,9 +126,300 @@ impl<L: SecurityLevel> PregeneratedPrimes<L> {
         Self {
             p: fast_paillier::utils::generate_safe_prime(rng, 4 * L::SECURITY_BITS),
             q: fast_paillier::utils::generate_safe_prime(rng, 4 * L::SECURITY_BITS),
+            kind: PrimesKind::Safe,
             _phantom: std::marker::PhantomData,
         }
     }
+
+    /// Generates primes of given kind
+    ///
+    /// [`PrimesKind::Blum`] primes are much faster to generate, but they do not meet the spec.
+    /// Protocols refuse to use them unless explicitly allowed, see
+    /// [`GenericKeyRefreshBuilder::dangerous_allow_blum_primes`].
+    pub fn generate_with_kind<R: RngCore>(rng: &mut R, kind: PrimesKind) -> Self {
+        match kind {
+            PrimesKind::Safe => Self::generate(rng),
+            PrimesKind::Blum => Self {
+                p: utils::generate_blum_prime(rng, 4 * L::SECURITY_BITS),
+                q: utils::generate_blum_prime(rng, 4 * L::SECURITY_BITS),
+                kind,
+                _phantom: std::marker::PhantomData,
+            },
+        }
+    }
+
+    /// Returns kind of the primes
+    pub fn kind(&self) -> PrimesKind {
+        self.kind
+    }
+
+    /// Generates primes on `pool`, or on rayon's current thread pool if it's not specified
+    ///
+    /// Each thread of the pool tests its own random candidates, generation finishes as soon as two
+    /// safe primes are found. Which thread finds a prime first depends on thread scheduling, so
+    /// output is not determined by the `rng`. When pool has only one thread, it's equivalent to
+    /// [`generate`](Self::generate), which should be used when reproducibility is required (e.g.
+    /// in tests).
+    #[cfg(feature = "parallel")]
+    pub fn generate_parallel<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        pool: Option<&rayon::ThreadPool>,
+    ) -> Self {
+        let threads = pool.map_or_else(rayon::current_num_threads, |pool| {
+            pool.current_num_threads()
+        });
+        if threads == 1 {
+            return Self::generate(rng);
+        }
+
+        let bits = 4 * L::SECURITY_BITS;
+        let found = std::sync::Mutex::new(Vec::with_capacity(2));
+        let done = std::sync::atomic::AtomicBool::new(false);
+        let rngs = std::iter::repeat_with(|| utils::fork_rng(rng))
+            .take(threads)
+            .collect::<Vec<_>>();
+
+        utils::par_map(pool, rngs, |mut rng| {
+            let sieve = primes::sieve(primes::SIEVE_SIZE);
+            while !done.load(std::sync::atomic::Ordering::Relaxed) {
+                let Some(prime) = primes::try_safe_prime(&mut rng, bits, &sieve) else {
+                    continue;
+                };
+                let mut found = found.lock().unwrap_or_else(|e| e.into_inner());
+                if found.len() < 2 {
+                    found.push(prime);
+                }
+                if found.len() == 2 {
+                    done.store(true, std::sync::atomic::Ordering::Relaxed);
+                }
+            }
+        });
+
+        let mut found = found.into_inner().unwrap_or_else(|e| e.into_inner());
+        let (q, p) = (found.pop(), found.pop());
+        match (p, q) {
+            (Some(p), Some(q)) => Self {
+                p,
+                q,
+                kind: PrimesKind::Safe,
+                _phantom: std::marker::PhantomData,
+            },
+            // Unreachable: workers don't stop until both primes are found
+            _ => Self::generate(rng),
+        }
+    }
+
+    /// Generates primes, reporting progress and checking for cancellation
+    ///
+    /// `on_progress` is called after every tested candidate, so it should be cheap: e.g. it may
+    /// only update a progress bar once in a while. Generation is aborted as soon as `cancel` is
+    /// [cancelled](CancellationToken::cancel), in which case error is returned.
+    pub fn generate_with_progress<R: RngCore>(
+        rng: &mut R,
+        cancel: &CancellationToken,
+        mut on_progress: impl FnMut(&PrimesGenerationProgress),
+    ) -> Result<Self, PrimesGenerationCancelled> {
+        let bits = 4 * L::SECURITY_BITS;
+        let sieve = primes::sieve(primes::SIEVE_SIZE);
+        let started_at = std::time::Instant::now();
+        let mut progress = PrimesGenerationProgress {
+            candidates_tried: 0,
+            primes_found: 0,
+            elapsed: std::time::Duration::ZERO,
+        };
+
+        let mut found = Vec::with_capacity(2);
+        while found.len() < 2 {
+            if cancel.is_cancelled() {
+                return Err(PrimesGenerationCancelled);
+            }
+            if let Some(prime) = primes::try_safe_prime(rng, bits, &sieve) {
+                found.push(prime);
+            }
+            progress.candidates_tried += 1;
+            progress.primes_found = found.len();
+            progress.elapsed = started_at.elapsed();
+            on_progress(&progress);
+        }
+
+        let q = found.pop();
+        let p = found.pop();
+        match (p, q) {
+            (Some(p), Some(q)) => Ok(Self {
+                p,
+                q,
+                kind: PrimesKind::Safe,
+                _phantom: std::marker::PhantomData,
+            }),
+            // Unreachable: loop only exits when both primes are found
+            _ => Err(PrimesGenerationCancelled),
+        }
+    }
+
+    /// Validates primes
+    ///
+    /// Checks that `p` and `q` are distinct primes of bit length required by security level `L`,
+    /// and that they're of declared [kind](Self::kind): both kinds must be Blum primes, safe
+    /// primes additionally must be safe. It's recommended to validate primes obtained
+    /// from untrusted storage: invalid primes otherwise only make key refresh fail without clear
+    /// reason.
+    ///
+    /// Takes some time as it performs primality tests.
+    pub fn validate(&self) -> Result<(), InvalidPregeneratedPrimes> {
+        if self.p == self.q {
+            return Err(InvalidPrimesReason::Equal.into());
+        }
+        for (name, x) in [("p", &self.p), ("q", &self.q)] {
+            let bits = x.significant_bits();
+            if bits < 4 * L::SECURITY_BITS {
+                return Err(InvalidPrimesReason::TooSmall {
+                    name,
+                    bits,
+                    required: 4 * L::SECURITY_BITS,
+                }
+                .into());
+            }
+            if x.mod_u(4) != 3 {
+                return Err(InvalidPrimesReason::NotBlum(name).into());
+            }
+            if !primes::is_prime(x) {
+                return Err(InvalidPrimesReason::NotPrime(name).into());
+            }
+            if self.kind == PrimesKind::Safe && !primes::is_prime(&(x.clone() >> 1)) {
+                return Err(InvalidPrimesReason::NotSafe(name).into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Progress of primes generation, see [`PregeneratedPrimes::generate_with_progress`]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PrimesGenerationProgress {
+    /// Amount of candidates tested so far
+    pub candidates_tried: u64,
+    /// Amount of primes found so far, out of 2
+    pub primes_found: usize,
+    /// Time passed since generation started
+    pub elapsed: std::time::Duration,
+}
+
+/// Token that cancels primes generation
+///
+/// Token can be cloned and sent to another thread, cancelling any of the clones cancels them all.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(std::sync::Arc<std::sync::atomic::AtomicBool>);
+
+impl CancellationToken {
+    /// Constructs a new token
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the generation
+    pub fn cancel(&self) {
+        self.0.store(true, std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Checks whether the token was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+/// Error indicating that primes generation was cancelled
+#[derive(Debug, Error)]
+#[error("primes generation was cancelled")]
+pub struct PrimesGenerationCancelled;
+
+/// Error indicating that [`PregeneratedPrimes`] are not valid
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct InvalidPregeneratedPrimes(InvalidPrimesReason);
+
+#[derive(Debug, Error)]
+enum InvalidPrimesReason {
+    #[error("p and q are equal")]
+    Equal,
+    #[error("{name} is {bits} bits long, but security level requires at least {required} bits")]
+    TooSmall {
+        name: &'static str,
+        bits: u32,
+        required: u32,
+    },
+    #[error("{0} is not a Blum integer: it's not equal to 3 modulo 4")]
+    NotBlum(&'static str),
+    #[error("{0} is not a prime")]
+    NotPrime(&'static str),
+    #[error("{0} is not a safe prime: ({0} - 1) / 2 is not a prime")]
+    NotSafe(&'static str),
+}
+
+crate::errors::impl_from! {
+    impl From for InvalidPregeneratedPrimes {
+        err: InvalidPrimesReason => InvalidPregeneratedPrimes(err),
+    }
+}
+
+/// Safe primes search
+pub(crate) mod primes {
+    use rand_core::RngCore;
+
+    use crate::rug::{integer::IsPrime, Integer};
+
+    /// Amount of small primes candidates are sieved with
+    ///
+    /// Matches one used by [`fast_paillier::utils::generate_safe_prime`](crate::fast_paillier::utils::generate_safe_prime)
+    pub const SIEVE_SIZE: usize = 135;
+
+    /// Returns first `amount` odd primes
+    pub fn sieve(amount: usize) -> Vec<u32> {
+        (3u32..)
+            .step_by(2)
+            .filter(|&x| {
+                (3..)
+                    .step_by(2)
+                    .take_while(|d| d * d <= x)
+                    .all(|d| x % d != 0)
+            })
+            .take(amount)
+            .collect()
+    }
+
+    /// Tests one random candidate
+    ///
+    /// Returns safe prime $2x + 1$ of `bits` bits if random $x$ and $2x + 1$ are both primes.
+    /// Search algorithm is the same as in [`fast_paillier::utils::generate_safe_prime`](crate::fast_paillier::utils::generate_safe_prime),
+    /// but split into trials, so it can be interrupted between them.
+    pub fn try_safe_prime(rng: &mut impl RngCore, bits: u32, sieve: &[u32]) -> Option<Integer> {
+        let mut x = Integer::from(Integer::random_bits(
+            bits - 1,
+            &mut crate::fast_paillier::utils::external_rand(rng),
+        ));
+        x.set_bit(bits - 2, true);
+        x |= 1u32;
+
+        // 2x + 1 is divisible by small prime `p` iff x = (p - 1) / 2 mod p
+        if sieve.iter().any(|&p| x.mod_u(p) == (p - 1) / 2) {
+            return None;
+        }
+        if !is_prime(&x) {
+            return None;
+        }
+        x <<= 1;
+        x += 1;
+        if !is_prime(&x) {
+            return None;
+        }
+        Some(x)
+    }
+
+    /// Probabilistic primality test
+    pub fn is_prime(x: &Integer) -> bool {
+        // 25 taken same as one used in mpz_nextprime
+        x.is_probably_prime(25) != IsPrime::No
+    }
 }
 
 /// A variant of [`GenericKeyRefreshBuilder`] that performs key refresh
@@ -100,7 +439,7 @@ pub type AuxInfoGenerationBuilder<
 > = GenericKeyRefreshBuilder<'a, AuxOnly, L, D>;
 
 /// Entry point for key refresh and auxiliary info generation.
-pub struct GenericKeyRefreshBuilder<'a, M, L, D>
+pub struct GenericKeyRefreshBuilder<'a, M, L, D, H = D>
 where
     L: SecurityLevel,
     D: Digest,
@@ -109,14 +448,53 @@ where
     execution_id: ExecutionId<'a>,
     pregenerated: PregeneratedPrimes<L>,
     tracer: Option<&'a mut dyn Tracer>,
+    eid_registry: Option<&'a dyn EidRegistry>,
     enforce_reliable_broadcast: bool,
+    enforce_echo_broadcast: bool,
     precompute_multiexp_tables: bool,
     precompute_crt: bool,
-    _digest: std::marker::PhantomData<D>,
+    allow_blum_primes: bool,
+    ring_pedersen_params: Option<RingPedersenParams>,
+    thread_pool: Option<&'a utils::ThreadPool>,
+    _digest: std::marker::PhantomData<(D, H)>,
+}
+
+/// Key share refreshed by [`KeyRefreshBuilder`]
+///
+/// Obtained from [`KeyShare`], [`KeyShareRef`] or [`IncompleteKeyShare`]. Key usage
+/// [policy](crate::key_share::DirtyKeyShare::policy) of the key share, if any, is carried into
+/// the refreshed key share.
+pub struct RefreshShare<'a, E: Curve> {
+    core: &'a DirtyIncompleteKeyShare<E>,
+    policy: Option<&'a KeyUsagePolicy>,
+}
+
+impl<'a, E: Curve, L: SecurityLevel> From<&'a KeyShare<E, L>> for RefreshShare<'a, E> {
+    fn from(key_share: &'a KeyShare<E, L>) -> Self {
+        Self {
+            core: &key_share.core,
+            policy: key_share.policy.as_ref(),
+        }
+    }
 }
 
-/// A marker for [`KeyRefreshBuilder`]
-pub struct RefreshShare<'a, E: Curve>(&'a DirtyIncompleteKeyShare<E>);
+impl<'a, E: Curve, L: SecurityLevel> From<KeyShareRef<'a, E, L>> for RefreshShare<'a, E> {
+    fn from(key_share: KeyShareRef<'a, E, L>) -> Self {
+        Self {
+            core: key_share.core,
+            policy: key_share.policy,
+        }
+    }
+}
+
+impl<'a, E: Curve> From<&'a IncompleteKeyShare<E>> for RefreshShare<'a, E> {
+    fn from(key_share: &'a IncompleteKeyShare<E>) -> Self {
+        Self {
+            core: key_share,
+            policy: None,
+        }
+    }
+}
 /// A marker for [`AuxInfoGenerationBuilder`]
 pub struct AuxOnly {
     i: u16,
@@ -134,21 +512,34 @@ where
     /// PregeneratedPrimes can be obtained with [`PregeneratedPrimes::generate`]
     pub fn new(
         eid: ExecutionId<'a>,
-        key_share: &'a impl AnyKeyShare<E>,
+        key_share: impl Into<RefreshShare<'a, E>>,
         pregenerated: PregeneratedPrimes<L>,
     ) -> Self {
         Self {
-            target: RefreshShare(key_share.as_ref()),
+            target: key_share.into(),
             execution_id: eid,
             pregenerated,
             tracer: None,
+            eid_registry: None,
             enforce_reliable_broadcast: true,
+            enforce_echo_broadcast: false,
             precompute_multiexp_tables: false,
             precompute_crt: false,
+            allow_blum_primes: false,
+            ring_pedersen_params: None,
+            thread_pool: None,
             _digest: std::marker::PhantomData,
         }
     }
+}
 
+impl<'a, E, L, D, H> GenericKeyRefreshBuilder<'a, RefreshShare<'a, E>, L, D, H>
+where
+    E: Curve,
+    L: SecurityLevel,
+    D: Digest,
+    H: Digest<OutputSize = D::OutputSize>,
+{
     /// Carry out the refresh procedure. Takes a lot of time
     pub async fn start<R, M>(self, rng: &mut R, party: M) -> Result<KeyShare<E, L>, KeyRefreshError>
     where
@@ -156,18 +547,52 @@ where
         M: Mpc<ProtocolMessage = NonThresholdMsg<E, D, L>>,
         E: Curve,
         L: SecurityLevel,
-        D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+        D: Digest + Clone + Sync + 'static,
     {
-        non_threshold::run_refresh(
+        self.start_with_rid(rng, party)
+            .await
+            .map(|(key_share, _rid)| key_share)
+    }
+
+    /// Carry out the refresh procedure, outputs refreshed key share along with a new `rid`
+    ///
+    /// `rid` is collective random bytes that all parties agree on. It's sampled with the same
+    /// commit-reveal structure as `rid` in keygen: each party commits to its share of bytes in the
+    /// first round and reveals it in the second one, `rid` is XOR of all revealed shares. No party
+    /// can bias it unless all of them collude.
+    ///
+    /// Key share doesn't store `rid`, so derivations bound to it can be rotated along with the key
+    /// shares by replacing previously used `rid` with the returned one.
+    pub async fn start_with_rid<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+    ) -> Result<(KeyShare<E, L>, L::Rid), KeyRefreshError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = NonThresholdMsg<E, D, L>>,
+        E: Curve,
+        L: SecurityLevel,
+        D: Digest + Clone + Sync + 'static,
+    {
+        self.check_primes_kind()?;
+        if let Some(registry) = self.eid_registry {
+            registry.register(self.execution_id)?;
+        }
+        non_threshold::run_refresh::<_, _, _, _, _, H>(
             rng,
             party,
             self.execution_id,
             self.pregenerated,
             self.tracer,
             self.enforce_reliable_broadcast,
+            self.enforce_echo_broadcast,
             self.precompute_multiexp_tables,
             self.precompute_crt,
-            self.target.0,
+            self.ring_pedersen_params,
+            self.target.core,
+            self.target.policy,
+            self.thread_pool,
         )
         .await
     }
@@ -192,22 +617,92 @@ where
             execution_id: eid,
             pregenerated,
             tracer: None,
+            eid_registry: None,
             enforce_reliable_broadcast: true,
+            enforce_echo_broadcast: false,
             precompute_multiexp_tables: false,
             precompute_crt: false,
+            allow_blum_primes: false,
+            ring_pedersen_params: None,
+            thread_pool: None,
             _digest: std::marker::PhantomData,
         }
     }
+}
 
+impl<'a, L, D, H> GenericKeyRefreshBuilder<'a, AuxOnly, L, D, H>
+where
+    L: SecurityLevel,
+    D: Digest,
+    H: Digest<OutputSize = D::OutputSize>,
+{
     /// Carry out the aux info generation procedure. Takes a lot of time
     pub async fn start<R, M>(self, rng: &mut R, party: M) -> Result<AuxInfo<L>, KeyRefreshError>
     where
         R: RngCore + CryptoRng,
         M: Mpc<ProtocolMessage = aux_only::Msg<D, L>>,
         L: SecurityLevel,
-        D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+        D: Digest + Clone + Sync + 'static,
+    {
+        self.start_with_proofs(rng, party)
+            .await
+            .map(|(aux, _proofs)| aux)
+    }
+
+    /// Carry out the aux info generation procedure, outputs aux info along with proofs received from
+    /// other parties
+    ///
+    /// Proofs can be persisted and re-verified later with [`verify_aux_proofs`].
+    pub async fn start_with_proofs<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+    ) -> Result<(AuxInfo<L>, AuxProofs), KeyRefreshError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = aux_only::Msg<D, L>>,
+        L: SecurityLevel,
+        D: Digest + Clone + Sync + 'static,
+    {
+        self.run_aux_gen(rng, party)
+            .await
+            .map(|(aux, proofs, _transcript)| (aux, proofs))
+    }
+
+    /// Carry out the aux info generation procedure, outputs aux info along with
+    /// [transcript](crate::transcript::Transcript) of all broadcast messages
+    pub async fn start_with_transcript<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+    ) -> Result<(AuxInfo<L>, Transcript<AuxGenRounds<L, D>>), KeyRefreshError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = aux_only::Msg<D, L>>,
+        L: SecurityLevel,
+        D: Digest + Clone + Sync + 'static,
     {
-        aux_only::run_aux_gen(
+        self.run_aux_gen(rng, party)
+            .await
+            .map(|(aux, _proofs, transcript)| (aux, transcript))
+    }
+
+    async fn run_aux_gen<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+    ) -> Result<(AuxInfo<L>, AuxProofs, Transcript<AuxGenRounds<L, D>>), KeyRefreshError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = aux_only::Msg<D, L>>,
+        L: SecurityLevel,
+        D: Digest + Clone + Sync + 'static,
+    {
+        self.check_primes_kind()?;
+        if let Some(registry) = self.eid_registry {
+            registry.register(self.execution_id)?;
+        }
+        aux_only::run_aux_gen::<_, _, _, _, H>(
             self.target.i,
             self.target.n,
             rng,
@@ -216,28 +711,65 @@ where
             self.pregenerated,
             self.tracer,
             self.enforce_reliable_broadcast,
+            self.enforce_echo_broadcast,
             self.precompute_multiexp_tables,
             self.precompute_crt,
+            self.ring_pedersen_params,
+            self.thread_pool,
         )
         .await
     }
 }
 
-impl<'a, L, D, T> GenericKeyRefreshBuilder<'a, T, L, D>
+impl<'a, L, D, H, T> GenericKeyRefreshBuilder<'a, T, L, D, H>
 where
     L: SecurityLevel,
     D: Digest,
+    H: Digest<OutputSize = D::OutputSize>,
 {
     /// Specifies another hash function to use
+    ///
+    /// Any digest with at least 256 bits output can be used, see
+    /// [`SigningBuilder::set_digest`](crate::signing::SigningBuilder::set_digest).
+    ///
+    /// Also resets digest used in reliability checks to `D2`, see
+    /// [`set_reliability_digest`](Self::set_reliability_digest).
     pub fn set_digest<D2: Digest>(self) -> GenericKeyRefreshBuilder<'a, T, L, D2> {
         GenericKeyRefreshBuilder {
             target: self.target,
             execution_id: self.execution_id,
             pregenerated: self.pregenerated,
             tracer: self.tracer,
+            eid_registry: self.eid_registry,
             enforce_reliable_broadcast: self.enforce_reliable_broadcast,
+            enforce_echo_broadcast: self.enforce_echo_broadcast,
             precompute_multiexp_tables: self.precompute_multiexp_tables,
             precompute_crt: self.precompute_crt,
+            allow_blum_primes: self.allow_blum_primes,
+            ring_pedersen_params: self.ring_pedersen_params,
+            thread_pool: self.thread_pool,
+            _digest: std::marker::PhantomData,
+        }
+    }
+
+    #[doc = include_str!("../docs/set_reliability_digest.md")]
+    pub fn set_reliability_digest<H2>(self) -> GenericKeyRefreshBuilder<'a, T, L, D, H2>
+    where
+        H2: Digest<OutputSize = D::OutputSize>,
+    {
+        GenericKeyRefreshBuilder {
+            target: self.target,
+            execution_id: self.execution_id,
+            pregenerated: self.pregenerated,
+            tracer: self.tracer,
+            eid_registry: self.eid_registry,
+            enforce_reliable_broadcast: self.enforce_reliable_broadcast,
+            enforce_echo_broadcast: self.enforce_echo_broadcast,
+            precompute_multiexp_tables: self.precompute_multiexp_tables,
+            precompute_crt: self.precompute_crt,
+            allow_blum_primes: self.allow_blum_primes,
+            ring_pedersen_params: self.ring_pedersen_params,
+            thread_pool: self.thread_pool,
             _digest: std::marker::PhantomData,
         }
     }
@@ -248,6 +780,26 @@ where
         self
     }
 
+    /// Sets a registry of used execution IDs
+    ///
+    /// Before starting the protocol, execution ID is registered in the registry. If it was
+    /// already used before, protocol won't start and returns an error.
+    pub fn set_eid_registry(mut self, registry: &'a dyn EidRegistry) -> Self {
+        self.eid_registry = Some(registry);
+        self
+    }
+
+    /// Specifies thread pool to run heavy computations on
+    ///
+    /// Proofs generation and verification are spread across threads of `pool`. By default,
+    /// rayon's global thread pool is used. Dedicated pool lets you limit parallelism, so protocol
+    /// doesn't starve other services running within the same process.
+    #[cfg(feature = "parallel")]
+    pub fn set_thread_pool(mut self, pool: &'a rayon::ThreadPool) -> Self {
+        self.thread_pool = Some(pool);
+        self
+    }
+
     #[doc = include_str!("../docs/enforce_reliable_broadcast.md")]
     pub fn enforce_reliable_broadcast(self, v: bool) -> Self {
         Self {
@@ -256,6 +808,14 @@ where
         }
     }
 
+    #[doc = include_str!("../docs/enforce_echo_broadcast.md")]
+    pub fn enforce_echo_broadcast(self, v: bool) -> Self {
+        Self {
+            enforce_echo_broadcast: v,
+            ..self
+        }
+    }
+
     /// Precomputes multiexponentiation tables for output aux data
     ///
     /// Enables optimization that makes signing and presigning faster. Precomputation takes a
@@ -277,6 +837,54 @@ where
         self.precompute_crt = v;
         self
     }
+
+    /// Allows using [Blum primes](PrimesKind::Blum)
+    ///
+    /// By default, protocol refuses to start if pregenerated primes are not safe primes. Blum
+    /// primes are much faster to generate, which makes them handy in tests, but security of the
+    /// protocol is not proven for them. Never enable it in production.
+    pub fn dangerous_allow_blum_primes(mut self, v: bool) -> Self {
+        self.allow_blum_primes = v;
+        self
+    }
+
+    /// Uses pregenerated ring-Pedersen parameters instead of sampling them
+    ///
+    /// Parameters may be produced externally (e.g. in HSM) for modulus $N = pq$ made of pregenerated
+    /// primes. Protocol validates them and proves that they're well-formed as usual. Parameters
+    /// must be fresh: never reuse them across protocol executions.
+    pub fn set_ring_pedersen_params(mut self, params: RingPedersenParams) -> Self {
+        self.ring_pedersen_params = Some(params);
+        self
+    }
+
+    /// Checks that pregenerated primes are safe primes unless Blum primes are allowed
+    ///
+    /// Declared [kind](PregeneratedPrimes::kind) can't be trusted as primes constructed via
+    /// [`PregeneratedPrimes::new`] or deserialized are always declared safe, so $(p-1)/2$ and
+    /// $(q-1)/2$ are tested for primality.
+    fn check_primes_kind(&self) -> Result<(), KeyRefreshError> {
+        if self.allow_blum_primes {
+            return Ok(());
+        }
+        let is_safe = |x: &Integer| primes::is_prime(&(x.clone() >> 1));
+        if self.pregenerated.kind == PrimesKind::Blum
+            || !is_safe(&self.pregenerated.p)
+            || !is_safe(&self.pregenerated.q)
+        {
+            return Err(KeyRefreshError(Reason::BlumPrimesNotAllowed));
+        }
+        Ok(())
+    }
+
+    /// Returns total amount of rounds the protocol will go through with the current settings
+    ///
+    /// Matches amount of [`Event::RoundBegins`](crate::progress::Event::RoundBegins) events
+    /// emitted to the tracer, so it can be used with
+    /// [`ProgressTracer`](crate::progress::ProgressTracer) to report progress to the end user.
+    pub fn rounds_count(&self) -> u16 {
+        4 + u16::from(self.enforce_reliable_broadcast) + u16::from(self.enforce_echo_broadcast)
+    }
 }
 
 /// Error of key refresh and aux info generation protocols
@@ -284,10 +892,48 @@ where
 #[error("key refresh protocol failed to complete")]
 pub struct KeyRefreshError(#[source] Reason);
 
+impl KeyRefreshError {
+    /// Returns index of the party who aborted the protocol by sending
+    /// [`MsgAbort`](crate::abort::MsgAbort), if protocol failed for this reason
+    pub fn aborted_by_peer(&self) -> Option<PartyIndex> {
+        match &self.0 {
+            Reason::PeerAborted(PeerAborted(j)) => Some(*j),
+            _ => None,
+        }
+    }
+
+    /// Returns stable code of the error
+    pub fn code(&self) -> ErrorCode {
+        match &self.0 {
+            Reason::Aborted(err) => err.reason.code(),
+            Reason::IoError(err) => err.code(),
+            Reason::PeerAborted(_) => ErrorCode::PeerAborted,
+            Reason::EidRegistry(_) => ErrorCode::ExecutionIdRejected,
+            Reason::BlumPrimesNotAllowed => ErrorCode::BlumPrimesNotAllowed,
+            Reason::InvalidRingPedersenParams(_) => ErrorCode::InvalidArgs,
+            Reason::InternalError(_) => ErrorCode::InternalError,
+        }
+    }
+
+    /// Returns serializable report of the error
+    pub fn report(&self) -> ErrorReport {
+        let parties = match &self.0 {
+            Reason::Aborted(err) => err.parties.iter().map(|b| b.faulty_party).collect(),
+            Reason::PeerAborted(PeerAborted(j)) => vec![*j],
+            _ => vec![],
+        };
+        ErrorReport::new(self.code(), self, parties)
+    }
+}
+
 crate::errors::impl_from! {
     impl From for KeyRefreshError {
         err: ProtocolAborted => KeyRefreshError(Reason::Aborted(err)),
-        err: IoError => KeyRefreshError(Reason::IoError(err)),
+        err: IoError => match err {
+            IoError::PeerAborted(err) => KeyRefreshError(Reason::PeerAborted(err)),
+            err => KeyRefreshError(Reason::IoError(err)),
+        },
+        err: EidRegistryError => KeyRefreshError(Reason::EidRegistry(err)),
         err: Bug => KeyRefreshError(Reason::InternalError(err)),
     }
 }
@@ -299,6 +945,14 @@ enum Reason {
     Aborted(#[source] ProtocolAborted),
     #[error("i/o error")]
     IoError(#[source] IoError),
+    #[error("protocol was aborted by another party")]
+    PeerAborted(#[source] PeerAborted),
+    #[error("execution id was rejected by registry")]
+    EidRegistry(#[source] EidRegistryError),
+    #[error("pregenerated primes are not safe primes, but blum primes are not allowed")]
+    BlumPrimesNotAllowed,
+    #[error("provided ring-pedersen parameters are invalid")]
+    InvalidRingPedersenParams(#[source] RingPedersenError),
     #[error("internal error")]
     InternalError(#[from] Bug),
 }
@@ -353,6 +1007,8 @@ enum ProtocolAbortReason {
     InvalidFacProof,
     #[error("N, s and t parameters are invalid")]
     InvalidRingPedersenParameters,
+    #[error("Paillier modulus N is equal to or shares a factor with modulus of another party")]
+    RelatedPaillierModuli,
     #[error("X is malformed")]
     InvalidX,
     #[error("x doesn't correspond to X")]
@@ -361,8 +1017,26 @@ enum ProtocolAbortReason {
     InvalidDataSize,
     #[error("party message could not be decrypted")]
     PaillierDec,
-    #[error("round 1 was not reliable")]
-    Round1NotReliable,
+    #[error("broadcast at round {round} wasn't reliable")]
+    BroadcastNotReliable { round: u16 },
+}
+
+impl ProtocolAbortReason {
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::InvalidDecommitment => ErrorCode::InvalidDecommitment,
+            Self::InvalidSchnorrProof => ErrorCode::InvalidSchnorrProof,
+            Self::InvalidModProof => ErrorCode::InvalidModProof,
+            Self::InvalidFacProof => ErrorCode::InvalidFacProof,
+            Self::InvalidRingPedersenParameters => ErrorCode::InvalidRingPedersenParameters,
+            Self::RelatedPaillierModuli => ErrorCode::RelatedPaillierModuli,
+            Self::InvalidX => ErrorCode::InvalidPublicShare,
+            Self::InvalidXShare => ErrorCode::InvalidSecretShare,
+            Self::InvalidDataSize => ErrorCode::InvalidDataSize,
+            Self::PaillierDec => ErrorCode::UndecryptableMessage,
+            Self::BroadcastNotReliable { .. } => ErrorCode::BroadcastNotReliable,
+        }
+    }
 }
 
 macro_rules! make_factory {
@@ -384,9 +1058,16 @@ impl ProtocolAborted {
         invalid_ring_pedersen_parameters,
         InvalidRingPedersenParameters
     );
+    make_factory!(related_paillier_moduli, RelatedPaillierModuli);
     make_factory!(invalid_x, InvalidX);
     make_factory!(invalid_x_share, InvalidXShare);
     make_factory!(invalid_data_size, InvalidDataSize);
     make_factory!(paillier_dec, PaillierDec);
-    make_factory!(round1_not_reliable, Round1NotReliable);
+
+    fn broadcast_not_reliable(round: u16, parties: Vec<AbortBlame>) -> Self {
+        Self {
+            reason: ProtocolAbortReason::BroadcastNotReliable { round },
+            parties,
+        }
+    }
 }
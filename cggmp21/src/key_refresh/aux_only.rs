@@ -4,14 +4,15 @@ use paillier_zk::{
     no_small_factor::non_interactive as π_fac,
     paillier_blum_modulus as π_mod,
     rug::{Complete, Integer},
-    IntegerExt,
 };
 use rand_core::{CryptoRng, RngCore};
 use round_based::{
     rounds_router::{simple_store::RoundInput, RoundsRouter},
     Delivery, Mpc, MpcParty, Outgoing, ProtocolMessage,
 };
-use serde::{Deserialize, Serialize};
+
+use cggmp21_keygen::{abort, transcript::Transcript};
+pub use cggmp21_keygen::{abort::MsgAbort, echo_broadcast::MsgEcho};
 
 use crate::{
     errors::IoError,
@@ -19,16 +20,20 @@ use crate::{
     progress::Tracer,
     security_level::SecurityLevel,
     utils,
-    utils::{collect_blame, AbortBlame},
+    utils::{collect_blame, par_collect_blame, AbortBlame},
     zk::ring_pedersen_parameters as π_prm,
     ExecutionId,
 };
 
-use super::{Bug, KeyRefreshError, PregeneratedPrimes, ProtocolAborted};
+use super::{
+    AuxProofs, Bug, KeyRefreshError, PartyAuxProofs, PregeneratedPrimes, ProtocolAborted, Reason,
+    RingPedersenParams,
+};
 
 /// Message of key refresh protocol
-#[derive(ProtocolMessage, Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(ProtocolMessage, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 // 3 kilobytes for the largest option, and 2.5 kilobytes for second largest
 #[allow(clippy::large_enum_variant)]
 pub enum Msg<D: Digest, L: SecurityLevel> {
@@ -40,23 +45,29 @@ pub enum Msg<D: Digest, L: SecurityLevel> {
     Round3(MsgRound3),
     /// Reliability check message (optional additional round)
     ReliabilityCheck(MsgReliabilityCheck<D>),
+    /// Echo of round 2 messages (optional additional round)
+    Round2Echo(MsgEcho<D, 2>),
+    /// Party aborts the protocol (not part of any round)
+    Abort(MsgAbort),
 }
 
 /// Message from round 1
-#[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[udigest(tag = "dfns.cggmp21.aux_gen.round1")]
 #[udigest(bound = "")]
-#[serde(bound = "")]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct MsgRound1<D: Digest> {
     /// $V_i$
     #[udigest(as_bytes)]
     pub commitment: digest::Output<D>,
 }
 /// Message from round 2
-#[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[udigest(tag = "dfns.cggmp21.aux_gen.round2")]
 #[udigest(bound = "")]
-#[serde(bound = "")]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct MsgRound2<L: SecurityLevel> {
     /// $N_i$
     #[udigest(with = utils::encoding::integer)]
@@ -72,16 +83,17 @@ pub struct MsgRound2<L: SecurityLevel> {
     pub params_proof: π_prm::Proof<{ crate::security_level::M }>,
     /// $\rho_i$
     // ideally it would be [u8; L::SECURITY_BYTES], but no rustc support yet
-    #[serde(with = "hex")]
+    #[cfg_attr(feature = "serde", serde(with = "hex"))]
     #[udigest(as_bytes)]
     pub rho_bytes: L::Rid,
     /// $u_i$
-    #[serde(with = "hex")]
+    #[cfg_attr(feature = "serde", serde(with = "hex"))]
     #[udigest(as_bytes)]
     pub decommit: L::Rid,
 }
 /// Unicast message of round 3, sent to each participant
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MsgRound3 {
     /// $\psi_i$
     // this should be L::M instead, but no rustc support yet
@@ -93,9 +105,26 @@ pub struct MsgRound3 {
     pub fac_proof: π_fac::Proof,
 }
 
+/// Broadcast messages of aux info generation, recorded in [`Transcript`]
+///
+/// `roundN[j]` is the message that $j$-th party sent in round `N`. Proofs sent over p2p channels
+/// in round 3 are not recorded.
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[udigest(tag = "dfns.cggmp21.aux_gen.transcript")]
+#[udigest(bound = "")]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct AuxGenRounds<L: SecurityLevel, D: Digest> {
+    /// Commitments
+    pub round1: Vec<MsgRound1<D>>,
+    /// Decommitments
+    pub round2: Vec<MsgRound2<L>>,
+}
+
 /// Message from an optional round that enforces reliability check
-#[derive(Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct MsgReliabilityCheck<D: Digest>(pub digest::Output<D>);
 
 #[derive(udigest::Digestable)]
@@ -114,7 +143,7 @@ enum Tag<'a> {
     },
 }
 
-pub async fn run_aux_gen<R, M, L, D>(
+pub async fn run_aux_gen<R, M, L, D, H>(
     i: u16,
     n: u16,
     mut rng: &mut R,
@@ -123,14 +152,18 @@ pub async fn run_aux_gen<R, M, L, D>(
     pregenerated: PregeneratedPrimes<L>,
     mut tracer: Option<&mut dyn Tracer>,
     reliable_broadcast_enforced: bool,
+    echo_broadcast_enforced: bool,
     compute_multiexp_table: bool,
     compute_crt: bool,
-) -> Result<AuxInfo<L>, KeyRefreshError>
+    ring_pedersen_params: Option<RingPedersenParams>,
+    thread_pool: Option<&utils::ThreadPool>,
+) -> Result<(AuxInfo<L>, AuxProofs, Transcript<AuxGenRounds<L, D>>), KeyRefreshError>
 where
     R: RngCore + CryptoRng,
     M: Mpc<ProtocolMessage = Msg<D, L>>,
     L: SecurityLevel,
-    D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+    D: Digest + Clone + Sync + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
 {
     tracer.protocol_begins();
 
@@ -144,8 +177,9 @@ where
     let round1 = rounds.add_round(RoundInput::<MsgRound1<D>>::broadcast(i, n));
     let round1_sync = rounds.add_round(RoundInput::<MsgReliabilityCheck<D>>::broadcast(i, n));
     let round2 = rounds.add_round(RoundInput::<MsgRound2<L>>::broadcast(i, n));
+    let round2_echo = rounds.add_round(RoundInput::<MsgEcho<D, 2>>::broadcast(i, n));
     let round3 = rounds.add_round(RoundInput::<MsgRound3>::p2p(i, n));
-    let mut rounds = rounds.listen(incomings);
+    let mut rounds = rounds.listen(abort::intercept(incomings));
 
     tracer.stage("Precompute execution id and shared state");
     let sid = execution_id.as_bytes();
@@ -156,7 +190,7 @@ where
         })
     };
     let tag_i = tag(i);
-    let parties_shared_state = D::new_with_prefix(D::digest(sid));
+    let parties_shared_state = utils::Truncated::new(D::new_with_prefix(D::digest(sid)));
 
     // Round 1
     tracer.round_begins();
@@ -168,12 +202,15 @@ where
     let phi_N = (&p - 1u8).complete() * (&q - 1u8).complete();
 
     tracer.stage("Generate auxiliary params r, λ, t, s");
-    let r = Integer::gen_invertible(&N, rng);
-    let lambda = phi_N
-        .random_below_ref(&mut utils::external_rand(rng))
-        .into();
-    let t = r.square().modulo(&N);
-    let s = t.pow_mod_ref(&lambda, &N).ok_or(Bug::PowMod)?.into();
+    let RingPedersenParams { s, t, lambda } = match ring_pedersen_params {
+        Some(params) => {
+            params
+                .validate(&p, &q)
+                .map_err(|err| KeyRefreshError(Reason::InvalidRingPedersenParams(err)))?;
+            params
+        }
+        None => RingPedersenParams::generate(rng, &p, &q).map_err(|_| Bug::PowMod)?,
+    };
 
     tracer.stage("Prove Πprm (ψˆ_i)");
     let hat_psi = π_prm::prove(
@@ -228,12 +265,12 @@ where
         .complete(round1)
         .await
         .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
+    tracer.msgs_received_count(usize::from(n) - 1);
 
     // Optional reliability check
     if reliable_broadcast_enforced {
         tracer.stage("Hash received msgs (reliability check)");
-        let h_i = udigest::Tag::<D>::new_structured(Tag::Unindexed { sid })
+        let h_i = udigest::Tag::<H>::new_structured(Tag::Unindexed { sid })
             .digest_iter(commitments.iter_including_me(&commitment));
 
         tracer.send_msg();
@@ -252,16 +289,16 @@ where
             .complete(round1_sync)
             .await
             .map_err(IoError::receive_message)?;
-        tracer.msgs_received();
+        tracer.msgs_received_count(usize::from(n) - 1);
 
         tracer.stage("Assert other parties hashed messages (reliability check)");
-        let parties_have_different_hashes = hashes
+        let blame = hashes
             .into_iter_indexed()
             .filter(|(_j, _msg_id, h_j)| h_i != h_j.0)
             .map(|(j, msg_id, _)| AbortBlame::new(j, msg_id, msg_id))
             .collect::<Vec<_>>();
-        if !parties_have_different_hashes.is_empty() {
-            return Err(ProtocolAborted::round1_not_reliable(parties_have_different_hashes).into());
+        if !blame.is_empty() {
+            return Err(ProtocolAborted::broadcast_not_reliable(1, blame).into());
         }
     }
 
@@ -280,7 +317,42 @@ where
         .complete(round2)
         .await
         .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
+    tracer.msgs_received_count(usize::from(n) - 1);
+
+    // Optional echo broadcast
+    if echo_broadcast_enforced {
+        tracer.stage("Hash received msgs (echo broadcast)");
+        let echo = MsgEcho::<D, 2>::new_with_digest::<H>(
+            sid,
+            decommitments.iter_including_me(&decommitment),
+        );
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round2Echo(echo.clone())))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.round_begins();
+
+        tracer.receive_msgs();
+        let echoes = rounds
+            .complete(round2_echo)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received_count(usize::from(n) - 1);
+
+        tracer.stage("Assert other parties echoed the same msgs (echo broadcast)");
+        let blame = echo
+            .find_mismatched(echoes)
+            .into_iter()
+            .map(|(j, msg_id)| AbortBlame::new(j, msg_id, msg_id))
+            .collect::<Vec<_>>();
+        if !blame.is_empty() {
+            return Err(ProtocolAborted::broadcast_not_reliable(2, blame).into());
+        }
+    }
 
     // validate decommitments
     tracer.stage("Validate round 1 decommitments");
@@ -292,7 +364,7 @@ where
     }
     // validate parameters and param_proofs
     tracer.stage("Validate П_prm (ψ_i)");
-    let blame = collect_blame(&decommitments, &decommitments, |j, d, _| {
+    let blame = par_collect_blame(thread_pool, &decommitments, &decommitments, |j, d, _| {
         if !crate::security_level::validate_public_paillier_key_size::<L>(&d.N) {
             true
         } else {
@@ -312,6 +384,17 @@ where
     if !blame.is_empty() {
         return Err(ProtocolAborted::invalid_ring_pedersen_parameters(blame).into());
     }
+    tracer.stage("Check that Paillier moduli are not related");
+    let related =
+        utils::related_moduli(decommitments.iter_including_me(&decommitment).map(|d| &d.N));
+    let blame = decommitments
+        .iter_indexed()
+        .filter(|(j, _, _)| related.contains(&usize::from(*j)))
+        .map(|(j, msg_id, _)| AbortBlame::new(j, msg_id, msg_id))
+        .collect::<Vec<_>>();
+    if !blame.is_empty() {
+        return Err(ProtocolAborted::related_paillier_moduli(blame).into());
+    }
 
     tracer.stage("Add together shared random bytes");
     // rho in paper, collective random bytes
@@ -344,11 +427,14 @@ where
     };
     let n_sqrt = utils::sqrt(&N);
 
-    // message to each party
-    for (j, _, d) in decommitments.iter_indexed() {
-        tracer.send_msg();
-
-        tracer.stage("Compute П_fac (ф_i^j)");
+    // Per-peer proofs are independent, so they're computed in parallel if `parallel` feature is
+    // enabled. Each peer gets its own rng seeded from the main one.
+    tracer.stage("Compute П_fac (ф_i^j)");
+    let peers = decommitments
+        .iter_indexed()
+        .map(|(j, _, d)| (j, d, utils::fork_rng(rng)))
+        .collect::<Vec<_>>();
+    let phis = utils::par_map(thread_pool, peers, |(j, d, mut rng)| {
         let phi = π_fac::prove(
             my_shared_state.clone(),
             &π_fac::Aux {
@@ -367,11 +453,18 @@ where
             &mut rng,
         )
         .map_err(Bug::PiFac)?;
+        Ok::<_, Bug>((j, phi))
+    });
+
+    // message to each party
+    for phi in phis {
+        let (j, phi) = phi?;
+        tracer.process_peer(j);
 
         tracer.send_msg();
         let msg = MsgRound3 {
             mod_proof: psi.clone(),
-            fac_proof: phi.clone(),
+            fac_proof: phi,
         };
         outgoings
             .send(Outgoing::p2p(j, Msg::Round3(msg)))
@@ -388,11 +481,12 @@ where
         .complete(round3)
         .await
         .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
+    tracer.msgs_received_count(usize::from(n) - 1);
 
     tracer.stage("Validate ψ_j (П_mod)");
     // verify mod proofs
-    let blame = collect_blame(
+    let blame = par_collect_blame(
+        thread_pool,
         &decommitments,
         &shares_msg_b,
         |j, decommitment, proof_msg| {
@@ -432,7 +526,8 @@ where
         multiexp: None,
         crt: crt.clone(),
     };
-    let blame = collect_blame(
+    let blame = par_collect_blame(
+        thread_pool,
         &decommitments,
         &shares_msg_b,
         |j, decommitment, proof_msg| {
@@ -474,7 +569,9 @@ where
         p,
         q,
         parties: party_auxes,
+        key_binding: None,
         security_level: std::marker::PhantomData,
+        decryption_key_cache: Default::default(),
     };
 
     if compute_multiexp_table {
@@ -488,6 +585,41 @@ where
         .validate()
         .map_err(|err| Bug::InvalidShareGenerated(err.into_error()))?;
 
+    tracer.stage("Collect proofs");
+    tracer.stage("Record transcript");
+    let transcript = Transcript {
+        eid: sid.to_vec(),
+        i,
+        rounds: AuxGenRounds {
+            round1: commitments
+                .iter_including_me(&commitment)
+                .cloned()
+                .collect(),
+            round2: decommitments
+                .iter_including_me(&decommitment)
+                .cloned()
+                .collect(),
+        },
+    };
+
+    let mut parties_proofs = vec![None; usize::from(n)];
+    for ((j, _, decommitment), (_, _, proof_msg)) in decommitments
+        .into_iter_indexed()
+        .zip(shares_msg_b.into_iter_indexed())
+    {
+        parties_proofs[usize::from(j)] = Some(PartyAuxProofs {
+            params_proof: decommitment.params_proof,
+            mod_proof: proof_msg.mod_proof,
+            fac_proof: proof_msg.fac_proof,
+        });
+    }
+    let proofs = AuxProofs {
+        sid: sid.to_vec(),
+        i,
+        rho: rho_bytes.as_ref().to_vec(),
+        parties: parties_proofs,
+    };
+
     tracer.protocol_ends();
-    Ok(aux)
+    Ok((aux, proofs, transcript))
 }
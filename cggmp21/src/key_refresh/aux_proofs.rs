@@ -0,0 +1,174 @@
+use digest::Digest;
+use paillier_zk::{no_small_factor::non_interactive as π_fac, paillier_blum_modulus as π_mod};
+use round_based::PartyIndex;
+use thiserror::Error;
+
+use crate::{
+    key_share::DirtyAuxInfo, security_level::SecurityLevel, utils,
+    zk::ring_pedersen_parameters as π_prm,
+};
+
+/// Proofs of validity of aux data received during [aux info generation](super::AuxInfoGenerationBuilder)
+///
+/// Obtained via [`AuxInfoGenerationBuilder::start_with_proofs`](super::AuxInfoGenerationBuilder::start_with_proofs).
+/// Proofs can be persisted along with aux info and re-verified later via
+/// [`verify_aux_proofs`], e.g. to audit that stored aux info is still consistent.
+///
+/// Proofs are not secret, but they're only meaningful to the party who received them: П_fac
+/// proofs are bound to ring-Pedersen parameters of the party.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AuxProofs {
+    /// Execution ID of the protocol run
+    #[cfg_attr(feature = "serde", serde(with = "hex"))]
+    pub sid: Vec<u8>,
+    /// Index of the party who received the proofs
+    pub i: PartyIndex,
+    /// Collective random bytes $\rho$
+    #[cfg_attr(feature = "serde", serde(with = "hex"))]
+    pub rho: Vec<u8>,
+    /// Proofs of each party
+    ///
+    /// `parties[j]` corresponds to [`DirtyAuxInfo::parties`]`[j]`. Party doesn't receive proofs
+    /// from itself, so `parties[i]` is `None`.
+    pub parties: Vec<Option<PartyAuxProofs>>,
+}
+
+/// Proofs of validity of one party aux data
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartyAuxProofs {
+    /// $\hat \psi_j$, proves that ring-Pedersen parameters are well-formed
+    pub params_proof: π_prm::Proof<{ crate::security_level::M }>,
+    /// $\psi_j$, proves that $N_j$ is a Paillier-Blum modulus
+    pub mod_proof: (
+        π_mod::Commitment,
+        π_mod::Proof<{ crate::security_level::M }>,
+    ),
+    /// $\phi_j^i$, proves that $N_j$ has no small factors
+    pub fac_proof: π_fac::Proof,
+}
+
+/// Re-verifies proofs of aux data outside of protocol execution
+///
+/// Checks that `proofs` are valid for the public aux data of all other parties stored in `aux`.
+/// `D` must be the same hash function that was used in aux info generation.
+pub fn verify_aux_proofs<L, D>(
+    aux: &DirtyAuxInfo<L>,
+    proofs: &AuxProofs,
+) -> Result<(), InvalidAuxProofs>
+where
+    L: SecurityLevel,
+    D: Digest + Clone,
+{
+    let i = usize::from(proofs.i);
+    let aux_i = aux.parties.get(i).ok_or(Reason::IndexOutOfBounds)?;
+    if proofs.parties.len() != aux.parties.len() {
+        return Err(Reason::LengthMismatch.into());
+    }
+    if aux.p.clone() * &aux.q != aux_i.N {
+        return Err(Reason::NotOwnAux.into());
+    }
+
+    let parties_shared_state = utils::Truncated::new(D::new_with_prefix(D::digest(&proofs.sid)));
+    let fac_security = π_fac::SecurityParams {
+        l: L::ELL,
+        epsilon: L::EPSILON,
+        q: L::q(),
+    };
+    let fac_aux = π_fac::Aux {
+        s: aux_i.s.clone(),
+        t: aux_i.t.clone(),
+        rsa_modulo: aux_i.N.clone(),
+        multiexp: None,
+        crt: None,
+    };
+
+    let mut faulty = vec![];
+    for ((j, aux_j), proofs_j) in (0u16..).zip(&aux.parties).zip(&proofs.parties) {
+        if usize::from(j) == i {
+            continue;
+        }
+        let Some(proofs_j) = proofs_j else {
+            faulty.push(j);
+            continue;
+        };
+
+        let shared_state_j = parties_shared_state.clone().chain_update(j.to_be_bytes());
+        let shared_state_rho_j = shared_state_j.clone().chain_update(&proofs.rho);
+        let prm_valid = crate::security_level::validate_public_paillier_key_size::<L>(&aux_j.N)
+            && π_prm::verify(
+                shared_state_j,
+                π_prm::Data {
+                    N: &aux_j.N,
+                    s: &aux_j.s,
+                    t: &aux_j.t,
+                },
+                &proofs_j.params_proof,
+            )
+            .is_ok();
+        let mod_valid = prm_valid
+            && π_mod::non_interactive::verify(
+                shared_state_rho_j.clone(),
+                &π_mod::Data { n: aux_j.N.clone() },
+                &proofs_j.mod_proof.0,
+                &proofs_j.mod_proof.1,
+            )
+            .is_ok();
+        let fac_valid = mod_valid
+            && π_fac::verify(
+                shared_state_rho_j,
+                &fac_aux,
+                π_fac::Data {
+                    n: &aux_j.N,
+                    n_root: &utils::sqrt(&aux_j.N),
+                },
+                &fac_security,
+                &proofs_j.fac_proof,
+            )
+            .is_ok();
+        if !fac_valid {
+            faulty.push(j);
+        }
+    }
+
+    if !faulty.is_empty() {
+        return Err(Reason::InvalidProofs(faulty).into());
+    }
+    Ok(())
+}
+
+/// Error indicating that [`AuxProofs`] are not valid
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct InvalidAuxProofs(Reason);
+
+impl InvalidAuxProofs {
+    /// Returns indexes of parties whose aux data is not backed by valid proofs
+    ///
+    /// Returns empty slice if proofs don't correspond to the aux info at all.
+    pub fn blame(&self) -> &[PartyIndex] {
+        match &self.0 {
+            Reason::InvalidProofs(parties) => parties,
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("index of the party is out of bounds")]
+    IndexOutOfBounds,
+    #[error("amount of proofs doesn't match amount of parties")]
+    LengthMismatch,
+    #[error("proofs were received by another party")]
+    NotOwnAux,
+    #[error("proofs of parties {0:?} are invalid")]
+    InvalidProofs(Vec<PartyIndex>),
+}
+
+crate::errors::impl_from! {
+    impl From for InvalidAuxProofs {
+        err: Reason => InvalidAuxProofs(err),
+    }
+}
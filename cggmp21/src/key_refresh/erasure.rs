@@ -0,0 +1,258 @@
+use digest::Digest;
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use generic_ec_zkp::schnorr_pok;
+use rand_core::{CryptoRng, RngCore};
+use round_based::PartyIndex;
+use thiserror::Error;
+
+use crate::{
+    key_share::{AnyKeyShare, DirtyKeyInfo},
+    ExecutionId,
+};
+
+/// Statement of the party that it erased key share preceding the refresh
+#[derive(Debug, Clone, PartialEq, Eq, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+#[udigest(bound = "")]
+#[udigest(tag = "dfns.cggmp21.key_refresh.erasure")]
+pub struct ErasureStatement<E: Curve> {
+    /// Execution ID of the key refresh
+    #[cfg_attr(feature = "serde", serde(with = "hex"))]
+    #[udigest(as_bytes)]
+    pub eid: Vec<u8>,
+    /// Index of the party
+    pub i: PartyIndex,
+    /// Public key shared by signers
+    pub shared_public_key: Point<E>,
+    /// Public share of the party before the refresh
+    pub old_public_share: Point<E>,
+    /// Public share of the party after the refresh
+    pub new_public_share: Point<E>,
+}
+
+/// Attestation that party erased its key share after the refresh
+///
+/// Produced by [`attest_erasure`], collected and checked by [`verify_erasure_attestations`].
+///
+/// Attestation is a signed statement, not a cryptographic proof: nothing can prove that data has
+/// been destroyed. It's bound to the refresh execution ID and to the new key share via proof of
+/// knowledge of the new secret share, so only the party holding the new share can produce it, and
+/// it can't be reused for another refresh. This makes the party accountable for its claim, which
+/// is what custodial compliance procedures typically need.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct ErasureAttestation<E: Curve> {
+    /// Statement
+    pub statement: ErasureStatement<E>,
+    /// Commitment of the proof of knowledge of the new secret share
+    pub commit: schnorr_pok::Commit<E>,
+    /// Proof of knowledge of the new secret share
+    pub proof: schnorr_pok::Proof<E>,
+}
+
+#[derive(udigest::Digestable)]
+#[udigest(bound = "")]
+struct ChallengeInput<'a, E: Curve> {
+    statement: &'a ErasureStatement<E>,
+    commit: &'a schnorr_pok::Commit<E>,
+}
+
+/// Erases key share preceding the refresh and attests it
+///
+/// Takes ownership of the old key share and drops it, which zeroizes the secret share in memory.
+/// Any other copies of the old key share (e.g. in the persistent storage) must be destroyed by the
+/// caller before publishing the attestation.
+///
+/// `eid` must be the execution ID of the key refresh that produced `new_key_share`.
+pub fn attest_erasure<E, D, R>(
+    rng: &mut R,
+    eid: ExecutionId<'_>,
+    old_key_share: impl AnyKeyShare<E>,
+    new_key_share: &impl AnyKeyShare<E>,
+) -> Result<ErasureAttestation<E>, ErasureError>
+where
+    E: Curve,
+    D: Digest,
+    R: RngCore + CryptoRng,
+{
+    let old = old_key_share.as_ref();
+    let new = new_key_share.as_ref();
+    if old.i != new.i || old.shared_public_key != new.shared_public_key {
+        return Err(Reason::DifferentKeys.into());
+    }
+    let statement = ErasureStatement {
+        eid: eid.as_bytes().to_vec(),
+        i: new.i,
+        shared_public_key: *new.shared_public_key,
+        old_public_share: *old.public_shares[usize::from(old.i)],
+        new_public_share: *new.public_shares[usize::from(new.i)],
+    };
+    if statement.old_public_share == statement.new_public_share {
+        return Err(Reason::ShareNotRefreshed.into());
+    }
+    drop(old_key_share);
+
+    let (secret, commit) = schnorr_pok::prover_commits_ephemeral_secret::<E, _>(rng);
+    let challenge = challenge::<E, D>(&statement, &commit);
+    let x: &SecretScalar<E> = new.x.as_ref();
+    let proof = schnorr_pok::prove(&secret, &challenge, x);
+
+    Ok(ErasureAttestation {
+        statement,
+        commit,
+        proof,
+    })
+}
+
+/// Checks that every party attested erasure of its key share preceding the refresh
+///
+/// `old_key` and `new_key` are public key info before and after the refresh (key shares can be
+/// passed directly). `D` must be the same hash function that was used in [`attest_erasure`].
+/// Attestations may come in any order, but there must be exactly one attestation per party.
+pub fn verify_erasure_attestations<E, D>(
+    eid: ExecutionId<'_>,
+    old_key: &DirtyKeyInfo<E>,
+    new_key: &DirtyKeyInfo<E>,
+    attestations: &[ErasureAttestation<E>],
+) -> Result<(), InvalidErasureAttestations>
+where
+    E: Curve,
+    D: Digest,
+{
+    if old_key.shared_public_key != new_key.shared_public_key
+        || old_key.public_shares.len() != new_key.public_shares.len()
+    {
+        return Err(InvalidReason::DifferentKeys.into());
+    }
+
+    let mut attested = vec![false; new_key.public_shares.len()];
+    let mut faulty = vec![];
+    for attestation in attestations {
+        let i = attestation.statement.i;
+        let Some(attested_i) = attested.get_mut(usize::from(i)) else {
+            return Err(InvalidReason::IndexOutOfBounds(i).into());
+        };
+        if *attested_i {
+            return Err(InvalidReason::Duplicate(i).into());
+        }
+        *attested_i = true;
+
+        let is_valid = attestation
+            .verify::<D>(
+                eid,
+                &old_key.shared_public_key,
+                &old_key.public_shares[usize::from(i)],
+                &new_key.public_shares[usize::from(i)],
+            )
+            .is_ok();
+        if !is_valid {
+            faulty.push(i);
+        }
+    }
+    faulty.extend(
+        (0u16..)
+            .zip(&attested)
+            .filter(|(_, attested)| !**attested)
+            .map(|(j, _)| j),
+    );
+
+    if !faulty.is_empty() {
+        faulty.sort_unstable();
+        return Err(InvalidReason::Faulty(faulty).into());
+    }
+    Ok(())
+}
+
+impl<E: Curve> ErasureAttestation<E> {
+    /// Verifies attestation of a single party
+    ///
+    /// Checks that the statement matches provided public data, and that the proof is valid.
+    pub fn verify<D: Digest>(
+        &self,
+        eid: ExecutionId<'_>,
+        shared_public_key: &NonZero<Point<E>>,
+        old_public_share: &NonZero<Point<E>>,
+        new_public_share: &NonZero<Point<E>>,
+    ) -> Result<(), InvalidErasureAttestations> {
+        let statement = &self.statement;
+        if statement.eid != eid.as_bytes()
+            || statement.shared_public_key != **shared_public_key
+            || statement.old_public_share != **old_public_share
+            || statement.new_public_share != **new_public_share
+        {
+            return Err(InvalidReason::Faulty(vec![statement.i]).into());
+        }
+        let challenge = challenge::<E, D>(statement, &self.commit);
+        self.proof
+            .verify(&self.commit, &challenge, &statement.new_public_share)
+            .map_err(|_| InvalidReason::Faulty(vec![statement.i]))?;
+        Ok(())
+    }
+}
+
+fn challenge<E: Curve, D: Digest>(
+    statement: &ErasureStatement<E>,
+    commit: &schnorr_pok::Commit<E>,
+) -> schnorr_pok::Challenge<E> {
+    let hash = udigest::Tag::<D>::new("dfns.cggmp21.key_refresh.erasure")
+        .digest(ChallengeInput { statement, commit });
+    schnorr_pok::Challenge {
+        nonce: Scalar::from_be_bytes_mod_order(hash),
+    }
+}
+
+/// Error indicating that erasure couldn't be attested
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct ErasureError(Reason);
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("old and new key shares belong to different keys or parties")]
+    DifferentKeys,
+    #[error("public share of the party hasn't changed, old key share is still in use")]
+    ShareNotRefreshed,
+}
+
+crate::errors::impl_from! {
+    impl From for ErasureError {
+        err: Reason => ErasureError(err),
+    }
+}
+
+/// Error indicating that erasure attestations are not valid
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct InvalidErasureAttestations(InvalidReason);
+
+impl InvalidErasureAttestations {
+    /// Returns indexes of parties whose attestation is missing or invalid
+    ///
+    /// Returns empty slice if attestations can't be attributed to parties.
+    pub fn blame(&self) -> &[PartyIndex] {
+        match &self.0 {
+            InvalidReason::Faulty(parties) => parties,
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+enum InvalidReason {
+    #[error("old and new public key info correspond to different keys")]
+    DifferentKeys,
+    #[error("party index {0} is out of bounds")]
+    IndexOutOfBounds(PartyIndex),
+    #[error("party {0} provided more than one attestation")]
+    Duplicate(PartyIndex),
+    #[error("attestations of parties {0:?} are missing or invalid")]
+    Faulty(Vec<PartyIndex>),
+}
+
+crate::errors::impl_from! {
+    impl From for InvalidErasureAttestations {
+        err: InvalidReason => InvalidErasureAttestations(err),
+    }
+}
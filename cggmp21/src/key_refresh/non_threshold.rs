@@ -15,28 +15,34 @@ use round_based::{
     rounds_router::{simple_store::RoundInput, RoundsRouter},
     Delivery, Mpc, MpcParty, Outgoing,
 };
-use serde::{Deserialize, Serialize};
 
-use super::{Bug, KeyRefreshError, PregeneratedPrimes, ProtocolAborted};
+use cggmp21_keygen::abort;
+pub use cggmp21_keygen::{abort::MsgAbort, echo_broadcast::MsgEcho};
+
+use super::{
+    Bug, KeyRefreshError, PregeneratedPrimes, ProtocolAborted, Reason, RingPedersenParams,
+};
 use crate::{
     errors::IoError,
     key_share::{
         DirtyAuxInfo, DirtyIncompleteKeyShare, DirtyKeyInfo, KeyShare, PartyAux, Validate,
     },
+    policy::KeyUsagePolicy,
     progress::Tracer,
     security_level::SecurityLevel,
     utils,
     utils::{
-        but_nth, collect_blame, collect_simple_blame, iter_peers, scalar_to_bignumber, xor_array,
-        AbortBlame,
+        but_nth, collect_blame, collect_simple_blame, iter_peers, par_collect_blame,
+        scalar_to_bignumber, xor_array, AbortBlame,
     },
     zk::ring_pedersen_parameters as π_prm,
     ExecutionId, IncompleteKeyShare,
 };
 
 /// Message of key refresh protocol
-#[derive(ProtocolMessage, Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(ProtocolMessage, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 // 3 kilobytes for the largest option, and 2.5 kilobytes for second largest
 #[allow(clippy::large_enum_variant)]
 pub enum Msg<E: Curve, D: Digest, L: SecurityLevel> {
@@ -48,23 +54,29 @@ pub enum Msg<E: Curve, D: Digest, L: SecurityLevel> {
     Round3(MsgRound3<E>),
     /// Reliability check message (optional additional round)
     ReliabilityCheck(MsgReliabilityCheck<D>),
+    /// Echo of round 2 messages (optional additional round)
+    Round2Echo(MsgEcho<D, 2>),
+    /// Party aborts the protocol (not part of any round)
+    Abort(MsgAbort),
 }
 
 /// Message from round 1
-#[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[udigest(tag = "dfns.cggmp21.full_key_refresh.non_threshold.round1")]
 #[udigest(bound = "")]
-#[serde(bound = "")]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct MsgRound1<D: Digest> {
     /// $V_i$
     #[udigest(as_bytes)]
     pub commitment: digest::Output<D>,
 }
 /// Message from round 2
-#[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[udigest(tag = "dfns.cggmp21.full_key_refresh.non_threshold.round2")]
 #[udigest(bound = "")]
-#[serde(bound = "")]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct MsgRound2<E: Curve, L: SecurityLevel> {
     /// $\vec X_i$
     pub Xs: Vec<Point<E>>,
@@ -84,17 +96,18 @@ pub struct MsgRound2<E: Curve, L: SecurityLevel> {
     pub params_proof: π_prm::Proof<{ crate::security_level::M }>,
     /// $\rho_i$
     // ideally it would be [u8; L::SECURITY_BYTES], but no rustc support yet
-    #[serde(with = "hex")]
+    #[cfg_attr(feature = "serde", serde(with = "hex"))]
     #[udigest(as_bytes)]
     pub rho_bytes: L::Rid,
     /// $u_i$
-    #[serde(with = "hex")]
+    #[cfg_attr(feature = "serde", serde(with = "hex"))]
     #[udigest(as_bytes)]
     pub decommit: L::Rid,
 }
 /// Unicast message of round 3, sent to each participant
-#[derive(Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct MsgRound3<E: Curve> {
     /// $\psi_i$
     // this should be L::M instead, but no rustc support yet
@@ -115,8 +128,9 @@ pub struct MsgRound3<E: Curve> {
 }
 
 /// Message of optional round that enforces reliability check
-#[derive(Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct MsgReliabilityCheck<D: Digest>(pub digest::Output<D>);
 
 #[derive(udigest::Digestable)]
@@ -135,23 +149,28 @@ enum Tag<'a> {
     },
 }
 
-pub async fn run_refresh<R, M, E, L, D>(
+pub async fn run_refresh<R, M, E, L, D, H>(
     mut rng: &mut R,
     party: M,
     execution_id: ExecutionId<'_>,
     pregenerated: PregeneratedPrimes<L>,
     mut tracer: Option<&mut dyn Tracer>,
     reliable_broadcast_enforced: bool,
+    echo_broadcast_enforced: bool,
     build_multiexp_tables: bool,
     build_crt: bool,
+    ring_pedersen_params: Option<RingPedersenParams>,
     core_share: &DirtyIncompleteKeyShare<E>,
-) -> Result<KeyShare<E, L>, KeyRefreshError>
+    policy: Option<&KeyUsagePolicy>,
+    thread_pool: Option<&utils::ThreadPool>,
+) -> Result<(KeyShare<E, L>, L::Rid), KeyRefreshError>
 where
     R: RngCore + CryptoRng,
     M: Mpc<ProtocolMessage = Msg<E, D, L>>,
     E: Curve,
     L: SecurityLevel,
-    D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+    D: Digest + Clone + Sync + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
 {
     tracer.protocol_begins();
 
@@ -167,8 +186,9 @@ where
     let round1 = rounds.add_round(RoundInput::<MsgRound1<D>>::broadcast(i, n));
     let round1_sync = rounds.add_round(RoundInput::<MsgReliabilityCheck<D>>::broadcast(i, n));
     let round2 = rounds.add_round(RoundInput::<MsgRound2<E, L>>::broadcast(i, n));
+    let round2_echo = rounds.add_round(RoundInput::<MsgEcho<D, 2>>::broadcast(i, n));
     let round3 = rounds.add_round(RoundInput::<MsgRound3<E>>::p2p(i, n));
-    let mut rounds = rounds.listen(incomings);
+    let mut rounds = rounds.listen(abort::intercept(incomings));
 
     tracer.stage("Precompute execution id and shared state");
     let sid = execution_id.as_bytes();
@@ -179,7 +199,7 @@ where
         })
     };
     let tag_i = tag(i);
-    let parties_shared_state = D::new_with_prefix(D::digest(sid));
+    let parties_shared_state = utils::Truncated::new(D::new_with_prefix(D::digest(sid)));
 
     // Round 1
     tracer.round_begins();
@@ -210,12 +230,15 @@ where
         .collect::<Vec<_>>();
 
     tracer.stage("Generate auxiliary params r, λ, t, s");
-    let r = Integer::gen_invertible(&N, rng);
-    let lambda = phi_N
-        .random_below_ref(&mut utils::external_rand(rng))
-        .into();
-    let t = r.square().modulo(&N);
-    let s = t.pow_mod_ref(&lambda, &N).ok_or(Bug::PowMod)?.into();
+    let RingPedersenParams { s, t, lambda } = match ring_pedersen_params {
+        Some(params) => {
+            params
+                .validate(&p, &q)
+                .map_err(|err| KeyRefreshError(Reason::InvalidRingPedersenParams(err)))?;
+            params
+        }
+        None => RingPedersenParams::generate(rng, &p, &q).map_err(|_| Bug::PowMod)?,
+    };
 
     tracer.stage("Prove Πprm (ψˆ_i)");
     let hat_psi = π_prm::prove(
@@ -278,12 +301,12 @@ where
         .complete(round1)
         .await
         .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
+    tracer.msgs_received_count(usize::from(n) - 1);
 
     // Optional reliability check
     if reliable_broadcast_enforced {
         tracer.stage("Hash received msgs (reliability check)");
-        let h_i = udigest::Tag::<D>::new_structured(Tag::Unindexed { sid })
+        let h_i = udigest::Tag::<H>::new_structured(Tag::Unindexed { sid })
             .digest_iter(commitments.iter_including_me(&commitment));
 
         tracer.send_msg();
@@ -302,16 +325,16 @@ where
             .complete(round1_sync)
             .await
             .map_err(IoError::receive_message)?;
-        tracer.msgs_received();
+        tracer.msgs_received_count(usize::from(n) - 1);
 
         tracer.stage("Assert other parties hashed messages (reliability check)");
-        let parties_have_different_hashes = hashes
+        let blame = hashes
             .into_iter_indexed()
             .filter(|(_j, _msg_id, h_j)| h_i != h_j.0)
             .map(|(j, msg_id, _)| AbortBlame::new(j, msg_id, msg_id))
             .collect::<Vec<_>>();
-        if !parties_have_different_hashes.is_empty() {
-            return Err(ProtocolAborted::round1_not_reliable(parties_have_different_hashes).into());
+        if !blame.is_empty() {
+            return Err(ProtocolAborted::broadcast_not_reliable(1, blame).into());
         }
     }
 
@@ -330,7 +353,42 @@ where
         .complete(round2)
         .await
         .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
+    tracer.msgs_received_count(usize::from(n) - 1);
+
+    // Optional echo broadcast
+    if echo_broadcast_enforced {
+        tracer.stage("Hash received msgs (echo broadcast)");
+        let echo = MsgEcho::<D, 2>::new_with_digest::<H>(
+            sid,
+            decommitments.iter_including_me(&decommitment),
+        );
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round2Echo(echo.clone())))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.round_begins();
+
+        tracer.receive_msgs();
+        let echoes = rounds
+            .complete(round2_echo)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received_count(usize::from(n) - 1);
+
+        tracer.stage("Assert other parties echoed the same msgs (echo broadcast)");
+        let blame = echo
+            .find_mismatched(echoes)
+            .into_iter()
+            .map(|(j, msg_id)| AbortBlame::new(j, msg_id, msg_id))
+            .collect::<Vec<_>>();
+        if !blame.is_empty() {
+            return Err(ProtocolAborted::broadcast_not_reliable(2, blame).into());
+        }
+    }
 
     // validate decommitments
     tracer.stage("Validate round 1 decommitments");
@@ -351,7 +409,7 @@ where
     }
     // validate parameters and param_proofs
     tracer.stage("Validate П_prm (ψ_i)");
-    let blame = collect_blame(&decommitments, &decommitments, |j, d, _| {
+    let blame = par_collect_blame(thread_pool, &decommitments, &decommitments, |j, d, _| {
         if !crate::security_level::validate_public_paillier_key_size::<L>(&d.N) {
             true
         } else {
@@ -371,6 +429,17 @@ where
     if !blame.is_empty() {
         return Err(ProtocolAborted::invalid_ring_pedersen_parameters(blame).into());
     }
+    tracer.stage("Check that Paillier moduli are not related");
+    let related =
+        utils::related_moduli(decommitments.iter_including_me(&decommitment).map(|d| &d.N));
+    let blame = decommitments
+        .iter_indexed()
+        .filter(|(j, _, _)| related.contains(&usize::from(*j)))
+        .map(|(j, msg_id, _)| AbortBlame::new(j, msg_id, msg_id))
+        .collect::<Vec<_>>();
+    if !blame.is_empty() {
+        return Err(ProtocolAborted::related_paillier_moduli(blame).into());
+    }
     // validate Xs add to zero
     tracer.stage("Validate X_i");
     let blame = collect_simple_blame(&decommitments, |d| {
@@ -434,20 +503,21 @@ where
         .zip(taus.iter())
         .map(|(x_j, secret_j)| schnorr_pok::prove(secret_j, &challenge, x_j))
         .collect::<Vec<_>>();
-    tracer.stage("Prepare auxiliary params and security level for proofs");
-    // message to each party
-    let iterator =
+    // Per-peer work is independent, so it's computed in parallel if `parallel` feature is
+    // enabled. Each peer gets its own rng seeded from the main one.
+    tracer.stage("Paillier encryption of x_i^j and П_fac (ф_i^j)");
+    let peers =
         // use every share except ours
         but_nth(i, xs.iter())
         .zip(&encs)
         .zip(decommitments.iter())
-        .zip(iter_peers(i, n));
-    for (((x, enc), d), j) in iterator {
-        tracer.stage("Paillier encryption of x_i^j");
+        .zip(iter_peers(i, n))
+        .map(|(((x, enc), d), j)| (j, x, enc, d, utils::fork_rng(rng)))
+        .collect::<Vec<_>>();
+    let round3_outputs = utils::par_map(thread_pool, peers, |(j, x, enc, d, mut rng)| {
         let (C, _) = enc
             .encrypt_with_random(&mut rng, &scalar_to_bignumber(x))
             .map_err(|_| Bug::PaillierEnc)?;
-        tracer.stage("Compute П_fac (ф_i^j)");
         let phi = π_fac::prove(
             my_shared_state.clone(),
             &π_fac::Aux {
@@ -466,11 +536,18 @@ where
             &mut rng,
         )
         .map_err(Bug::PiFac)?;
+        Ok::<_, Bug>((j, C, phi))
+    });
+
+    // message to each party
+    for output in round3_outputs {
+        let (j, C, phi) = output?;
+        tracer.process_peer(j);
 
         tracer.send_msg();
         let msg = MsgRound3 {
             mod_proof: psi.clone(),
-            fac_proof: phi.clone(),
+            fac_proof: phi,
             sch_proofs_x: psis.clone(),
             C,
         };
@@ -489,7 +566,16 @@ where
         .complete(round3)
         .await
         .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
+    tracer.msgs_received_count(usize::from(n) - 1);
+
+    tracer.stage("Validate structure of C_j^i");
+    let nn_i = dec.encryption_key().nn();
+    let blame = collect_simple_blame(&shares_msg_b, |m| {
+        !crate::limits::is_ciphertext_well_formed(&m.C, nn_i)
+    });
+    if !blame.is_empty() {
+        return Err(ProtocolAborted::paillier_dec(blame).into());
+    }
 
     tracer.stage("Paillier decrypt x_j^i from C_j^i");
     // x_j^i in paper. x_i^i is a share from self to self, so it was never sent,
@@ -572,7 +658,8 @@ where
 
     tracer.stage("Validate ψ_j (П_mod)");
     // verify mod proofs
-    let blame = collect_blame(
+    let blame = par_collect_blame(
+        thread_pool,
         &decommitments,
         &shares_msg_b,
         |j, decommitment, proof_msg| {
@@ -612,7 +699,8 @@ where
         multiexp: None,
         crt: crt.clone(),
     };
-    let blame = collect_blame(
+    let blame = par_collect_blame(
+        thread_pool,
         &decommitments,
         &shares_msg_b,
         |j, decommitment, proof_msg| {
@@ -685,7 +773,9 @@ where
         p,
         q,
         parties: party_auxes,
+        key_binding: None,
         security_level: std::marker::PhantomData,
+        decryption_key_cache: dec.into(),
     };
 
     if build_multiexp_tables {
@@ -700,9 +790,9 @@ where
         .map_err(|err| Bug::InvalidShareGenerated(err.into_error()))?;
 
     tracer.stage("Assemble key share");
-    let key_share = KeyShare::from_parts((new_core_share, aux))
+    let key_share = KeyShare::from_parts((new_core_share, aux, policy.cloned()))
         .map_err(|err| Bug::InvalidShareGenerated(err.into_error()))?;
 
     tracer.protocol_ends();
-    Ok(key_share)
+    Ok((key_share, rho_bytes))
 }
@@ -0,0 +1,178 @@
+use std::collections::VecDeque;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+
+use rand_core::{CryptoRng, RngCore};
+
+use super::{primes, PregeneratedPrimes};
+use crate::{rug::Integer, security_level::SecurityLevel};
+
+/// Pool of [`PregeneratedPrimes`] generated in the background
+///
+/// Generating primes takes tens of seconds. Pool keeps generating them on background threads until
+/// there are `depth` of them ready, and hands them out on demand, so aux info generation and key
+/// refresh don't need to wait for primes search.
+///
+/// Dropping the pool stops background generation.
+///
+/// ## Example
+/// ```rust,no_run
+/// use std::num::NonZeroUsize;
+/// use cggmp21::key_refresh::PrimesPool;
+///
+/// let pool = PrimesPool::<cggmp21::security_level::SecurityLevel128>::start(
+///     rand::rngs::OsRng,
+///     NonZeroUsize::new(4).unwrap(),
+///     NonZeroUsize::new(2).unwrap(),
+/// );
+/// // ...
+/// let pregenerated_primes = pool.take();
+/// # let _ = pregenerated_primes;
+/// ```
+pub struct PrimesPool<L: SecurityLevel = crate::default_choice::SecurityLevel> {
+    shared: Arc<Shared<L>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+struct Shared<L> {
+    depth: usize,
+    ready: Mutex<VecDeque<PregeneratedPrimes<L>>>,
+    /// Notified when primes are added to the pool
+    added: Condvar,
+    /// Notified when primes are taken from the pool
+    taken: Condvar,
+    stopped: AtomicBool,
+}
+
+impl<L: SecurityLevel> PrimesPool<L> {
+    /// Starts background generation
+    ///
+    /// Primes are generated on `threads` background threads, each of them uses its own rng
+    /// derived from `rng`. Generation is paused when there are `depth` primes ready.
+    pub fn start<R: RngCore + CryptoRng>(
+        mut rng: R,
+        depth: NonZeroUsize,
+        threads: NonZeroUsize,
+    ) -> Self {
+        let shared = Arc::new(Shared {
+            depth: depth.get(),
+            ready: Mutex::new(VecDeque::with_capacity(depth.get())),
+            added: Condvar::new(),
+            taken: Condvar::new(),
+            stopped: AtomicBool::new(false),
+        });
+        let workers = std::iter::repeat_with(|| crate::utils::fork_rng(&mut rng))
+            .take(threads.get())
+            .map(|rng| {
+                let shared = shared.clone();
+                std::thread::spawn(move || shared.work(rng))
+            })
+            .collect();
+        Self { shared, workers }
+    }
+
+    /// Takes primes from the pool
+    ///
+    /// Blocks until primes are available
+    pub fn take(&self) -> PregeneratedPrimes<L> {
+        let mut ready = self.shared.lock();
+        loop {
+            if let Some(primes) = ready.pop_front() {
+                self.shared.taken.notify_one();
+                return primes;
+            }
+            ready = self
+                .shared
+                .added
+                .wait(ready)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Takes primes from the pool if they're available
+    ///
+    /// Never blocks. Returns `None` if there are no primes ready.
+    pub fn try_take(&self) -> Option<PregeneratedPrimes<L>> {
+        let primes = self.shared.lock().pop_front()?;
+        self.shared.taken.notify_one();
+        Some(primes)
+    }
+
+    /// Returns amount of primes ready to be taken
+    pub fn ready(&self) -> usize {
+        self.shared.lock().len()
+    }
+
+    /// Returns maximum amount of primes the pool keeps ready
+    pub fn depth(&self) -> usize {
+        self.shared.depth
+    }
+}
+
+impl<L: SecurityLevel> Drop for PrimesPool<L> {
+    fn drop(&mut self) {
+        self.shared.stopped.store(true, Ordering::Relaxed);
+        // Taking the lock guarantees that workers waiting for space are notified
+        drop(self.shared.lock());
+        self.shared.taken.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl<L: SecurityLevel> Shared<L> {
+    fn lock(&self) -> MutexGuard<VecDeque<PregeneratedPrimes<L>>> {
+        self.ready.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    fn work(&self, mut rng: impl RngCore) {
+        let sieve = primes::sieve(primes::SIEVE_SIZE);
+        loop {
+            {
+                let mut ready = self.lock();
+                while ready.len() >= self.depth && !self.is_stopped() {
+                    ready = self.taken.wait(ready).unwrap_or_else(|e| e.into_inner());
+                }
+            }
+            let (Some(p), Some(q)) = (
+                self.generate_safe_prime(&mut rng, &sieve),
+                self.generate_safe_prime(&mut rng, &sieve),
+            ) else {
+                return;
+            };
+            let primes = PregeneratedPrimes {
+                p,
+                q,
+                kind: super::PrimesKind::Safe,
+                _phantom: std::marker::PhantomData,
+            };
+
+            let mut ready = self.lock();
+            if self.is_stopped() {
+                return;
+            }
+            // Other workers may have filled the pool in the meantime. We keep extra primes
+            // rather than throw away results of a long computation.
+            ready.push_back(primes);
+            self.added.notify_one();
+        }
+    }
+
+    /// Searches for a safe prime, returns `None` if the pool was stopped before it was found
+    fn generate_safe_prime(&self, rng: &mut impl RngCore, sieve: &[u32]) -> Option<Integer> {
+        let bits = 4 * L::SECURITY_BITS;
+        while !self.is_stopped() {
+            if let Some(prime) = primes::try_safe_prime(rng, bits, sieve) {
+                return Some(prime);
+            }
+        }
+        None
+    }
+}
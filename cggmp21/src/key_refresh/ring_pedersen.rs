@@ -0,0 +1,239 @@
+use digest::Digest;
+use paillier_zk::{
+    rug::{Complete, Integer},
+    IntegerExt,
+};
+use rand_core::{CryptoRng, RngCore};
+use round_based::PartyIndex;
+use thiserror::Error;
+
+use crate::{
+    key_share::{AuxInfo, InvalidKeyShare, Validate},
+    security_level::SecurityLevel,
+    utils,
+    zk::ring_pedersen_parameters as π_prm,
+    ExecutionId,
+};
+
+/// Ring-Pedersen parameters $s = t^\lambda \mod N$
+///
+/// Contains secret $\lambda$ which is needed to prove that parameters are well-formed.
+pub struct RingPedersenParams {
+    /// $s$
+    pub s: Integer,
+    /// $t$
+    pub t: Integer,
+    /// $\lambda$
+    pub lambda: Integer,
+}
+
+impl RingPedersenParams {
+    /// Samples ring-Pedersen parameters for modulus $N = pq$
+    pub fn generate<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        p: &Integer,
+        q: &Integer,
+    ) -> Result<Self, RingPedersenError> {
+        let N = (p * q).complete();
+        let phi_N = (p - 1u8).complete() * (q - 1u8).complete();
+
+        let r = Integer::gen_invertible(&N, rng);
+        let lambda = phi_N
+            .random_below_ref(&mut utils::external_rand(rng))
+            .into();
+        let t = r.square().modulo(&N);
+        let s = t.pow_mod_ref(&lambda, &N).ok_or(Reason::PowMod)?.into();
+        Ok(Self { s, t, lambda })
+    }
+
+    /// Checks that parameters are well-formed for modulus $N = pq$
+    ///
+    /// Parameters must satisfy $s = t^\lambda \mod N$ where $t$ is a quadratic residue
+    /// modulo $N$.
+    pub fn validate(&self, p: &Integer, q: &Integer) -> Result<(), RingPedersenError> {
+        let N = (p * q).complete();
+        let in_group =
+            |x: &Integer| x.cmp0().is_gt() && *x < N && x.legendre(p) == 1 && x.legendre(q) == 1;
+        if !in_group(&self.t) || !in_group(&self.s) {
+            return Err(Reason::NotQuadraticResidue.into());
+        }
+        let s = self.t.pow_mod_ref(&self.lambda, &N).map(Integer::from);
+        if self.lambda.cmp0().is_le() || s.as_ref() != Some(&self.s) {
+            return Err(Reason::InvalidLambda.into());
+        }
+        Ok(())
+    }
+
+    /// Proves that parameters are well-formed
+    ///
+    /// Proof is bound to execution ID `eid` and index `i` of the prover.
+    pub fn prove<D, R>(
+        &self,
+        rng: &mut R,
+        eid: ExecutionId<'_>,
+        i: PartyIndex,
+        p: &Integer,
+        q: &Integer,
+    ) -> Result<π_prm::Proof<{ crate::security_level::M }>, RingPedersenError>
+    where
+        D: Digest,
+        R: RngCore + CryptoRng,
+    {
+        let N = (p * q).complete();
+        let phi_N = (p - 1u8).complete() * (q - 1u8).complete();
+        π_prm::prove(
+            shared_state::<D>(eid, i),
+            rng,
+            π_prm::Data {
+                N: &N,
+                s: &self.s,
+                t: &self.t,
+            },
+            &phi_N,
+            &self.lambda,
+        )
+        .map_err(|err| Reason::Prove(err).into())
+    }
+}
+
+/// New ring-Pedersen parameters of the party along with proof of their validity
+///
+/// Produced by [`regenerate_ring_pedersen_params`], and needs to be sent to all other parties,
+/// who apply it via [`apply_ring_pedersen_update`].
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RingPedersenUpdate {
+    /// Index of the party who regenerated parameters
+    pub i: PartyIndex,
+    /// $s$
+    pub s: Integer,
+    /// $t$
+    pub t: Integer,
+    /// $\hat \psi$, proves that parameters are well-formed
+    pub proof: π_prm::Proof<{ crate::security_level::M }>,
+}
+
+/// Regenerates ring-Pedersen parameters of the local party
+///
+/// Samples fresh parameters for the Paillier modulus of the local party and proves that they're
+/// well-formed. Returns updated aux info and an update that needs to be sent to other parties.
+/// Allows repairing ring-Pedersen parameters without running full aux info generation.
+///
+/// `eid` must be unique for every regeneration, and other parties need to know it to verify the
+/// update. Precomputed multiexponentiation table of local party is dropped as it doesn't match
+/// new parameters.
+pub fn regenerate_ring_pedersen_params<L, D, R>(
+    rng: &mut R,
+    eid: ExecutionId<'_>,
+    aux: &AuxInfo<L>,
+) -> Result<(AuxInfo<L>, RingPedersenUpdate), RingPedersenError>
+where
+    L: SecurityLevel,
+    D: Digest,
+    R: RngCore + CryptoRng,
+{
+    let N = (&aux.p * &aux.q).complete();
+    let i = aux
+        .parties
+        .iter()
+        .position(|party| party.N == N)
+        .ok_or(Reason::OwnAuxNotFound)?;
+    let i = PartyIndex::try_from(i).map_err(|_| Reason::OwnAuxNotFound)?;
+
+    let params = RingPedersenParams::generate(rng, &aux.p, &aux.q)?;
+    let proof = params.prove::<D, _>(rng, eid, i, &aux.p, &aux.q)?;
+
+    let mut new_aux = aux.clone().into_inner();
+    let party = &mut new_aux.parties[usize::from(i)];
+    party.s = params.s.clone();
+    party.t = params.t.clone();
+    party.multiexp = None;
+    let new_aux = new_aux
+        .validate()
+        .map_err(|err| Reason::InvalidAux(err.into_error()))?;
+
+    Ok((
+        new_aux,
+        RingPedersenUpdate {
+            i,
+            s: params.s,
+            t: params.t,
+            proof,
+        },
+    ))
+}
+
+/// Verifies ring-Pedersen parameters update received from another party and applies it
+///
+/// Returns updated aux info, or error if the proof is invalid. `eid` must match the one used by
+/// the party in [`regenerate_ring_pedersen_params`].
+pub fn apply_ring_pedersen_update<L, D>(
+    eid: ExecutionId<'_>,
+    aux: &AuxInfo<L>,
+    update: &RingPedersenUpdate,
+) -> Result<AuxInfo<L>, RingPedersenError>
+where
+    L: SecurityLevel,
+    D: Digest,
+{
+    let party = aux
+        .parties
+        .get(usize::from(update.i))
+        .ok_or(Reason::IndexOutOfBounds)?;
+    π_prm::verify(
+        shared_state::<D>(eid, update.i),
+        π_prm::Data {
+            N: &party.N,
+            s: &update.s,
+            t: &update.t,
+        },
+        &update.proof,
+    )
+    .map_err(|_| Reason::InvalidProof)?;
+
+    let mut new_aux = aux.clone().into_inner();
+    let party = &mut new_aux.parties[usize::from(update.i)];
+    party.s = update.s.clone();
+    party.t = update.t.clone();
+    party.multiexp = None;
+    new_aux
+        .validate()
+        .map_err(|err| Reason::InvalidAux(err.into_error()).into())
+}
+
+fn shared_state<D: Digest>(eid: ExecutionId<'_>, i: PartyIndex) -> utils::Truncated<D> {
+    utils::Truncated::new(
+        D::new_with_prefix(D::digest(eid.as_bytes())).chain_update(i.to_be_bytes()),
+    )
+}
+
+/// Error related to ring-Pedersen parameters
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct RingPedersenError(Reason);
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("pow mod undefined")]
+    PowMod,
+    #[error("couldn't prove prm statement")]
+    Prove(#[source] π_prm::ZkError),
+    #[error("s or t is not a quadratic residue modulo N")]
+    NotQuadraticResidue,
+    #[error("s is not equal to t^lambda")]
+    InvalidLambda,
+    #[error("aux info doesn't contain public data of local party")]
+    OwnAuxNotFound,
+    #[error("party index is out of bounds")]
+    IndexOutOfBounds,
+    #[error("proof of ring-Pedersen parameters is invalid")]
+    InvalidProof,
+    #[error("updated aux info is invalid")]
+    InvalidAux(#[source] InvalidKeyShare),
+}
+
+crate::errors::impl_from! {
+    impl From for RingPedersenError {
+        err: Reason => RingPedersenError(err),
+    }
+}
@@ -3,19 +3,23 @@
 use std::ops;
 use std::sync::Arc;
 
+use digest::Digest;
 use generic_ec::{Curve, NonZero, Point, SecretScalar};
+use paillier_zk::fast_paillier;
 use paillier_zk::paillier_encryption_in_range as π_enc;
 use paillier_zk::rug::{Complete, Integer};
-use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::policy::KeyUsagePolicy;
 use crate::security_level::SecurityLevel;
 
+pub mod health;
+
 #[doc(inline)]
 pub use cggmp21_keygen::key_share::{
     CoreKeyShare as IncompleteKeyShare, DirtyCoreKeyShare as DirtyIncompleteKeyShare, DirtyKeyInfo,
-    HdError, InvalidCoreShare as InvalidIncompleteKeyShare, KeyInfo, Valid, Validate,
-    ValidateError, ValidateFromParts, VssSetup,
+    HdError, IntoValidParts, InvalidCoreShare as InvalidIncompleteKeyShare, InvalidSubset, KeyInfo,
+    Valid, Validate, ValidateError, ValidateFromParts, VssSetup,
 };
 
 /// Key share
@@ -30,8 +34,9 @@ pub type KeyShare<E, L = crate::default_choice::SecurityLevel> = Valid<DirtyKeyS
 pub type AuxInfo<L = crate::default_choice::SecurityLevel> = Valid<DirtyAuxInfo<L>>;
 
 /// Dirty aux info
-#[derive(Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct DirtyAuxInfo<L: SecurityLevel = crate::default_choice::SecurityLevel> {
     /// Secret prime $p$
     pub p: Integer,
@@ -41,26 +46,102 @@ pub struct DirtyAuxInfo<L: SecurityLevel = crate::default_choice::SecurityLevel>
     ///
     /// `parties[i]` corresponds to public auxiliary data of $\ith$ party
     pub parties: Vec<PartyAux>,
+    /// Binding of aux info to the key
+    ///
+    /// Optional. If set, aux info can only be paired with key share of the bound key, see
+    /// [`bind_to_key`](Self::bind_to_key).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub key_binding: Option<AuxKeyBinding>,
     /// Security level that was used to generate aux info
-    #[serde(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub security_level: std::marker::PhantomData<L>,
+    /// Cached Paillier decryption key
+    ///
+    /// Populated on the first call to [`decryption_key`](Self::decryption_key). It's not serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub decryption_key_cache: DecryptionKeyCache,
+}
+
+/// Fingerprint of the key
+///
+/// SHA-256 hash of the shared public key (in compressed form) and the curve name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyFingerprint(#[cfg_attr(feature = "serde", serde(with = "hex"))] pub [u8; 32]);
+
+impl KeyFingerprint {
+    /// Computes fingerprint of the key
+    pub fn of<E: Curve>(key_info: &DirtyKeyInfo<E>) -> Self {
+        Self(
+            sha2::Sha256::new()
+                .chain_update(b"dfns.cggmp21.key_fingerprint")
+                .chain_update(E::CURVE_NAME)
+                .chain_update(key_info.shared_public_key.to_bytes(true))
+                .finalize()
+                .into(),
+        )
+    }
+}
+
+/// Binding of aux info to the key
+///
+/// Prevents pairing aux info with key share of another key, or with key share obtained at another
+/// refresh epoch. Set via [`DirtyAuxInfo::bind_to_key`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AuxKeyBinding {
+    /// Fingerprint of the key that aux info belongs to
+    pub key: KeyFingerprint,
+    /// Refresh epoch of the key at which aux info was generated
+    ///
+    /// See [`KeyEntry::epoch`](crate::registry::KeyEntry::epoch)
+    pub epoch: u64,
+}
+
+/// Lazily constructed Paillier decryption key
+///
+/// Constructing decryption key from primes `p`, `q` involves CRT precomputations. The cache allows
+/// to do it only once per aux info rather than on every signing. Clones of aux info share the
+/// same cache.
+///
+/// Note: cached key contains secret information. Keep it secret (as well as rest of the key share).
+#[derive(Clone, Default)]
+pub struct DecryptionKeyCache(Arc<std::sync::OnceLock<fast_paillier::DecryptionKey>>);
+
+impl From<fast_paillier::DecryptionKey> for DecryptionKeyCache {
+    fn from(dec: fast_paillier::DecryptionKey) -> Self {
+        Self(Arc::new(dec.into()))
+    }
 }
 
 /// Dirty (unvalidated) key share
 ///
 #[doc = include_str!("../docs/key_share.md")]
-#[derive(Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct DirtyKeyShare<E: Curve, L: SecurityLevel = crate::default_choice::SecurityLevel> {
     /// Core key share
     pub core: DirtyIncompleteKeyShare<E>,
     /// Auxiliary info
     pub aux: DirtyAuxInfo<L>,
+    /// Key usage policy
+    ///
+    /// If set, signing protocol refuses to issue a partial signature that violates the policy
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub policy: Option<KeyUsagePolicy>,
 }
 
 /// Party public auxiliary data
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct PartyAux {
     /// $N_i = p_i \cdot q_i$
     pub N: Integer,
@@ -69,12 +150,12 @@ pub struct PartyAux {
     /// Ring-Perdesten parameter $t_i$
     pub t: Integer,
     /// Precomputed table for faster multiexponentiation
-    #[serde(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub multiexp: Option<Arc<paillier_zk::multiexp::MultiexpTable>>,
     /// Enables faster modular exponentiation when factorization of `N` is known
     ///
     /// Note that it is extreamly sensitive! Leaking `crt` exposes Paillier private key.
-    #[serde(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub crt: Option<paillier_zk::fast_paillier::utils::CrtExp>,
 }
 
@@ -155,6 +236,22 @@ impl<L: SecurityLevel> DirtyAuxInfo<L> {
             .sum()
     }
 
+    /// Binds aux info to the key at given refresh epoch
+    ///
+    /// Once bound, [`KeyShare::from_parts`] refuses to pair aux info with key share of another key.
+    /// Epoch is checked when expected epoch is provided, i.e. when key share is constructed from
+    /// `(core, aux, epoch)` parts. Binding is serialized along with aux info. Previous binding, if
+    /// present, is overwritten.
+    ///
+    /// Binding doesn't affect validity of aux info, so it can be set on [`AuxInfo`] by
+    /// [deconstructing](Valid::into_inner) it and validating it again.
+    pub fn bind_to_key<E: Curve>(&mut self, key_info: &DirtyKeyInfo<E>, epoch: u64) {
+        self.key_binding = Some(AuxKeyBinding {
+            key: KeyFingerprint::of(key_info),
+            epoch,
+        });
+    }
+
     /// Precomputes CRT parameters
     ///
     /// Refer to [`PartyAux::precompute_crt`] for the docs.
@@ -165,6 +262,33 @@ impl<L: SecurityLevel> DirtyAuxInfo<L> {
             .ok_or(InvalidKeyShareReason::CrtINotInRange)?;
         aux_i.precompute_crt(&self.p, &self.q)
     }
+
+    /// Returns Paillier decryption key corresponding to primes `p`, `q`
+    ///
+    /// Decryption key is constructed on the first call and cached, subsequent calls reuse it. If
+    /// `p` or `q` were modified after the key was cached, a fresh key is constructed instead.
+    ///
+    /// Returns error if `p`, `q` do not form a valid Paillier secret key.
+    pub fn decryption_key(
+        &self,
+    ) -> Result<std::borrow::Cow<fast_paillier::DecryptionKey>, InvalidKeyShare> {
+        let build = || {
+            fast_paillier::DecryptionKey::from_primes(self.p.clone(), self.q.clone())
+                .map_err(|_| InvalidKeyShareReason::BuildDecryptionKey)
+        };
+        let cached = match self.decryption_key_cache.0.get() {
+            Some(cached) => cached,
+            None => {
+                let dec = build()?;
+                self.decryption_key_cache.0.get_or_init(|| dec)
+            }
+        };
+        if cached.p() == &self.p && cached.q() == &self.q {
+            Ok(std::borrow::Cow::Borrowed(cached))
+        } else {
+            Ok(std::borrow::Cow::Owned(build()?))
+        }
+    }
 }
 
 impl PartyAux {
@@ -240,6 +364,58 @@ impl<E: Curve, L: SecurityLevel> ValidateFromParts<(IncompleteKeyShare<E>, AuxIn
         Self {
             core: core.into_inner(),
             aux: aux.into_inner(),
+            policy: None,
+        }
+    }
+}
+
+/// Pairs key share with aux info, additionally checking refresh epoch
+///
+/// If aux info is [bound to the key](DirtyAuxInfo::bind_to_key), its epoch must match the provided
+/// one. Unbound aux info is accepted at any epoch.
+impl<E: Curve, L: SecurityLevel> ValidateFromParts<(IncompleteKeyShare<E>, AuxInfo<L>, u64)>
+    for DirtyKeyShare<E, L>
+{
+    fn validate_parts(
+        (core, aux, epoch): &(IncompleteKeyShare<E>, AuxInfo<L>, u64),
+    ) -> Result<(), Self::Error> {
+        Self::validate_consistency(core, aux)?;
+        match &aux.key_binding {
+            Some(binding) if binding.epoch != *epoch => Err(InvalidKeyShareReason::AuxEpoch {
+                expected: *epoch,
+                actual: binding.epoch,
+            }
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn from_parts((core, aux, _epoch): (IncompleteKeyShare<E>, AuxInfo<L>, u64)) -> Self {
+        <Self as ValidateFromParts<(IncompleteKeyShare<E>, AuxInfo<L>)>>::from_parts((core, aux))
+    }
+}
+
+/// Pairs key share with aux info, restoring key usage [`policy`](DirtyKeyShare::policy)
+///
+/// Counterpart of [`split`]: merges the parts back into the same key share.
+impl<E: Curve, L: SecurityLevel>
+    ValidateFromParts<(IncompleteKeyShare<E>, AuxInfo<L>, Option<KeyUsagePolicy>)>
+    for DirtyKeyShare<E, L>
+{
+    fn validate_parts(
+        (core, aux, _policy): &(IncompleteKeyShare<E>, AuxInfo<L>, Option<KeyUsagePolicy>),
+    ) -> Result<(), Self::Error> {
+        Self::validate_consistency(core, aux)
+    }
+
+    fn from_parts(
+        (core, aux, policy): (IncompleteKeyShare<E>, AuxInfo<L>, Option<KeyUsagePolicy>),
+    ) -> Self {
+        Self {
+            policy,
+            ..<Self as ValidateFromParts<(IncompleteKeyShare<E>, AuxInfo<L>)>>::from_parts((
+                core, aux,
+            ))
         }
     }
 }
@@ -251,18 +427,155 @@ impl<E: Curve, L: SecurityLevel> DirtyKeyShare<E, L> {
         aux: &DirtyAuxInfo<L>,
     ) -> Result<(), InvalidKeyShare> {
         if core.public_shares.len() != aux.parties.len() {
-            return Err(InvalidKeyShareReason::AuxLen.into());
+            return Err(InvalidKeyShareReason::AuxLen {
+                n: core.public_shares.len(),
+                aux_len: aux.parties.len(),
+            }
+            .into());
+        }
+
+        if let Some(binding) = &aux.key_binding {
+            if binding.key != KeyFingerprint::of(&core.key_info) {
+                return Err(InvalidKeyShareReason::AuxBoundToAnotherKey.into());
+            }
         }
 
         let N_i = &aux.parties[usize::from(core.i)].N;
         if *N_i != (&aux.p * &aux.q).complete() {
-            return Err(InvalidKeyShareReason::PrimesMul.into());
+            return Err(InvalidKeyShareReason::PrimesMul { i: core.i }.into());
         }
 
         Ok(())
     }
 }
 
+/// Aux info shared by many key shares of the same committee
+///
+/// Aux info doesn't depend on the key, so the same committee may generate aux info once and bind it
+/// to any amount of key shares via [`bind`](Self::bind), instead of generating a Paillier key per
+/// each ECDSA key. Aux info is reference-counted: clones of `SharedAuxInfo` are cheap, and key
+/// shares bound to the same aux info share precomputations like
+/// [decryption key](DirtyAuxInfo::decryption_key) and multiexponentiation tables.
+///
+/// Note that aux info contains secret Paillier key. Keep it secret (as well as key shares).
+#[derive(Clone)]
+pub struct SharedAuxInfo<L: SecurityLevel = crate::default_choice::SecurityLevel>(Arc<AuxInfo<L>>);
+
+impl<L: SecurityLevel> SharedAuxInfo<L> {
+    /// Wraps aux info
+    pub fn new(aux: AuxInfo<L>) -> Self {
+        Self(Arc::new(aux))
+    }
+
+    /// Returns aux info
+    pub fn aux_info(&self) -> &AuxInfo<L> {
+        &self.0
+    }
+
+    /// Checks that aux info can be bound to the key share
+    ///
+    /// Key share must be shared among the same amount of parties, and index of the party holding
+    /// the key share must correspond to the index of the party holding this aux info.
+    pub fn check<E: Curve>(&self, core: &IncompleteKeyShare<E>) -> Result<(), InvalidKeyShare> {
+        DirtyKeyShare::validate_consistency(core, &self.0)
+    }
+
+    /// Binds aux info to the key share, producing complete key share
+    ///
+    /// Returns error if aux info can't be bound to this key share, see [`check`](Self::check).
+    pub fn bind<E: Curve>(
+        &self,
+        core: IncompleteKeyShare<E>,
+    ) -> Result<KeyShare<E, L>, InvalidKeyShare> {
+        KeyShare::from_parts((core, (*self.0).clone())).map_err(|err| err.into_error())
+    }
+}
+
+impl<L: SecurityLevel> From<AuxInfo<L>> for SharedAuxInfo<L> {
+    fn from(aux: AuxInfo<L>) -> Self {
+        Self::new(aux)
+    }
+}
+
+/// Key share borrowed as two separate parts
+///
+/// Refers to [`IncompleteKeyShare`] and [`AuxInfo`] that were checked to be consistent with each
+/// other, same as when they're merged into [`KeyShare`]. Allows signing with key share and aux
+/// info that are stored separately without cloning aux info, see
+/// [`SigningBuilder::from_parts`](crate::signing::SigningBuilder::from_parts).
+pub struct KeyShareRef<'a, E: Curve, L: SecurityLevel = crate::default_choice::SecurityLevel> {
+    pub(crate) core: &'a IncompleteKeyShare<E>,
+    pub(crate) aux: &'a AuxInfo<L>,
+    pub(crate) policy: Option<&'a KeyUsagePolicy>,
+}
+
+impl<'a, E: Curve, L: SecurityLevel> KeyShareRef<'a, E, L> {
+    /// Checks that key share and aux info are consistent with each other
+    ///
+    /// Performs the same check as [`Valid::from_parts`] when building [`KeyShare`], but doesn't
+    /// take ownership of the parts.
+    pub fn new(
+        core: &'a IncompleteKeyShare<E>,
+        aux: &'a AuxInfo<L>,
+    ) -> Result<Self, InvalidKeyShare> {
+        DirtyKeyShare::validate_consistency(core, aux)?;
+        Ok(Self {
+            core,
+            aux,
+            policy: None,
+        })
+    }
+
+    /// Sets key usage policy
+    ///
+    /// See [`DirtyKeyShare::policy`]
+    pub fn set_policy(self, policy: &'a KeyUsagePolicy) -> Self {
+        Self {
+            policy: Some(policy),
+            ..self
+        }
+    }
+
+    /// Returns key share
+    pub fn core(&self) -> &'a IncompleteKeyShare<E> {
+        self.core
+    }
+
+    /// Returns aux info
+    pub fn aux(&self) -> &'a AuxInfo<L> {
+        self.aux
+    }
+
+    /// Returns key usage policy
+    pub fn policy(&self) -> Option<&'a KeyUsagePolicy> {
+        self.policy
+    }
+}
+
+impl<'a, E: Curve, L: SecurityLevel> Clone for KeyShareRef<'a, E, L> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, E: Curve, L: SecurityLevel> Copy for KeyShareRef<'a, E, L> {}
+
+impl<'a, E: Curve, L: SecurityLevel> From<&'a KeyShare<E, L>> for KeyShareRef<'a, E, L> {
+    fn from(key_share: &'a KeyShare<E, L>) -> Self {
+        Self {
+            core: key_share.as_ref(),
+            aux: key_share.as_ref(),
+            policy: key_share.policy.as_ref(),
+        }
+    }
+}
+
+impl<'a, E: Curve, L: SecurityLevel> AsRef<IncompleteKeyShare<E>> for KeyShareRef<'a, E, L> {
+    fn as_ref(&self) -> &IncompleteKeyShare<E> {
+        self.core
+    }
+}
+
 impl<E: Curve> DirtyKeyShare<E> {
     /// Precomputes CRT parameters
     ///
@@ -290,6 +603,11 @@ impl<E: Curve, L: SecurityLevel> AsRef<DirtyAuxInfo<L>> for DirtyKeyShare<E, L>
         &self.aux
     }
 }
+impl<E: Curve, L: SecurityLevel> AsRef<DirtyKeyInfo<E>> for DirtyKeyShare<E, L> {
+    fn as_ref(&self) -> &DirtyKeyInfo<E> {
+        &self.core.key_info
+    }
+}
 
 impl<E: Curve, L: SecurityLevel> ops::Deref for DirtyKeyShare<E, L> {
     type Target = DirtyIncompleteKeyShare<E>;
@@ -362,19 +680,130 @@ impl From<&PartyAux> for π_enc::Aux {
     }
 }
 
+/// Splits key share into core share, aux info and key usage policy
+///
+/// Core share and aux info can be stored separately, and merged back via [`Valid::from_parts`]
+/// along with the policy. Policy must be kept with the parts: merging them without the policy
+/// produces key share that doesn't enforce it.
+pub fn split<E: Curve, L: SecurityLevel>(
+    key_share: KeyShare<E, L>,
+) -> (IncompleteKeyShare<E>, AuxInfo<L>, Option<KeyUsagePolicy>) {
+    let DirtyKeyShare { core, aux, policy } = key_share.into_inner();
+    #[allow(clippy::expect_used)]
+    let core = core
+        .validate()
+        .expect("core share of valid key share is valid");
+    #[allow(clippy::expect_used)]
+    let aux = aux
+        .validate()
+        .expect("aux info of valid key share is valid");
+    (core, aux, policy)
+}
+
+/// Upgrades key share to a higher security level
+///
+/// Security level determines size of Paillier keys. To upgrade existing key shares, parties run
+/// [aux info generation](crate::aux_info_gen) at the new level `L2` and bind obtained aux info to
+/// their key shares via this function. ECDSA key, key shares and key usage
+/// [policy](DirtyKeyShare::policy) remain the same.
+///
+/// Returns error if `L2` is lower than `L1`, or if aux info can't be bound to the key share.
+pub fn upgrade_security_level<E: Curve, L1: SecurityLevel, L2: SecurityLevel>(
+    key_share: KeyShare<E, L1>,
+    aux: AuxInfo<L2>,
+) -> Result<KeyShare<E, L2>, UpgradeError> {
+    if L2::SECURITY_BITS < L1::SECURITY_BITS {
+        return Err(UpgradeReason::Downgrade {
+            from: L1::SECURITY_BITS,
+            to: L2::SECURITY_BITS,
+        }
+        .into());
+    }
+    let DirtyKeyShare { core, policy, .. } = key_share.into_inner();
+    let core = core
+        .validate()
+        .map_err(|err| UpgradeReason::Bind(err.into_error().into()))?;
+    KeyShare::from_parts((core, aux, policy))
+        .map_err(|err| UpgradeReason::Bind(err.into_error()).into())
+}
+
+/// Checks that two key shares refer to the same ECDSA key
+///
+/// Key shares must belong to the same party, and share the same public key among the same amount
+/// of parties. Can be used to make sure that key share migrated to another security level (or
+/// refreshed) still corresponds to the original key.
+pub fn ensure_same_key<E: Curve, L1: SecurityLevel, L2: SecurityLevel>(
+    old: &DirtyKeyShare<E, L1>,
+    new: &DirtyKeyShare<E, L2>,
+) -> Result<(), UpgradeError> {
+    if old.core.i != new.core.i
+        || old.core.public_shares.len() != new.core.public_shares.len()
+        || old.core.shared_public_key != new.core.shared_public_key
+    {
+        return Err(UpgradeReason::KeyMismatch.into());
+    }
+    #[cfg(feature = "hd-wallets")]
+    if old.core.chain_code != new.core.chain_code {
+        return Err(UpgradeReason::KeyMismatch.into());
+    }
+    Ok(())
+}
+
+/// Error indicating that key share can't be upgraded
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct UpgradeError(UpgradeReason);
+
+#[derive(Debug, Error)]
+enum UpgradeReason {
+    #[error("security level can't be downgraded: from {from} bits to {to} bits")]
+    Downgrade { from: u32, to: u32 },
+    #[error("aux info can't be bound to the key share")]
+    Bind(#[source] InvalidKeyShare),
+    #[error("key shares refer to different keys")]
+    KeyMismatch,
+}
+
+crate::errors::impl_from! {
+    impl From for UpgradeError {
+        err: UpgradeReason => UpgradeError(err),
+    }
+}
+
 /// Error indicating that key share is not valid
 #[derive(Debug, Error)]
 #[error(transparent)]
 pub struct InvalidKeyShare(#[from] InvalidKeyShareReason);
 
+impl InvalidKeyShare {
+    /// Indicates that core share and aux info are valid on their own, but don't belong to each other
+    ///
+    /// E.g. aux info was generated for another committee, or belongs to another signer
+    pub fn is_mismatched_parts(&self) -> bool {
+        matches!(
+            self.0,
+            InvalidKeyShareReason::AuxLen { .. }
+                | InvalidKeyShareReason::PrimesMul { .. }
+                | InvalidKeyShareReason::AuxBoundToAnotherKey
+                | InvalidKeyShareReason::AuxEpoch { .. }
+        )
+    }
+}
+
 #[derive(Debug, Error)]
 enum InvalidKeyShareReason {
     #[error(transparent)]
     InvalidCoreShare(InvalidIncompleteKeyShare),
-    #[error("size of parties auxiliary data list doesn't match `n`: n != parties.len()")]
-    AuxLen,
-    #[error("N_i != p q")]
-    PrimesMul,
+    #[error("aux info doesn't match the key share: it's generated for {aux_len} parties, but key share has n = {n}")]
+    AuxLen { n: usize, aux_len: usize },
+    #[error("aux info doesn't match the key share: N_i != p q, where i = {i} is index of the signer in the key share")]
+    PrimesMul { i: u16 },
+    #[error("aux info is bound to another key")]
+    AuxBoundToAnotherKey,
+    #[error(
+        "aux info is bound to the key at epoch {actual}, but key share is at epoch {expected}"
+    )]
+    AuxEpoch { expected: u64, actual: u64 },
     #[error("gcd(s_j, N_j) != 1 or gcd(t_j, N_j) != 1")]
     StGcdN,
     #[error("paillier secret key doesn't match security level (primes are too small)")]
@@ -389,6 +818,8 @@ enum InvalidKeyShareReason {
     CrtInvalidPq,
     #[error("couldn't build CRT parameters")]
     BuildCrt,
+    #[error("couldn't build paillier decryption key")]
+    BuildDecryptionKey,
 }
 
 /// Error indicating that [key reconstruction](reconstruct_secret_key) failed
@@ -0,0 +1,202 @@
+//! Health checks of key shares
+//!
+//! Key share is validated when it's constructed or deserialized, but the validation is kept cheap.
+//! [`DirtyKeyShare::health_check`] performs deep validation which is too expensive to do on every
+//! signing (primality tests, Paillier encryption round-trip, re-verification of aux proofs), and
+//! is intended to be run on a schedule, e.g. to detect corruption of stored key shares early,
+//! before they're needed for signing.
+
+use digest::Digest;
+use generic_ec::{Curve, Point};
+use paillier_zk::{fast_paillier, rug::Integer};
+
+use crate::key_refresh::{verify_aux_proofs, AuxProofs};
+use crate::security_level::SecurityLevel;
+
+use super::{DirtyKeyShare, Validate};
+
+/// Check performed by the health check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Check {
+    /// Secret share matches the public share of the party
+    PublicShare,
+    /// Public shares are consistent with VSS setup and shared public key
+    VssCommitments,
+    /// Own Paillier key: `p` and `q` are Blum primes of required size, and encryption followed by
+    /// decryption returns the same plaintext
+    PaillierKey,
+    /// Public aux data of other parties: Paillier moduli are of required size and pairwise
+    /// coprime, ring-Pedersen parameters are coprime with the modulus
+    PartiesAux,
+    /// Proofs of aux data received at aux info generation are valid
+    AuxProofs,
+}
+
+/// Result of a single check
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(rename_all = "kebab-case", tag = "status", content = "reason")
+)]
+pub enum CheckStatus {
+    /// Check passed
+    Passed,
+    /// Check failed
+    Failed(String),
+    /// Check wasn't performed as required material isn't available
+    Skipped(String),
+}
+
+/// Report produced by [`DirtyKeyShare::health_check`]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HealthReport {
+    /// Results of the checks, in order they were performed
+    pub checks: Vec<(Check, CheckStatus)>,
+}
+
+impl HealthReport {
+    /// Indicates that none of the checks failed
+    ///
+    /// Skipped checks don't make key share unhealthy.
+    pub fn is_healthy(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Returns failed checks and the reason of failure
+    pub fn failures(&self) -> impl Iterator<Item = (Check, &str)> {
+        self.checks
+            .iter()
+            .filter_map(|(check, status)| match status {
+                CheckStatus::Failed(reason) => Some((*check, reason.as_str())),
+                _ => None,
+            })
+    }
+
+    /// Returns status of the check
+    pub fn status(&self, check: Check) -> Option<&CheckStatus> {
+        self.checks
+            .iter()
+            .find(|(c, _)| *c == check)
+            .map(|(_, status)| status)
+    }
+}
+
+impl<E: Curve, L: SecurityLevel> DirtyKeyShare<E, L> {
+    /// Performs deep validation of the key share
+    ///
+    /// Takes some time as it performs primality tests. [`Check::AuxProofs`] is skipped, use
+    /// [`health_check_with_aux_proofs`](Self::health_check_with_aux_proofs) if proofs are stored
+    /// along with the key share.
+    pub fn health_check(&self) -> HealthReport {
+        let mut report = self.health_check_without_proofs();
+        report.checks.push((
+            Check::AuxProofs,
+            CheckStatus::Skipped("aux proofs are not provided".into()),
+        ));
+        report
+    }
+
+    /// Performs deep validation of the key share, including re-verification of aux proofs
+    ///
+    /// `D` must be the same hash function that was used in aux info generation.
+    pub fn health_check_with_aux_proofs<D>(&self, proofs: &AuxProofs) -> HealthReport
+    where
+        D: Digest + Clone,
+    {
+        let mut report = self.health_check_without_proofs();
+        let status = if proofs.i != self.core.i {
+            CheckStatus::Failed("proofs were received by another party".into())
+        } else {
+            to_status(verify_aux_proofs::<L, D>(&self.aux, proofs).map_err(|e| e.to_string()))
+        };
+        report.checks.push((Check::AuxProofs, status));
+        report
+    }
+
+    fn health_check_without_proofs(&self) -> HealthReport {
+        HealthReport {
+            checks: vec![
+                (Check::PublicShare, to_status(self.check_public_share())),
+                (
+                    Check::VssCommitments,
+                    to_status(self.check_vss_commitments()),
+                ),
+                (Check::PaillierKey, to_status(self.check_paillier_key())),
+                (Check::PartiesAux, to_status(self.check_parties_aux())),
+            ],
+        }
+    }
+
+    fn check_public_share(&self) -> Result<(), String> {
+        let public_share = self
+            .core
+            .public_shares
+            .get(usize::from(self.core.i))
+            .ok_or("party index is out of bounds")?;
+        if *public_share != Point::generator() * &self.core.x {
+            return Err("secret share doesn't match public share".into());
+        }
+        Ok(())
+    }
+
+    fn check_vss_commitments(&self) -> Result<(), String> {
+        self.core.key_info.is_valid().map_err(|e| e.to_string())
+    }
+
+    fn check_paillier_key(&self) -> Result<(), String> {
+        let aux = &self.aux;
+        let N_i = &aux
+            .parties
+            .get(usize::from(self.core.i))
+            .ok_or("party index is out of bounds")?
+            .N;
+        if Integer::from(&aux.p * &aux.q) != *N_i {
+            return Err("N_i != p q".into());
+        }
+        if !crate::security_level::validate_secret_paillier_key_size::<L>(&aux.p, &aux.q) {
+            return Err("primes are too small".into());
+        }
+        for (name, x) in [("p", &aux.p), ("q", &aux.q)] {
+            if x.mod_u(4) != 3 {
+                return Err(format!("{name} is not a Blum prime"));
+            }
+            if !crate::key_refresh::primes::is_prime(x) {
+                return Err(format!("{name} is not a prime"));
+            }
+        }
+
+        let dec = fast_paillier::DecryptionKey::from_primes(aux.p.clone(), aux.q.clone())
+            .map_err(|_| "couldn't build decryption key")?;
+        let plaintext = Integer::from(0x5eed_u32);
+        let ciphertext = dec
+            .encrypt_with(&plaintext, &Integer::from(2))
+            .map_err(|_| "encryption failed")?;
+        let decrypted = dec.decrypt(&ciphertext).map_err(|_| "decryption failed")?;
+        if decrypted != plaintext {
+            return Err("decrypted plaintext doesn't match encrypted one".into());
+        }
+        Ok(())
+    }
+
+    fn check_parties_aux(&self) -> Result<(), String> {
+        self.aux.is_valid().map_err(|e| e.to_string())?;
+        let related = crate::utils::related_moduli(self.aux.parties.iter().map(|p| &p.N));
+        if !related.is_empty() {
+            return Err(format!(
+                "Paillier moduli of parties {related:?} are equal or share a factor"
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn to_status(result: Result<(), String>) -> CheckStatus {
+    match result {
+        Ok(()) => CheckStatus::Passed,
+        Err(reason) => CheckStatus::Failed(reason),
+    }
+}
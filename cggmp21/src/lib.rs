@@ -65,6 +65,15 @@
 //! (like Redis or Postgres); some specific applications may want to communicate over a public
 //! blockchain, and so on.
 //!
+//! The `examples/demo` directory of the repository shows how parties running
+//! in separate processes can communicate over TCP or WebSocket. It's only meant as a starting point:
+//! messages are neither authenticated nor encrypted.
+//!
+//! Ready-made transports over TCP (optionally secured with TLS), WebSocket and QUIC, which handle
+//! framing and reconnection, are available in `transport` module behind `transport-tcp`,
+//! `transport-tls`, `transport-websocket` and `transport-quic` features. Applications without
+//! an async runtime can drive the protocols via [`blocking`] module.
+//!
 //! Whatever networking implementation you use, keep in mind that:
 //!
 //! * All messages must be authenticated \
@@ -220,6 +229,23 @@
 //!
 //! **Never reuse presignatures!** If you use the same presignature to sign two different messages,
 //! the private key may be leaked.
+//! To help with that, issuing a partial signature consumes the presignature, and `Presignature`
+//! doesn't implement `Clone`.
+//!
+//! ## Supported curves
+//! Curves are compiled in only when requested via feature flags, so products that work with a
+//! single curve don't pull in dependencies of other curves. No curve is enabled by default:
+//! * `curve-secp256k1` enables [`Secp256k1`](supported_curves::Secp256k1), pulls in `k256`
+//! * `curve-secp256r1` enables [`Secp256r1`](supported_curves::Secp256r1) (NIST P-256), pulls
+//!   in `p256`
+//! * `curve-secp384r1` enables [`Secp384r1`](supported_curves::Secp384r1) (NIST P-384), pulls
+//!   in `p384`. Must be used with [`SecurityLevel128Wide`](security_level::SecurityLevel128Wide)
+//! * `curve-stark` enables [`Stark`](supported_curves::Stark) curve
+//! * `all-curves` enables all of the above
+//!
+//! Chain-specific features enable the curve they need, e.g. `ethereum` and `bitcoin` enable
+//! `curve-secp256k1`. Protocols are generic over the curve, see [`supported_curves`] for
+//! requirements on other curves.
 //!
 //! ## HD wallets support
 //! Library supports non-hardened deterministic key derivation based on [slip10] standard (compatible
@@ -242,7 +268,31 @@
 //!
 //! Such use-cases contradict to nature of MPC so we don't include those primitives by default.
 //! However, you may opt for them by enabling `spof` feature, then you can use [`trusted_dealer`]
-//! for key import and [`key_share::reconstruct_secret_key`] for key export.
+//! for key import and [`key_share::reconstruct_secret_key`] for key export. Reconstructed key can be
+//! converted into standard wallet formats via [`key_export`].
+//!
+//! ## Key share tooling
+//! `cli` feature enables `cggmp21-key-share` binary that inspects serialized key shares (curve,
+//! threshold, share indexes, security level, sizes of aux data), validates them, and migrates key
+//! shares produced by older versions of the library to the current format:
+//! ```text
+//! cargo install cggmp21 --features cli
+//! cggmp21-key-share inspect key_share.json
+//! cggmp21-key-share validate --deep key_share.json
+//! cggmp21-key-share migrate --encoding cbor -o key_share.cbor key_share.json
+//! ```
+//!
+//! ## Minimal build
+//! The only feature enabled by default is `serde`, which implements serialization for key shares,
+//! protocol messages, progress reports and other public types. Everything else (curves, `spof`,
+//! `hd-wallets`, chain-specific integrations, co-signer, tooling) is opt-in. For environments where
+//! every dependency needs to be reviewed, the protocols can be built without serialization support:
+//! ```toml
+//! cggmp21 = { version = "0.2", default-features = false, features = ["curve-secp256k1"] }
+//! ```
+//! In this configuration, the application is responsible for encoding protocol messages and
+//! persisting key shares. Features that inherently rely on serialization, such as `co-signer`,
+//! `share-recovery`, `jose` or `cli`, enable `serde` automatically.
 //!
 //! ## Differences between the implementation and [CGGMP21]
 //! [CGGMP21] only defines a non-threshold protocol. To support general thresholds,
@@ -266,6 +316,13 @@
 //! execution. We consider timing attacks out of scope as they are nearly impossible to perform for such
 //! complicated protcol as CGGMP21 and impossible to do in our specific deployment. Thus, we intentionally
 //! don't do constant-time operations which gives us a significant performance boost.
+//!
+//! Nevertheless, the repository contains a harness of dudect-style statistical timing tests of
+//! secret-dependent code paths (Paillier decryption, scalar arithmetic, proof generation), which
+//! can be used to track how a change affects them:
+//! ```text
+//! cargo run -r -p cggmp21-tests --features side-channel --bin side_channel
+//! ```
 
 #![allow(
     non_snake_case,
@@ -278,8 +335,27 @@
 #![cfg_attr(not(test), forbid(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+#[cfg(feature = "curve-secp256k1")]
+pub use k256;
+#[cfg(feature = "curve-secp256r1")]
+pub use p256;
+#[cfg(feature = "curve-secp384r1")]
+pub use p384;
+#[cfg(feature = "transport-quic")]
+pub use quinn;
+#[cfg(feature = "parallel")]
+pub use rayon;
+#[cfg(feature = "signature")]
+pub use signature;
 #[cfg(feature = "hd-wallets")]
 pub use slip_10;
+#[cfg(feature = "transport-tls")]
+pub use tokio_rustls::rustls;
+#[cfg(feature = "transport-websocket")]
+pub use tokio_tungstenite::tungstenite;
+// Dependencies of `cggmp21-key-share` binary
+#[cfg(feature = "cli")]
+use {anyhow as _, bpaf as _, ciborium as _};
 pub use {
     generic_ec, paillier_zk,
     paillier_zk::{fast_paillier, rug},
@@ -287,23 +363,54 @@ pub use {
 };
 
 #[doc(inline)]
-pub use cggmp21_keygen::{keygen, progress, ExecutionId};
+pub use ::key_share::poly;
+#[cfg(feature = "test-utils")]
+#[doc(inline)]
+pub use cggmp21_keygen::test_utils;
+#[doc(inline)]
+pub use cggmp21_keygen::{
+    abort, attempts, error_code, execution_id, keygen, progress, transcript, version, DomainTag,
+    EidRegistry, EidRegistryError, ExecutionId, InMemoryEidRegistry, InvalidDomainTag,
+};
 
 use generic_ec::{coords::HasAffineX, Curve, Point};
-use key_share::AnyKeyShare;
 use round_based::PartyIndex;
 use security_level::SecurityLevel;
 use signing::SigningBuilder;
 
+#[cfg(feature = "adversarial")]
+pub mod adversarial;
+pub mod blocking;
+#[cfg(feature = "co-signer")]
+pub mod co_signer;
+#[cfg(feature = "debug-replay")]
+pub mod debug_replay;
+pub mod digests;
 mod errors;
 pub mod key_refresh;
 pub mod key_share;
+pub mod limits;
+pub mod paillier_backend;
+pub mod policy;
+pub mod pvss;
+pub mod registry;
+pub mod rekey;
+pub mod roster;
+pub mod secret_storage;
 pub mod security_level;
+#[cfg(feature = "share-recovery")]
+pub mod share_recovery;
 pub mod signing;
 pub mod supported_curves;
+#[cfg(feature = "transport")]
+pub mod transport;
+#[cfg(feature = "tss-lib")]
+pub mod tss_lib;
 mod utils;
 mod zk;
 
+#[cfg(feature = "spof")]
+pub mod key_export;
 #[cfg(feature = "spof")]
 pub mod trusted_dealer;
 
@@ -317,19 +424,23 @@ mod default_choice {
 pub mod keygen {
     #[doc(inline)]
     pub use cggmp21_keygen::{
-        msg, GenericKeygenBuilder, KeygenBuilder, KeygenError, NonThreshold,
+        msg, pop, to_threshold, GenericKeygenBuilder, KeygenBuilder, KeygenError, NonThreshold,
         ThresholdKeygenBuilder, WithThreshold,
     };
 
     pub use msg::non_threshold::Msg as NonThresholdMsg;
     pub use msg::threshold::Msg as ThresholdMsg;
+    pub use msg::to_threshold::Msg as ToThresholdMsg;
+    pub use msg::two_round::Msg as TwoRoundMsg;
 }
 
 pub use self::{
     key_refresh::{KeyRefreshError, PregeneratedPrimes},
     key_share::{IncompleteKeyShare, KeyShare},
     keygen::KeygenError,
-    signing::{DataToSign, PartialSignature, Presignature, Signature, SigningError},
+    signing::{
+        DataToSign, PartialSignature, Presignature, PresignaturePublicData, Signature, SigningError,
+    },
 };
 
 /// Protocol for finalizing the keygen by generating aux info.
@@ -365,7 +476,7 @@ where
 /// PregeneratedPrimes can be obtained with [`key_refresh::PregeneratedPrimes::generate`]
 pub fn key_refresh<'a, E, L>(
     eid: ExecutionId<'a>,
-    key_share: &'a impl AnyKeyShare<E>,
+    key_share: impl Into<key_refresh::RefreshShare<'a, E>>,
     pregenerated: key_refresh::PregeneratedPrimes<L>,
 ) -> key_refresh::KeyRefreshBuilder<'a, E, L>
 where
@@ -376,6 +487,11 @@ where
 }
 
 /// Protocol for generating a signature or presignature
+///
+/// `parties_indexes_at_keygen` is a set of signers $S$, and `i` is the position of local party
+/// in $S$. Alternatively, $S$ can be constructed and validated via
+/// [`SignersSet`](signing::SignersSet), then signing is started with
+/// [`SigningBuilder::with_signers`] which computes `i` automatically.
 pub fn signing<'r, E, L>(
     eid: ExecutionId<'r>,
     i: PartyIndex,
@@ -390,7 +506,7 @@ where
     SigningBuilder::new(eid, i, parties_indexes_at_keygen, key_share)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "serde"))]
 mod tests {
     use digest::Digest;
     use generic_ec::Curve;
@@ -0,0 +1,124 @@
+//! Structural limits on received messages
+//!
+//! Malicious party may send a message that is well-formed from the serialization point of view,
+//! but forces pathological amount of work or memory on the recipient: e.g. a huge Paillier
+//! modulus makes every proof verification slow, a ciphertext that's not reduced modulo $N^2$
+//! makes homomorphic operations slow. Protocols check structural bounds on received messages
+//! before doing any cryptographic operations on them, and abort blaming the sender if they're
+//! not met:
+//!
+//! * Paillier moduli of other parties must be of size required by the security level, and
+//!   at most [`max_paillier_modulus_bits`]
+//! * Paillier ciphertexts must be in range $[1, N^2)$, where $N$ is the modulus of the key they
+//!   were encrypted with
+//! * Vectors (e.g. public shares) must have the length determined by amount of parties
+//!
+//! Points and scalars are validated upon deserialization: points must be on the curve and
+//! scalars must be canonical, so they don't require additional checks.
+//!
+//! ## Message size
+//! Serialized size of the message can't be checked by the protocol: by the time the message is
+//! received, it's already deserialized, so all the allocations were already done. Transport layer
+//! should reject messages larger than [`MessageSizeLimit`] before deserializing them.
+
+use crate::rug::Integer;
+use crate::security_level::SecurityLevel;
+
+/// Max bit length of Paillier modulus $N$ of another party
+///
+/// Allows moduli up to 4 times larger than the size required by the security level, so parties
+/// may use larger primes than required.
+pub fn max_paillier_modulus_bits<L: SecurityLevel>() -> u32 {
+    4 * 8 * L::SECURITY_BITS
+}
+
+/// Limit on serialized size of a single protocol message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageSizeLimit {
+    max_size: usize,
+}
+
+impl MessageSizeLimit {
+    /// Limit suitable for all protocols of this crate executed by `n` parties at security level `L`
+    ///
+    /// Limit is an upper bound on size of messages of honest parties, with large margin to
+    /// accommodate any reasonable serialization format (including text formats like JSON with
+    /// hex-encoded integers). Largest messages are the ones carrying $\Pi^\text{prm}$ and
+    /// $\Pi^\text{mod}$ proofs in auxiliary info generation and key refresh, which consist of
+    /// a few [`M`](SecurityLevel::M) integers of size of $N$, and messages of key refresh that
+    /// contain a point for every party.
+    pub fn new<L: SecurityLevel>(n: u16) -> Self {
+        // Any integer in the messages is bounded by N^2 with a range proof slack
+        let integer_bytes =
+            (2 * max_paillier_modulus_bits::<L>() as usize + L::ELL_PRIME + L::EPSILON).div_ceil(8);
+        // Amount of integers in the message carrying П_prm or П_mod & П_fac proofs
+        let integers = 4 * L::M + 64;
+        // Every party contributes a point, a Schnorr commitment and a proof
+        let per_party = 256;
+        // x2 accounts for hex encoding, plus fixed overhead for serialization framing
+        let max_size = 2 * (integers * integer_bytes + usize::from(n) * per_party) + 64 * 1024;
+        Self { max_size }
+    }
+
+    /// Custom limit
+    pub fn with_max_size(max_size: usize) -> Self {
+        Self { max_size }
+    }
+
+    /// Max size of serialized message in bytes
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Checks that serialized message doesn't exceed the limit
+    pub fn check(&self, serialized: &[u8]) -> Result<(), MessageTooLarge> {
+        if serialized.len() > self.max_size {
+            Err(MessageTooLarge {
+                size: serialized.len(),
+                limit: self.max_size,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Error indicating that received message exceeds [`MessageSizeLimit`]
+#[derive(Debug, thiserror::Error)]
+#[error("message size {size} exceeds the limit {limit}")]
+pub struct MessageTooLarge {
+    size: usize,
+    limit: usize,
+}
+
+/// Checks that Paillier ciphertext is in range $[1, N^2)$
+pub(crate) fn is_ciphertext_well_formed(ciphertext: &Integer, nn: &Integer) -> bool {
+    ciphertext.cmp0().is_gt() && ciphertext < nn
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rug::Integer;
+    use crate::security_level::SecurityLevel128;
+
+    use super::{is_ciphertext_well_formed, MessageSizeLimit};
+
+    #[test]
+    fn size_limit() {
+        let limit = MessageSizeLimit::new::<SecurityLevel128>(3);
+        assert!(limit.check(&vec![0; limit.max_size()]).is_ok());
+        assert!(limit.check(&vec![0; limit.max_size() + 1]).is_err());
+        assert!(MessageSizeLimit::new::<SecurityLevel128>(100).max_size() > limit.max_size());
+    }
+
+    #[test]
+    fn ciphertext_range() {
+        let nn = Integer::from(35 * 35);
+        assert!(is_ciphertext_well_formed(&Integer::from(1), &nn));
+        assert!(is_ciphertext_well_formed(&(nn.clone() - 1u8), &nn));
+        assert!(!is_ciphertext_well_formed(&Integer::from(0), &nn));
+        assert!(!is_ciphertext_well_formed(&Integer::from(-1), &nn));
+        assert!(!is_ciphertext_well_formed(&nn, &nn));
+        assert!(!is_ciphertext_well_formed(&(nn.clone() << 1000u32), &nn));
+    }
+}
@@ -0,0 +1,83 @@
+//! Pluggable back-ends for Paillier secret-key operations
+//!
+//! During signing, each party decrypts ciphertexts $D_{i,j}$ and $\hat D_{i,j}$ received from
+//! other signers using its Paillier secret key. [`PaillierDecryptor`] abstracts this operation,
+//! so the decryption can be delegated to an external device (e.g. HSM) via
+//! [`SigningBuilder::set_paillier_decryptor`](crate::signing::SigningBuilder::set_paillier_decryptor).
+//! By default, signing decrypts with a key derived from the key share.
+//!
+//! With `pkcs11` feature enabled, [`pkcs11::Pkcs11Decryptor`] is provided which performs
+//! decryption via a PKCS#11 token.
+//!
+//! ## Limitations
+//! Decryption is the only secret-key operation performed by a signer in the happy path. However,
+//! the key share still must contain Paillier primes $p$, $q$: they're verified against the
+//! public modulus when key share is validated, and they're required to prove honesty when the
+//! protocol identifies a misbehaving party. Back-end must hold the same key as the key share,
+//! which is checked when the signing starts.
+
+use paillier_zk::{fast_paillier, rug::Integer};
+
+#[cfg(feature = "pkcs11")]
+pub mod pkcs11;
+
+/// Performs Paillier decryption
+pub trait PaillierDecryptor: Sync {
+    /// Public modulus $N$ of the Paillier key
+    fn n(&self) -> &Integer;
+    /// Decrypts a ciphertext
+    ///
+    /// Ciphertext is an integer in $\Z_{N^2}^*$. Returns plaintext in signed form, i.e. in
+    /// $\{-N/2, \dots, N/2\}$, same as [`fast_paillier::DecryptionKey::decrypt`] does.
+    fn decrypt(&self, ciphertext: &Integer) -> Result<Integer, DecryptionError>;
+}
+
+impl PaillierDecryptor for fast_paillier::DecryptionKey {
+    fn n(&self) -> &Integer {
+        fast_paillier::DecryptionKey::n(self)
+    }
+    fn decrypt(&self, ciphertext: &Integer) -> Result<Integer, DecryptionError> {
+        fast_paillier::DecryptionKey::decrypt(self, ciphertext)
+            .map_err(|_| Reason::InvalidCiphertext.into())
+    }
+}
+
+impl<T: PaillierDecryptor + ?Sized> PaillierDecryptor for &T {
+    fn n(&self) -> &Integer {
+        (**self).n()
+    }
+    fn decrypt(&self, ciphertext: &Integer) -> Result<Integer, DecryptionError> {
+        (**self).decrypt(ciphertext)
+    }
+}
+
+/// Error returned by [`PaillierDecryptor`]
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct DecryptionError(Reason);
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("ciphertext is not valid")]
+    InvalidCiphertext,
+    #[error("decryption back-end failed")]
+    Backend(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl DecryptionError {
+    /// Constructs an error indicating that ciphertext is not valid
+    pub fn invalid_ciphertext() -> Self {
+        Self(Reason::InvalidCiphertext)
+    }
+
+    /// Constructs an error indicating that decryption back-end failed
+    pub fn backend(err: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self(Reason::Backend(Box::new(err)))
+    }
+}
+
+crate::errors::impl_from! {
+    impl From for DecryptionError {
+        err: Reason => DecryptionError(err),
+    }
+}
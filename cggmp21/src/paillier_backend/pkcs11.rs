@@ -0,0 +1,115 @@
+//! Paillier decryption via PKCS#11 token
+//!
+//! PKCS#11 doesn't standardize a Paillier mechanism, so tokens supporting Paillier expose it as
+//! a vendor-defined mechanism. Its type needs to be looked up in the token documentation and
+//! provided to [`Pkcs11Decryptor::new`].
+//!
+//! Ciphertext is passed to the token as big-endian integer padded to the byte length of $N^2$.
+//! Token is expected to output plaintext as big-endian integer in $[0, N)$.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn f(
+//! #     session: cryptoki::session::Session,
+//! #     key: cryptoki::object::ObjectHandle,
+//! #     mechanism: cryptoki::mechanism::MechanismType,
+//! #     key_share: &cggmp21::KeyShare<cggmp21::supported_curves::Secp256k1>,
+//! # ) {
+//! use cggmp21::paillier_backend::pkcs11::Pkcs11Decryptor;
+//!
+//! let i = key_share.core.i;
+//! let N_i = key_share.aux.parties[usize::from(i)].N.clone();
+//! let decryptor = Pkcs11Decryptor::new(session, key, mechanism, N_i);
+//! # }
+//! ```
+
+use std::sync::Mutex;
+
+use cryptoki::mechanism::{vendor_defined::VendorDefinedMechanism, Mechanism, MechanismType};
+use cryptoki::object::ObjectHandle;
+use cryptoki::session::Session;
+use paillier_zk::rug::{integer::Order, Complete, Integer};
+
+use super::{DecryptionError, PaillierDecryptor};
+
+/// Performs Paillier decryption via PKCS#11 token
+///
+/// Secret key never leaves the token: it's referred by [`ObjectHandle`] within opened session.
+/// Session must be logged in and have access to the key.
+pub struct Pkcs11Decryptor {
+    session: Mutex<Session>,
+    key: ObjectHandle,
+    mechanism: MechanismType,
+    n: Integer,
+    nn_len: usize,
+}
+
+impl Pkcs11Decryptor {
+    /// Constructs a decryptor
+    ///
+    /// * `key` is a handle of Paillier secret key stored on the token
+    /// * `mechanism` is a vendor-defined mechanism performing Paillier decryption
+    /// * `n` is a public modulus of the key
+    pub fn new(session: Session, key: ObjectHandle, mechanism: MechanismType, n: Integer) -> Self {
+        let nn_len = (n.significant_bits() * 2).div_ceil(8) as usize;
+        Self {
+            session: Mutex::new(session),
+            key,
+            mechanism,
+            n,
+            nn_len,
+        }
+    }
+
+    /// Returns the session back
+    pub fn into_session(self) -> Session {
+        self.session
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl PaillierDecryptor for Pkcs11Decryptor {
+    fn n(&self) -> &Integer {
+        &self.n
+    }
+
+    fn decrypt(&self, ciphertext: &Integer) -> Result<Integer, DecryptionError> {
+        if ciphertext.cmp0().is_le()
+            || ciphertext.significant_bits() > 2 * self.n.significant_bits()
+        {
+            return Err(DecryptionError::invalid_ciphertext());
+        }
+        let digits = ciphertext.to_digits::<u8>(Order::Msf);
+        let mut encoded = vec![0u8; self.nn_len];
+        encoded[self.nn_len - digits.len()..].copy_from_slice(&digits);
+
+        let mechanism =
+            Mechanism::VendorDefined(VendorDefinedMechanism::new::<()>(self.mechanism, None));
+        let plaintext = self
+            .session
+            .lock()
+            .map_err(|_| DecryptionError::backend(PoisonedLock))?
+            .decrypt(&mechanism, self.key, &encoded)
+            .map_err(DecryptionError::backend)?;
+        let plaintext = Integer::from_digits(&plaintext, Order::Msf);
+        if plaintext >= self.n {
+            return Err(DecryptionError::backend(PlaintextOutOfRange));
+        }
+
+        // Convert plaintext to signed form
+        if (&plaintext << 1u32).complete() >= self.n {
+            Ok(plaintext - &self.n)
+        } else {
+            Ok(plaintext)
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("lock is poisoned")]
+struct PoisonedLock;
+
+#[derive(Debug, thiserror::Error)]
+#[error("token returned plaintext out of range")]
+struct PlaintextOutOfRange;
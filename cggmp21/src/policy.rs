@@ -0,0 +1,178 @@
+//! Key usage policy
+//!
+//! [`KeyUsagePolicy`] can be embedded into the [key share](crate::key_share::DirtyKeyShare::policy)
+//! to restrict what the key can be used for. Signing protocol evaluates the policy right before
+//! issuing the partial signature. If the policy is violated, the signer aborts the protocol and
+//! returns an error for which [`SigningError::is_policy_violation`] is `true`.
+//!
+//! Policy may restrict:
+//! * Curves the key may be used with
+//! * Messages that can be signed, by allowed prefixes (e.g. domain separation tags)
+//! * Messages that can be signed, by arbitrary callback (e.g. parse a transaction and check that
+//!   transferred value doesn't exceed a limit)
+//!
+//! Signing protocol only deals with hash of the message ([`DataToSign`]). When the policy
+//! restricts messages, the message itself must be provided via
+//! [`SigningBuilder::set_message`](crate::signing::SigningBuilder::set_message), or, when the
+//! message is [deferred](crate::signing::SigningBuilder::sign_with_deferred_message), via
+//! [`MessageSender::send_with_message`](crate::signing::MessageSender::send_with_message).
+//! Signer checks that the message hashes to the data to sign with the digest of the signing
+//! builder.
+//!
+//! Policy is not evaluated when partial signature is issued from a
+//! [`Presignature`](crate::signing::Presignature), since presignature is not bound to the key
+//! share.
+//!
+//! Callback can't be serialized. Instead, policy remembers that a callback is
+//! [required](KeyUsagePolicy::requires_message_check), so signing fails if the key share was
+//! deserialized and the callback wasn't set back via [`KeyUsagePolicy::set_message_check`].
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn f(key_share: cggmp21::KeyShare<cggmp21::supported_curves::Secp256k1>) -> Result<(), Box<dyn std::error::Error>> {
+//! use cggmp21::key_share::Validate;
+//! use cggmp21::policy::KeyUsagePolicy;
+//!
+//! let mut policy = KeyUsagePolicy::default();
+//! policy.allowed_prefixes.push(b"example.com/payments:".to_vec());
+//! policy.set_message_check(|msg| msg.len() <= 1024);
+//!
+//! let mut key_share = key_share.into_inner();
+//! key_share.policy = Some(policy);
+//! let key_share = key_share.validate().map_err(|e| e.into_error())?;
+//! # Ok(()) }
+//! ```
+//!
+//! [`SigningError::is_policy_violation`]: crate::signing::SigningError::is_policy_violation
+
+use std::sync::Arc;
+
+use digest::Digest;
+use generic_ec::Curve;
+#[cfg(feature = "serde")]
+use serde_with::As;
+
+use crate::signing::DataToSign;
+
+/// Callback deciding whether message is allowed to be signed
+pub type MessageCheck = Arc<dyn Fn(&[u8]) -> bool + Send + Sync>;
+
+/// Key usage policy
+///
+/// Default policy doesn't impose any restrictions. See [module-level docs](self) for details.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyUsagePolicy {
+    /// Names of curves the key may be used with
+    ///
+    /// If empty, any curve is allowed. Names are as in [`Curve::CURVE_NAME`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub allowed_curves: Vec<String>,
+    /// Allowed prefixes of messages
+    ///
+    /// If non-empty, only messages starting with one of the prefixes can be signed.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    #[cfg_attr(feature = "serde", serde(with = "As::<Vec<serde_with::Bytes>>"))]
+    pub allowed_prefixes: Vec<Vec<u8>>,
+    /// Indicates that messages must be approved by the [message check](Self::set_message_check)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub requires_message_check: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    message_check: Option<MessageCheck>,
+}
+
+impl KeyUsagePolicy {
+    /// Sets a callback which decides whether message is allowed to be signed
+    ///
+    /// Also sets [`requires_message_check`](Self::requires_message_check) flag.
+    pub fn set_message_check(&mut self, check: impl Fn(&[u8]) -> bool + Send + Sync + 'static) {
+        self.message_check = Some(Arc::new(check));
+        self.requires_message_check = true;
+    }
+
+    /// Returns message check, if it's set
+    pub fn message_check(&self) -> Option<&MessageCheck> {
+        self.message_check.as_ref()
+    }
+
+    /// Indicates whether the policy restricts messages, i.e. message needs to be known to
+    /// evaluate it
+    pub fn restricts_messages(&self) -> bool {
+        !self.allowed_prefixes.is_empty() || self.requires_message_check
+    }
+
+    /// Evaluates the policy
+    ///
+    /// `message` is the message which `data_to_sign` was obtained from by hashing it with `D`.
+    /// It's only required if [policy restricts messages](Self::restricts_messages).
+    pub fn check<E: Curve, D: Digest>(
+        &self,
+        data_to_sign: &DataToSign<E>,
+        message: Option<&[u8]>,
+    ) -> Result<(), PolicyViolation> {
+        if !self.allowed_curves.is_empty()
+            && !self.allowed_curves.iter().any(|c| c == E::CURVE_NAME)
+        {
+            return Err(Reason::CurveNotAllowed(E::CURVE_NAME).into());
+        }
+        if !self.restricts_messages() {
+            return Ok(());
+        }
+
+        let message = message.ok_or(Reason::MessageNotProvided)?;
+        if DataToSign::<E>::digest::<D>(message).to_scalar() != data_to_sign.to_scalar() {
+            return Err(Reason::MessageMismatch.into());
+        }
+        if !self.allowed_prefixes.is_empty()
+            && !self
+                .allowed_prefixes
+                .iter()
+                .any(|prefix| message.starts_with(prefix))
+        {
+            return Err(Reason::PrefixNotAllowed.into());
+        }
+        if self.requires_message_check {
+            let check = self
+                .message_check
+                .as_ref()
+                .ok_or(Reason::MessageCheckMissing)?;
+            if !check(message) {
+                return Err(Reason::RejectedByMessageCheck.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Error indicating that key usage policy is violated
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct PolicyViolation(Reason);
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("key is not allowed to be used with curve {0}")]
+    CurveNotAllowed(&'static str),
+    #[error("policy restricts messages, but message wasn't provided")]
+    MessageNotProvided,
+    #[error("provided message doesn't match data to sign")]
+    MessageMismatch,
+    #[error("message doesn't start with any of allowed prefixes")]
+    PrefixNotAllowed,
+    #[error("policy requires message check, but callback is not set")]
+    MessageCheckMissing,
+    #[error("message was rejected by message check")]
+    RejectedByMessageCheck,
+}
+
+crate::errors::impl_from! {
+    impl From for PolicyViolation {
+        err: Reason => PolicyViolation(err),
+    }
+}
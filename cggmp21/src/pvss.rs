@@ -0,0 +1,507 @@
+//! Publicly verifiable key generation
+//!
+//! Variant of threshold key generation where every party posts a single [`Dealing`] to a public
+//! bulletin board (e.g. a smart contract). Dealing contains Feldman commitment to the party's
+//! polynomial, shares of all parties encrypted under their Paillier keys, and proofs that every
+//! ciphertext encrypts a share consistent with the commitment. Anyone, not only participants, can
+//! [verify the dealings](verify_dealings) and obtain public key info of the generated key. Each
+//! party then [decrypts its shares](complete) to obtain a key share.
+//!
+//! Protocol requires public auxiliary data of the parties (Paillier keys and ring-Pedersen
+//! parameters) to be known in advance, i.e. [aux info generation](crate::aux_info_gen) needs to be
+//! carried out before key generation. Validity of public aux data itself is out of scope of this
+//! module: verifier must obtain it from a trusted source or check its proofs separately.
+//!
+//! Proof that ciphertext addressed to party $j$ is well-formed is done with respect to the
+//! ring-Pedersen parameters of party $j$. Party $j$ knows their trapdoor, so the proof convinces
+//! everyone as long as the dealer doesn't collude with the recipient, and such collusion doesn't
+//! give the dealer anything: it could disclose the recipient's share anyway.
+//!
+//! Differences from [interactive keygen](crate::keygen): generated key doesn't support HD wallets,
+//! and all dealings must be valid, otherwise protocol needs to be restarted without faulty parties.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn f<R: rand_core::RngCore + rand_core::CryptoRng>(
+//! #     rng: &mut R,
+//! #     aux_info: cggmp21::key_share::AuxInfo,
+//! #     i: u16,
+//! #     post: impl Fn(&cggmp21::pvss::Dealing<cggmp21::supported_curves::Secp256k1>),
+//! #     fetch: impl Fn() -> Vec<cggmp21::pvss::Dealing<cggmp21::supported_curves::Secp256k1>>,
+//! # ) -> Result<(), Box<dyn std::error::Error>> {
+//! use cggmp21::{security_level::SecurityLevel128, supported_curves::Secp256k1};
+//! use sha2::Sha256;
+//!
+//! let eid = cggmp21::ExecutionId::new(b"execution id, unique per protocol execution");
+//! let t = 2;
+//!
+//! let dealing = cggmp21::pvss::deal::<Secp256k1, SecurityLevel128, Sha256, _>(
+//!     rng,
+//!     eid,
+//!     i,
+//!     t,
+//!     &aux_info.parties,
+//! )?;
+//! post(&dealing);
+//!
+//! // Any observer can verify posted dealings
+//! let dealings = fetch();
+//! let key_info = cggmp21::pvss::verify_dealings::<Secp256k1, SecurityLevel128, Sha256>(
+//!     eid,
+//!     t,
+//!     &aux_info.parties,
+//!     &dealings,
+//! )?;
+//!
+//! // Participant decrypts its shares
+//! let key_share = cggmp21::pvss::complete::<Secp256k1, SecurityLevel128, Sha256>(
+//!     eid, t, i, aux_info, &dealings,
+//! )?;
+//! assert_eq!(key_share.shared_public_key, key_info.shared_public_key);
+//! # Ok(()) }
+//! ```
+
+use digest::Digest;
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use generic_ec_zkp::{polynomial::Polynomial, schnorr_pok};
+use paillier_zk::{
+    fast_paillier, group_element_vs_paillier_encryption_in_range as pi_log, rug::Integer,
+    IntegerExt,
+};
+use rand_core::{CryptoRng, RngCore};
+use round_based::PartyIndex;
+use thiserror::Error;
+
+use crate::{
+    key_share::{
+        AuxInfo, DirtyIncompleteKeyShare, DirtyKeyInfo, InvalidKeyShare, KeyInfo, KeyShare,
+        PartyAux, Validate, VssSetup,
+    },
+    security_level::SecurityLevel,
+    utils, ExecutionId,
+};
+
+/// Dealing posted by a party
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct Dealing<E: Curve> {
+    /// Index of the party who made the dealing
+    pub i: PartyIndex,
+    /// Feldman commitment to the polynomial $F_i(x) = f_i(x) \cdot G$
+    pub commitment: Polynomial<Point<E>>,
+    /// `ciphertexts[j]` is the share $f_i(j+1)$ encrypted under Paillier key of $j$-th party
+    pub ciphertexts: Vec<fast_paillier::Ciphertext>,
+    /// `proofs[j]` proves that `ciphertexts[j]` encrypts discrete logarithm of $F_i(j+1)$
+    pub proofs: Vec<(pi_log::Commitment<E>, pi_log::Proof)>,
+    /// Commitment of the proof of knowledge of $f_i(0)$
+    pub sch_commit: schnorr_pok::Commit<E>,
+    /// Proof of knowledge of $f_i(0)$
+    pub sch_proof: schnorr_pok::Proof<E>,
+}
+
+#[derive(udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.pvss.sch")]
+#[udigest(bound = "")]
+struct SchChallengeInput<'a, E: Curve> {
+    #[udigest(as_bytes)]
+    eid: &'a [u8],
+    i: PartyIndex,
+    commitment: &'a Polynomial<Point<E>>,
+    sch_commit: &'a schnorr_pok::Commit<E>,
+}
+
+#[derive(udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.pvss.enc")]
+#[udigest(bound = "")]
+struct EncProofContext<'a, E: Curve> {
+    #[udigest(as_bytes)]
+    eid: &'a [u8],
+    dealer: PartyIndex,
+    recipient: PartyIndex,
+    commitment: &'a Polynomial<Point<E>>,
+}
+
+/// Makes a dealing of $i$-th party
+///
+/// `parties` is public aux data of all parties (see
+/// [`AuxInfo::parties`](crate::key_share::DirtyAuxInfo::parties)), `t` is threshold of the
+/// generated key.
+pub fn deal<E, L, D, R>(
+    rng: &mut R,
+    eid: ExecutionId<'_>,
+    i: PartyIndex,
+    t: u16,
+    parties: &[PartyAux],
+) -> Result<Dealing<E>, DealingError>
+where
+    E: Curve,
+    L: SecurityLevel,
+    D: Digest + Clone,
+    R: RngCore + CryptoRng,
+{
+    let n = u16::try_from(parties.len()).map_err(|_| Reason::TooManyParties)?;
+    if !(2..=n).contains(&t) {
+        return Err(Reason::InvalidThreshold { t, n }.into());
+    }
+    if i >= n {
+        return Err(Reason::IndexOutOfBounds { i, n }.into());
+    }
+    let eid = eid.as_bytes();
+    let security_params = utils::SecurityParams::new::<L>();
+
+    let f = Polynomial::<SecretScalar<E>>::sample(rng, usize::from(t) - 1);
+    let commitment = &f * &Point::generator();
+
+    let mut ciphertexts = Vec::with_capacity(parties.len());
+    let mut proofs = Vec::with_capacity(parties.len());
+    for (j, aux_j) in (0u16..).zip(parties) {
+        let sigma: Scalar<E> = f.value(&Scalar::from(j + 1));
+        let sigma = utils::scalar_to_bignumber(sigma);
+        let enc_j = fast_paillier::EncryptionKey::from_n(aux_j.N.clone());
+        let nonce = Integer::gen_invertible(enc_j.n(), rng);
+        let ciphertext = enc_j
+            .encrypt_with(&sigma, &nonce)
+            .map_err(|_| Reason::Encrypt)?;
+        let X_j = commitment.value::<_, Point<E>>(&Scalar::from(j + 1));
+
+        let proof = pi_log::non_interactive::prove(
+            enc_proof_shared_state::<E, D>(eid, i, j, &commitment),
+            &aux_j.into(),
+            pi_log::Data {
+                key0: &enc_j,
+                c: &ciphertext,
+                x: &X_j,
+                b: &Point::<E>::generator().to_point(),
+            },
+            pi_log::PrivateData {
+                x: &sigma,
+                nonce: &nonce,
+            },
+            &security_params.pi_log,
+            &mut *rng,
+        )
+        .map_err(|_| Reason::Prove)?;
+
+        ciphertexts.push(ciphertext);
+        proofs.push(proof);
+    }
+
+    let (sch_secret, sch_commit) = schnorr_pok::prover_commits_ephemeral_secret::<E, _>(rng);
+    let challenge = sch_challenge::<E, D>(eid, i, &commitment, &sch_commit);
+    let sch_proof = schnorr_pok::prove(&sch_secret, &challenge, &f.coefs()[0]);
+
+    Ok(Dealing {
+        i,
+        commitment,
+        ciphertexts,
+        proofs,
+        sch_commit,
+        sch_proof,
+    })
+}
+
+impl<E: Curve> Dealing<E> {
+    /// Verifies a single dealing
+    ///
+    /// `D` and `L` must be the same as were used in [`deal`].
+    pub fn verify<L, D>(
+        &self,
+        eid: ExecutionId<'_>,
+        t: u16,
+        parties: &[PartyAux],
+    ) -> Result<(), InvalidDealings>
+    where
+        L: SecurityLevel,
+        D: Digest + Clone,
+    {
+        let faulty = || InvalidReason::Faulty(vec![self.i]);
+        let eid = eid.as_bytes();
+        if self.commitment.degree() + 1 != usize::from(t)
+            || self.ciphertexts.len() != parties.len()
+            || self.proofs.len() != parties.len()
+        {
+            return Err(faulty().into());
+        }
+
+        let challenge = sch_challenge::<E, D>(eid, self.i, &self.commitment, &self.sch_commit);
+        let public_coef = self.commitment.value::<_, Point<E>>(&Scalar::zero());
+        self.sch_proof
+            .verify(&self.sch_commit, &challenge, &public_coef)
+            .map_err(|_| faulty())?;
+
+        let security_params = utils::SecurityParams::new::<L>();
+        for ((j, aux_j), (ciphertext, (commit, proof))) in (0u16..)
+            .zip(parties)
+            .zip(self.ciphertexts.iter().zip(&self.proofs))
+        {
+            let enc_j = fast_paillier::EncryptionKey::from_n(aux_j.N.clone());
+            let X_j = self.commitment.value::<_, Point<E>>(&Scalar::from(j + 1));
+            pi_log::non_interactive::verify(
+                enc_proof_shared_state::<E, D>(eid, self.i, j, &self.commitment),
+                &aux_j.into(),
+                pi_log::Data {
+                    key0: &enc_j,
+                    c: ciphertext,
+                    x: &X_j,
+                    b: &Point::<E>::generator().to_point(),
+                },
+                commit,
+                &security_params.pi_log,
+                proof,
+            )
+            .map_err(|_| faulty())?;
+        }
+        Ok(())
+    }
+}
+
+/// Verifies dealings of all parties and returns public key info of the generated key
+///
+/// Can be called by anyone knowing public aux data of the parties. `dealings[j]` must be the
+/// dealing of $j$-th party.
+pub fn verify_dealings<E, L, D>(
+    eid: ExecutionId<'_>,
+    t: u16,
+    parties: &[PartyAux],
+    dealings: &[Dealing<E>],
+) -> Result<KeyInfo<E>, InvalidDealings>
+where
+    E: Curve,
+    L: SecurityLevel,
+    D: Digest + Clone,
+{
+    if dealings.len() != parties.len() {
+        return Err(InvalidReason::WrongAmount {
+            expected: parties.len(),
+            actual: dealings.len(),
+        }
+        .into());
+    }
+    let mut faulty = vec![];
+    for (j, dealing) in (0u16..).zip(dealings) {
+        if dealing.i != j || dealing.verify::<L, D>(eid, t, parties).is_err() {
+            faulty.push(j)
+        }
+    }
+    if !faulty.is_empty() {
+        return Err(InvalidReason::Faulty(faulty).into());
+    }
+
+    let polynomial_sum = dealings
+        .iter()
+        .map(|d| &d.commitment)
+        .sum::<Polynomial<_>>();
+    let shared_public_key = polynomial_sum.value::<_, Point<E>>(&Scalar::zero());
+    let n = u16::try_from(parties.len()).map_err(|_| InvalidReason::TooManyParties)?;
+    let public_shares = (1..=n)
+        .map(|j| NonZero::from_point(polynomial_sum.value(&Scalar::from(j))))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(InvalidReason::ZeroShare)?;
+    let I = (1..=n)
+        .map(|j| NonZero::from_scalar(Scalar::from(j)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(InvalidReason::ZeroShare)?;
+
+    DirtyKeyInfo {
+        curve: Default::default(),
+        shared_public_key: NonZero::from_point(shared_public_key)
+            .ok_or(InvalidReason::ZeroShare)?,
+        public_shares,
+        vss_setup: Some(VssSetup {
+            min_signers: t,
+            I,
+            weighted: None,
+            commitments: Some(polynomial_sum.coefs().to_vec()),
+        }),
+        #[cfg(feature = "hd-wallets")]
+        chain_code: None,
+    }
+    .validate()
+    .map_err(|_| InvalidReason::InvalidKeyInfo.into())
+}
+
+/// Verifies dealings and decrypts shares of $i$-th party, producing a key share
+///
+/// `aux_info` is aux info of $i$-th party. Dealings are verified the same way as in
+/// [`verify_dealings`].
+pub fn complete<E, L, D>(
+    eid: ExecutionId<'_>,
+    t: u16,
+    i: PartyIndex,
+    aux_info: AuxInfo<L>,
+    dealings: &[Dealing<E>],
+) -> Result<KeyShare<E, L>, CompleteError>
+where
+    E: Curve,
+    L: SecurityLevel,
+    D: Digest + Clone,
+{
+    let key_info = verify_dealings::<E, L, D>(eid, t, &aux_info.parties, dealings)?;
+    if usize::from(i) >= aux_info.parties.len() {
+        return Err(CompleteReason::IndexOutOfBounds.into());
+    }
+
+    let dec = aux_info
+        .decryption_key()
+        .map_err(CompleteReason::InvalidKeyShare)?;
+    let mut x = Scalar::<E>::zero();
+    for dealing in dealings {
+        let sigma = dec
+            .decrypt(&dealing.ciphertexts[usize::from(i)])
+            .map_err(|_| InvalidReason::Faulty(vec![dealing.i]))?
+            .to_scalar::<E>();
+        if Point::generator() * sigma
+            != dealing
+                .commitment
+                .value::<_, Point<E>>(&Scalar::from(i + 1))
+        {
+            return Err(InvalidDealings::from(InvalidReason::Faulty(vec![dealing.i])).into());
+        }
+        x += sigma;
+    }
+    let x =
+        NonZero::from_secret_scalar(SecretScalar::new(&mut x)).ok_or(CompleteReason::ZeroShare)?;
+
+    let core = DirtyIncompleteKeyShare {
+        i,
+        key_info: key_info.into_inner(),
+        x,
+        extra_x: Vec::new(),
+    }
+    .validate()
+    .map_err(|err| CompleteReason::InvalidKeyShare(err.into_error().into()))?;
+    KeyShare::from_parts((core, aux_info))
+        .map_err(|err| CompleteReason::InvalidKeyShare(err.into_error()).into())
+}
+
+fn sch_challenge<E: Curve, D: Digest>(
+    eid: &[u8],
+    i: PartyIndex,
+    commitment: &Polynomial<Point<E>>,
+    sch_commit: &schnorr_pok::Commit<E>,
+) -> schnorr_pok::Challenge<E> {
+    let hash = udigest::Tag::<D>::new("dfns.cggmp21.pvss").digest(SchChallengeInput {
+        eid,
+        i,
+        commitment,
+        sch_commit,
+    });
+    schnorr_pok::Challenge {
+        nonce: Scalar::from_be_bytes_mod_order(hash),
+    }
+}
+
+fn enc_proof_shared_state<E: Curve, D: Digest>(
+    eid: &[u8],
+    dealer: PartyIndex,
+    recipient: PartyIndex,
+    commitment: &Polynomial<Point<E>>,
+) -> utils::Truncated<D> {
+    let context = udigest::Tag::<D>::new("dfns.cggmp21.pvss").digest(EncProofContext {
+        eid,
+        dealer,
+        recipient,
+        commitment,
+    });
+    utils::Truncated::new(D::new_with_prefix(context))
+}
+
+/// Error indicating that dealing couldn't be made
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct DealingError(Reason);
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("too many parties")]
+    TooManyParties,
+    #[error("invalid threshold: t={t}, n={n}")]
+    InvalidThreshold { t: u16, n: u16 },
+    #[error("party index {i} is out of bounds (n={n})")]
+    IndexOutOfBounds { i: u16, n: u16 },
+    #[error("couldn't encrypt a share")]
+    Encrypt,
+    #[error("couldn't prove that share is encrypted correctly")]
+    Prove,
+}
+
+crate::errors::impl_from! {
+    impl From for DealingError {
+        err: Reason => DealingError(err),
+    }
+}
+
+/// Error indicating that dealings are not valid
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct InvalidDealings(InvalidReason);
+
+impl InvalidDealings {
+    /// Returns indexes of parties whose dealing is invalid
+    ///
+    /// Returns empty slice if error can't be attributed to parties.
+    pub fn blame(&self) -> &[PartyIndex] {
+        match &self.0 {
+            InvalidReason::Faulty(parties) => parties,
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+enum InvalidReason {
+    #[error("expected {expected} dealings, got {actual}")]
+    WrongAmount { expected: usize, actual: usize },
+    #[error("too many parties")]
+    TooManyParties,
+    #[error("dealings of parties {0:?} are invalid")]
+    Faulty(Vec<PartyIndex>),
+    #[error("resulting key has zero public share")]
+    ZeroShare,
+    #[error("resulting key info is invalid")]
+    InvalidKeyInfo,
+}
+
+crate::errors::impl_from! {
+    impl From for InvalidDealings {
+        err: InvalidReason => InvalidDealings(err),
+    }
+}
+
+/// Error indicating that key share couldn't be obtained from dealings
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct CompleteError(CompleteReason);
+
+impl CompleteError {
+    /// Returns indexes of parties whose dealing is invalid
+    ///
+    /// Returns empty slice if error can't be attributed to parties.
+    pub fn blame(&self) -> &[PartyIndex] {
+        match &self.0 {
+            CompleteReason::InvalidDealings(err) => err.blame(),
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+enum CompleteReason {
+    #[error("invalid dealings")]
+    InvalidDealings(#[source] InvalidDealings),
+    #[error("party index is out of bounds")]
+    IndexOutOfBounds,
+    #[error("secret share is zero")]
+    ZeroShare,
+    #[error("resulting key share is invalid")]
+    InvalidKeyShare(#[source] InvalidKeyShare),
+}
+
+crate::errors::impl_from! {
+    impl From for CompleteError {
+        err: CompleteReason => CompleteError(err),
+        err: InvalidDealings => CompleteError(CompleteReason::InvalidDealings(err)),
+        err: InvalidReason => CompleteError(CompleteReason::InvalidDealings(err.into())),
+    }
+}
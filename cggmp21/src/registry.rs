@@ -0,0 +1,282 @@
+//! Registry of key shares
+//!
+//! Services managing many MPC keys need to keep track of key shares, presignatures generated for
+//! each key, and key refreshes. [`KeyRegistry`] does the bookkeeping:
+//!
+//! * Key shares are looked up by [`KeyId`], which is the [fingerprint](KeyFingerprint) of the
+//!   shared public key, so all signers of the key refer to it by the same id
+//! * Each key has its own [pool of presignatures](KeyRegistry::presignatures)
+//! * Each key has a refresh [epoch](KeyEntry::epoch): a counter which is incremented every time
+//!   the key share is replaced with a [refreshed](KeyRegistry::refresh) one. Refresh discards
+//!   presignatures generated for the previous epoch, as they're bound to the old shares.
+//! * Bulk operations: [inserting](KeyRegistry::insert_many) many keys at once, listing keys which
+//!   [weren't refreshed](KeyRegistry::stale_keys) since given epoch, [retaining](KeyRegistry::retain)
+//!   keys matching the predicate
+//!
+//! Key shares which share the same aux info can be registered via
+//! [`insert_core`](KeyRegistry::insert_core), which binds [`SharedAuxInfo`] to the key share.
+//!
+//! Registry keeps everything in RAM. Note that key shares contain secrets, so registry must be
+//! treated as secret as well.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn f(key_shares: Vec<cggmp21::KeyShare<cggmp21::supported_curves::Secp256k1>>) -> Result<(), cggmp21::registry::RegistryError> {
+//! use cggmp21::registry::KeyRegistry;
+//!
+//! let registry = KeyRegistry::new();
+//! let ids = registry.insert_many(key_shares)?;
+//!
+//! let entry = registry.get(&ids[0]).expect("key is registered");
+//! let presignatures = registry.presignatures(&ids[0]).expect("key is registered");
+//! # let _ = (entry, presignatures);
+//! # Ok(()) }
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use generic_ec::Curve;
+
+use crate::key_share::{
+    IncompleteKeyShare, InvalidKeyShare, KeyFingerprint, SharedAuxInfo, UpgradeError,
+};
+use crate::security_level::SecurityLevel;
+use crate::signing::presignature_store::InMemoryPresignatureStore;
+use crate::KeyShare;
+
+/// Identifier of the key in the registry
+pub type KeyId = KeyFingerprint;
+
+/// Key registered in the [`KeyRegistry`]
+pub struct KeyEntry<E: Curve, L: SecurityLevel> {
+    /// Id of the key
+    pub id: KeyId,
+    /// Current key share
+    pub key_share: Arc<KeyShare<E, L>>,
+    /// Refresh epoch
+    ///
+    /// Key share registered for the first time has epoch `0`. Epoch is incremented each time key
+    /// share is refreshed.
+    pub epoch: u64,
+}
+
+impl<E: Curve, L: SecurityLevel> Clone for KeyEntry<E, L> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            key_share: self.key_share.clone(),
+            epoch: self.epoch,
+        }
+    }
+}
+
+struct Slot<E: Curve, L: SecurityLevel> {
+    entry: KeyEntry<E, L>,
+    presignatures: Arc<InMemoryPresignatureStore<E>>,
+}
+
+/// Registry of key shares
+///
+/// See [module-level docs](self) for details.
+pub struct KeyRegistry<E: Curve, L: SecurityLevel = crate::default_choice::SecurityLevel> {
+    keys: Mutex<BTreeMap<KeyId, Slot<E, L>>>,
+}
+
+impl<E: Curve, L: SecurityLevel> KeyRegistry<E, L> {
+    /// Constructs an empty registry
+    pub fn new() -> Self {
+        Self {
+            keys: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Registers a key share
+    ///
+    /// Returns error if the key is already registered. Use [`refresh`](Self::refresh) to replace
+    /// the key share of registered key.
+    pub fn insert(&self, key_share: KeyShare<E, L>) -> Result<KeyId, RegistryError> {
+        let id = KeyId::of(&key_share.core.key_info);
+        let mut keys = self.lock()?;
+        if keys.contains_key(&id) {
+            return Err(Reason::AlreadyRegistered(id).into());
+        }
+        keys.insert(
+            id,
+            Slot {
+                entry: KeyEntry {
+                    id,
+                    key_share: Arc::new(key_share),
+                    epoch: 0,
+                },
+                presignatures: Arc::new(InMemoryPresignatureStore::new()),
+            },
+        );
+        Ok(id)
+    }
+
+    /// Binds shared aux info to the key share and registers it
+    pub fn insert_core(
+        &self,
+        core: IncompleteKeyShare<E>,
+        aux: &SharedAuxInfo<L>,
+    ) -> Result<KeyId, RegistryError> {
+        let key_share = aux.bind(core).map_err(Reason::Bind)?;
+        self.insert(key_share)
+    }
+
+    /// Registers many key shares at once
+    ///
+    /// Either all key shares are registered, or none of them: if any of the keys is already
+    /// registered (or appears twice in the input), registry stays unmodified and error is returned.
+    pub fn insert_many(
+        &self,
+        key_shares: impl IntoIterator<Item = KeyShare<E, L>>,
+    ) -> Result<Vec<KeyId>, RegistryError> {
+        let key_shares = key_shares
+            .into_iter()
+            .map(|key_share| (KeyId::of(&key_share.core.key_info), key_share))
+            .collect::<Vec<_>>();
+
+        let mut keys = self.lock()?;
+        let mut new_keys = BTreeMap::new();
+        for (id, key_share) in key_shares {
+            if keys.contains_key(&id) || new_keys.contains_key(&id) {
+                return Err(Reason::AlreadyRegistered(id).into());
+            }
+            new_keys.insert(
+                id,
+                Slot {
+                    entry: KeyEntry {
+                        id,
+                        key_share: Arc::new(key_share),
+                        epoch: 0,
+                    },
+                    presignatures: Arc::new(InMemoryPresignatureStore::new()),
+                },
+            );
+        }
+        let ids = new_keys.keys().copied().collect();
+        keys.append(&mut new_keys);
+        Ok(ids)
+    }
+
+    /// Looks up the key by id
+    pub fn get(&self, id: &KeyId) -> Option<KeyEntry<E, L>> {
+        let keys = self.lock().ok()?;
+        keys.get(id).map(|slot| slot.entry.clone())
+    }
+
+    /// Returns pool of presignatures of the key
+    ///
+    /// Presignatures put into the pool must be generated using the key share of the current
+    /// [epoch](KeyEntry::epoch). Pool is replaced with an empty one when the key is refreshed,
+    /// so the pool returned by this method should not be retained across refreshes.
+    pub fn presignatures(&self, id: &KeyId) -> Option<Arc<InMemoryPresignatureStore<E>>> {
+        let keys = self.lock().ok()?;
+        keys.get(id).map(|slot| slot.presignatures.clone())
+    }
+
+    /// Replaces the key share with a refreshed one
+    ///
+    /// Refreshed key share must correspond to the same key (see [`ensure_same_key`]). Increments
+    /// the epoch of the key, and discards all presignatures of the previous epoch. Returns the
+    /// new epoch.
+    ///
+    /// [`ensure_same_key`]: crate::key_share::ensure_same_key
+    pub fn refresh(&self, id: &KeyId, key_share: KeyShare<E, L>) -> Result<u64, RegistryError> {
+        let mut keys = self.lock()?;
+        let slot = keys.get_mut(id).ok_or(Reason::UnknownKey(*id))?;
+        crate::key_share::ensure_same_key(&slot.entry.key_share, &key_share)
+            .map_err(Reason::KeyMismatch)?;
+
+        let epoch = slot
+            .entry
+            .epoch
+            .checked_add(1)
+            .ok_or(Reason::EpochOverflow)?;
+        slot.entry.key_share = Arc::new(key_share);
+        slot.entry.epoch = epoch;
+        slot.presignatures = Arc::new(InMemoryPresignatureStore::new());
+        Ok(epoch)
+    }
+
+    /// Removes the key from the registry
+    ///
+    /// Returns the removed entry, or `None` if key wasn't registered. Presignatures of the key
+    /// are discarded.
+    pub fn remove(&self, id: &KeyId) -> Option<KeyEntry<E, L>> {
+        let mut keys = self.lock().ok()?;
+        keys.remove(id).map(|slot| slot.entry)
+    }
+
+    /// Retains only keys for which `f` returns `true`, removes the rest
+    pub fn retain(&self, mut f: impl FnMut(&KeyEntry<E, L>) -> bool) -> Result<(), RegistryError> {
+        self.lock()?.retain(|_, slot| f(&slot.entry));
+        Ok(())
+    }
+
+    /// Returns ids of all registered keys
+    pub fn ids(&self) -> Result<Vec<KeyId>, RegistryError> {
+        Ok(self.lock()?.keys().copied().collect())
+    }
+
+    /// Returns ids of keys which epoch is less than `epoch`
+    ///
+    /// Can be used to find keys that need to be refreshed.
+    pub fn stale_keys(&self, epoch: u64) -> Result<Vec<KeyId>, RegistryError> {
+        Ok(self
+            .lock()?
+            .values()
+            .filter(|slot| slot.entry.epoch < epoch)
+            .map(|slot| slot.entry.id)
+            .collect())
+    }
+
+    /// Returns amount of registered keys
+    pub fn len(&self) -> Result<usize, RegistryError> {
+        Ok(self.lock()?.len())
+    }
+
+    /// Checks whether registry is empty
+    pub fn is_empty(&self) -> Result<bool, RegistryError> {
+        Ok(self.lock()?.is_empty())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, BTreeMap<KeyId, Slot<E, L>>>, RegistryError> {
+        self.keys.lock().map_err(|_| Reason::PoisonedLock.into())
+    }
+}
+
+impl<E: Curve, L: SecurityLevel> Default for KeyRegistry<E, L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Error returned by [`KeyRegistry`]
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct RegistryError(Reason);
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("key {0:?} is already registered")]
+    AlreadyRegistered(KeyId),
+    #[error("key {0:?} is not registered")]
+    UnknownKey(KeyId),
+    #[error("aux info can't be bound to the key share")]
+    Bind(#[source] InvalidKeyShare),
+    #[error("refreshed key share doesn't correspond to the registered key")]
+    KeyMismatch(#[source] UpgradeError),
+    #[error("epoch overflow")]
+    EpochOverflow,
+    #[error("lock is poisoned")]
+    PoisonedLock,
+}
+
+crate::errors::impl_from! {
+    impl From for RegistryError {
+        err: Reason => RegistryError(err),
+    }
+}
@@ -0,0 +1,146 @@
+//! Full rekey: rotation of the shared public key within the same committee
+//!
+//! [Key refresh](crate::key_refresh) updates key shares, but public key stays the same. Some
+//! policies require key rotation, i.e. replacing a key with a brand-new one. Full rekey is
+//! carried out by the same committee in a few steps:
+//!
+//! 1. Committee runs [keygen](crate::keygen) to generate a new key. Aux info of the old key can be
+//!    reused for the new key (see [`SharedAuxInfo`](crate::key_share::SharedAuxInfo)).
+//! 2. Parties build a [`HandoverStatement`] binding old and new public keys.
+//! 3. Committee signs the statement with the old key and with the new key.
+//! 4. [`SignedHandover`] is published, then parties erase shares of the old key.
+//!
+//! Signature of the old key attests that the key owner hands the control over to the new key,
+//! signature of the new key proves that the committee actually holds it. Anyone can
+//! [verify](SignedHandover::verify) the handover knowing only the old public key.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn f(
+//! #     old_key_share: cggmp21::KeyShare<cggmp21::supported_curves::Secp256k1>,
+//! #     new_key_share: cggmp21::KeyShare<cggmp21::supported_curves::Secp256k1>,
+//! #     sign: impl Fn(
+//! #         &cggmp21::KeyShare<cggmp21::supported_curves::Secp256k1>,
+//! #         &cggmp21::DataToSign<cggmp21::supported_curves::Secp256k1>,
+//! #     ) -> cggmp21::Signature<cggmp21::supported_curves::Secp256k1>,
+//! # ) -> Result<(), cggmp21::rekey::InvalidHandover> {
+//! use cggmp21::rekey::{HandoverStatement, SignedHandover};
+//!
+//! let statement = HandoverStatement::new(
+//!     b"rekey ceremony id",
+//!     &old_key_share,
+//!     &new_key_share,
+//! );
+//! let data_to_sign = statement.data_to_sign::<sha2::Sha256>();
+//! // Signatures are obtained by running signing protocol with old and new key shares
+//! let handover = SignedHandover {
+//!     old_key_signature: sign(&old_key_share, &data_to_sign),
+//!     new_key_signature: sign(&new_key_share, &data_to_sign),
+//!     statement,
+//! };
+//! handover.verify::<sha2::Sha256>()?;
+//! # Ok(()) }
+//! ```
+
+use digest::Digest;
+use generic_ec::{coords::AlwaysHasAffineX, Curve, NonZero, Point, Scalar};
+use thiserror::Error;
+
+use crate::{
+    key_share::DirtyKeyInfo,
+    signing::{DataToSign, Signature},
+};
+
+/// Statement that old key is replaced with a new one
+#[derive(Debug, Clone, PartialEq, Eq, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+#[udigest(bound = "")]
+#[udigest(tag = "dfns.cggmp21.rekey.handover")]
+pub struct HandoverStatement<E: Curve> {
+    /// Identifier of the rekey ceremony
+    #[cfg_attr(feature = "serde", serde(with = "hex"))]
+    #[udigest(as_bytes)]
+    pub ceremony_id: Vec<u8>,
+    /// Public key being retired
+    pub old_public_key: Point<E>,
+    /// Public key that replaces the old one
+    pub new_public_key: Point<E>,
+}
+
+impl<E: Curve> HandoverStatement<E> {
+    /// Constructs a statement
+    ///
+    /// `ceremony_id` must be unique for every rekey. Takes public key info of old and new keys,
+    /// key shares can be passed directly as they deref into it.
+    pub fn new(ceremony_id: &[u8], old_key: &DirtyKeyInfo<E>, new_key: &DirtyKeyInfo<E>) -> Self {
+        Self {
+            ceremony_id: ceremony_id.to_vec(),
+            old_public_key: *old_key.shared_public_key,
+            new_public_key: *new_key.shared_public_key,
+        }
+    }
+
+    /// Returns data to be signed by both keys
+    pub fn data_to_sign<D: Digest>(&self) -> DataToSign<E> {
+        let hash = udigest::Tag::<D>::new("dfns.cggmp21.rekey").digest(self);
+        DataToSign::from_scalar(Scalar::from_be_bytes_mod_order(hash))
+    }
+}
+
+/// Handover statement signed by old and new keys
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct SignedHandover<E: Curve> {
+    /// Statement
+    pub statement: HandoverStatement<E>,
+    /// Signature of the statement by the old key
+    pub old_key_signature: Signature<E>,
+    /// Signature of the statement by the new key
+    pub new_key_signature: Signature<E>,
+}
+
+impl<E: Curve> SignedHandover<E>
+where
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+{
+    /// Verifies both signatures
+    ///
+    /// `D` must be the same hash function that was used to obtain
+    /// [data to sign](HandoverStatement::data_to_sign).
+    pub fn verify<D: Digest>(&self) -> Result<(), InvalidHandover> {
+        if self.statement.old_public_key == self.statement.new_public_key {
+            return Err(Reason::SameKey.into());
+        }
+        let data_to_sign = self.statement.data_to_sign::<D>();
+        self.old_key_signature
+            .verify(&self.statement.old_public_key, &data_to_sign)
+            .map_err(|_| Reason::OldKeySignature)?;
+        self.new_key_signature
+            .verify(&self.statement.new_public_key, &data_to_sign)
+            .map_err(|_| Reason::NewKeySignature)?;
+        Ok(())
+    }
+}
+
+/// Error indicating that [`SignedHandover`] is not valid
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct InvalidHandover(Reason);
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("old and new public keys are the same")]
+    SameKey,
+    #[error("signature of the old key is invalid")]
+    OldKeySignature,
+    #[error("signature of the new key is invalid")]
+    NewKeySignature,
+}
+
+crate::errors::impl_from! {
+    impl From for InvalidHandover {
+        err: Reason => InvalidHandover(err),
+    }
+}
@@ -0,0 +1,496 @@
+//! Identity-bound DKG roster
+//!
+//! By default, party index is the only thing that identifies a party in the protocol. Roster
+//! assigns each party index to the long-term identity public key of the operator, so the
+//! resulting key provably belongs to a specific set of operators:
+//!
+//! 1. Parties agree on a [`Roster`]: `identities[i]` is the identity public key of $\ith$ party.
+//! 2. Parties run keygen with execution ID [bound](Roster::bind_execution_id) to the roster. Every
+//!    commitment and proof in the transcript is tied to execution ID, so it's tied to the roster as
+//!    well.
+//! 3. Each party [attests](Roster::attest) with its identity key that it holds $\ith$ share of the
+//!    generated key, and attestations are published.
+//! 4. Anyone can [verify](Roster::verify_attestations) that every operator in the roster
+//!    acknowledged its share of the key.
+//!
+//! Identity keys are Schnorr keys on the same curve as the generated key. They can also be used to
+//! [sign transcripts](Roster::sign_transcript) of the protocol, so the ceremony can be archived and
+//! re-verified later.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # async fn f(
+//! #     identities: Vec<generic_ec::NonZero<generic_ec::Point<cggmp21::supported_curves::Secp256k1>>>,
+//! #     identity_secret_key: generic_ec::SecretScalar<cggmp21::supported_curves::Secp256k1>,
+//! #     i: u16,
+//! #     party: impl round_based::Mpc<ProtocolMessage = cggmp21::keygen::ThresholdMsg<
+//! #         cggmp21::supported_curves::Secp256k1,
+//! #         cggmp21::security_level::SecurityLevel128,
+//! #         sha2::Sha256,
+//! #     >>,
+//! # ) -> Result<(), Box<dyn std::error::Error>> {
+//! use cggmp21::{roster::Roster, supported_curves::Secp256k1, ExecutionId};
+//! # let mut rng = rand::rngs::OsRng;
+//!
+//! let roster = Roster::<Secp256k1>::new(identities)?;
+//! let eid = roster.bind_execution_id(ExecutionId::new(b"execution id, unique per protocol execution"));
+//! let eid = ExecutionId::new(&eid);
+//! let n = roster.n();
+//!
+//! let key_share = cggmp21::keygen::<Secp256k1>(eid, i, n)
+//!     .set_threshold(2)
+//!     .start(&mut rng, party)
+//!     .await?;
+//! let attestation = roster.attest::<sha2::Sha256, _>(&mut rng, eid, &identity_secret_key, &key_share)?;
+//! // Attestations of all parties are published and verified by anyone
+//! # let attestations = [attestation];
+//! roster.verify_attestations::<sha2::Sha256>(eid, &key_share, &attestations)?;
+//! # Ok(()) }
+//! ```
+
+use digest::Digest;
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use generic_ec_zkp::schnorr_pok;
+use rand_core::{CryptoRng, RngCore};
+use round_based::PartyIndex;
+use thiserror::Error;
+
+use crate::{key_share::DirtyKeyInfo, transcript::Transcript, ExecutionId};
+
+/// List of identity public keys of the operators
+///
+/// `identities[i]` is the identity public key of $\ith$ party.
+#[derive(Debug, Clone, PartialEq, Eq, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound = "", try_from = "Vec<Point<E>>", into = "Vec<Point<E>>")
+)]
+#[udigest(bound = "")]
+#[udigest(tag = "dfns.cggmp21.roster")]
+pub struct Roster<E: Curve> {
+    identities: Vec<Point<E>>,
+}
+
+impl<E: Curve> TryFrom<Vec<Point<E>>> for Roster<E> {
+    type Error = InvalidRoster;
+    fn try_from(identities: Vec<Point<E>>) -> Result<Self, Self::Error> {
+        let identities = identities
+            .into_iter()
+            .map(NonZero::from_point)
+            .collect::<Option<Vec<_>>>()
+            .ok_or(RosterReason::ZeroIdentity)?;
+        Self::new(identities)
+    }
+}
+
+impl<E: Curve> From<Roster<E>> for Vec<Point<E>> {
+    fn from(roster: Roster<E>) -> Self {
+        roster.identities
+    }
+}
+
+/// Attestation of the party that it holds a share of the key generated with the roster
+///
+/// Produced by [`Roster::attest`]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct RosterAttestation<E: Curve> {
+    /// Index of the party
+    pub i: PartyIndex,
+    /// Commitment of the Schnorr signature
+    pub commit: schnorr_pok::Commit<E>,
+    /// Schnorr signature
+    pub proof: schnorr_pok::Proof<E>,
+}
+
+/// Transcript signed by identity key of the party who recorded it
+///
+/// Produced by [`Roster::sign_transcript`], verified by [`Roster::verify_transcript`]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "T: serde::Serialize",
+        deserialize = "T: serde::de::DeserializeOwned"
+    ))
+)]
+pub struct SignedTranscript<E: Curve, T> {
+    /// Transcript
+    pub transcript: Transcript<T>,
+    /// Commitment of the Schnorr signature
+    pub commit: schnorr_pok::Commit<E>,
+    /// Schnorr signature
+    pub proof: schnorr_pok::Proof<E>,
+}
+
+#[derive(udigest::Digestable)]
+#[udigest(bound = "")]
+struct AttestedStatement<'a, E: Curve> {
+    roster: &'a Roster<E>,
+    #[udigest(as_bytes)]
+    eid: &'a [u8],
+    i: PartyIndex,
+    shared_public_key: Point<E>,
+    public_share: Point<E>,
+    commit: &'a schnorr_pok::Commit<E>,
+}
+
+#[derive(udigest::Digestable)]
+#[udigest(bound = "")]
+struct TranscriptStatement<'a, E: Curve> {
+    roster: &'a Roster<E>,
+    i: PartyIndex,
+    #[udigest(as_bytes)]
+    transcript_hash: &'a [u8],
+    commit: &'a schnorr_pok::Commit<E>,
+}
+
+impl<E: Curve> Roster<E> {
+    /// Constructs a roster
+    ///
+    /// Returns error if roster is empty, too large, or contains the same identity more than once.
+    pub fn new(identities: Vec<NonZero<Point<E>>>) -> Result<Self, InvalidRoster> {
+        if identities.is_empty() {
+            return Err(RosterReason::Empty.into());
+        }
+        if u16::try_from(identities.len()).is_err() {
+            return Err(RosterReason::TooLarge.into());
+        }
+        if (0..identities.len()).any(|k| identities[..k].contains(&identities[k])) {
+            return Err(RosterReason::DuplicatedIdentity.into());
+        }
+        Ok(Self {
+            identities: identities.into_iter().map(|i| *i).collect(),
+        })
+    }
+
+    /// Returns identity public keys
+    ///
+    /// `identities()[i]` is the identity public key of $\ith$ party
+    pub fn identities(&self) -> &[Point<E>] {
+        &self.identities
+    }
+
+    /// Returns amount of parties in the roster
+    #[allow(clippy::expect_used)]
+    pub fn n(&self) -> u16 {
+        self.identities
+            .len()
+            .try_into()
+            .expect("roster size is checked on construction")
+    }
+
+    /// Returns index of the party with given identity
+    pub fn index_of(&self, identity: &Point<E>) -> Option<PartyIndex> {
+        let i = self.identities.iter().position(|id| id == identity)?;
+        i.try_into().ok()
+    }
+
+    /// Derives execution ID bound to the roster
+    ///
+    /// Keygen must be carried out with derived execution ID. Any change in the roster, including
+    /// assigning the same identities to different indexes, results into different execution ID.
+    pub fn bind_execution_id(&self, eid: ExecutionId) -> [u8; 32] {
+        #[derive(udigest::Digestable)]
+        #[udigest(bound = "")]
+        struct Binding<'a, E: Curve> {
+            #[udigest(as_bytes)]
+            eid: &'a [u8],
+            roster: &'a Roster<E>,
+        }
+        udigest::Tag::<sha2::Sha256>::new("dfns.cggmp21.roster.execution_id")
+            .digest(Binding {
+                eid: eid.as_bytes(),
+                roster: self,
+            })
+            .into()
+    }
+
+    /// Attests that local party holds a share of the key generated with the roster
+    ///
+    /// `eid` must be the execution ID that keygen was carried out with, i.e. the one obtained via
+    /// [`bind_execution_id`](Self::bind_execution_id). `identity_secret_key` must correspond to
+    /// identity public key of the party in the roster.
+    pub fn attest<D, R>(
+        &self,
+        rng: &mut R,
+        eid: ExecutionId,
+        identity_secret_key: &SecretScalar<E>,
+        key_share: &crate::key_share::DirtyIncompleteKeyShare<E>,
+    ) -> Result<RosterAttestation<E>, AttestError>
+    where
+        D: Digest,
+        R: RngCore + CryptoRng,
+    {
+        let i = key_share.i;
+        if key_share.public_shares.len() != self.identities.len() {
+            return Err(AttestReason::SizeMismatch.into());
+        }
+        let identity = self
+            .identities
+            .get(usize::from(i))
+            .ok_or(AttestReason::SizeMismatch)?;
+        if Point::generator() * identity_secret_key != *identity {
+            return Err(AttestReason::WrongIdentityKey.into());
+        }
+
+        let (secret, commit) = schnorr_pok::prover_commits_ephemeral_secret::<E, _>(rng);
+        let challenge = self.challenge::<D>(eid, i, key_share, &commit);
+        let proof = schnorr_pok::prove(&secret, &challenge, identity_secret_key);
+        Ok(RosterAttestation { i, commit, proof })
+    }
+
+    /// Verifies that every party in the roster attested it holds a share of the key
+    ///
+    /// `key_info` is public info of the generated key (key shares can be passed directly). `D` must
+    /// be the same hash function that was used in [`attest`](Self::attest). Attestations may come
+    /// in any order, but there must be exactly one attestation per party.
+    pub fn verify_attestations<D: Digest>(
+        &self,
+        eid: ExecutionId,
+        key_info: &DirtyKeyInfo<E>,
+        attestations: &[RosterAttestation<E>],
+    ) -> Result<(), InvalidRosterAttestations> {
+        if key_info.public_shares.len() != self.identities.len() {
+            return Err(InvalidReason::SizeMismatch.into());
+        }
+
+        let mut attested = vec![false; self.identities.len()];
+        let mut faulty = vec![];
+        for attestation in attestations {
+            let i = attestation.i;
+            let Some(attested_i) = attested.get_mut(usize::from(i)) else {
+                return Err(InvalidReason::IndexOutOfBounds(i).into());
+            };
+            if *attested_i {
+                return Err(InvalidReason::Duplicate(i).into());
+            }
+            *attested_i = true;
+
+            let challenge = self.challenge::<D>(eid, i, key_info, &attestation.commit);
+            if attestation
+                .proof
+                .verify(
+                    &attestation.commit,
+                    &challenge,
+                    &self.identities[usize::from(i)],
+                )
+                .is_err()
+            {
+                faulty.push(i);
+            }
+        }
+        faulty.extend(
+            (0u16..)
+                .zip(&attested)
+                .filter(|(_, attested)| !**attested)
+                .map(|(j, _)| j),
+        );
+
+        if !faulty.is_empty() {
+            faulty.sort_unstable();
+            return Err(InvalidReason::Faulty(faulty).into());
+        }
+        Ok(())
+    }
+
+    /// Signs transcript recorded by local party with its identity key
+    ///
+    /// Transcript can be obtained by running the protocol via `start_with_transcript` (e.g.
+    /// [keygen](crate::keygen::GenericKeygenBuilder::start_with_transcript)).
+    /// `identity_secret_key` must correspond to identity of the party who recorded the transcript.
+    pub fn sign_transcript<D, R, T>(
+        &self,
+        rng: &mut R,
+        identity_secret_key: &SecretScalar<E>,
+        transcript: Transcript<T>,
+    ) -> Result<SignedTranscript<E, T>, AttestError>
+    where
+        D: Digest,
+        R: RngCore + CryptoRng,
+        T: udigest::Digestable,
+    {
+        let i = transcript.i;
+        let identity = self
+            .identities
+            .get(usize::from(i))
+            .ok_or(AttestReason::SizeMismatch)?;
+        if Point::generator() * identity_secret_key != *identity {
+            return Err(AttestReason::WrongIdentityKey.into());
+        }
+
+        let (secret, commit) = schnorr_pok::prover_commits_ephemeral_secret::<E, _>(rng);
+        let challenge = self.transcript_challenge::<D>(i, &transcript.hash::<D>(), &commit);
+        let proof = schnorr_pok::prove(&secret, &challenge, identity_secret_key);
+        Ok(SignedTranscript {
+            transcript,
+            commit,
+            proof,
+        })
+    }
+
+    /// Verifies that transcript is signed by the party who recorded it
+    ///
+    /// `D` must be the same hash function that was used in
+    /// [`sign_transcript`](Self::sign_transcript). Returns hash of the transcript that can be
+    /// compared with transcripts of other parties.
+    pub fn verify_transcript<D, T>(
+        &self,
+        signed: &SignedTranscript<E, T>,
+    ) -> Result<digest::Output<D>, InvalidSignedTranscript>
+    where
+        D: Digest,
+        T: udigest::Digestable,
+    {
+        let i = signed.transcript.i;
+        let identity = self
+            .identities
+            .get(usize::from(i))
+            .ok_or(InvalidTranscriptReason::IndexOutOfBounds(i))?;
+        let hash = signed.transcript.hash::<D>();
+        let challenge = self.transcript_challenge::<D>(i, &hash, &signed.commit);
+        signed
+            .proof
+            .verify(&signed.commit, &challenge, identity)
+            .map_err(|_| InvalidTranscriptReason::InvalidSignature)?;
+        Ok(hash)
+    }
+
+    fn transcript_challenge<D: Digest>(
+        &self,
+        i: PartyIndex,
+        transcript_hash: &[u8],
+        commit: &schnorr_pok::Commit<E>,
+    ) -> schnorr_pok::Challenge<E> {
+        let hash =
+            udigest::Tag::<D>::new("dfns.cggmp21.roster.transcript").digest(TranscriptStatement {
+                roster: self,
+                i,
+                transcript_hash,
+                commit,
+            });
+        schnorr_pok::Challenge {
+            nonce: Scalar::from_be_bytes_mod_order(hash),
+        }
+    }
+
+    fn challenge<D: Digest>(
+        &self,
+        eid: ExecutionId,
+        i: PartyIndex,
+        key_info: &DirtyKeyInfo<E>,
+        commit: &schnorr_pok::Commit<E>,
+    ) -> schnorr_pok::Challenge<E> {
+        let hash =
+            udigest::Tag::<D>::new("dfns.cggmp21.roster.attestation").digest(AttestedStatement {
+                roster: self,
+                eid: eid.as_bytes(),
+                i,
+                shared_public_key: *key_info.shared_public_key,
+                public_share: *key_info.public_shares[usize::from(i)],
+                commit,
+            });
+        schnorr_pok::Challenge {
+            nonce: Scalar::from_be_bytes_mod_order(hash),
+        }
+    }
+}
+
+/// Error indicating that roster is not valid
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct InvalidRoster(RosterReason);
+
+#[derive(Debug, Error)]
+enum RosterReason {
+    #[error("roster is empty")]
+    Empty,
+    #[error("roster is too large")]
+    TooLarge,
+    #[error("identity public key is zero")]
+    ZeroIdentity,
+    #[error("roster contains the same identity more than once")]
+    DuplicatedIdentity,
+}
+
+/// Error indicating that attestation couldn't be produced
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct AttestError(AttestReason);
+
+#[derive(Debug, Error)]
+enum AttestReason {
+    #[error("amount of parties sharing the key doesn't match roster size")]
+    SizeMismatch,
+    #[error("identity secret key doesn't match identity of the party in the roster")]
+    WrongIdentityKey,
+}
+
+/// Error indicating that roster attestations are not valid
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct InvalidRosterAttestations(InvalidReason);
+
+impl InvalidRosterAttestations {
+    /// Returns indexes of parties whose attestation is missing or invalid
+    ///
+    /// Returns empty slice if attestations can't be attributed to parties.
+    pub fn blame(&self) -> &[PartyIndex] {
+        match &self.0 {
+            InvalidReason::Faulty(parties) => parties,
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+enum InvalidReason {
+    #[error("amount of parties sharing the key doesn't match roster size")]
+    SizeMismatch,
+    #[error("party index {0} is out of bounds")]
+    IndexOutOfBounds(PartyIndex),
+    #[error("party {0} provided more than one attestation")]
+    Duplicate(PartyIndex),
+    #[error("attestations of parties {0:?} are missing or invalid")]
+    Faulty(Vec<PartyIndex>),
+}
+
+/// Error indicating that transcript signature is not valid
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct InvalidSignedTranscript(InvalidTranscriptReason);
+
+#[derive(Debug, Error)]
+enum InvalidTranscriptReason {
+    #[error("party index {0} is out of bounds")]
+    IndexOutOfBounds(PartyIndex),
+    #[error("signature is invalid")]
+    InvalidSignature,
+}
+
+crate::errors::impl_from! {
+    impl From for InvalidSignedTranscript {
+        err: InvalidTranscriptReason => InvalidSignedTranscript(err),
+    }
+}
+
+crate::errors::impl_from! {
+    impl From for InvalidRoster {
+        err: RosterReason => InvalidRoster(err),
+    }
+}
+
+crate::errors::impl_from! {
+    impl From for AttestError {
+        err: AttestReason => AttestError(err),
+    }
+}
+
+crate::errors::impl_from! {
+    impl From for InvalidRosterAttestations {
+        err: InvalidReason => InvalidRosterAttestations(err),
+    }
+}
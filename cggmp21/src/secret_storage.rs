@@ -0,0 +1,285 @@
+//! Pluggable storage of secret components of the key share
+//!
+//! Key share contains a few secrets: share of the key $x$ (and extra shares, if key is weighted)
+//! and primes $p$, $q$ of Paillier secret key. [`SecretStorage`] allows keeping them in external
+//! storage (e.g. secure element or an encrypted database), so only a [`SealedKeyShare`] holding
+//! a handle and public data of the key share is kept along with the application. Secrets are
+//! loaded on demand, when the protocol is about to be run, via [`SealedKeyShare::unseal`].
+//!
+//! [`InMemorySecretStorage`] is provided out of box, mainly for testing purposes. With `tpm`
+//! feature enabled, [`tpm::TpmSecretStorage`] is available which seals secrets to a TPM.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn f<E: generic_ec::Curve>(key_share: cggmp21::KeyShare<E>) -> Result<(), cggmp21::secret_storage::SecretStorageError> {
+//! use cggmp21::secret_storage::{InMemorySecretStorage, SealedKeyShare};
+//!
+//! let storage = InMemorySecretStorage::new();
+//! let sealed = SealedKeyShare::seal(key_share, &storage)?;
+//! // ... later, when the key share is needed for signing
+//! let key_share: cggmp21::KeyShare<E> = sealed.unseal(&storage)?;
+//! # Ok(()) }
+//! ```
+
+use std::collections::HashMap;
+
+use generic_ec::{Curve, NonZero, SecretScalar};
+use paillier_zk::rug::Integer;
+
+use crate::key_share::{
+    AuxKeyBinding, DirtyAuxInfo, DirtyIncompleteKeyShare, DirtyKeyInfo, DirtyKeyShare,
+    InvalidKeyShare, KeyShare, PartyAux, Validate,
+};
+use crate::policy::KeyUsagePolicy;
+use crate::security_level::SecurityLevel;
+
+#[cfg(feature = "tpm")]
+pub mod tpm;
+
+/// Secret components of the key share
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct SecretComponents<E: Curve> {
+    /// Secret share $x_i$
+    pub x: NonZero<SecretScalar<E>>,
+    /// Extra secret shares, if key is weighted
+    pub extra_x: Vec<NonZero<SecretScalar<E>>>,
+    /// Secret prime $p$
+    pub p: Integer,
+    /// Secret prime $q$
+    pub q: Integer,
+}
+
+/// Storage of secret components of key shares
+///
+/// [`InMemorySecretStorage`] is provided out of box. Back-ends that are backed by secure elements
+/// or databases can use [`SecretStorageError::backend`] to report a failure.
+pub trait SecretStorage<E: Curve> {
+    /// Handle referring to the stored secrets
+    type Handle: Clone;
+
+    /// Stores secrets, returns a handle that can be used to load them later
+    fn store(&self, secrets: SecretComponents<E>) -> Result<Self::Handle, SecretStorageError>;
+    /// Loads secrets
+    ///
+    /// Returns [unknown handle](SecretStorageError::unknown_handle) error if storage doesn't
+    /// have secrets for this handle.
+    fn load(&self, handle: &Self::Handle) -> Result<SecretComponents<E>, SecretStorageError>;
+    /// Erases secrets from the storage
+    fn erase(&self, handle: &Self::Handle) -> Result<(), SecretStorageError>;
+}
+
+impl<E: Curve, S: SecretStorage<E> + ?Sized> SecretStorage<E> for &S {
+    type Handle = S::Handle;
+
+    fn store(&self, secrets: SecretComponents<E>) -> Result<Self::Handle, SecretStorageError> {
+        (**self).store(secrets)
+    }
+    fn load(&self, handle: &Self::Handle) -> Result<SecretComponents<E>, SecretStorageError> {
+        (**self).load(handle)
+    }
+    fn erase(&self, handle: &Self::Handle) -> Result<(), SecretStorageError> {
+        (**self).erase(handle)
+    }
+}
+
+/// Key share with secret components kept in [`SecretStorage`]
+///
+/// Contains only public data of the key share and a handle to the secrets, so it can be
+/// persisted along with the application as long as the handle is serializable.
+///
+/// Note that [CRT parameters](PartyAux::crt) are secret and therefore are not kept in the sealed
+/// key share. They can be [recomputed](DirtyKeyShare::precompute_crt) after unsealing.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "H: serde::Serialize",
+        deserialize = "H: serde::Deserialize<'de>"
+    ))
+)]
+pub struct SealedKeyShare<E: Curve, H, L: SecurityLevel = crate::default_choice::SecurityLevel> {
+    /// Index of local party $i$
+    pub i: u16,
+    /// Public key info
+    pub key_info: DirtyKeyInfo<E>,
+    /// Public auxiliary data of all parties sharing the key
+    pub parties: Vec<PartyAux>,
+    /// Binding of aux info to the key
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub key_binding: Option<AuxKeyBinding>,
+    /// Key usage policy
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub policy: Option<KeyUsagePolicy>,
+    /// Handle to the secret components
+    pub handle: H,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _security_level: std::marker::PhantomData<L>,
+}
+
+impl<E: Curve, H: Clone, L: SecurityLevel> SealedKeyShare<E, H, L> {
+    /// Moves secrets of the key share into the storage
+    pub fn seal<S>(key_share: KeyShare<E, L>, storage: &S) -> Result<Self, SecretStorageError>
+    where
+        S: SecretStorage<E, Handle = H> + ?Sized,
+    {
+        let DirtyKeyShare { core, aux, policy } = key_share.into_inner();
+        let handle = storage.store(SecretComponents {
+            x: core.x,
+            extra_x: core.extra_x,
+            p: aux.p,
+            q: aux.q,
+        })?;
+        Ok(Self {
+            i: core.i,
+            key_info: core.key_info,
+            parties: aux
+                .parties
+                .into_iter()
+                .map(|aux_j| PartyAux { crt: None, ..aux_j })
+                .collect(),
+            key_binding: aux.key_binding,
+            policy,
+            handle,
+            _security_level: std::marker::PhantomData,
+        })
+    }
+
+    /// Loads secrets from the storage, returns complete key share
+    ///
+    /// Secrets stay in the storage. Returned key share must be dropped as soon as it's not
+    /// needed anymore.
+    pub fn unseal<S>(&self, storage: &S) -> Result<KeyShare<E, L>, SecretStorageError>
+    where
+        S: SecretStorage<E, Handle = H> + ?Sized,
+    {
+        let secrets = storage.load(&self.handle)?;
+        DirtyKeyShare {
+            core: DirtyIncompleteKeyShare {
+                i: self.i,
+                key_info: self.key_info.clone(),
+                x: secrets.x,
+                extra_x: secrets.extra_x,
+            },
+            aux: DirtyAuxInfo {
+                p: secrets.p,
+                q: secrets.q,
+                parties: self.parties.clone(),
+                key_binding: self.key_binding,
+                security_level: std::marker::PhantomData,
+                decryption_key_cache: Default::default(),
+            },
+            policy: self.policy.clone(),
+        }
+        .validate()
+        .map_err(|err| SecretStorageReason::InvalidKeyShare(err.into_error()).into())
+    }
+}
+
+/// In-memory storage of secrets
+///
+/// Keeps secrets in RAM, so they're lost when program exits.
+pub struct InMemorySecretStorage<E: Curve> {
+    secrets: std::sync::Mutex<(u64, HashMap<u64, SecretComponents<E>>)>,
+}
+
+impl<E: Curve> InMemorySecretStorage<E> {
+    /// Constructs an empty storage
+    pub fn new() -> Self {
+        Self {
+            secrets: Default::default(),
+        }
+    }
+}
+
+impl<E: Curve> Default for InMemorySecretStorage<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Curve> SecretStorage<E> for InMemorySecretStorage<E> {
+    type Handle = u64;
+
+    fn store(&self, secrets: SecretComponents<E>) -> Result<u64, SecretStorageError> {
+        let mut guard = self
+            .secrets
+            .lock()
+            .map_err(|_| SecretStorageError::backend(PoisonedLock))?;
+        let (next_handle, stored) = &mut *guard;
+        let handle = *next_handle;
+        *next_handle += 1;
+        stored.insert(handle, secrets);
+        Ok(handle)
+    }
+
+    fn load(&self, handle: &u64) -> Result<SecretComponents<E>, SecretStorageError> {
+        self.secrets
+            .lock()
+            .map_err(|_| SecretStorageError::backend(PoisonedLock))?
+            .1
+            .get(handle)
+            .cloned()
+            .ok_or_else(SecretStorageError::unknown_handle)
+    }
+
+    fn erase(&self, handle: &u64) -> Result<(), SecretStorageError> {
+        self.secrets
+            .lock()
+            .map_err(|_| SecretStorageError::backend(PoisonedLock))?
+            .1
+            .remove(handle)
+            .map(|_| ())
+            .ok_or_else(SecretStorageError::unknown_handle)
+    }
+}
+
+/// Error returned by [`SecretStorage`]
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct SecretStorageError(SecretStorageReason);
+
+#[derive(Debug, thiserror::Error)]
+enum SecretStorageReason {
+    #[error("storage doesn't have secrets for provided handle")]
+    UnknownHandle,
+    #[error("storage back-end failed")]
+    Backend(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("secrets loaded from the storage don't match the key share")]
+    InvalidKeyShare(#[source] InvalidKeyShare),
+}
+
+impl SecretStorageError {
+    /// Constructs an error indicating that storage doesn't have secrets for provided handle
+    pub fn unknown_handle() -> Self {
+        Self(SecretStorageReason::UnknownHandle)
+    }
+
+    /// Constructs an error indicating that storage back-end failed
+    pub fn backend(err: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self(SecretStorageReason::Backend(Box::new(err)))
+    }
+
+    /// Indicates whether storage doesn't have secrets for provided handle
+    pub fn is_unknown_handle(&self) -> bool {
+        matches!(self.0, SecretStorageReason::UnknownHandle)
+    }
+}
+
+crate::errors::impl_from! {
+    impl From for SecretStorageError {
+        err: SecretStorageReason => SecretStorageError(err),
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("lock is poisoned")]
+struct PoisonedLock;
@@ -0,0 +1,314 @@
+//! Secret storage sealing secrets to a TPM
+//!
+//! [`TpmSecretStorage`] encrypts secret components of the key share with XChaCha20-Poly1305
+//! under a random data key. Data key is sealed to the TPM: it's stored in a keyed-hash object
+//! under the storage primary key of the owner hierarchy, and its unsealing is bound to the
+//! policy requiring PCRs to have the same values as at the moment of sealing. Secrets thus can
+//! only be loaded on the same device and in the same boot state (e.g. with the same firmware
+//! and bootloader, depending on the selected PCRs).
+//!
+//! The handle ([`TpmSealedSecrets`]) is self-contained and is not secret: it can be persisted
+//! along with [`SealedKeyShare`](super::SealedKeyShare), for instance, on disk. Storage itself
+//! doesn't keep any state, so [erasing](SecretStorage::erase) secrets is a no-op: secrets are
+//! gone once the handle is deleted.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn f<E: generic_ec::Curve>(key_share: cggmp21::KeyShare<E>) -> Result<(), Box<dyn std::error::Error>> {
+//! use cggmp21::secret_storage::{tpm::TpmSecretStorage, SealedKeyShare};
+//! use tss_esapi::{
+//!     interface_types::algorithm::HashingAlgorithm,
+//!     structures::{PcrSelectionList, PcrSlot},
+//!     tcti_ldr::TctiNameConf,
+//! };
+//!
+//! let context = tss_esapi::Context::new(TctiNameConf::from_environment_variable()?)?;
+//! let pcrs = PcrSelectionList::builder()
+//!     .with_selection(HashingAlgorithm::Sha256, &[PcrSlot::Slot0, PcrSlot::Slot7])
+//!     .build()?;
+//! let storage = TpmSecretStorage::new(context, pcrs);
+//!
+//! let sealed = SealedKeyShare::seal(key_share, &storage)?;
+//! let sealed = serde_json::to_vec(&sealed)?;
+//! // ... later
+//! let sealed: SealedKeyShare<E, _> = serde_json::from_slice(&sealed)?;
+//! let key_share: cggmp21::KeyShare<E> = sealed.unseal(&storage)?;
+//! # Ok(()) }
+//! ```
+
+use std::sync::Mutex;
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use generic_ec::Curve;
+use serde::{Deserialize, Serialize};
+use tss_esapi::{
+    attributes::ObjectAttributesBuilder,
+    constants::SessionType,
+    handles::{KeyHandle, ObjectHandle, SessionHandle},
+    interface_types::{
+        algorithm::{HashingAlgorithm, PublicAlgorithm},
+        key_bits::RsaKeyBits,
+        resource_handles::Hierarchy,
+        session_handles::{AuthSession, PolicySession},
+    },
+    structures::{
+        Digest, KeyedHashScheme, PcrSelectionList, Private, Public, PublicBuilder,
+        PublicKeyedHashParameters, RsaExponent, SensitiveData, SymmetricDefinition,
+        SymmetricDefinitionObject,
+    },
+    traits::{Marshall, UnMarshall},
+    Context,
+};
+use zeroize::Zeroizing;
+
+use super::{SecretComponents, SecretStorage, SecretStorageError};
+
+const VERSION: u8 = 1;
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+const AAD: &[u8] = b"dfns.cggmp21.secret_storage.tpm";
+
+/// Secret storage sealing secrets to a TPM
+///
+/// See [module-level docs](self) for details.
+pub struct TpmSecretStorage {
+    context: Mutex<Context>,
+    pcrs: PcrSelectionList,
+}
+
+/// Secrets sealed to the TPM
+///
+/// Handle returned by [`TpmSecretStorage`]
+#[serde_with::serde_as]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TpmSealedSecrets {
+    /// Version of the format
+    pub version: u8,
+    /// Public area of the sealed data key object, marshalled as `TPM2B_PUBLIC`
+    #[serde_as(as = "serde_with::Bytes")]
+    pub public: Vec<u8>,
+    /// Private area of the sealed data key object, as returned by the TPM
+    #[serde_as(as = "serde_with::Bytes")]
+    pub private: Vec<u8>,
+    /// `nonce (24 bytes) || encryption of secret components serialized to JSON`
+    #[serde_as(as = "serde_with::Bytes")]
+    pub ciphertext: Vec<u8>,
+}
+
+impl TpmSecretStorage {
+    /// Constructs a storage
+    ///
+    /// `pcrs` specifies PCRs which values the sealed secrets are bound to
+    pub fn new(context: Context, pcrs: PcrSelectionList) -> Self {
+        Self {
+            context: Mutex::new(context),
+            pcrs,
+        }
+    }
+
+    /// Returns TPM context back
+    pub fn into_context(self) -> Context {
+        self.context
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn seal_data_key(
+        &self,
+        ctx: &mut Context,
+    ) -> Result<(Zeroizing<Vec<u8>>, Private, Public), tss_esapi::Error> {
+        let data_key = Zeroizing::new(ctx.get_random(KEY_LEN)?.value().to_vec());
+
+        let policy_digest = {
+            let session = start_policy_session(ctx, SessionType::Trial)?;
+            let digest = ctx
+                .policy_pcr(session, Digest::default(), self.pcrs.clone())
+                .and_then(|()| ctx.policy_get_digest(session));
+            flush_session(ctx, session)?;
+            digest?
+        };
+
+        let attributes = ObjectAttributesBuilder::new()
+            .with_fixed_tpm(true)
+            .with_fixed_parent(true)
+            .with_no_da(true)
+            .with_user_with_auth(false)
+            .build()?;
+        let public = PublicBuilder::new()
+            .with_public_algorithm(PublicAlgorithm::KeyedHash)
+            .with_name_hashing_algorithm(HashingAlgorithm::Sha256)
+            .with_object_attributes(attributes)
+            .with_auth_policy(policy_digest)
+            .with_keyed_hash_parameters(PublicKeyedHashParameters::new(KeyedHashScheme::Null))
+            .with_keyed_hash_unique_identifier(Digest::default())
+            .build()?;
+
+        let primary = create_primary(ctx)?;
+        let sealed = ctx.execute_with_nullauth_session(|ctx| {
+            ctx.create(
+                primary,
+                public,
+                None,
+                Some(SensitiveData::try_from(data_key.to_vec())?),
+                None,
+                None,
+            )
+        });
+        ctx.flush_context(ObjectHandle::from(primary))?;
+        let sealed = sealed?;
+
+        Ok((data_key, sealed.out_private, sealed.out_public))
+    }
+
+    fn unseal_data_key(
+        &self,
+        ctx: &mut Context,
+        private: Private,
+        public: Public,
+    ) -> Result<Zeroizing<Vec<u8>>, tss_esapi::Error> {
+        let primary = create_primary(ctx)?;
+        let object = ctx.execute_with_nullauth_session(|ctx| ctx.load(primary, private, public));
+        ctx.flush_context(ObjectHandle::from(primary))?;
+        let object = object?;
+
+        let data_key = start_policy_session(ctx, SessionType::Policy).and_then(|session| {
+            let data_key = ctx
+                .policy_pcr(session, Digest::default(), self.pcrs.clone())
+                .and_then(|()| {
+                    ctx.execute_with_session(Some(session.into()), |ctx| {
+                        ctx.unseal(ObjectHandle::from(object))
+                    })
+                })
+                .map(|data| Zeroizing::new(data.value().to_vec()));
+            flush_session(ctx, session)?;
+            data_key
+        });
+        ctx.flush_context(ObjectHandle::from(object))?;
+        data_key
+    }
+}
+
+impl<E: Curve> SecretStorage<E> for TpmSecretStorage {
+    type Handle = TpmSealedSecrets;
+
+    fn store(&self, secrets: SecretComponents<E>) -> Result<TpmSealedSecrets, SecretStorageError> {
+        let mut ctx = self
+            .context
+            .lock()
+            .map_err(|_| SecretStorageError::backend(PoisonedLock))?;
+        let (data_key, private, public) = self
+            .seal_data_key(&mut ctx)
+            .map_err(SecretStorageError::backend)?;
+        let nonce = ctx
+            .get_random(NONCE_LEN)
+            .map_err(SecretStorageError::backend)?;
+        if nonce.value().len() != NONCE_LEN {
+            return Err(SecretStorageError::backend(Malformed));
+        }
+
+        let plaintext =
+            Zeroizing::new(serde_json::to_vec(&secrets).map_err(SecretStorageError::backend)?);
+        let encrypted = cipher(&data_key)?
+            .encrypt(
+                XNonce::from_slice(nonce.value()),
+                Payload {
+                    msg: &plaintext,
+                    aad: AAD,
+                },
+            )
+            .map_err(|_| SecretStorageError::backend(Malformed))?;
+
+        Ok(TpmSealedSecrets {
+            version: VERSION,
+            public: public.marshall().map_err(SecretStorageError::backend)?,
+            private: private.value().to_vec(),
+            ciphertext: [nonce.value(), &encrypted[..]].concat(),
+        })
+    }
+
+    fn load(&self, handle: &TpmSealedSecrets) -> Result<SecretComponents<E>, SecretStorageError> {
+        if handle.version != VERSION || handle.ciphertext.len() < NONCE_LEN {
+            return Err(SecretStorageError::backend(Malformed));
+        }
+        let public = Public::unmarshall(&handle.public).map_err(SecretStorageError::backend)?;
+        let private =
+            Private::try_from(handle.private.clone()).map_err(SecretStorageError::backend)?;
+
+        let data_key = {
+            let mut ctx = self
+                .context
+                .lock()
+                .map_err(|_| SecretStorageError::backend(PoisonedLock))?;
+            self.unseal_data_key(&mut ctx, private, public)
+                .map_err(SecretStorageError::backend)?
+        };
+
+        let (nonce, ciphertext) = handle.ciphertext.split_at(NONCE_LEN);
+        let plaintext = Zeroizing::new(
+            cipher(&data_key)?
+                .decrypt(
+                    XNonce::from_slice(nonce),
+                    Payload {
+                        msg: ciphertext,
+                        aad: AAD,
+                    },
+                )
+                .map_err(|_| SecretStorageError::backend(Malformed))?,
+        );
+        serde_json::from_slice(&plaintext).map_err(SecretStorageError::backend)
+    }
+
+    fn erase(&self, _handle: &TpmSealedSecrets) -> Result<(), SecretStorageError> {
+        Ok(())
+    }
+}
+
+fn create_primary(ctx: &mut Context) -> Result<KeyHandle, tss_esapi::Error> {
+    let public = tss_esapi::utils::create_restricted_decryption_rsa_public(
+        SymmetricDefinitionObject::AES_128_CFB,
+        RsaKeyBits::Rsa2048,
+        RsaExponent::default(),
+    )?;
+    ctx.execute_with_nullauth_session(|ctx| {
+        ctx.create_primary(Hierarchy::Owner, public, None, None, None, None)
+    })
+    .map(|primary| primary.key_handle)
+}
+
+fn start_policy_session(
+    ctx: &mut Context,
+    session_type: SessionType,
+) -> Result<PolicySession, tss_esapi::Error> {
+    let session = ctx
+        .start_auth_session(
+            None,
+            None,
+            None,
+            session_type,
+            SymmetricDefinition::AES_128_CFB,
+            HashingAlgorithm::Sha256,
+        )?
+        .ok_or(tss_esapi::Error::WrapperError(
+            tss_esapi::WrapperErrorKind::WrongValueFromTpm,
+        ))?;
+    PolicySession::try_from(session)
+}
+
+fn flush_session(ctx: &mut Context, session: PolicySession) -> Result<(), tss_esapi::Error> {
+    ctx.flush_context(SessionHandle::from(AuthSession::from(session)).into())
+}
+
+fn cipher(data_key: &[u8]) -> Result<XChaCha20Poly1305, SecretStorageError> {
+    XChaCha20Poly1305::new_from_slice(data_key).map_err(|_| SecretStorageError::backend(Malformed))
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("lock is poisoned")]
+struct PoisonedLock;
+
+#[derive(Debug, thiserror::Error)]
+#[error("sealed secrets are malformed")]
+struct Malformed;
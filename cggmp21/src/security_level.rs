@@ -3,7 +3,8 @@
 //! Security level is defined as set of parameters in the CGGMP paper. Higher security level gives more
 //! security but makes protocol execution slower.
 //!
-//! We provide a predefined default [SecurityLevel128].
+//! We provide a predefined default [SecurityLevel128], and [SecurityLevel128Wide] for curves with
+//! order wider than 256 bits.
 //!
 //! You can define your own security level using macro [define_security_level]. Be sure that you properly
 //! analyzed the CGGMP paper and you understand implications. Inconsistent security level may cause unexpected
@@ -202,9 +203,32 @@ define_security_level!(SecurityLevel128{
     q = (Integer::ONE << 128_u32).into(),
 });
 
+/// 128-bits security level for curves with up to 384 bits order
+///
+/// Same as [SecurityLevel128], but $\ell$ and $\ell'$ are increased by 128 and 256 bits
+/// respectively, so secret shares of curves with up to 384 bits order, like
+/// [Secp384r1](crate::supported_curves::Secp384r1), fit into ranges of ZK proofs. Paillier
+/// keys have the same size as in [SecurityLevel128], so [pregenerated primes](crate::PregeneratedPrimes)
+/// can be generated and used in the same way. Protocol is slower than with [SecurityLevel128] due
+/// to larger proofs.
+#[derive(Clone)]
+pub struct SecurityLevel128Wide;
+define_security_level!(SecurityLevel128Wide{
+    security_bits = 384,
+    epsilon = 230,
+    ell = 384,
+    ell_prime = 1104,
+    m = 128,
+    q = (Integer::ONE << 128_u32).into(),
+});
+
 /// Checks that public paillier key meets security level constraints
+///
+/// Key must not exceed [`max_paillier_modulus_bits`](crate::limits::max_paillier_modulus_bits)
+/// as well, so that malicious party can't make proof verification arbitrarily slow.
 pub(crate) fn validate_public_paillier_key_size<L: SecurityLevel>(N: &Integer) -> bool {
-    N.significant_bits() >= 8 * L::SECURITY_BITS - 1
+    let bits = N.significant_bits();
+    bits >= 8 * L::SECURITY_BITS - 1 && bits <= crate::limits::max_paillier_modulus_bits::<L>()
 }
 
 /// Checks that secret paillier key meets security level constraints
@@ -0,0 +1,255 @@
+//! Social recovery of an individual key share
+//!
+//! Allows a party to back up its own [`KeyShare`] with guardians (e.g. friends, other devices, or
+//! custodians), and restore it after losing the device without involving other parties of the
+//! committee:
+//!
+//! 1. Key share is encrypted with XChaCha20-Poly1305 under a random key.
+//! 2. Encryption key is split via Shamir secret sharing into $k$ [`RecoveryShard`]s, so any
+//!    $m$ of them are sufficient to recover it. Each shard carries a copy of the ciphertext.
+//! 3. Shards are handed over to the guardians.
+//! 4. To [restore] the key share, any $m$ shards are collected back.
+//!
+//! Less than $m$ shards reveal nothing about the key share. A guardian who provides a corrupted
+//! shard can't make restoration output a wrong key share: decryption fails instead.
+//!
+//! Each shard is a secret on its own and must be delivered to its guardian over a confidential
+//! channel.
+//!
+//! ## Shard format
+//! Shards are versioned: restoration fails on shards produced by an unknown version of the
+//! scheme. Ciphertext is `nonce (24 bytes) || encryption of the key share serialized to JSON`.
+//! Encryption key is `SHA-256(tag || s)` where $s$ is the secret shared between the shards,
+//! encoded as big-endian scalar.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn f<E: generic_ec::Curve>(key_share: cggmp21::KeyShare<E>) -> Result<(), Box<dyn std::error::Error>> {
+//! use cggmp21::share_recovery;
+//! let mut rng = rand_dev::DevRng::new();
+//!
+//! // Any 2 out of 3 guardians can restore the key share
+//! let shards = share_recovery::split(&mut rng, &key_share, 2, 3)?;
+//! // ... send shards to guardians, then collect any 2 of them back
+//! let restored: cggmp21::KeyShare<E> = share_recovery::restore(&shards[1..])?;
+//! # Ok(()) }
+//! ```
+
+use crate::poly::{lagrange_coefficient, Polynomial};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use digest::Digest;
+use generic_ec::{Curve, NonZero, Scalar, SecretScalar};
+use rand_core::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::key_share::KeyShare;
+use crate::security_level::SecurityLevel;
+
+const VERSION: u8 = 1;
+const NONCE_LEN: usize = 24;
+const KEY_TAG: &[u8] = b"dfns.cggmp21.share_recovery.key";
+const AAD_TAG: &[u8] = b"dfns.cggmp21.share_recovery";
+
+/// Shard of a key share backup
+///
+/// Produced by [`split`], consumed by [`restore`]. Shard is a secret and must be kept by
+/// the guardian as such.
+#[serde_with::serde_as]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct RecoveryShard<E: Curve> {
+    /// Version of the backup scheme
+    pub version: u8,
+    /// Amount of shards $m$ required to restore the key share
+    pub min_shards: u16,
+    /// Index of the shard, $0 \le j < k$
+    pub index: u16,
+    /// Share of the encryption key
+    pub share: Scalar<E>,
+    /// Encrypted key share
+    #[serde_as(as = "serde_with::Bytes")]
+    pub ciphertext: Vec<u8>,
+}
+
+/// Splits the key share into `k` recovery shards, any `min_shards` of which can restore it
+pub fn split<E, L, R>(
+    rng: &mut R,
+    key_share: &KeyShare<E, L>,
+    min_shards: u16,
+    k: u16,
+) -> Result<Vec<RecoveryShard<E>>, SplitError>
+where
+    E: Curve,
+    L: SecurityLevel,
+    R: RngCore + CryptoRng,
+{
+    if min_shards == 0 || min_shards > k {
+        return Err(SplitReason::InvalidThreshold { min_shards, k }.into());
+    }
+
+    let secret = SecretScalar::<E>::random(rng);
+    let cipher = cipher(&secret);
+
+    let plaintext = Zeroizing::new(serde_json::to_vec(key_share).map_err(SplitReason::Serialize)?);
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill_bytes(&mut nonce);
+    #[allow(clippy::expect_used)]
+    let encrypted = cipher
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: &plaintext,
+                aad: &aad(min_shards),
+            },
+        )
+        .expect("encryption never fails for plaintext of a key share size");
+    let ciphertext = [&nonce[..], &encrypted[..]].concat();
+
+    let f = Polynomial::sample_with_const_term(rng, usize::from(min_shards) - 1, secret);
+    Ok((0..k)
+        .map(|j| RecoveryShard {
+            version: VERSION,
+            min_shards,
+            index: j,
+            share: f.value(&shard_point(j)),
+            ciphertext: ciphertext.clone(),
+        })
+        .collect())
+}
+
+/// Restores the key share from recovery shards
+///
+/// Requires at least `min_shards` distinct shards of the same backup.
+pub fn restore<E, L>(shards: &[RecoveryShard<E>]) -> Result<KeyShare<E, L>, RestoreError>
+where
+    E: Curve,
+    L: SecurityLevel,
+{
+    let first = shards.first().ok_or(RestoreReason::NoShards)?;
+    if let Some(shard) = shards.iter().find(|s| s.version != VERSION) {
+        return Err(RestoreReason::UnknownVersion(shard.version).into());
+    }
+    if shards
+        .iter()
+        .any(|s| s.min_shards != first.min_shards || s.ciphertext != first.ciphertext)
+    {
+        return Err(RestoreReason::DifferentBackups.into());
+    }
+    if (1..shards.len()).any(|j| shards[..j].iter().any(|s| s.index == shards[j].index)) {
+        return Err(RestoreReason::DuplicatedShard.into());
+    }
+    if shards.len() < usize::from(first.min_shards) {
+        return Err(RestoreReason::TooFewShards {
+            len: shards.len(),
+            min_shards: first.min_shards,
+        }
+        .into());
+    }
+
+    let shards = &shards[..usize::from(first.min_shards)];
+    let I = shards
+        .iter()
+        .map(|s| NonZero::from_scalar(shard_point(s.index)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(RestoreReason::Interpolation)?;
+    let mut secret = (0..)
+        .map(|j| lagrange_coefficient(Scalar::zero(), j, &I))
+        .zip(shards)
+        .try_fold(Scalar::zero(), |acc, (lambda_j, s)| {
+            Some(acc + *lambda_j? * s.share)
+        })
+        .ok_or(RestoreReason::Interpolation)?;
+    let secret = SecretScalar::new(&mut secret);
+
+    if first.ciphertext.len() < NONCE_LEN {
+        return Err(RestoreReason::Malformed.into());
+    }
+    let (nonce, ciphertext) = first.ciphertext.split_at(NONCE_LEN);
+    let plaintext = Zeroizing::new(
+        cipher(&secret)
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &aad(first.min_shards),
+                },
+            )
+            .map_err(|_| RestoreReason::Decrypt)?,
+    );
+    serde_json::from_slice(&plaintext).map_err(|err| RestoreReason::Deserialize(err).into())
+}
+
+fn shard_point<E: Curve>(j: u16) -> Scalar<E> {
+    Scalar::from(j) + Scalar::one()
+}
+
+fn cipher<E: Curve>(secret: &SecretScalar<E>) -> XChaCha20Poly1305 {
+    let key: Zeroizing<[u8; 32]> = Zeroizing::new(
+        sha2::Sha256::new()
+            .chain_update(KEY_TAG)
+            .chain_update(secret.as_ref().to_be_bytes())
+            .finalize()
+            .into(),
+    );
+    XChaCha20Poly1305::new(chacha20poly1305::Key::from_slice(&*key))
+}
+
+fn aad(min_shards: u16) -> Vec<u8> {
+    [AAD_TAG, &[VERSION], &min_shards.to_be_bytes()].concat()
+}
+
+/// Error returned by [`split`]
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct SplitError(SplitReason);
+
+#[derive(Debug, thiserror::Error)]
+enum SplitReason {
+    #[error("invalid threshold: min_shards={min_shards}, k={k}")]
+    InvalidThreshold { min_shards: u16, k: u16 },
+    #[error("serialize key share")]
+    Serialize(#[source] serde_json::Error),
+}
+
+/// Error returned by [`restore`]
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct RestoreError(RestoreReason);
+
+#[derive(Debug, thiserror::Error)]
+enum RestoreReason {
+    #[error("no shards provided")]
+    NoShards,
+    #[error("unknown shard version: {0}")]
+    UnknownVersion(u8),
+    #[error("shards belong to different backups")]
+    DifferentBackups,
+    #[error("shards are not distinct")]
+    DuplicatedShard,
+    #[error("expected at least {min_shards} shards, but {len} shards were provided")]
+    TooFewShards { len: usize, min_shards: u16 },
+    #[error("interpolation failed (seems like a bug)")]
+    Interpolation,
+    #[error("malformed ciphertext")]
+    Malformed,
+    #[error("decryption failed: one of the shards is corrupted")]
+    Decrypt,
+    #[error("deserialize key share")]
+    Deserialize(#[source] serde_json::Error),
+}
+
+crate::errors::impl_from! {
+    impl From for SplitError {
+        err: SplitReason => SplitError(err),
+    }
+}
+
+crate::errors::impl_from! {
+    impl From for RestoreError {
+        err: RestoreReason => RestoreError(err),
+    }
+}
@@ -1,9 +1,31 @@
 //! Signing protocol
 
+pub mod approval;
+#[cfg(feature = "bitcoin")]
+pub mod bitcoin;
+pub mod coordinator;
+#[cfg(feature = "ethereum")]
+pub mod ethereum;
+#[cfg(feature = "jose")]
+pub mod jose;
+#[cfg(feature = "presignature-encryption")]
+pub mod presignature_encryption;
+pub mod presignature_store;
+pub mod rate_limit;
+#[cfg(any(feature = "ethereum", feature = "bitcoin"))]
+mod recovery;
+#[cfg(any(
+    feature = "curve-secp256k1",
+    feature = "curve-secp256r1",
+    feature = "curve-secp384r1"
+))]
+pub mod rustcrypto;
+#[cfg(feature = "signature")]
+pub mod signer;
+
 use digest::Digest;
 use futures::SinkExt;
 use generic_ec::{coords::AlwaysHasAffineX, Curve, NonZero, Point, Scalar, SecretScalar};
-use generic_ec_zkp::polynomial::lagrange_coefficient;
 use paillier_zk::rug::Complete;
 use paillier_zk::{fast_paillier, rug::Integer};
 use paillier_zk::{
@@ -17,15 +39,30 @@ use round_based::{
     runtime::AsyncRuntime,
     Delivery, Mpc, MpcParty, MsgId, Outgoing, PartyIndex,
 };
-use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use cggmp21_keygen::abort::{self, PeerAborted};
+
 use crate::errors::IoError;
-use crate::key_share::{KeyShare, PartyAux, VssSetup};
+use crate::key_share::{
+    AnyKeyShare, AuxInfo, DirtyKeyInfo, IncompleteKeyShare, KeyFingerprint, KeyShare, KeyShareRef,
+    PartyAux, VssSetup,
+};
+use crate::paillier_backend::{DecryptionError, PaillierDecryptor};
+use crate::policy::{KeyUsagePolicy, PolicyViolation};
+use crate::poly::{self, lagrange_coefficient};
 use crate::progress::Tracer;
-use crate::{key_share::InvalidKeyShare, security_level::SecurityLevel, utils, ExecutionId};
+use crate::{
+    error_code::{ErrorCode, ErrorReport},
+    key_share::InvalidKeyShare,
+    limits,
+    security_level::SecurityLevel,
+    utils, zk, EidRegistry, EidRegistryError, ExecutionId,
+};
 
+use self::approval::{Approval, SigningApproval};
 use self::msg::*;
+use self::rate_limit::{RateLimitError, SignatureLimiter};
 
 /// A (prehashed) data to be signed
 ///
@@ -45,6 +82,14 @@ impl<E: Curve> DataToSign<E> {
         DataToSign(Scalar::from_be_bytes_mod_order(D::digest(data)))
     }
 
+    /// Construct a `DataToSign` by hashing `data` with Keccak256, as used in Ethereum
+    ///
+    /// `data_to_sign = keccak256(data) mod q`
+    #[cfg(feature = "sha3")]
+    pub fn keccak256(data: &[u8]) -> Self {
+        Self::digest::<sha3::Keccak256>(data)
+    }
+
     /// Constructs a `DataToSign` from output of given digest
     ///
     /// `data_to_sign = hash(data) mod q`
@@ -67,11 +112,77 @@ impl<E: Curve> DataToSign<E> {
     }
 }
 
+/// Creates a channel for supplying message to sign after signing protocol is started
+///
+/// See [`SigningBuilder::sign_with_deferred_message`]
+pub fn deferred_message<E: Curve>() -> (MessageSender<E>, DeferredMessage<E>) {
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    (MessageSender(sender), DeferredMessage(receiver))
+}
+
+/// Supplies message to sign to the [signing protocol](SigningBuilder::sign_with_deferred_message)
+#[derive(Debug)]
+pub struct MessageSender<E: Curve>(futures::channel::oneshot::Sender<Deferred<E>>);
+
+impl<E: Curve> MessageSender<E> {
+    /// Sends message to sign
+    ///
+    /// Returns the message back if signing protocol is not running anymore
+    pub fn send(self, message_to_sign: DataToSign<E>) -> Result<(), DataToSign<E>> {
+        self.send_deferred(message_to_sign, None)
+    }
+
+    /// Sends message to sign along with the message which it was derived from
+    ///
+    /// Same as [`SigningBuilder::set_message`], the message is required if
+    /// [key usage policy](crate::policy) of the key share restricts messages, and `message_to_sign`
+    /// must be [`DataToSign::digest::<D>(&message)`](DataToSign::digest). Overrides the message
+    /// set via the builder.
+    ///
+    /// Returns the message to sign back if signing protocol is not running anymore
+    pub fn send_with_message(
+        self,
+        message_to_sign: DataToSign<E>,
+        message: Vec<u8>,
+    ) -> Result<(), DataToSign<E>> {
+        self.send_deferred(message_to_sign, Some(message))
+    }
+
+    fn send_deferred(
+        self,
+        message_to_sign: DataToSign<E>,
+        message: Option<Vec<u8>>,
+    ) -> Result<(), DataToSign<E>> {
+        self.0
+            .send(Deferred {
+                message_to_sign,
+                message,
+            })
+            .map_err(|deferred| deferred.message_to_sign)
+    }
+}
+
+/// Message to sign that will be supplied by [`MessageSender`]
+#[derive(Debug)]
+pub struct DeferredMessage<E: Curve>(futures::channel::oneshot::Receiver<Deferred<E>>);
+
+/// Data sent via [`MessageSender`]
+#[derive(Debug)]
+struct Deferred<E: Curve> {
+    message_to_sign: DataToSign<E>,
+    message: Option<Vec<u8>>,
+}
+
 /// Presignature, can be used to issue a [partial signature](PartialSignature) without interacting with other signers
 ///
 /// [Threshold](crate::key_share::AnyKeyShare::min_signers) amount of partial signatures (from different signers) can be [combined](PartialSignature::combine) into regular signature
-#[derive(Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
+///
+/// Presignature is single-use: [issuing partial signature](Presignature::issue_partial_signature)
+/// consumes it, and the type doesn't implement `Clone`. If a copy is needed, it can be obtained via
+/// [`dangerously_clone`](Presignature::dangerously_clone): copies keep track of issued partial
+/// signature, so only one message can be signed with all of them.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct Presignature<E: Curve> {
     /// $R$ component of presignature
     pub R: NonZero<Point<E>>,
@@ -79,6 +190,12 @@ pub struct Presignature<E: Curve> {
     pub k: SecretScalar<E>,
     /// $\chi$ component of presignature
     pub chi: SecretScalar<E>,
+    /// Message for which partial signature was issued, shared between copies of presignature
+    ///
+    /// Not serialized: issuance can't be tracked across serialized copies, so presignature
+    /// must be deleted from the storage once it's used.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    issued_for: std::sync::Arc<std::sync::Mutex<Option<Scalar<E>>>>,
 }
 
 /// Partial signature issued by signer for given message
@@ -86,8 +203,9 @@ pub struct Presignature<E: Curve> {
 /// Can be obtained using [`Presignature::issue_partial_signature`]. Partial signature doesn't carry any sensitive inforamtion.
 ///
 /// Threshold amount of partial signatures can be combined into a regular signature using [`PartialSignature::combine`]
-#[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct PartialSignature<E: Curve> {
     /// $r$ component of partial signature
     pub r: Scalar<E>,
@@ -95,9 +213,28 @@ pub struct PartialSignature<E: Curve> {
     pub sigma: Scalar<E>,
 }
 
+/// Public commitments to the presignature
+///
+/// Can be obtained using [`Presignature::public_data`]. Signers exchange public data before
+/// issuing partial signatures, which makes it possible to identify which partial signature
+/// is faulty if combined signature is invalid (see [`PartialSignature::combine_and_identify`]).
+/// Public data doesn't carry any sensitive information.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct PresignaturePublicData<E: Curve> {
+    /// $R$ component of presignature
+    pub R: NonZero<Point<E>>,
+    /// $R^{k_i}$
+    pub K: Point<E>,
+    /// $R^{\chi_i}$
+    pub Chi: Point<E>,
+}
+
 /// ECDSA signature
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
-#[serde(bound = "")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct Signature<E: Curve> {
     /// $r$ component of signature
     pub r: NonZero<Scalar<E>>,
@@ -117,15 +254,17 @@ pub mod msg {
         paillier_affine_operation_in_range as pi_aff, paillier_encryption_in_range as pi_enc,
     };
     use round_based::ProtocolMessage;
-    use serde::{Deserialize, Serialize};
 
     use crate::utils;
 
+    pub use cggmp21_keygen::{abort::MsgAbort, echo_broadcast::MsgEcho};
+
     /// Signing protocol message
     ///
     /// Enumerates messages from all rounds
-    #[derive(Clone, ProtocolMessage, Serialize, Deserialize)]
-    #[serde(bound = "")]
+    #[derive(Clone, ProtocolMessage)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "serde", serde(bound = ""))]
     #[allow(clippy::large_enum_variant)]
     pub enum Msg<E: Curve, D: Digest> {
         /// Round 1a message
@@ -140,10 +279,19 @@ pub mod msg {
         Round4(MsgRound4<E>),
         /// Reliability check message (optional additional round)
         ReliabilityCheck(MsgReliabilityCheck<D>),
+        /// Echo of round 4 messages (optional additional round)
+        Round4Echo(MsgEcho<D, 4>),
+        /// Party aborts the protocol (not part of any round)
+        Abort(MsgAbort),
+        /// Round 2 message with piggybacked reliability check (used instead of [`Msg::Round2`]
+        /// and [`Msg::ReliabilityCheck`] in [round-optimized](crate::signing::SigningVariant::RoundOptimized)
+        /// variant)
+        Round2WithReliabilityCheck(MsgRound2WithReliabilityCheck<E, D>),
     }
 
     /// Message from round 1a
-    #[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
+    #[derive(Clone, udigest::Digestable)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[udigest(tag = "dfns.cggmp21.signing.round1")]
     pub struct MsgRound1a {
         /// $K_i$
@@ -155,15 +303,17 @@ pub mod msg {
     }
 
     /// Message from round 1b
-    #[derive(Clone, Serialize, Deserialize)]
+    #[derive(Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct MsgRound1b {
         /// $\psi^0_{j,i}$
         pub psi0: (pi_enc::Commitment, pi_enc::Proof),
     }
 
     /// Message from round 2
-    #[derive(Clone, Serialize, Deserialize)]
-    #[serde(bound = "")]
+    #[derive(Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "serde", serde(bound = ""))]
     pub struct MsgRound2<E: Curve> {
         /// $\Gamma_i$
         pub Gamma: Point<E>,
@@ -184,8 +334,9 @@ pub mod msg {
     }
 
     /// Message from round 3
-    #[derive(Clone, Serialize, Deserialize)]
-    #[serde(bound = "")]
+    #[derive(Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "serde", serde(bound = ""))]
     pub struct MsgRound3<E: Curve> {
         /// $\delta_i$
         pub delta: Scalar<E>,
@@ -196,17 +347,226 @@ pub mod msg {
     }
 
     /// Message from round 4
-    #[derive(Clone, Serialize, Deserialize)]
-    #[serde(bound = "")]
+    #[derive(Clone, udigest::Digestable)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "serde", serde(bound = ""))]
+    #[udigest(bound = "")]
+    #[udigest(tag = "dfns.cggmp21.signing.round4")]
     pub struct MsgRound4<E: Curve> {
         /// $\sigma_i$
         pub sigma: Scalar<E>,
     }
 
     /// Message from auxiliary round for reliability check
-    #[derive(Clone, Serialize, Deserialize)]
-    #[serde(bound = "")]
+    #[derive(Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "serde", serde(bound = ""))]
     pub struct MsgReliabilityCheck<D: Digest>(pub digest::Output<D>);
+
+    /// Message from round 2 with piggybacked reliability check of round 1a
+    #[derive(Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "serde", serde(bound = ""))]
+    pub struct MsgRound2WithReliabilityCheck<E: Curve, D: Digest> {
+        /// Round 2 message
+        pub round2: MsgRound2<E>,
+        /// Hash of messages received in round 1a
+        pub reliability_check: MsgReliabilityCheck<D>,
+    }
+}
+
+/// Set of signers $S$
+///
+/// Contains distinct indexes (at keygen) of parties who take part in signing, sorted in ascending
+/// order. Sorting makes $S$ independent of the order in which indexes were provided, so all signers
+/// obtain the same $S$ as long as they agree on who is signing. Position of local party in $S$ (i.e.
+/// argument `i` of [`signing`](crate::signing)) is computed automatically when signing is started
+/// via [`SigningBuilder::with_signers`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignersSet(Vec<PartyIndex>);
+
+impl SignersSet {
+    /// Constructs a set of signers from their indexes at keygen
+    ///
+    /// Returns error if amount of signers doesn't match the threshold, some index is out of
+    /// bounds, or some index is duplicated.
+    pub fn new<E: Curve, L: SecurityLevel>(
+        key_share: &KeyShare<E, L>,
+        signers: impl IntoIterator<Item = PartyIndex>,
+    ) -> Result<Self, InvalidSigners> {
+        let mut S = signers.into_iter().collect::<Vec<_>>();
+        S.sort_unstable();
+        validate_key_signers(&key_share.core.key_info, &S)?;
+        Ok(Self(S))
+    }
+
+    /// Returns indexes of signers at keygen
+    pub fn as_slice(&self) -> &[PartyIndex] {
+        &self.0
+    }
+
+    /// Returns position of the signer in $S$, given its index at keygen
+    ///
+    /// Returns `None` if the party is not in $S$
+    pub fn position_of(&self, keygen_index: PartyIndex) -> Option<PartyIndex> {
+        let position = self.0.binary_search(&keygen_index).ok()?;
+        position.try_into().ok()
+    }
+}
+
+impl AsRef<[PartyIndex]> for SignersSet {
+    fn as_ref(&self) -> &[PartyIndex] {
+        &self.0
+    }
+}
+
+/// Precomputed data of local signer for a fixed set of signers $S$
+///
+/// To sign with t-out-of-n key, signers convert their key shares into additive shares by
+/// multiplying them at lagrange coefficients, and do the same with public shares. The context
+/// caches the conversion, so when the same set of signers signs repeatedly (e.g. in 2-out-of-3
+/// wallets), it can be done only once. Signing is started with
+/// [`SigningBuilder::with_signer_set_context`].
+///
+/// Note: context contains secret additive share of the signer. Keep it secret (as well as the key
+/// share it's derived from).
+#[derive(Clone)]
+pub struct SignerSetContext<E: Curve> {
+    signers: SignersSet,
+    i: PartyIndex,
+    keygen_index: PartyIndex,
+    shared_public_key: NonZero<Point<E>>,
+    x_i: NonZero<SecretScalar<E>>,
+    X: Vec<NonZero<Point<E>>>,
+}
+
+impl<E: Curve> SignerSetContext<E> {
+    /// Precomputes context for the local signer and set of signers $S$
+    ///
+    /// Returns error if local party is not in $S$ or $S$ is not valid for the key share
+    pub fn new<L: SecurityLevel>(
+        key_share: &KeyShare<E, L>,
+        signers: &SignersSet,
+    ) -> Result<Self, SigningError> {
+        let S = signers.as_slice();
+        validate_key_signers(&key_share.core.key_info, S)?;
+        let keygen_index = key_share.core.i;
+        let i = signers
+            .position_of(keygen_index)
+            .ok_or(InvalidSignersReason::NotASigner(keygen_index))
+            .map_err(InvalidSigners::from)?;
+        let (x_i, X) = additive_shares(key_share.into(), i, S)?;
+        Ok(Self {
+            signers: signers.clone(),
+            i,
+            keygen_index,
+            shared_public_key: key_share.core.shared_public_key,
+            x_i,
+            X,
+        })
+    }
+
+    /// Returns set of signers $S$
+    pub fn signers(&self) -> &SignersSet {
+        &self.signers
+    }
+
+    /// Returns position of local signer in $S$
+    pub fn i(&self) -> PartyIndex {
+        self.i
+    }
+
+    /// Returns public shares $X_j$ of signers converted into additive form
+    ///
+    /// `public_shares()[j]` corresponds to signer `S[j]`. They sum up to the shared public key.
+    pub fn public_shares(&self) -> &[NonZero<Point<E>>] {
+        &self.X
+    }
+
+    /// Checks that context was derived from the key share for given signers
+    fn matches<L: SecurityLevel>(
+        &self,
+        key_share: KeyShareRef<'_, E, L>,
+        i: PartyIndex,
+        S: &[PartyIndex],
+    ) -> bool {
+        self.i == i
+            && self.signers.as_slice() == S
+            && self.keygen_index == key_share.core.i
+            && self.shared_public_key == key_share.core.shared_public_key
+    }
+}
+
+/// Validates set of signers $S$
+///
+/// Checks that $S$ contains exactly $t$ distinct indexes, each of which is less than $n$
+fn validate_signers(n: u16, t: u16, S: &[PartyIndex]) -> Result<(), InvalidSigners> {
+    if S.len() != usize::from(t) {
+        return Err(InvalidSignersReason::MismatchedAmountOfParties {
+            expected: t,
+            actual: S.len(),
+        }
+        .into());
+    }
+    let mut seen = vec![false; usize::from(n)];
+    for &S_j in S {
+        let Some(seen_j) = seen.get_mut(usize::from(S_j)) else {
+            return Err(InvalidSignersReason::UnknownIndex { index: S_j, n }.into());
+        };
+        if *seen_j {
+            return Err(InvalidSignersReason::DuplicatedIndex(S_j).into());
+        }
+        *seen_j = true;
+    }
+    Ok(())
+}
+
+/// Validates set of signers $S$ for the key
+///
+/// For weighted keys, $S$ may contain any amount of distinct signers as long as their total weight
+/// is at least threshold. Otherwise, $S$ must contain exactly $t$ signers
+/// (see [`validate_signers`]).
+fn validate_key_signers<E: Curve>(
+    key_info: &DirtyKeyInfo<E>,
+    S: &[PartyIndex],
+) -> Result<(), InvalidSigners> {
+    let n = u16::try_from(key_info.public_shares.len()).unwrap_or(u16::MAX);
+    match &key_info.vss_setup {
+        Some(vss_setup) if vss_setup.weighted.is_some() => {
+            validate_signers(n, u16::try_from(S.len()).unwrap_or(u16::MAX), S)?;
+            let weight = S
+                .iter()
+                .map(|&j| u32::from(vss_setup.weight(j).unwrap_or(0)))
+                .sum::<u32>();
+            if weight < u32::from(vss_setup.min_signers) {
+                return Err(InvalidSignersReason::InsufficientWeight {
+                    expected: vss_setup.min_signers,
+                    actual: weight,
+                }
+                .into());
+            }
+            Ok(())
+        }
+        Some(vss_setup) => validate_signers(n, vss_setup.min_signers, S),
+        None => validate_signers(n, n, S),
+    }
+}
+
+/// Variant of the signing protocol
+///
+/// All signers must use the same variant, otherwise protocol fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SigningVariant {
+    /// Default flow: when [reliable broadcast](SigningBuilder::enforce_reliable_broadcast) is
+    /// enforced, it takes a dedicated round in which parties exchange hashes of round 1 messages
+    #[default]
+    Standard,
+    /// Round-optimized flow: hashes of round 1 messages are piggybacked to round 2 messages and
+    /// checked once round 2 is complete, which saves one round at the cost of bigger round 2
+    /// messages
+    ///
+    /// Suits latency-sensitive deployments. Has no effect if reliable broadcast is not enforced.
+    RoundOptimized,
 }
 
 /// Signing entry point
@@ -215,6 +575,7 @@ pub struct SigningBuilder<
     E,
     L = crate::default_choice::SecurityLevel,
     D = crate::default_choice::Digest,
+    H = D,
 > where
     E: Curve,
     L: SecurityLevel,
@@ -222,11 +583,20 @@ pub struct SigningBuilder<
 {
     i: PartyIndex,
     parties_indexes_at_keygen: &'r [PartyIndex],
-    key_share: &'r KeyShare<E, L>,
+    key_share: KeyShareRef<'r, E, L>,
     execution_id: ExecutionId<'r>,
     tracer: Option<&'r mut dyn Tracer>,
+    eid_registry: Option<&'r dyn EidRegistry>,
     enforce_reliable_broadcast: bool,
-    _digest: std::marker::PhantomData<D>,
+    enforce_echo_broadcast: bool,
+    variant: SigningVariant,
+    signer_set_context: Option<&'r SignerSetContext<E>>,
+    thread_pool: Option<&'r utils::ThreadPool>,
+    paillier_decryptor: Option<&'r dyn PaillierDecryptor>,
+    message: Option<&'r [u8]>,
+    approval_hook: Option<&'r dyn SigningApproval<E>>,
+    rate_limiter: Option<&'r dyn SignatureLimiter>,
+    _digest: std::marker::PhantomData<(D, H)>,
 
     #[cfg(feature = "hd-wallets")]
     additive_shift: Option<Scalar<E>>,
@@ -237,7 +607,7 @@ where
     E: Curve,
     NonZero<Point<E>>: AlwaysHasAffineX<E>,
     L: SecurityLevel,
-    D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+    D: Digest + Clone + Sync + 'static,
 {
     /// Construct a signing builder
     pub fn new(
@@ -245,21 +615,115 @@ where
         i: PartyIndex,
         parties_indexes_at_keygen: &'r [PartyIndex],
         secret_key_share: &'r KeyShare<E, L>,
+    ) -> Self {
+        Self::from_key_share_ref(eid, i, parties_indexes_at_keygen, secret_key_share.into())
+    }
+
+    /// Construct a signing builder from key share and aux info stored separately
+    ///
+    /// Unlike [`new`](Self::new), doesn't require them to be merged into [`KeyShare`], so aux info
+    /// doesn't need to be cloned for every signing. Returns error if key share and aux info are not
+    /// consistent with each other.
+    ///
+    /// `policy` is the [key usage policy](crate::key_share::DirtyKeyShare::policy) of the key
+    /// share. It's not carried by the parts, so it must be kept alongside them when the key share
+    /// is split, otherwise signing isn't restricted.
+    pub fn from_parts(
+        eid: ExecutionId<'r>,
+        i: PartyIndex,
+        parties_indexes_at_keygen: &'r [PartyIndex],
+        key_share: &'r IncompleteKeyShare<E>,
+        aux_info: &'r AuxInfo<L>,
+        policy: Option<&'r KeyUsagePolicy>,
+    ) -> Result<Self, InvalidKeyShare> {
+        let mut key_share = KeyShareRef::new(key_share, aux_info)?;
+        key_share.policy = policy;
+        Ok(Self::from_key_share_ref(
+            eid,
+            i,
+            parties_indexes_at_keygen,
+            key_share,
+        ))
+    }
+
+    /// Construct a signing builder from borrowed key share parts
+    pub fn from_key_share_ref(
+        eid: ExecutionId<'r>,
+        i: PartyIndex,
+        parties_indexes_at_keygen: &'r [PartyIndex],
+        key_share: KeyShareRef<'r, E, L>,
     ) -> Self {
         Self {
             i,
             parties_indexes_at_keygen,
-            key_share: secret_key_share,
+            key_share,
             execution_id: eid,
             tracer: None,
+            eid_registry: None,
             enforce_reliable_broadcast: true,
+            enforce_echo_broadcast: false,
+            variant: SigningVariant::Standard,
+            signer_set_context: None,
+            thread_pool: None,
+            paillier_decryptor: None,
+            message: None,
+            approval_hook: None,
+            rate_limiter: None,
             _digest: std::marker::PhantomData,
             #[cfg(feature = "hd-wallets")]
             additive_shift: None,
         }
     }
 
+    /// Construct a signing builder from the set of signers
+    ///
+    /// Position of local party in $S$ is computed automatically. Returns error if local party
+    /// is not in the set of signers.
+    pub fn with_signers(
+        eid: ExecutionId<'r>,
+        signers: &'r SignersSet,
+        secret_key_share: &'r KeyShare<E, L>,
+    ) -> Result<Self, InvalidSigners> {
+        let keygen_index = secret_key_share.core.i;
+        let i = signers
+            .position_of(keygen_index)
+            .ok_or(InvalidSignersReason::NotASigner(keygen_index))?;
+        Ok(Self::new(eid, i, signers.as_slice(), secret_key_share))
+    }
+
+    /// Construct a signing builder from precomputed [`SignerSetContext`]
+    ///
+    /// Set of signers and position of local party in it are taken from the context. Lagrange
+    /// coefficients are not recomputed: additive shares cached in the context are used instead.
+    /// Context must be derived from `secret_key_share`, otherwise protocol returns an error.
+    pub fn with_signer_set_context(
+        eid: ExecutionId<'r>,
+        context: &'r SignerSetContext<E>,
+        secret_key_share: &'r KeyShare<E, L>,
+    ) -> Self {
+        Self {
+            signer_set_context: Some(context),
+            ..Self::new(eid, context.i, context.signers.as_slice(), secret_key_share)
+        }
+    }
+}
+
+impl<'r, E, L, D, H> SigningBuilder<'r, E, L, D, H>
+where
+    E: Curve,
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+    L: SecurityLevel,
+    D: Digest + Clone + Sync + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
+{
     /// Specifies another hash function to use
+    ///
+    /// Any digest with at least 256 bits output can be used, e.g. `sha3::Keccak256` for Ethereum
+    /// flows, or `sha2::Sha512`. Wider outputs are truncated to 256 bits where the protocol needs
+    /// a 256-bit hash, e.g. when deriving challenges of ZK proofs.
+    ///
+    /// Also resets digest used in reliability checks to `D2`, see
+    /// [`set_reliability_digest`](Self::set_reliability_digest).
     pub fn set_digest<D2>(self) -> SigningBuilder<'r, E, L, D2>
     where
         D2: Digest,
@@ -269,7 +733,43 @@ where
             parties_indexes_at_keygen: self.parties_indexes_at_keygen,
             key_share: self.key_share,
             tracer: self.tracer,
+            eid_registry: self.eid_registry,
             enforce_reliable_broadcast: self.enforce_reliable_broadcast,
+            enforce_echo_broadcast: self.enforce_echo_broadcast,
+            variant: self.variant,
+            signer_set_context: self.signer_set_context,
+            thread_pool: self.thread_pool,
+            paillier_decryptor: self.paillier_decryptor,
+            message: self.message,
+            approval_hook: self.approval_hook,
+            rate_limiter: self.rate_limiter,
+            execution_id: self.execution_id,
+            _digest: std::marker::PhantomData,
+            #[cfg(feature = "hd-wallets")]
+            additive_shift: self.additive_shift,
+        }
+    }
+
+    #[doc = include_str!("../docs/set_reliability_digest.md")]
+    pub fn set_reliability_digest<H2>(self) -> SigningBuilder<'r, E, L, D, H2>
+    where
+        H2: Digest<OutputSize = D::OutputSize>,
+    {
+        SigningBuilder {
+            i: self.i,
+            parties_indexes_at_keygen: self.parties_indexes_at_keygen,
+            key_share: self.key_share,
+            tracer: self.tracer,
+            eid_registry: self.eid_registry,
+            enforce_reliable_broadcast: self.enforce_reliable_broadcast,
+            enforce_echo_broadcast: self.enforce_echo_broadcast,
+            variant: self.variant,
+            signer_set_context: self.signer_set_context,
+            thread_pool: self.thread_pool,
+            paillier_decryptor: self.paillier_decryptor,
+            message: self.message,
+            approval_hook: self.approval_hook,
+            rate_limiter: self.rate_limiter,
             execution_id: self.execution_id,
             _digest: std::marker::PhantomData,
             #[cfg(feature = "hd-wallets")]
@@ -283,6 +783,64 @@ where
         self
     }
 
+    /// Sets a registry of used execution IDs
+    ///
+    /// Before starting the protocol, execution ID is registered in the registry. If it was
+    /// already used before, protocol won't start and returns an error.
+    pub fn set_eid_registry(mut self, registry: &'r dyn EidRegistry) -> Self {
+        self.eid_registry = Some(registry);
+        self
+    }
+
+    /// Specifies thread pool to run heavy computations on
+    ///
+    /// Proofs generation and verification are spread across threads of `pool`. By default,
+    /// rayon's global thread pool is used. Dedicated pool lets you limit parallelism, so protocol
+    /// doesn't starve other services running within the same process.
+    #[cfg(feature = "parallel")]
+    pub fn set_thread_pool(mut self, pool: &'r rayon::ThreadPool) -> Self {
+        self.thread_pool = Some(pool);
+        self
+    }
+
+    /// Specifies back-end performing Paillier decryption
+    ///
+    /// By default, ciphertexts are decrypted with a Paillier key of the key share. Back-end
+    /// must hold the same key, otherwise protocol returns an error. See [`paillier_backend`](crate::paillier_backend)
+    /// module docs for more details.
+    pub fn set_paillier_decryptor(mut self, decryptor: &'r dyn PaillierDecryptor) -> Self {
+        self.paillier_decryptor = Some(decryptor);
+        self
+    }
+
+    /// Specifies the message which data to sign was derived from
+    ///
+    /// Required if [key usage policy](crate::policy) of the key share restricts messages. Data to
+    /// sign must be a hash of the message computed with digest `D` of the builder, i.e.
+    /// [`DataToSign::digest::<D>(message)`](DataToSign::digest).
+    pub fn set_message(mut self, message: &'r [u8]) -> Self {
+        self.message = Some(message);
+        self
+    }
+
+    /// Sets a hook approving the signing
+    ///
+    /// Hook is invoked before round 1 and before the partial signature is released. See
+    /// [`approval`] module docs for details.
+    pub fn set_approval_hook(mut self, hook: &'r dyn SigningApproval<E>) -> Self {
+        self.approval_hook = Some(hook);
+        self
+    }
+
+    /// Sets a limiter of signatures issued with the key
+    ///
+    /// Limiter is consulted right before the partial signature is released. See [`rate_limit`]
+    /// module docs for details.
+    pub fn set_rate_limiter(mut self, limiter: &'r dyn SignatureLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
     #[doc = include_str!("../docs/enforce_reliable_broadcast.md")]
     pub fn enforce_reliable_broadcast(self, v: bool) -> Self {
         Self {
@@ -291,6 +849,44 @@ where
         }
     }
 
+    #[doc = include_str!("../docs/enforce_echo_broadcast.md")]
+    pub fn enforce_echo_broadcast(self, v: bool) -> Self {
+        Self {
+            enforce_echo_broadcast: v,
+            ..self
+        }
+    }
+
+    /// Specifies variant of the protocol
+    ///
+    /// Default is [`SigningVariant::Standard`]. See [`SigningVariant`] for trade-offs.
+    pub fn set_variant(self, variant: SigningVariant) -> Self {
+        Self { variant, ..self }
+    }
+
+    /// Returns total amount of rounds of [presignature generation](Self::generate_presignature)
+    /// with the current settings
+    ///
+    /// Matches amount of [`Event::RoundBegins`](crate::progress::Event::RoundBegins) events
+    /// emitted to the tracer, so it can be used with
+    /// [`ProgressTracer`](crate::progress::ProgressTracer) to report progress to the end user.
+    pub fn presigning_rounds_count(&self) -> u16 {
+        4 + u16::from(self.separate_reliability_check_round())
+    }
+
+    /// Returns total amount of rounds of [signing](Self::sign) with the current settings
+    ///
+    /// Similar to [`presigning_rounds_count`](Self::presigning_rounds_count), but also
+    /// counts rounds of issuing and combining partial signatures.
+    pub fn signing_rounds_count(&self) -> u16 {
+        self.presigning_rounds_count() + 2 + u16::from(self.enforce_echo_broadcast)
+    }
+
+    /// Checks whether reliability check takes a dedicated round
+    fn separate_reliability_check_round(&self) -> bool {
+        self.enforce_reliable_broadcast && self.variant == SigningVariant::Standard
+    }
+
     /// Specifies HD derivation path
     ///
     /// Note: when generating a presignature, derivation path doesn't need to be known in advance. Instead
@@ -319,6 +915,7 @@ where
         use crate::key_share::HdError;
         let public_key = self
             .key_share
+            .core
             .extended_public_key()
             .ok_or(HdError::DisabledHd)?;
         self.additive_shift =
@@ -336,23 +933,7 @@ where
         R: RngCore + CryptoRng,
         M: Mpc<ProtocolMessage = Msg<E, D>>,
     {
-        match signing_t_out_of_n(
-            self.tracer,
-            rng,
-            party,
-            self.execution_id,
-            self.i,
-            self.key_share,
-            self.parties_indexes_at_keygen,
-            None,
-            self.enforce_reliable_broadcast,
-            #[cfg(feature = "hd-wallets")]
-            self.additive_shift,
-            #[cfg(not(feature = "hd-wallets"))]
-            None,
-        )
-        .await?
-        {
+        match self.run(rng, party, None).await? {
             ProtocolOutput::Presignature(presig) => Ok(presig),
             ProtocolOutput::Signature(_) => Err(Bug::UnexpectedProtocolOutput.into()),
         }
@@ -369,7 +950,87 @@ where
         R: RngCore + CryptoRng,
         M: Mpc<ProtocolMessage = Msg<E, D>>,
     {
-        match signing_t_out_of_n(
+        match self
+            .run(rng, party, Some(MessageToSign::Known(message_to_sign)))
+            .await?
+        {
+            ProtocolOutput::Signature(sig) => Ok(sig),
+            ProtocolOutput::Presignature(_) => Err(Bug::UnexpectedProtocolOutput.into()),
+        }
+    }
+
+    /// Starts signing protocol without knowing the message to sign
+    ///
+    /// Message is supplied via [`MessageSender`] obtained from [`deferred_message`]. The protocol
+    /// waits for the message only right before issuing partial signature, so expensive presigning
+    /// rounds can overlap with computing the message (e.g. constructing a transaction).
+    ///
+    /// If `MessageSender` is dropped without sending the message, the protocol is aborted:
+    /// other signers are notified via [`MsgAbort`](crate::abort::MsgAbort) and an error is returned.
+    ///
+    /// If [key usage policy](crate::policy) of the key share restricts messages, send the message
+    /// along with data to sign via [`MessageSender::send_with_message`].
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// # async fn sign<M>(
+    /// #     eid: cggmp21::ExecutionId<'_>,
+    /// #     i: u16,
+    /// #     parties_indexes_at_keygen: &[u16],
+    /// #     key_share: &cggmp21::KeyShare<cggmp21::supported_curves::Secp256k1>,
+    /// #     party: M,
+    /// #     build_transaction: impl std::future::Future<Output = Vec<u8>>,
+    /// # ) -> Result<(), cggmp21::signing::SigningError>
+    /// # where
+    /// #     M: round_based::Mpc<ProtocolMessage = cggmp21::signing::msg::Msg<cggmp21::supported_curves::Secp256k1, sha2::Sha256>>,
+    /// # {
+    /// use cggmp21::signing::{deferred_message, DataToSign};
+    ///
+    /// let (sender, message) = deferred_message();
+    /// let mut rng = rand::rngs::OsRng;
+    /// let signing = cggmp21::signing(eid, i, parties_indexes_at_keygen, key_share)
+    ///     .sign_with_deferred_message(&mut rng, party, message);
+    /// let build_transaction = async {
+    ///     let tx = build_transaction.await;
+    ///     let _ = sender.send(DataToSign::digest::<sha2::Sha256>(&tx));
+    /// };
+    /// let (signature, ()) = futures::join!(signing, build_transaction);
+    /// # let _ = signature?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn sign_with_deferred_message<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+        message_to_sign: DeferredMessage<E>,
+    ) -> Result<Signature<E>, SigningError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = Msg<E, D>>,
+    {
+        match self
+            .run(rng, party, Some(MessageToSign::Deferred(message_to_sign)))
+            .await?
+        {
+            ProtocolOutput::Signature(sig) => Ok(sig),
+            ProtocolOutput::Presignature(_) => Err(Bug::UnexpectedProtocolOutput.into()),
+        }
+    }
+
+    async fn run<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+        message_to_sign: Option<MessageToSign<E>>,
+    ) -> Result<ProtocolOutput<E>, SigningError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = Msg<E, D>>,
+    {
+        if let Some(registry) = self.eid_registry {
+            registry.register(self.execution_id)?;
+        }
+        signing_t_out_of_n::<_, _, _, _, H, _>(
             self.tracer,
             rng,
             party,
@@ -377,18 +1038,22 @@ where
             self.i,
             self.key_share,
             self.parties_indexes_at_keygen,
-            Some(message_to_sign),
+            self.signer_set_context,
+            message_to_sign,
             self.enforce_reliable_broadcast,
+            self.enforce_echo_broadcast,
+            self.variant,
+            self.thread_pool,
+            self.paillier_decryptor,
+            self.message,
+            self.approval_hook,
+            self.rate_limiter,
             #[cfg(feature = "hd-wallets")]
             self.additive_shift,
             #[cfg(not(feature = "hd-wallets"))]
             None,
         )
-        .await?
-        {
-            ProtocolOutput::Signature(sig) => Ok(sig),
-            ProtocolOutput::Presignature(_) => Err(Bug::UnexpectedProtocolOutput.into()),
-        }
+        .await
     }
 }
 
@@ -399,29 +1064,104 @@ struct TagUnindexed<'a> {
     sid: &'a [u8],
 }
 
+/// Converts key share and public shares of signers $S$ into additive form
+///
+/// Returns $x_i$ and $\vec X$ such that $\sum_j X_j$ equals to the shared public key
+#[allow(clippy::type_complexity)]
+fn additive_shares<E: Curve, L: SecurityLevel>(
+    key_share: KeyShareRef<'_, E, L>,
+    i: PartyIndex,
+    S: &[PartyIndex],
+) -> Result<(NonZero<SecretScalar<E>>, Vec<NonZero<Point<E>>>), Bug> {
+    let X = key_share
+        .core
+        .signers_public_shares(S)
+        .map_err(Bug::SignersPublicShares)?;
+
+    if let Some(
+        vss_setup @ VssSetup {
+            weighted: Some(_), ..
+        },
+    ) = &key_share.core.vss_setup
+    {
+        // For weighted keys, each signer may hold several shares. Additive share of the signer
+        // is a sum of its shares multiplied at lagrange coefficients.
+        let shares = S
+            .iter()
+            .map(|&j| vss_setup.shares_of(j, &key_share.core.public_shares))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Bug::Subset)?;
+        let I = shares
+            .iter()
+            .flatten()
+            .map(|(I_jk, _)| *I_jk)
+            .collect::<Vec<_>>();
+        let own_shares = shares.get(usize::from(i)).ok_or(Bug::Subset)?;
+        // Position of first share of local signer in `I`
+        let offset = shares[..usize::from(i)].iter().map(Vec::len).sum::<usize>();
+
+        let x = std::iter::once(&key_share.core.x).chain(&key_share.core.extra_x);
+        if own_shares.len() != x.clone().count() {
+            return Err(Bug::Subset);
+        }
+        let x_i = (offset..)
+            .zip(x)
+            .try_fold(Scalar::zero(), |acc, (k, x_ik)| {
+                let lambda_ik = lagrange_coefficient(Scalar::zero(), k, &I)?;
+                Some(acc + *(lambda_ik * x_ik))
+            })
+            .ok_or(Bug::LagrangeCoef)?;
+        let x_i = NonZero::from_scalar(x_i)
+            .ok_or(Bug::ZeroAdditiveShare)?
+            .into_secret();
+
+        Ok((x_i, X))
+    } else if let Some(VssSetup { I, .. }) = &key_share.core.vss_setup {
+        // For t-out-of-n keys generated via VSS DKG scheme
+        let I = poly::subset(S, I).ok_or(Bug::Subset)?;
+        let lambda_i =
+            lagrange_coefficient(Scalar::zero(), usize::from(i), &I).ok_or(Bug::LagrangeCoef)?;
+        let x_i = (lambda_i * &key_share.core.x).into_secret();
+
+        Ok((x_i, X))
+    } else {
+        // For n-out-of-n keys generated using original CGGMP DKG
+        Ok((key_share.core.x.clone(), X))
+    }
+}
+
 /// t-out-of-n signing
 ///
 /// CGGMP paper doesn't support threshold signing out of the box. However, threshold signing
 /// can be easily implemented on top of CGGMP's [`signing_n_out_of_n`] by converting polynomial
 /// (VSS) key shares into additive (by multiplying at lagrange coefficient) and calling
 /// t-out-of-t protocol. The trick is described in more details in the spec.
-async fn signing_t_out_of_n<M, E, L, D, R>(
+async fn signing_t_out_of_n<M, E, L, D, H, R>(
     mut tracer: Option<&mut dyn Tracer>,
     rng: &mut R,
     party: M,
     sid: ExecutionId<'_>,
     i: PartyIndex,
-    key_share: &KeyShare<E, L>,
+    key_share: KeyShareRef<'_, E, L>,
     S: &[PartyIndex],
-    message_to_sign: Option<DataToSign<E>>,
+    signer_set_context: Option<&SignerSetContext<E>>,
+    message_to_sign: Option<MessageToSign<E>>,
     enforce_reliable_broadcast: bool,
+    enforce_echo_broadcast: bool,
+    variant: SigningVariant,
+    thread_pool: Option<&utils::ThreadPool>,
+    paillier_decryptor: Option<&dyn PaillierDecryptor>,
+    message: Option<&[u8]>,
+    approval_hook: Option<&dyn SigningApproval<E>>,
+    rate_limiter: Option<&dyn SignatureLimiter>,
     additive_shift: Option<Scalar<E>>,
 ) -> Result<ProtocolOutput<E>, SigningError>
 where
     M: Mpc<ProtocolMessage = Msg<E, D>>,
     E: Curve,
     L: SecurityLevel,
-    D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+    D: Digest + Clone + Sync + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
     R: RngCore + CryptoRng,
     NonZero<Point<E>>: AlwaysHasAffineX<E>,
 {
@@ -435,44 +1175,45 @@ where
         .len()
         .try_into()
         .map_err(|_| Bug::PartiesNumberExceedsU16)?;
-    let t = key_share
-        .core
-        .vss_setup
-        .as_ref()
-        .map(|s| s.min_signers)
-        .unwrap_or(n);
-    if S.len() != usize::from(t) {
-        return Err(InvalidArgs::MismatchedAmountOfParties.into());
-    }
+    validate_key_signers(&key_share.core.key_info, S)?;
+    // Amount of signers. For weighted keys, it may differ from the threshold
+    let t = u16::try_from(S.len()).map_err(|_| Bug::PartiesNumberExceedsU16)?;
     if !(i < t) {
         return Err(InvalidArgs::SignerIndexOutOfBounds.into());
     }
-    if S.iter().any(|&S_j| S_j >= n) {
-        return Err(InvalidArgs::InvalidS.into());
+    if S[usize::from(i)] != key_share.core.i {
+        return Err(InvalidArgs::SignerIndexMismatch {
+            expected: key_share.core.i,
+            actual: S[usize::from(i)],
+        }
+        .into());
     }
 
-    // Assemble x_i and \vec X
-    let (mut x_i, mut X) = if let Some(VssSetup { I, .. }) = &key_share.core.vss_setup {
-        // For t-out-of-n keys generated via VSS DKG scheme
-        let I = utils::subset(S, I).ok_or(Bug::Subset)?;
-        let X = utils::subset(S, &key_share.core.public_shares).ok_or(Bug::Subset)?;
-
-        let lambda_i =
-            lagrange_coefficient(Scalar::zero(), usize::from(i), &I).ok_or(Bug::LagrangeCoef)?;
-        let x_i = (lambda_i * &key_share.core.x).into_secret();
-
-        let lambda = (0..t).map(|j| lagrange_coefficient(Scalar::zero(), usize::from(j), &I));
-        let X = lambda
-            .zip(&X)
-            .map(|(lambda_j, X_j)| Some(lambda_j? * X_j))
-            .collect::<Option<Vec<_>>>()
-            .ok_or(Bug::LagrangeCoef)?;
+    let approval = approval_hook.map(|hook| Approval {
+        hook,
+        eid: sid,
+        signers: S,
+    });
+    if let Some(approval) = &approval {
+        tracer.stage("Request approval of signing");
+        let data_to_sign = match &message_to_sign {
+            Some(MessageToSign::Known(data_to_sign)) => Some(*data_to_sign),
+            _ => None,
+        };
+        approval
+            .check(approval::Stage::BeforeRound1, data_to_sign)
+            .map_err(|err| SigningError(Reason::Rejected(err)))?;
+    }
 
-        (x_i, X)
-    } else {
-        // For n-out-of-n keys generated using original CGGMP DKG
-        let X = utils::subset(S, &key_share.core.public_shares).ok_or(Bug::Subset)?;
-        (key_share.core.x.clone(), X)
+    // Assemble x_i and \vec X
+    let (mut x_i, mut X) = match signer_set_context {
+        Some(context) => {
+            if !context.matches(key_share, i, S) {
+                return Err(InvalidArgs::SignerSetContextMismatch.into());
+            }
+            (context.x_i.clone(), context.X.clone())
+        }
+        None => additive_shares(key_share, i, S)?,
     };
     debug_assert_eq!(key_share.core.shared_public_key, X.iter().sum::<Point<E>>());
 
@@ -492,11 +1233,21 @@ where
     );
 
     // Assemble rest of the data
-    let (p_i, q_i) = (&key_share.aux.p, &key_share.aux.q);
-    let R = utils::subset(S, &key_share.aux.parties).ok_or(Bug::Subset)?;
+    let dec_i = key_share
+        .aux
+        .decryption_key()
+        .map_err(|_| Bug::InvalidOwnPaillierKey)?;
+    let decryptor: &dyn PaillierDecryptor = match paillier_decryptor {
+        Some(decryptor) if decryptor.n() != dec_i.n() => {
+            return Err(InvalidArgs::PaillierDecryptorMismatch.into())
+        }
+        Some(decryptor) => decryptor,
+        None => &*dec_i,
+    };
+    let R = poly::subset(S, &key_share.aux.parties).ok_or(Bug::Subset)?;
 
     // t-out-of-t signing
-    signing_n_out_of_n::<_, _, L, _, _>(
+    signing_n_out_of_n::<_, _, L, _, H, _>(
         tracer,
         rng,
         party,
@@ -506,11 +1257,18 @@ where
         &x_i,
         &X,
         key_share.core.shared_public_key + Shift,
-        p_i,
-        q_i,
+        &dec_i,
+        decryptor,
         &R,
         message_to_sign,
+        key_share.policy,
+        message,
+        approval,
+        rate_limiter.map(|limiter| (limiter, KeyFingerprint::of(&key_share.core.key_info))),
         enforce_reliable_broadcast,
+        enforce_echo_broadcast,
+        variant,
+        thread_pool,
     )
     .await
 }
@@ -519,7 +1277,7 @@ where
 ///
 /// Implementation has very little differences compared to original CGGMP protocol: we added broadcast
 /// reliability check, fixed some typos in CGGMP, etc. Differences are covered in the specs.
-async fn signing_n_out_of_n<M, E, L, D, R>(
+async fn signing_n_out_of_n<M, E, L, D, H, R>(
     mut tracer: Option<&mut dyn Tracer>,
     rng: &mut R,
     party: M,
@@ -529,17 +1287,25 @@ async fn signing_n_out_of_n<M, E, L, D, R>(
     x_i: &NonZero<SecretScalar<E>>,
     X: &[NonZero<Point<E>>],
     pk: Point<E>,
-    p_i: &Integer,
-    q_i: &Integer,
+    dec_i: &fast_paillier::DecryptionKey,
+    decryptor: &dyn PaillierDecryptor,
     R: &[PartyAux],
-    message_to_sign: Option<DataToSign<E>>,
+    message_to_sign: Option<MessageToSign<E>>,
+    policy: Option<&KeyUsagePolicy>,
+    message: Option<&[u8]>,
+    approval: Option<Approval<'_, E>>,
+    rate_limiter: Option<(&dyn SignatureLimiter, KeyFingerprint)>,
     enforce_reliable_broadcast: bool,
+    enforce_echo_broadcast: bool,
+    variant: SigningVariant,
+    thread_pool: Option<&utils::ThreadPool>,
 ) -> Result<ProtocolOutput<E>, SigningError>
 where
     M: Mpc<ProtocolMessage = Msg<E, D>>,
     E: Curve,
     L: SecurityLevel,
-    D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+    D: Digest + Clone + Sync + 'static,
+    H: Digest<OutputSize = D::OutputSize>,
     R: RngCore + CryptoRng,
     NonZero<Point<E>>: AlwaysHasAffineX<E>,
 {
@@ -551,9 +1317,20 @@ where
     tracer.stage("Retrieve auxiliary data");
     let R_i = &R[usize::from(i)];
     let N_i = &R_i.N;
-    let dec_i: fast_paillier::DecryptionKey =
-        fast_paillier::DecryptionKey::from_primes(p_i.clone(), q_i.clone())
-            .map_err(|_| Bug::InvalidOwnPaillierKey)?;
+    let encs = R
+        .iter()
+        .map(|R_j| fast_paillier::EncryptionKey::from_n(R_j.N.clone()))
+        .collect::<Vec<_>>();
+
+    tracer.stage("Check that Paillier moduli are not related");
+    let related = utils::related_moduli(R.iter().map(|R_j| &R_j.N));
+    if !related.is_empty() {
+        let parties = related
+            .into_iter()
+            .filter_map(|j| PartyIndex::try_from(j).ok())
+            .collect();
+        return Err(SigningAborted::RelatedPaillierModuli(parties).into());
+    }
 
     tracer.stage("Precompute execution id and security params");
     let sid = sid.as_bytes();
@@ -565,9 +1342,12 @@ where
     let round1b = rounds.add_round(RoundInput::<MsgRound1b>::p2p(i, n));
     let round1a_sync = rounds.add_round(RoundInput::<MsgReliabilityCheck<D>>::broadcast(i, n));
     let round2 = rounds.add_round(RoundInput::<MsgRound2<E>>::p2p(i, n));
+    let round2_with_check =
+        rounds.add_round(RoundInput::<MsgRound2WithReliabilityCheck<E, D>>::p2p(i, n));
     let round3 = rounds.add_round(RoundInput::<MsgRound3<E>>::p2p(i, n));
     let round4 = rounds.add_round(RoundInput::<MsgRound4<E>>::broadcast(i, n));
-    let mut rounds = rounds.listen(incomings);
+    let round4_echo = rounds.add_round(RoundInput::<MsgEcho<D, 4>>::broadcast(i, n));
+    let mut rounds = rounds.listen(abort::intercept(incomings));
 
     // Round 1
     tracer.round_begins();
@@ -598,8 +1378,9 @@ where
         .map_err(IoError::send_message)?;
     tracer.msg_sent();
 
-    let parties_shared_state = D::new_with_prefix(D::digest(sid));
+    let parties_shared_state = utils::Truncated::new(D::new_with_prefix(D::digest(sid)));
     for j in utils::iter_peers(i, n) {
+        tracer.process_peer(j);
         tracer.stage("Prove ψ0_j");
         let R_j = &R[usize::from(j)];
 
@@ -607,7 +1388,7 @@ where
             parties_shared_state.clone().chain_update(i.to_be_bytes()),
             &R_j.into(),
             pi_enc::Data {
-                key: &dec_i,
+                key: dec_i,
                 ciphertext: &K_i,
             },
             pi_enc::PrivateData {
@@ -642,41 +1423,64 @@ where
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
 
+    tracer.stage("Validate structure of K_j, G_j");
+    let malformed = ciphertexts
+        .iter_indexed()
+        .filter(|(j, _msg_id, msg)| {
+            let nn = encs[usize::from(*j)].nn();
+            !limits::is_ciphertext_well_formed(&msg.K, nn)
+                || !limits::is_ciphertext_well_formed(&msg.G, nn)
+        })
+        .map(|(j, msg_id, _)| (j, msg_id))
+        .collect::<Vec<_>>();
+    if !malformed.is_empty() {
+        return Err(SigningAborted::MalformedCiphertext(malformed).into());
+    }
+
     // Reliability check (if enabled)
+    //
+    // In round-optimized variant, hash of received messages is piggybacked to round 2
+    // messages instead of being sent in a dedicated round
+    let mut piggybacked_reliability_check = None;
     if enforce_reliable_broadcast {
         tracer.stage("Hash received msgs (reliability check)");
-        let h_i = udigest::Tag::<D>::new_structured(TagUnindexed { sid }).digest_iter(
+        let h_i = udigest::Tag::<H>::new_structured(TagUnindexed { sid }).digest_iter(
             ciphertexts.iter_including_me(&MsgRound1a {
                 K: K_i.clone(),
                 G: G_i.clone(),
             }),
         );
 
-        tracer.send_msg();
-        outgoings
-            .send(Outgoing::broadcast(Msg::ReliabilityCheck(
-                MsgReliabilityCheck(h_i),
-            )))
-            .await
-            .map_err(IoError::send_message)?;
-        tracer.msg_sent();
-
-        tracer.round_begins();
-
-        tracer.receive_msgs();
-        let round1a_hashes = rounds
-            .complete(round1a_sync)
-            .await
-            .map_err(IoError::receive_message)?;
-        tracer.msgs_received();
-        tracer.stage("Assert other parties hashed messages (reliability check)");
-        let parties_have_different_hashes = round1a_hashes
-            .into_iter_indexed()
-            .filter(|(_j, _msg_id, hash)| hash.0 != h_i)
-            .map(|(j, msg_id, _)| (j, msg_id))
-            .collect::<Vec<_>>();
-        if !parties_have_different_hashes.is_empty() {
-            return Err(SigningAborted::Round1aNotReliable(parties_have_different_hashes).into());
+        match variant {
+            SigningVariant::Standard => {
+                tracer.send_msg();
+                outgoings
+                    .send(Outgoing::broadcast(Msg::ReliabilityCheck(
+                        MsgReliabilityCheck(h_i),
+                    )))
+                    .await
+                    .map_err(IoError::send_message)?;
+                tracer.msg_sent();
+
+                tracer.round_begins();
+
+                tracer.receive_msgs();
+                let round1a_hashes = rounds
+                    .complete(round1a_sync)
+                    .await
+                    .map_err(IoError::receive_message)?;
+                tracer.msgs_received();
+                tracer.stage("Assert other parties hashed messages (reliability check)");
+                let parties = round1a_hashes
+                    .into_iter_indexed()
+                    .filter(|(_j, _msg_id, hash)| hash.0 != h_i)
+                    .map(|(j, msg_id, _)| (j, msg_id))
+                    .collect::<Vec<_>>();
+                if !parties.is_empty() {
+                    return Err(SigningAborted::BroadcastNotReliable { round: 1, parties }.into());
+                }
+            }
+            SigningVariant::RoundOptimized => piggybacked_reliability_check = Some(h_i),
         }
     }
 
@@ -687,12 +1491,12 @@ where
         for ((j, msg1_id, ciphertext), (_, msg2_id, proof)) in
             ciphertexts.iter_indexed().zip(psi0.iter_indexed())
         {
-            let R_j = &R[usize::from(j)];
+            tracer.process_peer(j);
             if pi_enc::non_interactive::verify(
                 parties_shared_state.clone().chain_update(j.to_be_bytes()),
                 &R_i.into(),
                 pi_enc::Data {
-                    key: &fast_paillier::EncryptionKey::from_n(R_j.N.clone()),
+                    key: &encs[usize::from(j)],
                     ciphertext: &ciphertext.K,
                 },
                 &proof.psi0.0,
@@ -715,13 +1519,19 @@ where
     let Gamma_i = Point::generator() * &gamma_i;
     let J = (Integer::ONE << L::ELL_PRIME).complete();
 
-    let mut beta_sum = Scalar::zero();
-    let mut hat_beta_sum = Scalar::zero();
-    for (j, _, ciphertext_j) in ciphertexts.iter_indexed() {
-        tracer.stage("Sample random r, hat_r, s, hat_s, beta, hat_beta");
+    // Per-peer work is independent, so it's computed in parallel if `parallel` feature is enabled.
+    // Each peer gets its own rng seeded from the main one.
+    tracer
+        .stage("Compute D_ji, F_ji, hat_D_ji, hat_F_ji and prove psi_ji, hat_psi_ji, psi_prime_ji");
+    let peers = ciphertexts
+        .iter_indexed()
+        .map(|(j, _, ciphertext_j)| (j, ciphertext_j, utils::fork_rng(rng)))
+        .collect::<Vec<_>>();
+    let psi_cst = parties_shared_state.clone().chain_update(i.to_be_bytes());
+    let round2_outputs = utils::par_map(thread_pool, peers, |(j, ciphertext_j, mut rng)| {
+        let rng = &mut rng;
         let R_j = &R[usize::from(j)];
-        let N_j = &R_j.N;
-        let enc_j = fast_paillier::EncryptionKey::from_n(N_j.clone());
+        let enc_j = &encs[usize::from(j)];
 
         let r_ij = N_i.random_below_ref(&mut utils::external_rand(rng)).into();
         let hat_r_ij = N_i.random_below_ref(&mut utils::external_rand(rng)).into();
@@ -731,10 +1541,6 @@ where
         let beta_ij = Integer::from_rng_pm(&J, rng);
         let hat_beta_ij = Integer::from_rng_pm(&J, rng);
 
-        beta_sum += beta_ij.to_scalar();
-        hat_beta_sum += hat_beta_ij.to_scalar();
-
-        tracer.stage("Encrypt D_ji");
         // D_ji = (gamma_i * K_j) + enc_j(-beta_ij, s_ij)
         let D_ji = {
             let gamma_i_times_K_j = enc_j
@@ -748,12 +1554,10 @@ where
                 .map_err(|_| Bug::PaillierOp(BugSource::D_ji))?
         };
 
-        tracer.stage("Encrypt F_ji");
         let F_ji = dec_i
             .encrypt_with(&(-&beta_ij).complete(), &r_ij)
             .map_err(|_| Bug::PaillierEnc(BugSource::F_ji))?;
 
-        tracer.stage("Encrypt hat_D_ji");
         // Dˆ_ji = (x_i * K_j) + enc_j(-hat_beta_ij, hat_s_ij)
         let hat_D_ji = {
             let x_i_times_K_j = enc_j
@@ -766,21 +1570,17 @@ where
                 .oadd(&x_i_times_K_j, &neg_hat_beta_ij_enc)
                 .map_err(|_| Bug::PaillierOp(BugSource::hat_D))?
         };
-        runtime.yield_now().await;
 
-        tracer.stage("Encrypt hat_F_ji");
         let hat_F_ji = dec_i
             .encrypt_with(&(-&hat_beta_ij).complete(), &hat_r_ij)
             .map_err(|_| Bug::PaillierEnc(BugSource::hat_F))?;
 
-        tracer.stage("Prove psi_ji");
-        let psi_cst = parties_shared_state.clone().chain_update(i.to_be_bytes());
         let psi_ji = pi_aff::non_interactive::prove(
             psi_cst.clone(),
             &R_j.into(),
             pi_aff::Data {
-                key0: &enc_j,
-                key1: &dec_i,
+                key0: enc_j,
+                key1: dec_i,
                 c: &ciphertext_j.K,
                 d: &D_ji,
                 y: &F_ji,
@@ -796,15 +1596,13 @@ where
             &mut *rng,
         )
         .map_err(|e| Bug::PiAffG(BugSource::psi, e))?;
-        runtime.yield_now().await;
 
-        tracer.stage("Prove psiˆ_ji");
         let hat_psi_ji = pi_aff::non_interactive::prove(
             psi_cst.clone(),
             &R_j.into(),
             pi_aff::Data {
-                key0: &enc_j,
-                key1: &dec_i,
+                key0: enc_j,
+                key1: dec_i,
                 c: &ciphertext_j.K,
                 d: &hat_D_ji,
                 y: &hat_F_ji,
@@ -821,12 +1619,11 @@ where
         )
         .map_err(|e| Bug::PiAffG(BugSource::hat_psi, e))?;
 
-        tracer.stage("Prove psi_prime_ji ");
         let psi_prime_ji = pi_log::non_interactive::prove(
-            psi_cst,
+            psi_cst.clone(),
             &R_j.into(),
             pi_log::Data {
-                key0: &dec_i,
+                key0: dec_i,
                 c: &G_i,
                 x: &Gamma_i,
                 b: &Point::<E>::generator().to_point(),
@@ -839,23 +1636,44 @@ where
             &mut *rng,
         )
         .map_err(|e| Bug::PiLog(BugSource::psi_prime, e))?;
-        runtime.yield_now().await;
+
+        Ok::<_, Bug>((
+            j,
+            beta_ij.to_scalar::<E>(),
+            hat_beta_ij.to_scalar::<E>(),
+            MsgRound2 {
+                Gamma: Gamma_i,
+                D: D_ji,
+                F: F_ji,
+                hat_D: hat_D_ji,
+                hat_F: hat_F_ji,
+                psi: psi_ji,
+                hat_psi: hat_psi_ji,
+                psi_prime: psi_prime_ji,
+            },
+        ))
+    });
+    runtime.yield_now().await;
+
+    let mut beta_sum = Scalar::zero();
+    let mut hat_beta_sum = Scalar::zero();
+    for output in round2_outputs {
+        let (j, beta_ij, hat_beta_ij, msg) = output?;
+        tracer.process_peer(j);
+        beta_sum += beta_ij;
+        hat_beta_sum += hat_beta_ij;
+
+        let msg = match piggybacked_reliability_check {
+            Some(h_i) => Msg::Round2WithReliabilityCheck(MsgRound2WithReliabilityCheck {
+                round2: msg,
+                reliability_check: MsgReliabilityCheck(h_i),
+            }),
+            None => Msg::Round2(msg),
+        };
 
         tracer.send_msg();
         outgoings
-            .send(Outgoing::p2p(
-                j,
-                Msg::Round2(MsgRound2 {
-                    Gamma: Gamma_i,
-                    D: D_ji,
-                    F: F_ji,
-                    hat_D: hat_D_ji,
-                    hat_F: hat_F_ji,
-                    psi: psi_ji,
-                    hat_psi: hat_psi_ji,
-                    psi_prime: psi_prime_ji,
-                }),
-            ))
+            .send(Outgoing::p2p(j, msg))
             .await
             .map_err(IoError::send_message)?;
         tracer.msg_sent();
@@ -866,127 +1684,250 @@ where
 
     // Step 1
     tracer.receive_msgs();
-    let round2_msgs = rounds
-        .complete(round2)
-        .await
-        .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
+    let round2_msgs = if let Some(h_i) = piggybacked_reliability_check {
+        let round2_msgs = rounds
+            .complete(round2_with_check)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received();
 
-    let mut faulty_parties = vec![];
-    for ((j, msg_id, msg), (_, ciphertext_msg_id, ciphertexts)) in
-        round2_msgs.iter_indexed().zip(ciphertexts.iter_indexed())
-    {
-        tracer.stage("Retrieve auxiliary data");
-        let X_j = X[usize::from(j)];
-        let R_j = &R[usize::from(j)];
-        let enc_j = fast_paillier::EncryptionKey::from_n(R_j.N.clone());
-        let cst_j = parties_shared_state.clone().chain_update(j.to_be_bytes());
+        tracer.stage("Assert other parties hashed messages (reliability check)");
+        let parties = round2_msgs
+            .iter_indexed()
+            .filter(|(_j, _msg_id, msg)| msg.reliability_check.0 != h_i)
+            .map(|(j, msg_id, _)| (j, msg_id))
+            .collect::<Vec<_>>();
+        if !parties.is_empty() {
+            return Err(SigningAborted::BroadcastNotReliable { round: 1, parties }.into());
+        }
+        round2_msgs
+            .into_iter_indexed()
+            .map(|(j, msg_id, msg)| (j, msg_id, msg.round2))
+            .collect::<Vec<_>>()
+    } else {
+        let round2_msgs = rounds
+            .complete(round2)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received();
+        round2_msgs.into_iter_indexed().collect::<Vec<_>>()
+    };
 
-        tracer.stage("Validate psi");
-        let psi_invalid = pi_aff::non_interactive::verify(
-            cst_j.clone(),
-            &R_i.into(),
-            pi_aff::Data {
-                key0: &dec_i,
-                key1: &enc_j,
-                c: &K_i,
-                d: &msg.D,
-                y: &msg.F,
-                x: &msg.Gamma,
-            },
-            &msg.psi.0,
-            &security_params.pi_aff,
-            &msg.psi.1,
-        )
-        .err();
+    tracer.stage("Validate structure of D_ij, F_ji, hat_D_ij, hat_F_ji");
+    let malformed = round2_msgs
+        .iter()
+        .filter(|(j, _msg_id, msg)| {
+            // D and hat_D are encrypted with our key, F and hat_F with the key of the sender
+            let nn_i = encs[usize::from(i)].nn();
+            let nn_j = encs[usize::from(*j)].nn();
+            !limits::is_ciphertext_well_formed(&msg.D, nn_i)
+                || !limits::is_ciphertext_well_formed(&msg.hat_D, nn_i)
+                || !limits::is_ciphertext_well_formed(&msg.F, nn_j)
+                || !limits::is_ciphertext_well_formed(&msg.hat_F, nn_j)
+        })
+        .map(|(j, msg_id, _)| (*j, *msg_id))
+        .collect::<Vec<_>>();
+    if !malformed.is_empty() {
+        return Err(SigningAborted::MalformedCiphertext(malformed).into());
+    }
 
-        tracer.stage("Validate hat_psi");
-        let hat_psi_invalid = pi_aff::non_interactive::verify(
-            cst_j.clone(),
-            &R_i.into(),
-            pi_aff::Data {
-                key0: &dec_i,
-                key1: &enc_j,
-                c: &K_i,
-                d: &msg.hat_D,
-                y: &msg.hat_F,
-                x: &X_j,
-            },
-            &msg.hat_psi.0,
-            &security_params.pi_aff,
-            &msg.hat_psi.1,
-        )
-        .err();
+    // All proofs are verified against our own ring-Pedersen parameters, so the most expensive
+    // part of verification is done in batch. Rest of the checks are done for each peer independently,
+    // in parallel if `parallel` feature is enabled. If batch verification fails, proofs are verified
+    // individually to find out which parties are faulty.
+    tracer.stage("Validate psi, hat_psi, psi_prime in batch");
+    let aux_i: pi_aff::Aux = R_i.into();
+    let peers = round2_msgs
+        .iter()
+        .map(|(j, msg_id, msg)| (*j, *msg_id, msg))
+        .zip(ciphertexts.iter_indexed())
+        .collect::<Vec<_>>();
+    let equations = utils::par_map(
+        thread_pool,
+        peers.clone(),
+        |((j, _msg_id, msg), (_, _ciphertext_msg_id, ciphertexts))| {
+            let X_j = X[usize::from(j)];
+            let enc_j = &encs[usize::from(j)];
+            let cst_j = parties_shared_state.clone().chain_update(j.to_be_bytes());
+
+            let [psi_eq1, psi_eq2] = zk::batch::pi_aff(
+                cst_j.clone(),
+                &aux_i,
+                pi_aff::Data {
+                    key0: dec_i,
+                    key1: enc_j,
+                    c: &K_i,
+                    d: &msg.D,
+                    y: &msg.F,
+                    x: &msg.Gamma,
+                },
+                &msg.psi.0,
+                &security_params.pi_aff,
+                &msg.psi.1,
+            )?;
+            let [hat_psi_eq1, hat_psi_eq2] = zk::batch::pi_aff(
+                cst_j.clone(),
+                &aux_i,
+                pi_aff::Data {
+                    key0: dec_i,
+                    key1: enc_j,
+                    c: &K_i,
+                    d: &msg.hat_D,
+                    y: &msg.hat_F,
+                    x: &X_j,
+                },
+                &msg.hat_psi.0,
+                &security_params.pi_aff,
+                &msg.hat_psi.1,
+            )?;
+            let psi_prime_eq = zk::batch::pi_log(
+                cst_j,
+                &aux_i,
+                pi_log::Data {
+                    key0: enc_j,
+                    c: &ciphertexts.G,
+                    x: &msg.Gamma,
+                    b: &Point::<E>::generator().to_point(),
+                },
+                &msg.psi_prime.0,
+                &security_params.pi_log,
+                &msg.psi_prime.1,
+            )?;
+            Some([psi_eq1, psi_eq2, hat_psi_eq1, hat_psi_eq2, psi_prime_eq])
+        },
+    );
+    let batch_valid = equations.iter().all(Option::is_some) && {
+        let mut batch = zk::batch::RingPedersenBatch::new(&aux_i, dec_i.p(), dec_i.q());
+        for eq in equations.into_iter().flatten().flatten() {
+            batch.add(rng, eq);
+        }
+        batch.verify()
+    };
+    runtime.yield_now().await;
 
-        tracer.stage("Validate psi_prime");
-        let psi_prime_invalid = pi_log::non_interactive::verify(
-            cst_j,
-            &R_i.into(),
-            pi_log::Data {
-                key0: &enc_j,
-                c: &ciphertexts.G,
-                x: &msg.Gamma,
-                b: &Point::<E>::generator().to_point(),
+    if !batch_valid {
+        tracer.stage("Validate psi, hat_psi, psi_prime individually");
+        let faulty_parties = utils::par_map(
+            thread_pool,
+            peers,
+            |((j, msg_id, msg), (_, ciphertext_msg_id, ciphertexts))| {
+                let X_j = X[usize::from(j)];
+                let enc_j = &encs[usize::from(j)];
+                let cst_j = parties_shared_state.clone().chain_update(j.to_be_bytes());
+
+                let psi_invalid = pi_aff::non_interactive::verify(
+                    cst_j.clone(),
+                    &aux_i,
+                    pi_aff::Data {
+                        key0: dec_i,
+                        key1: enc_j,
+                        c: &K_i,
+                        d: &msg.D,
+                        y: &msg.F,
+                        x: &msg.Gamma,
+                    },
+                    &msg.psi.0,
+                    &security_params.pi_aff,
+                    &msg.psi.1,
+                )
+                .err();
+
+                let hat_psi_invalid = pi_aff::non_interactive::verify(
+                    cst_j.clone(),
+                    &aux_i,
+                    pi_aff::Data {
+                        key0: dec_i,
+                        key1: enc_j,
+                        c: &K_i,
+                        d: &msg.hat_D,
+                        y: &msg.hat_F,
+                        x: &X_j,
+                    },
+                    &msg.hat_psi.0,
+                    &security_params.pi_aff,
+                    &msg.hat_psi.1,
+                )
+                .err();
+
+                let psi_prime_invalid = pi_log::non_interactive::verify(
+                    cst_j,
+                    &aux_i,
+                    pi_log::Data {
+                        key0: enc_j,
+                        c: &ciphertexts.G,
+                        x: &msg.Gamma,
+                        b: &Point::<E>::generator().to_point(),
+                    },
+                    &msg.psi_prime.0,
+                    &security_params.pi_log,
+                    &msg.psi_prime.1,
+                )
+                .err();
+
+                if psi_invalid.is_some() || hat_psi_invalid.is_some() || psi_prime_invalid.is_some()
+                {
+                    Some((
+                        j,
+                        ciphertext_msg_id,
+                        msg_id,
+                        (psi_invalid, hat_psi_invalid, psi_prime_invalid),
+                    ))
+                } else {
+                    None
+                }
             },
-            &msg.psi_prime.0,
-            &security_params.pi_log,
-            &msg.psi_prime.1,
         )
-        .err();
-
-        if psi_invalid.is_some() || hat_psi_invalid.is_some() || psi_prime_invalid.is_some() {
-            faulty_parties.push((
-                j,
-                ciphertext_msg_id,
-                msg_id,
-                (psi_invalid, hat_psi_invalid, psi_prime_invalid),
-            ))
-        }
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
         runtime.yield_now().await;
-    }
 
-    if !faulty_parties.is_empty() {
-        return Err(SigningAborted::InvalidPsi(faulty_parties).into());
+        if !faulty_parties.is_empty() {
+            return Err(SigningAborted::InvalidPsi(faulty_parties).into());
+        }
     }
 
     // Step 2
     tracer.stage("Compute Gamma, Delta_i, delta_i, chi_i");
-    let Gamma = Gamma_i + round2_msgs.iter().map(|msg| msg.Gamma).sum::<Point<E>>();
+    let Gamma = Gamma_i
+        + round2_msgs
+            .iter()
+            .map(|(_, _, msg)| msg.Gamma)
+            .sum::<Point<E>>();
     let Delta_i = Gamma * &k_i;
 
-    let alpha_sum =
-        round2_msgs
-            .iter()
-            .map(|msg| &msg.D)
-            .try_fold(Scalar::<E>::zero(), |sum, D_ij| {
-                let alpha_ij = dec_i
-                    .decrypt(D_ij)
-                    .map_err(|_| Bug::PaillierDec(BugSource::alpha))?;
-                Ok::<_, Bug>(sum + alpha_ij.to_scalar())
-            })?;
-    let hat_alpha_sum =
-        round2_msgs
-            .iter()
-            .map(|msg| &msg.hat_D)
-            .try_fold(Scalar::zero(), |sum, hat_D_ij| {
-                let hat_alpha_ij = dec_i
-                    .decrypt(hat_D_ij)
-                    .map_err(|_| Bug::PaillierDec(BugSource::hat_alpha))?;
-                Ok::<_, Bug>(sum + hat_alpha_ij.to_scalar())
-            })?;
+    let alpha_sum = round2_msgs.iter().map(|(_, _, msg)| &msg.D).try_fold(
+        Scalar::<E>::zero(),
+        |sum, D_ij| {
+            let alpha_ij = decryptor
+                .decrypt(D_ij)
+                .map_err(|e| SigningError(Reason::PaillierDecryption(e)))?;
+            Ok::<_, SigningError>(sum + alpha_ij.to_scalar())
+        },
+    )?;
+    let hat_alpha_sum = round2_msgs.iter().map(|(_, _, msg)| &msg.hat_D).try_fold(
+        Scalar::zero(),
+        |sum, hat_D_ij| {
+            let hat_alpha_ij = decryptor
+                .decrypt(hat_D_ij)
+                .map_err(|e| SigningError(Reason::PaillierDecryption(e)))?;
+            Ok::<_, SigningError>(sum + hat_alpha_ij.to_scalar())
+        },
+    )?;
 
     let delta_i = gamma_i.as_ref() * k_i.as_ref() + alpha_sum + beta_sum;
     let chi_i = x_i * k_i.as_ref() + hat_alpha_sum + hat_beta_sum;
     runtime.yield_now().await;
 
     for j in utils::iter_peers(i, n) {
+        tracer.process_peer(j);
         tracer.stage("Prove psi_prime_prime");
         let R_j = &R[usize::from(j)];
         let psi_prime_prime = pi_log::non_interactive::prove(
             parties_shared_state.clone().chain_update(i.to_be_bytes()),
             &R_j.into(),
             pi_log::Data {
-                key0: &dec_i,
+                key0: dec_i,
                 c: &K_i,
                 x: &Delta_i,
                 b: &Gamma,
@@ -1031,11 +1972,11 @@ where
     for ((j, msg_id, msg_j), (_, ciphertext_id, ciphertext_j)) in
         round3_msgs.iter_indexed().zip(ciphertexts.iter_indexed())
     {
-        let R_j = &R[usize::from(j)];
-        let enc_j = fast_paillier::EncryptionKey::from_n(R_j.N.clone());
+        tracer.process_peer(j);
+        let enc_j = &encs[usize::from(j)];
 
         let data = pi_log::Data {
-            key0: &enc_j,
+            key0: enc_j,
             c: &ciphertext_j.K,
             x: &msg_j.Delta,
             b: &Gamma,
@@ -1074,11 +2015,7 @@ where
 
     let R = Gamma * delta.invert().ok_or(Bug::ZeroDelta)?;
     let R = NonZero::from_point(R).ok_or(Bug::ZeroR)?;
-    let presig = Presignature {
-        R,
-        k: k_i,
-        chi: SecretScalar::new(&mut chi_i.clone()),
-    };
+    let presig = Presignature::from_parts(R, k_i, SecretScalar::new(&mut chi_i.clone()));
 
     // If message is not specified, protocol terminates here and outputs partial
     // signature
@@ -1086,18 +2023,77 @@ where
         tracer.protocol_ends();
         return Ok(ProtocolOutput::Presignature(presig));
     };
+    let deferred_message;
+    let (message_to_sign, message) = match message_to_sign {
+        MessageToSign::Known(message_to_sign) => (message_to_sign, message),
+        MessageToSign::Deferred(message_to_sign) => {
+            tracer.stage("Wait for message to sign");
+            match message_to_sign.0.await {
+                Ok(deferred) => {
+                    deferred_message = deferred.message;
+                    (
+                        deferred.message_to_sign,
+                        deferred_message.as_deref().or(message),
+                    )
+                }
+                Err(_) => {
+                    outgoings
+                        .send(MsgAbort::broadcast())
+                        .await
+                        .map_err(IoError::send_message)?;
+                    return Err(SigningError(Reason::MessageNotProvided));
+                }
+            }
+        }
+    };
+    if let Some(policy) = policy {
+        tracer.stage("Evaluate key usage policy");
+        if let Err(err) = policy.check::<E, D>(&message_to_sign, message) {
+            outgoings
+                .send(MsgAbort::broadcast())
+                .await
+                .map_err(IoError::send_message)?;
+            return Err(SigningError(Reason::PolicyViolation(err)));
+        }
+    }
+    if let Some(approval) = &approval {
+        tracer.stage("Request approval of partial signature");
+        if let Err(err) = approval.check(
+            approval::Stage::BeforePartialSignature,
+            Some(message_to_sign),
+        ) {
+            outgoings
+                .send(MsgAbort::broadcast())
+                .await
+                .map_err(IoError::send_message)?;
+            return Err(SigningError(Reason::Rejected(err)));
+        }
+    }
+    if let Some((limiter, key)) = rate_limiter {
+        tracer.stage("Account signature in rate limiter");
+        if let Err(err) = limiter.acquire(&key) {
+            outgoings
+                .send(MsgAbort::broadcast())
+                .await
+                .map_err(IoError::send_message)?;
+            return Err(SigningError(Reason::RateLimited(err)));
+        }
+    }
 
     // Signing
     tracer.named_round_begins("Partial signing");
 
     // Round 1
-    let partial_sig = presig.issue_partial_signature(message_to_sign);
+    let partial_sig = presig
+        .issue_partial_signature(message_to_sign)
+        .map_err(|_| Bug::PresignatureReused)?;
+    let my_partial_sig = MsgRound4 {
+        sigma: partial_sig.sigma,
+    };
 
     tracer.send_msg();
     outgoings
-        .send(Outgoing::broadcast(Msg::Round4(MsgRound4 {
-            sigma: partial_sig.sigma,
-        })))
+        .send(Outgoing::broadcast(Msg::Round4(my_partial_sig.clone())))
         .await
         .map_err(IoError::send_message)?;
     tracer.msg_sent();
@@ -1111,6 +2107,37 @@ where
         .await
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
+
+    // Optional echo broadcast
+    if enforce_echo_broadcast {
+        tracer.stage("Hash received msgs (echo broadcast)");
+        let echo = MsgEcho::<D, 4>::new_with_digest::<H>(
+            sid,
+            partial_sigs.iter_including_me(&my_partial_sig),
+        );
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round4Echo(echo.clone())))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.round_begins();
+
+        tracer.receive_msgs();
+        let echoes = rounds
+            .complete(round4_echo)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received();
+
+        tracer.stage("Assert other parties echoed the same msgs (echo broadcast)");
+        let parties = echo.find_mismatched(echoes);
+        if !parties.is_empty() {
+            return Err(SigningAborted::BroadcastNotReliable { round: 4, parties }.into());
+        }
+    }
     let sig = {
         let r = NonZero::from_scalar(partial_sig.r);
         let s = NonZero::from_scalar(
@@ -1143,15 +2170,68 @@ where
     ///
     /// **Never reuse presignatures!** If you use the same presignatures to sign two different
     /// messages, it leaks the private key!
-    pub fn issue_partial_signature(self, message_to_sign: DataToSign<E>) -> PartialSignature<E> {
-        let r = self.R.x().to_scalar();
+    ///
+    /// Presignature is consumed. If partial signature was already issued by a
+    /// [copy](Self::dangerously_clone) of this presignature for a different message, an
+    /// error is returned. Issuing partial signature for the same message is harmless and
+    /// always succeeds.
+    pub fn issue_partial_signature(
+        self,
+        message_to_sign: DataToSign<E>,
+    ) -> Result<PartialSignature<E>, PresignatureReused> {
         let m = message_to_sign.to_scalar();
+        {
+            let mut issued_for = self.issued_for.lock().map_err(|_| PresignatureReused)?;
+            if matches!(*issued_for, Some(prev_m) if prev_m != m) {
+                return Err(PresignatureReused);
+            }
+            *issued_for = Some(m);
+        }
+
+        let r = self.R.x().to_scalar();
         let sigma_i = self.k.as_ref() * m + r * self.chi.as_ref();
-        PartialSignature { r, sigma: sigma_i }
+        Ok(PartialSignature { r, sigma: sigma_i })
     }
 }
 
 impl<E: Curve> Presignature<E> {
+    /// Constructs a presignature from its components
+    pub fn from_parts(R: NonZero<Point<E>>, k: SecretScalar<E>, chi: SecretScalar<E>) -> Self {
+        Self {
+            R,
+            k,
+            chi,
+            issued_for: Default::default(),
+        }
+    }
+
+    /// Returns public commitments to the presignature
+    ///
+    /// If [derivation path](Self::set_derivation_path) is used, public data needs to be obtained
+    /// after derivation path is set.
+    pub fn public_data(&self) -> PresignaturePublicData<E> {
+        PresignaturePublicData {
+            R: self.R,
+            K: *self.R * self.k.as_ref(),
+            Chi: *self.R * self.chi.as_ref(),
+        }
+    }
+
+    /// Copies the presignature
+    ///
+    /// **Never reuse presignatures!** A copy may be needed, for instance, to persist the
+    /// presignature while keeping it in memory. Copies share issuance tracking: once partial
+    /// signature is issued by any of them, other copies refuse to sign a different message.
+    /// Note that tracking doesn't survive serialization.
+    pub fn dangerously_clone(&self) -> Self {
+        Self {
+            R: self.R,
+            k: self.k.clone(),
+            chi: self.chi.clone(),
+            issued_for: self.issued_for.clone(),
+        }
+    }
+
     /// Specifies HD derivation path
     ///
     /// Outputs a presignature that can be used to sign a message with a child
@@ -1221,6 +2301,75 @@ impl<E: Curve> PartialSignature<E> {
     }
 }
 
+impl<E> PartialSignature<E>
+where
+    E: Curve,
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+{
+    /// Verifies partial signature against public data of presignature it was issued with
+    pub fn verify(
+        &self,
+        public_data: &PresignaturePublicData<E>,
+        message: &DataToSign<E>,
+    ) -> Result<(), InvalidSignature> {
+        let r = public_data.R.x().to_scalar();
+        if self.r == r
+            && *public_data.R * self.sigma
+                == public_data.K * message.to_scalar() + public_data.Chi * r
+        {
+            Ok(())
+        } else {
+            Err(InvalidSignature)
+        }
+    }
+
+    /// Combines partial signatures into regular signature, identifying faulty ones
+    ///
+    /// Unlike [`combine`](Self::combine), takes public data of all signers' presignatures, which
+    /// signers need to exchange before issuing partial signatures. `public_data[j]` must correspond
+    /// to presignature used to issue `partial_signatures[j]`.
+    ///
+    /// Returned signature is always valid for `public_key` and `message`. If it can't be obtained,
+    /// returned error [points out](CombineError::faulty_partial_signatures) which partial signatures
+    /// are faulty, when it's possible.
+    pub fn combine_and_identify(
+        partial_signatures: &[PartialSignature<E>],
+        public_data: &[PresignaturePublicData<E>],
+        public_key: &Point<E>,
+        message: &DataToSign<E>,
+    ) -> Result<Signature<E>, CombineError> {
+        if partial_signatures.is_empty() || partial_signatures.len() != public_data.len() {
+            return Err(CombineReason::MismatchedLength.into());
+        }
+        let R = public_data[0].R;
+        if public_data.iter().any(|d| d.R != R) {
+            return Err(CombineReason::InconsistentR.into());
+        }
+        // Sum of commitments must match $R^k = G$ and $R^{k x} = X$
+        let K = public_data.iter().map(|d| d.K).sum::<Point<E>>();
+        let Chi = public_data.iter().map(|d| d.Chi).sum::<Point<E>>();
+        if K != Point::generator().to_point() || Chi != *public_key {
+            return Err(CombineReason::InconsistentPublicData.into());
+        }
+
+        let faulty = partial_signatures
+            .iter()
+            .zip(public_data)
+            .enumerate()
+            .filter(|(_, (sig, public_data))| sig.verify(public_data, message).is_err())
+            .map(|(j, _)| j)
+            .collect::<Vec<_>>();
+        if !faulty.is_empty() {
+            return Err(CombineReason::FaultyPartialSignatures(faulty).into());
+        }
+
+        let sig = Self::combine(partial_signatures).ok_or(CombineReason::Malformed)?;
+        sig.verify(public_key, message)
+            .map_err(|_| CombineReason::Malformed)?;
+        Ok(sig)
+    }
+}
+
 impl<E: Curve> Signature<E>
 where
     NonZero<Point<E>>: AlwaysHasAffineX<E>,
@@ -1309,17 +2458,83 @@ enum ProtocolOutput<E: Curve> {
     Signature(Signature<E>),
 }
 
+enum MessageToSign<E: Curve> {
+    Known(DataToSign<E>),
+    Deferred(DeferredMessage<E>),
+}
+
 /// Error indicating that signing protocol failed
 #[derive(Debug, Error)]
 #[error("signing protocol failed")]
 pub struct SigningError(#[source] Reason);
 
+impl SigningError {
+    /// Returns index of the party who aborted the protocol by sending
+    /// [`MsgAbort`](crate::abort::MsgAbort), if protocol failed for this reason
+    pub fn aborted_by_peer(&self) -> Option<PartyIndex> {
+        match &self.0 {
+            Reason::PeerAborted(PeerAborted(j)) => Some(*j),
+            _ => None,
+        }
+    }
+
+    /// Indicates whether signing failed because [key usage policy](crate::policy) is violated
+    pub fn is_policy_violation(&self) -> bool {
+        matches!(self.0, Reason::PolicyViolation(_))
+    }
+
+    /// Indicates whether signing was rejected by [approval hook](approval)
+    pub fn is_rejected(&self) -> bool {
+        matches!(self.0, Reason::Rejected(_))
+    }
+
+    /// Indicates whether signing was not allowed by [rate limiter](rate_limit)
+    ///
+    /// It's `true` both when limit is exceeded and when limiter failed to account the signature.
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self.0, Reason::RateLimited(_))
+    }
+
+    /// Returns stable code of the error
+    pub fn code(&self) -> ErrorCode {
+        match &self.0 {
+            Reason::InvalidArgs(_) => ErrorCode::InvalidArgs,
+            Reason::InvalidKeyShare(_) => ErrorCode::InvalidKeyShare,
+            Reason::Aborted(err) => err.code(),
+            Reason::IoError(err) => err.code(),
+            Reason::PeerAborted(_) => ErrorCode::PeerAborted,
+            Reason::EidRegistry(_) => ErrorCode::ExecutionIdRejected,
+            Reason::MessageNotProvided => ErrorCode::MessageNotProvided,
+            Reason::PaillierDecryption(_) => ErrorCode::PaillierBackend,
+            Reason::PolicyViolation(_) => ErrorCode::PolicyViolation,
+            Reason::Rejected(_) => ErrorCode::Rejected,
+            Reason::RateLimited(_) => ErrorCode::RateLimited,
+            Reason::Bug(_) => ErrorCode::InternalError,
+        }
+    }
+
+    /// Returns serializable report of the error
+    pub fn report(&self) -> ErrorReport {
+        let parties = match &self.0 {
+            Reason::Aborted(err) => err.blamed_parties(),
+            Reason::PeerAborted(PeerAborted(j)) => vec![*j],
+            _ => vec![],
+        };
+        ErrorReport::new(self.code(), self, parties)
+    }
+}
+
 crate::errors::impl_from! {
     impl From for SigningError {
         err: InvalidArgs => SigningError(Reason::InvalidArgs(err)),
+        err: InvalidSigners => SigningError(Reason::InvalidArgs(InvalidArgs::InvalidS(err))),
         err: InvalidKeyShare => SigningError(Reason::InvalidKeyShare(err)),
         err: SigningAborted => SigningError(Reason::Aborted(err)),
-        err: IoError => SigningError(Reason::IoError(err)),
+        err: IoError => match err {
+            IoError::PeerAborted(err) => SigningError(Reason::PeerAborted(err)),
+            err => SigningError(Reason::IoError(err)),
+        },
+        err: EidRegistryError => SigningError(Reason::EidRegistry(err)),
         err: Bug => SigningError(Reason::Bug(err)),
     }
 }
@@ -1348,6 +2563,20 @@ enum Reason {
     ),
     #[error("i/o error")]
     IoError(#[source] IoError),
+    #[error("protocol was aborted by another party")]
+    PeerAborted(#[source] PeerAborted),
+    #[error("execution id was rejected by registry")]
+    EidRegistry(#[source] EidRegistryError),
+    #[error("message to sign was not provided")]
+    MessageNotProvided,
+    #[error("paillier decryption failed")]
+    PaillierDecryption(#[source] DecryptionError),
+    #[error("key usage policy is violated")]
+    PolicyViolation(#[source] PolicyViolation),
+    #[error("signing was rejected by approval hook")]
+    Rejected(#[source] approval::Rejected),
+    #[error("signature was not allowed by rate limiter")]
+    RateLimited(#[source] RateLimitError),
     /// Bug occurred
     #[error("bug occurred")]
     Bug(Bug),
@@ -1359,6 +2588,10 @@ enum Reason {
 #[allow(clippy::type_complexity)]
 #[derive(Debug, Error)]
 enum SigningAborted {
+    #[error("party sent a ciphertext out of range [1, N^2)")]
+    MalformedCiphertext(Vec<(PartyIndex, MsgId)>),
+    #[error("Paillier moduli of signers {0:?} are equal or share a factor")]
+    RelatedPaillierModuli(Vec<PartyIndex>),
     #[error("pi_enc::verify(K) failed")]
     EncProofOfK(Vec<(PartyIndex, MsgId, MsgId)>),
     #[error("ψ, ψˆ, or ψ' proofs are invalid")]
@@ -1380,18 +2613,82 @@ enum SigningAborted {
     MismatchedDelta,
     #[error("resulting signature is not valid")]
     SignatureInvalid,
-    #[error("other parties received different broadcast messages at round1a")]
-    Round1aNotReliable(Vec<(PartyIndex, MsgId)>),
+    #[error("broadcast at round {round} wasn't reliable: {parties:?}")]
+    BroadcastNotReliable {
+        round: u16,
+        parties: Vec<(PartyIndex, MsgId)>,
+    },
+}
+
+impl SigningAborted {
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::MalformedCiphertext(_) => ErrorCode::MalformedCiphertext,
+            Self::RelatedPaillierModuli(_) => ErrorCode::RelatedPaillierModuli,
+            Self::EncProofOfK(_) => ErrorCode::InvalidEncProof,
+            Self::InvalidPsi(_) => ErrorCode::InvalidPsiProofs,
+            Self::InvalidPsiPrimePrime(_) => ErrorCode::InvalidPsiPrimePrimeProof,
+            Self::MismatchedDelta => ErrorCode::MismatchedDelta,
+            Self::SignatureInvalid => ErrorCode::InvalidSignature,
+            Self::BroadcastNotReliable { .. } => ErrorCode::BroadcastNotReliable,
+        }
+    }
+
+    fn blamed_parties(&self) -> Vec<PartyIndex> {
+        match self {
+            Self::MalformedCiphertext(parties) | Self::BroadcastNotReliable { parties, .. } => {
+                parties.iter().map(|(j, _)| *j).collect()
+            }
+            Self::RelatedPaillierModuli(parties) => parties.clone(),
+            Self::EncProofOfK(parties) | Self::InvalidPsiPrimePrime(parties) => {
+                parties.iter().map(|(j, _, _)| *j).collect()
+            }
+            Self::InvalidPsi(parties) => parties.iter().map(|(j, ..)| *j).collect(),
+            Self::MismatchedDelta | Self::SignatureInvalid => vec![],
+        }
+    }
 }
 
 #[derive(Debug, Error)]
 enum InvalidArgs {
-    #[error("exactly `threshold` amount of parties should take part in signing")]
-    MismatchedAmountOfParties,
-    #[error("signer index `i` is out of bounds (must be < n)")]
+    #[error("invalid set of signers S")]
+    InvalidS(#[source] InvalidSigners),
+    #[error("signer index `i` is out of bounds (must be < t)")]
     SignerIndexOutOfBounds,
-    #[error("party index in S is out of bounds (must be < n)")]
-    InvalidS,
+    #[error("S[i] = {actual} doesn't match index of key share at keygen = {expected}")]
+    SignerIndexMismatch {
+        expected: PartyIndex,
+        actual: PartyIndex,
+    },
+    #[error("signer set context doesn't match the key share or the set of signers")]
+    SignerSetContextMismatch,
+    #[error("paillier decryptor holds a key different from the key share")]
+    PaillierDecryptorMismatch,
+}
+
+/// Error indicating that set of signers $S$ is not valid
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct InvalidSigners(InvalidSignersReason);
+
+#[derive(Debug, Error)]
+enum InvalidSignersReason {
+    #[error("exactly `threshold` amount of parties should take part in signing (expected {expected}, got {actual})")]
+    MismatchedAmountOfParties { expected: u16, actual: usize },
+    #[error("party index {index} in S is out of bounds (must be < n = {n})")]
+    UnknownIndex { index: PartyIndex, n: u16 },
+    #[error("party index {0} appears in S more than once")]
+    DuplicatedIndex(PartyIndex),
+    #[error("local party (index {0} at keygen) is not in S")]
+    NotASigner(PartyIndex),
+    #[error("total weight of signers is less than threshold (expected at least {expected}, got {actual})")]
+    InsufficientWeight { expected: u16, actual: u32 },
+}
+
+crate::errors::impl_from! {
+    impl From for InvalidSigners {
+        err: InvalidSignersReason => InvalidSigners(err),
+    }
 }
 
 #[derive(Debug, Error)]
@@ -1410,14 +2707,14 @@ enum Bug {
     PiAffG(BugSource, paillier_zk::Error),
     #[error("π log* failed to prove statement: {0:?}")]
     PiLog(BugSource, paillier_zk::Error),
-    #[error("couldn't decrypt a message: {0:?}")]
-    PaillierDec(BugSource),
     #[error("delta is zero")]
     ZeroDelta,
     #[error("R is zero")]
     ZeroR,
     #[error("unexpected protocol output")]
     UnexpectedProtocolOutput,
+    #[error("freshly generated presignature is considered reused")]
+    PresignatureReused,
     #[error("derive lagrange coef")]
     LagrangeCoef,
     #[error("subset function returned error")]
@@ -1426,6 +2723,10 @@ enum Bug {
     DerivedChildKeyZero,
     #[error("derived child share is zero - probability of that is negligible")]
     DerivedChildShareZero,
+    #[error("additive share is zero - probability of that is negligible")]
+    ZeroAdditiveShare,
+    #[error("derive public shares of signers")]
+    SignersPublicShares(#[source] crate::key_share::InvalidSubset),
 }
 
 #[derive(Debug)]
@@ -1445,8 +2746,6 @@ enum BugSource {
     psi,
     hat_psi,
     psi_prime,
-    alpha,
-    hat_alpha,
     psi_prime_prime,
 }
 
@@ -1455,6 +2754,46 @@ enum BugSource {
 #[error("signature is not valid")]
 pub struct InvalidSignature;
 
+/// Error returned by [`PartialSignature::combine_and_identify`]
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct CombineError(CombineReason);
+
+impl CombineError {
+    /// Returns positions of faulty partial signatures in the input, if they were identified
+    pub fn faulty_partial_signatures(&self) -> Option<&[usize]> {
+        match &self.0 {
+            CombineReason::FaultyPartialSignatures(faulty) => Some(faulty),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+enum CombineReason {
+    #[error("amount of partial signatures doesn't match amount of public data, or it's empty")]
+    MismatchedLength,
+    #[error("public data has inconsistent R")]
+    InconsistentR,
+    #[error("public data is inconsistent with public key")]
+    InconsistentPublicData,
+    #[error("faulty partial signatures at positions {0:?}")]
+    FaultyPartialSignatures(Vec<usize>),
+    #[error("partial signatures are malformed")]
+    Malformed,
+}
+
+crate::errors::impl_from! {
+    impl From for CombineError {
+        err: CombineReason => CombineError(err),
+    }
+}
+
+/// Error indicating that presignature was already used to sign a different message
+#[derive(Debug, Error)]
+#[error("presignature was already used to sign a different message")]
+pub struct PresignatureReused;
+
 #[cfg(test)]
 mod test {
     fn read_write_signature<E: generic_ec::Curve>() {
@@ -1470,14 +2809,22 @@ mod test {
         }
     }
 
+    #[cfg(feature = "curve-secp256k1")]
     #[test]
     fn read_write_signature_secp256k1() {
         read_write_signature::<crate::supported_curves::Secp256k1>()
     }
+    #[cfg(feature = "curve-secp256r1")]
     #[test]
     fn read_write_signature_secp256r1() {
         read_write_signature::<crate::supported_curves::Secp256r1>()
     }
+    #[cfg(feature = "curve-secp384r1")]
+    #[test]
+    fn read_write_signature_secp384r1() {
+        read_write_signature::<crate::supported_curves::Secp384r1>()
+    }
+    #[cfg(feature = "curve-stark")]
     #[test]
     fn read_write_signature_stark() {
         read_write_signature::<crate::supported_curves::Stark>()
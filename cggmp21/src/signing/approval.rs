@@ -0,0 +1,137 @@
+//! Signing approval hooks
+//!
+//! [`SigningApproval`] hook is set via
+//! [`SigningBuilder::set_approval_hook`](super::SigningBuilder::set_approval_hook) and is invoked
+//! twice during signing:
+//!
+//! 1. [`Stage::BeforeRound1`]: before any message is sent. Data to sign may be unknown at this
+//!    stage (e.g. when message is [deferred](super::deferred_message)).
+//! 2. [`Stage::BeforePartialSignature`]: right before the partial signature is released, when
+//!    data to sign is known.
+//!
+//! If hook rejects the request, the protocol terminates with an error for which
+//! [`SigningError::is_rejected`](super::SigningError::is_rejected) is `true`. Rejection at
+//! second stage is reported to other signers via [`MsgAbort`](crate::abort::MsgAbort).
+//!
+//! Hook can be used to wire interactive approval, rate limiters or policy engines into the
+//! protocol flow. Note that hook is called from within async protocol: it should not block for
+//! long.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # async fn sign<M>(
+//! #     eid: cggmp21::ExecutionId<'_>,
+//! #     i: u16,
+//! #     parties_indexes_at_keygen: &[u16],
+//! #     key_share: &cggmp21::KeyShare<cggmp21::supported_curves::Secp256k1>,
+//! #     party: M,
+//! #     data_to_sign: cggmp21::DataToSign<cggmp21::supported_curves::Secp256k1>,
+//! # ) -> Result<(), cggmp21::signing::SigningError>
+//! # where
+//! #     M: round_based::Mpc<ProtocolMessage = cggmp21::signing::msg::Msg<cggmp21::supported_curves::Secp256k1, sha2::Sha256>>,
+//! # {
+//! use cggmp21::signing::approval::{ApprovalRequest, Rejected};
+//! use cggmp21::supported_curves::Secp256k1;
+//!
+//! fn only_party_0_and_1(request: &ApprovalRequest<Secp256k1>) -> Result<(), Rejected> {
+//!     if request.signers == [0, 1] {
+//!         Ok(())
+//!     } else {
+//!         Err(Rejected::new("unexpected set of signers"))
+//!     }
+//! }
+//!
+//! let mut rng = rand::rngs::OsRng;
+//! let signature = cggmp21::signing(eid, i, parties_indexes_at_keygen, key_share)
+//!     .set_approval_hook(&only_party_0_and_1)
+//!     .sign(&mut rng, party, data_to_sign)
+//!     .await?;
+//! # Ok(()) }
+//! ```
+
+use generic_ec::Curve;
+use round_based::PartyIndex;
+
+use super::DataToSign;
+use crate::ExecutionId;
+
+/// Signing approval hook
+///
+/// Implemented for any `Fn(&ApprovalRequest<E>) -> Result<(), Rejected> + Sync`.
+pub trait SigningApproval<E: Curve>: Sync {
+    /// Approves or rejects the request
+    fn approve(&self, request: &ApprovalRequest<E>) -> Result<(), Rejected>;
+}
+
+impl<E: Curve, F> SigningApproval<E> for F
+where
+    F: Fn(&ApprovalRequest<E>) -> Result<(), Rejected> + Sync,
+{
+    fn approve(&self, request: &ApprovalRequest<E>) -> Result<(), Rejected> {
+        self(request)
+    }
+}
+
+/// Stage of signing at which hook is invoked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Before round 1, no messages were sent yet
+    BeforeRound1,
+    /// Before the partial signature is released
+    BeforePartialSignature,
+}
+
+/// Request to approve
+#[derive(Clone, Copy)]
+pub struct ApprovalRequest<'a, E: Curve> {
+    /// Stage of signing
+    pub stage: Stage,
+    /// Execution ID of the signing
+    pub eid: ExecutionId<'a>,
+    /// Indexes of signers at keygen
+    pub signers: &'a [PartyIndex],
+    /// Data to be signed
+    ///
+    /// Always present at [`Stage::BeforePartialSignature`]. At [`Stage::BeforeRound1`], it's
+    /// `None` if message is not known yet.
+    pub data_to_sign: Option<DataToSign<E>>,
+}
+
+/// Error indicating that the request was rejected by the hook
+#[derive(Debug, thiserror::Error)]
+#[error("request rejected: {reason}")]
+pub struct Rejected {
+    reason: String,
+}
+
+impl Rejected {
+    /// Constructs an error with a reason of rejection
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+
+    /// Returns a reason of rejection
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+/// Hook bound to the signing execution
+pub(crate) struct Approval<'a, E: Curve> {
+    pub hook: &'a dyn SigningApproval<E>,
+    pub eid: ExecutionId<'a>,
+    pub signers: &'a [PartyIndex],
+}
+
+impl<E: Curve> Approval<'_, E> {
+    pub fn check(&self, stage: Stage, data_to_sign: Option<DataToSign<E>>) -> Result<(), Rejected> {
+        self.hook.approve(&ApprovalRequest {
+            stage,
+            eid: self.eid,
+            signers: self.signers,
+            data_to_sign,
+        })
+    }
+}
@@ -0,0 +1,265 @@
+//! Signing Bitcoin PSBTs
+//!
+//! Helpers to sign [Partially Signed Bitcoin Transaction](::bitcoin::Psbt) (BIP-174) with the
+//! MPC key: [`sighash`] extracts data to sign from PSBT input, and [`insert_signature`] writes
+//! the resulting DER signature back into the input's `partial_sigs`. [`sign_psbt`] combines
+//! both for every input of the PSBT.
+//!
+//! Only ECDSA inputs (legacy and segwit v0) are supported.
+//!
+//! Signing messages in BIP-137 format is supported by [`message`] module.
+//!
+//! ## Example
+//! Signature for each input can be obtained by running the [signing protocol](crate::signing)
+//! as shown below, or by combining partial signatures issued using presignatures.
+//! ```rust,no_run
+//! # async fn sign<M>(
+//! #     psbt: &mut bitcoin::Psbt,
+//! #     key_share: &cggmp21::KeyShare<cggmp21::supported_curves::Secp256k1>,
+//! #     parties_indexes_at_keygen: &[u16],
+//! #     i: u16,
+//! #     mut party: impl FnMut(usize) -> M,
+//! # ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+//! # where
+//! #     M: round_based::Mpc<ProtocolMessage = cggmp21::signing::msg::Msg<cggmp21::supported_curves::Secp256k1, sha2::Sha256>>,
+//! # {
+//! let rng = rand::rngs::OsRng;
+//! let public_key = key_share.shared_public_key;
+//! cggmp21::signing::bitcoin::sign_psbt(psbt, &public_key, |sighash| {
+//!     // Each input is signed by a separate protocol execution
+//!     let eid = format!("psbt input {}", sighash.input_index);
+//!     let party = party(sighash.input_index);
+//!     let mut rng = rng;
+//!     async move {
+//!         cggmp21::signing(cggmp21::ExecutionId::new(eid.as_bytes()), i, parties_indexes_at_keygen, key_share)
+//!             .sign(&mut rng, party, sighash.data_to_sign)
+//!             .await
+//!     }
+//! })
+//! .await?;
+//! # Ok(()) }
+//! ```
+
+pub mod message;
+
+use std::future::Future;
+
+use ::bitcoin::{
+    ecdsa, psbt::SignError, secp256k1, sighash::SighashCache, EcdsaSighashType, Psbt, PublicKey,
+};
+use generic_ec::{Point, Scalar};
+
+use super::{DataToSign, Signature};
+use crate::supported_curves::Secp256k1;
+
+/// Data to be signed for PSBT input
+#[derive(Debug, Clone, Copy)]
+pub struct PsbtSighash {
+    /// Index of the input
+    pub input_index: usize,
+    /// Sighash of the input mapped to [`DataToSign`]
+    pub data_to_sign: DataToSign<Secp256k1>,
+    /// Sighash type to be appended to the signature
+    pub sighash_type: EcdsaSighashType,
+}
+
+/// Extracts data to be signed for given PSBT input
+pub fn sighash(psbt: &Psbt, input_index: usize) -> Result<PsbtSighash, PsbtError> {
+    let mut cache = SighashCache::new(&psbt.unsigned_tx);
+    sighash_with_cache(psbt, input_index, &mut cache)
+}
+
+fn sighash_with_cache(
+    psbt: &Psbt,
+    input_index: usize,
+    cache: &mut SighashCache<&::bitcoin::Transaction>,
+) -> Result<PsbtSighash, PsbtError> {
+    let (msg, sighash_type) = psbt
+        .sighash_ecdsa(input_index, cache)
+        .map_err(|err| Reason::Sighash { input_index, err })?;
+    let msg: &[u8; 32] = msg.as_ref();
+    Ok(PsbtSighash {
+        input_index,
+        data_to_sign: DataToSign::from_scalar(Scalar::from_be_bytes_mod_order(msg)),
+        sighash_type,
+    })
+}
+
+/// Writes signature into PSBT input
+///
+/// Signature is verified against `public_key` and normalized as required by Bitcoin before it's
+/// written into `partial_sigs` of the input.
+pub fn insert_signature(
+    psbt: &mut Psbt,
+    sighash: &PsbtSighash,
+    public_key: &Point<Secp256k1>,
+    signature: Signature<Secp256k1>,
+) -> Result<(), PsbtError> {
+    let input_index = sighash.input_index;
+    signature
+        .verify(public_key, &sighash.data_to_sign)
+        .map_err(|_| Reason::InvalidSignature { input_index })?;
+
+    let mut bytes = [0u8; 64];
+    signature.normalize_s().write_to_slice(&mut bytes);
+    #[allow(clippy::expect_used)]
+    let signature = secp256k1::ecdsa::Signature::from_compact(&bytes)
+        .expect("valid signature is always compatible with secp256k1");
+    #[allow(clippy::expect_used)]
+    let public_key = PublicKey::from_slice(public_key.to_bytes(true).as_bytes())
+        .expect("valid point is always compatible with secp256k1");
+
+    psbt.inputs
+        .get_mut(input_index)
+        .ok_or(Reason::InputIndexOutOfBounds { input_index })?
+        .partial_sigs
+        .insert(
+            public_key,
+            ecdsa::Signature {
+                signature,
+                sighash_type: sighash.sighash_type,
+            },
+        );
+    Ok(())
+}
+
+/// Signs every input of the PSBT
+///
+/// `sign` is called for each input sequentially. It must produce a signature of given data, e.g.
+/// by running the [signing protocol](crate::signing) or by combining partial signatures
+/// issued from presignatures. Resulting signatures are written into PSBT as described in
+/// [`insert_signature`].
+pub async fn sign_psbt<F, Fut, Err>(
+    psbt: &mut Psbt,
+    public_key: &Point<Secp256k1>,
+    mut sign: F,
+) -> Result<(), PsbtError>
+where
+    F: FnMut(PsbtSighash) -> Fut,
+    Fut: Future<Output = Result<Signature<Secp256k1>, Err>>,
+    Err: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let sighashes = {
+        let mut cache = SighashCache::new(&psbt.unsigned_tx);
+        (0..psbt.inputs.len())
+            .map(|input_index| sighash_with_cache(psbt, input_index, &mut cache))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    for sighash in sighashes {
+        let signature = sign(sighash).await.map_err(|err| Reason::Signing {
+            input_index: sighash.input_index,
+            err: err.into(),
+        })?;
+        insert_signature(psbt, &sighash, public_key, signature)?;
+    }
+    Ok(())
+}
+
+/// Error related to signing PSBT
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct PsbtError(Reason);
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("compute sighash of input {input_index}")]
+    Sighash {
+        input_index: usize,
+        #[source]
+        err: SignError,
+    },
+    #[error("input {input_index} is out of bounds")]
+    InputIndexOutOfBounds { input_index: usize },
+    #[error("signature of input {input_index} is not valid")]
+    InvalidSignature { input_index: usize },
+    #[error("signing input {input_index} failed")]
+    Signing {
+        input_index: usize,
+        #[source]
+        err: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+crate::errors::impl_from! {
+    impl From for PsbtError {
+        err: Reason => PsbtError(err),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ::bitcoin::{
+        absolute, secp256k1, sighash::SighashCache, transaction, Amount, CompressedPublicKey,
+        OutPoint, Psbt, ScriptBuf, Transaction, TxIn, TxOut,
+    };
+    use generic_ec::{coords::AlwaysHasAffineX, NonZero, Point, Scalar, SecretScalar};
+
+    use crate::signing::{DataToSign, Signature};
+    use crate::supported_curves::Secp256k1;
+
+    fn ecdsa_sign(
+        rng: &mut rand_dev::DevRng,
+        sk: &SecretScalar<Secp256k1>,
+        data: DataToSign<Secp256k1>,
+    ) -> Signature<Secp256k1> {
+        let k = NonZero::<Scalar<Secp256k1>>::random(rng);
+        let R = NonZero::from_point(Point::generator() * *k).unwrap();
+        let r = NonZero::from_scalar(R.x().to_scalar()).unwrap();
+        let s = NonZero::from_scalar(*k.invert() * (data.to_scalar() + *r * sk.as_ref())).unwrap();
+        Signature::from_raw_parts(r, s)
+    }
+
+    #[test]
+    fn signs_p2wpkh_inputs() {
+        let mut rng = rand_dev::DevRng::new();
+        let sk = SecretScalar::<Secp256k1>::random(&mut rng);
+        let pk = Point::generator() * &sk;
+        let btc_pk = CompressedPublicKey::from_slice(pk.to_bytes(true).as_bytes()).unwrap();
+
+        let tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn::default(), TxIn::default()],
+            output: vec![TxOut {
+                value: Amount::from_sat(1_500),
+                script_pubkey: ScriptBuf::new_p2wpkh(&btc_pk.wpubkey_hash()),
+            }],
+        };
+        let mut psbt = Psbt::from_unsigned_tx(tx).unwrap();
+        for (i, input) in psbt.inputs.iter_mut().enumerate() {
+            input.witness_utxo = Some(TxOut {
+                value: Amount::from_sat(1_000 * (i as u64 + 1)),
+                script_pubkey: ScriptBuf::new_p2wpkh(&btc_pk.wpubkey_hash()),
+            });
+        }
+        psbt.unsigned_tx.input[1].previous_output = OutPoint {
+            vout: 1,
+            ..OutPoint::null()
+        };
+
+        let mut sign_rng = rng.fork();
+        futures::executor::block_on(super::sign_psbt(&mut psbt, &pk, |sighash| {
+            let sig = ecdsa_sign(&mut sign_rng, &sk, sighash.data_to_sign);
+            async move { Ok::<_, std::convert::Infallible>(sig) }
+        }))
+        .unwrap();
+
+        // Verify signatures with libsecp256k1
+        let secp = secp256k1::Secp256k1::verification_only();
+        let mut cache = SighashCache::new(&psbt.unsigned_tx);
+        for input_index in 0..psbt.inputs.len() {
+            let (msg, _) = psbt.sighash_ecdsa(input_index, &mut cache).unwrap();
+            let sig = psbt.inputs[input_index]
+                .partial_sigs
+                .get(&btc_pk.into())
+                .unwrap();
+            secp.verify_ecdsa(&msg, &sig.signature, &btc_pk.0).unwrap();
+        }
+
+        // Invalid signature is rejected
+        let sighash = super::sighash(&psbt, 0).unwrap();
+        let other_data = DataToSign::digest::<sha2::Sha256>(b"other data");
+        let sig = ecdsa_sign(&mut rng, &sk, other_data);
+        assert!(super::insert_signature(&mut psbt, &sighash, &pk, sig).is_err());
+    }
+}
@@ -0,0 +1,242 @@
+//! Bitcoin signed messages (BIP-137)
+//!
+//! Signed message proves ownership of an address, e.g. in proof-of-reserves. Message is hashed
+//! with a magic prefix:
+//!
+//! `data_to_sign = sha256d("\x18Bitcoin Signed Message:\n" || compact_size(len(message)) || message)`
+//!
+//! Resulting signature is encoded as 65 bytes `header || r || s` (usually in base64), where header
+//! encodes recovery id and [type of the address](AddressType).
+//!
+//! ## Example
+//! ```rust
+//! use cggmp21::signing::bitcoin::message::{message_hash, AddressType, SignedMessage};
+//! # fn f(
+//! #     public_key: generic_ec::Point<cggmp21::supported_curves::Secp256k1>,
+//! #     sign: impl FnOnce(cggmp21::DataToSign<cggmp21::supported_curves::Secp256k1>) -> cggmp21::Signature<cggmp21::supported_curves::Secp256k1>,
+//! # ) -> Result<(), cggmp21::signing::InvalidSignature> {
+//! let message = b"proof of reserves";
+//! // Signature is obtained by running signing protocol
+//! let signature = sign(message_hash(message));
+//! let signed = SignedMessage::new(signature, &public_key, message, AddressType::P2wpkh)?;
+//! println!("{}", signed.to_base64());
+//! # Ok(()) }
+//! ```
+
+use ::bitcoin::base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use generic_ec::{NonZero, Point, Scalar};
+use sha2::{Digest, Sha256};
+
+use crate::signing::{recovery, DataToSign, InvalidSignature, Signature};
+use crate::supported_curves::Secp256k1;
+
+const MAGIC_PREFIX: &[u8] = b"\x18Bitcoin Signed Message:\n";
+
+/// Type of address the signed message is bound to
+///
+/// Determines the header byte of the signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressType {
+    /// P2PKH address derived from uncompressed public key
+    P2pkhUncompressed,
+    /// P2PKH address derived from compressed public key
+    P2pkhCompressed,
+    /// Segwit P2SH-P2WPKH address
+    P2shP2wpkh,
+    /// Native segwit P2WPKH address
+    P2wpkh,
+}
+
+impl AddressType {
+    fn header_base(&self) -> u8 {
+        match self {
+            Self::P2pkhUncompressed => 27,
+            Self::P2pkhCompressed => 31,
+            Self::P2shP2wpkh => 35,
+            Self::P2wpkh => 39,
+        }
+    }
+
+    fn from_header(header: u8) -> Option<(Self, u8)> {
+        let address_type = match header {
+            27..=30 => Self::P2pkhUncompressed,
+            31..=34 => Self::P2pkhCompressed,
+            35..=38 => Self::P2shP2wpkh,
+            39..=42 => Self::P2wpkh,
+            _ => return None,
+        };
+        Some((address_type, header - address_type.header_base()))
+    }
+}
+
+/// Computes hash of the message to be signed
+pub fn message_hash(message: &[u8]) -> DataToSign<Secp256k1> {
+    let mut hash = Sha256::new();
+    hash.update(MAGIC_PREFIX);
+    hash.update(compact_size(message.len()));
+    hash.update(message);
+    DataToSign::from_scalar(Scalar::from_be_bytes_mod_order(Sha256::digest(
+        hash.finalize(),
+    )))
+}
+
+/// Signed message in BIP-137 format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignedMessage {
+    signature: Signature<Secp256k1>,
+    recovery_id: u8,
+    address_type: AddressType,
+}
+
+impl SignedMessage {
+    /// Constructs signed message from signature of the [message hash](message_hash)
+    ///
+    /// Returns error if signature is not valid for given public key and message.
+    pub fn new(
+        signature: Signature<Secp256k1>,
+        public_key: &Point<Secp256k1>,
+        message: &[u8],
+        address_type: AddressType,
+    ) -> Result<Self, InvalidSignature> {
+        let signature = signature.normalize_s();
+        let y_parity = recovery::find_y_parity(&signature, public_key, &message_hash(message))
+            .ok_or(InvalidSignature)?;
+        Ok(Self {
+            signature,
+            recovery_id: u8::from(y_parity),
+            address_type,
+        })
+    }
+
+    /// Returns `(r, s)` signature
+    pub fn signature(&self) -> Signature<Secp256k1> {
+        self.signature
+    }
+
+    /// Returns type of the address
+    pub fn address_type(&self) -> AddressType {
+        self.address_type
+    }
+
+    /// Returns header byte that encodes recovery id and address type
+    pub fn header(&self) -> u8 {
+        self.address_type.header_base() + self.recovery_id
+    }
+
+    /// Encodes signed message as `header || r || s`
+    pub fn to_bytes(&self) -> [u8; 65] {
+        let mut out = [0u8; 65];
+        out[0] = self.header();
+        self.signature.write_to_slice(&mut out[1..]);
+        out
+    }
+
+    /// Encodes signed message in base64
+    pub fn to_base64(&self) -> String {
+        BASE64.encode(self.to_bytes())
+    }
+
+    /// Decodes signed message from `header || r || s`
+    ///
+    /// Returns `None` if bytes are malformed. Recovery ids for reduced `r` (2 and 3) are not
+    /// supported.
+    pub fn from_bytes(bytes: &[u8; 65]) -> Option<Self> {
+        let (address_type, recovery_id) = AddressType::from_header(bytes[0])?;
+        if recovery_id > 1 {
+            return None;
+        }
+        Some(Self {
+            signature: Signature::read_from_slice(&bytes[1..])?,
+            recovery_id,
+            address_type,
+        })
+    }
+
+    /// Decodes signed message from base64
+    pub fn from_base64(s: &str) -> Option<Self> {
+        let bytes = BASE64.decode(s).ok()?;
+        Self::from_bytes(bytes.as_slice().try_into().ok()?)
+    }
+
+    /// Recovers public key of signer
+    ///
+    /// Returns `None` if signature is malformed
+    pub fn recover_public_key(&self, message: &[u8]) -> Option<NonZero<Point<Secp256k1>>> {
+        recovery::recover_public_key(
+            &self.signature,
+            self.recovery_id == 1,
+            &message_hash(message),
+        )
+    }
+}
+
+/// Bitcoin's variable length integer encoding
+fn compact_size(len: usize) -> Vec<u8> {
+    match len {
+        0..=0xfc => vec![len as u8],
+        0xfd..=0xffff => [&[0xfd][..], &(len as u16).to_le_bytes()].concat(),
+        0x10000..=0xffff_ffff => [&[0xfe][..], &(len as u32).to_le_bytes()].concat(),
+        _ => [&[0xff][..], &(len as u64).to_le_bytes()].concat(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ::bitcoin::{hashes::Hash, secp256k1, sign_message};
+    use generic_ec::{coords::AlwaysHasAffineX, NonZero, Point, Scalar, SecretScalar};
+
+    use super::{message_hash, AddressType, SignedMessage};
+    use crate::signing::Signature;
+    use crate::supported_curves::Secp256k1;
+
+    #[test]
+    fn sign_and_recover() {
+        let mut rng = rand_dev::DevRng::new();
+        let sk = SecretScalar::<Secp256k1>::random(&mut rng);
+        let pk = Point::generator() * &sk;
+        let message = "proof of reserves";
+
+        // Message hash matches implementation of `bitcoin` crate
+        let hash = message_hash(message.as_bytes());
+        assert_eq!(
+            hash.to_scalar().to_be_bytes().as_bytes(),
+            &sign_message::signed_msg_hash(message).to_byte_array()[..]
+        );
+
+        let k = NonZero::<Scalar<Secp256k1>>::random(&mut rng);
+        let R = NonZero::from_point(Point::generator() * *k).unwrap();
+        let r = NonZero::from_scalar(R.x().to_scalar()).unwrap();
+        let s = NonZero::from_scalar(*k.invert() * (hash.to_scalar() + *r * sk.as_ref())).unwrap();
+        let signature = Signature::from_raw_parts(r, s);
+
+        let signed = SignedMessage::new(
+            signature,
+            &pk,
+            message.as_bytes(),
+            AddressType::P2pkhCompressed,
+        )
+        .unwrap();
+        assert_eq!(
+            signed.recover_public_key(message.as_bytes()).as_deref(),
+            Some(&pk)
+        );
+        assert_eq!(
+            SignedMessage::from_base64(&signed.to_base64()),
+            Some(signed)
+        );
+
+        // Compressed P2PKH format is understood by `bitcoin` crate
+        let btc_signature = sign_message::MessageSignature::from_slice(&signed.to_bytes()).unwrap();
+        let secp = secp256k1::Secp256k1::verification_only();
+        let recovered = btc_signature
+            .recover_pubkey(&secp, sign_message::signed_msg_hash(message))
+            .unwrap();
+        assert_eq!(recovered.to_bytes(), pk.to_bytes(true).as_bytes());
+
+        // Other address types differ only in header
+        let segwit =
+            SignedMessage::new(signature, &pk, message.as_bytes(), AddressType::P2wpkh).unwrap();
+        assert_eq!(segwit.header(), signed.header() + 8);
+        assert_eq!(segwit.to_bytes()[1..], signed.to_bytes()[1..]);
+    }
+}
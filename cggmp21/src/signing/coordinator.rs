@@ -0,0 +1,219 @@
+//! Coordinator combining partial signatures
+//!
+//! In server-mediated deployments, signers generate presignatures in advance and, once message
+//! to sign is known, send partial signatures to the coordinator. Coordinator doesn't hold a key
+//! share: it only needs [public key info](KeyInfo) to collect partial signatures, validate each
+//! of them, combine them into regular signature and verify the result. If signature can't be
+//! obtained, [`CoordinatorError`] reports which signers submitted bad partial signatures.
+//!
+//! Each signer submits [public data](PresignaturePublicData) of its presignature along with the
+//! partial signature. Signers are identified by their indexes at keygen.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn f(
+//! #     key_share: &cggmp21::KeyShare<cggmp21::supported_curves::Secp256k1>,
+//! #     submissions: Vec<(u16, cggmp21::PresignaturePublicData<cggmp21::supported_curves::Secp256k1>, cggmp21::PartialSignature<cggmp21::supported_curves::Secp256k1>)>,
+//! # ) -> Result<(), Box<dyn std::error::Error>> {
+//! use cggmp21::signing::{coordinator::Coordinator, DataToSign};
+//!
+//! let message = DataToSign::digest::<sha2::Sha256>(b"message to sign");
+//! let mut coordinator = Coordinator::new(key_share.as_ref(), [0, 2], message)?;
+//! for (signer, public_data, partial_signature) in submissions {
+//!     coordinator.submit(signer, public_data, partial_signature)?;
+//! }
+//! let signature = match coordinator.finish() {
+//!     Ok(signature) => signature,
+//!     Err(err) => {
+//!         println!("signers to blame: {:?}", err.blame());
+//!         return Err(err.into());
+//!     }
+//! };
+//! # let _ = signature;
+//! # Ok(()) }
+//! ```
+
+use generic_ec::{coords::AlwaysHasAffineX, Curve, NonZero, Point};
+use round_based::PartyIndex;
+
+use super::{
+    validate_key_signers, DataToSign, InvalidSigners, PartialSignature, PresignaturePublicData,
+    Signature,
+};
+use crate::key_share::KeyInfo;
+
+/// Collects partial signatures and combines them into regular signature
+pub struct Coordinator<E: Curve> {
+    public_key: Point<E>,
+    #[cfg(feature = "hd-wallets")]
+    chain_code: Option<slip_10::ChainCode>,
+    message: DataToSign<E>,
+    /// Indexes of signers at keygen, sorted in ascending order
+    signers: Vec<PartyIndex>,
+    /// `submissions[j]` corresponds to signer `signers[j]`
+    submissions: Vec<Option<(PresignaturePublicData<E>, PartialSignature<E>)>>,
+}
+
+impl<E: Curve> Coordinator<E>
+where
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+{
+    /// Constructs a coordinator
+    ///
+    /// Takes public key info, indexes (at keygen) of signers who generated presignature, and
+    /// message to be signed. Returns error if set of signers is not valid for the key.
+    pub fn new(
+        key_info: &KeyInfo<E>,
+        signers: impl IntoIterator<Item = PartyIndex>,
+        message: DataToSign<E>,
+    ) -> Result<Self, InvalidSigners> {
+        let mut signers = signers.into_iter().collect::<Vec<_>>();
+        signers.sort_unstable();
+        validate_key_signers(key_info, &signers)?;
+
+        Ok(Self {
+            public_key: key_info.shared_public_key.into_inner(),
+            #[cfg(feature = "hd-wallets")]
+            chain_code: key_info.chain_code,
+            message,
+            submissions: vec![None; signers.len()],
+            signers,
+        })
+    }
+
+    /// Specifies HD derivation path
+    ///
+    /// Resulting signature is verified against child public key derived using `path`. Signers
+    /// must set the same derivation path on their presignatures.
+    #[cfg(feature = "hd-wallets")]
+    pub fn set_derivation_path<Index>(
+        mut self,
+        path: impl IntoIterator<Item = Index>,
+    ) -> Result<Self, crate::key_share::HdError<<Index as TryInto<slip_10::NonHardenedIndex>>::Error>>
+    where
+        slip_10::NonHardenedIndex: TryFrom<Index>,
+    {
+        use crate::key_share::HdError;
+        let epub = slip_10::ExtendedPublicKey {
+            public_key: self.public_key,
+            chain_code: self.chain_code.ok_or(HdError::DisabledHd)?,
+        };
+        let child = slip_10::try_derive_child_public_key_with_path(
+            &epub,
+            path.into_iter().map(|index| index.try_into()),
+        )
+        .map_err(HdError::InvalidPath)?;
+        self.public_key = child.public_key;
+        self.chain_code = Some(child.chain_code);
+        Ok(self)
+    }
+
+    /// Returns public key which resulting signature is verified against
+    pub fn public_key(&self) -> Point<E> {
+        self.public_key
+    }
+
+    /// Accepts submission from the signer
+    ///
+    /// `signer` is index of the signer at keygen. Returns error if the party is not among
+    /// signers, or it already made a submission. Partial signature is validated when the
+    /// coordinator [finishes](Self::finish).
+    pub fn submit(
+        &mut self,
+        signer: PartyIndex,
+        public_data: PresignaturePublicData<E>,
+        partial_signature: PartialSignature<E>,
+    ) -> Result<(), CoordinatorError> {
+        let position = self
+            .signers
+            .binary_search(&signer)
+            .map_err(|_| Reason::UnknownSigner(signer))?;
+        let submission = &mut self.submissions[position];
+        if submission.is_some() {
+            return Err(Reason::DuplicatedSubmission(signer).into());
+        }
+        *submission = Some((public_data, partial_signature));
+        Ok(())
+    }
+
+    /// Returns indexes of signers who haven't made a submission yet
+    pub fn missing_signers(&self) -> Vec<PartyIndex> {
+        self.signers
+            .iter()
+            .zip(&self.submissions)
+            .filter(|(_, submission)| submission.is_none())
+            .map(|(signer, _)| *signer)
+            .collect()
+    }
+
+    /// Checks whether all signers made a submission
+    pub fn is_complete(&self) -> bool {
+        self.submissions.iter().all(Option::is_some)
+    }
+
+    /// Validates submissions, combines partial signatures, and verifies resulting signature
+    ///
+    /// Returns error if some submissions are missing or bad. Signers whose partial signatures
+    /// are not valid are reported by [`CoordinatorError::blame`].
+    pub fn finish(self) -> Result<Signature<E>, CoordinatorError> {
+        let missing = self.missing_signers();
+        if !missing.is_empty() {
+            return Err(Reason::MissingSubmissions(missing).into());
+        }
+        let (public_data, partial_signatures): (Vec<_>, Vec<_>) =
+            self.submissions.into_iter().flatten().unzip();
+
+        PartialSignature::combine_and_identify(
+            &partial_signatures,
+            &public_data,
+            &self.public_key,
+            &self.message,
+        )
+        .map_err(|err| {
+            if let Some(faulty) = err.faulty_partial_signatures() {
+                let blame = faulty.iter().map(|&j| self.signers[j]).collect();
+                return Reason::FaultyPartialSignatures(blame).into();
+            }
+            Reason::Combine(err).into()
+        })
+    }
+}
+
+/// Error returned by [`Coordinator`]
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CoordinatorError(Reason);
+
+impl CoordinatorError {
+    /// Returns indexes (at keygen) of signers who submitted bad partial signatures
+    ///
+    /// Returns empty slice if error can't be attributed to specific signers. For instance,
+    /// when public data submitted by signers is inconsistent with the public key, it's not
+    /// possible to tell which of signers is misbehaving.
+    pub fn blame(&self) -> &[PartyIndex] {
+        match &self.0 {
+            Reason::FaultyPartialSignatures(signers) => signers,
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("party {0} is not among signers")]
+    UnknownSigner(PartyIndex),
+    #[error("signer {0} already made a submission")]
+    DuplicatedSubmission(PartyIndex),
+    #[error("signers {0:?} haven't made a submission")]
+    MissingSubmissions(Vec<PartyIndex>),
+    #[error("signers {0:?} submitted invalid partial signatures")]
+    FaultyPartialSignatures(Vec<PartyIndex>),
+    #[error("combine partial signatures")]
+    Combine(#[source] super::CombineError),
+}
+
+crate::errors::impl_from! {
+    impl From for CoordinatorError {
+        err: Reason => CoordinatorError(err),
+    }
+}
@@ -0,0 +1,331 @@
+//! Ethereum signatures
+//!
+//! Ethereum expects a recoverable signature: besides `(r, s)`, it contains `v` that allows
+//! to recover signer's public key from the signature (see `ecrecover`). Signing protocol
+//! outputs a regular ECDSA [`Signature`], which can be converted into [`EthereumSignature`]
+//! given the public key and signed data.
+//!
+//! `v` is encoded differently depending on the transaction type:
+//! * [`EthereumSignature::y_parity`] for typed transactions (EIP-2930, EIP-1559, etc.)
+//! * [`EthereumSignature::v_legacy`] for pre-EIP-155 transactions and `personal_sign` messages
+//! * [`EthereumSignature::v_eip155`] for EIP-155 legacy transactions bound to a chain id
+//!
+//! Conversions to `alloy` and `ethers` signature types are available with `alloy` and `ethers`
+//! features respectively. Hashing of EIP-712 typed data is provided in [`eip712`] module with `eip712`
+//! feature.
+//!
+//! ## Example
+//! ```rust
+//! use cggmp21::signing::ethereum::EthereumSignature;
+//! # fn f(
+//! #     signature: cggmp21::Signature<cggmp21::supported_curves::Secp256k1>,
+//! #     public_key: generic_ec::Point<cggmp21::supported_curves::Secp256k1>,
+//! #     data_to_sign: cggmp21::DataToSign<cggmp21::supported_curves::Secp256k1>,
+//! # ) -> Result<(), cggmp21::signing::InvalidSignature> {
+//! let signature = EthereumSignature::new(signature, &public_key, &data_to_sign)?;
+//! let (r, s, v) = signature.rsv(Some(1)).expect("chain id is small enough");
+//! # let _ = (r, s, v);
+//! # Ok(()) }
+//! ```
+
+#[cfg(feature = "eip712")]
+pub mod eip712;
+
+use generic_ec::{NonZero, Point, Scalar};
+use sha3::{Digest, Keccak256};
+
+use super::{recovery, DataToSign, InvalidSignature, Signature};
+use crate::supported_curves::Secp256k1;
+
+/// Ethereum address
+pub type Address = [u8; 20];
+
+/// Recoverable ECDSA signature used in Ethereum
+///
+/// `s` is always normalized (i.e. in lower half of curve order) as required by Ethereum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EthereumSignature {
+    signature: Signature<Secp256k1>,
+    y_parity: bool,
+}
+
+impl EthereumSignature {
+    /// Constructs Ethereum signature from signature issued for given public key and data
+    ///
+    /// Returns error if signature is not valid for given public key and data.
+    pub fn new(
+        signature: Signature<Secp256k1>,
+        public_key: &Point<Secp256k1>,
+        data_to_sign: &DataToSign<Secp256k1>,
+    ) -> Result<Self, InvalidSignature> {
+        let signature = signature.normalize_s();
+        let y_parity = recovery::find_y_parity(&signature, public_key, data_to_sign)
+            .ok_or(InvalidSignature)?;
+        Ok(Self {
+            signature,
+            y_parity,
+        })
+    }
+
+    /// Constructs Ethereum signature from `(r, s)` and parity of `y` coordinate of `R`
+    ///
+    /// Returns `None` if `s` is not normalized.
+    pub fn from_parts(signature: Signature<Secp256k1>, y_parity: bool) -> Option<Self> {
+        if signature.normalize_s() != signature {
+            return None;
+        }
+        Some(Self {
+            signature,
+            y_parity,
+        })
+    }
+
+    /// Constructs Ethereum signature from `(r, s, v)`
+    ///
+    /// `v` can be either a y parity (0 or 1), legacy `v` (27 or 28), or EIP-155 `v` (`chain_id * 2 + 35`
+    /// or `chain_id * 2 + 36`). Returns `None` if `r`, `s`, or `v` are invalid, or `s` is not normalized.
+    pub fn from_rsv(r: &[u8; 32], s: &[u8; 32], v: u64) -> Option<Self> {
+        let r = NonZero::from_scalar(Scalar::from_be_bytes(r).ok()?)?;
+        let s = NonZero::from_scalar(Scalar::from_be_bytes(s).ok()?)?;
+        Self::from_parts(Signature::from_raw_parts(r, s), y_parity_from_v(v)?)
+    }
+
+    /// Returns `(r, s)` signature
+    pub fn signature(&self) -> Signature<Secp256k1> {
+        self.signature
+    }
+
+    /// Returns `r` component of signature
+    pub fn r(&self) -> [u8; 32] {
+        scalar_to_bytes(&self.signature.r)
+    }
+
+    /// Returns `s` component of signature
+    pub fn s(&self) -> [u8; 32] {
+        scalar_to_bytes(&self.signature.s)
+    }
+
+    /// Returns parity of `y` coordinate of `R`, used as `v` in typed transactions
+    pub fn y_parity(&self) -> bool {
+        self.y_parity
+    }
+
+    /// Returns `v` for legacy transactions that aren't bound to chain id, i.e. `27 + y_parity`
+    pub fn v_legacy(&self) -> u64 {
+        27 + u64::from(self.y_parity)
+    }
+
+    /// Returns `v` for EIP-155 transactions, i.e. `chain_id * 2 + 35 + y_parity`
+    ///
+    /// Returns `None` if `v` overflows `u64`
+    pub fn v_eip155(&self, chain_id: u64) -> Option<u64> {
+        chain_id
+            .checked_mul(2)?
+            .checked_add(35 + u64::from(self.y_parity))
+    }
+
+    /// Returns `(r, s, v)`
+    ///
+    /// `v` is computed as per EIP-155 if `chain_id` is provided, otherwise legacy `v` is
+    /// returned. Returns `None` if `v` overflows `u64`.
+    pub fn rsv(&self, chain_id: Option<u64>) -> Option<([u8; 32], [u8; 32], u64)> {
+        let v = match chain_id {
+            Some(chain_id) => self.v_eip155(chain_id)?,
+            None => self.v_legacy(),
+        };
+        Some((self.r(), self.s(), v))
+    }
+
+    /// Returns 65 bytes signature `r || s || v` with legacy `v`, as used by `personal_sign`
+    pub fn to_bytes(&self) -> [u8; 65] {
+        let mut out = [0u8; 65];
+        out[..32].copy_from_slice(&self.r());
+        out[32..64].copy_from_slice(&self.s());
+        out[64] = 27 + u8::from(self.y_parity);
+        out
+    }
+
+    /// Recovers public key of signer, same as `ecrecover`
+    ///
+    /// Returns `None` if signature is malformed
+    pub fn recover_public_key(
+        &self,
+        data_to_sign: &DataToSign<Secp256k1>,
+    ) -> Option<NonZero<Point<Secp256k1>>> {
+        recovery::recover_public_key(&self.signature, self.y_parity, data_to_sign)
+    }
+
+    /// Recovers address of signer
+    ///
+    /// Returns `None` if signature is malformed
+    pub fn recover_address(&self, data_to_sign: &DataToSign<Secp256k1>) -> Option<Address> {
+        self.recover_public_key(data_to_sign)
+            .map(|public_key| address(&public_key))
+    }
+}
+
+/// Computes Ethereum address of the public key
+pub fn address(public_key: &Point<Secp256k1>) -> Address {
+    let public_key = public_key.to_bytes(false);
+    let hash = Keccak256::digest(public_key.as_bytes().get(1..).unwrap_or_default());
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+/// Parses `y_parity` from `v`
+///
+/// `v` can be either a y parity (0 or 1), legacy `v` (27 or 28), or EIP-155 `v`. Returns `None`
+/// if `v` is invalid.
+pub fn y_parity_from_v(v: u64) -> Option<bool> {
+    match v {
+        0 | 1 => Some(v == 1),
+        27 | 28 => Some(v == 28),
+        35.. => Some((v - 35) % 2 == 1),
+        _ => None,
+    }
+}
+
+fn scalar_to_bytes(scalar: &Scalar<Secp256k1>) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(scalar.to_be_bytes().as_bytes());
+    out
+}
+
+#[cfg(feature = "alloy")]
+impl From<EthereumSignature> for alloy_primitives::Signature {
+    fn from(sig: EthereumSignature) -> Self {
+        alloy_primitives::Signature::new(
+            alloy_primitives::U256::from_be_bytes(sig.r()),
+            alloy_primitives::U256::from_be_bytes(sig.s()),
+            sig.y_parity(),
+        )
+    }
+}
+
+#[cfg(feature = "alloy")]
+impl TryFrom<alloy_primitives::Signature> for EthereumSignature {
+    type Error = InvalidSignature;
+    fn try_from(sig: alloy_primitives::Signature) -> Result<Self, Self::Error> {
+        Self::from_rsv(
+            &sig.r().to_be_bytes(),
+            &sig.s().to_be_bytes(),
+            u64::from(sig.v()),
+        )
+        .ok_or(InvalidSignature)
+    }
+}
+
+#[cfg(feature = "ethers")]
+impl EthereumSignature {
+    /// Converts signature into `ethers` signature type
+    ///
+    /// `v` is computed as per EIP-155 if `chain_id` is provided, otherwise legacy `v` is
+    /// used. Returns `None` if `v` overflows `u64`.
+    pub fn to_ethers(&self, chain_id: Option<u64>) -> Option<ethers_core::types::Signature> {
+        let (r, s, v) = self.rsv(chain_id)?;
+        Some(ethers_core::types::Signature {
+            r: ethers_core::types::U256::from_big_endian(&r),
+            s: ethers_core::types::U256::from_big_endian(&s),
+            v,
+        })
+    }
+}
+
+#[cfg(feature = "ethers")]
+impl TryFrom<ethers_core::types::Signature> for EthereumSignature {
+    type Error = InvalidSignature;
+    fn try_from(sig: ethers_core::types::Signature) -> Result<Self, Self::Error> {
+        let (mut r, mut s) = ([0u8; 32], [0u8; 32]);
+        sig.r.to_big_endian(&mut r);
+        sig.s.to_big_endian(&mut s);
+        Self::from_rsv(&r, &s, sig.v).ok_or(InvalidSignature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use generic_ec::{coords::AlwaysHasAffineX, NonZero, Point, Scalar, SecretScalar};
+    use k256::ecdsa::{RecoveryId, VerifyingKey};
+
+    use super::{address, y_parity_from_v, EthereumSignature};
+    use crate::signing::{DataToSign, Signature};
+    use crate::supported_curves::Secp256k1;
+
+    /// Issues regular ECDSA signature
+    fn sign(
+        rng: &mut rand_dev::DevRng,
+        sk: &SecretScalar<Secp256k1>,
+        data: &DataToSign<Secp256k1>,
+    ) -> Signature<Secp256k1> {
+        let k = NonZero::<Scalar<Secp256k1>>::random(rng);
+        let R = NonZero::from_point(Point::generator() * *k).unwrap();
+        let r = NonZero::from_scalar(R.x().to_scalar()).unwrap();
+        let s = NonZero::from_scalar(*k.invert() * (data.to_scalar() + *r * sk.as_ref())).unwrap();
+        Signature::from_raw_parts(r, s)
+    }
+
+    #[test]
+    fn recovers_signer() {
+        let mut rng = rand_dev::DevRng::new();
+
+        for _ in 0..20 {
+            let sk = SecretScalar::<Secp256k1>::random(&mut rng);
+            let pk = Point::generator() * &sk;
+            let data = DataToSign::digest::<sha3::Keccak256>(b"message");
+
+            let sig = EthereumSignature::new(sign(&mut rng, &sk, &data), &pk, &data).unwrap();
+            assert_eq!(sig.recover_public_key(&data).as_deref(), Some(&pk));
+            assert_eq!(sig.recover_address(&data), Some(address(&pk)));
+
+            // Cross-check with independent implementation of `ecrecover`
+            let k256_sig = k256::ecdsa::Signature::from_scalars(sig.r(), sig.s()).unwrap();
+            let recovered = VerifyingKey::recover_from_prehash(
+                data.to_scalar().to_be_bytes().as_bytes(),
+                &k256_sig,
+                RecoveryId::new(sig.y_parity(), false),
+            )
+            .unwrap();
+            assert_eq!(
+                recovered.to_encoded_point(true).as_bytes(),
+                pk.to_bytes(true).as_bytes()
+            );
+
+            // Signature is bound to the data
+            let other_data = DataToSign::digest::<sha3::Keccak256>(b"other message");
+            assert_ne!(sig.recover_public_key(&other_data).as_deref(), Some(&pk));
+            assert!(EthereumSignature::new(sig.signature(), &pk, &other_data).is_err());
+
+            // Round trip via `(r, s, v)`
+            for chain_id in [None, Some(1), Some(137)] {
+                let (r, s, v) = sig.rsv(chain_id).unwrap();
+                assert_eq!(EthereumSignature::from_rsv(&r, &s, v), Some(sig));
+            }
+        }
+    }
+
+    #[test]
+    fn v_encoding() {
+        assert_eq!(y_parity_from_v(0), Some(false));
+        assert_eq!(y_parity_from_v(1), Some(true));
+        assert_eq!(y_parity_from_v(27), Some(false));
+        assert_eq!(y_parity_from_v(28), Some(true));
+        assert_eq!(y_parity_from_v(37), Some(false));
+        assert_eq!(y_parity_from_v(38), Some(true));
+        assert_eq!(y_parity_from_v(2), None);
+        assert_eq!(y_parity_from_v(30), None);
+    }
+
+    #[test]
+    fn known_address() {
+        // Private key `1`
+        let pk = Point::<Secp256k1>::generator() * Scalar::one();
+        assert_eq!(
+            address(&pk),
+            [
+                0x7e, 0x5f, 0x45, 0x52, 0x09, 0x1a, 0x69, 0x12, 0x5d, 0x5d, 0xfc, 0xb7, 0xb8, 0xc2,
+                0x65, 0x90, 0x29, 0x39, 0x5b, 0xdf
+            ]
+        );
+    }
+}
@@ -0,0 +1,421 @@
+//! EIP-712 typed structured data hashing
+//!
+//! Computes hash of typed data as defined in [EIP-712], the same that's signed by
+//! `eth_signTypedData_v4`, and maps it into [`DataToSign`]:
+//!
+//! `data_to_sign = keccak256("\x19\x01" || domainSeparator || hashStruct(message))`
+//!
+//! Typed data is provided in the same JSON format as accepted by `eth_signTypedData_v4`.
+//! If `types` don't define `EIP712Domain`, it's derived from fields present in `domain`.
+//!
+//! ## Example
+//! ```rust
+//! use cggmp21::signing::ethereum::eip712::TypedData;
+//! # fn f() -> Result<(), cggmp21::signing::ethereum::eip712::Eip712Error> {
+//! let typed_data = TypedData::from_json(r#"{
+//!     "types": {
+//!         "Mail": [
+//!             { "name": "from", "type": "address" },
+//!             { "name": "to", "type": "address" },
+//!             { "name": "contents", "type": "string" }
+//!         ]
+//!     },
+//!     "primaryType": "Mail",
+//!     "domain": { "name": "Ether Mail", "version": "1", "chainId": 1 },
+//!     "message": {
+//!         "from": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826",
+//!         "to": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB",
+//!         "contents": "Hello, Bob!"
+//!     }
+//! }"#)?;
+//! let data_to_sign = typed_data.data_to_sign()?;
+//! # let _ = data_to_sign;
+//! # Ok(()) }
+//! ```
+//!
+//! [EIP-712]: https://eips.ethereum.org/EIPS/eip-712
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+
+use generic_ec::Scalar;
+use paillier_zk::rug::{integer::Order, Integer};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha3::{Digest, Keccak256};
+
+use crate::signing::DataToSign;
+use crate::supported_curves::Secp256k1;
+
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// Typed structured data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    /// Definitions of struct types
+    pub types: BTreeMap<String, Vec<Field>>,
+    /// Type of the [message](Self::message)
+    pub primary_type: String,
+    /// Domain, an instance of `EIP712Domain` type
+    pub domain: Value,
+    /// Message, an instance of [primary type](Self::primary_type)
+    pub message: Value,
+}
+
+/// Member of a struct type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Field {
+    /// Name of the member
+    pub name: String,
+    /// Type of the member
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+impl TypedData {
+    /// Parses typed data from JSON
+    pub fn from_json(json: &str) -> Result<Self, Eip712Error> {
+        serde_json::from_str(json).map_err(|err| Reason::Json(err).into())
+    }
+
+    /// Computes `domainSeparator = hashStruct(domain)`
+    pub fn domain_separator(&self) -> Result<[u8; 32], Eip712Error> {
+        let types = self.types_with_domain()?;
+        Encoder { types: &types }.hash_struct(DOMAIN_TYPE, &self.domain)
+    }
+
+    /// Computes `hashStruct(message)`
+    pub fn struct_hash(&self) -> Result<[u8; 32], Eip712Error> {
+        Encoder { types: &self.types }.hash_struct(&self.primary_type, &self.message)
+    }
+
+    /// Computes `keccak256("\x19\x01" || domainSeparator || hashStruct(message))`
+    pub fn signing_hash(&self) -> Result<[u8; 32], Eip712Error> {
+        let hash = Keccak256::new()
+            .chain_update([0x19, 0x01])
+            .chain_update(self.domain_separator()?)
+            .chain_update(self.struct_hash()?)
+            .finalize();
+        Ok(hash.into())
+    }
+
+    /// Returns [`DataToSign`] corresponding to [signing hash](Self::signing_hash)
+    pub fn data_to_sign(&self) -> Result<DataToSign<Secp256k1>, Eip712Error> {
+        Ok(DataToSign::from_scalar(Scalar::from_be_bytes_mod_order(
+            self.signing_hash()?,
+        )))
+    }
+
+    /// Returns types with `EIP712Domain` defined
+    ///
+    /// If it's not defined in `types`, it's derived from fields present in the domain
+    fn types_with_domain(&self) -> Result<Cow<BTreeMap<String, Vec<Field>>>, Eip712Error> {
+        if self.types.contains_key(DOMAIN_TYPE) {
+            return Ok(Cow::Borrowed(&self.types));
+        }
+        let domain = self
+            .domain
+            .as_object()
+            .ok_or_else(|| Reason::InvalidValue(DOMAIN_TYPE.to_owned()))?;
+        let fields = [
+            ("name", "string"),
+            ("version", "string"),
+            ("chainId", "uint256"),
+            ("verifyingContract", "address"),
+            ("salt", "bytes32"),
+        ]
+        .into_iter()
+        .filter(|(name, _)| domain.contains_key(*name))
+        .map(|(name, ty)| Field {
+            name: name.to_owned(),
+            ty: ty.to_owned(),
+        })
+        .collect();
+
+        let mut types = self.types.clone();
+        types.insert(DOMAIN_TYPE.to_owned(), fields);
+        Ok(Cow::Owned(types))
+    }
+}
+
+struct Encoder<'a> {
+    types: &'a BTreeMap<String, Vec<Field>>,
+}
+
+impl Encoder<'_> {
+    /// `hashStruct(s) = keccak256(typeHash || encodeData(s))`
+    fn hash_struct(&self, ty: &str, value: &Value) -> Result<[u8; 32], Eip712Error> {
+        let fields = self.fields(ty)?;
+        let value = value
+            .as_object()
+            .ok_or_else(|| Reason::InvalidValue(ty.to_owned()))?;
+
+        let mut hash = Keccak256::new().chain_update(Keccak256::digest(self.encode_type(ty)?));
+        for field in fields {
+            let field_value = value.get(&field.name).ok_or_else(|| Reason::MissingField {
+                ty: ty.to_owned(),
+                field: field.name.clone(),
+            })?;
+            hash.update(self.encode_value(&field.ty, field_value)?);
+        }
+        Ok(hash.finalize().into())
+    }
+
+    /// Encodes type along with all types it references, sorted by name
+    fn encode_type(&self, ty: &str) -> Result<String, Eip712Error> {
+        let mut deps = BTreeSet::new();
+        self.collect_dependencies(ty, &mut deps)?;
+        deps.remove(ty);
+
+        let mut encoded = String::new();
+        for ty in std::iter::once(ty).chain(deps.iter().copied()) {
+            let fields = self
+                .fields(ty)?
+                .iter()
+                .map(|f| format!("{} {}", f.ty, f.name))
+                .collect::<Vec<_>>()
+                .join(",");
+            encoded.push_str(&format!("{ty}({fields})"));
+        }
+        Ok(encoded)
+    }
+
+    fn collect_dependencies<'t>(
+        &'t self,
+        ty: &'t str,
+        deps: &mut BTreeSet<&'t str>,
+    ) -> Result<(), Eip712Error> {
+        let ty = base_type(ty);
+        if !self.types.contains_key(ty) || deps.contains(ty) {
+            return Ok(());
+        }
+        deps.insert(ty);
+        for field in self.fields(ty)? {
+            self.collect_dependencies(&field.ty, deps)?;
+        }
+        Ok(())
+    }
+
+    fn encode_value(&self, ty: &str, value: &Value) -> Result<[u8; 32], Eip712Error> {
+        let invalid = || Eip712Error::from(Reason::InvalidValue(ty.to_owned()));
+
+        if let Some((item_ty, len)) = parse_array(ty) {
+            let items = value.as_array().ok_or_else(invalid)?;
+            if matches!(len, Some(len) if len != items.len()) {
+                return Err(invalid());
+            }
+            let mut hash = Keccak256::new();
+            for item in items {
+                hash.update(self.encode_value(item_ty, item)?);
+            }
+            return Ok(hash.finalize().into());
+        }
+        if self.types.contains_key(ty) {
+            return self.hash_struct(ty, value);
+        }
+
+        let mut out = [0u8; 32];
+        match ty {
+            "string" => out = Keccak256::digest(value.as_str().ok_or_else(invalid)?).into(),
+            "bytes" => {
+                let bytes = value.as_str().and_then(parse_hex).ok_or_else(invalid)?;
+                out = Keccak256::digest(bytes).into()
+            }
+            "bool" => out[31] = u8::from(value.as_bool().ok_or_else(invalid)?),
+            "address" => {
+                let bytes = value.as_str().and_then(parse_hex).ok_or_else(invalid)?;
+                if bytes.len() != 20 {
+                    return Err(invalid());
+                }
+                out[12..].copy_from_slice(&bytes)
+            }
+            _ if ty.starts_with("bytes") => {
+                let len: usize = ty["bytes".len()..].parse().map_err(|_| invalid())?;
+                let bytes = value.as_str().and_then(parse_hex).ok_or_else(invalid)?;
+                if !(1..=32).contains(&len) || bytes.len() != len {
+                    return Err(invalid());
+                }
+                out[..len].copy_from_slice(&bytes)
+            }
+            _ if ty.starts_with("uint") || ty.starts_with("int") => {
+                let signed = ty.starts_with("int");
+                let bits: u32 = ty.trim_start_matches('u')["int".len()..]
+                    .parse()
+                    .map_err(|_| invalid())?;
+                if bits == 0 || bits > 256 || bits % 8 != 0 {
+                    return Err(invalid());
+                }
+                let mut int = parse_integer(value).ok_or_else(invalid)?;
+                let (min, max) = if signed {
+                    (
+                        -(Integer::from(1) << (bits - 1)),
+                        Integer::from(1) << (bits - 1),
+                    )
+                } else {
+                    (Integer::new(), Integer::from(1) << bits)
+                };
+                if int < min || int >= max {
+                    return Err(invalid());
+                }
+                if int < 0 {
+                    // Two's complement
+                    int += Integer::from(1) << 256;
+                }
+                let digits = int.to_digits::<u8>(Order::Msf);
+                out[32 - digits.len()..].copy_from_slice(&digits)
+            }
+            _ => return Err(Reason::UnknownType(ty.to_owned()).into()),
+        }
+        Ok(out)
+    }
+
+    fn fields(&self, ty: &str) -> Result<&[Field], Eip712Error> {
+        self.types
+            .get(ty)
+            .map(Vec::as_slice)
+            .ok_or_else(|| Reason::UnknownType(ty.to_owned()).into())
+    }
+}
+
+/// Strips array suffixes, e.g. `Person[][2]` becomes `Person`
+fn base_type(ty: &str) -> &str {
+    ty.split('[').next().unwrap_or(ty)
+}
+
+/// Parses array type, e.g. `Person[][2]` is parsed as `(Person[], Some(2))`
+fn parse_array(ty: &str) -> Option<(&str, Option<usize>)> {
+    let ty = ty.strip_suffix(']')?;
+    let (item_ty, len) = ty.rsplit_once('[')?;
+    let len = if len.is_empty() {
+        None
+    } else {
+        Some(len.parse().ok()?)
+    };
+    Some((item_ty, len))
+}
+
+fn parse_hex(s: &str) -> Option<Vec<u8>> {
+    let s = s.strip_prefix("0x")?;
+    let mut bytes = vec![0u8; s.len() / 2];
+    hex::decode_to_slice(s, &mut bytes).ok()?;
+    Some(bytes)
+}
+
+/// Parses integer given either as JSON number, decimal string, or `0x`-prefixed hex string
+fn parse_integer(value: &Value) -> Option<Integer> {
+    match value {
+        Value::Number(n) => n
+            .as_u64()
+            .map(Integer::from)
+            .or_else(|| n.as_i64().map(Integer::from)),
+        Value::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => Integer::from_str_radix(hex, 16).ok(),
+            None => Integer::from_str_radix(s, 10).ok(),
+        },
+        _ => None,
+    }
+}
+
+/// Error returned by [`TypedData`] methods
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct Eip712Error(Reason);
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("invalid json")]
+    Json(#[source] serde_json::Error),
+    #[error("unknown type: {0}")]
+    UnknownType(String),
+    #[error("struct {ty} is missing field {field}")]
+    MissingField { ty: String, field: String },
+    #[error("invalid value of type {0}")]
+    InvalidValue(String),
+}
+
+crate::errors::impl_from! {
+    impl From for Eip712Error {
+        err: Reason => Eip712Error(err),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_hex, TypedData};
+
+    /// Example from EIP-712 specs
+    const MAIL: &str = r#"{
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" }
+            ],
+            "Person": [
+                { "name": "name", "type": "string" },
+                { "name": "wallet", "type": "address" }
+            ],
+            "Mail": [
+                { "name": "from", "type": "Person" },
+                { "name": "to", "type": "Person" },
+                { "name": "contents", "type": "string" }
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        },
+        "message": {
+            "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+            "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+            "contents": "Hello, Bob!"
+        }
+    }"#;
+
+    #[test]
+    fn mail_example() {
+        let typed_data = TypedData::from_json(MAIL).unwrap();
+        assert_eq!(
+            typed_data.domain_separator().unwrap().to_vec(),
+            parse_hex("0xf2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f")
+                .unwrap()
+        );
+        assert_eq!(
+            typed_data.struct_hash().unwrap().to_vec(),
+            parse_hex("0xc52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e")
+                .unwrap()
+        );
+        assert_eq!(
+            typed_data.signing_hash().unwrap().to_vec(),
+            parse_hex("0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn domain_type_is_derived() {
+        let mut typed_data = TypedData::from_json(MAIL).unwrap();
+        let expected = typed_data.signing_hash().unwrap();
+        typed_data.types.remove("EIP712Domain");
+        assert_eq!(typed_data.signing_hash().unwrap(), expected);
+    }
+
+    #[test]
+    fn rejects_malformed_message() {
+        let mut typed_data = TypedData::from_json(MAIL).unwrap();
+        typed_data.message["from"]["wallet"] = "0x1234".into();
+        assert!(typed_data.signing_hash().is_err());
+
+        let mut typed_data = TypedData::from_json(MAIL).unwrap();
+        typed_data.message["to"]
+            .as_object_mut()
+            .unwrap()
+            .remove("name");
+        assert!(typed_data.signing_hash().is_err());
+    }
+}
@@ -0,0 +1,223 @@
+//! JWS signing with ES256
+//!
+//! Allows MPC key to back OIDC/JWT issuance: [`JwsSigningInput`] builds JWS signing input
+//! `base64url(header) || '.' || base64url(payload)` and maps it into [`DataToSign`]. Signature
+//! produced by the signing protocol is then appended to obtain JWS in compact serialization.
+//! Verifiers can be provided with [`Jwk`] of the shared public key.
+//!
+//! ## Example
+//! ```rust
+//! use cggmp21::signing::jose::{Jwk, JwsSigningInput};
+//! # fn f(
+//! #     public_key: generic_ec::NonZero<generic_ec::Point<cggmp21::supported_curves::Secp256r1>>,
+//! #     sign: impl FnOnce(cggmp21::DataToSign<cggmp21::supported_curves::Secp256r1>) -> cggmp21::Signature<cggmp21::supported_curves::Secp256r1>,
+//! # ) -> Result<(), cggmp21::signing::jose::JoseError> {
+//! let input = JwsSigningInput::jwt(&serde_json::json!({ "sub": "alice", "iat": 1700000000 }))?;
+//! // Signature is obtained by running signing protocol
+//! let signature = sign(input.data_to_sign());
+//! let jwt = input.into_compact(&signature);
+//!
+//! // JWK to be published, so anyone can verify the JWT
+//! let jwk = Jwk::new(&public_key);
+//! # let _ = (jwt, jwk);
+//! # Ok(()) }
+//! ```
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as BASE64URL, Engine};
+use generic_ec::{NonZero, Point};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{DataToSign, Signature};
+use crate::supported_curves::Secp256r1;
+
+const ALG: &str = "ES256";
+
+/// JWS signing input
+#[derive(Debug, Clone)]
+pub struct JwsSigningInput {
+    signing_input: String,
+}
+
+impl JwsSigningInput {
+    /// Constructs signing input from JOSE header and payload
+    ///
+    /// `alg` is set to `ES256` if it's not present in the header. Returns error if header
+    /// specifies a different algorithm.
+    pub fn new(mut header: Map<String, Value>, payload: &[u8]) -> Result<Self, JoseError> {
+        match header.get("alg") {
+            None => {
+                header.insert("alg".to_owned(), ALG.into());
+            }
+            Some(Value::String(alg)) if alg == ALG => (),
+            Some(alg) => return Err(Reason::UnsupportedAlg(alg.to_string()).into()),
+        }
+        let header = serde_json::to_vec(&header).map_err(Reason::Json)?;
+        Ok(Self {
+            signing_input: format!("{}.{}", BASE64URL.encode(header), BASE64URL.encode(payload)),
+        })
+    }
+
+    /// Constructs signing input of JWT with given claims
+    ///
+    /// Header is `{"alg":"ES256","typ":"JWT"}`
+    pub fn jwt(claims: &impl Serialize) -> Result<Self, JoseError> {
+        let mut header = Map::new();
+        header.insert("alg".to_owned(), ALG.into());
+        header.insert("typ".to_owned(), "JWT".into());
+        let payload = serde_json::to_vec(claims).map_err(Reason::Json)?;
+        Self::new(header, &payload)
+    }
+
+    /// Returns signing input `base64url(header) || '.' || base64url(payload)`
+    pub fn as_str(&self) -> &str {
+        &self.signing_input
+    }
+
+    /// Returns data to be signed, i.e. SHA-256 hash of signing input
+    pub fn data_to_sign(&self) -> DataToSign<Secp256r1> {
+        DataToSign::digest::<sha2::Sha256>(self.signing_input.as_bytes())
+    }
+
+    /// Outputs JWS in compact serialization, i.e. signing input followed by `.` and
+    /// [encoded signature](encode_signature)
+    pub fn into_compact(self, signature: &Signature<Secp256r1>) -> String {
+        format!("{}.{}", self.signing_input, encode_signature(signature))
+    }
+}
+
+/// Encodes signature as required by JWS: base64url of `r || s`
+pub fn encode_signature(signature: &Signature<Secp256r1>) -> String {
+    let mut bytes = [0u8; 64];
+    signature.write_to_slice(&mut bytes);
+    BASE64URL.encode(bytes)
+}
+
+/// Decodes JWS signature
+///
+/// Returns `None` if signature is malformed
+pub fn decode_signature(signature: &str) -> Option<Signature<Secp256r1>> {
+    let bytes = BASE64URL.decode(signature).ok()?;
+    Signature::read_from_slice(&bytes)
+}
+
+/// JSON Web Key of P-256 public key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Jwk {
+    /// Key type, always `EC`
+    pub kty: String,
+    /// Curve, always `P-256`
+    pub crv: String,
+    /// base64url-encoded `x` coordinate
+    pub x: String,
+    /// base64url-encoded `y` coordinate
+    pub y: String,
+    /// Optional key ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+}
+
+impl Jwk {
+    /// Constructs JWK of the public key
+    pub fn new(public_key: &NonZero<Point<Secp256r1>>) -> Self {
+        let encoded = public_key.to_bytes(false);
+        // Uncompressed encoding is `0x04 || x || y`
+        let (x, y) = encoded.as_bytes()[1..].split_at(32);
+        Self {
+            kty: "EC".to_owned(),
+            crv: "P-256".to_owned(),
+            x: BASE64URL.encode(x),
+            y: BASE64URL.encode(y),
+            kid: None,
+        }
+    }
+
+    /// Sets key ID
+    pub fn set_kid(mut self, kid: impl Into<String>) -> Self {
+        self.kid = Some(kid.into());
+        self
+    }
+
+    /// Decodes public key
+    ///
+    /// Returns `None` if JWK is malformed or doesn't correspond to P-256 key
+    pub fn public_key(&self) -> Option<NonZero<Point<Secp256r1>>> {
+        if self.kty != "EC" || self.crv != "P-256" {
+            return None;
+        }
+        let x = BASE64URL.decode(&self.x).ok()?;
+        let y = BASE64URL.decode(&self.y).ok()?;
+        if x.len() != 32 || y.len() != 32 {
+            return None;
+        }
+        let encoded = [&[0x04][..], &x, &y].concat();
+        NonZero::from_point(Point::from_bytes(encoded).ok()?)
+    }
+}
+
+/// Error related to JWS signing
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct JoseError(Reason);
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("serialize to json")]
+    Json(#[source] serde_json::Error),
+    #[error("unsupported algorithm: {0}")]
+    UnsupportedAlg(String),
+}
+
+crate::errors::impl_from! {
+    impl From for JoseError {
+        err: Reason => JoseError(err),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use generic_ec::{coords::AlwaysHasAffineX, NonZero, Point, Scalar, SecretScalar};
+    use p256::ecdsa::signature::Verifier;
+
+    use super::{decode_signature, Jwk, JwsSigningInput};
+    use crate::signing::Signature;
+    use crate::supported_curves::Secp256r1;
+
+    #[test]
+    fn jwt_is_verified_by_p256() {
+        let mut rng = rand_dev::DevRng::new();
+        let sk = SecretScalar::<Secp256r1>::random(&mut rng);
+        let pk = NonZero::from_point(Point::generator() * &sk).unwrap();
+
+        let input = JwsSigningInput::jwt(&serde_json::json!({ "sub": "alice" })).unwrap();
+        let data = input.data_to_sign();
+
+        let k = NonZero::<Scalar<Secp256r1>>::random(&mut rng);
+        let R = NonZero::from_point(Point::generator() * *k).unwrap();
+        let r = NonZero::from_scalar(R.x().to_scalar()).unwrap();
+        let s = NonZero::from_scalar(*k.invert() * (data.to_scalar() + *r * sk.as_ref())).unwrap();
+        let signature = Signature::from_raw_parts(r, s);
+
+        let jwt = input.clone().into_compact(&signature);
+        let (signing_input, encoded_signature) = jwt.rsplit_once('.').unwrap();
+        assert_eq!(signing_input, input.as_str());
+        assert_eq!(decode_signature(encoded_signature), Some(signature));
+
+        // Public key survives JWK round trip
+        let jwk = Jwk::new(&pk).set_kid("key-1");
+        let jwk: Jwk = serde_json::from_str(&serde_json::to_string(&jwk).unwrap()).unwrap();
+        assert_eq!(jwk.public_key(), Some(pk));
+
+        // Independent ES256 verification
+        let vk = crate::signing::rustcrypto::to_p256_verifying_key(&jwk.public_key().unwrap());
+        let signature: p256::ecdsa::Signature = signature.into();
+        vk.verify(signing_input.as_bytes(), &signature).unwrap();
+    }
+
+    #[test]
+    fn rejects_other_algorithms() {
+        let mut header = serde_json::Map::new();
+        header.insert("alg".to_owned(), "RS256".into());
+        assert!(JwsSigningInput::new(header, b"payload").is_err());
+    }
+}
@@ -0,0 +1,274 @@
+//! Encryption of presignatures at rest
+//!
+//! [`Presignature`] contains secret scalars, so it must not be persisted in plaintext. This module
+//! provides an encrypted envelope format for storing presignatures: presignature is encoded,
+//! then encrypted and authenticated with XChaCha20-Poly1305 under a 256-bit key.
+//!
+//! Encrypted presignature can be bound to the context (e.g. key ID and set of signers) by
+//! providing `associated_data`: decryption fails if it doesn't match.
+//!
+//! Secret components of in-memory [`Presignature`] are zeroized on drop. Buffers holding
+//! encoded plaintext presignature during encryption and decryption are zeroized as well.
+//!
+//! ## Envelope format
+//! `version (1 byte) || nonce (24 bytes) || ciphertext`, where ciphertext is encryption of
+//! `R (compressed point) || k || chi (big-endian scalars)`.
+//!
+//! ## Example
+//! ```rust
+//! use cggmp21::signing::presignature_encryption::PresignatureEncryptionKey;
+//! # fn f<E: generic_ec::Curve>(presignature: cggmp21::Presignature<E>) -> Result<(), Box<dyn std::error::Error>> {
+//! let mut rng = rand_dev::DevRng::new();
+//! let key = PresignatureEncryptionKey::generate(&mut rng);
+//!
+//! let encrypted = presignature.encrypt(&mut rng, &key, b"key id");
+//! let bytes = encrypted.to_bytes();
+//! // ... store the bytes, and retrieve them later
+//! let encrypted = cggmp21::signing::presignature_encryption::EncryptedPresignature::from_bytes(bytes);
+//! let presignature: cggmp21::Presignature<E> = encrypted.decrypt(&key, b"key id")?;
+//! # Ok(()) }
+//! ```
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde_with::As;
+use zeroize::Zeroizing;
+
+use super::Presignature;
+
+const VERSION: u8 = 1;
+const NONCE_LEN: usize = 24;
+const AAD_TAG: &[u8] = b"dfns.cggmp21.presignature_encryption";
+
+/// Key used to encrypt presignatures
+///
+/// Zeroized on drop
+#[derive(Clone)]
+pub struct PresignatureEncryptionKey(Zeroizing<[u8; 32]>);
+
+impl PresignatureEncryptionKey {
+    /// Constructs a key from bytes
+    pub fn from_bytes(key: [u8; 32]) -> Self {
+        Self(Zeroizing::new(key))
+    }
+
+    /// Generates a random key
+    pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut key = Zeroizing::new([0u8; 32]);
+        rng.fill_bytes(&mut *key);
+        Self(key)
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(chacha20poly1305::Key::from_slice(&*self.0))
+    }
+}
+
+/// Encrypted presignature
+///
+/// Can be obtained via [`Presignature::encrypt`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct EncryptedPresignature(
+    #[cfg_attr(feature = "serde", serde(with = "As::<serde_with::Bytes>"))] Vec<u8>,
+);
+
+impl EncryptedPresignature {
+    /// Wraps bytes of encrypted presignature
+    ///
+    /// Bytes are not validated until [decryption](Self::decrypt)
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns bytes of encrypted presignature
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+
+    /// Returns bytes of encrypted presignature
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Decrypts presignature
+    ///
+    /// `associated_data` must be the same as was provided on encryption
+    pub fn decrypt<E: Curve>(
+        &self,
+        key: &PresignatureEncryptionKey,
+        associated_data: &[u8],
+    ) -> Result<Presignature<E>, DecryptError> {
+        let (&version, rest) = self.0.split_first().ok_or(Reason::Malformed)?;
+        if version != VERSION {
+            return Err(Reason::UnknownVersion(version).into());
+        }
+        if rest.len() < NONCE_LEN {
+            return Err(Reason::Malformed.into());
+        }
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+        let aad = aad(associated_data);
+        let plaintext = Zeroizing::new(
+            key.cipher()
+                .decrypt(
+                    XNonce::from_slice(nonce),
+                    Payload {
+                        msg: ciphertext,
+                        aad: &aad,
+                    },
+                )
+                .map_err(|_| Reason::Decrypt)?,
+        );
+        decode(&plaintext).ok_or_else(|| Reason::Malformed.into())
+    }
+}
+
+impl<E: Curve> Presignature<E> {
+    /// Encrypts presignature to be stored at rest
+    ///
+    /// `associated_data` binds encrypted presignature to the context, the same associated data
+    /// must be provided on [decryption](EncryptedPresignature::decrypt).
+    pub fn encrypt<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        key: &PresignatureEncryptionKey,
+        associated_data: &[u8],
+    ) -> EncryptedPresignature {
+        let plaintext = encode(self);
+
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill_bytes(&mut nonce);
+
+        let aad = aad(associated_data);
+        #[allow(clippy::expect_used)]
+        let ciphertext = key
+            .cipher()
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: &aad,
+                },
+            )
+            .expect("encryption never fails for plaintext of a presignature size");
+
+        let mut envelope = Vec::with_capacity(1 + NONCE_LEN + ciphertext.len());
+        envelope.push(VERSION);
+        envelope.extend_from_slice(&nonce);
+        envelope.extend_from_slice(&ciphertext);
+        EncryptedPresignature(envelope)
+    }
+}
+
+fn aad(associated_data: &[u8]) -> Vec<u8> {
+    [AAD_TAG, &[VERSION], associated_data].concat()
+}
+
+fn encode<E: Curve>(presig: &Presignature<E>) -> Zeroizing<Vec<u8>> {
+    let mut bytes = Zeroizing::new(Vec::new());
+    bytes.extend_from_slice(presig.R.to_bytes(true).as_bytes());
+    bytes.extend_from_slice(presig.k.as_ref().to_be_bytes().as_bytes());
+    bytes.extend_from_slice(presig.chi.as_ref().to_be_bytes().as_bytes());
+    bytes
+}
+
+fn decode<E: Curve>(bytes: &[u8]) -> Option<Presignature<E>> {
+    let scalar_len = Scalar::<E>::serialized_len();
+    let point_len = bytes.len().checked_sub(2 * scalar_len)?;
+    let (R, scalars) = bytes.split_at(point_len);
+    let (k, chi) = scalars.split_at(scalar_len);
+
+    let R = NonZero::from_point(Point::from_bytes(R).ok()?)?;
+    let mut k = Scalar::from_be_bytes(k).ok()?;
+    let mut chi = Scalar::from_be_bytes(chi).ok()?;
+    Some(Presignature::from_parts(
+        R,
+        SecretScalar::new(&mut k),
+        SecretScalar::new(&mut chi),
+    ))
+}
+
+/// Error returned by [`EncryptedPresignature::decrypt`]
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct DecryptError(Reason);
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("unknown envelope version: {0}")]
+    UnknownVersion(u8),
+    #[error("malformed encrypted presignature")]
+    Malformed,
+    #[error("decryption failed: wrong key, associated data, or ciphertext is corrupted")]
+    Decrypt,
+}
+
+crate::errors::impl_from! {
+    impl From for DecryptError {
+        err: Reason => DecryptError(err),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use generic_ec::{Curve, NonZero, Point, SecretScalar};
+
+    use super::{EncryptedPresignature, PresignatureEncryptionKey};
+    use crate::Presignature;
+
+    fn encrypt_decrypt<E: Curve>() {
+        let mut rng = rand_dev::DevRng::new();
+        let key = PresignatureEncryptionKey::generate(&mut rng);
+
+        let presig = Presignature::<E>::from_parts(
+            NonZero::from_point(Point::<E>::generator() * &SecretScalar::random(&mut rng)).unwrap(),
+            SecretScalar::random(&mut rng),
+            SecretScalar::random(&mut rng),
+        );
+        let encrypted = presig.encrypt(&mut rng, &key, b"associated data");
+
+        let decrypted = encrypted
+            .decrypt::<E>(&key, b"associated data")
+            .expect("decryption failed");
+        assert_eq!(presig.R, decrypted.R);
+        assert_eq!(presig.k.as_ref(), decrypted.k.as_ref());
+        assert_eq!(presig.chi.as_ref(), decrypted.chi.as_ref());
+
+        // Wrong associated data
+        assert!(encrypted.decrypt::<E>(&key, b"other data").is_err());
+        // Wrong key
+        let other_key = PresignatureEncryptionKey::generate(&mut rng);
+        assert!(encrypted
+            .decrypt::<E>(&other_key, b"associated data")
+            .is_err());
+        // Corrupted ciphertext
+        let mut bytes = encrypted.to_bytes();
+        *bytes.last_mut().unwrap() ^= 1;
+        assert!(EncryptedPresignature::from_bytes(bytes)
+            .decrypt::<E>(&key, b"associated data")
+            .is_err());
+    }
+
+    #[cfg(feature = "curve-secp256k1")]
+    #[test]
+    fn encrypt_decrypt_secp256k1() {
+        encrypt_decrypt::<crate::supported_curves::Secp256k1>()
+    }
+    #[cfg(feature = "curve-secp256r1")]
+    #[test]
+    fn encrypt_decrypt_secp256r1() {
+        encrypt_decrypt::<crate::supported_curves::Secp256r1>()
+    }
+    #[cfg(feature = "curve-stark")]
+    #[test]
+    fn encrypt_decrypt_stark() {
+        encrypt_decrypt::<crate::supported_curves::Stark>()
+    }
+}
@@ -0,0 +1,127 @@
+//! Storage of presignatures
+//!
+//! Presignature must never be used to issue more than one partial signature: reusing it leaks
+//! the secret key. [`PresignatureStore`] enforces that by API: once presignature is put into
+//! the store, the only way to get it back is [`consume_one`](PresignatureStore::consume_one),
+//! which removes it from the store.
+//!
+//! Note that all signers need to use presignatures obtained from the same protocol execution
+//! to issue partial signatures for the same message. Store hands out presignatures in the order
+//! they were put, so signers who put presignatures in the same order and consume them in lockstep
+//! stay in sync.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use generic_ec::Curve;
+
+use super::Presignature;
+
+/// Storage of presignatures with atomic consume semantics
+///
+/// [`InMemoryPresignatureStore`] is provided out of box. Persistent back-ends can be implemented
+/// on top of a database, in which case [`PresignatureStoreError::backend`] can be used to report
+/// a storage failure.
+pub trait PresignatureStore<E: Curve> {
+    /// Puts presignature into the store
+    fn put(&self, presignature: Presignature<E>) -> Result<(), PresignatureStoreError>;
+
+    /// Takes the oldest presignature out of the store
+    ///
+    /// Returns `None` if store is empty. Taking presignature out must be done atomically, i.e.
+    /// the same presignature must never be returned twice, even if two calls are made concurrently.
+    fn consume_one(&self) -> Result<Option<Presignature<E>>, PresignatureStoreError>;
+
+    /// Returns amount of presignatures in the store
+    fn len(&self) -> Result<usize, PresignatureStoreError>;
+
+    /// Checks whether store is empty
+    fn is_empty(&self) -> Result<bool, PresignatureStoreError> {
+        Ok(self.len()? == 0)
+    }
+}
+
+impl<E: Curve, S: PresignatureStore<E> + ?Sized> PresignatureStore<E> for &S {
+    fn put(&self, presignature: Presignature<E>) -> Result<(), PresignatureStoreError> {
+        (**self).put(presignature)
+    }
+    fn consume_one(&self) -> Result<Option<Presignature<E>>, PresignatureStoreError> {
+        (**self).consume_one()
+    }
+    fn len(&self) -> Result<usize, PresignatureStoreError> {
+        (**self).len()
+    }
+    fn is_empty(&self) -> Result<bool, PresignatureStoreError> {
+        (**self).is_empty()
+    }
+}
+
+/// In-memory store of presignatures
+///
+/// Keeps all presignatures in RAM, so they're lost when program exits.
+pub struct InMemoryPresignatureStore<E: Curve> {
+    presignatures: Mutex<VecDeque<Presignature<E>>>,
+}
+
+impl<E: Curve> InMemoryPresignatureStore<E> {
+    /// Constructs an empty store
+    pub fn new() -> Self {
+        Self {
+            presignatures: Mutex::new(VecDeque::new()),
+        }
+    }
+}
+
+impl<E: Curve> Default for InMemoryPresignatureStore<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Curve> PresignatureStore<E> for InMemoryPresignatureStore<E> {
+    fn put(&self, presignature: Presignature<E>) -> Result<(), PresignatureStoreError> {
+        self.presignatures
+            .lock()
+            .map_err(|_| PresignatureStoreError::backend(PoisonedLock))?
+            .push_back(presignature);
+        Ok(())
+    }
+
+    fn consume_one(&self) -> Result<Option<Presignature<E>>, PresignatureStoreError> {
+        Ok(self
+            .presignatures
+            .lock()
+            .map_err(|_| PresignatureStoreError::backend(PoisonedLock))?
+            .pop_front())
+    }
+
+    fn len(&self) -> Result<usize, PresignatureStoreError> {
+        Ok(self
+            .presignatures
+            .lock()
+            .map_err(|_| PresignatureStoreError::backend(PoisonedLock))?
+            .len())
+    }
+}
+
+/// Error returned by [`PresignatureStore`]
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct PresignatureStoreError(Reason);
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("store back-end failed")]
+    Backend(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl PresignatureStoreError {
+    /// Constructs an error indicating that store back-end failed
+    pub fn backend(err: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self(Reason::Backend(Box::new(err)))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("lock is poisoned")]
+struct PoisonedLock;
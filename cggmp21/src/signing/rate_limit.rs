@@ -0,0 +1,275 @@
+//! Signature accounting and rate limiting
+//!
+//! [`RateLimiter`] counts signatures issued with each key and enforces [`RateLimit`]: amount of
+//! signatures per period and total amount of signatures over lifetime of the key. Limiter is
+//! consulted by the signing protocol right before the partial signature is released, when set
+//! via [`SigningBuilder::set_rate_limiter`](super::SigningBuilder::set_rate_limiter). If limit
+//! is exceeded, the signer aborts the protocol and returns an error for which
+//! [`SigningError::is_rate_limited`](super::SigningError::is_rate_limited) is `true`.
+//!
+//! Keys are identified by [`KeyFingerprint`]. Counters are persisted via [`UsageStore`]:
+//! [`InMemoryUsageStore`] is provided out of box, persistent back-ends can be implemented on
+//! top of a database.
+//!
+//! Periods are fixed windows: counter of signatures in period is reset once `period` elapsed
+//! since the first signature in the window.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use cggmp21::signing::rate_limit::{InMemoryUsageStore, RateLimit, RateLimiter};
+//!
+//! let limiter = RateLimiter::new(
+//!     InMemoryUsageStore::new(),
+//!     RateLimit {
+//!         per_period: Some((100, Duration::from_secs(3600))),
+//!         total: Some(1_000_000),
+//!     },
+//! );
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[doc(no_inline)]
+pub use crate::key_share::KeyFingerprint;
+
+/// Limits on amount of signatures
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RateLimit {
+    /// Max amount of signatures within period
+    pub per_period: Option<(u64, Duration)>,
+    /// Max amount of signatures over lifetime of the key
+    pub total: Option<u64>,
+}
+
+/// Usage of the key
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignatureUsage {
+    /// Total amount of signatures
+    pub total: u64,
+    /// Unix timestamp (in seconds) of beginning of current period
+    pub period_start: u64,
+    /// Amount of signatures in current period
+    pub in_period: u64,
+}
+
+/// Persistent storage of signature counters
+pub trait UsageStore {
+    /// Updates usage of the key
+    ///
+    /// `update` takes current usage (`None` if key was never used) and returns the new usage,
+    /// which must be saved, or an error, in which case usage must stay unchanged. Update must
+    /// be done atomically, i.e. if two updates are made concurrently for the same key, second
+    /// one must observe the result of the first one.
+    fn update(
+        &self,
+        key: &KeyFingerprint,
+        update: &mut dyn FnMut(Option<SignatureUsage>) -> Result<SignatureUsage, RateLimitError>,
+    ) -> Result<(), RateLimitError>;
+
+    /// Returns usage of the key
+    fn usage(&self, key: &KeyFingerprint) -> Result<Option<SignatureUsage>, RateLimitError>;
+}
+
+impl<S: UsageStore + ?Sized> UsageStore for &S {
+    fn update(
+        &self,
+        key: &KeyFingerprint,
+        update: &mut dyn FnMut(Option<SignatureUsage>) -> Result<SignatureUsage, RateLimitError>,
+    ) -> Result<(), RateLimitError> {
+        (**self).update(key, update)
+    }
+    fn usage(&self, key: &KeyFingerprint) -> Result<Option<SignatureUsage>, RateLimitError> {
+        (**self).usage(key)
+    }
+}
+
+/// Limiter consulted by the signing protocol
+///
+/// Implemented by [`RateLimiter`]
+pub trait SignatureLimiter: Sync {
+    /// Accounts one more signature issued with the key
+    ///
+    /// Returns error if limit is exceeded, in which case signature must not be issued.
+    fn acquire(&self, key: &KeyFingerprint) -> Result<(), RateLimitError>;
+}
+
+/// Enforces [`RateLimit`] on all keys, counters are kept in [`UsageStore`]
+pub struct RateLimiter<S> {
+    store: S,
+    limit: RateLimit,
+}
+
+impl<S: UsageStore> RateLimiter<S> {
+    /// Constructs a rate limiter
+    pub fn new(store: S, limit: RateLimit) -> Self {
+        Self { store, limit }
+    }
+
+    /// Returns the store
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Accounts one more signature issued with the key at specified time
+    pub fn acquire_at(&self, key: &KeyFingerprint, now: SystemTime) -> Result<(), RateLimitError> {
+        let now = now
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| Reason::Clock)?
+            .as_secs();
+        self.store.update(key, &mut |usage| {
+            let mut usage = usage.unwrap_or(SignatureUsage {
+                total: 0,
+                period_start: now,
+                in_period: 0,
+            });
+            if let Some(total) = self.limit.total {
+                if usage.total >= total {
+                    return Err(Reason::TotalLimitExceeded { limit: total }.into());
+                }
+            }
+            if let Some((limit, period)) = self.limit.per_period {
+                if now.saturating_sub(usage.period_start) >= period.as_secs() {
+                    usage.period_start = now;
+                    usage.in_period = 0;
+                }
+                if usage.in_period >= limit {
+                    return Err(Reason::PeriodLimitExceeded { limit, period }.into());
+                }
+            }
+            usage.total += 1;
+            usage.in_period += 1;
+            Ok(usage)
+        })
+    }
+}
+
+impl<S: UsageStore + Sync> SignatureLimiter for RateLimiter<S> {
+    fn acquire(&self, key: &KeyFingerprint) -> Result<(), RateLimitError> {
+        self.acquire_at(key, SystemTime::now())
+    }
+}
+
+/// In-memory storage of signature counters
+///
+/// Keeps counters in RAM, so they're reset when program exits.
+#[derive(Debug, Default)]
+pub struct InMemoryUsageStore {
+    usage: Mutex<HashMap<KeyFingerprint, SignatureUsage>>,
+}
+
+impl InMemoryUsageStore {
+    /// Constructs an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl UsageStore for InMemoryUsageStore {
+    fn update(
+        &self,
+        key: &KeyFingerprint,
+        update: &mut dyn FnMut(Option<SignatureUsage>) -> Result<SignatureUsage, RateLimitError>,
+    ) -> Result<(), RateLimitError> {
+        let mut usage = self
+            .usage
+            .lock()
+            .map_err(|_| RateLimitError::backend(PoisonedLock))?;
+        let new_usage = update(usage.get(key).copied())?;
+        usage.insert(*key, new_usage);
+        Ok(())
+    }
+
+    fn usage(&self, key: &KeyFingerprint) -> Result<Option<SignatureUsage>, RateLimitError> {
+        Ok(self
+            .usage
+            .lock()
+            .map_err(|_| RateLimitError::backend(PoisonedLock))?
+            .get(key)
+            .copied())
+    }
+}
+
+/// Error returned by [`SignatureLimiter`] and [`UsageStore`]
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct RateLimitError(Reason);
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("limit of {limit} signatures per {period:?} is exceeded")]
+    PeriodLimitExceeded { limit: u64, period: Duration },
+    #[error("limit of {limit} signatures in total is exceeded")]
+    TotalLimitExceeded { limit: u64 },
+    #[error("system time is before unix epoch")]
+    Clock,
+    #[error("usage store back-end failed")]
+    Backend(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl RateLimitError {
+    /// Constructs an error indicating that storage back-end failed
+    pub fn backend(err: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self(Reason::Backend(Box::new(err)))
+    }
+
+    /// Indicates whether limit is exceeded
+    pub fn is_limit_exceeded(&self) -> bool {
+        matches!(
+            self.0,
+            Reason::PeriodLimitExceeded { .. } | Reason::TotalLimitExceeded { .. }
+        )
+    }
+}
+
+crate::errors::impl_from! {
+    impl From for RateLimitError {
+        err: Reason => RateLimitError(err),
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("lock is poisoned")]
+struct PoisonedLock;
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime};
+
+    use super::{InMemoryUsageStore, KeyFingerprint, RateLimit, RateLimiter, UsageStore};
+
+    #[test]
+    fn limits_are_enforced() {
+        let limiter = RateLimiter::new(
+            InMemoryUsageStore::new(),
+            RateLimit {
+                per_period: Some((2, Duration::from_secs(60))),
+                total: Some(3),
+            },
+        );
+        let key = KeyFingerprint([1; 32]);
+        let other_key = KeyFingerprint([2; 32]);
+        let t0 = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        limiter.acquire_at(&key, t0).unwrap();
+        limiter.acquire_at(&key, t0).unwrap();
+        let err = limiter.acquire_at(&key, t0).unwrap_err();
+        assert!(err.is_limit_exceeded());
+
+        // Other keys are not affected
+        limiter.acquire_at(&other_key, t0).unwrap();
+
+        // Once period elapsed, key can be used again, until total limit is reached
+        let t1 = t0 + Duration::from_secs(60);
+        limiter.acquire_at(&key, t1).unwrap();
+        let err = limiter.acquire_at(&key, t1).unwrap_err();
+        assert!(err.is_limit_exceeded());
+
+        let usage = limiter.store().usage(&key).unwrap().unwrap();
+        assert_eq!(usage.total, 3);
+        assert_eq!(usage.in_period, 1);
+    }
+}
@@ -0,0 +1,44 @@
+//! Recovery of public key from secp256k1 ECDSA signature
+
+use generic_ec::{coords::AlwaysHasAffineX, NonZero, Point};
+
+use super::{DataToSign, Signature};
+use crate::supported_curves::Secp256k1;
+
+/// Recovers public key from signature, given parity of `y` coordinate of `R`
+///
+/// Returns `None` if signature is malformed
+pub(crate) fn recover_public_key(
+    signature: &Signature<Secp256k1>,
+    y_parity: bool,
+    data_to_sign: &DataToSign<Secp256k1>,
+) -> Option<NonZero<Point<Secp256k1>>> {
+    let mut R = [0u8; 33];
+    R[0] = if y_parity { 0x03 } else { 0x02 };
+    R[1..].copy_from_slice(&signature.r.to_be_bytes());
+    let R = Point::<Secp256k1>::from_bytes(R).ok()?;
+
+    let r_inv = signature.r.invert();
+    let public_key = (R * *signature.s - Point::generator() * data_to_sign.to_scalar()) * *r_inv;
+    let public_key = NonZero::from_point(public_key)?;
+
+    // Sanity check: `r` must be an affine `x` of `R`. It can only be violated if `r` was
+    // reduced modulo curve order, which is not supported.
+    if NonZero::from_point(R)?.x().to_scalar() != *signature.r {
+        return None;
+    }
+    Some(public_key)
+}
+
+/// Finds parity of `y` coordinate of `R` such that `public_key` is recovered from the signature
+///
+/// Returns `None` if signature is not valid for given public key and data
+pub(crate) fn find_y_parity(
+    signature: &Signature<Secp256k1>,
+    public_key: &Point<Secp256k1>,
+    data_to_sign: &DataToSign<Secp256k1>,
+) -> Option<bool> {
+    [false, true].into_iter().find(|&y_parity| {
+        recover_public_key(signature, y_parity, data_to_sign).as_deref() == Some(public_key)
+    })
+}
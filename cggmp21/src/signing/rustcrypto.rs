@@ -0,0 +1,171 @@
+//! Conversions to/from [`k256`], [`p256`] and [`p384`] ECDSA types
+//!
+//! [`Signature<Secp256k1>`](Signature), [`Signature<Secp256r1>`](Signature) and
+//! [`Signature<Secp384r1>`](Signature) can be converted to and from `k256::ecdsa::Signature`,
+//! `p256::ecdsa::Signature` and `p384::ecdsa::Signature` using `From` trait. Public keys can be
+//! converted using functions provided in this module.
+//!
+//! Conversions are available when corresponding curve feature (`curve-secp256k1`,
+//! `curve-secp256r1` or `curve-secp384r1`) is enabled.
+//!
+//! ## Example
+//! ```rust
+//! # #[cfg(feature = "curve-secp256k1")]
+//! # fn f(signature: cggmp21::Signature<cggmp21::supported_curves::Secp256k1>) {
+//! let signature: cggmp21::k256::ecdsa::Signature = signature.into();
+//! let signature: cggmp21::Signature<_> = signature.into();
+//! # let _: cggmp21::Signature<cggmp21::supported_curves::Secp256k1> = signature;
+//! # }
+//! ```
+
+use generic_ec::{NonZero, Point};
+
+use super::Signature;
+
+macro_rules! impl_conversions {
+    (
+        curve: $curve:ty,
+        crate: $crate_name:ident,
+        to_verifying_key: $to_vk:ident,
+        from_verifying_key: $from_vk:ident,
+    ) => {
+        impl From<Signature<$curve>> for $crate_name::ecdsa::Signature {
+            fn from(sig: Signature<$curve>) -> Self {
+                let mut bytes = vec![0u8; Signature::<$curve>::serialized_len()];
+                sig.write_to_slice(&mut bytes);
+                #[allow(clippy::expect_used)]
+                $crate_name::ecdsa::Signature::from_slice(&bytes)
+                    .expect("r and s are non-zero scalars, signature is always valid")
+            }
+        }
+
+        impl From<$crate_name::ecdsa::Signature> for Signature<$curve> {
+            fn from(sig: $crate_name::ecdsa::Signature) -> Self {
+                #[allow(clippy::expect_used)]
+                Signature::read_from_slice(&sig.to_bytes())
+                    .expect("r and s are non-zero scalars, signature is always valid")
+            }
+        }
+
+        #[doc = concat!("Converts public key into `", stringify!($crate_name), "::ecdsa::VerifyingKey`")]
+        pub fn $to_vk(public_key: &NonZero<Point<$curve>>) -> $crate_name::ecdsa::VerifyingKey {
+            #[allow(clippy::expect_used)]
+            $crate_name::ecdsa::VerifyingKey::from_sec1_bytes(public_key.to_bytes(true).as_bytes())
+                .expect("non-zero point is always a valid verifying key")
+        }
+
+        #[doc = concat!("Converts `", stringify!($crate_name), "::ecdsa::VerifyingKey` into public key")]
+        pub fn $from_vk(verifying_key: &$crate_name::ecdsa::VerifyingKey) -> NonZero<Point<$curve>> {
+            #[allow(clippy::expect_used)]
+            Point::from_bytes(verifying_key.to_encoded_point(true).as_bytes())
+                .ok()
+                .and_then(NonZero::from_point)
+                .expect("verifying key is always a valid non-zero point")
+        }
+
+        #[cfg(feature = "signature")]
+        impl<D> From<super::signer::VerifyingKey<$curve, D>> for $crate_name::ecdsa::VerifyingKey {
+            fn from(vk: super::signer::VerifyingKey<$curve, D>) -> Self {
+                #[allow(clippy::expect_used)]
+                let public_key = NonZero::from_point(vk.public_key())
+                    .expect("identity point is not a valid public key");
+                $to_vk(&public_key)
+            }
+        }
+
+        #[cfg(feature = "signature")]
+        impl From<$crate_name::ecdsa::VerifyingKey> for super::signer::VerifyingKey<$curve> {
+            fn from(vk: $crate_name::ecdsa::VerifyingKey) -> Self {
+                super::signer::VerifyingKey::new(*$from_vk(&vk))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "curve-secp256k1")]
+impl_conversions! {
+    curve: crate::supported_curves::Secp256k1,
+    crate: k256,
+    to_verifying_key: to_k256_verifying_key,
+    from_verifying_key: from_k256_verifying_key,
+}
+
+#[cfg(feature = "curve-secp256r1")]
+impl_conversions! {
+    curve: crate::supported_curves::Secp256r1,
+    crate: p256,
+    to_verifying_key: to_p256_verifying_key,
+    from_verifying_key: from_p256_verifying_key,
+}
+
+#[cfg(feature = "curve-secp384r1")]
+impl_conversions! {
+    curve: crate::supported_curves::Secp384r1,
+    crate: p384,
+    to_verifying_key: to_p384_verifying_key,
+    from_verifying_key: from_p384_verifying_key,
+}
+
+#[cfg(test)]
+mod test {
+    macro_rules! conversion_tests {
+        ($name:ident, $curve:ty, $crate_name:ident, $to_vk:ident, $from_vk:ident) => {
+            #[test]
+            fn $name() {
+                use generic_ec::{NonZero, Point, SecretScalar};
+                use $crate_name::ecdsa::signature::hazmat::{PrehashSigner, PrehashVerifier};
+
+                use crate::signing::{DataToSign, Signature};
+
+                let mut rng = rand_dev::DevRng::new();
+                let sk = SecretScalar::<$curve>::random(&mut rng);
+                let pk = NonZero::from_point(Point::generator() * &sk).unwrap();
+
+                let foreign_sk =
+                    $crate_name::ecdsa::SigningKey::from_slice(&sk.as_ref().to_be_bytes()).unwrap();
+                let digest = sha2::Sha256::digest(b"message");
+                let foreign_sig: $crate_name::ecdsa::Signature =
+                    foreign_sk.sign_prehash(&digest).unwrap();
+
+                let sig: Signature<$curve> = foreign_sig.into();
+                sig.verify(&pk, &DataToSign::digest::<sha2::Sha256>(b"message"))
+                    .unwrap();
+
+                let back: $crate_name::ecdsa::Signature = sig.into();
+                assert_eq!(back, foreign_sig);
+
+                let vk = crate::signing::rustcrypto::$to_vk(&pk);
+                assert_eq!(&vk, foreign_sk.verifying_key());
+                vk.verify_prehash(&digest, &back).unwrap();
+                assert_eq!(crate::signing::rustcrypto::$from_vk(&vk), pk);
+            }
+        };
+    }
+
+    use sha2::Digest;
+
+    #[cfg(feature = "curve-secp256k1")]
+    conversion_tests!(
+        secp256k1,
+        crate::supported_curves::Secp256k1,
+        k256,
+        to_k256_verifying_key,
+        from_k256_verifying_key
+    );
+    #[cfg(feature = "curve-secp256r1")]
+    conversion_tests!(
+        secp256r1,
+        crate::supported_curves::Secp256r1,
+        p256,
+        to_p256_verifying_key,
+        from_p256_verifying_key
+    );
+    #[cfg(feature = "curve-secp384r1")]
+    conversion_tests!(
+        secp384r1,
+        crate::supported_curves::Secp384r1,
+        p384,
+        to_p384_verifying_key,
+        from_p384_verifying_key
+    );
+}
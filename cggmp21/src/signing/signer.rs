@@ -0,0 +1,406 @@
+//! Implementation of [`signature`] crate traits
+//!
+//! Allows MPC key to be used with any library that accepts RustCrypto signer traits.
+//! [`MpcSigner`] implements [`Signer`](signature::Signer) and
+//! [`DigestSigner`](signature::DigestSigner), [`VerifyingKey`] implements
+//! [`Verifier`](signature::Verifier) and [`DigestVerifier`](signature::DigestVerifier).
+//!
+//! Signer traits are synchronous and non-interactive, whereas producing a signature requires
+//! threshold amount of signers. [`MpcSigner`] delegates it to [`SignatureCoordinator`] which may,
+//! for instance, run the full signing protocol with other signers and wait for its completion.
+//! [`PresignaturePool`] is a coordinator that signs using presignatures from
+//! [`PresignatureStore`]: it only needs to exchange partial signatures with other signers,
+//! which is done via [`PartialSignaturesExchange`].
+//!
+//! Every signature produced by [`MpcSigner`] is verified against the public key before it's
+//! returned.
+//!
+//! ## Example
+//! ```rust
+//! use cggmp21::signing::signer::{MpcSigner, PresignaturePool};
+//! use cggmp21::signature::Signer;
+//! # use cggmp21::signing::{presignature_store::InMemoryPresignatureStore, signer::PartialSignaturesExchange};
+//! # fn f<E, X>(public_key: generic_ec::Point<E>, exchange: X) -> Result<(), signature::Error>
+//! # where
+//! #     E: generic_ec::Curve,
+//! #     generic_ec::NonZero<generic_ec::Point<E>>: generic_ec::coords::AlwaysHasAffineX<E>,
+//! #     X: PartialSignaturesExchange<E>,
+//! # {
+//! let store = InMemoryPresignatureStore::<E>::new();
+//! // ... fill the store with presignatures
+//!
+//! let signer: MpcSigner<E, _> = MpcSigner::new(public_key, PresignaturePool::new(store, exchange));
+//! let signature: cggmp21::Signature<E> = signer.try_sign(b"message")?;
+//! # Ok(()) }
+//! ```
+
+use std::marker::PhantomData;
+
+use digest::Digest;
+use generic_ec::{coords::AlwaysHasAffineX, Curve, NonZero, Point};
+
+use super::{
+    presignature_store::{PresignatureStore, PresignatureStoreError},
+    DataToSign, PartialSignature, PresignatureReused, Signature,
+};
+
+/// Boxed error returned by [`SignatureCoordinator`] and [`PartialSignaturesExchange`]
+pub type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Produces signatures on behalf of a group of signers
+///
+/// Implementation is responsible for interacting with other signers, e.g. by running
+/// the [signing protocol](crate::signing) and blocking until it completes.
+pub trait SignatureCoordinator<E: Curve> {
+    /// Produces a signature of given data
+    ///
+    /// Returned signature doesn't need to be verified: [`MpcSigner`] does it.
+    fn sign(&self, data_to_sign: DataToSign<E>) -> Result<Signature<E>, BoxedError>;
+}
+
+impl<E: Curve, C: SignatureCoordinator<E> + ?Sized> SignatureCoordinator<E> for &C {
+    fn sign(&self, data_to_sign: DataToSign<E>) -> Result<Signature<E>, BoxedError> {
+        (**self).sign(data_to_sign)
+    }
+}
+
+/// Exchanges partial signatures with other signers
+pub trait PartialSignaturesExchange<E: Curve> {
+    /// Sends partial signature of local party to other signers, and returns partial signatures
+    /// received from them
+    ///
+    /// Returned partial signatures must not include `own` partial signature. All signers must
+    /// issue partial signatures using presignatures obtained from the same protocol execution.
+    fn exchange(
+        &self,
+        data_to_sign: DataToSign<E>,
+        own: &PartialSignature<E>,
+    ) -> Result<Vec<PartialSignature<E>>, BoxedError>;
+}
+
+impl<E: Curve, X: PartialSignaturesExchange<E> + ?Sized> PartialSignaturesExchange<E> for &X {
+    fn exchange(
+        &self,
+        data_to_sign: DataToSign<E>,
+        own: &PartialSignature<E>,
+    ) -> Result<Vec<PartialSignature<E>>, BoxedError> {
+        (**self).exchange(data_to_sign, own)
+    }
+}
+
+/// Coordinator that produces signatures using presignatures from the store
+///
+/// Each signature consumes one presignature from the store.
+pub struct PresignaturePool<S, X> {
+    store: S,
+    exchange: X,
+}
+
+impl<S, X> PresignaturePool<S, X> {
+    /// Constructs a pool
+    pub fn new(store: S, exchange: X) -> Self {
+        Self { store, exchange }
+    }
+
+    /// Returns the store of presignatures
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+}
+
+impl<E, S, X> SignatureCoordinator<E> for PresignaturePool<S, X>
+where
+    E: Curve,
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+    S: PresignatureStore<E>,
+    X: PartialSignaturesExchange<E>,
+{
+    fn sign(&self, data_to_sign: DataToSign<E>) -> Result<Signature<E>, BoxedError> {
+        let presignature = self
+            .store
+            .consume_one()
+            .map_err(Reason::Store)?
+            .ok_or(Reason::NoPresignatures)?;
+        let own = presignature
+            .issue_partial_signature(data_to_sign)
+            .map_err(Reason::PresignatureReused)?;
+        let mut partial_signatures = self
+            .exchange
+            .exchange(data_to_sign, &own)
+            .map_err(Reason::Exchange)?;
+        partial_signatures.push(own);
+        Ok(PartialSignature::combine(&partial_signatures).ok_or(Reason::Combine)?)
+    }
+}
+
+/// Signer backed by MPC
+///
+/// Implements [`Signer`](signature::Signer) that hashes the message with digest `D`,
+/// and [`DigestSigner`](signature::DigestSigner).
+pub struct MpcSigner<E: Curve, C, D = crate::default_choice::Digest> {
+    verifying_key: VerifyingKey<E, D>,
+    coordinator: C,
+}
+
+impl<E: Curve, C> MpcSigner<E, C> {
+    /// Constructs a signer
+    ///
+    /// `public_key` is a public key shared by all signers, e.g. [`shared_public_key`](crate::key_share::CoreKeyShare::shared_public_key)
+    pub fn new(public_key: Point<E>, coordinator: C) -> Self {
+        Self {
+            verifying_key: VerifyingKey::new(public_key),
+            coordinator,
+        }
+    }
+}
+
+impl<E: Curve, C, D> MpcSigner<E, C, D> {
+    /// Specifies digest used to hash the message
+    pub fn set_digest<D2: Digest>(self) -> MpcSigner<E, C, D2> {
+        MpcSigner {
+            verifying_key: self.verifying_key.set_digest(),
+            coordinator: self.coordinator,
+        }
+    }
+
+    /// Returns verifying key
+    pub fn verifying_key(&self) -> &VerifyingKey<E, D> {
+        &self.verifying_key
+    }
+
+    /// Returns signature coordinator
+    pub fn coordinator(&self) -> &C {
+        &self.coordinator
+    }
+
+    fn sign_data(&self, data_to_sign: DataToSign<E>) -> Result<Signature<E>, signature::Error>
+    where
+        NonZero<Point<E>>: AlwaysHasAffineX<E>,
+        C: SignatureCoordinator<E>,
+    {
+        let sig = self
+            .coordinator
+            .sign(data_to_sign)
+            .map_err(|err| signature::Error::from_source(Reason::Coordinator(err)))?;
+        sig.verify(&self.verifying_key.public_key, &data_to_sign)
+            .map_err(|_| signature::Error::from_source(Reason::InvalidSignature))?;
+        Ok(sig)
+    }
+}
+
+impl<E, C, D> signature::Signer<Signature<E>> for MpcSigner<E, C, D>
+where
+    E: Curve,
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+    C: SignatureCoordinator<E>,
+    D: Digest,
+{
+    fn try_sign(&self, msg: &[u8]) -> Result<Signature<E>, signature::Error> {
+        self.sign_data(DataToSign::digest::<D>(msg))
+    }
+}
+
+impl<E, C, D, D2> signature::DigestSigner<D2, Signature<E>> for MpcSigner<E, C, D>
+where
+    E: Curve,
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+    C: SignatureCoordinator<E>,
+    D2: Digest,
+{
+    fn try_sign_digest(&self, digest: D2) -> Result<Signature<E>, signature::Error> {
+        self.sign_data(DataToSign::from_digest(digest))
+    }
+}
+
+impl<E: Curve, C, D> signature::Keypair for MpcSigner<E, C, D> {
+    type VerifyingKey = VerifyingKey<E, D>;
+
+    fn verifying_key(&self) -> Self::VerifyingKey {
+        self.verifying_key.clone()
+    }
+}
+
+/// Public key that verifies signatures
+///
+/// Implements [`Verifier`](signature::Verifier) that hashes the message with digest `D`,
+/// and [`DigestVerifier`](signature::DigestVerifier).
+pub struct VerifyingKey<E: Curve, D = crate::default_choice::Digest> {
+    public_key: Point<E>,
+    _digest: PhantomData<fn() -> D>,
+}
+
+impl<E: Curve> VerifyingKey<E> {
+    /// Constructs a verifying key
+    pub fn new(public_key: Point<E>) -> Self {
+        Self {
+            public_key,
+            _digest: PhantomData,
+        }
+    }
+}
+
+impl<E: Curve, D> VerifyingKey<E, D> {
+    /// Specifies digest used to hash the message
+    pub fn set_digest<D2: Digest>(self) -> VerifyingKey<E, D2> {
+        VerifyingKey {
+            public_key: self.public_key,
+            _digest: PhantomData,
+        }
+    }
+
+    /// Returns public key
+    pub fn public_key(&self) -> Point<E> {
+        self.public_key
+    }
+}
+
+impl<E: Curve, D> Clone for VerifyingKey<E, D> {
+    fn clone(&self) -> Self {
+        Self {
+            public_key: self.public_key,
+            _digest: PhantomData,
+        }
+    }
+}
+
+impl<E: Curve, D> std::fmt::Debug for VerifyingKey<E, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VerifyingKey")
+            .field("public_key", &self.public_key)
+            .finish()
+    }
+}
+
+impl<E, D> signature::Verifier<Signature<E>> for VerifyingKey<E, D>
+where
+    E: Curve,
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+    D: Digest,
+{
+    fn verify(&self, msg: &[u8], signature: &Signature<E>) -> Result<(), signature::Error> {
+        signature
+            .verify(&self.public_key, &DataToSign::digest::<D>(msg))
+            .map_err(signature::Error::from_source)
+    }
+}
+
+impl<E, D, D2> signature::DigestVerifier<D2, Signature<E>> for VerifyingKey<E, D>
+where
+    E: Curve,
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+    D2: Digest,
+{
+    fn verify_digest(&self, digest: D2, signature: &Signature<E>) -> Result<(), signature::Error> {
+        signature
+            .verify(&self.public_key, &DataToSign::from_digest(digest))
+            .map_err(signature::Error::from_source)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("no presignatures left in the store")]
+    NoPresignatures,
+    #[error("presignature store failed")]
+    Store(#[source] PresignatureStoreError),
+    #[error(transparent)]
+    PresignatureReused(PresignatureReused),
+    #[error("exchange partial signatures")]
+    Exchange(#[source] BoxedError),
+    #[error("couldn't combine partial signatures")]
+    Combine,
+    #[error("signature coordinator failed")]
+    Coordinator(#[source] BoxedError),
+    #[error("resulting signature is not valid")]
+    InvalidSignature,
+}
+
+#[cfg(test)]
+mod test {
+    use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+    use signature::{Signer, Verifier};
+
+    use super::{BoxedError, MpcSigner, PartialSignaturesExchange, PresignaturePool};
+    use crate::signing::presignature_store::{InMemoryPresignatureStore, PresignatureStore};
+    use crate::signing::{DataToSign, PartialSignature, Presignature};
+
+    /// Simulates remote signer holding presignatures in its own store
+    struct RemoteSigner<E: Curve>(InMemoryPresignatureStore<E>);
+
+    impl<E: Curve> PartialSignaturesExchange<E> for RemoteSigner<E>
+    where
+        NonZero<Point<E>>: generic_ec::coords::AlwaysHasAffineX<E>,
+    {
+        fn exchange(
+            &self,
+            data_to_sign: DataToSign<E>,
+            _own: &PartialSignature<E>,
+        ) -> Result<Vec<PartialSignature<E>>, BoxedError> {
+            let presig = self.0.consume_one()?.ok_or("no presignatures")?;
+            Ok(vec![presig.issue_partial_signature(data_to_sign)?])
+        }
+    }
+
+    /// Generates additive sharing of presignature between two signers
+    fn presignatures<E: Curve>(
+        rng: &mut rand_dev::DevRng,
+        x: &Scalar<E>,
+    ) -> (Presignature<E>, Presignature<E>) {
+        let k = NonZero::<Scalar<E>>::random(rng);
+        let R = NonZero::from_point(Point::generator() * *k.invert()).unwrap();
+        let mut k1 = Scalar::random(rng);
+        let mut chi1 = Scalar::random(rng);
+        let mut k2 = *k - k1;
+        let mut chi2 = *k * x - chi1;
+        (
+            Presignature::from_parts(R, SecretScalar::new(&mut k1), SecretScalar::new(&mut chi1)),
+            Presignature::from_parts(R, SecretScalar::new(&mut k2), SecretScalar::new(&mut chi2)),
+        )
+    }
+
+    fn sign_and_verify<E: Curve>()
+    where
+        NonZero<Point<E>>: generic_ec::coords::AlwaysHasAffineX<E>,
+    {
+        let mut rng = rand_dev::DevRng::new();
+        let x = Scalar::<E>::random(&mut rng);
+        let public_key = Point::generator() * x;
+
+        let local = InMemoryPresignatureStore::new();
+        let remote = RemoteSigner(InMemoryPresignatureStore::new());
+        let (p1, p2) = presignatures(&mut rng, &x);
+        local.put(p1).unwrap();
+        remote.0.put(p2).unwrap();
+
+        let signer: MpcSigner<E, _> =
+            MpcSigner::new(public_key, PresignaturePool::new(&local, &remote));
+        let sig = signer.try_sign(b"message").expect("signing failed");
+        signer
+            .verifying_key()
+            .verify(b"message", &sig)
+            .expect("signature is not valid");
+        assert!(signer
+            .verifying_key()
+            .verify(b"other message", &sig)
+            .is_err());
+
+        // Presignatures are exhausted
+        assert!(signer.try_sign(b"message").is_err());
+    }
+
+    #[cfg(feature = "curve-secp256k1")]
+    #[test]
+    fn sign_and_verify_secp256k1() {
+        sign_and_verify::<crate::supported_curves::Secp256k1>()
+    }
+    #[cfg(feature = "curve-secp256r1")]
+    #[test]
+    fn sign_and_verify_secp256r1() {
+        sign_and_verify::<crate::supported_curves::Secp256r1>()
+    }
+    #[cfg(feature = "curve-stark")]
+    #[test]
+    fn sign_and_verify_stark() {
+        sign_and_verify::<crate::supported_curves::Stark>()
+    }
+}
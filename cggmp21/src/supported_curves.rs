@@ -6,6 +6,26 @@
 //! unexpected consequences: for instance, [default security level](crate::security_level::SecurityLevel128)
 //! might not be compatible with another curve, which might result into unexpected runtime error or
 //! reduced security of the protocol.
+//!
+//! ## Security level
+//! [`SecurityLevel128`](crate::security_level::SecurityLevel128) is designed for curves with
+//! 256 bits order: $\ell = 256$ must be at least bit size of the curve order, otherwise ZK proofs
+//! produced by honest parties don't verify. [`Secp384r1`] must be used with
+//! [`SecurityLevel128Wide`](crate::security_level::SecurityLevel128Wide). Note that P-384 itself
+//! provides 192 bits of security, but security of the protocol is bounded by the size of Paillier
+//! keys, so the protocol provides 128 bits of security with either of curves.
+//!
+//! ## Hashing to scalars
+//! Curves aren't required to implement [`FromHash`](generic_ec::hash_to_curve::FromHash) or any
+//! other hash-to-field primitive. Fiat-Shamir challenges are sampled by seeding a hash-based RNG
+//! with the transcript hash and calling [`Scalar::random`](generic_ec::Scalar::random), and the
+//! remaining hashes (e.g. [`DataToSign::digest`](crate::DataToSign::digest)) are reduced via
+//! [`Scalar::from_be_bytes_mod_order`](generic_ec::Scalar::from_be_bytes_mod_order). Both are
+//! defined generically for every [`Curve`], so a new curve only needs affine $x$ coordinate on
+//! top of the [`Curve`] implementation, as done for [`Secp384r1`].
+
+#[cfg(feature = "curve-secp384r1")]
+mod secp384r1;
 
 #[cfg(feature = "curve-secp256k1")]
 pub use generic_ec::curves::Secp256k1;
@@ -13,6 +33,8 @@ pub use generic_ec::curves::Secp256k1;
 pub use generic_ec::curves::Secp256r1;
 #[cfg(feature = "curve-stark")]
 pub use generic_ec::curves::Stark;
+#[cfg(feature = "curve-secp384r1")]
+pub use secp384r1::Secp384r1;
 
 pub use generic_ec::Curve;
 
@@ -32,6 +54,8 @@ mod check_compatibility {
         curve_is_compatible::<super::Secp256k1>();
         #[cfg(feature = "curve-secp256r1")]
         curve_is_compatible::<super::Secp256r1>();
+        #[cfg(feature = "curve-secp384r1")]
+        curve_is_compatible::<super::Secp384r1>();
         #[cfg(feature = "curve-stark")]
         curve_is_compatible::<super::Stark>();
     }
@@ -0,0 +1,131 @@
+//! secp384r1 curve, also known as NIST P-384
+//!
+//! [`generic_ec`] doesn't provide P-384 out of the box, so the curve is defined here on top of
+//! [`p384`] crate. Point and scalar arithmetic is reused from [`generic_ec_curves`], we only
+//! need to provide curve definition and access to affine coordinates.
+
+use generic_ec::core::{
+    coords::{HasAffineX, HasAffineXAndParity, HasAffineXY, HasAffineY, Parity},
+    CompressedEncoding, Curve, IntegerEncoding, UncompressedEncoding,
+};
+use generic_ec_curves::rust_crypto::{RustCryptoPoint, RustCryptoScalar};
+use p384::elliptic_curve::sec1::{Coordinates, FromEncodedPoint, ToEncodedPoint};
+use p384::{AffinePoint, EncodedPoint, FieldBytes, NistP384};
+
+/// secp384r1 curve, also known as NIST P-384
+///
+/// Based on [`p384`] crate. Order of the curve is 384 bits, so it must be used with
+/// [`SecurityLevel128Wide`](crate::security_level::SecurityLevel128Wide) or another security
+/// level with $\ell \ge 384$.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Secp384r1 {
+    _private: (),
+}
+
+impl Curve for Secp384r1 {
+    const CURVE_NAME: &'static str = "secp384r1";
+
+    type Point = RustCryptoPoint<NistP384>;
+    type Scalar = RustCryptoScalar<NistP384>;
+
+    type CompressedPointArray = <Self::Point as CompressedEncoding>::Bytes;
+    type UncompressedPointArray = <Self::Point as UncompressedEncoding>::Bytes;
+
+    type ScalarArray = <Self::Scalar as IntegerEncoding>::Bytes;
+
+    type CoordinateArray = FieldBytes;
+}
+
+impl HasAffineX for Secp384r1 {
+    fn x(point: &Self::Point) -> Option<Self::CoordinateArray> {
+        match AffinePoint::from(point.0)
+            .to_encoded_point(false)
+            .coordinates()
+        {
+            Coordinates::Uncompressed { x, .. } => Some(*x),
+            _ => None,
+        }
+    }
+}
+
+impl HasAffineXAndParity for Secp384r1 {
+    fn x_and_parity(point: &Self::Point) -> Option<(Self::CoordinateArray, Parity)> {
+        match AffinePoint::from(point.0)
+            .to_encoded_point(true)
+            .coordinates()
+        {
+            Coordinates::Compressed { x, y_is_odd } => {
+                Some((*x, if y_is_odd { Parity::Odd } else { Parity::Even }))
+            }
+            _ => None,
+        }
+    }
+
+    fn from_x_and_parity(x: &Self::CoordinateArray, y_parity: Parity) -> Option<Self::Point> {
+        let tag = match y_parity {
+            Parity::Even => 0x02,
+            Parity::Odd => 0x03,
+        };
+        let mut encoding = [0u8; 49];
+        encoding[0] = tag;
+        encoding[1..].copy_from_slice(x);
+
+        let encoded_point = EncodedPoint::from_bytes(encoding).ok()?;
+        Option::from(AffinePoint::from_encoded_point(&encoded_point))
+            .map(|point: AffinePoint| RustCryptoPoint(point.into()))
+    }
+}
+
+impl HasAffineY for Secp384r1 {
+    fn y(point: &Self::Point) -> Option<Self::CoordinateArray> {
+        match AffinePoint::from(point.0)
+            .to_encoded_point(false)
+            .coordinates()
+        {
+            Coordinates::Uncompressed { y, .. } => Some(*y),
+            _ => None,
+        }
+    }
+}
+
+impl HasAffineXY for Secp384r1 {
+    fn x_and_y(point: &Self::Point) -> Option<(Self::CoordinateArray, Self::CoordinateArray)> {
+        match AffinePoint::from(point.0)
+            .to_encoded_point(false)
+            .coordinates()
+        {
+            Coordinates::Uncompressed { x, y } => Some((*x, *y)),
+            _ => None,
+        }
+    }
+
+    fn from_x_and_y(x: &Self::CoordinateArray, y: &Self::CoordinateArray) -> Option<Self::Point> {
+        let encoded_point = EncodedPoint::from_affine_coordinates(x, y, false);
+        Option::from(AffinePoint::from_encoded_point(&encoded_point))
+            .map(|point: AffinePoint| RustCryptoPoint(point.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use generic_ec::{coords::HasAffineXAndParity, Point, Scalar};
+
+    use super::Secp384r1;
+
+    #[test]
+    fn generator_matches_p384() {
+        let g = Point::<Secp384r1>::generator().to_point();
+        let expected = p384::EncodedPoint::from(p384::AffinePoint::GENERATOR);
+        assert_eq!(g.to_bytes(true).as_bytes(), expected.compress().as_bytes());
+    }
+
+    #[test]
+    fn x_and_parity_roundtrip() {
+        let mut rng = rand_dev::DevRng::new();
+        for _ in 0..10 {
+            let point = Point::generator() * Scalar::<Secp384r1>::random(&mut rng);
+            let (x, parity) = point.x_and_parity().unwrap();
+            assert_eq!(Point::from_x_and_parity(&x, parity).unwrap(), point);
+        }
+    }
+}
@@ -0,0 +1,874 @@
+//! Ready-made network transports
+//!
+//! Protocols of this crate only need a stream of incoming and a sink of outgoing messages (see
+//! [`round_based::Delivery`]), but a transport that works reliably in production is easy to get
+//! wrong. [`Network`] connects local party to all other parties and provides [`MpcParty`] that
+//! can be passed to any protocol. It takes care of:
+//! * Connecting each pair of parties exactly once: party dials parties with lower index and
+//!   accepts connections from parties with higher index
+//! * Framing messages, and rejecting frames larger than a limit (by default, [`MessageSizeLimit`]
+//!   for the amount of parties, see [`default_max_frame_size`])
+//! * Re-establishing lost connections: messages which weren't received by other party are sent
+//!   again, and duplicates are dropped, so protocol sees every message exactly once
+//! * Running several protocols over the same connections one after another: every message is
+//!   tagged with a _phase_ (e.g. `"keygen"`), and [`Network::party`] returns a party which only
+//!   sends and receives messages of one phase. Messages of the phase that's not started yet (e.g.
+//!   when other party completed the previous protocol faster) are kept until it starts.
+//!
+//! Connections are established via [`Link`]. Following links are available:
+//! * [`tcp::TcpLink`], requires `transport-tcp` feature. Connections can be secured with TLS,
+//!   requires `transport-tls` feature
+//! * [`websocket::WebSocketLink`], requires `transport-websocket` feature
+//! * [`quic::QuicLink`], requires `transport-quic` feature
+//!
+//! Messages are serialized to JSON. Network is driven by tasks spawned on [tokio] runtime.
+//!
+//! ## Authentication
+//! Protocols require authenticated channels between the parties. When TLS (or QUIC) is used with
+//! client authentication, links identify the remote party by its certificate: certificate must be
+//! issued for the name of exactly one party (the same names that are used to verify certificates
+//! of the servers). Network then drops connections in which remote party claims index of another
+//! party, and only lets the same authenticated party re-establish a lost connection.
+//!
+//! Connections that are not authenticated by the link (e.g. plain TCP) are refused by default.
+//! Such links can only be used in trusted networks, as the index that remote party claims in the
+//! handshake is trusted as is. Use [`Config::insecure_unauthenticated`] to opt in.
+
+use std::collections::HashMap;
+use std::io;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::channel::{mpsc, oneshot};
+use futures::future::{self, BoxFuture};
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use round_based::{
+    Delivery, Incoming, MessageDestination, MessageType, MpcParty, Outgoing, PartyIndex,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::task::JoinHandle;
+
+use crate::limits::MessageSizeLimit;
+
+#[cfg(feature = "transport-quic")]
+pub mod quic;
+#[cfg(feature = "transport-tcp")]
+pub mod tcp;
+#[cfg(feature = "transport-tls")]
+pub mod tls;
+#[cfg(feature = "transport-websocket")]
+pub mod websocket;
+
+/// Default limit on size of a single frame when `n` parties are connected
+///
+/// Every frame carries at most one protocol message, so the limit is [`MessageSizeLimit`] of
+/// `n` parties at the default security level. Set larger limit on the link if protocols are
+/// executed at higher security level.
+pub fn default_max_frame_size(n: usize) -> usize {
+    let n = u16::try_from(n).unwrap_or(u16::MAX);
+    MessageSizeLimit::new::<crate::default_choice::SecurityLevel>(n).max_size()
+}
+
+/// Sends frames to the remote party
+pub type FrameSink = Pin<Box<dyn Sink<Vec<u8>, Error = io::Error> + Send>>;
+/// Receives frames from the remote party
+pub type FrameStream = Pin<Box<dyn Stream<Item = io::Result<Vec<u8>>> + Send>>;
+
+/// Connection with the remote party
+///
+/// Frames must be delivered in the order they were sent.
+pub struct Channel {
+    /// Outgoing frames
+    pub sink: FrameSink,
+    /// Incoming frames
+    pub stream: FrameStream,
+    /// Index of the remote party as authenticated by the link
+    ///
+    /// `None` if link doesn't authenticate parties. Such connections are refused unless
+    /// [`Config::require_authentication`] is disabled, in which case the index that remote party
+    /// claims in the handshake is trusted.
+    pub peer: Option<PartyIndex>,
+}
+
+impl Channel {
+    /// Frames the byte stream: each frame is prefixed with its length
+    ///
+    /// Received frames larger than `max_frame_size` are rejected.
+    pub fn length_delimited<S>(stream: S, max_frame_size: usize) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let (reader, writer) = tokio::io::split(stream);
+        Self::length_delimited_split(reader, writer, max_frame_size)
+    }
+
+    /// Frames the byte stream given as separate read and write halves
+    ///
+    /// Same as [`Channel::length_delimited`]
+    pub fn length_delimited_split<R, W>(reader: R, writer: W, max_frame_size: usize) -> Self
+    where
+        R: AsyncRead + Send + 'static,
+        W: AsyncWrite + Send + 'static,
+    {
+        let codec = || {
+            tokio_util::codec::LengthDelimitedCodec::builder()
+                .max_frame_length(max_frame_size)
+                .new_codec()
+        };
+        let sink = tokio_util::codec::FramedWrite::new(writer, codec())
+            .with(|frame: Vec<u8>| future::ready(Ok::<_, io::Error>(bytes::Bytes::from(frame))));
+        let stream = tokio_util::codec::FramedRead::new(reader, codec())
+            .map(|frame| frame.map(|frame| frame.to_vec()));
+        Self {
+            sink: Box::pin(sink),
+            stream: Box::pin(stream),
+            peer: None,
+        }
+    }
+
+    /// Marks the channel as authenticated to be connected with party `j`
+    pub fn authenticated(mut self, j: PartyIndex) -> Self {
+        self.peer = Some(j);
+        self
+    }
+}
+
+/// Finds the party that the certificate is issued for
+///
+/// `names[j]` is the name of $j$-th party. Certificate chain must be already verified. Returns
+/// error if certificate is not valid for exactly one party.
+#[cfg(any(feature = "transport-tls", feature = "transport-quic"))]
+fn party_of_certificate<'a>(
+    certificate: &[u8],
+    names: impl IntoIterator<Item = &'a str>,
+) -> io::Result<PartyIndex> {
+    let certificate = webpki::EndEntityCert::try_from(certificate).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid certificate: {err:?}"),
+        )
+    })?;
+    let mut parties = (0..)
+        .zip(names)
+        .filter(|(_, name)| {
+            webpki::SubjectNameRef::try_from_ascii_str(name).map_or(false, |name| {
+                certificate.verify_is_valid_for_subject_name(name).is_ok()
+            })
+        })
+        .map(|(j, _)| j);
+    match (parties.next(), parties.next()) {
+        (Some(j), None) => Ok(j),
+        _ => Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "certificate doesn't identify exactly one party",
+        )),
+    }
+}
+
+/// Establishes connections between the parties
+///
+/// Implemented for all the links in this module. Custom link can be implemented for other
+/// transport stacks.
+pub trait Link: Send + Sync + 'static {
+    /// Connects to party `j`
+    fn dial(&self, j: PartyIndex) -> BoxFuture<'_, io::Result<Channel>>;
+    /// Waits for the next connection from any party
+    fn accept(&self) -> BoxFuture<'_, io::Result<Channel>>;
+}
+
+/// Parameters of the [`Network`]
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// How long to wait until all parties are connected
+    pub connect_timeout: Duration,
+    /// How long to try to re-establish lost connection before giving up on the party
+    pub reconnect_timeout: Duration,
+    /// Delay between attempts to dial a party
+    pub retry_delay: Duration,
+    /// Refuses connections that are not [authenticated](Channel::peer) by the link
+    ///
+    /// Enabled by default
+    pub require_authentication: bool,
+}
+
+impl Config {
+    /// Config that accepts connections not authenticated by the link, e.g. plain TCP
+    ///
+    /// Remote party is trusted to be the party whose index it claims in the handshake. Only use it
+    /// in trusted networks, or when parties are authenticated by other means.
+    pub fn insecure_unauthenticated() -> Self {
+        Self {
+            require_authentication: false,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(30),
+            reconnect_timeout: Duration::from_secs(30),
+            retry_delay: Duration::from_millis(200),
+            require_authentication: true,
+        }
+    }
+}
+
+/// Frame as it's sent over the wire
+#[derive(Serialize, Deserialize)]
+enum Frame {
+    /// First frame sent over a new connection by both sides
+    Hello {
+        party: PartyIndex,
+        /// Amount of data frames received from the other side so far
+        received: u64,
+    },
+    Data {
+        seq: u64,
+        phase: String,
+        broadcast: bool,
+        msg: serde_json::Value,
+    },
+}
+
+impl Frame {
+    fn encode(&self) -> io::Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(io::Error::from)
+    }
+
+    fn decode(bytes: &[u8]) -> io::Result<Self> {
+        serde_json::from_slice(bytes).map_err(io::Error::from)
+    }
+}
+
+/// Message to be sent to one party
+#[derive(Clone)]
+struct Outbound {
+    phase: Arc<str>,
+    broadcast: bool,
+    msg: Arc<serde_json::Value>,
+}
+
+/// Parties connected to each other
+pub struct Network {
+    i: PartyIndex,
+    n: u16,
+    /// `peers[j]` sends messages to $j$-th party, `None` for local party
+    peers: Vec<Option<mpsc::UnboundedSender<Outbound>>>,
+    router: Arc<Router>,
+    tasks: Vec<JoinHandle<()>>,
+    acceptor: Option<JoinHandle<()>>,
+}
+
+impl Network {
+    /// Connects local party `i` to all other `n - 1` parties
+    ///
+    /// Waits until all parties are connected, which may take up to
+    /// [`connect_timeout`](Config::connect_timeout). Must be called within tokio runtime.
+    pub async fn connect(
+        link: impl Link,
+        i: PartyIndex,
+        n: u16,
+        config: Config,
+    ) -> io::Result<Self> {
+        if i >= n {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("party index {i} is out of bounds (must be < n = {n})"),
+            ));
+        }
+
+        let link: Arc<dyn Link> = Arc::new(link);
+        let router = Arc::new(Router::default());
+        let mut network = Self {
+            i,
+            n,
+            peers: vec![],
+            router: router.clone(),
+            tasks: vec![],
+            acceptor: None,
+        };
+        let mut accepted = vec![];
+        let mut ready = vec![];
+        for j in 0..n {
+            if j == i {
+                network.peers.push(None);
+                accepted.push(None);
+                continue;
+            }
+            let (outbox_sender, outbox) = mpsc::unbounded();
+            network.peers.push(Some(outbox_sender));
+
+            // Parties with higher index dial us, we dial parties with lower index
+            let accepted_channels = if j > i {
+                let (sender, receiver) = mpsc::unbounded();
+                accepted.push(Some(sender));
+                Some(receiver)
+            } else {
+                accepted.push(None);
+                None
+            };
+
+            let (ready_sender, ready_receiver) = oneshot::channel();
+            ready.push(ready_receiver);
+            let peer = Peer {
+                i,
+                j,
+                link: link.clone(),
+                router: router.clone(),
+                config: config.clone(),
+                sent: vec![],
+                received: 0,
+                authenticated: false,
+            };
+            network.tasks.push(tokio::spawn(peer.run(
+                outbox,
+                accepted_channels,
+                ready_sender,
+            )));
+        }
+        if i + 1 < n {
+            network.acceptor = Some(tokio::spawn(accept(link, accepted, config)));
+        }
+
+        for ready in ready {
+            ready.await.map_err(|_| {
+                io::Error::new(io::ErrorKind::Other, "connection task has panicked")
+            })??;
+        }
+        Ok(network)
+    }
+
+    /// Index of local party
+    pub fn i(&self) -> PartyIndex {
+        self.i
+    }
+
+    /// Amount of parties
+    pub fn n(&self) -> u16 {
+        self.n
+    }
+
+    /// Returns party which sends and receives messages of the `phase`
+    ///
+    /// Each phase can be started only once.
+    pub fn party<M>(&self, phase: &str) -> io::Result<MpcParty<M, impl Delivery<M>>>
+    where
+        M: Serialize + DeserializeOwned + Send + 'static,
+    {
+        let incomings = self.router.subscribe(phase).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("phase `{phase}` was already started"),
+            )
+        })?;
+        let incomings = incomings.map(|incoming| {
+            incoming.and_then(|incoming| {
+                incoming
+                    .try_map(serde_json::from_value)
+                    .map_err(io::Error::from)
+            })
+        });
+        let outgoings = Outgoings {
+            phase: phase.into(),
+            peers: self.peers.clone(),
+            _msg: PhantomData,
+        };
+        Ok(MpcParty::connected((incomings, outgoings)))
+    }
+
+    /// Sends all queued messages and closes connections
+    pub async fn shutdown(mut self) {
+        for peer in self.peers.iter().flatten() {
+            peer.close_channel();
+        }
+        for task in std::mem::take(&mut self.tasks) {
+            let _ = task.await;
+        }
+    }
+}
+
+impl Drop for Network {
+    fn drop(&mut self) {
+        for task in self.tasks.iter().chain(&self.acceptor) {
+            task.abort();
+        }
+    }
+}
+
+/// Accepts connections from parties with higher index and passes them to the corresponding
+/// [`Peer`] along with amount of frames that party has received
+async fn accept(
+    link: Arc<dyn Link>,
+    peers: Vec<Option<mpsc::UnboundedSender<(Channel, u64)>>>,
+    config: Config,
+) {
+    loop {
+        let mut channel = match link.accept().await {
+            Ok(channel) => channel,
+            Err(_) => {
+                tokio::time::sleep(config.retry_delay).await;
+                continue;
+            }
+        };
+        let hello = tokio::time::timeout(config.connect_timeout, channel.stream.next()).await;
+        let Ok(Some(Ok(hello))) = hello else {
+            continue;
+        };
+        let Ok(Frame::Hello { party, received }) = Frame::decode(&hello) else {
+            continue;
+        };
+        // Authenticated party must not claim index of another party
+        match channel.peer {
+            Some(peer) if peer != party => continue,
+            None if config.require_authentication => continue,
+            _ => {}
+        }
+        // Connections from unexpected parties (e.g. a leftover process from the previous run)
+        // are dropped
+        if let Some(Some(peer)) = peers.get(usize::from(party)) {
+            let _ = peer.unbounded_send((channel, received));
+        }
+    }
+}
+
+/// Reason why [`Peer::serve`] returned
+enum Interrupted {
+    /// Network is shut down
+    Shutdown,
+    /// Connection is lost
+    Lost,
+    /// Party has connected again, carries new channel and amount of frames received by the party
+    Reconnected(Channel, u64),
+}
+
+/// Maintains connection with party `j`
+struct Peer {
+    i: PartyIndex,
+    j: PartyIndex,
+    link: Arc<dyn Link>,
+    router: Arc<Router>,
+    config: Config,
+    /// Frames sent to party `j`, kept to be sent again if connection is lost
+    sent: Vec<Vec<u8>>,
+    /// Amount of frames received from party `j`
+    received: u64,
+    /// Whether connection with party `j` was authenticated by the link
+    ///
+    /// Once it's set, only authenticated party `j` can re-establish the connection.
+    authenticated: bool,
+}
+
+impl Peer {
+    /// Drives the connection with party `j`
+    ///
+    /// `accepted` receives connections accepted from party `j`, it's `None` if local party
+    /// dials `j`. `ready` is notified once connection is established for the first time.
+    async fn run(
+        mut self,
+        mut outbox: mpsc::UnboundedReceiver<Outbound>,
+        mut accepted: Option<mpsc::UnboundedReceiver<(Channel, u64)>>,
+        ready: oneshot::Sender<io::Result<()>>,
+    ) {
+        let connected = self
+            .connect(&mut accepted, self.config.connect_timeout)
+            .await;
+        let mut channel = match connected {
+            Ok(channel) => {
+                let _ = ready.send(Ok(()));
+                self.authenticated = channel.peer.is_some();
+                channel
+            }
+            Err(err) => {
+                let _ = ready.send(Err(io::Error::new(err.kind(), err.to_string())));
+                self.router.fail(self.j, err);
+                return;
+            }
+        };
+
+        loop {
+            let resumed = match self.serve(&mut channel, &mut outbox, &mut accepted).await {
+                Interrupted::Shutdown => {
+                    let _ = channel.sink.close().await;
+                    return;
+                }
+                Interrupted::Lost => None,
+                Interrupted::Reconnected(new_channel, received) => Some((new_channel, received)),
+            };
+            match self.reconnect(&mut outbox, &mut accepted, resumed).await {
+                Some(Ok(new_channel)) => channel = new_channel,
+                Some(Err(err)) => {
+                    self.router.fail(self.j, err);
+                    return;
+                }
+                None => return,
+            }
+        }
+    }
+
+    /// Re-establishes connection with party `j`
+    ///
+    /// `resumed` is a connection that party `j` has already established, if any. Messages sent
+    /// in the meantime are queued and sent once connection is re-established. Returns `None` if
+    /// network is shut down before that.
+    async fn reconnect(
+        &mut self,
+        outbox: &mut mpsc::UnboundedReceiver<Outbound>,
+        accepted: &mut Option<mpsc::UnboundedReceiver<(Channel, u64)>>,
+        mut resumed: Option<(Channel, u64)>,
+    ) -> Option<io::Result<Channel>> {
+        let mut queued = vec![];
+        let connected = {
+            let connect = async {
+                if let Some((channel, received)) = resumed.take() {
+                    if let Ok(channel) = self.resume_accepted(channel, received).await {
+                        return Ok(channel);
+                    }
+                }
+                self.connect(accepted, self.config.reconnect_timeout).await
+            };
+            let shutdown = async {
+                while let Some(outbound) = outbox.next().await {
+                    queued.push(outbound)
+                }
+            };
+            tokio::select! {
+                connected = connect => connected,
+                () = shutdown => return None,
+            }
+        };
+        let mut channel = match connected {
+            Ok(channel) => channel,
+            Err(err) => return Some(Err(err)),
+        };
+        for outbound in queued {
+            let Ok(frame) = self.enqueue(outbound) else {
+                continue;
+            };
+            // If connection is lost again, frames will be sent once it's re-established
+            let _ = channel.sink.feed(frame).await;
+        }
+        let _ = channel.sink.flush().await;
+        Some(Ok(channel))
+    }
+
+    /// Exchanges frames with party `j` until connection is interrupted
+    async fn serve(
+        &mut self,
+        channel: &mut Channel,
+        outbox: &mut mpsc::UnboundedReceiver<Outbound>,
+        accepted: &mut Option<mpsc::UnboundedReceiver<(Channel, u64)>>,
+    ) -> Interrupted {
+        loop {
+            let next_accepted = async {
+                match accepted {
+                    Some(accepted) => accepted.next().await,
+                    None => future::pending().await,
+                }
+            };
+            tokio::select! {
+                outbound = outbox.next() => {
+                    let Some(outbound) = outbound else {
+                        return Interrupted::Shutdown;
+                    };
+                    let Ok(frame) = self.enqueue(outbound) else {
+                        continue;
+                    };
+                    if channel.sink.send(frame).await.is_err() {
+                        return Interrupted::Lost;
+                    }
+                }
+                frame = channel.stream.next() => {
+                    match frame {
+                        Some(Ok(frame)) if self.receive(&frame).is_ok() => {}
+                        _ => return Interrupted::Lost,
+                    }
+                }
+                Some((new_channel, received)) = next_accepted => {
+                    if self.trusts(&new_channel) {
+                        return Interrupted::Reconnected(new_channel, received);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Establishes connection with party `j`, retrying until `timeout` elapses
+    async fn connect(
+        &self,
+        accepted: &mut Option<mpsc::UnboundedReceiver<(Channel, u64)>>,
+        timeout: Duration,
+    ) -> io::Result<Channel> {
+        let connect = async {
+            loop {
+                let result = match accepted {
+                    None => self.dial().await,
+                    Some(accepted) => {
+                        let Some((channel, received)) = accepted.next().await else {
+                            // Network is being dropped
+                            return future::pending().await;
+                        };
+                        if !self.trusts(&channel) {
+                            continue;
+                        }
+                        self.resume_accepted(channel, received).await
+                    }
+                };
+                match result {
+                    Ok(channel) => return channel,
+                    Err(_) if accepted.is_none() => {
+                        tokio::time::sleep(self.config.retry_delay).await
+                    }
+                    Err(_) => {}
+                }
+            }
+        };
+        tokio::time::timeout(timeout, connect).await.map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("couldn't connect to party {} within {timeout:?}", self.j),
+            )
+        })
+    }
+
+    /// Checks that connection accepted from party `j` can replace the current one
+    fn trusts(&self, channel: &Channel) -> bool {
+        !self.authenticated || channel.peer == Some(self.j)
+    }
+
+    /// Dials party `j` and performs the handshake
+    async fn dial(&self) -> io::Result<Channel> {
+        let mut channel = self.link.dial(self.j).await?;
+        if !self.trusts(&channel)
+            || (self.config.require_authentication && channel.peer != Some(self.j))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("connection to party {} is not authenticated", self.j),
+            ));
+        }
+        channel.sink.send(self.hello()?).await?;
+        let reply = channel
+            .stream
+            .next()
+            .await
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))??;
+        match Frame::decode(&reply)? {
+            Frame::Hello { party, received } if party == self.j => {
+                self.resend(&mut channel, received).await?;
+                Ok(channel)
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unexpected handshake",
+            )),
+        }
+    }
+
+    /// Completes the handshake on connection accepted from party `j`
+    async fn resume_accepted(&self, mut channel: Channel, received: u64) -> io::Result<Channel> {
+        channel.sink.send(self.hello()?).await?;
+        self.resend(&mut channel, received).await?;
+        Ok(channel)
+    }
+
+    fn hello(&self) -> io::Result<Vec<u8>> {
+        Frame::Hello {
+            party: self.i,
+            received: self.received,
+        }
+        .encode()
+    }
+
+    /// Sends again frames which were not received by party `j`
+    async fn resend(&self, channel: &mut Channel, received: u64) -> io::Result<()> {
+        let frames = usize::try_from(received)
+            .ok()
+            .and_then(|received| self.sent.get(received..))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "party claims to have received more frames than were sent",
+                )
+            })?;
+        for frame in frames {
+            channel.sink.feed(frame.clone()).await?;
+        }
+        channel.sink.flush().await
+    }
+
+    /// Encodes the message and remembers it to be sent again if connection is lost
+    fn enqueue(&mut self, outbound: Outbound) -> io::Result<Vec<u8>> {
+        let frame = Frame::Data {
+            seq: self.sent.len() as u64,
+            phase: outbound.phase.to_string(),
+            broadcast: outbound.broadcast,
+            msg: (*outbound.msg).clone(),
+        }
+        .encode()?;
+        self.sent.push(frame.clone());
+        Ok(frame)
+    }
+
+    fn receive(&mut self, frame: &[u8]) -> io::Result<()> {
+        match Frame::decode(frame)? {
+            // Frame was already received before the connection was re-established
+            Frame::Data { seq, .. } if seq < self.received => Ok(()),
+            Frame::Data {
+                seq,
+                phase,
+                broadcast,
+                msg,
+            } if seq == self.received => {
+                self.received += 1;
+                self.router.dispatch(self.j, phase, broadcast, msg);
+                Ok(())
+            }
+            Frame::Data { .. } => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "frames were received out of order",
+            )),
+            Frame::Hello { .. } => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unexpected handshake",
+            )),
+        }
+    }
+}
+
+type IncomingResult = io::Result<Incoming<serde_json::Value>>;
+
+/// Dispatches received messages to the phases
+#[derive(Default)]
+struct Router {
+    state: Mutex<RouterState>,
+    next_id: AtomicU64,
+}
+
+#[derive(Default)]
+struct RouterState {
+    phases: HashMap<String, Phase>,
+    /// Set once connection with any party is lost for good
+    failure: Option<(io::ErrorKind, String)>,
+}
+
+struct Phase {
+    sender: mpsc::UnboundedSender<IncomingResult>,
+    /// `None` if phase is already started
+    receiver: Option<mpsc::UnboundedReceiver<IncomingResult>>,
+}
+
+impl Router {
+    fn with_phase<R>(&self, phase: &str, f: impl FnOnce(&mut Phase) -> R) -> R {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let RouterState { phases, failure } = &mut *state;
+        let phase = phases.entry(phase.to_owned()).or_insert_with(|| {
+            let (sender, receiver) = mpsc::unbounded();
+            if let Some((kind, err)) = failure {
+                let _ = sender.unbounded_send(Err(io::Error::new(*kind, err.clone())));
+            }
+            Phase {
+                sender,
+                receiver: Some(receiver),
+            }
+        });
+        f(phase)
+    }
+
+    fn dispatch(&self, sender: PartyIndex, phase: String, broadcast: bool, msg: serde_json::Value) {
+        let incoming = Incoming {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            sender,
+            msg_type: if broadcast {
+                MessageType::Broadcast
+            } else {
+                MessageType::P2P
+            },
+            msg,
+        };
+        // Sending fails only if the phase is completed, late messages can be ignored
+        let _ = self.with_phase(&phase, |phase| phase.sender.unbounded_send(Ok(incoming)));
+    }
+
+    fn subscribe(&self, phase: &str) -> Option<mpsc::UnboundedReceiver<IncomingResult>> {
+        self.with_phase(phase, |phase| phase.receiver.take())
+    }
+
+    /// Reports to all phases that connection with party `j` is lost
+    fn fail(&self, j: PartyIndex, err: io::Error) {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let kind = err.kind();
+        let err = format!("lost connection to party {j}: {err}");
+        for phase in state.phases.values() {
+            let _ = phase
+                .sender
+                .unbounded_send(Err(io::Error::new(kind, err.clone())));
+        }
+        state.failure.get_or_insert((kind, err));
+    }
+}
+
+/// Sends messages of one phase
+struct Outgoings<M> {
+    phase: Arc<str>,
+    peers: Vec<Option<mpsc::UnboundedSender<Outbound>>>,
+    _msg: PhantomData<fn(M)>,
+}
+
+impl<M: Serialize> Sink<Outgoing<M>> for Outgoings<M> {
+    type Error = io::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, outgoing: Outgoing<M>) -> Result<(), Self::Error> {
+        let outbound = || {
+            Ok::<_, io::Error>(Outbound {
+                phase: self.phase.clone(),
+                broadcast: outgoing.recipient.is_broadcast(),
+                msg: Arc::new(serde_json::to_value(&outgoing.msg)?),
+            })
+        };
+        let closed = |_| io::Error::new(io::ErrorKind::BrokenPipe, "network is shut down");
+        match outgoing.recipient {
+            MessageDestination::AllParties => {
+                let outbound = outbound()?;
+                for peer in self.peers.iter().flatten() {
+                    peer.unbounded_send(outbound.clone()).map_err(closed)?;
+                }
+            }
+            MessageDestination::OneParty(j) => {
+                let peer = self
+                    .peers
+                    .get(usize::from(j))
+                    .and_then(Option::as_ref)
+                    .ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidInput, format!("unknown party {j}"))
+                    })?;
+                peer.unbounded_send(outbound()?).map_err(closed)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
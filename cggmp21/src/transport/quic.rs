@@ -0,0 +1,113 @@
+//! QUIC link
+
+use std::io;
+use std::net::SocketAddr;
+
+use futures::future::BoxFuture;
+use round_based::PartyIndex;
+
+use super::{default_max_frame_size, Channel, Link};
+
+/// Connects parties over QUIC
+///
+/// Every connection carries a single bidirectional stream with length-delimited frames.
+pub struct QuicLink {
+    endpoint: quinn::Endpoint,
+    addrs: Vec<SocketAddr>,
+    server_names: Vec<String>,
+    max_frame_size: usize,
+}
+
+impl QuicLink {
+    /// Constructs a link
+    ///
+    /// `endpoint` must be configured as a server, to accept connections from parties with higher
+    /// index, and must have default client config, which is used to dial parties with lower index.
+    /// `addrs[j]` is address of $j$-th party and `server_names[j]` is the name that its
+    /// certificate is issued for. If client authentication is enabled in the server config,
+    /// accepted connections are identified by client certificates, see
+    /// [module-level docs](super#authentication).
+    pub fn new(
+        endpoint: quinn::Endpoint,
+        addrs: Vec<SocketAddr>,
+        server_names: Vec<String>,
+    ) -> Self {
+        let max_frame_size = default_max_frame_size(addrs.len());
+        Self {
+            endpoint,
+            addrs,
+            server_names,
+            max_frame_size,
+        }
+    }
+
+    /// Sets limit on size of received frames
+    ///
+    /// [`default_max_frame_size`] for the amount of parties is used by default.
+    pub fn set_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+}
+
+impl QuicLink {
+    /// Identifies the party by the certificate it presented, if any
+    fn authenticate(&self, connection: &quinn::Connection) -> io::Result<Option<PartyIndex>> {
+        let Some(identity) = connection.peer_identity() else {
+            return Ok(None);
+        };
+        let certificates = identity
+            .downcast::<Vec<rustls::Certificate>>()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "unsupported peer identity"))?;
+        match certificates.first() {
+            Some(certificate) => super::party_of_certificate(
+                &certificate.0,
+                self.server_names.iter().map(String::as_str),
+            )
+            .map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Link for QuicLink {
+    fn dial(&self, j: PartyIndex) -> BoxFuture<'_, io::Result<Channel>> {
+        Box::pin(async move {
+            let unknown_party =
+                || io::Error::new(io::ErrorKind::InvalidInput, format!("unknown party {j}"));
+            let addr = *self.addrs.get(usize::from(j)).ok_or_else(unknown_party)?;
+            let server_name = self
+                .server_names
+                .get(usize::from(j))
+                .ok_or_else(unknown_party)?;
+            let connection = self
+                .endpoint
+                .connect(addr, server_name)
+                .map_err(quic_error)?
+                .await
+                .map_err(quic_error)?;
+            let (send, recv) = connection.open_bi().await.map_err(quic_error)?;
+            // Server is authenticated by its certificate
+            Ok(Channel::length_delimited_split(recv, send, self.max_frame_size).authenticated(j))
+        })
+    }
+
+    fn accept(&self) -> BoxFuture<'_, io::Result<Channel>> {
+        Box::pin(async move {
+            let connecting =
+                self.endpoint.accept().await.ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::NotConnected, "endpoint is closed")
+                })?;
+            let connection = connecting.await.map_err(quic_error)?;
+            let peer = self.authenticate(&connection)?;
+            let (send, recv) = connection.accept_bi().await.map_err(quic_error)?;
+            let mut channel = Channel::length_delimited_split(recv, send, self.max_frame_size);
+            channel.peer = peer;
+            Ok(channel)
+        })
+    }
+}
+
+fn quic_error(err: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
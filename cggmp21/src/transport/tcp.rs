@@ -0,0 +1,112 @@
+//! TCP link
+//!
+//! ## Example
+//! ```rust,no_run
+//! # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+//! use cggmp21::transport::{tcp::TcpLink, Config, Network};
+//!
+//! # let (i, n) = (0, 3);
+//! let addrs: Vec<std::net::SocketAddr> = vec![
+//!     "10.0.0.1:4000".parse()?,
+//!     "10.0.0.2:4000".parse()?,
+//!     "10.0.0.3:4000".parse()?,
+//! ];
+//! let listener = tokio::net::TcpListener::bind(addrs[usize::from(i)]).await?;
+//! // Plain TCP doesn't authenticate parties, so it needs to be explicitly allowed. Secure the link
+//! // with TLS to use the default config
+//! let link = TcpLink::new(listener, addrs);
+//! let network = Network::connect(link, i, n, Config::insecure_unauthenticated()).await?;
+//!
+//! # type Msg = cggmp21::signing::msg::Msg<cggmp21::supported_curves::Secp256k1, sha2::Sha256>;
+//! let party = network.party::<Msg>("signing")?;
+//! // run the protocol
+//! # let _ = party;
+//! network.shutdown().await;
+//! # Ok(()) }
+//! ```
+
+use std::io;
+use std::net::SocketAddr;
+
+use futures::future::BoxFuture;
+use round_based::PartyIndex;
+use tokio::net::{TcpListener, TcpStream};
+
+use super::{default_max_frame_size, Channel, Link};
+
+/// Connects parties over TCP
+///
+/// Frames are length-delimited. Connections can be secured with TLS via
+/// [`with_tls`](Self::with_tls) if `transport-tls` feature is enabled.
+pub struct TcpLink {
+    listener: TcpListener,
+    addrs: Vec<SocketAddr>,
+    max_frame_size: usize,
+    #[cfg(feature = "transport-tls")]
+    tls: Option<super::tls::TlsConfig>,
+}
+
+impl TcpLink {
+    /// Constructs a link
+    ///
+    /// `addrs[j]` is address of $j$-th party, `listener` must be bound to address of local party.
+    pub fn new(listener: TcpListener, addrs: Vec<SocketAddr>) -> Self {
+        let max_frame_size = default_max_frame_size(addrs.len());
+        Self {
+            listener,
+            addrs,
+            max_frame_size,
+            #[cfg(feature = "transport-tls")]
+            tls: None,
+        }
+    }
+
+    /// Sets limit on size of received frames
+    ///
+    /// [`default_max_frame_size`] for the amount of parties is used by default.
+    pub fn set_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Secures connections with TLS
+    #[cfg(feature = "transport-tls")]
+    pub fn with_tls(mut self, tls: super::tls::TlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+}
+
+impl Link for TcpLink {
+    fn dial(&self, j: PartyIndex) -> BoxFuture<'_, io::Result<Channel>> {
+        Box::pin(async move {
+            let addr = *self.addrs.get(usize::from(j)).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, format!("unknown party {j}"))
+            })?;
+            let stream = TcpStream::connect(addr).await?;
+            stream.set_nodelay(true)?;
+            #[cfg(feature = "transport-tls")]
+            if let Some(tls) = &self.tls {
+                // Server is authenticated by its certificate
+                let stream = tls.connect(j, stream).await?;
+                return Ok(Channel::length_delimited(stream, self.max_frame_size).authenticated(j));
+            }
+            Ok(Channel::length_delimited(stream, self.max_frame_size))
+        })
+    }
+
+    fn accept(&self) -> BoxFuture<'_, io::Result<Channel>> {
+        Box::pin(async move {
+            let (stream, _) = self.listener.accept().await?;
+            stream.set_nodelay(true)?;
+            #[cfg(feature = "transport-tls")]
+            if let Some(tls) = &self.tls {
+                let (stream, peer) = tls.accept(stream).await?;
+                let mut channel = Channel::length_delimited(stream, self.max_frame_size);
+                channel.peer = peer;
+                return Ok(channel);
+            }
+            Ok(Channel::length_delimited(stream, self.max_frame_size))
+        })
+    }
+}
@@ -0,0 +1,95 @@
+//! TLS settings
+//!
+//! Used by [`TcpLink`](super::tcp::TcpLink) and, if `transport-websocket` feature is enabled,
+//! by [`WebSocketLink`](super::websocket::WebSocketLink).
+
+use std::io;
+use std::sync::Arc;
+
+use round_based::PartyIndex;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::rustls::{ClientConfig, ServerConfig, ServerName};
+use tokio_rustls::{client, server, TlsAcceptor, TlsConnector};
+
+/// TLS settings
+///
+/// Local party acts as TLS client when it dials parties with lower index, and as TLS server when
+/// it accepts connections from parties with higher index, so both configs are required. Enable
+/// client authentication in `server` config to make sure that only parties of the committee can
+/// connect: certificate presented by the client identifies the party, see
+/// [module-level docs](super#authentication).
+#[derive(Clone)]
+pub struct TlsConfig {
+    connector: TlsConnector,
+    acceptor: TlsAcceptor,
+    server_names: Vec<ServerName>,
+}
+
+impl TlsConfig {
+    /// Constructs TLS settings
+    ///
+    /// `server_names[j]` is the name that certificate of $j$-th party is issued for. It's used both
+    /// to verify the server when dialing party `j`, and to identify party `j` by its client
+    /// certificate when accepting connection from it.
+    pub fn new(
+        client: Arc<ClientConfig>,
+        server: Arc<ServerConfig>,
+        server_names: Vec<ServerName>,
+    ) -> Self {
+        Self {
+            connector: client.into(),
+            acceptor: server.into(),
+            server_names,
+        }
+    }
+
+    pub(super) async fn connect<S>(
+        &self,
+        j: PartyIndex,
+        stream: S,
+    ) -> io::Result<client::TlsStream<S>>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let server_name = self.server_names.get(usize::from(j)).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("server name of party {j} is not specified"),
+            )
+        })?;
+        self.connector.connect(server_name.clone(), stream).await
+    }
+
+    /// Accepts TLS connection
+    ///
+    /// Returns index of the party identified by the client certificate, if client presented one
+    pub(super) async fn accept<S>(
+        &self,
+        stream: S,
+    ) -> io::Result<(server::TlsStream<S>, Option<PartyIndex>)>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let stream = self.acceptor.accept(stream).await?;
+        let peer = match stream.get_ref().1.peer_certificates() {
+            Some([certificate, ..]) => {
+                let names = self
+                    .server_names
+                    .iter()
+                    .map(|name| match name {
+                        ServerName::DnsName(name) => name.as_ref().to_owned(),
+                        ServerName::IpAddress(ip) => ip.to_string(),
+                        // Names of unknown kinds don't match any certificate
+                        _ => String::new(),
+                    })
+                    .collect::<Vec<_>>();
+                Some(super::party_of_certificate(
+                    &certificate.0,
+                    names.iter().map(String::as_str),
+                )?)
+            }
+            _ => None,
+        };
+        Ok((stream, peer))
+    }
+}
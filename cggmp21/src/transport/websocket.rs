@@ -0,0 +1,140 @@
+//! WebSocket link
+
+use std::io;
+use std::net::SocketAddr;
+
+use futures::future::{self, BoxFuture};
+use futures::{SinkExt, StreamExt};
+use round_based::PartyIndex;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::{protocol::WebSocketConfig, Message};
+use tokio_tungstenite::WebSocketStream;
+
+use super::{default_max_frame_size, Channel, Link};
+
+/// Connects parties over WebSocket
+///
+/// Every frame is sent as a binary message. Connections can be secured with TLS (i.e. `wss://`)
+/// via [`with_tls`](Self::with_tls) if `transport-tls` feature is enabled.
+pub struct WebSocketLink {
+    listener: TcpListener,
+    addrs: Vec<SocketAddr>,
+    config: WebSocketConfig,
+    #[cfg(feature = "transport-tls")]
+    tls: Option<super::tls::TlsConfig>,
+}
+
+/// Byte stream that WebSocket runs on top of
+trait ByteStream: AsyncRead + AsyncWrite + Send + Unpin {}
+impl<S: AsyncRead + AsyncWrite + Send + Unpin> ByteStream for S {}
+
+impl WebSocketLink {
+    /// Constructs a link
+    ///
+    /// `addrs[j]` is address of $j$-th party, `listener` must be bound to address of local party.
+    pub fn new(listener: TcpListener, addrs: Vec<SocketAddr>) -> Self {
+        let max_frame_size = default_max_frame_size(addrs.len());
+        Self {
+            listener,
+            addrs,
+            config: WebSocketConfig {
+                max_message_size: Some(max_frame_size),
+                max_frame_size: Some(max_frame_size),
+                ..Default::default()
+            },
+            #[cfg(feature = "transport-tls")]
+            tls: None,
+        }
+    }
+
+    /// Sets limit on size of received frames
+    ///
+    /// [`default_max_frame_size`] for the amount of parties is used by default.
+    pub fn set_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.config.max_message_size = Some(max_frame_size);
+        self.config.max_frame_size = Some(max_frame_size);
+        self
+    }
+
+    /// Secures connections with TLS
+    #[cfg(feature = "transport-tls")]
+    pub fn with_tls(mut self, tls: super::tls::TlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+}
+
+impl Link for WebSocketLink {
+    fn dial(&self, j: PartyIndex) -> BoxFuture<'_, io::Result<Channel>> {
+        Box::pin(async move {
+            let addr = *self.addrs.get(usize::from(j)).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, format!("unknown party {j}"))
+            })?;
+            let stream = TcpStream::connect(addr).await?;
+            stream.set_nodelay(true)?;
+            #[cfg(feature = "transport-tls")]
+            let (stream, scheme): (Box<dyn ByteStream>, _) = match &self.tls {
+                Some(tls) => (Box::new(tls.connect(j, stream).await?), "wss"),
+                None => (Box::new(stream), "ws"),
+            };
+            #[cfg(not(feature = "transport-tls"))]
+            let scheme = "ws";
+            // Server is authenticated by its certificate
+            let peer = (scheme == "wss").then_some(j);
+            let (ws, _) = tokio_tungstenite::client_async_with_config(
+                format!("{scheme}://{addr}/"),
+                stream,
+                Some(self.config),
+            )
+            .await
+            .map_err(ws_error)?;
+            Ok(channel(ws, peer))
+        })
+    }
+
+    fn accept(&self) -> BoxFuture<'_, io::Result<Channel>> {
+        Box::pin(async move {
+            let (stream, _) = self.listener.accept().await?;
+            stream.set_nodelay(true)?;
+            #[cfg(feature = "transport-tls")]
+            let (stream, peer): (Box<dyn ByteStream>, _) = match &self.tls {
+                Some(tls) => {
+                    let (stream, peer) = tls.accept(stream).await?;
+                    (Box::new(stream), peer)
+                }
+                None => (Box::new(stream), None),
+            };
+            #[cfg(not(feature = "transport-tls"))]
+            let peer = None;
+            let ws = tokio_tungstenite::accept_async_with_config(stream, Some(self.config))
+                .await
+                .map_err(ws_error)?;
+            Ok(channel(ws, peer))
+        })
+    }
+}
+
+fn channel<S: ByteStream + 'static>(ws: WebSocketStream<S>, peer: Option<PartyIndex>) -> Channel {
+    let (sink, stream) = ws.split();
+    let sink = sink
+        .sink_map_err(ws_error)
+        .with(|frame: Vec<u8>| future::ready(Ok::<_, io::Error>(Message::Binary(frame))));
+    let stream = stream.filter_map(|msg| {
+        future::ready(match msg {
+            Ok(Message::Binary(frame)) => Some(Ok(frame)),
+            // Pings are answered by tungstenite, other messages are not used
+            Ok(_) => None,
+            Err(err) => Some(Err(ws_error(err))),
+        })
+    });
+    Channel {
+        sink: Box::pin(sink),
+        stream: Box::pin(stream),
+        peer,
+    }
+}
+
+fn ws_error(err: tokio_tungstenite::tungstenite::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
@@ -21,6 +21,16 @@
 //!     .generate_shares(&mut rng)?;
 //! # Ok::<_, cggmp21::trusted_dealer::TrustedDealerError>(())
 //! ```
+//!
+//! ## Large committees
+//! Generating auxiliary data takes most of the time as it requires generating two safe primes
+//! per party. When `parallel` feature is enabled, primes, as well as rest of auxiliary data, are
+//! generated in parallel for all parties, which makes it feasible to deal shares for hundreds of
+//! parties (e.g. for simulations and tests). Output is determined by the `rng` regardless of
+//! whether `parallel` feature is enabled.
+//!
+//! Computations are run on rayon's current thread pool. To limit parallelism, specify dedicated
+//! pool via [`TrustedDealerBuilder::set_thread_pool`].
 
 use std::{iter, marker::PhantomData};
 
@@ -45,23 +55,24 @@ use crate::{
 /// Takes amount of key shares `n` to be generated
 ///
 /// Alias to [`TrustedDealerBuilder::new`]
-pub fn builder<E: Curve, L: SecurityLevel>(n: u16) -> TrustedDealerBuilder<E, L> {
+pub fn builder<'a, E: Curve, L: SecurityLevel>(n: u16) -> TrustedDealerBuilder<'a, E, L> {
     TrustedDealerBuilder::new(n)
 }
 
 type CoreBuilder<E> = key_share::trusted_dealer::TrustedDealerBuilder<E>;
 
 /// Trusted dealer builder
-pub struct TrustedDealerBuilder<E: Curve, L: SecurityLevel> {
+pub struct TrustedDealerBuilder<'a, E: Curve, L: SecurityLevel> {
     inner: CoreBuilder<E>,
     n: u16,
     pregenerated_primes: Option<Vec<(Integer, Integer)>>,
     enable_mulitexp: bool,
     enable_crt: bool,
+    thread_pool: Option<&'a utils::ThreadPool>,
     _ph: PhantomData<L>,
 }
 
-impl<E: Curve, L: SecurityLevel> TrustedDealerBuilder<E, L> {
+impl<'a, E: Curve, L: SecurityLevel> TrustedDealerBuilder<'a, E, L> {
     /// Construct a trusted dealer builder
     ///
     /// Takes amount of key shares `n` to be generated
@@ -72,6 +83,7 @@ impl<E: Curve, L: SecurityLevel> TrustedDealerBuilder<E, L> {
             pregenerated_primes: None,
             enable_mulitexp: false,
             enable_crt: false,
+            thread_pool: None,
             _ph: PhantomData,
         }
     }
@@ -136,6 +148,18 @@ impl<E: Curve, L: SecurityLevel> TrustedDealerBuilder<E, L> {
         }
     }
 
+    /// Specifies thread pool to generate auxiliary data on
+    ///
+    /// By default, rayon's current thread pool is used. Dedicated pool lets you limit
+    /// parallelism, so generation doesn't starve other services running within the same process.
+    #[cfg(feature = "parallel")]
+    pub fn set_thread_pool(self, pool: &'a rayon::ThreadPool) -> Self {
+        Self {
+            thread_pool: Some(pool),
+            ..self
+        }
+    }
+
     /// Specifies that the key being generated shall support HD derivation
     #[cfg(feature = "hd-wallets")]
     pub fn hd_wallet(self, v: bool) -> Self {
@@ -170,14 +194,15 @@ impl<E: Curve, L: SecurityLevel> TrustedDealerBuilder<E, L> {
         let n = self.n;
         let enable_multiexp = self.enable_mulitexp;
         let enable_crt = self.enable_crt;
+        let pool = self.thread_pool;
 
         let primes = self.pregenerated_primes.take();
         let core_key_shares = self.inner.generate_shares(rng).map_err(Reason::CoreError)?;
-        let aux_data = if let Some(primes) = primes {
-            generate_aux_data_with_primes(rng, primes, enable_multiexp, enable_crt)?
-        } else {
-            generate_aux_data(rng, n, enable_multiexp, enable_crt)?
+        let primes = match primes {
+            Some(primes) => primes,
+            None => generate_primes::<L, _>(pool, rng, n),
         };
+        let aux_data = aux_data_with_primes(pool, rng, primes, enable_multiexp, enable_crt)?;
 
         let key_shares = core_key_shares
             .into_iter()
@@ -196,18 +221,17 @@ impl<E: Curve, L: SecurityLevel> TrustedDealerBuilder<E, L> {
 ///
 /// `enable_multiexp` and `enable_crt` flags configure whether to enable [multiexp](TrustedDealerBuilder::enable_multiexp)
 /// and [CRT](TrustedDealerBuilder::enable_crt) optimizations.
+///
+/// Computations are run on rayon's current thread pool, use [`TrustedDealerBuilder::set_thread_pool`]
+/// to run them on dedicated pool.
 pub fn generate_aux_data<L: SecurityLevel, R: RngCore + CryptoRng>(
     rng: &mut R,
     n: u16,
     enable_multiexp: bool,
     enable_crt: bool,
 ) -> Result<Vec<AuxInfo<L>>, TrustedDealerError> {
-    let primes =
-        iter::repeat_with(|| crate::key_refresh::PregeneratedPrimes::<L>::generate(rng).split())
-            .take(n.into())
-            .collect::<Vec<_>>();
-
-    generate_aux_data_with_primes(rng, primes, enable_multiexp, enable_crt)
+    let primes = generate_primes::<L, _>(None, rng, n);
+    aux_data_with_primes(None, rng, primes, enable_multiexp, enable_crt)
 }
 
 /// Generates auxiliary data for `n` signers using provided pregenerated primes
@@ -216,62 +240,93 @@ pub fn generate_aux_data<L: SecurityLevel, R: RngCore + CryptoRng>(
 ///
 /// `enable_multiexp` and `enable_crt` flags configure whether to enable [multiexp](TrustedDealerBuilder::enable_multiexp)
 /// and [CRT](TrustedDealerBuilder::enable_crt) optimizations.
+///
+/// Computations are run on rayon's current thread pool, use [`TrustedDealerBuilder::set_thread_pool`]
+/// to run them on dedicated pool.
 pub fn generate_aux_data_with_primes<L: SecurityLevel, R: RngCore + CryptoRng>(
     rng: &mut R,
     pregenerated_primes: Vec<(Integer, Integer)>,
     enable_multiexp: bool,
     enable_crt: bool,
 ) -> Result<Vec<AuxInfo<L>>, TrustedDealerError> {
-    let public_aux_data = pregenerated_primes
+    aux_data_with_primes(None, rng, pregenerated_primes, enable_multiexp, enable_crt)
+}
+
+fn generate_primes<L: SecurityLevel, R: RngCore + CryptoRng>(
+    pool: Option<&utils::ThreadPool>,
+    rng: &mut R,
+    n: u16,
+) -> Vec<(Integer, Integer)> {
+    let rngs = iter::repeat_with(|| utils::fork_rng(rng))
+        .take(n.into())
+        .collect::<Vec<_>>();
+    utils::par_map(pool, rngs, |mut rng| {
+        crate::key_refresh::PregeneratedPrimes::<L>::generate(&mut rng).split()
+    })
+}
+
+fn aux_data_with_primes<L: SecurityLevel, R: RngCore + CryptoRng>(
+    pool: Option<&utils::ThreadPool>,
+    rng: &mut R,
+    pregenerated_primes: Vec<(Integer, Integer)>,
+    enable_multiexp: bool,
+    enable_crt: bool,
+) -> Result<Vec<AuxInfo<L>>, TrustedDealerError> {
+    let parties = pregenerated_primes
         .iter()
-        .map(|(p, q)| {
-            let N = (p * q).complete();
+        .map(|primes| (primes, utils::fork_rng(rng)))
+        .collect::<Vec<_>>();
+    let public_aux_data = utils::par_map(pool, parties, |((p, q), mut rng)| {
+        let rng = &mut rng;
+        let N = (p * q).complete();
 
-            let φ_N = (p - 1u8).complete() * (q - 1u8).complete();
+        let φ_N = (p - 1u8).complete() * (q - 1u8).complete();
 
-            let r = Integer::gen_invertible(&N, rng);
-            let λ = φ_N.random_below_ref(&mut utils::external_rand(rng)).into();
+        let r = Integer::gen_invertible(&N, rng);
+        let λ = φ_N.random_below_ref(&mut utils::external_rand(rng)).into();
 
-            let t = r.square().modulo(&N);
-            let s = t.pow_mod_ref(&λ, &N).ok_or(Reason::PowMod)?.into();
+        let t = r.square().modulo(&N);
+        let s = t.pow_mod_ref(&λ, &N).ok_or(Reason::PowMod)?.into();
 
-            let mut aux = PartyAux {
-                N,
-                s,
-                t,
-                multiexp: None,
-                crt: None,
-            };
-            if enable_multiexp {
-                aux.precompute_multiexp_table::<L>()
-                    .map_err(Reason::BuildMultiexp)?;
-            }
-            Ok(aux)
-        })
-        .collect::<Result<Vec<_>, Reason>>()?;
+        let mut aux = PartyAux {
+            N,
+            s,
+            t,
+            multiexp: None,
+            crt: None,
+        };
+        if enable_multiexp {
+            aux.precompute_multiexp_table::<L>()
+                .map_err(Reason::BuildMultiexp)?;
+        }
+        Ok::<_, Reason>(aux)
+    })
+    .into_iter()
+    .collect::<Result<Vec<_>, Reason>>()?;
 
-    pregenerated_primes
-        .into_iter()
-        .enumerate()
-        .map(|(i, (p, q))| {
-            let mut public_aux_data = public_aux_data.clone();
-            if enable_crt {
-                public_aux_data[i]
-                    .precompute_crt(&p, &q)
-                    .map_err(Reason::BuildCrt)?;
-            }
+    let parties = pregenerated_primes.into_iter().enumerate().collect();
+    utils::par_map(pool, parties, |(i, (p, q))| {
+        let mut public_aux_data = public_aux_data.clone();
+        if enable_crt {
+            public_aux_data[i]
+                .precompute_crt(&p, &q)
+                .map_err(Reason::BuildCrt)?;
+        }
 
-            DirtyAuxInfo {
-                p,
-                q,
-                parties: public_aux_data,
-                security_level: PhantomData,
-            }
-            .validate()
-            .map_err(|err| Reason::InvalidKeyShare(err.into_error()))
-        })
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(TrustedDealerError)
+        DirtyAuxInfo {
+            p,
+            q,
+            parties: public_aux_data,
+            key_binding: None,
+            security_level: PhantomData,
+            decryption_key_cache: Default::default(),
+        }
+        .validate()
+        .map_err(|err| Reason::InvalidKeyShare(err.into_error()))
+    })
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(TrustedDealerError)
 }
 
 /// Error explaining why trusted dealer failed to generate shares
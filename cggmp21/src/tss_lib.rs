@@ -0,0 +1,226 @@
+//! Interoperability with [bnb-chain/tss-lib](https://github.com/bnb-chain/tss-lib)
+//!
+//! tss-lib implements GG18/GG20 family of protocols. Their round messages don't structurally align
+//! with CGGMP21 ones: keygen commits to Feldman VSS instead of hash-committing to Schnorr proofs,
+//! signing uses MtA with different range proofs, and auxiliary info uses a separate ring-Pedersen
+//! modulus $\tilde N$ instead of reusing Paillier modulus. For that reason, parties running tss-lib
+//! and parties running this crate can't form a committee within the same protocol execution, and no
+//! message translation is provided.
+//!
+//! What does align is the key itself: both libraries Shamir-share the secret key with Feldman
+//! public commitments, so the key generated with tss-lib can be migrated to this crate, and
+//! signatures can be cross-validated between the implementations:
+//!
+//! * [`import_key_share`] converts tss-lib `LocalPartySaveData` (as serialized by Go's
+//!   `encoding/json`) into [`IncompleteKeyShare`]. Aux info isn't converted (see above), so
+//!   committee needs to run [aux info generation](crate::aux_info_gen) afterwards and
+//!   [bind](crate::key_share::SharedAuxInfo::bind) it to the imported shares.
+//! * [`SignatureData`] mirrors tss-lib `common.SignatureData`, so signatures produced by this crate
+//!   can be fed into tss-lib tooling.
+//!
+//! Only curves registered in tss-lib are supported: secp256k1 and secp256r1 (`nist256p1` in
+//! tss-lib).
+
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+
+use crate::{
+    key_share::{
+        DirtyIncompleteKeyShare, DirtyKeyInfo, IncompleteKeyShare, InvalidIncompleteKeyShare,
+        Validate, VssSetup,
+    },
+    rug::{integer::Order, Integer},
+    signing::Signature,
+};
+
+/// Converts tss-lib ECDSA key share into [`IncompleteKeyShare`]
+///
+/// `save_data` is JSON-serialized `keygen.LocalPartySaveData`. `threshold` is tss-lib threshold
+/// parameter the key was generated with: `threshold + 1` signers are required to sign.
+///
+/// Index of local party in the resulting key share is the position of its `ShareID` in `Ks`.
+pub fn import_key_share<E: Curve>(
+    save_data: &[u8],
+    threshold: u16,
+) -> Result<IncompleteKeyShare<E>, ImportError> {
+    let save_data: SaveData = serde_json::from_slice(save_data).map_err(Reason::Parse)?;
+
+    let share_id = parse_integer(&save_data.share_id)?;
+    let ks = save_data
+        .ks
+        .iter()
+        .map(|k| parse_integer(k))
+        .collect::<Result<Vec<_>, _>>()?;
+    let i = ks
+        .iter()
+        .position(|k| *k == share_id)
+        .ok_or(Reason::UnknownShareId)?;
+    let i = u16::try_from(i).map_err(|_| Reason::TooManyParties)?;
+
+    let I = ks
+        .iter()
+        .map(|k| NonZero::from_scalar(integer_to_scalar(k)).ok_or(Reason::ZeroShare))
+        .collect::<Result<Vec<_>, _>>()?;
+    let public_shares = save_data
+        .big_xj
+        .iter()
+        .map(|p| p.to_point::<E>()?.ok_or_else(|| Reason::ZeroShare.into()))
+        .collect::<Result<Vec<_>, ImportError>>()?;
+    let shared_public_key = save_data
+        .ecdsa_pub
+        .to_point::<E>()?
+        .ok_or(Reason::ZeroShare)?;
+    let mut x = integer_to_scalar(&parse_integer(&save_data.xi)?);
+    let x = NonZero::from_secret_scalar(SecretScalar::new(&mut x)).ok_or(Reason::ZeroShare)?;
+
+    DirtyIncompleteKeyShare {
+        i,
+        key_info: DirtyKeyInfo {
+            curve: Default::default(),
+            shared_public_key,
+            public_shares,
+            vss_setup: Some(VssSetup {
+                min_signers: threshold.checked_add(1).ok_or(Reason::TooManyParties)?,
+                I,
+                weighted: None,
+                commitments: None,
+            }),
+            #[cfg(feature = "hd-wallets")]
+            chain_code: None,
+        },
+        x,
+        extra_x: vec![],
+    }
+    .validate()
+    .map_err(|err| Reason::InvalidKeyShare(err.into_error()).into())
+}
+
+/// tss-lib `common.SignatureData`
+///
+/// Serializes the same way as Go's `encoding/json` serializes the tss-lib struct (byte strings
+/// are base64-encoded).
+#[serde_with::serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureData {
+    /// Concatenation of `r` and `s`
+    #[serde_as(as = "serde_with::base64::Base64")]
+    pub signature: Vec<u8>,
+    /// `r` component of the signature, big-endian
+    #[serde_as(as = "serde_with::base64::Base64")]
+    pub r: Vec<u8>,
+    /// `s` component of the signature, big-endian
+    #[serde_as(as = "serde_with::base64::Base64")]
+    pub s: Vec<u8>,
+    /// Signed message (hash of the message, as it was passed to the signing)
+    #[serde_as(as = "serde_with::base64::Base64")]
+    pub m: Vec<u8>,
+}
+
+impl SignatureData {
+    /// Converts signature produced by this crate
+    ///
+    /// `message_hash` is the hash of the message that was signed. Signature should be
+    /// [normalized](Signature::normalize_s), as tss-lib always outputs low-S signatures.
+    pub fn new<E: Curve>(signature: &Signature<E>, message_hash: &[u8]) -> Self {
+        let r = signature.r.to_be_bytes().as_bytes().to_vec();
+        let s = signature.s.to_be_bytes().as_bytes().to_vec();
+        Self {
+            signature: [r.as_slice(), s.as_slice()].concat(),
+            r,
+            s,
+            m: message_hash.to_vec(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SaveData {
+    #[serde(rename = "Xi")]
+    xi: Box<RawValue>,
+    #[serde(rename = "ShareID")]
+    share_id: Box<RawValue>,
+    #[serde(rename = "Ks")]
+    ks: Vec<Box<RawValue>>,
+    #[serde(rename = "BigXj")]
+    big_xj: Vec<EcPoint>,
+    #[serde(rename = "ECDSAPub")]
+    ecdsa_pub: EcPoint,
+}
+
+#[derive(Deserialize)]
+struct EcPoint {
+    #[serde(rename = "Curve")]
+    curve: String,
+    #[serde(rename = "Coords")]
+    coords: [Box<RawValue>; 2],
+}
+
+impl EcPoint {
+    fn to_point<E: Curve>(&self) -> Result<Option<NonZero<Point<E>>>, ImportError> {
+        let expected = match E::CURVE_NAME {
+            "secp256k1" => "secp256k1",
+            "secp256r1" => "nist256p1",
+            curve => return Err(Reason::UnsupportedCurve(curve).into()),
+        };
+        if self.curve != expected {
+            return Err(Reason::CurveMismatch(self.curve.clone()).into());
+        }
+
+        // Encode point in uncompressed SEC1 form: 0x04 || x || y
+        let size = (Point::<E>::generator().to_bytes(false).len() - 1) / 2;
+        let mut bytes = vec![0x04];
+        for coord in &self.coords {
+            let coord = parse_integer(coord)?.to_digits::<u8>(Order::Msf);
+            if coord.len() > size {
+                return Err(Reason::InvalidPoint.into());
+            }
+            bytes.resize(bytes.len() + size - coord.len(), 0);
+            bytes.extend_from_slice(&coord);
+        }
+        let point = Point::<E>::from_bytes(&bytes).map_err(|_| Reason::InvalidPoint)?;
+        Ok(NonZero::from_point(point))
+    }
+}
+
+/// Go's `big.Int` is serialized as JSON number of arbitrary length
+fn parse_integer(value: &RawValue) -> Result<Integer, ImportError> {
+    Integer::from_str_radix(value.get(), 10).map_err(|_| Reason::InvalidInteger.into())
+}
+
+fn integer_to_scalar<E: Curve>(x: &Integer) -> Scalar<E> {
+    Scalar::from_be_bytes_mod_order(x.to_digits::<u8>(Order::Msf))
+}
+
+/// Error indicating that tss-lib key share can't be imported
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct ImportError(Reason);
+
+#[derive(Debug, thiserror::Error)]
+enum Reason {
+    #[error("parse save data")]
+    Parse(#[source] serde_json::Error),
+    #[error("invalid integer")]
+    InvalidInteger,
+    #[error("invalid point")]
+    InvalidPoint,
+    #[error("curve {0} is not supported by tss-lib")]
+    UnsupportedCurve(&'static str),
+    #[error("key share is on another curve: {0}")]
+    CurveMismatch(String),
+    #[error("share id is not in the list of share ids")]
+    UnknownShareId,
+    #[error("too many parties")]
+    TooManyParties,
+    #[error("share or public key is zero")]
+    ZeroShare,
+    #[error("imported key share is not valid")]
+    InvalidKeyShare(#[source] InvalidIncompleteKeyShare),
+}
+
+crate::errors::impl_from! {
+    impl From for ImportError {
+        err: Reason => ImportError(err),
+    }
+}
@@ -1,9 +1,14 @@
+use digest::{
+    typenum::{Unsigned, U32},
+    Digest,
+};
 use generic_ec::{Curve, Scalar};
 use paillier_zk::rug::{self, Integer};
 use paillier_zk::{
     group_element_vs_paillier_encryption_in_range as pi_log,
     paillier_affine_operation_in_range as pi_aff, paillier_encryption_in_range as pi_enc,
 };
+use rand_core::{CryptoRng, RngCore, SeedableRng};
 use round_based::rounds_router::simple_store::RoundMsgs;
 use round_based::{MsgId, PartyIndex};
 
@@ -11,6 +16,101 @@ use crate::security_level::SecurityLevel;
 
 pub use paillier_zk::fast_paillier::utils::external_rand;
 
+/// Thread pool that heavy computations are run on
+///
+/// Uninhabited if `parallel` feature is disabled, so only `None` can be passed to [`par_map`]
+#[cfg(feature = "parallel")]
+pub use rayon::ThreadPool;
+#[cfg(not(feature = "parallel"))]
+pub enum ThreadPool {}
+
+/// Applies `f` to every item
+///
+/// If `parallel` feature is enabled, items are processed in parallel on `pool`, or, if it's not
+/// specified, on rayon's current thread pool. Order of outputs matches order of inputs.
+pub fn par_map<I, T, F>(pool: Option<&ThreadPool>, items: Vec<I>, f: F) -> Vec<T>
+where
+    I: Send,
+    T: Send,
+    F: Fn(I) -> T + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::iter::{IntoParallelIterator, ParallelIterator};
+        let run = || items.into_par_iter().map(f).collect();
+        match pool {
+            Some(pool) => pool.install(run),
+            None => run(),
+        }
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        if let Some(pool) = pool {
+            match *pool {}
+        }
+        items.into_iter().map(f).collect()
+    }
+}
+
+/// Derives an independent rng from `rng`
+///
+/// Used to give each of parallel tasks its own source of randomness
+pub fn fork_rng<R: RngCore + CryptoRng>(rng: &mut R) -> rand_chacha::ChaCha20Rng {
+    let mut seed = <rand_chacha::ChaCha20Rng as SeedableRng>::Seed::default();
+    rng.fill_bytes(&mut seed);
+    rand_chacha::ChaCha20Rng::from_seed(seed)
+}
+
+/// Digest `D` with output truncated to 32 bytes
+///
+/// ZK proofs and challenge derivation require a 32 bytes digest. Wrapping the protocol digest
+/// into `Truncated` allows using digests with wider output (e.g. SHA-512) as well. For digests
+/// with exactly 32 bytes output, `Truncated<D>` outputs the same bytes as `D`, so proofs are
+/// not affected.
+///
+/// Digests with output shorter than 32 bytes are rejected at compile time.
+#[derive(Clone)]
+pub struct Truncated<D>(D);
+
+impl<D: Digest> Truncated<D> {
+    const OUTPUT_IS_WIDE_ENOUGH: () = assert!(
+        <D::OutputSize as Unsigned>::USIZE >= 32,
+        "digest output must be at least 32 bytes"
+    );
+
+    /// Wraps the hasher, preserving its state
+    pub fn new(hasher: D) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::OUTPUT_IS_WIDE_ENOUGH;
+        Self(hasher)
+    }
+}
+
+impl<D: Digest> Default for Truncated<D> {
+    fn default() -> Self {
+        Self::new(D::new())
+    }
+}
+
+impl<D: Digest> digest::OutputSizeUser for Truncated<D> {
+    type OutputSize = U32;
+}
+
+impl<D: Digest> digest::Update for Truncated<D> {
+    fn update(&mut self, data: &[u8]) {
+        Digest::update(&mut self.0, data)
+    }
+}
+
+impl<D: Digest> digest::FixedOutput for Truncated<D> {
+    fn finalize_into(self, out: &mut digest::Output<Self>) {
+        let output = self.0.finalize();
+        out.copy_from_slice(&output[..out.len()])
+    }
+}
+
+impl<D: Digest> digest::HashMarker for Truncated<D> {}
+
 /// Converts `&Scalar<E>` into Integer
 pub fn scalar_to_bignumber<E: Curve>(scalar: impl AsRef<Scalar<E>>) -> Integer {
     Integer::from_digits(&scalar.as_ref().to_be_bytes(), rug::integer::Order::Msf)
@@ -105,6 +205,34 @@ where
         .collect()
 }
 
+/// Same as [`collect_blame`], but `filter` is applied to message pairs in parallel, see [`par_map`]
+pub fn par_collect_blame<D, P, F>(
+    pool: Option<&ThreadPool>,
+    data_messages: &RoundMsgs<D>,
+    proof_messages: &RoundMsgs<P>,
+    filter: F,
+) -> Vec<AbortBlame>
+where
+    D: Sync,
+    P: Sync,
+    F: Fn(PartyIndex, &D, &P) -> bool + Send + Sync,
+{
+    let pairs = data_messages
+        .iter_indexed()
+        .zip(proof_messages.iter_indexed())
+        .collect::<Vec<_>>();
+    par_map(
+        pool,
+        pairs,
+        |((j, data_msg_id, data), (_, proof_msg_id, proof))| {
+            filter(j, data, proof).then(|| AbortBlame::new(j, data_msg_id, proof_msg_id))
+        },
+    )
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
 /// Filter returns `true` for every __faulty__ message. Data and proof are set
 /// to the same message.
 pub fn collect_simple_blame<D, F>(messages: &RoundMsgs<D>, mut filter: F) -> Vec<AbortBlame>
@@ -159,6 +287,30 @@ pub fn but_nth<T, I: IntoIterator<Item = T>>(n: u16, iter: I) -> impl Iterator<I
         .map(|(_, x)| x)
 }
 
+/// Finds Paillier moduli which share a factor with any other modulus in the list
+///
+/// Returns positions of such moduli in the list. Honestly generated moduli have a common factor
+/// with negligible probability, so a common factor (in particular, equal moduli) indicates that
+/// the modulus was copied from, or derived from the modulus of another party.
+pub fn related_moduli<'a>(moduli: impl IntoIterator<Item = &'a Integer>) -> Vec<usize> {
+    let moduli = moduli.into_iter().collect::<Vec<_>>();
+    let mut related = vec![false; moduli.len()];
+    for (a, n_a) in moduli.iter().enumerate() {
+        for (b, n_b) in moduli.iter().enumerate().skip(a + 1) {
+            if Integer::from(n_a.gcd_ref(n_b)) != *Integer::ONE {
+                related[a] = true;
+                related[b] = true;
+            }
+        }
+    }
+    related
+        .into_iter()
+        .enumerate()
+        .filter(|(_, related)| *related)
+        .map(|(k, _)| k)
+        .collect()
+}
+
 /// Binary search for rounded down square root. For non-positive numbers returns
 /// one
 pub fn sqrt(x: &Integer) -> Integer {
@@ -185,23 +337,12 @@ where
     (oks, errs)
 }
 
-/// Returns `[list[indexes[0]], list[indexes[1]], ..., list[indexes[n-1]]]`
-///
-/// Result is `None` if any of `indexes[i]` is out of range of `list`
-pub fn subset<T: Clone, I: Into<usize> + Copy>(indexes: &[I], list: &[T]) -> Option<Vec<T>> {
-    indexes
-        .iter()
-        .map(|&i| list.get(i.into()).cloned())
-        .collect()
-}
-
 /// Generates **unsafe** blum primes
 ///
 /// Blum primes are faster to generate than safe primes, and they don't break correctness of CGGMP protocol.
 /// However, they do break security of the protocol.
 ///
-/// Only supposed to be used in the tests.
-#[cfg(test)]
+/// Only supposed to be used in the tests, see [`PrimesKind::Blum`](crate::key_refresh::PrimesKind::Blum).
 pub fn generate_blum_prime(rng: &mut impl rand_core::RngCore, bits_size: u32) -> Integer {
     loop {
         let mut n: Integer = Integer::random_bits(bits_size, &mut external_rand(rng)).into();
@@ -269,4 +410,42 @@ mod test {
             assert!(root.square_ref().complete() > x);
         }
     }
+
+    #[test]
+    fn truncated_digest() {
+        use sha2::{Digest, Sha256, Sha512};
+
+        use super::Truncated;
+
+        let data = b"some data to hash";
+        assert_eq!(
+            Truncated::<Sha256>::digest(data).as_slice(),
+            Sha256::digest(data).as_slice()
+        );
+        assert_eq!(
+            Truncated::<Sha512>::digest(data).as_slice(),
+            &Sha512::digest(data)[..32]
+        );
+        assert_eq!(
+            Truncated::new(Sha512::new_with_prefix(b"some data"))
+                .chain_update(b" to hash")
+                .finalize(),
+            Truncated::<Sha512>::digest(data)
+        );
+    }
+
+    #[test]
+    fn related_moduli() {
+        use super::{related_moduli, Integer};
+        let moduli = [
+            Integer::from(7 * 11),
+            Integer::from(13 * 17),
+            Integer::from(19 * 23),
+            Integer::from(7 * 11),
+            Integer::from(29 * 17),
+        ];
+        assert_eq!(related_moduli(&moduli[..3]), Vec::<usize>::new());
+        assert_eq!(related_moduli(&moduli[..4]), vec![0, 3]);
+        assert_eq!(related_moduli(&moduli), vec![0, 1, 3, 4]);
+    }
 }
@@ -1 +1,2 @@
+pub mod batch;
 pub mod ring_pedersen_parameters;
@@ -0,0 +1,336 @@
+//! Batch verification of Пaff-g and Пlog* proofs
+//!
+//! When a party receives proofs from all other signers, all of them are verified against
+//! ring-Pedersen parameters of that party. Ring-Pedersen equalities $s^x t^y = c \cdot S^e \mod \hat N$
+//! are the most expensive part of verification, so instead of checking them one by one, we check
+//! a random linear combination of them:
+//!
+//! $$s^{\sum_j \rho_j x_j} t^{\sum_j \rho_j y_j} = \prod_j (c_j \cdot S_j^{e_j})^{\rho_j} \mod \hat N$$
+//!
+//! Remaining equalities (over prover's Paillier modulus and over the curve) and range checks are
+//! performed individually.
+//!
+//! $\mathbb{Z}_{\hat N}^*$ has elements of order 2, so equalities which only hold up to the sign
+//! would pass the combined check with noticeable probability. Verifier knows factorization of
+//! its own $\hat N$ and uses it to make sure that every right hand side is a quadratic residue (left
+//! hand side always is). Quadratic residues modulo product of safe primes form a group of odd
+//! order in which the combined check is sound.
+//!
+//! Batch check only tells whether all proofs are valid. If it fails, proofs need to be verified
+//! individually to find out which of them are faulty.
+
+use digest::{typenum::U32, Digest};
+use generic_ec::{Curve, Point};
+use paillier_zk::{
+    fast_paillier::utils::external_rand,
+    group_element_vs_paillier_encryption_in_range as pi_log,
+    paillier_affine_operation_in_range as pi_aff,
+    rug::{Complete, Integer},
+    IntegerExt,
+};
+use rand_core::{CryptoRng, RngCore};
+
+/// Bit size of coefficients $\rho_j$ of linear combination
+const COEF_BITS: u32 = 128;
+
+/// Ring-Pedersen equality $s^x t^y = c \cdot S^e \mod \hat N$
+pub struct RingPedersenEq {
+    pub x: Integer,
+    pub y: Integer,
+    pub c: Integer,
+    pub S: Integer,
+    pub e: Integer,
+}
+
+/// Accumulates ring-Pedersen equalities to be checked at once
+pub struct RingPedersenBatch<'a> {
+    aux: &'a pi_aff::Aux,
+    p: &'a Integer,
+    q: &'a Integer,
+    x: Integer,
+    y: Integer,
+    rhs: Integer,
+    valid: bool,
+}
+
+impl<'a> RingPedersenBatch<'a> {
+    /// Constructs an empty batch
+    ///
+    /// Takes ring-Pedersen parameters of the verifier and primes $p, q$ such that $\hat N = pq$
+    pub fn new(aux: &'a pi_aff::Aux, p: &'a Integer, q: &'a Integer) -> Self {
+        let mut batch = Self {
+            aux,
+            p,
+            q,
+            x: Integer::ZERO,
+            y: Integer::ZERO,
+            rhs: Integer::from(1),
+            valid: (p * q).complete() == aux.rsa_modulo,
+        };
+        batch.valid &= batch.is_qr(&aux.s) && batch.is_qr(&aux.t);
+        batch
+    }
+
+    /// Adds equality to the batch
+    pub fn add<R: RngCore + CryptoRng>(&mut self, rng: &mut R, eq: RingPedersenEq) {
+        if !self.valid {
+            return;
+        }
+        if !self.is_qr(&eq.c) || (eq.e.is_odd() && !self.is_qr(&eq.S)) {
+            self.valid = false;
+            return;
+        }
+
+        let rho = Integer::from(Integer::random_bits(COEF_BITS, &mut external_rand(rng)));
+        let N = &self.aux.rsa_modulo;
+        let Ok(rhs_i) = N.combine(&eq.c, &rho, &eq.S, &(&eq.e * &rho).complete()) else {
+            self.valid = false;
+            return;
+        };
+        self.x += &rho * eq.x;
+        self.y += rho * eq.y;
+        self.rhs = (&self.rhs * rhs_i).modulo(N);
+    }
+
+    /// Checks that all equalities in the batch hold
+    pub fn verify(self) -> bool {
+        self.valid
+            && self
+                .aux
+                .rsa_modulo
+                .combine(&self.aux.s, &self.x, &self.aux.t, &self.y)
+                .map(|lhs| lhs == self.rhs)
+                .unwrap_or(false)
+    }
+
+    fn is_qr(&self, x: &Integer) -> bool {
+        x.legendre(self.p) == 1 && x.legendre(self.q) == 1
+    }
+}
+
+/// Verifies Пaff-g proof except for ring-Pedersen equalities
+///
+/// Returns ring-Pedersen equalities to be checked in the batch, or `None` if proof is invalid
+pub fn pi_aff<C: Curve, D: Digest<OutputSize = U32>>(
+    shared_state: D,
+    aux: &pi_aff::Aux,
+    data: pi_aff::Data<C>,
+    commitment: &pi_aff::Commitment<C>,
+    security: &pi_aff::SecurityParams,
+    proof: &pi_aff::Proof,
+) -> Option<[RingPedersenEq; 2]> {
+    let e = pi_aff::non_interactive::challenge(shared_state, aux, data, commitment, security);
+
+    let lhs = data
+        .key0
+        .oadd(
+            &data.key0.omul(&proof.z1, data.c).ok()?,
+            &data.key0.encrypt_with(&proof.z2, &proof.w).ok()?,
+        )
+        .ok()?;
+    let rhs = data
+        .key0
+        .oadd(&commitment.a, &data.key0.omul(&e, data.d).ok()?)
+        .ok()?;
+    if lhs != rhs {
+        return None;
+    }
+
+    if Point::<C>::generator() * proof.z1.to_scalar() != commitment.b_x + data.x * e.to_scalar() {
+        return None;
+    }
+
+    let lhs = data.key1.encrypt_with(&proof.z2, &proof.w_y).ok()?;
+    let rhs = data
+        .key1
+        .oadd(&commitment.b_y, &data.key1.omul(&e, data.y).ok()?)
+        .ok()?;
+    if lhs != rhs {
+        return None;
+    }
+
+    if !proof
+        .z1
+        .is_in_pm(&(Integer::ONE << (security.l_x + security.epsilon)).complete())
+        || !proof
+            .z2
+            .is_in_pm(&(Integer::ONE << (security.l_y + security.epsilon)).complete())
+    {
+        return None;
+    }
+
+    Some([
+        RingPedersenEq {
+            x: proof.z1.clone(),
+            y: proof.z3.clone(),
+            c: commitment.e.clone(),
+            S: commitment.s.clone(),
+            e: e.clone(),
+        },
+        RingPedersenEq {
+            x: proof.z2.clone(),
+            y: proof.z4.clone(),
+            c: commitment.f.clone(),
+            S: commitment.t.clone(),
+            e,
+        },
+    ])
+}
+
+/// Verifies Пlog* proof except for ring-Pedersen equality
+///
+/// Returns ring-Pedersen equality to be checked in the batch, or `None` if proof is invalid
+pub fn pi_log<C: Curve, D: Digest<OutputSize = U32>>(
+    shared_state: D,
+    aux: &pi_log::Aux,
+    data: pi_log::Data<C>,
+    commitment: &pi_log::Commitment<C>,
+    security: &pi_log::SecurityParams,
+    proof: &pi_log::Proof,
+) -> Option<RingPedersenEq> {
+    let e = pi_log::non_interactive::challenge(shared_state, aux, data, commitment, security);
+
+    let lhs = data.key0.encrypt_with(&proof.z1, &proof.z2).ok()?;
+    let rhs = data
+        .key0
+        .oadd(&commitment.a, &data.key0.omul(&e, data.c).ok()?)
+        .ok()?;
+    if lhs != rhs {
+        return None;
+    }
+
+    if data.b * proof.z1.to_scalar() != commitment.y + data.x * e.to_scalar() {
+        return None;
+    }
+
+    if !proof
+        .z1
+        .is_in_pm(&(Integer::ONE << (security.l + security.epsilon)).complete())
+    {
+        return None;
+    }
+
+    Some(RingPedersenEq {
+        x: proof.z1.clone(),
+        y: proof.z3.clone(),
+        c: commitment.d.clone(),
+        S: commitment.s.clone(),
+        e,
+    })
+}
+
+#[cfg(all(test, feature = "curve-secp256k1"))]
+mod test {
+    use generic_ec::Point;
+    use paillier_zk::{
+        fast_paillier, group_element_vs_paillier_encryption_in_range as pi_log,
+        rug::{Complete, Integer},
+        IntegerExt,
+    };
+
+    use super::{RingPedersenBatch, RingPedersenEq};
+    use crate::{security_level::SecurityLevel128, supported_curves::Secp256k1, utils};
+
+    type E = Secp256k1;
+
+    /// Generates Пlog* proof and returns whether it's individually valid along with its
+    /// ring-Pedersen equality
+    ///
+    /// If `flip_sign` is set, ring-Pedersen equality of the proof holds only up to the sign
+    fn prove(
+        rng: &mut rand_dev::DevRng,
+        aux: &pi_log::Aux,
+        flip_sign: bool,
+    ) -> (bool, RingPedersenEq) {
+        let security = utils::SecurityParams::new::<SecurityLevel128>().pi_log;
+        let dec = fast_paillier::DecryptionKey::from_primes(
+            utils::generate_blum_prime(rng, 512),
+            utils::generate_blum_prime(rng, 512),
+        )
+        .unwrap();
+
+        let x = Integer::from_rng_pm(&(Integer::ONE << security.l).complete(), rng);
+        let (c, nonce) = dec.encrypt_with_random(rng, &x).unwrap();
+        let X = Point::<E>::generator() * x.to_scalar();
+        let b = Point::<E>::generator().to_point();
+        let data = pi_log::Data {
+            key0: &dec,
+            c: &c,
+            x: &X,
+            b: &b,
+        };
+        let pdata = pi_log::PrivateData {
+            x: &x,
+            nonce: &nonce,
+        };
+        let shared_state = sha2::Sha256::default();
+
+        let (mut commitment, pcommitment) =
+            pi_log::interactive::commit(aux, data, pdata, &security, &mut *rng).unwrap();
+        if flip_sign {
+            commitment.d = (&aux.rsa_modulo - &commitment.d).complete();
+        }
+        let challenge = pi_log::non_interactive::challenge(
+            shared_state.clone(),
+            aux,
+            data,
+            &commitment,
+            &security,
+        );
+        let proof = pi_log::interactive::prove(data, pdata, &pcommitment, &challenge).unwrap();
+
+        let valid = pi_log::non_interactive::verify(
+            shared_state.clone(),
+            aux,
+            data,
+            &commitment,
+            &security,
+            &proof,
+        )
+        .is_ok();
+        let eq = super::pi_log(shared_state, aux, data, &commitment, &security, &proof)
+            .expect("only ring-pedersen equality may be invalid");
+        (valid, eq)
+    }
+
+    #[test]
+    fn batch_verification() {
+        let mut rng = rand_dev::DevRng::new();
+
+        // Verifier's ring-Pedersen parameters
+        let p = utils::generate_blum_prime(&mut rng, 512);
+        let q = utils::generate_blum_prime(&mut rng, 512);
+        let N = (&p * &q).complete();
+        let phi = (&p - 1u8).complete() * (&q - 1u8).complete();
+        let t = Integer::gen_invertible(&N, &mut rng).square().modulo(&N);
+        let lambda = phi
+            .random_below_ref(&mut utils::external_rand(&mut rng))
+            .into();
+        let s = t.pow_mod_ref(&lambda, &N).unwrap().into();
+        let aux = pi_log::Aux {
+            s,
+            t,
+            rsa_modulo: N,
+            multiexp: None,
+            crt: None,
+        };
+
+        // Valid proofs pass the batch
+        let mut batch = RingPedersenBatch::new(&aux, &p, &q);
+        for _ in 0..3 {
+            let (valid, eq) = prove(&mut rng, &aux, false);
+            assert!(valid);
+            batch.add(&mut rng, eq);
+        }
+        assert!(batch.verify());
+
+        // Proof that is valid only up to the sign fails the batch
+        let mut batch = RingPedersenBatch::new(&aux, &p, &q);
+        for flip_sign in [false, true, false] {
+            let (valid, eq) = prove(&mut rng, &aux, flip_sign);
+            assert_eq!(valid, !flip_sign);
+            batch.add(&mut rng, eq);
+        }
+        assert!(!batch.verify());
+    }
+}
@@ -6,8 +6,8 @@ use paillier_zk::{
     rug::{self, Complete, Integer},
 };
 use rand_core::{RngCore, SeedableRng};
-use serde::{Deserialize, Serialize};
-use serde_with::serde_as;
+#[cfg(feature = "serde")]
+use serde_with::As;
 use thiserror::Error;
 
 struct Challenge<const M: usize> {
@@ -27,13 +27,13 @@ pub struct Data<'a> {
 /// Parameter `M` is security level. The probability of an adversary generating
 /// a correct proof for incorrect data is $2^{-M}$. You can use M defined here
 /// as [`SECURITY`]
-#[serde_as]
-#[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
+#[derive(Clone, udigest::Digestable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Proof<const M: usize> {
-    #[serde_as(as = "[_; M]")]
+    #[cfg_attr(feature = "serde", serde(with = "As::<[serde_with::Same; M]>"))]
     #[udigest(with = crate::utils::encoding::integers_list)]
     pub commitment: [Integer; M],
-    #[serde_as(as = "[_; M]")]
+    #[cfg_attr(feature = "serde", serde(with = "As::<[serde_with::Same; M]>"))]
     #[udigest(with = crate::utils::encoding::integers_list)]
     pub zs: [Integer; M],
 }
@@ -0,0 +1,134 @@
+//! Runs CGGMP21 protocols between processes connected over TCP or WebSocket
+//!
+//! Each process runs one party. After the parties are [connected](network::Network::connect),
+//! [`run`] performs distributed key generation, aux info generation and, if local party is one
+//! of the signers, signs a message.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use cggmp21::{
+    key_refresh::{AuxOnlyMsg, PregeneratedPrimes},
+    keygen::ThresholdMsg,
+    security_level::SecurityLevel128,
+    signing::{msg::Msg, DataToSign},
+    supported_curves::Secp256k1,
+    ExecutionId, KeyShare, Signature,
+};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+
+pub mod network;
+
+pub use network::{Network, Transport};
+
+/// Curve used in the demo
+pub type E = Secp256k1;
+/// Security level used in the demo
+pub type L = SecurityLevel128;
+type D = Sha256;
+
+/// Parameters of the demo
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Threshold of the generated key
+    pub t: u16,
+    /// Indexes of parties who sign the message, must contain exactly `t` parties
+    pub signers: Vec<u16>,
+    /// Identifier of the session, must be the same for all parties and unique per run
+    pub session: String,
+    /// Message to sign
+    pub message: Vec<u8>,
+    /// Maximum duration of each protocol
+    pub timeout: Duration,
+    /// Allows using Blum primes in aux info generation
+    ///
+    /// See [`dangerous_allow_blum_primes`](cggmp21::key_refresh::GenericKeyRefreshBuilder::dangerous_allow_blum_primes).
+    /// Must only be set in tests.
+    pub dangerous_allow_blum_primes: bool,
+}
+
+/// Result of the demo
+pub struct Output {
+    /// Generated key share
+    pub key_share: KeyShare<E, L>,
+    /// Signature, `None` if local party is not a signer
+    pub signature: Option<Signature<E>>,
+}
+
+/// Runs keygen, aux info generation and signing
+///
+/// `primes` are used in aux info generation. Generating them takes a while, so it's better to do
+/// it before connecting to other parties.
+pub async fn run<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    network: &Network,
+    config: &Config,
+    primes: PregeneratedPrimes<L>,
+) -> Result<Output> {
+    let (i, n) = (network.i(), network.n());
+    let eid = |phase: &str| -> [u8; 32] {
+        Sha256::new()
+            .chain_update(&config.session)
+            .chain_update(b":")
+            .chain_update(phase)
+            .finalize()
+            .into()
+    };
+
+    let keygen_eid = eid("keygen");
+    let incomplete_share = tokio::time::timeout(
+        config.timeout,
+        cggmp21::keygen::<E>(ExecutionId::new(&keygen_eid), i, n)
+            .set_threshold(config.t)
+            .start(rng, network.party::<ThresholdMsg<E, L, D>>("keygen")?),
+    )
+    .await
+    .context("keygen timed out")?
+    .context("keygen failed")?;
+
+    let aux_eid = eid("aux");
+    let aux_info = tokio::time::timeout(
+        config.timeout,
+        cggmp21::aux_info_gen(ExecutionId::new(&aux_eid), i, n, primes)
+            .dangerous_allow_blum_primes(config.dangerous_allow_blum_primes)
+            .start(rng, network.party::<AuxOnlyMsg<D, L>>("aux")?),
+    )
+    .await
+    .context("aux info generation timed out")?
+    .context("aux info generation failed")?;
+
+    let key_share =
+        KeyShare::from_parts((incomplete_share, aux_info)).context("invalid key share")?;
+
+    let Some(signer_index) = config.signers.iter().position(|&j| j == i) else {
+        return Ok(Output {
+            key_share,
+            signature: None,
+        });
+    };
+    let signer_index = u16::try_from(signer_index).context("too many signers")?;
+    let message = DataToSign::digest::<D>(&config.message);
+    let signing_eid = eid("signing");
+    let signature = tokio::time::timeout(
+        config.timeout,
+        cggmp21::signing(
+            ExecutionId::new(&signing_eid),
+            signer_index,
+            &config.signers,
+            &key_share,
+        )
+        .sign(rng, network.party::<Msg<E, D>>("signing")?, message),
+    )
+    .await
+    .context("signing timed out")?
+    .context("signing failed")?;
+    signature
+        .verify(&key_share.shared_public_key, &message)
+        .context("produced signature is invalid")?;
+
+    Ok(Output {
+        key_share,
+        signature: Some(signature),
+    })
+}
@@ -0,0 +1,152 @@
+//! Runs one party of the demo
+//!
+//! Start one process per party, all of them with the same list of addresses, e.g. for 2-out-of-3
+//! signing over TCP:
+//!
+//! ```text
+//! cggmp21-demo --index 0 --parties 127.0.0.1:7000,127.0.0.1:7001,127.0.0.1:7002 -t 2
+//! cggmp21-demo --index 1 --parties 127.0.0.1:7000,127.0.0.1:7001,127.0.0.1:7002 -t 2
+//! cggmp21-demo --index 2 --parties 127.0.0.1:7000,127.0.0.1:7001,127.0.0.1:7002 -t 2
+//! ```
+//!
+//! Parties generate a key, aux info and then first `t` parties sign the message. Every party
+//! prints the public key, and signers print the signature.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{ensure, Context, Result};
+use cggmp21::{key_refresh::PregeneratedPrimes, Signature};
+use cggmp21_demo::{Config, Network, Transport, E, L};
+use rand::rngs::OsRng;
+
+struct Args {
+    index: u16,
+    parties: Vec<SocketAddr>,
+    threshold: u16,
+    transport: Transport,
+    signers: Option<Vec<u16>>,
+    message: String,
+    session: String,
+    timeout: u64,
+    output: Option<PathBuf>,
+}
+
+fn args() -> Args {
+    use bpaf::Parser;
+    let index = bpaf::long("index")
+        .short('i')
+        .help("Index of local party")
+        .argument::<u16>("INDEX");
+    let parties = bpaf::long("parties")
+        .short('p')
+        .help("Comma-separated addresses of all parties, ordered by index")
+        .argument::<String>("ADDRS")
+        .parse(|addrs| addrs.split(',').map(str::parse).collect::<Result<_, _>>());
+    let threshold = bpaf::long("threshold")
+        .short('t')
+        .help("Threshold of the generated key")
+        .argument::<u16>("T");
+    let transport = bpaf::long("transport")
+        .help("Transport: tcp or ws")
+        .argument::<Transport>("TRANSPORT")
+        .fallback(Transport::Tcp);
+    let signers = bpaf::long("signers")
+        .help("Comma-separated indexes of `t` signers, first `t` parties by default")
+        .argument::<String>("INDEXES")
+        .parse(|signers| signers.split(',').map(str::parse).collect::<Result<_, _>>())
+        .optional();
+    let message = bpaf::long("message")
+        .help("Message to sign")
+        .argument::<String>("MESSAGE")
+        .fallback("hello, cggmp21".to_owned());
+    let session = bpaf::long("session")
+        .help("Identifier of the session, must be unique for every run")
+        .argument::<String>("SESSION")
+        .fallback("cggmp21-demo".to_owned());
+    let timeout = bpaf::long("timeout")
+        .help("Timeout of connecting and of every protocol, in seconds")
+        .argument::<u64>("SECS")
+        .fallback(600);
+    let output = bpaf::long("output")
+        .short('o')
+        .help("Where to save generated key share")
+        .argument::<PathBuf>("PATH")
+        .optional();
+
+    bpaf::construct!(Args {
+        index,
+        parties,
+        threshold,
+        transport,
+        signers,
+        message,
+        session,
+        timeout,
+        output,
+    })
+    .to_options()
+    .descr("Runs keygen, aux info generation and signing over TCP or WebSocket")
+    .run()
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = args();
+    let signers = args
+        .signers
+        .unwrap_or_else(|| (0..args.threshold).collect());
+    ensure!(
+        signers.len() == usize::from(args.threshold),
+        "exactly `t` signers must be specified"
+    );
+    let config = Config {
+        t: args.threshold,
+        signers,
+        session: args.session,
+        message: args.message.into_bytes(),
+        timeout: Duration::from_secs(args.timeout),
+        dangerous_allow_blum_primes: false,
+    };
+
+    eprintln!("Generating primes, it may take a while...");
+    let primes = PregeneratedPrimes::<L>::generate(&mut OsRng);
+
+    let addr = *args
+        .parties
+        .get(usize::from(args.index))
+        .context("party index is out of bounds")?;
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("bind to {addr}"))?;
+    eprintln!("Connecting to other parties...");
+    let network = Network::connect(
+        args.transport,
+        args.index,
+        listener,
+        &args.parties,
+        config.timeout,
+    )
+    .await?;
+
+    eprintln!("Running protocols...");
+    let output = cggmp21_demo::run(&mut OsRng, &network, &config, primes).await?;
+    network.shutdown().await;
+
+    println!(
+        "Public key: {}",
+        hex::encode(output.key_share.shared_public_key.to_bytes(true))
+    );
+    if let Some(signature) = output.signature {
+        let mut bytes = vec![0u8; Signature::<E>::serialized_len()];
+        signature.write_to_slice(&mut bytes);
+        println!("Signature: {}", hex::encode(bytes));
+    }
+    if let Some(path) = args.output {
+        let key_share = serde_json::to_vec_pretty(&output.key_share).context("serialize")?;
+        std::fs::write(&path, key_share)
+            .with_context(|| format!("write key share to {}", path.display()))?;
+    }
+    Ok(())
+}
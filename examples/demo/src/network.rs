@@ -0,0 +1,368 @@
+//! Delivery of protocol messages over TCP or WebSocket
+//!
+//! Every party listens on its own address and dials all parties with lower index, so each pair of
+//! parties is connected exactly once. Dialing party introduces itself by sending its index as
+//! the first frame. Over TCP, frames are length-delimited; over WebSocket, every frame is sent
+//! as a binary message.
+//!
+//! Several protocols are run over the same connections one after another, so every message is
+//! tagged with a _phase_ (e.g. `"keygen"`), and [`Network::party`] returns a party which only
+//! sends and receives messages of one phase. Messages of the phase that's not started yet (e.g.
+//! when other party completed the previous protocol faster) are kept until it starts.
+
+use std::collections::HashMap;
+use std::io;
+use std::marker::PhantomData;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, ensure, Context as _, Result};
+use bytes::Bytes;
+use futures::channel::mpsc;
+use futures::{future, Sink, SinkExt, Stream, StreamExt};
+use round_based::{
+    Delivery, Incoming, MessageDestination, MessageType, MpcParty, Outgoing, PartyIndex,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+/// Delay between attempts to dial a party which is not listening yet
+const RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// Transport used to connect the parties
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    /// Plain TCP with length-delimited frames
+    Tcp,
+    /// WebSocket over TCP
+    WebSocket,
+}
+
+impl std::str::FromStr for Transport {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tcp" => Ok(Self::Tcp),
+            "ws" => Ok(Self::WebSocket),
+            _ => Err(format!("unknown transport `{s}`, expected one of: tcp, ws")),
+        }
+    }
+}
+
+type FrameSink = Pin<Box<dyn Sink<Bytes, Error = io::Error> + Send>>;
+type FrameStream = Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send>>;
+
+/// Message as it's sent over the wire
+#[derive(Serialize, Deserialize)]
+struct Frame {
+    phase: String,
+    broadcast: bool,
+    msg: serde_json::Value,
+}
+
+/// Parties connected to each other
+pub struct Network {
+    i: PartyIndex,
+    n: u16,
+    /// `peers[j]` sends frames to $j$-th party, `None` for local party
+    peers: Vec<Option<mpsc::UnboundedSender<Bytes>>>,
+    router: Arc<Router>,
+    writers: Vec<JoinHandle<()>>,
+    readers: Vec<JoinHandle<()>>,
+}
+
+impl Network {
+    /// Connects local party `i` to all other parties
+    ///
+    /// `addrs[j]` is address of $j$-th party, `listener` must be bound to `addrs[i]`. Waits
+    /// until all parties are connected, which may take up to `timeout`.
+    pub async fn connect(
+        transport: Transport,
+        i: PartyIndex,
+        listener: TcpListener,
+        addrs: &[SocketAddr],
+        timeout: Duration,
+    ) -> Result<Self> {
+        let n = u16::try_from(addrs.len()).context("too many parties")?;
+        ensure!(
+            i < n,
+            "party index {i} is out of bounds (must be < n = {n})"
+        );
+
+        // Parties with higher index dial us, we dial parties with lower index
+        let accept = async {
+            let mut connections = vec![];
+            while connections.len() < usize::from(n - i - 1) {
+                let (stream, _) = listener.accept().await.context("accept connection")?;
+                let (mut sink, mut stream) = open(transport, stream, None).await?;
+                let hello = stream
+                    .next()
+                    .await
+                    .ok_or_else(|| anyhow!("connection closed before handshake"))?
+                    .context("receive handshake")?;
+                let j = <[u8; 2]>::try_from(hello.as_ref())
+                    .map(u16::from_be_bytes)
+                    .map_err(|_| anyhow!("invalid handshake"))?;
+                if j <= i || j >= n || connections.iter().any(|(k, ..)| *k == j) {
+                    // Unexpected party, e.g. a leftover process from the previous run
+                    let _ = sink.close().await;
+                    continue;
+                }
+                connections.push((j, sink, stream));
+            }
+            Ok::<_, anyhow::Error>(connections)
+        };
+        let dial = async {
+            let mut connections = vec![];
+            for j in 0..i {
+                let addr = addrs[usize::from(j)];
+                let stream = loop {
+                    match TcpStream::connect(addr).await {
+                        Ok(stream) => break stream,
+                        Err(_) => tokio::time::sleep(RETRY_DELAY).await,
+                    }
+                };
+                let (mut sink, stream) = open(transport, stream, Some(addr)).await?;
+                sink.send(Bytes::copy_from_slice(&i.to_be_bytes()))
+                    .await
+                    .context("send handshake")?;
+                connections.push((j, sink, stream));
+            }
+            Ok::<_, anyhow::Error>(connections)
+        };
+        let (accepted, dialed) = tokio::time::timeout(timeout, future::try_join(accept, dial))
+            .await
+            .map_err(|_| anyhow!("couldn't connect to all parties within {timeout:?}"))??;
+
+        let router = Arc::new(Router::default());
+        let mut network = Self {
+            i,
+            n,
+            peers: (0..n).map(|_| None).collect(),
+            router: router.clone(),
+            writers: vec![],
+            readers: vec![],
+        };
+        for (j, mut sink, mut stream) in accepted.into_iter().chain(dialed) {
+            let (sender, receiver) = mpsc::unbounded();
+            network.peers[usize::from(j)] = Some(sender);
+            network.writers.push(tokio::spawn(async move {
+                let _ = sink.send_all(&mut receiver.map(Ok)).await;
+                let _ = sink.close().await;
+            }));
+            let router = router.clone();
+            network.readers.push(tokio::spawn(async move {
+                while let Some(Ok(bytes)) = stream.next().await {
+                    match serde_json::from_slice::<Frame>(&bytes) {
+                        Ok(frame) => router.dispatch(j, frame),
+                        Err(err) => eprintln!("party {j} sent malformed frame: {err}"),
+                    }
+                }
+            }));
+        }
+        Ok(network)
+    }
+
+    /// Index of local party
+    pub fn i(&self) -> PartyIndex {
+        self.i
+    }
+
+    /// Amount of parties
+    pub fn n(&self) -> u16 {
+        self.n
+    }
+
+    /// Returns party which sends and receives messages of the `phase`
+    ///
+    /// Each phase can be started only once.
+    pub fn party<M>(&self, phase: &str) -> Result<MpcParty<M, impl Delivery<M>>>
+    where
+        M: Serialize + DeserializeOwned + Send + 'static,
+    {
+        let Some(incomings) = self.router.subscribe(phase) else {
+            bail!("phase `{phase}` was already started");
+        };
+        let incomings = incomings.map(|incoming: Incoming<serde_json::Value>| {
+            incoming
+                .try_map(serde_json::from_value)
+                .map_err(io::Error::from)
+        });
+        let outgoings = Outgoings {
+            phase: phase.to_owned(),
+            peers: self.peers.clone(),
+            _msg: PhantomData,
+        };
+        Ok(MpcParty::connected((incomings, outgoings)))
+    }
+
+    /// Sends all queued messages and closes connections
+    pub async fn shutdown(self) {
+        for peer in self.peers.iter().flatten() {
+            peer.close_channel();
+        }
+        for writer in self.writers {
+            let _ = writer.await;
+        }
+        for reader in self.readers {
+            reader.abort();
+        }
+    }
+}
+
+/// Establishes the connection over the `transport`
+///
+/// `addr` is address of the remote party if local party is dialing it, `None` if connection was
+/// accepted.
+async fn open(
+    transport: Transport,
+    stream: TcpStream,
+    addr: Option<SocketAddr>,
+) -> Result<(FrameSink, FrameStream)> {
+    stream.set_nodelay(true).context("set nodelay")?;
+    match transport {
+        Transport::Tcp => {
+            let (sink, stream) = Framed::new(stream, LengthDelimitedCodec::new()).split();
+            let stream = stream.map(|frame| frame.map(|frame| frame.freeze()));
+            Ok((Box::pin(sink), Box::pin(stream)))
+        }
+        Transport::WebSocket => {
+            let ws = match addr {
+                Some(addr) => {
+                    tokio_tungstenite::client_async(format!("ws://{addr}/"), stream)
+                        .await
+                        .context("websocket handshake")?
+                        .0
+                }
+                None => tokio_tungstenite::accept_async(stream)
+                    .await
+                    .context("websocket handshake")?,
+            };
+            let (sink, stream) = ws.split();
+            let sink = sink
+                .sink_map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+                .with(|frame: Bytes| {
+                    future::ready(Ok::<_, io::Error>(WsMessage::Binary(frame.to_vec())))
+                });
+            let stream = stream.filter_map(|msg| {
+                future::ready(match msg {
+                    Ok(WsMessage::Binary(frame)) => Some(Ok(Bytes::from(frame))),
+                    // Pings are answered by tungstenite, other messages are not used
+                    Ok(_) => None,
+                    Err(err) => Some(Err(io::Error::new(io::ErrorKind::Other, err))),
+                })
+            });
+            Ok((Box::pin(sink), Box::pin(stream)))
+        }
+    }
+}
+
+/// Dispatches received messages to the phases
+#[derive(Default)]
+struct Router {
+    phases: Mutex<HashMap<String, Phase>>,
+    next_id: AtomicU64,
+}
+
+struct Phase {
+    sender: mpsc::UnboundedSender<Incoming<serde_json::Value>>,
+    /// `None` if phase is already started
+    receiver: Option<mpsc::UnboundedReceiver<Incoming<serde_json::Value>>>,
+}
+
+impl Router {
+    fn with_phase<R>(&self, phase: &str, f: impl FnOnce(&mut Phase) -> R) -> R {
+        let mut phases = self
+            .phases
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let phase = phases.entry(phase.to_owned()).or_insert_with(|| {
+            let (sender, receiver) = mpsc::unbounded();
+            Phase {
+                sender,
+                receiver: Some(receiver),
+            }
+        });
+        f(phase)
+    }
+
+    fn dispatch(&self, sender: PartyIndex, frame: Frame) {
+        let incoming = Incoming {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            sender,
+            msg_type: if frame.broadcast {
+                MessageType::Broadcast
+            } else {
+                MessageType::P2P
+            },
+            msg: frame.msg,
+        };
+        // Sending fails only if the phase is completed, late messages can be ignored
+        let _ = self.with_phase(&frame.phase, |phase| phase.sender.unbounded_send(incoming));
+    }
+
+    fn subscribe(
+        &self,
+        phase: &str,
+    ) -> Option<mpsc::UnboundedReceiver<Incoming<serde_json::Value>>> {
+        self.with_phase(phase, |phase| phase.receiver.take())
+    }
+}
+
+/// Sends messages of one phase
+struct Outgoings<M> {
+    phase: String,
+    peers: Vec<Option<mpsc::UnboundedSender<Bytes>>>,
+    _msg: PhantomData<fn(M)>,
+}
+
+impl<M: Serialize> Sink<Outgoing<M>> for Outgoings<M> {
+    type Error = io::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, outgoing: Outgoing<M>) -> Result<(), Self::Error> {
+        let frame = Frame {
+            phase: self.phase.clone(),
+            broadcast: outgoing.recipient.is_broadcast(),
+            msg: serde_json::to_value(&outgoing.msg)?,
+        };
+        let frame = Bytes::from(serde_json::to_vec(&frame)?);
+        let closed = |_| io::Error::new(io::ErrorKind::BrokenPipe, "connection is closed");
+        match outgoing.recipient {
+            MessageDestination::AllParties => {
+                for peer in self.peers.iter().flatten() {
+                    peer.unbounded_send(frame.clone()).map_err(closed)?;
+                }
+            }
+            MessageDestination::OneParty(j) => {
+                let peer = self
+                    .peers
+                    .get(usize::from(j))
+                    .and_then(Option::as_ref)
+                    .ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidInput, format!("unknown party {j}"))
+                    })?;
+                peer.unbounded_send(frame).map_err(closed)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,22 @@
+#![no_main]
+
+use cggmp21::{key_refresh::AuxOnlyMsg, security_level::SecurityLevel128, ExecutionId};
+use libfuzzer_sys::fuzz_target;
+use sha2::Sha256;
+
+fuzz_target!(|data: &[u8]| {
+    cggmp21_fuzz::feed_messages::<AuxOnlyMsg<Sha256, SecurityLevel128>, _, _>(
+        data,
+        |party| async move {
+            let mut rng = cggmp21_fuzz::rng();
+            let primes = cggmp21_tests::CACHED_PRIMES
+                .iter::<SecurityLevel128>()
+                .next()
+                .expect("no cached primes");
+            cggmp21::aux_info_gen(ExecutionId::new(b"fuzz"), 0, 2, primes)
+                .dangerous_allow_blum_primes(true)
+                .start(&mut rng, party)
+                .await
+        },
+    );
+});
@@ -0,0 +1,19 @@
+#![no_main]
+
+use cggmp21::{
+    key_share::{DirtyAuxInfo, DirtyKeyShare, Validate},
+    security_level::SecurityLevel128,
+    supported_curves::Secp256k1,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(key_share) =
+        serde_json::from_slice::<DirtyKeyShare<Secp256k1, SecurityLevel128>>(data)
+    {
+        let _ = key_share.validate();
+    }
+    if let Ok(aux) = serde_json::from_slice::<DirtyAuxInfo<SecurityLevel128>>(data) {
+        let _ = aux.validate();
+    }
+});
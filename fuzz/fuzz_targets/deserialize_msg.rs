@@ -0,0 +1,33 @@
+#![no_main]
+
+use cggmp21::{
+    key_refresh::{AuxOnlyMsg, NonThresholdMsg as KeyRefreshMsg},
+    keygen::{NonThresholdMsg, ThresholdMsg},
+    security_level::SecurityLevel128,
+    signing::msg::Msg as SigningMsg,
+    supported_curves::{Secp256k1, Secp256r1, Stark},
+};
+use libfuzzer_sys::fuzz_target;
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::Sha256;
+
+fn roundtrip<T: Serialize + DeserializeOwned>(data: &[u8]) {
+    if let Ok(msg) = serde_json::from_slice::<T>(data) {
+        let serialized = serde_json::to_vec(&msg).expect("message can't be serialized");
+        let _: T = serde_json::from_slice(&serialized).expect("serialized message can't be parsed");
+    }
+}
+
+fn all_messages<E: generic_ec::Curve>(data: &[u8]) {
+    roundtrip::<NonThresholdMsg<E, SecurityLevel128, Sha256>>(data);
+    roundtrip::<ThresholdMsg<E, SecurityLevel128, Sha256>>(data);
+    roundtrip::<KeyRefreshMsg<E, Sha256, SecurityLevel128>>(data);
+    roundtrip::<SigningMsg<E, Sha256>>(data);
+}
+
+fuzz_target!(|data: &[u8]| {
+    roundtrip::<AuxOnlyMsg<Sha256, SecurityLevel128>>(data);
+    all_messages::<Secp256k1>(data);
+    all_messages::<Secp256r1>(data);
+    all_messages::<Stark>(data);
+});
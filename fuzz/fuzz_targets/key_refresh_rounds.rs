@@ -0,0 +1,28 @@
+#![no_main]
+
+use cggmp21::{
+    key_refresh::NonThresholdMsg, security_level::SecurityLevel128, supported_curves::Secp256k1,
+    ExecutionId,
+};
+use libfuzzer_sys::fuzz_target;
+use sha2::Sha256;
+
+fuzz_target!(|data: &[u8]| {
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, 2, false)
+        .expect("retrieve cached shares");
+    cggmp21_fuzz::feed_messages::<NonThresholdMsg<Secp256k1, Sha256, SecurityLevel128>, _, _>(
+        data,
+        |party| async move {
+            let mut rng = cggmp21_fuzz::rng();
+            let primes = cggmp21_tests::CACHED_PRIMES
+                .iter::<SecurityLevel128>()
+                .next()
+                .expect("no cached primes");
+            cggmp21::key_refresh(ExecutionId::new(b"fuzz"), &shares[0], primes)
+                .dangerous_allow_blum_primes(true)
+                .start(&mut rng, party)
+                .await
+        },
+    );
+});
@@ -0,0 +1,21 @@
+#![no_main]
+
+use cggmp21::{
+    keygen::ThresholdMsg, security_level::SecurityLevel128, supported_curves::Secp256k1,
+    ExecutionId,
+};
+use libfuzzer_sys::fuzz_target;
+use sha2::Sha256;
+
+fuzz_target!(|data: &[u8]| {
+    cggmp21_fuzz::feed_messages::<ThresholdMsg<Secp256k1, SecurityLevel128, Sha256>, _, _>(
+        data,
+        |party| async move {
+            let mut rng = cggmp21_fuzz::rng();
+            cggmp21::keygen::<Secp256k1>(ExecutionId::new(b"fuzz"), 0, 2)
+                .set_threshold(2)
+                .start(&mut rng, party)
+                .await
+        },
+    );
+});
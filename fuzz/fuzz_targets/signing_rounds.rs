@@ -0,0 +1,23 @@
+#![no_main]
+
+use cggmp21::{
+    security_level::SecurityLevel128,
+    signing::{msg::Msg, DataToSign},
+    supported_curves::Secp256k1,
+    ExecutionId,
+};
+use libfuzzer_sys::fuzz_target;
+use sha2::Sha256;
+
+fuzz_target!(|data: &[u8]| {
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, 2, false)
+        .expect("retrieve cached shares");
+    cggmp21_fuzz::feed_messages::<Msg<Secp256k1, Sha256>, _, _>(data, |party| async move {
+        let mut rng = cggmp21_fuzz::rng();
+        let data_to_sign = DataToSign::digest::<Sha256>(b"fuzz");
+        cggmp21::signing(ExecutionId::new(b"fuzz"), 0, &[0, 1], &shares[0])
+            .sign(&mut rng, party, data_to_sign)
+            .await
+    });
+});
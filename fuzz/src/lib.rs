@@ -0,0 +1,55 @@
+//! Helpers shared by fuzz targets
+
+use std::convert::Infallible;
+use std::future::Future;
+
+use cggmp21::co_signer::Inbound;
+use futures::stream;
+use round_based::{Incoming, MessageType, MpcParty, Outgoing};
+use serde::de::DeserializeOwned;
+
+/// Delivery which feeds the party with predefined messages and discards all its outgoing messages
+pub type Delivery<M> = (
+    stream::Iter<std::vec::IntoIter<Result<Incoming<M>, Infallible>>>,
+    futures::sink::Drain<Outgoing<M>>,
+);
+
+/// Parses `data` as a JSON list of [`Inbound`] messages, and runs the protocol with party
+/// receiving exactly these messages
+///
+/// Does nothing if `data` can't be parsed. Output of the protocol is ignored: protocol is only
+/// expected to terminate without panicking.
+pub fn feed_messages<M, F, Fut>(data: &[u8], protocol: F)
+where
+    M: DeserializeOwned,
+    F: FnOnce(MpcParty<M, Delivery<M>>) -> Fut,
+    Fut: Future,
+{
+    let Ok(inbound) = serde_json::from_slice::<Vec<Inbound<M>>>(data) else {
+        return;
+    };
+    let incomings = inbound
+        .into_iter()
+        .map(|msg| {
+            Ok(Incoming {
+                id: msg.id,
+                sender: msg.sender,
+                msg_type: if msg.broadcast {
+                    MessageType::Broadcast
+                } else {
+                    MessageType::P2P
+                },
+                msg: msg.msg,
+            })
+        })
+        .collect::<Vec<_>>();
+    let party = MpcParty::connected((stream::iter(incomings), futures::sink::drain()));
+    let _ = futures::executor::block_on(protocol(party));
+}
+
+/// Deterministic randomness source
+///
+/// Fuzzing must be reproducible, so all targets use fixed seed.
+pub fn rng() -> rand_chacha::ChaCha20Rng {
+    rand_core::SeedableRng::seed_from_u64(0xf022)
+}
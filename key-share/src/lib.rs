@@ -25,9 +25,10 @@ extern crate alloc;
 use alloc::vec::Vec;
 use core::ops;
 
+use self::poly::lagrange_coefficient;
 use generic_ec::{serde::CurveName, Curve, NonZero, Point, Scalar, SecretScalar};
-use generic_ec_zkp::polynomial::lagrange_coefficient;
 
+pub mod poly;
 #[cfg(feature = "serde")]
 mod serde_fix;
 #[cfg(feature = "spof")]
@@ -35,7 +36,7 @@ pub mod trusted_dealer;
 mod utils;
 mod valid;
 
-pub use self::valid::{Valid, Validate, ValidateError, ValidateFromParts};
+pub use self::valid::{IntoValidParts, Valid, Validate, ValidateError, ValidateFromParts};
 
 /// Core key share
 ///
@@ -123,6 +124,12 @@ pub struct DirtyCoreKeyShare<E: Curve> {
     pub key_info: DirtyKeyInfo<E>,
     /// Secret share $x_i$
     pub x: NonZero<SecretScalar<E>>,
+    /// Additional secret shares of local party, if key is shared using weighted scheme
+    ///
+    /// `extra_x[k]` corresponds to additional share with preimage
+    /// [`WeightedShares::I`]`[i][k]`. Empty if key is not weighted, or if local party has
+    /// weight 1.
+    pub extra_x: Vec<NonZero<SecretScalar<E>>>,
 }
 
 #[cfg(feature = "serde")]
@@ -144,6 +151,7 @@ impl<E: Curve> serde::Serialize for DirtyCoreKeyShare<E> {
                     chain_code,
                 },
             x,
+            extra_x,
         } = &self;
         serde_fix::ser::CoreKeyShare {
             i,
@@ -152,6 +160,7 @@ impl<E: Curve> serde::Serialize for DirtyCoreKeyShare<E> {
             public_shares,
             vss_setup,
             x,
+            extra_x,
             #[cfg(feature = "hd-wallets")]
             chain_code,
         }
@@ -173,6 +182,7 @@ impl<'de, E: Curve> serde::Deserialize<'de> for DirtyCoreKeyShare<E> {
             public_shares,
             vss_setup,
             x,
+            extra_x,
             #[cfg(feature = "hd-wallets")]
             chain_code,
         } = serde::Deserialize::deserialize(deserializer)?;
@@ -187,6 +197,7 @@ impl<'de, E: Curve> serde::Deserialize<'de> for DirtyCoreKeyShare<E> {
                 chain_code,
             },
             x,
+            extra_x,
         })
     }
 }
@@ -243,7 +254,8 @@ pub struct DirtyKeyInfo<E: Curve> {
 pub struct VssSetup<E: Curve> {
     /// Threshold parameter
     ///
-    /// Specifies how many signers are required to perform signing
+    /// Specifies how many signers are required to perform signing. If key is
+    /// [weighted](Self::weighted), specifies minimal total weight of signers.
     pub min_signers: u16,
     /// Key shares indexes
     ///
@@ -253,6 +265,126 @@ pub struct VssSetup<E: Curve> {
         serde(with = "As::<Vec<generic_ec::serde::PreferCompact>>")
     )]
     pub I: Vec<NonZero<Scalar<E>>>,
+    /// Additional shares held by the signers, present if key is shared using weighted scheme
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub weighted: Option<WeightedShares<E>>,
+    /// Feldman commitments of the polynomial the key is shared with
+    ///
+    /// `commitments[k]` is $a_k \cdot G$, where $a_k$ is $k$-th coefficient of polynomial
+    /// $F(x) = \sum_k a_k x^k$ such that $x_j = F(I_j)$. Present if key share was produced by
+    /// threshold keygen or trusted dealer that recorded the commitments. Any share of the key can be
+    /// verified against them, see [`verify_public_share`](Self::verify_public_share).
+    ///
+    /// Commitments are fully determined by the public shares, so they're not mixed into the
+    /// digest of the key info.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none"),
+        serde(with = "As::<Option<Vec<generic_ec::serde::Compact>>>")
+    )]
+    #[cfg_attr(feature = "udigest", udigest(skip))]
+    pub commitments: Option<Vec<Point<E>>>,
+}
+
+/// Additional shares of weighted secret sharing
+///
+/// In weighted secret sharing, $\ith$ signer with weight $w_i$ holds $w_i$ shares of the secret
+/// polynomial: share $F(I_i)$ and $w_i - 1$ additional shares. Any set of signers with total weight
+/// at least [`min_signers`](VssSetup::min_signers) can sign.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+#[cfg_attr(feature = "udigest", derive(udigest::Digestable))]
+pub struct WeightedShares<E: Curve> {
+    /// Preimages of additional shares
+    ///
+    /// `I[i]` lists preimages of additional shares held by $\ith$ signer
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "As::<Vec<Vec<generic_ec::serde::PreferCompact>>>")
+    )]
+    pub I: Vec<Vec<NonZero<Scalar<E>>>>,
+    /// Public commitments of additional shares
+    ///
+    /// `public_shares[i][k]` is a public commitment of share with preimage `I[i][k]`
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "As::<Vec<Vec<generic_ec::serde::Compact>>>")
+    )]
+    pub public_shares: Vec<Vec<NonZero<Point<E>>>>,
+}
+
+impl<E: Curve> VssSetup<E> {
+    /// Returns weight of $\jth$ signer
+    ///
+    /// Weight is 1 for non-weighted keys. Returns `None` if `j` is out of bounds.
+    pub fn weight(&self, j: u16) -> Option<u16> {
+        let j = usize::from(j);
+        if j >= self.I.len() {
+            return None;
+        }
+        match &self.weighted {
+            Some(weighted) => {
+                let extra = weighted.I.get(j)?.len();
+                u16::try_from(extra).ok()?.checked_add(1)
+            }
+            None => Some(1),
+        }
+    }
+
+    /// Returns preimages and public commitments of all shares held by $\jth$ signer
+    ///
+    /// `public_shares` are public shares of the key. Returns `None` if `j` is out of bounds.
+    pub fn shares_of(
+        &self,
+        j: u16,
+        public_shares: &[NonZero<Point<E>>],
+    ) -> Option<Vec<(NonZero<Scalar<E>>, NonZero<Point<E>>)>> {
+        let j = usize::from(j);
+        let mut shares = alloc::vec![(*self.I.get(j)?, *public_shares.get(j)?)];
+        if let Some(weighted) = &self.weighted {
+            let I = weighted.I.get(j)?;
+            let X = weighted.public_shares.get(j)?;
+            if I.len() != X.len() {
+                return None;
+            }
+            shares.extend(I.iter().copied().zip(X.iter().copied()));
+        }
+        Some(shares)
+    }
+
+    /// Evaluates committed polynomial at point `x`, i.e. returns $F(x) \cdot G$
+    ///
+    /// Returns `None` if [commitments](Self::commitments) are not present.
+    pub fn committed_value(&self, x: &Scalar<E>) -> Option<Point<E>> {
+        let commitments = self.commitments.as_ref()?;
+        Some(
+            commitments
+                .iter()
+                .rev()
+                .fold(Point::zero(), |acc, a_k| acc * x + a_k),
+        )
+    }
+
+    /// Verifies public share of $\jth$ signer against [commitments](Self::commitments)
+    ///
+    /// For weighted keys, only the share at $I_j$ is verified, additional shares can be verified
+    /// using [`committed_value`](Self::committed_value). Returns `None` if commitments are not
+    /// present or `j` is out of bounds.
+    pub fn verify_public_share(&self, j: u16, public_share: &Point<E>) -> Option<bool> {
+        let I_j = self.I.get(usize::from(j))?;
+        Some(self.committed_value(&**I_j)? == *public_share)
+    }
+
+    /// Verifies secret share of $\jth$ signer against [commitments](Self::commitments)
+    ///
+    /// Same as [`verify_public_share`](Self::verify_public_share), but takes a secret share.
+    pub fn verify_secret_share(&self, j: u16, secret_share: &SecretScalar<E>) -> Option<bool> {
+        self.verify_public_share(j, &(Point::generator() * secret_share))
+    }
 }
 
 impl<E: Curve> Validate for DirtyCoreKeyShare<E> {
@@ -266,6 +398,7 @@ impl<E: Curve> Validate for DirtyCoreKeyShare<E> {
         if *party_public_share != Point::generator() * &self.x {
             return Err(InvalidShareReason::PartySecretShareDoesntMatchPublicShare.into());
         }
+        validate_extra_shares(&self.key_info, self.i, &self.extra_x)?;
 
         self.key_info.is_valid()?;
 
@@ -286,13 +419,48 @@ impl<E: Curve> ValidateFromParts<(u16, DirtyKeyInfo<E>, NonZero<SecretScalar<E>>
         if *party_public_share != Point::generator() * x {
             return Err(InvalidShareReason::PartySecretShareDoesntMatchPublicShare.into());
         }
+        validate_extra_shares(key_info, *i, &[])?;
 
         Ok(())
     }
 
     fn from_parts((i, key_info, x): (u16, DirtyKeyInfo<E>, NonZero<SecretScalar<E>>)) -> Self {
-        Self { i, key_info, x }
+        Self {
+            i,
+            key_info,
+            x,
+            extra_x: Vec::new(),
+        }
+    }
+}
+
+fn validate_extra_shares<E: Curve>(
+    key_info: &DirtyKeyInfo<E>,
+    i: u16,
+    extra_x: &[NonZero<SecretScalar<E>>],
+) -> Result<(), InvalidCoreShare> {
+    let weighted = key_info
+        .vss_setup
+        .as_ref()
+        .and_then(|vss_setup| vss_setup.weighted.as_ref());
+    let extra_X: &[NonZero<Point<E>>] = match weighted {
+        Some(weighted) => weighted
+            .public_shares
+            .get(usize::from(i))
+            .ok_or(InvalidShareReason::PartyIndexOutOfBounds)?,
+        None => &[],
+    };
+    if extra_X.len() != extra_x.len() {
+        return Err(InvalidShareReason::ExtraSharesLen.into());
+    }
+    if extra_X
+        .iter()
+        .zip(extra_x)
+        .any(|(X, x)| *X != Point::generator() * x)
+    {
+        return Err(InvalidShareReason::PartySecretShareDoesntMatchPublicShare.into());
     }
+    Ok(())
 }
 
 impl<E: Curve> Validate for DirtyKeyInfo<E> {
@@ -322,16 +490,37 @@ fn validate_vss_key_info<E: Curve>(
         return Err(InvalidShareReason::TooFewParties.into());
     }
 
+    if vss_setup.I.len() != usize::from(n) {
+        return Err(InvalidShareReason::ILen.into());
+    }
+
+    // For weighted keys, all shares of all signers are checked
+    let mut I = vss_setup.I.clone();
+    let mut public_shares = public_shares.to_vec();
+    if let Some(weighted) = &vss_setup.weighted {
+        if weighted.I.len() != usize::from(n) || weighted.public_shares.len() != usize::from(n) {
+            return Err(InvalidShareReason::ILen.into());
+        }
+        for (I_j, X_j) in weighted.I.iter().zip(&weighted.public_shares) {
+            if I_j.len() != X_j.len() {
+                return Err(InvalidShareReason::ILen.into());
+            }
+            I.extend_from_slice(I_j);
+            public_shares.extend_from_slice(X_j);
+        }
+    }
+    let total_weight: u16 = I
+        .len()
+        .try_into()
+        .map_err(|_| InvalidShareReason::NOverflowsU16)?;
+
     let t = vss_setup.min_signers;
     if !(2 <= t) {
         return Err(InvalidShareReason::ThresholdTooSmall.into());
     }
-    if !(t <= n) {
+    if !(t <= total_weight) {
         return Err(InvalidShareReason::ThresholdTooLarge.into());
     }
-    if vss_setup.I.len() != usize::from(n) {
-        return Err(InvalidShareReason::ILen.into());
-    }
 
     // Now we need to check that public key shares indeed form a public key.
     // We do that in two steps:
@@ -341,7 +530,7 @@ fn validate_vss_key_info<E: Curve>(
     //    and compare with the ones specified in the key share
 
     let first_t_shares = &public_shares[0..usize::from(t)];
-    let indexes = &vss_setup.I[0..usize::from(t)];
+    let indexes = &I[0..usize::from(t)];
     let interpolation = |x: Scalar<E>| {
         let lagrange_coefficients =
             (0..usize::from(t)).map(|j| lagrange_coefficient(x, j, indexes));
@@ -357,12 +546,26 @@ fn validate_vss_key_info<E: Curve>(
         return Err(InvalidShareReason::SharesDontMatchPublicKey.into());
     }
 
-    for (&j, public_share_j) in vss_setup.I.iter().zip(public_shares).skip(t.into()) {
+    for (&j, public_share_j) in I.iter().zip(&public_shares).skip(t.into()) {
         if interpolation(j.into())? != *public_share_j {
             return Err(InvalidShareReason::SharesDontMatchPublicKey.into());
         }
     }
 
+    if let Some(commitments) = &vss_setup.commitments {
+        if commitments.len() != usize::from(t) {
+            return Err(InvalidShareReason::CommitmentsLen.into());
+        }
+        if commitments[0] != *shared_public_key {
+            return Err(InvalidShareReason::SharesDontMatchCommitments.into());
+        }
+        for (I_j, public_share_j) in I.iter().zip(&public_shares) {
+            if vss_setup.committed_value(&**I_j) != Some(**public_share_j) {
+                return Err(InvalidShareReason::SharesDontMatchCommitments.into());
+            }
+        }
+    }
+
     Ok(())
 }
 
@@ -405,6 +608,76 @@ impl<E: Curve> DirtyKeyInfo<E> {
             None
         }
     }
+
+    /// Returns effective public shares of signers `S`
+    ///
+    /// `S[k]` is index of $k$-th signer. Effective public share of $k$-th signer is its public
+    /// share (or sum of its public shares, if key is weighted) multiplied at Lagrange coefficient
+    /// with respect to set of signers `S`, so that effective public shares of all signers sum up
+    /// to the [shared public key](Self::shared_public_key). These are the shares signers commit
+    /// to during the signing, so they can be used to verify partial signatures or attribute blame
+    /// outside the protocol. For keys without VSS setup, `S` must contain all the parties, and
+    /// public shares are returned as is.
+    pub fn signers_public_shares(
+        &self,
+        S: &[u16],
+    ) -> Result<Vec<NonZero<Point<E>>>, InvalidSubset> {
+        if S.iter().enumerate().any(|(k, j)| S[..k].contains(j)) {
+            return Err(InvalidSubsetReason::NotDistinct.into());
+        }
+
+        let vss_setup = match &self.vss_setup {
+            Some(vss_setup) => vss_setup,
+            None => {
+                if S.len() != self.public_shares.len() {
+                    return Err(InvalidSubsetReason::TooFewSigners {
+                        weight: S.len(),
+                        t: self.public_shares.len(),
+                    }
+                    .into());
+                }
+                let X = S
+                    .iter()
+                    .map(|&j| self.public_shares.get(usize::from(j)).copied())
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or(InvalidSubsetReason::IndexOutOfBounds)?;
+                return Ok(X);
+            }
+        };
+
+        let shares = S
+            .iter()
+            .map(|&j| vss_setup.shares_of(j, &self.public_shares))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(InvalidSubsetReason::IndexOutOfBounds)?;
+        let I = shares
+            .iter()
+            .flatten()
+            .map(|(I_jk, _)| *I_jk)
+            .collect::<Vec<_>>();
+        if I.len() < usize::from(vss_setup.min_signers) {
+            return Err(InvalidSubsetReason::TooFewSigners {
+                weight: I.len(),
+                t: vss_setup.min_signers.into(),
+            }
+            .into());
+        }
+
+        let mut lambda = (0..I.len()).map(|k| lagrange_coefficient(Scalar::zero(), k, &I));
+        let mut X = Vec::with_capacity(shares.len());
+        for shares_j in &shares {
+            let mut X_j = Point::zero();
+            for (_, X_jk) in shares_j {
+                let lambda_jk = lambda
+                    .next()
+                    .flatten()
+                    .ok_or(InvalidSubsetReason::Interpolation)?;
+                X_j = X_j + *(lambda_jk * X_jk);
+            }
+            X.push(NonZero::from_point(X_j).ok_or(InvalidSubsetReason::ZeroShare)?);
+        }
+        Ok(X)
+    }
 }
 
 #[cfg(feature = "hd-wallets")]
@@ -516,6 +789,12 @@ enum InvalidShareReason {
     ILen,
     #[displaydoc("indexes of shares in I are not pairwise distinct")]
     INotPairwiseDistinct,
+    #[displaydoc("amount of additional secret shares doesn't match weight of the party")]
+    ExtraSharesLen,
+    #[displaydoc("mismatched length of VSS commitments: commitments.len() != min_signers")]
+    CommitmentsLen,
+    #[displaydoc("public shares don't match VSS commitments")]
+    SharesDontMatchCommitments,
 }
 
 impl From<InvalidShareReason> for InvalidCoreShare {
@@ -524,6 +803,33 @@ impl From<InvalidShareReason> for InvalidCoreShare {
     }
 }
 
+/// Error indicating that set of signers is not valid
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[displaydoc("invalid set of signers")]
+pub struct InvalidSubset(#[cfg_attr(feature = "std", source)] InvalidSubsetReason);
+
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum InvalidSubsetReason {
+    #[displaydoc("signer index is out of bounds")]
+    IndexOutOfBounds,
+    #[displaydoc("signers indexes are not pairwise distinct")]
+    NotDistinct,
+    #[displaydoc("expected signers of total weight at least `t={t}`, but it's {weight}")]
+    TooFewSigners { weight: usize, t: usize },
+    #[displaydoc("share preimages are not pairwise distinct")]
+    Interpolation,
+    #[displaydoc("effective public share is zero - probability of that is negligible")]
+    ZeroShare,
+}
+
+impl From<InvalidSubsetReason> for InvalidSubset {
+    fn from(err: InvalidSubsetReason) -> Self {
+        Self(err)
+    }
+}
+
 /// Error related to HD key derivation
 #[derive(Debug, displaydoc::Display)]
 #[cfg_attr(feature = "std", derive(thiserror::Error))]
@@ -544,7 +850,8 @@ impl<T> From<ValidateError<T, InvalidCoreShare>> for InvalidCoreShare {
 /// [`min_signers`](CoreKeyShare::min_signers) key shares
 ///
 /// Requires at least [`min_signers`](CoreKeyShare::min_signers) distinct key
-/// shares. Returns error if input is invalid.
+/// shares (or key shares of total weight at least `min_signers`, if key is weighted).
+/// Returns error if input is invalid.
 ///
 /// Note that, normally, secret key is not supposed to be reconstructed, and key
 /// shares should never be at one place. This basically defeats purpose of MPC and
@@ -571,27 +878,53 @@ pub fn reconstruct_secret_key<E: Curve>(
         return Err(ReconstructErrorReason::DifferentKeyShares.into());
     }
 
-    if key_shares.len() < usize::from(t) {
-        return Err(ReconstructErrorReason::TooFewKeyShares {
-            len: key_shares.len(),
-            t,
+    if let Some(vss) = vss {
+        // Collect all shares held by provided key shares (there can be more than one per key
+        // share if key is weighted)
+        let mut I = Vec::new();
+        let mut x = Vec::new();
+        for key_share in key_shares {
+            let key_share = key_share.as_ref();
+            I.push(
+                *vss.I
+                    .get(usize::from(key_share.i))
+                    .ok_or(ReconstructErrorReason::Subset)?,
+            );
+            x.push(&key_share.x);
+            if let Some(weighted) = &vss.weighted {
+                I.extend_from_slice(
+                    weighted
+                        .I
+                        .get(usize::from(key_share.i))
+                        .ok_or(ReconstructErrorReason::Subset)?,
+                );
+                x.extend(&key_share.extra_x);
+            }
+        }
+        if I.len() != x.len() {
+            return Err(ReconstructErrorReason::DifferentKeyShares.into());
+        }
+        if I.len() < usize::from(t) {
+            return Err(ReconstructErrorReason::TooFewKeyShares { len: I.len(), t }.into());
         }
-        .into());
-    }
 
-    if let Some(VssSetup { I, .. }) = vss {
-        let S = key_shares.iter().map(|s| s.as_ref().i).collect::<Vec<_>>();
-        let I = crate::utils::subset(&S, I).ok_or(ReconstructErrorReason::Subset)?;
-        let lagrange_coefficients =
-            (0..).map(|j| generic_ec_zkp::polynomial::lagrange_coefficient(Scalar::zero(), j, &I));
+        let lagrange_coefficients = (0..).map(|j| lagrange_coefficient(Scalar::zero(), j, &I));
         let mut sk = lagrange_coefficients
-            .zip(key_shares)
-            .try_fold(Scalar::zero(), |acc, (lambda_j, key_share_j)| {
-                Some(acc + lambda_j? * &key_share_j.as_ref().x)
+            .zip(x)
+            .try_fold(Scalar::zero(), |acc, (lambda_j, x_j)| {
+                Some(acc + lambda_j? * x_j)
             })
             .ok_or(ReconstructErrorReason::Interpolation)?;
         Ok(SecretScalar::new(&mut sk))
     } else {
+        if key_shares.len() < usize::from(t) {
+            return Err(ReconstructErrorReason::TooFewKeyShares {
+                len: key_shares.len(),
+                t,
+            }
+            .into());
+        }
+
         let mut sk = key_shares
             .iter()
             .map(|s| &s.as_ref().x)
@@ -0,0 +1,140 @@
+//! Polynomial utilities
+//!
+//! Key shares of $t$-out-of-$n$ keys are evaluations of a secret polynomial $F(x)$ of degree
+//! $t-1$ at points $I_1, \dots, I_n$ (see [`VssSetup::I`](crate::VssSetup::I)), and the secret key
+//! is $F(0)$. This module provides helpers to interpolate such polynomial (or its commitment
+//! $F(x) \cdot G$) at arbitrary points, which is used, for instance, to convert key shares into
+//! additive form, recover lost shares or reshare the key.
+
+use alloc::vec::Vec;
+use core::{iter, ops};
+
+use generic_ec::{Curve, NonZero, Point, Scalar};
+
+#[doc(inline)]
+pub use generic_ec_zkp::polynomial::{lagrange_coefficient, Polynomial};
+
+/// Calculates lagrange coefficients $\lambda_0, \dots, \lambda_{k-1}$ to interpolate a polynomial
+/// at point `x` from its values at points `xs`
+///
+/// Same as calling [`lagrange_coefficient`] for every `j` in `0..xs.len()`. Returns `None` if
+/// elements of `xs` are not pairwise distinct or if `x` equals to any of them.
+pub fn lagrange_coefficients<E: Curve>(
+    x: Scalar<E>,
+    xs: &[NonZero<Scalar<E>>],
+) -> Option<Vec<NonZero<Scalar<E>>>> {
+    (0..xs.len())
+        .map(|j| lagrange_coefficient(x, j, xs))
+        .collect()
+}
+
+/// Interpolates a polynomial at point `x` given its values `ys` at points `xs`
+///
+/// `ys[j]` is a value of polynomial at `xs[j]`. Values can be scalars (e.g. secret shares) or
+/// points (e.g. public shares), and can be wrapped into [`NonZero`] or be secret. Polynomial is
+/// assumed to have degree less than `xs.len()`, otherwise the result is meaningless.
+///
+/// Returns `None` if `xs` and `ys` have different length or if elements of `xs` are not pairwise
+/// distinct.
+///
+/// ## Example
+/// Reconstruct a shared public key from public shares of $t$ signers:
+/// ```rust
+/// # fn f<E: generic_ec::Curve>(key_share: &key_share::CoreKeyShare<E>) -> Option<()> {
+/// use generic_ec::{Point, Scalar};
+/// use key_share::poly::interpolate;
+///
+/// let vss_setup = key_share.vss_setup.as_ref()?;
+/// let t = usize::from(vss_setup.min_signers);
+/// let pk: Point<E> = interpolate(
+///     Scalar::zero(),
+///     &vss_setup.I[..t],
+///     &key_share.public_shares[..t],
+/// )?;
+/// assert_eq!(pk, *key_share.shared_public_key);
+/// # Some(()) }
+/// ```
+pub fn interpolate<E: Curve, Y, O>(x: Scalar<E>, xs: &[NonZero<Scalar<E>>], ys: &[Y]) -> Option<O>
+where
+    O: iter::Sum<O>,
+    for<'y> Scalar<E>: ops::Mul<&'y Y, Output = O>,
+{
+    if xs.len() != ys.len() {
+        return None;
+    }
+    if let Some(j) = xs.iter().position(|x_j| **x_j == x) {
+        // Lagrange coefficients are not defined when `x` is one of `xs`, but the value
+        // is known anyway
+        if xs.iter().filter(|x_m| ***x_m == x).count() != 1 {
+            return None;
+        }
+        return Some(Scalar::one() * &ys[j]);
+    }
+    let lambda = lagrange_coefficients(x, xs)?;
+    Some(
+        lambda
+            .into_iter()
+            .zip(ys)
+            .map(|(lambda_j, y_j)| *lambda_j * y_j)
+            .sum(),
+    )
+}
+
+/// Returns `[list[indexes[0]], list[indexes[1]], ..., list[indexes[n-1]]]`
+///
+/// Result is `None` if any of `indexes[i]` is out of range of `list`. Typically used to select
+/// share preimages or public shares of the signers.
+pub fn subset<T: Clone, I: Into<usize> + Copy>(indexes: &[I], list: &[T]) -> Option<Vec<T>> {
+    indexes
+        .iter()
+        .map(|&i| list.get(i.into()).cloned())
+        .collect()
+}
+
+/// Evaluates polynomial at points $1, 2, \dots, n$
+///
+/// Equivalent to evaluating polynomial at each point separately, but much faster for large $n$:
+/// only first $d+1$ values are evaluated directly (where $d$ is degree of the polynomial), the
+/// rest are derived from the table of forward differences, which takes $d$ point additions per
+/// value instead of $d$ point multiplications.
+pub fn evaluate_at_consecutive_points<E: Curve>(
+    polynomial: &Polynomial<Point<E>>,
+    n: u16,
+) -> Vec<Point<E>> {
+    let degree = polynomial.coefs().len().saturating_sub(1);
+    let n = usize::from(n);
+
+    // `table[k]` is k-th forward difference of polynomial at current point
+    let mut table = (1..)
+        .take(degree + 1)
+        .map(|x: u64| polynomial.value::<_, Point<E>>(&Scalar::from(x)))
+        .collect::<Vec<_>>();
+    for level in 1..table.len() {
+        for k in (level..table.len()).rev() {
+            table[k] = table[k] - table[k - 1];
+        }
+    }
+
+    let mut values = Vec::with_capacity(n);
+    for _ in 0..n {
+        values.push(table[0]);
+        for k in 0..degree {
+            table[k] = table[k] + table[k + 1];
+        }
+    }
+    values
+}
+
+/// Converts polynomial share into additive share
+///
+/// Given share `y_j` of $j$-th signer out of set of signers with share preimages `xs`, returns
+/// $\lambda_j \cdot y_j$ such that additive shares of all the signers sum up to the shared
+/// secret (or to the shared public key, if public shares are converted). Returns `None` if `j` is
+/// out of bounds or elements of `xs` are not pairwise distinct.
+pub fn to_additive_share<E: Curve, Y, O>(j: usize, xs: &[NonZero<Scalar<E>>], y_j: &Y) -> Option<O>
+where
+    for<'y> Scalar<E>: ops::Mul<&'y Y, Output = O>,
+{
+    let lambda_j = lagrange_coefficient(Scalar::zero(), j, xs)?;
+    Some(*lambda_j * y_j)
+}
@@ -63,4 +63,7 @@ core_key_share! {
 
     #[serde(with = "As::<generic_ec::serde::Compact>")]
     pub x: NonZero<SecretScalar<E>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[serde(with = "As::<Vec<generic_ec::serde::Compact>>")]
+    pub extra_x: Vec<NonZero<SecretScalar<E>>>,
 }
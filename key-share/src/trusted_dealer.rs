@@ -109,7 +109,7 @@ impl<E: Curve> TrustedDealerBuilder<E> {
             .map(|i| generic_ec::NonZero::from_scalar(Scalar::from(i)))
             .collect::<Option<Vec<_>>>()
             .ok_or(Reason::DeriveKeyShareIndex)?;
-        let secret_shares = if let Some(t) = self.t {
+        let (secret_shares, commitments) = if let Some(t) = self.t {
             let f = generic_ec_zkp::polynomial::Polynomial::sample_with_const_term(
                 rng,
                 usize::from(t) - 1,
@@ -120,12 +120,19 @@ impl<E: Curve> TrustedDealerBuilder<E> {
                 Point::generator() * f.value::<_, Scalar<_>>(&Scalar::zero())
             );
 
-            key_shares_indexes
+            let commitments = f
+                .coefs()
+                .iter()
+                .map(|a_k| Point::generator() * &**a_k)
+                .collect::<Vec<_>>();
+
+            let shares = key_shares_indexes
                 .iter()
                 .map(|I_i| f.value(I_i))
                 .map(|mut x_i| SecretScalar::new(&mut x_i))
                 .map(|x| NonZero::from_secret_scalar(x).ok_or(Reason::ZeroShare))
-                .collect::<Result<Vec<_>, _>>()?
+                .collect::<Result<Vec<_>, _>>()?;
+            (shares, Some(commitments))
         } else {
             let mut shares = core::iter::repeat_with(|| NonZero::<SecretScalar<E>>::random(rng))
                 .take((self.n - 1).into())
@@ -140,7 +147,7 @@ impl<E: Curve> TrustedDealerBuilder<E> {
                 shared_public_key,
                 shares.iter().sum::<SecretScalar<E>>() * Point::generator()
             );
-            shares
+            (shares, None)
         };
 
         let public_shares = secret_shares
@@ -151,6 +158,8 @@ impl<E: Curve> TrustedDealerBuilder<E> {
         let vss_setup = self.t.map(|t| VssSetup {
             min_signers: t,
             I: key_shares_indexes,
+            weighted: None,
+            commitments,
         });
 
         #[cfg(feature = "hd-wallets")]
@@ -162,21 +171,26 @@ impl<E: Curve> TrustedDealerBuilder<E> {
             None
         };
 
+        // Key info is shared by all key shares, so it's validated only once. It matters when
+        // `n` is large as validation of key info takes `O(n)` time.
+        let key_info = crate::Validate::validate(crate::DirtyKeyInfo {
+            curve: Default::default(),
+            shared_public_key,
+            public_shares,
+            vss_setup,
+            #[cfg(feature = "hd-wallets")]
+            chain_code,
+        })
+        .map_err(|err| Reason::InvalidKeyShare(err.into_error()))?;
+
         Ok((0u16..)
             .zip(secret_shares)
             .map(|(i, x_i)| {
-                crate::Validate::validate(crate::DirtyCoreKeyShare::<E> {
+                crate::Valid::<crate::DirtyCoreKeyShare<E>>::from_parts((
                     i,
-                    key_info: crate::DirtyKeyInfo {
-                        curve: Default::default(),
-                        shared_public_key,
-                        public_shares: public_shares.clone(),
-                        vss_setup: vss_setup.clone(),
-                        #[cfg(feature = "hd-wallets")]
-                        chain_code,
-                    },
-                    x: x_i,
-                })
+                    key_info.clone().into_inner(),
+                    x_i,
+                ))
                 .map_err(|err| Reason::InvalidKeyShare(err.into_error()))
             })
             .collect::<Result<Vec<_>, _>>()?)
@@ -122,6 +122,32 @@ where
         }
     }
 
+    /// Deconstructs value into validated parts
+    ///
+    /// Parts are not validated again: refer to [`IntoValidParts`] trait documentation
+    pub fn into_parts<A, B>(self) -> (Valid<A>, Valid<B>)
+    where
+        T: IntoValidParts<A, B>,
+        A: Validate,
+        B: Validate,
+    {
+        let (a, b) = self.0.into_parts();
+        (Valid::from_unchecked(a), Valid::from_unchecked(b))
+    }
+
+    /// Constructs `Valid<T>` from `T`, assumes that `T` has been validated
+    ///
+    /// Performs a debug assertion that `T` is validated
+    fn from_unchecked(value: T) -> Self {
+        #[cfg(debug_assertions)]
+        #[allow(clippy::expect_used)]
+        value
+            .is_valid()
+            .expect("debug assertions: value is invalid, but was assumed to be valid");
+
+        Self(value)
+    }
+
     /// Constructs `&Valid<T>` from `&T`, assumes that `T` has been validated
     ///
     /// Performs a debug assertion that `T` is validated
@@ -272,6 +298,19 @@ pub trait ValidateFromParts<Parts>: Validate {
     fn from_parts(parts: Parts) -> Self;
 }
 
+/// Value that can be deconstructed into two validated parts
+///
+/// Counterpart of [`ValidateFromParts`]. Similarly to [transitive valideness through `AsRef`](Valid#transitive-valideness-through-asref),
+/// `Valid<T>` assumes that both parts have been validated when `T` was validated, so [`Valid::into_parts`]
+/// doesn't validate them again.
+pub trait IntoValidParts<A: Validate, B: Validate>: Validate {
+    /// Deconstructs `Self` into parts
+    ///
+    /// Note: implementation **must** guarantee that if `self.is_valid().is_ok()` then both `A::is_valid` and
+    /// `B::is_valid` succeed for the returned parts
+    fn into_parts(self) -> (A, B);
+}
+
 /// Validation error
 ///
 /// Contains an error that explains why value was considered invalid, and the value itself. It can be used
@@ -0,0 +1,301 @@
+//! Produces signatures over fixed messages and keys, and checks them with external verifiers
+//!
+//! Keys are taken from precomputed key shares, signing is run with seeded randomness, so the
+//! output is reproducible. Each signature is checked by every verifier available for the curve:
+//! verifier must accept the signature, and reject it for another message.
+use anyhow::{Context, Result};
+use cggmp21::{
+    security_level::SecurityLevel128,
+    signing::{msg::Msg, DataToSign},
+    supported_curves::{Secp256k1, Secp256r1, Stark},
+    ExecutionId, Signature,
+};
+use cggmp21_tests::external_verifier::{self, ExternalVerifier};
+use generic_ec::{coords::HasAffineX, Curve, Point};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use round_based::simulation::Simulation;
+use serde::Serialize;
+use sha2::Sha256;
+
+const MESSAGES: &[&[u8]] = &[
+    b"",
+    b"abc",
+    b"The quick brown fox jumps over the lazy dog",
+    &[0xff; 1000],
+];
+
+struct Args {
+    curves: Vec<CurveName>,
+    seed: u64,
+    format: Format,
+    output: Option<std::path::PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CurveName {
+    Secp256k1,
+    Secp256r1,
+    Stark,
+}
+
+impl std::str::FromStr for CurveName {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "secp256k1" => Ok(Self::Secp256k1),
+            "secp256r1" => Ok(Self::Secp256r1),
+            "stark" => Ok(Self::Stark),
+            _ => Err(format!(
+                "unknown curve `{s}`, expected one of: secp256k1, secp256r1, stark"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("unknown format `{s}`, expected one of: text, json")),
+        }
+    }
+}
+
+fn args() -> Args {
+    use bpaf::Parser;
+    let curves = bpaf::long("curves")
+        .help("Curves to test, comma-separated")
+        .argument::<String>("CURVES")
+        .parse(|s| s.split(',').map(std::str::FromStr::from_str).collect())
+        .fallback(vec![
+            CurveName::Secp256k1,
+            CurveName::Secp256r1,
+            CurveName::Stark,
+        ]);
+    let seed = bpaf::long("seed")
+        .help("Seed of randomness used in signing")
+        .argument::<u64>("SEED")
+        .fallback(0);
+    let format = bpaf::long("format")
+        .help("Output format of the report: text or json")
+        .argument::<Format>("FORMAT")
+        .fallback(Format::Text);
+    let output = bpaf::long("output")
+        .help("Writes report to the file instead of stdout")
+        .argument::<std::path::PathBuf>("PATH")
+        .optional();
+
+    bpaf::construct!(Args {
+        curves,
+        seed,
+        format,
+        output
+    })
+    .to_options()
+    .run()
+}
+
+#[derive(Serialize)]
+struct Report {
+    seed: u64,
+    passed: usize,
+    failed: usize,
+    cases: Vec<Case>,
+}
+
+#[derive(Serialize)]
+struct Case {
+    curve: &'static str,
+    #[serde(with = "hex")]
+    message: Vec<u8>,
+    #[serde(with = "hex")]
+    public_key: Vec<u8>,
+    #[serde(with = "hex")]
+    signature: Vec<u8>,
+    results: Vec<VerifierResult>,
+}
+
+#[derive(Serialize)]
+struct VerifierResult {
+    verifier: &'static str,
+    /// Verifier accepted the signature
+    valid: bool,
+    /// Error returned by verifier, if signature was rejected
+    error: Option<String>,
+    /// Verifier rejected the signature for another message
+    rejects_other_message: bool,
+}
+
+impl VerifierResult {
+    fn passed(&self) -> bool {
+        self.valid && self.rejects_other_message
+    }
+}
+
+type VerifyFn<E> = fn(&Point<E>, &Signature<E>, &[u8]) -> Result<()>;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    let args = args();
+
+    let mut cases = vec![];
+    for curve in &args.curves {
+        match curve {
+            CurveName::Secp256k1 => {
+                #[cfg_attr(not(feature = "openssl"), allow(unused_mut))]
+                let mut verifiers: Vec<(&'static str, VerifyFn<Secp256k1>)> = vec![
+                    (
+                        "libsecp256k1",
+                        external_verifier::blockchains::Bitcoin::verify,
+                    ),
+                    (
+                        "rustcrypto-k256",
+                        external_verifier::rustcrypto::K256::verify,
+                    ),
+                ];
+                #[cfg(feature = "openssl")]
+                verifiers.push(("openssl", external_verifier::openssl::OpenSsl::verify));
+                cases.extend(curve_cases::<Secp256k1>("secp256k1", &verifiers, args.seed).await?)
+            }
+            CurveName::Secp256r1 => {
+                #[cfg_attr(not(feature = "openssl"), allow(unused_mut))]
+                let mut verifiers: Vec<(&'static str, VerifyFn<Secp256r1>)> = vec![(
+                    "rustcrypto-p256",
+                    external_verifier::rustcrypto::P256::verify,
+                )];
+                #[cfg(feature = "openssl")]
+                verifiers.push(("openssl", external_verifier::openssl::OpenSsl::verify));
+                cases.extend(curve_cases::<Secp256r1>("secp256r1", &verifiers, args.seed).await?)
+            }
+            CurveName::Stark => {
+                let verifiers: Vec<(&'static str, VerifyFn<Stark>)> = vec![(
+                    "starknet-crypto",
+                    external_verifier::blockchains::StarkNet::verify,
+                )];
+                cases.extend(curve_cases::<Stark>("stark", &verifiers, args.seed).await?)
+            }
+        }
+    }
+
+    let results = cases.iter().flat_map(|case| &case.results);
+    let passed = results.clone().filter(|r| r.passed()).count();
+    let failed = results.filter(|r| !r.passed()).count();
+    let report = Report {
+        seed: args.seed,
+        passed,
+        failed,
+        cases,
+    };
+
+    let output = match args.format {
+        Format::Json => serde_json::to_string_pretty(&report)?,
+        Format::Text => text_report(&report),
+    };
+    match &args.output {
+        Some(path) => std::fs::write(path, output).context("write report")?,
+        None => println!("{output}"),
+    }
+
+    if report.failed > 0 {
+        anyhow::bail!("{} verification(s) failed", report.failed)
+    }
+    Ok(())
+}
+
+async fn curve_cases<E: Curve>(
+    curve: &'static str,
+    verifiers: &[(&'static str, VerifyFn<E>)],
+    seed: u64,
+) -> Result<Vec<Case>>
+where
+    Point<E>: HasAffineX<E>,
+{
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(Some(2), 3, false)
+        .context("retrieve cached shares")?;
+    let public_key = shares[0].shared_public_key;
+    let participants = &[0, 1];
+
+    let mut cases = vec![];
+    for (k, message) in (0u64..).zip(MESSAGES) {
+        let eid = format!("interop-report/{curve}/{k}");
+        let eid = ExecutionId::new(eid.as_bytes());
+        let data_to_sign = DataToSign::digest::<Sha256>(message);
+
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let outputs = (0..).zip(participants).map(|(i, &j)| {
+            let party = simulation.add_party();
+            let share = &shares[usize::from(j)];
+            let mut party_rng = ChaCha20Rng::seed_from_u64(seed ^ (k << 8) ^ u64::from(i));
+            async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .sign(&mut party_rng, party, data_to_sign)
+                    .await
+            }
+        });
+        let signature = futures::future::try_join_all(outputs)
+            .await
+            .context("signing failed")?[0];
+
+        let mut other_message = message.to_vec();
+        other_message.push(0);
+        let results = verifiers
+            .iter()
+            .map(|(name, verify)| {
+                let result = verify(&public_key, &signature, message);
+                VerifierResult {
+                    verifier: *name,
+                    valid: result.is_ok(),
+                    error: result.err().map(|err| format!("{err:#}")),
+                    rejects_other_message: verify(&public_key, &signature, &other_message).is_err(),
+                }
+            })
+            .collect();
+
+        let mut signature_bytes = vec![0u8; 2 * signature.r.to_be_bytes().as_bytes().len()];
+        signature.write_to_slice(&mut signature_bytes);
+        cases.push(Case {
+            curve,
+            message: message.to_vec(),
+            public_key: public_key.to_bytes(true).as_bytes().to_vec(),
+            signature: signature_bytes,
+            results,
+        })
+    }
+    Ok(cases)
+}
+
+fn text_report(report: &Report) -> String {
+    use std::fmt::Write;
+    let mut out = String::new();
+    for case in &report.cases {
+        let _ = writeln!(
+            out,
+            "{} message={}",
+            case.curve,
+            hex::encode(&case.message[..case.message.len().min(16)])
+        );
+        for result in &case.results {
+            let status = if result.passed() { "ok" } else { "FAILED" };
+            let _ = write!(out, "  {}: {status}", result.verifier);
+            if let Some(err) = &result.error {
+                let _ = write!(out, " ({err})");
+            }
+            if !result.rejects_other_message {
+                let _ = write!(out, " (accepts signature for another message)");
+            }
+            let _ = writeln!(out);
+        }
+    }
+    let _ = write!(out, "passed: {}, failed: {}", report.passed, report.failed);
+    out
+}
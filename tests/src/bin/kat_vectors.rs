@@ -0,0 +1,80 @@
+//! Generates and checks known-answer test vectors
+//!
+//! `generate` executes every protocol from [`kat::default_set`] with seeded randomness and writes
+//! the vectors to the file. `check` replays vectors from the file and fails if any of them doesn't
+//! match, which indicates that messages or outputs of the protocol have changed.
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use cggmp21_tests::kat;
+
+const DEFAULT_PATH: &str = "./test-data/kat_vectors.json";
+
+#[derive(Clone, Debug)]
+enum Operation {
+    Generate { output: PathBuf, seed: u64 },
+    Check { input: PathBuf },
+}
+
+fn args() -> Operation {
+    use bpaf::Parser;
+    let output = bpaf::long("output")
+        .help("Path to the file where to save vectors")
+        .argument::<PathBuf>("PATH")
+        .fallback(DEFAULT_PATH.into());
+    let seed = bpaf::long("seed")
+        .help("Seed of parties randomness")
+        .argument::<u64>("SEED")
+        .fallback(0);
+    let generate = bpaf::construct!(Operation::Generate { output, seed })
+        .to_options()
+        .command("generate")
+        .help("Generates test vectors");
+
+    let input = bpaf::long("input")
+        .help("Path to the file with vectors")
+        .argument::<PathBuf>("PATH")
+        .fallback(DEFAULT_PATH.into());
+    let check = bpaf::construct!(Operation::Check { input })
+        .to_options()
+        .command("check")
+        .help("Replays test vectors and checks that answers match");
+
+    bpaf::construct!([generate, check]).to_options().run()
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    match args() {
+        Operation::Generate { output, seed } => {
+            let mut vectors = vec![];
+            for (protocol, curve, level, n, t) in kat::default_set() {
+                eprintln!("{protocol:?} {} {level:?}", curve.unwrap_or("-"));
+                vectors.push(kat::generate(protocol, curve, level, n, t, seed).await?);
+            }
+            let vectors = serde_json::to_string_pretty(&vectors)?;
+            std::fs::write(output, vectors).context("write vectors")
+        }
+        Operation::Check { input } => {
+            let vectors = std::fs::read(input).context("read vectors")?;
+            let vectors: Vec<kat::Vector> =
+                serde_json::from_slice(&vectors).context("parse vectors")?;
+            let mut failed = 0;
+            for vector in &vectors {
+                let curve = vector.curve.as_deref().unwrap_or("-");
+                let level = vector.security_level;
+                match kat::replay(vector).await {
+                    Ok(()) => eprintln!("{:?} {curve} {level:?}: ok", vector.protocol),
+                    Err(err) => {
+                        eprintln!("{:?} {curve} {level:?}: FAILED ({err:#})", vector.protocol);
+                        failed += 1;
+                    }
+                }
+            }
+            if failed > 0 {
+                bail!("{failed} vector(s) don't match")
+            }
+            Ok(())
+        }
+    }
+}
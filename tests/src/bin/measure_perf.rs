@@ -1,7 +1,7 @@
 use anyhow::Context;
 use cggmp21::{
     key_share::Validate,
-    progress::PerfProfiler,
+    progress::{PerfProfiler, PerfReport},
     security_level::{SecurityLevel, SecurityLevel128},
     signing::DataToSign,
     ExecutionId,
@@ -22,7 +22,30 @@ struct Args {
     bench_aux_data_gen: bool,
     bench_signing: bool,
     optimize_multiexp: bool,
+    batch_verification: bool,
     custom_sec_level: bool,
+    format: Format,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!(
+                "unknown format `{s}`, expected one of: text, json, csv"
+            )),
+        }
+    }
 }
 
 fn args() -> Args {
@@ -40,7 +63,14 @@ fn args() -> Args {
     let bench_aux_data_gen = bpaf::long("no-bench-aux-data-gen").switch().map(|b| !b);
     let bench_signing = bpaf::long("no-bench-signing").switch().map(|b| !b);
     let optimize_multiexp = bpaf::long("optimize-multiexp").switch();
+    let batch_verification = bpaf::long("batch-verification")
+        .help("Enables batch verification in threshold DKG, suitable for large committees. Run with and without the flag to compare duration of Feldman VSS validation stage")
+        .switch();
     let custom_sec_level = bpaf::long("custom-sec-level").switch();
+    let format = bpaf::long("format")
+        .help("Output format of performance reports: text, json, or csv. json and csv output statistics aggregated over all parties")
+        .argument::<Format>("FORMAT")
+        .fallback(Format::Text);
 
     bpaf::construct!(Args {
         n,
@@ -50,7 +80,9 @@ fn args() -> Args {
         bench_aux_data_gen,
         bench_signing,
         optimize_multiexp,
+        batch_verification,
         custom_sec_level,
+        format,
     })
     .to_options()
     .run()
@@ -115,9 +147,11 @@ async fn do_becnhmarks<L: SecurityLevel>(args: Args) {
                     .expect("non-threshold keygen failed");
 
                 if args.bench_non_threshold_keygen {
-                    println!("Non-threshold DKG");
-                    println!("{}", outputs[0].1.clone().display_io(false));
-                    println!();
+                    print_reports(
+                        args.format,
+                        "Non-threshold DKG",
+                        outputs.iter().map(|(_, report)| report),
+                    );
                 }
 
                 Some(outputs.into_iter().map(|(k, _)| k).collect())
@@ -137,6 +171,7 @@ async fn do_becnhmarks<L: SecurityLevel>(args: Args) {
                         (2 * n * n).into(),
                     );
 
+                let batch_verification = args.batch_verification;
                 let outputs = (0..n).map(|i| {
                     let party = simulation.add_party();
                     let mut party_rng = rng.fork();
@@ -146,6 +181,7 @@ async fn do_becnhmarks<L: SecurityLevel>(args: Args) {
                     async move {
                         let key_share = cggmp21::keygen(eid, i, n)
                             .set_threshold(t)
+                            .batch_verification(batch_verification)
                             .set_progress_tracer(&mut profiler)
                             .set_security_level::<L>()
                             .start(&mut party_rng, party)
@@ -160,9 +196,11 @@ async fn do_becnhmarks<L: SecurityLevel>(args: Args) {
                     .await
                     .expect("threshold keygen failed");
 
-                println!("Threshold DKG");
-                println!("{}", outputs[0].1.clone().display_io(false));
-                println!();
+                print_reports(
+                    args.format,
+                    "Threshold DKG",
+                    outputs.iter().map(|(_, report)| report),
+                );
 
                 Some(outputs.into_iter().map(|(k, _)| k).collect())
             } else {
@@ -187,6 +225,7 @@ async fn do_becnhmarks<L: SecurityLevel>(args: Args) {
 
                     async move {
                         let aux_data = cggmp21::aux_info_gen(eid, i, n, pregen)
+                            .dangerous_allow_blum_primes(true)
                             .set_progress_tracer(&mut profiler)
                             .start(&mut party_rng, party)
                             .await
@@ -201,9 +240,11 @@ async fn do_becnhmarks<L: SecurityLevel>(args: Args) {
                     .expect("key refresh failed");
 
                 if args.bench_aux_data_gen {
-                    println!("Auxiliary data generation protocol");
-                    println!("{}", outputs[0].1.clone().display_io(false));
-                    println!();
+                    print_reports(
+                        args.format,
+                        "Auxiliary data generation protocol",
+                        outputs.iter().map(|(_, report)| report),
+                    );
                 }
 
                 Some(outputs.into_iter().map(|(a, _)| a).collect())
@@ -285,11 +326,34 @@ async fn do_becnhmarks<L: SecurityLevel>(args: Args) {
                 .await
                 .expect("signing failed");
 
-            println!("Signing protocol");
-            println!("{}", perf_reports[0].clone().display_io(false));
-            println!();
+            print_reports(args.format, "Signing protocol", &perf_reports);
+        }
+    }
+}
+
+fn print_reports<'r>(
+    format: Format,
+    title: &str,
+    reports: impl IntoIterator<Item = &'r PerfReport>,
+) {
+    let reports = reports.into_iter().collect::<Vec<_>>();
+    match format {
+        Format::Text => {
+            println!("{title}");
+            println!("{}", reports[0].clone().display_io(false));
+        }
+        Format::Json => {
+            let stats = PerfReport::merge(reports).expect("reports are inconsistent");
+            let json = serde_json::json!({ "protocol": title, "stats": stats });
+            println!("{json}");
+        }
+        Format::Csv => {
+            let stats = PerfReport::merge(reports).expect("reports are inconsistent");
+            println!("# {title}");
+            print!("{}", stats.to_csv());
         }
     }
+    println!();
 }
 
 #[derive(Clone, Copy)]
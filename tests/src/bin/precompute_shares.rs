@@ -1,5 +1,6 @@
 use anyhow::{bail, Context, Result};
 use cggmp21::supported_curves::{Secp256k1, Secp256r1, Stark};
+use cggmp21::test_utils::SeededRng;
 use cggmp21::{
     security_level::{KeygenSecurityLevel, SecurityLevel128},
     trusted_dealer,
@@ -8,6 +9,12 @@ use cggmp21_tests::{generate_blum_prime, PrecomputedKeyShares, PregeneratedPrime
 use generic_ec::Curve;
 use rand::{rngs::OsRng, CryptoRng, RngCore};
 
+/// Seed from which precomputed key shares are derived
+///
+/// Given the same seed, `shares` command outputs exactly the same key shares, so test data
+/// can be regenerated and checked in a reproducible way.
+const SHARES_SEED: &[u8] = b"dfns.cggmp21.tests.precomputed_shares";
+
 fn main() -> Result<()> {
     match args() {
         Operation::GenShares => precompute_shares(),
@@ -46,7 +53,7 @@ fn args() -> Operation {
 }
 
 fn precompute_shares() -> Result<()> {
-    let mut rng = OsRng;
+    let mut rng = SeededRng::new(SHARES_SEED);
     let mut cache = PrecomputedKeyShares::empty();
 
     precompute_shares_for_curve::<Secp256r1, _>(&mut rng, &mut cache)?;
@@ -0,0 +1,181 @@
+//! Runs dudect-style timing tests of secret-dependent code paths
+//!
+//! Every operation is run on a fixed secret input and on random secret inputs, see
+//! [`cggmp21_tests::side_channel`] for the details of the statistical test. Exits with non-zero
+//! code if timings of any operation are distinguishable.
+//!
+//! Results depend on the machine, so for meaningful results run it on an idle machine and compare
+//! the reports before and after the change rather than relying on an absolute value.
+use std::hint::black_box;
+
+use cggmp21::{
+    fast_paillier, paillier_zk::IntegerExt, rug::Integer, security_level::SecurityLevel128,
+};
+use cggmp21_tests::side_channel::{self, Class, Report};
+use generic_ec::{Point, Scalar, SecretScalar};
+use generic_ec_zkp::schnorr_pok;
+use rand_dev::DevRng;
+
+type E = generic_ec::curves::Secp256k1;
+
+struct Args {
+    samples: usize,
+    ops: Vec<Op>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    PaillierDecrypt,
+    ScalarMul,
+    ScalarInvert,
+    SchnorrProve,
+}
+
+impl Op {
+    const ALL: &'static [Op] = &[
+        Op::PaillierDecrypt,
+        Op::ScalarMul,
+        Op::ScalarInvert,
+        Op::SchnorrProve,
+    ];
+}
+
+impl std::str::FromStr for Op {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "paillier-decrypt" => Ok(Self::PaillierDecrypt),
+            "scalar-mul" => Ok(Self::ScalarMul),
+            "scalar-invert" => Ok(Self::ScalarInvert),
+            "schnorr-prove" => Ok(Self::SchnorrProve),
+            _ => Err(format!(
+                "unknown operation `{s}`, expected one of: paillier-decrypt, scalar-mul, scalar-invert, schnorr-prove"
+            )),
+        }
+    }
+}
+
+fn args() -> Args {
+    use bpaf::Parser;
+    let samples = bpaf::long("samples")
+        .help("Amount of measurements per operation")
+        .argument::<usize>("N")
+        .fallback(100_000);
+    let ops = bpaf::long("ops")
+        .help("Operations to test, comma-separated")
+        .argument::<String>("OPS")
+        .parse(|s| s.split(',').map(std::str::FromStr::from_str).collect())
+        .fallback(Op::ALL.to_vec());
+
+    bpaf::construct!(Args { samples, ops }).to_options().run()
+}
+
+fn main() {
+    let args = args();
+    let mut rng = DevRng::new();
+
+    let reports = args
+        .ops
+        .iter()
+        .map(|op| match op {
+            Op::PaillierDecrypt => paillier_decrypt(&mut rng, args.samples),
+            Op::ScalarMul => scalar_mul(&mut rng, args.samples),
+            Op::ScalarInvert => scalar_invert(&mut rng, args.samples),
+            Op::SchnorrProve => schnorr_prove(&mut rng, args.samples),
+        })
+        .inspect(|report| {
+            println!(
+                "{:<20} samples: {:<8} max |t|: {:>8.2}  {}",
+                report.name,
+                report.samples,
+                report.max_t.abs(),
+                if report.leaks() { "LEAK" } else { "ok" }
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if reports.iter().any(Report::leaks) {
+        std::process::exit(1)
+    }
+}
+
+fn paillier_decrypt(rng: &mut DevRng, samples: usize) -> Report {
+    let (p, q) = cggmp21_tests::CACHED_PRIMES
+        .iter::<SecurityLevel128>()
+        .next()
+        .expect("no cached primes")
+        .split();
+    let dk = fast_paillier::DecryptionKey::from_primes(p, q).expect("invalid primes");
+    let encrypt = |rng: &mut DevRng| {
+        let x = Integer::gen_invertible(dk.n(), rng);
+        dk.encrypt_with_random(rng, &x)
+            .expect("encryption failed")
+            .0
+    };
+    let fixed = encrypt(rng);
+
+    side_channel::run(
+        "paillier-decrypt",
+        rng,
+        samples,
+        |rng, class| match class {
+            Class::Fixed => fixed.clone(),
+            Class::Random => encrypt(rng),
+        },
+        |ciphertext| {
+            black_box(dk.decrypt(ciphertext).ok());
+        },
+    )
+}
+
+fn scalar_mul(rng: &mut DevRng, samples: usize) -> Report {
+    side_channel::run(
+        "scalar-mul",
+        rng,
+        samples,
+        |rng, class| match class {
+            Class::Fixed => SecretScalar::<E>::one(),
+            Class::Random => SecretScalar::random(rng),
+        },
+        |k| {
+            black_box(Point::generator() * k);
+        },
+    )
+}
+
+fn scalar_invert(rng: &mut DevRng, samples: usize) -> Report {
+    side_channel::run(
+        "scalar-invert",
+        rng,
+        samples,
+        |rng, class| match class {
+            Class::Fixed => SecretScalar::<E>::one(),
+            Class::Random => SecretScalar::random(rng),
+        },
+        |k| {
+            black_box(k.invert());
+        },
+    )
+}
+
+fn schnorr_prove(rng: &mut DevRng, samples: usize) -> Report {
+    let challenge = schnorr_pok::Challenge {
+        nonce: Scalar::<E>::random(rng),
+    };
+    side_channel::run(
+        "schnorr-prove",
+        rng,
+        samples,
+        |rng, class| {
+            let (ephemeral, _) = schnorr_pok::prover_commits_ephemeral_secret::<E, _>(rng);
+            let secret = match class {
+                Class::Fixed => SecretScalar::<E>::one(),
+                Class::Random => SecretScalar::random(rng),
+            };
+            (ephemeral, secret)
+        },
+        |(ephemeral, secret)| {
+            black_box(schnorr_pok::prove(ephemeral, &challenge, secret));
+        },
+    )
+}
@@ -82,3 +82,139 @@ pub mod blockchains {
         }
     }
 }
+
+/// Verifiers from [RustCrypto](https://github.com/RustCrypto/elliptic-curves)
+pub mod rustcrypto {
+    use anyhow::Context;
+    use cggmp21::k256::ecdsa::signature::hazmat::PrehashVerifier;
+    use cggmp21::supported_curves::{Secp256k1, Secp256r1, Secp384r1};
+    use generic_ec::{NonZero, Point};
+    use sha2::Digest;
+
+    use super::ExternalVerifier;
+
+    /// Verifies secp256k1 signature using `k256` crate
+    pub struct K256;
+
+    impl ExternalVerifier<Secp256k1> for K256 {
+        fn verify(
+            public_key: &Point<Secp256k1>,
+            signature: &cggmp21::Signature<Secp256k1>,
+            message: &[u8],
+        ) -> anyhow::Result<()> {
+            let public_key = NonZero::from_point(*public_key).context("public key is zero")?;
+            let verifying_key = cggmp21::signing::rustcrypto::to_k256_verifying_key(&public_key);
+            let signature: cggmp21::k256::ecdsa::Signature = (*signature).into();
+            verifying_key
+                .verify_prehash(&sha2::Sha256::digest(message), &signature)
+                .context("invalid signature")
+        }
+    }
+
+    /// Verifies secp256r1 signature using `p256` crate
+    pub struct P256;
+
+    impl ExternalVerifier<Secp256r1> for P256 {
+        fn verify(
+            public_key: &Point<Secp256r1>,
+            signature: &cggmp21::Signature<Secp256r1>,
+            message: &[u8],
+        ) -> anyhow::Result<()> {
+            let public_key = NonZero::from_point(*public_key).context("public key is zero")?;
+            let verifying_key = cggmp21::signing::rustcrypto::to_p256_verifying_key(&public_key);
+            let signature: cggmp21::p256::ecdsa::Signature = (*signature).into();
+            verifying_key
+                .verify_prehash(&sha2::Sha256::digest(message), &signature)
+                .context("invalid signature")
+        }
+    }
+
+    /// Verifies secp384r1 signature using `p384` crate
+    pub struct P384;
+
+    impl ExternalVerifier<Secp384r1> for P384 {
+        fn verify(
+            public_key: &Point<Secp384r1>,
+            signature: &cggmp21::Signature<Secp384r1>,
+            message: &[u8],
+        ) -> anyhow::Result<()> {
+            let public_key = NonZero::from_point(*public_key).context("public key is zero")?;
+            let verifying_key = cggmp21::signing::rustcrypto::to_p384_verifying_key(&public_key);
+            let signature: cggmp21::p384::ecdsa::Signature = (*signature).into();
+            verifying_key
+                .verify_prehash(&sha2::Sha256::digest(message), &signature)
+                .context("invalid signature")
+        }
+    }
+}
+
+/// Verifier from OpenSSL (requires `openssl` feature and OpenSSL installed in the system)
+#[cfg(feature = "openssl")]
+pub mod openssl {
+    use anyhow::Context;
+    use cggmp21::supported_curves::{Secp256k1, Secp256r1, Secp384r1};
+    use generic_ec::{Curve, Point};
+    use openssl::{
+        bn::{BigNum, BigNumContext},
+        ec::{EcGroup, EcKey, EcPoint},
+        ecdsa::EcdsaSig,
+        nid::Nid,
+    };
+    use sha2::Digest;
+
+    use super::ExternalVerifier;
+
+    /// Verifies signature using OpenSSL
+    pub struct OpenSsl;
+
+    fn verify<E: Curve>(
+        nid: Nid,
+        public_key: &Point<E>,
+        signature: &cggmp21::Signature<E>,
+        message: &[u8],
+    ) -> anyhow::Result<()> {
+        let group = EcGroup::from_curve_name(nid).context("unknown curve")?;
+        let mut ctx = BigNumContext::new()?;
+        let point = EcPoint::from_bytes(&group, &public_key.to_bytes(true), &mut ctx)
+            .context("public key is not valid")?;
+        let key = EcKey::from_public_key(&group, &point)?;
+        let signature = EcdsaSig::from_private_components(
+            BigNum::from_slice(signature.r.to_be_bytes().as_bytes())?,
+            BigNum::from_slice(signature.s.to_be_bytes().as_bytes())?,
+        )?;
+        if !signature.verify(&sha2::Sha256::digest(message), &key)? {
+            anyhow::bail!("invalid signature")
+        }
+        Ok(())
+    }
+
+    impl ExternalVerifier<Secp256k1> for OpenSsl {
+        fn verify(
+            public_key: &Point<Secp256k1>,
+            signature: &cggmp21::Signature<Secp256k1>,
+            message: &[u8],
+        ) -> anyhow::Result<()> {
+            verify(Nid::SECP256K1, public_key, signature, message)
+        }
+    }
+
+    impl ExternalVerifier<Secp256r1> for OpenSsl {
+        fn verify(
+            public_key: &Point<Secp256r1>,
+            signature: &cggmp21::Signature<Secp256r1>,
+            message: &[u8],
+        ) -> anyhow::Result<()> {
+            verify(Nid::X9_62_PRIME256V1, public_key, signature, message)
+        }
+    }
+
+    impl ExternalVerifier<Secp384r1> for OpenSsl {
+        fn verify(
+            public_key: &Point<Secp384r1>,
+            signature: &cggmp21::Signature<Secp384r1>,
+            message: &[u8],
+        ) -> anyhow::Result<()> {
+            verify(Nid::SECP384R1, public_key, signature, message)
+        }
+    }
+}
@@ -0,0 +1,433 @@
+//! Known-answer test vectors
+//!
+//! Vector fixes inputs of the protocol execution (curve, amount of parties, threshold and seed of
+//! parties randomness) and the answer: hash of messages sent by each party, and hash of the output
+//! of each party. Key shares and primes used as inputs are taken from precomputed test data. Curves
+//! that have no precomputed shares (i.e. secp384r1) get shares dealt from precomputed primes with
+//! seeded randomness.
+//!
+//! [`replay`] re-executes the protocol from the recorded seed and compares the answer, so any change
+//! in messages or outputs (e.g. changed serialization, hashing or proof format) between versions of
+//! the library is detected.
+//!
+//! Vectors are generated and checked by `kat_vectors` binary.
+
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context as TaskContext, Poll};
+
+use anyhow::{bail, Context, Result};
+use cggmp21::{
+    key_refresh::{AuxOnlyMsg, NonThresholdMsg as KeyRefreshMsg},
+    key_share::KeyShare,
+    keygen::ThresholdMsg,
+    security_level::{SecurityLevel, SecurityLevel128, SecurityLevel128Wide},
+    signing::{msg::Msg as SigningMsg, DataToSign},
+    supported_curves::{Secp256k1, Secp256r1, Secp384r1, Stark},
+    ExecutionId,
+};
+use futures::Sink;
+use generic_ec::{coords::HasAffineX, Curve, Point};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use round_based::{
+    simulation::{MockedDelivery, MockedIncoming, MockedOutgoing, Simulation},
+    Delivery, Mpc, MpcParty, Outgoing,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Message signed in [`Protocol::Sign`] vectors
+pub const MESSAGE: &[u8] = b"cggmp21 known answer test";
+
+/// Protocol covered by the vector
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Protocol {
+    /// Threshold key generation
+    Keygen,
+    /// Auxiliary info generation
+    AuxGen,
+    /// Key refresh of non-threshold key
+    KeyRefresh,
+    /// Presignature generation
+    Presign,
+    /// Signing
+    Sign,
+}
+
+/// Security level the protocol is executed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Level {
+    /// [`SecurityLevel128`]
+    #[serde(rename = "128")]
+    L128,
+    /// [`SecurityLevel128Wide`]
+    #[serde(rename = "128-wide")]
+    L128Wide,
+}
+
+/// Known-answer test vector
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Vector {
+    /// Protocol
+    pub protocol: Protocol,
+    /// Curve, `None` for [`Protocol::AuxGen`] as it doesn't depend on the curve
+    pub curve: Option<String>,
+    /// Security level
+    pub security_level: Level,
+    /// Amount of parties
+    pub n: u16,
+    /// Threshold
+    pub t: Option<u16>,
+    /// Seed of parties randomness
+    pub seed: u64,
+    /// Answer
+    #[serde(flatten)]
+    pub answer: Answer,
+}
+
+/// Answer of the protocol execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Answer {
+    /// `transcripts[i]` is SHA-256 hash of all messages sent by $\ith$ party, in order they were sent
+    #[serde(with = "hex_list")]
+    pub transcripts: Vec<[u8; 32]>,
+    /// `outputs[i]` is SHA-256 hash of serialized output of $\ith$ party
+    #[serde(with = "hex_list")]
+    pub outputs: Vec<[u8; 32]>,
+}
+
+/// Entry of the [default set](default_set): `(protocol, curve, security level, n, t)`
+pub type Entry = (Protocol, Option<&'static str>, Level, u16, Option<u16>);
+
+/// Default set of vectors: every protocol on every curve with every security level that the curve
+/// can be used with
+pub fn default_set() -> Vec<Entry> {
+    let mut set = vec![];
+    for (curve, levels) in [
+        ("secp256k1", &[Level::L128, Level::L128Wide][..]),
+        ("secp256r1", &[Level::L128, Level::L128Wide]),
+        ("stark", &[Level::L128, Level::L128Wide]),
+        ("secp384r1", &[Level::L128Wide]),
+    ] {
+        for &level in levels {
+            set.extend([
+                (Protocol::Keygen, Some(curve), level, 3, Some(2)),
+                (Protocol::KeyRefresh, Some(curve), level, 3, None),
+                (Protocol::Presign, Some(curve), level, 3, Some(2)),
+                (Protocol::Sign, Some(curve), level, 3, Some(2)),
+            ]);
+        }
+    }
+    for level in [Level::L128, Level::L128Wide] {
+        set.push((Protocol::AuxGen, None, level, 3, None));
+    }
+    set
+}
+
+/// Executes the protocol and produces the vector
+pub async fn generate(
+    protocol: Protocol,
+    curve: Option<&str>,
+    level: Level,
+    n: u16,
+    t: Option<u16>,
+    seed: u64,
+) -> Result<Vector> {
+    use Level::*;
+    let answer = match (curve, level) {
+        (None | Some("secp256k1"), L128) => {
+            execute::<Secp256k1, SecurityLevel128>(protocol, n, t, seed).await?
+        }
+        (None | Some("secp256k1"), L128Wide) => {
+            execute::<Secp256k1, SecurityLevel128Wide>(protocol, n, t, seed).await?
+        }
+        (Some("secp256r1"), L128) => {
+            execute::<Secp256r1, SecurityLevel128>(protocol, n, t, seed).await?
+        }
+        (Some("secp256r1"), L128Wide) => {
+            execute::<Secp256r1, SecurityLevel128Wide>(protocol, n, t, seed).await?
+        }
+        (Some("stark"), L128) => execute::<Stark, SecurityLevel128>(protocol, n, t, seed).await?,
+        (Some("stark"), L128Wide) => {
+            execute::<Stark, SecurityLevel128Wide>(protocol, n, t, seed).await?
+        }
+        (Some("secp384r1"), L128Wide) => {
+            execute::<Secp384r1, SecurityLevel128Wide>(protocol, n, t, seed).await?
+        }
+        (Some(curve), level) => bail!("curve {curve} is not supported with {level:?}"),
+    };
+    Ok(Vector {
+        protocol,
+        curve: curve.map(str::to_owned),
+        security_level: level,
+        n,
+        t,
+        seed,
+        answer,
+    })
+}
+
+/// Re-executes the protocol from the recorded seed and checks that the answer matches
+pub async fn replay(vector: &Vector) -> Result<()> {
+    let replayed = generate(
+        vector.protocol,
+        vector.curve.as_deref(),
+        vector.security_level,
+        vector.n,
+        vector.t,
+        vector.seed,
+    )
+    .await?;
+    if replayed.answer.transcripts != vector.answer.transcripts {
+        bail!("transcripts don't match");
+    }
+    if replayed.answer.outputs != vector.answer.outputs {
+        bail!("outputs don't match");
+    }
+    Ok(())
+}
+
+async fn execute<E: Curve, L: SecurityLevel>(
+    protocol: Protocol,
+    n: u16,
+    t: Option<u16>,
+    seed: u64,
+) -> Result<Answer>
+where
+    Point<E>: HasAffineX<E>,
+{
+    let eid = format!("kat/{protocol:?}/{}/{n}/{t:?}/{seed}", E::CURVE_NAME);
+    let eid = ExecutionId::new(eid.as_bytes());
+    let rng = |i: u16| ChaCha20Rng::seed_from_u64(seed ^ (u64::from(i) << 48));
+
+    match protocol {
+        Protocol::Keygen => {
+            let t = t.context("threshold is required")?;
+            let mut simulation = Simulation::<ThresholdMsg<E, L, Sha256>>::new();
+            run(&mut simulation, n, |i, party| async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .start(&mut rng(i), party)
+                    .await
+            })
+            .await
+        }
+        Protocol::AuxGen => {
+            let primes = cached_primes::<L>(n)?;
+            let mut simulation = Simulation::<AuxOnlyMsg<Sha256, L>>::new();
+            run(&mut simulation, n, |i, party| {
+                let primes = primes[usize::from(i)].clone();
+                async move {
+                    cggmp21::aux_info_gen(eid, i, n, primes)
+                        .dangerous_allow_blum_primes(true)
+                        .start(&mut rng(i), party)
+                        .await
+                }
+            })
+            .await
+        }
+        Protocol::KeyRefresh => {
+            let primes = cached_primes::<L>(n)?;
+            let shares = shares::<E, L>(None, n, seed)?;
+            let mut simulation = Simulation::<KeyRefreshMsg<E, Sha256, L>>::new();
+            run(&mut simulation, n, |i, party| {
+                let primes = primes[usize::from(i)].clone();
+                let share = &shares[usize::from(i)];
+                async move {
+                    cggmp21::key_refresh(eid, share, primes)
+                        .dangerous_allow_blum_primes(true)
+                        .start(&mut rng(i), party)
+                        .await
+                }
+            })
+            .await
+        }
+        Protocol::Presign | Protocol::Sign => {
+            let t = t.context("threshold is required")?;
+            let shares = shares::<E, L>(Some(t), n, seed)?;
+            let participants = &(0..t).collect::<Vec<_>>();
+            let data_to_sign = DataToSign::digest::<Sha256>(MESSAGE);
+            let mut simulation = Simulation::<SigningMsg<E, Sha256>>::new();
+            if protocol == Protocol::Presign {
+                run(&mut simulation, t, |i, party| {
+                    let share = &shares[usize::from(i)];
+                    async move {
+                        cggmp21::signing(eid, i, participants, share)
+                            .generate_presignature(&mut rng(i), party)
+                            .await
+                    }
+                })
+                .await
+            } else {
+                run(&mut simulation, t, |i, party| {
+                    let share = &shares[usize::from(i)];
+                    async move {
+                        cggmp21::signing(eid, i, participants, share)
+                            .sign(&mut rng(i), party, data_to_sign)
+                            .await
+                    }
+                })
+                .await
+            }
+        }
+    }
+}
+
+/// Key shares of the parties
+///
+/// Taken from precomputed test data if there are shares for the curve, otherwise dealt from
+/// precomputed primes with randomness derived from `seed`
+fn shares<E: Curve, L: SecurityLevel>(
+    t: Option<u16>,
+    n: u16,
+    seed: u64,
+) -> Result<Vec<KeyShare<E, L>>> {
+    if let Ok(shares) = crate::CACHED_SHARES.get_shares::<E, L>(t, n, false) {
+        return Ok(shares);
+    }
+    let primes = cached_primes::<L>(n)?
+        .into_iter()
+        .map(|primes| primes.split())
+        .collect();
+    cggmp21::trusted_dealer::builder::<E, L>(n)
+        .set_threshold(t)
+        .set_pregenerated_primes(primes)
+        .generate_shares(&mut ChaCha20Rng::seed_from_u64(seed))
+        .context("deal key shares")
+}
+
+fn cached_primes<L: SecurityLevel>(
+    n: u16,
+) -> Result<Vec<cggmp21::key_refresh::PregeneratedPrimes<L>>> {
+    let primes = crate::CACHED_PRIMES
+        .iter::<L>()
+        .take(n.into())
+        .collect::<Vec<_>>();
+    if primes.len() != usize::from(n) {
+        bail!("not enough cached primes")
+    }
+    Ok(primes)
+}
+
+/// Runs `n` parties in the simulation, records messages they send and hashes their outputs
+async fn run<M, F, Fut, O, Err>(
+    simulation: &mut Simulation<M>,
+    n: u16,
+    mut party: F,
+) -> Result<Answer>
+where
+    M: Serialize + Clone + Send + Unpin + 'static,
+    F: FnMut(u16, RecordingParty<M>) -> Fut,
+    Fut: std::future::Future<Output = Result<O, Err>>,
+    O: Serialize,
+    Err: std::error::Error + Send + Sync + 'static,
+{
+    let mut transcripts = vec![];
+    let mut outputs = vec![];
+    for i in 0..n {
+        let (recording_party, transcript) = record(simulation.add_party());
+        transcripts.push(transcript);
+        outputs.push(party(i, recording_party));
+    }
+    let outputs = futures::future::try_join_all(outputs)
+        .await
+        .context("protocol failed")?;
+
+    Ok(Answer {
+        transcripts: transcripts
+            .iter()
+            .map(|t| t.lock().unwrap().clone().finalize().into())
+            .collect(),
+        outputs: outputs
+            .iter()
+            .map(|output| {
+                let output = serde_json::to_vec(output).context("serialize output")?;
+                Ok(Sha256::digest(output).into())
+            })
+            .collect::<Result<_>>()?,
+    })
+}
+
+type RecordingParty<M> = MpcParty<M, (MockedIncoming<M>, RecordingSink<MockedOutgoing<M>>)>;
+
+/// Wraps the party so that all messages it sends are hashed
+fn record<M>(party: MpcParty<M, MockedDelivery<M>>) -> (RecordingParty<M>, Arc<Mutex<Sha256>>)
+where
+    M: Serialize + Clone + Send + Unpin + 'static,
+{
+    let transcript = Arc::new(Mutex::new(Sha256::new()));
+    let (incomings, outgoings) = party.into_party().delivery.split();
+    let outgoings = RecordingSink {
+        inner: outgoings,
+        transcript: transcript.clone(),
+    };
+    (MpcParty::connected((incomings, outgoings)), transcript)
+}
+
+/// Sink which hashes every message before passing it to the inner sink
+struct RecordingSink<S> {
+    inner: S,
+    transcript: Arc<Mutex<Sha256>>,
+}
+
+impl<S, M> Sink<Outgoing<M>> for RecordingSink<S>
+where
+    S: Sink<Outgoing<M>> + Unpin,
+    M: Serialize,
+{
+    type Error = S::Error;
+
+    fn poll_ready(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+    ) -> Poll<Result<(), S::Error>> {
+        Pin::new(&mut self.inner).poll_ready(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Outgoing<M>) -> Result<(), S::Error> {
+        let msg = serde_json::to_vec(&item.msg).expect("serialize message");
+        {
+            let mut transcript = self.transcript.lock().unwrap();
+            transcript.update((msg.len() as u64).to_be_bytes());
+            transcript.update(&msg);
+        }
+        Pin::new(&mut self.inner).start_send(item)
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+    ) -> Poll<Result<(), S::Error>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+    ) -> Poll<Result<(), S::Error>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+mod hex_list {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(list: &[[u8; 32]], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(list.iter().map(hex::encode))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<[u8; 32]>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|h| {
+                let mut bytes = [0u8; 32];
+                hex::decode_to_slice(h, &mut bytes).map_err(D::Error::custom)?;
+                Ok(bytes)
+            })
+            .collect()
+    }
+}
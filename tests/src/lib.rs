@@ -5,6 +5,10 @@ use rand::RngCore;
 use serde_json::{Map, Value};
 
 pub mod external_verifier;
+pub mod kat;
+pub mod network;
+#[cfg(feature = "side-channel")]
+pub mod side_channel;
 
 lazy_static::lazy_static! {
     pub static ref CACHED_SHARES: PrecomputedKeyShares =
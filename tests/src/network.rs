@@ -0,0 +1,237 @@
+//! Simulation of network conditions
+//!
+//! [`NetworkSimulation`] is a drop-in replacement of [`round_based::simulation::Simulation`]
+//! which delivers messages over simulated links. Every link (from one party to another) has
+//! [`LinkConditions`]: latency, jitter, probability of reordering and probability of dropping
+//! the message. Messages are delivered by tokio tasks, so simulation must be run within tokio
+//! runtime with `time` enabled.
+//!
+//! Decisions (delays, drops, reorderings) are made with a seeded PRNG, so they're reproducible
+//! as long as parties send messages in the same order.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::channel::mpsc;
+use futures::Sink;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use round_based::{
+    Incoming, MessageDestination, MessageType, MpcParty, MsgId, Outgoing, PartyIndex,
+};
+
+/// Conditions of the link between two parties
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinkConditions {
+    /// Base delay of delivering the message
+    pub latency: Duration,
+    /// Max random delay added to the latency, uniformly distributed in `[0, jitter]`
+    pub jitter: Duration,
+    /// Probability that the message is held back so that messages sent after it overtake it
+    pub reorder_rate: f64,
+    /// Probability that the message is dropped
+    pub drop_rate: f64,
+}
+
+impl LinkConditions {
+    /// Perfect link: messages are delivered immediately, in order, and never dropped
+    pub fn perfect() -> Self {
+        Self::default()
+    }
+}
+
+/// Statistics of delivered messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NetworkStats {
+    /// Amount of messages that were delivered
+    pub delivered: usize,
+    /// Amount of messages that were dropped
+    pub dropped: usize,
+    /// Amount of messages that were held back to be reordered
+    pub reordered: usize,
+}
+
+/// Delivery of the party connected to [`NetworkSimulation`]
+pub type NetworkDelivery<M> = (
+    mpsc::UnboundedReceiver<Result<Incoming<M>, Infallible>>,
+    Outgoings<M>,
+);
+
+/// Simulation of network with configurable link conditions
+pub struct NetworkSimulation<M> {
+    network: Arc<Network<M>>,
+}
+
+struct Network<M> {
+    parties: Mutex<Vec<mpsc::UnboundedSender<Result<Incoming<M>, Infallible>>>>,
+    links: Mutex<HashMap<(PartyIndex, PartyIndex), LinkConditions>>,
+    default_link: Mutex<LinkConditions>,
+    rng: Mutex<ChaCha20Rng>,
+    next_id: Mutex<MsgId>,
+    stats: Mutex<NetworkStats>,
+}
+
+impl<M> NetworkSimulation<M>
+where
+    M: Clone + Send + 'static,
+{
+    /// Constructs a simulation with perfect links
+    ///
+    /// `seed` determines decisions made by the simulated network.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            network: Arc::new(Network {
+                parties: Mutex::new(vec![]),
+                links: Mutex::new(HashMap::new()),
+                default_link: Mutex::new(LinkConditions::perfect()),
+                rng: Mutex::new(ChaCha20Rng::seed_from_u64(seed)),
+                next_id: Mutex::new(0),
+                stats: Mutex::new(NetworkStats::default()),
+            }),
+        }
+    }
+
+    /// Sets conditions of all links which don't have conditions set via [`set_link`](Self::set_link)
+    pub fn set_default_link(&self, conditions: LinkConditions) {
+        *self.network.default_link.lock().unwrap() = conditions;
+    }
+
+    /// Sets conditions of the link from party `from` to party `to`
+    pub fn set_link(&self, from: PartyIndex, to: PartyIndex, conditions: LinkConditions) {
+        self.network
+            .links
+            .lock()
+            .unwrap()
+            .insert((from, to), conditions);
+    }
+
+    /// Adds a party into the simulation
+    pub fn add_party(&mut self) -> MpcParty<M, NetworkDelivery<M>> {
+        let (tx, rx) = mpsc::unbounded();
+        let mut parties = self.network.parties.lock().unwrap();
+        let i = PartyIndex::try_from(parties.len()).expect("too many parties");
+        parties.push(tx);
+        MpcParty::connected((
+            rx,
+            Outgoings {
+                i,
+                network: self.network.clone(),
+            },
+        ))
+    }
+
+    /// Returns statistics of messages sent so far
+    pub fn stats(&self) -> NetworkStats {
+        *self.network.stats.lock().unwrap()
+    }
+}
+
+/// Sending side of the party connected to [`NetworkSimulation`]
+pub struct Outgoings<M> {
+    i: PartyIndex,
+    network: Arc<Network<M>>,
+}
+
+impl<M> Outgoings<M>
+where
+    M: Clone + Send + 'static,
+{
+    fn route(&self, outgoing: Outgoing<M>) {
+        let network = &self.network;
+        let (recipients, msg_type) = match outgoing.recipient {
+            MessageDestination::AllParties => {
+                let n = PartyIndex::try_from(network.parties.lock().unwrap().len())
+                    .expect("too many parties");
+                let recipients = (0..n).filter(|j| *j != self.i).collect::<Vec<_>>();
+                (recipients, MessageType::Broadcast)
+            }
+            MessageDestination::OneParty(j) => (vec![j], MessageType::P2P),
+        };
+
+        for j in recipients {
+            let conditions = network
+                .links
+                .lock()
+                .unwrap()
+                .get(&(self.i, j))
+                .copied()
+                .unwrap_or_else(|| *network.default_link.lock().unwrap());
+            let id = {
+                let mut next_id = network.next_id.lock().unwrap();
+                let id = *next_id;
+                *next_id += 1;
+                id
+            };
+
+            let (dropped, reordered, delay) = {
+                let mut rng = network.rng.lock().unwrap();
+                let dropped = rng.gen_bool(conditions.drop_rate);
+                let reordered = rng.gen_bool(conditions.reorder_rate);
+                let mut delay = conditions.latency + conditions.jitter.mul_f64(rng.gen::<f64>());
+                if reordered {
+                    // Held back message arrives after the messages sent within the next
+                    // `latency + jitter` (at least 1ms)
+                    delay += (conditions.latency + conditions.jitter).max(Duration::from_millis(1));
+                }
+                (dropped, reordered, delay)
+            };
+
+            let mut stats = network.stats.lock().unwrap();
+            if dropped {
+                stats.dropped += 1;
+                continue;
+            }
+            stats.delivered += 1;
+            if reordered {
+                stats.reordered += 1;
+            }
+
+            let incoming = Incoming {
+                id,
+                sender: self.i,
+                msg_type,
+                msg: outgoing.msg.clone(),
+            };
+            let Some(recipient) = network.parties.lock().unwrap().get(usize::from(j)).cloned()
+            else {
+                continue;
+            };
+            if delay.is_zero() {
+                let _ = recipient.unbounded_send(Ok(incoming));
+            } else {
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    let _ = recipient.unbounded_send(Ok(incoming));
+                });
+            }
+        }
+    }
+}
+
+impl<M> Sink<Outgoing<M>> for Outgoings<M>
+where
+    M: Clone + Send + 'static,
+{
+    type Error = Infallible;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Outgoing<M>) -> Result<(), Self::Error> {
+        self.route(item);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
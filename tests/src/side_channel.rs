@@ -0,0 +1,198 @@
+//! Statistical timing tests in the style of [dudect]
+//!
+//! Operation under test is executed on inputs from two classes: a fixed input and random inputs.
+//! Classes are interleaved in random order, execution time of every run is measured, and Welch's
+//! t-test is used to decide whether distributions of timings of two classes are distinguishable.
+//! If $|t|$ exceeds [`T_THRESHOLD`], running time most likely depends on the input, i.e. the code
+//! has a data-dependent branch or memory access.
+//!
+//! Measurements are prone to outliers (interrupts, context switches), so, like [dudect], besides
+//! the test on all measurements we also run tests on measurements cropped at several percentiles,
+//! and report the largest $|t|$.
+//!
+//! Note that the library doesn't claim to be constant-time (see "Timing attacks" section in the
+//! crate docs), so the harness is meant for tracking regressions of specific code paths rather
+//! than for proving absence of leakage.
+//!
+//! [dudect]: https://eprint.iacr.org/2016/1123
+
+use std::time::Instant;
+
+use rand::{Rng, RngCore};
+
+/// $|t|$ above this value indicates that timings of two classes are distinguishable
+///
+/// The value is taken from [dudect](https://eprint.iacr.org/2016/1123).
+pub const T_THRESHOLD: f64 = 4.5;
+
+/// Amount of percentiles at which measurements are cropped
+const CROPS: usize = 10;
+
+/// Inputs are generated and measured in batches of this size
+const BATCH_SIZE: usize = 1000;
+
+/// Class of the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    /// Fixed input
+    Fixed,
+    /// Random input
+    Random,
+}
+
+impl Class {
+    fn index(self) -> usize {
+        match self {
+            Class::Fixed => 0,
+            Class::Random => 1,
+        }
+    }
+}
+
+/// Online Welch's t-test
+///
+/// Mean and variance of each class are updated with Welford's algorithm, so measurements don't
+/// need to be kept in memory.
+#[derive(Debug, Clone, Default)]
+pub struct WelchTTest {
+    n: [f64; 2],
+    mean: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl WelchTTest {
+    /// Adds a measurement of the class
+    pub fn push(&mut self, class: Class, x: f64) {
+        let i = class.index();
+        self.n[i] += 1.;
+        let delta = x - self.mean[i];
+        self.mean[i] += delta / self.n[i];
+        self.m2[i] += delta * (x - self.mean[i]);
+    }
+
+    /// Amount of measurements in both classes
+    pub fn samples(&self) -> u64 {
+        (self.n[0] + self.n[1]) as u64
+    }
+
+    /// Returns $t$ statistic
+    ///
+    /// Returns `0` if any of classes has less than two measurements, or if all measurements are
+    /// equal.
+    pub fn t(&self) -> f64 {
+        if self.n[0] < 2. || self.n[1] < 2. {
+            return 0.;
+        }
+        let var0 = self.m2[0] / (self.n[0] - 1.);
+        let var1 = self.m2[1] / (self.n[1] - 1.);
+        let denominator = (var0 / self.n[0] + var1 / self.n[1]).sqrt();
+        if denominator == 0. {
+            return 0.;
+        }
+        (self.mean[0] - self.mean[1]) / denominator
+    }
+}
+
+/// Result of the timing test
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// Name of the operation
+    pub name: &'static str,
+    /// Amount of measurements taken
+    pub samples: u64,
+    /// The largest $t$ (by absolute value) among all tests
+    pub max_t: f64,
+}
+
+impl Report {
+    /// Indicates whether timings of fixed and random inputs are distinguishable
+    pub fn leaks(&self) -> bool {
+        self.max_t.abs() > T_THRESHOLD
+    }
+}
+
+/// Runs timing test of operation `op`
+///
+/// Takes `samples` measurements. Inputs are produced by `gen_input` before measurements of the
+/// batch start, so time of input generation isn't included.
+pub fn run<R, I>(
+    name: &'static str,
+    rng: &mut R,
+    samples: usize,
+    mut gen_input: impl FnMut(&mut R, Class) -> I,
+    mut op: impl FnMut(&I),
+) -> Report
+where
+    R: RngCore,
+{
+    let mut measurements = Vec::with_capacity(samples);
+    while measurements.len() < samples {
+        let batch_size = BATCH_SIZE.min(samples - measurements.len());
+        let inputs = (0..batch_size)
+            .map(|_| {
+                let class = if rng.gen() {
+                    Class::Fixed
+                } else {
+                    Class::Random
+                };
+                let input = gen_input(rng, class);
+                (class, input)
+            })
+            .collect::<Vec<_>>();
+
+        for (class, input) in &inputs {
+            let start = Instant::now();
+            op(std::hint::black_box(input));
+            let took = start.elapsed();
+            measurements.push((*class, took.as_nanos() as f64));
+        }
+    }
+
+    analyze(name, &measurements)
+}
+
+/// Runs t-tests on measurements, including the tests on cropped measurements
+pub fn analyze(name: &'static str, measurements: &[(Class, f64)]) -> Report {
+    let thresholds = crop_thresholds(measurements);
+
+    let mut uncropped = WelchTTest::default();
+    let mut cropped = vec![WelchTTest::default(); thresholds.len()];
+    for &(class, x) in measurements {
+        uncropped.push(class, x);
+        for (test, &threshold) in cropped.iter_mut().zip(&thresholds) {
+            if x < threshold {
+                test.push(class, x);
+            }
+        }
+    }
+
+    let max_t = std::iter::once(&uncropped)
+        .chain(&cropped)
+        .map(WelchTTest::t)
+        .fold(
+            0.,
+            |max_t: f64, t| if t.abs() > max_t.abs() { t } else { max_t },
+        );
+
+    Report {
+        name,
+        samples: uncropped.samples(),
+        max_t,
+    }
+}
+
+/// Percentiles at which measurements are cropped: $1 - 0.5^{10(i+1)/\text{CROPS}}$
+fn crop_thresholds(measurements: &[(Class, f64)]) -> Vec<f64> {
+    if measurements.is_empty() {
+        return vec![];
+    }
+    let mut sorted = measurements.iter().map(|(_, x)| *x).collect::<Vec<_>>();
+    sorted.sort_by(f64::total_cmp);
+    (0..CROPS)
+        .map(|i| {
+            let p = 1. - 0.5_f64.powf(10. * (i + 1) as f64 / CROPS as f64);
+            let idx = ((sorted.len() - 1) as f64 * p) as usize;
+            sorted[idx]
+        })
+        .collect()
+}
@@ -0,0 +1,155 @@
+use cggmp21::{
+    adversarial::{self, Mutation},
+    error_code::ErrorCode,
+    rug::Integer,
+    security_level::SecurityLevel128,
+    signing::{msg::Msg, DataToSign},
+    supported_curves::Secp256k1,
+    ExecutionId,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use round_based::{simulation::Simulation, Incoming};
+use sha2::Sha256;
+
+#[tokio::test]
+async fn mutated_signing_messages_are_rejected() {
+    let mut rng = rand_dev::DevRng::new();
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, 2, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let message_to_sign = DataToSign::digest::<Sha256>(b"mutated messages");
+    let participants = &[0, 1];
+    let seed: u64 = rng.gen();
+
+    // Record messages received by party 0 in honest execution
+    let mut simulation = Simulation::<Msg<Secp256k1, Sha256>>::new();
+    let (party0, recording) = adversarial::record(simulation.add_party());
+    let party1 = simulation.add_party();
+    let mut party1_rng = rng.fork();
+    let (sig0, sig1) = futures::join!(
+        async {
+            let mut party_rng = ChaCha20Rng::seed_from_u64(seed);
+            cggmp21::signing(eid, 0, participants, &shares[0])
+                .sign(&mut party_rng, party0, message_to_sign)
+                .await
+        },
+        cggmp21::signing(eid, 1, participants, &shares[1]).sign(
+            &mut party1_rng,
+            party1,
+            message_to_sign
+        ),
+    );
+    sig0.expect("signing failed");
+    sig1.expect("signing failed");
+    let transcript = recording.transcript().unwrap();
+    assert!(!transcript.is_empty());
+
+    // Replay stale messages and a sample of other mutations
+    let mutations = adversarial::mutations(&transcript);
+    assert!(mutations
+        .iter()
+        .any(|m| matches!(m, Mutation::ReplayStale { .. })));
+    let (stale, other): (Vec<_>, Vec<_>) = mutations
+        .into_iter()
+        .partition(|m| matches!(m, Mutation::ReplayStale { .. }));
+    let mutations = stale.into_iter().chain(other.into_iter().step_by(10));
+
+    let report = adversarial::run(
+        &transcript,
+        mutations,
+        |messages: Vec<Incoming<Msg<Secp256k1, Sha256>>>| {
+            let share = &shares[0];
+            async move {
+                let mut party_rng = ChaCha20Rng::seed_from_u64(seed);
+                cggmp21::signing(eid, 0, participants, share)
+                    .sign(
+                        &mut party_rng,
+                        adversarial::replay(messages),
+                        message_to_sign,
+                    )
+                    .await
+            }
+        },
+    )
+    .await
+    .unwrap();
+
+    assert!(report.total > 0);
+    assert_eq!(report.total, report.rejected + report.unparseable);
+    report.ensure_all_rejected().unwrap();
+}
+
+#[test_case::case(|_nn| Integer::ZERO; "zero")]
+#[test_case::case(|nn| nn.clone(); "nn")]
+#[tokio::test]
+async fn malformed_ciphertext_is_rejected(malformed: fn(&Integer) -> Integer) {
+    let mut rng = rand_dev::DevRng::new();
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, 2, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let message_to_sign = DataToSign::digest::<Sha256>(b"malformed ciphertext");
+    let participants = &[0, 1];
+    let seed: u64 = rng.gen();
+
+    // Record messages received by party 0 in honest execution
+    let mut simulation = Simulation::<Msg<Secp256k1, Sha256>>::new();
+    let (party0, recording) = adversarial::record(simulation.add_party());
+    let party1 = simulation.add_party();
+    let mut party1_rng = rng.fork();
+    let (sig0, sig1) = futures::join!(
+        async {
+            let mut party_rng = ChaCha20Rng::seed_from_u64(seed);
+            cggmp21::signing(eid, 0, participants, &shares[0])
+                .sign(&mut party_rng, party0, message_to_sign)
+                .await
+        },
+        cggmp21::signing(eid, 1, participants, &shares[1]).sign(
+            &mut party1_rng,
+            party1,
+            message_to_sign
+        ),
+    );
+    sig0.expect("signing failed");
+    sig1.expect("signing failed");
+    let transcript = recording.transcript().unwrap();
+
+    // Party 1 sends K out of range [1, N^2)
+    let nn = shares[1].aux.parties[1].N.clone().square();
+    let mut messages = transcript
+        .into_iter()
+        .map(|incoming| Incoming {
+            id: incoming.id,
+            sender: incoming.sender,
+            msg_type: incoming.msg_type,
+            msg: serde_json::from_value::<Msg<Secp256k1, Sha256>>(incoming.msg).unwrap(),
+        })
+        .collect::<Vec<_>>();
+    let round1a = messages
+        .iter_mut()
+        .find_map(|incoming| match &mut incoming.msg {
+            Msg::Round1a(msg) if incoming.sender == 1 => Some(msg),
+            _ => None,
+        })
+        .expect("round 1a message not found");
+    round1a.K = malformed(&nn);
+
+    let mut party_rng = ChaCha20Rng::seed_from_u64(seed);
+    let err = cggmp21::signing(eid, 0, participants, &shares[0])
+        .sign(
+            &mut party_rng,
+            adversarial::replay(messages),
+            message_to_sign,
+        )
+        .await
+        .unwrap_err();
+    let report = err.report();
+    assert_eq!(report.code, ErrorCode::MalformedCiphertext);
+    assert_eq!(report.parties, [1]);
+}
@@ -0,0 +1,48 @@
+use cggmp21::{
+    key_refresh::{verify_aux_proofs, AuxOnlyMsg},
+    security_level::SecurityLevel128,
+    ExecutionId,
+};
+use rand::Rng;
+use round_based::simulation::Simulation;
+use sha2::Sha256;
+
+#[tokio::test]
+async fn aux_proofs_can_be_verified_offline() {
+    let mut rng = rand_dev::DevRng::new();
+    let n = 3;
+    let mut primes = cggmp21_tests::CACHED_PRIMES.iter();
+
+    let mut simulation = Simulation::<AuxOnlyMsg<Sha256, SecurityLevel128>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let outputs = (0..n).map(|i| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        let pregenerated_data = primes.next().expect("Can't fetch primes");
+        async move {
+            cggmp21::aux_info_gen(eid, i, n, pregenerated_data)
+                .dangerous_allow_blum_primes(true)
+                .start_with_proofs(&mut party_rng, party)
+                .await
+        }
+    });
+    let outputs = futures::future::try_join_all(outputs)
+        .await
+        .expect("aux gen failed");
+
+    for (aux, proofs) in &outputs {
+        verify_aux_proofs::<SecurityLevel128, Sha256>(aux, proofs).unwrap();
+    }
+
+    // Proofs received by one party can't be verified against aux info of another party
+    assert!(verify_aux_proofs::<SecurityLevel128, Sha256>(&outputs[0].0, &outputs[1].1).is_err());
+
+    // Tampered aux data is detected
+    let (aux, proofs) = &outputs[0];
+    let mut aux = aux.clone().into_inner();
+    aux.parties[2].s = aux.parties[1].s.clone();
+    let err = verify_aux_proofs::<SecurityLevel128, Sha256>(&aux, proofs).unwrap_err();
+    assert_eq!(err.blame(), [2]);
+}
@@ -0,0 +1,117 @@
+use std::convert::Infallible;
+use std::sync::mpsc;
+
+use cggmp21::{
+    blocking::{self, Transport},
+    security_level::SecurityLevel128,
+    signing::{msg::Msg, DataToSign},
+    supported_curves::Secp256k1,
+    ExecutionId,
+};
+use rand::Rng;
+use round_based::{Incoming, MessageDestination, MessageType, Outgoing, PartyIndex};
+use sha2::Sha256;
+
+/// Transport connecting parties running in separate threads via std channels
+struct ChannelTransport<M> {
+    i: PartyIndex,
+    next_id: u64,
+    parties: Vec<mpsc::Sender<Incoming<M>>>,
+    incomings: mpsc::Receiver<Incoming<M>>,
+}
+
+impl<M: Clone> ChannelTransport<M> {
+    fn network(n: u16) -> Vec<Self> {
+        let (senders, receivers): (Vec<_>, Vec<_>) = (0..n).map(|_| mpsc::channel()).unzip();
+        receivers
+            .into_iter()
+            .zip(0..)
+            .map(|(incomings, i)| Self {
+                i,
+                next_id: 0,
+                parties: senders.clone(),
+                incomings,
+            })
+            .collect()
+    }
+}
+
+impl<M: Clone> Transport<M> for ChannelTransport<M> {
+    type SendError = Infallible;
+    type ReceiveError = Infallible;
+
+    fn send(&mut self, outgoing: Outgoing<M>) -> Result<(), Self::SendError> {
+        let (recipients, msg_type) = match outgoing.recipient {
+            MessageDestination::AllParties => (
+                (0..)
+                    .take(self.parties.len())
+                    .filter(|j| *j != self.i)
+                    .collect::<Vec<_>>(),
+                MessageType::Broadcast,
+            ),
+            MessageDestination::OneParty(j) => (vec![j], MessageType::P2P),
+        };
+        for j in recipients {
+            let incoming = Incoming {
+                id: self.next_id,
+                sender: self.i,
+                msg_type,
+                msg: outgoing.msg.clone(),
+            };
+            self.next_id += 1;
+            // Recipient might have already finished the protocol
+            let _ = self.parties[usize::from(j)].send(incoming);
+        }
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Option<Result<Incoming<M>, Self::ReceiveError>> {
+        self.incomings.recv().ok().map(Ok)
+    }
+}
+
+#[test]
+fn signing_runs_without_async_runtime() {
+    let mut rng = rand_dev::DevRng::new();
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, 3, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let message_to_sign = DataToSign::digest::<Sha256>(b"signed without async runtime");
+    let participants = &[0, 1, 2];
+
+    let transports = ChannelTransport::<Msg<Secp256k1, Sha256>>::network(3);
+    let signatures = std::thread::scope(|s| {
+        let handles = transports
+            .into_iter()
+            .zip(0..)
+            .map(|(transport, i)| {
+                let share = &shares[usize::from(i)];
+                let mut party_rng = rng.fork();
+                s.spawn(move || {
+                    blocking::run_signing_blocking(
+                        cggmp21::signing(eid, i, participants, share),
+                        &mut party_rng,
+                        transport,
+                        message_to_sign,
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap().expect("signing failed"))
+            .collect::<Vec<_>>()
+    });
+
+    let public_key = shares[0].shared_public_key;
+    for signature in &signatures {
+        signature
+            .verify(&public_key, &message_to_sign)
+            .expect("signature is not valid");
+        assert_eq!(signature.r, signatures[0].r);
+        assert_eq!(signature.s, signatures[0].s);
+    }
+}
@@ -0,0 +1,159 @@
+use std::{cell::RefCell, convert::Infallible, future::Future, rc::Rc};
+
+use cggmp21::{
+    co_signer::{CoSigner, Inbound, Outbound, Reply},
+    security_level::SecurityLevel128,
+    signing::{msg::Msg, DataToSign, Signature},
+    supported_curves::Secp256k1,
+    ExecutionId,
+};
+use futures::{
+    channel::mpsc,
+    executor::{LocalPool, LocalSpawner},
+    task::LocalSpawnExt,
+};
+use rand::Rng;
+use rand_dev::DevRng;
+use round_based::{simulation::Simulation, Incoming, MessageDestination, MessageType, Outgoing};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::Sha256;
+
+type Delivery<M> = (
+    mpsc::UnboundedReceiver<Result<Incoming<M>, Infallible>>,
+    mpsc::UnboundedSender<Outgoing<M>>,
+);
+
+/// Emulates a device: runs the protocol synchronously, one step at a time
+struct EmulatedCoSigner<M, T> {
+    pool: LocalPool,
+    inbound: mpsc::UnboundedSender<Result<Incoming<M>, Infallible>>,
+    outbound: mpsc::UnboundedReceiver<Outgoing<M>>,
+    output: Rc<RefCell<Option<T>>>,
+}
+
+impl<M: 'static, T: 'static> EmulatedCoSigner<M, T> {
+    fn new<F>(protocol: impl FnOnce(round_based::MpcParty<M, Delivery<M>>) -> F) -> Self
+    where
+        F: Future<Output = T> + 'static,
+    {
+        let (inbound, inbound_rx) = mpsc::unbounded();
+        let (outbound_tx, outbound) = mpsc::unbounded();
+        let output = Rc::new(RefCell::new(None));
+
+        let pool = LocalPool::new();
+        let spawner: LocalSpawner = pool.spawner();
+        let protocol = protocol(round_based::MpcParty::connected((inbound_rx, outbound_tx)));
+        let output_slot = output.clone();
+        spawner
+            .spawn_local(async move {
+                let out = protocol.await;
+                *output_slot.borrow_mut() = Some(out);
+            })
+            .unwrap();
+
+        Self {
+            pool,
+            inbound,
+            outbound,
+            output,
+        }
+    }
+
+    fn take_output(&self) -> Option<T> {
+        self.output.borrow_mut().take()
+    }
+}
+
+impl<M: Serialize + DeserializeOwned, T> EmulatedCoSigner<M, T> {
+    fn step(&mut self) -> Vec<u8> {
+        self.pool.run_until_stalled();
+        let mut outgoing = vec![];
+        while let Ok(Some(Outgoing { recipient, msg })) = self.outbound.try_next() {
+            let recipient = match recipient {
+                MessageDestination::OneParty(j) => Some(j),
+                MessageDestination::AllParties => None,
+            };
+            outgoing.push(Outbound { recipient, msg })
+        }
+        Reply {
+            outgoing,
+            finished: self.output.borrow().is_some(),
+        }
+        .encode()
+        .unwrap()
+    }
+}
+
+impl<M: Serialize + DeserializeOwned, T> CoSigner for EmulatedCoSigner<M, T> {
+    type Error = Infallible;
+
+    fn start(&mut self) -> Result<Vec<u8>, Infallible> {
+        Ok(self.step())
+    }
+
+    fn handle(&mut self, inbound: &[u8]) -> Result<Vec<u8>, Infallible> {
+        let inbound = Inbound::<M>::decode(inbound).unwrap();
+        self.inbound
+            .unbounded_send(Ok(Incoming {
+                id: inbound.id,
+                sender: inbound.sender,
+                msg_type: if inbound.broadcast {
+                    MessageType::Broadcast
+                } else {
+                    MessageType::P2P
+                },
+                msg: inbound.msg,
+            }))
+            .unwrap();
+        Ok(self.step())
+    }
+}
+
+#[tokio::test]
+async fn co_signer_takes_part_in_signing() {
+    let mut rng = DevRng::new();
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(Some(2), 3, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let message_to_sign = DataToSign::digest::<Sha256>(b"signed with co-signer");
+    let participants = [0, 2];
+
+    let mut simulation = Simulation::<Msg<Secp256k1, Sha256>>::new();
+
+    // Signer 0 runs on the host
+    let host_party = simulation.add_party();
+    let mut host_rng = rng.fork();
+    let host_share = &shares[0];
+    let host = async {
+        cggmp21::signing(ExecutionId::new(&eid), 0, &participants, host_share)
+            .sign(&mut host_rng, host_party, message_to_sign)
+            .await
+    };
+
+    // Signer 1 is a device connected to the host
+    let device_party = simulation.add_party();
+    let device_share = shares[2].clone();
+    let mut device_rng = rng.fork();
+    let mut device =
+        EmulatedCoSigner::<_, Result<Signature<Secp256k1>, _>>::new(|party| async move {
+            cggmp21::signing(ExecutionId::new(&eid), 1, &participants, &device_share)
+                .sign(&mut device_rng, party, message_to_sign)
+                .await
+        });
+    let relay = cggmp21::co_signer::relay(&mut device, device_party);
+
+    let (host_sig, relay) = futures::join!(host, relay);
+    relay.expect("relay failed");
+    let host_sig = host_sig.expect("signing failed");
+    let device_sig = device
+        .take_output()
+        .expect("device didn't finish")
+        .expect("device signing failed");
+
+    host_sig
+        .verify(&shares[0].shared_public_key, &message_to_sign)
+        .expect("signature is not valid");
+    assert!(host_sig == device_sig);
+}
@@ -0,0 +1,72 @@
+use cggmp21::{
+    debug_replay,
+    security_level::SecurityLevel128,
+    signing::{msg::Msg, DataToSign},
+    supported_curves::Secp256k1,
+    ExecutionId,
+};
+use rand::Rng;
+use round_based::simulation::Simulation;
+use sha2::Sha256;
+
+#[tokio::test]
+async fn signing_is_replayed_from_log() {
+    let mut rng = rand_dev::DevRng::new();
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, 2, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let message_to_sign = DataToSign::digest::<Sha256>(b"replayed execution");
+    let participants = &[0, 1];
+
+    // Record execution of party 0
+    let mut simulation = Simulation::<Msg<Secp256k1, Sha256>>::new();
+    let (party0, mut party0_rng, recorder) =
+        debug_replay::record(simulation.add_party(), rng.fork());
+    let party1 = simulation.add_party();
+    let mut party1_rng = rng.fork();
+    let (sig0, sig1) = futures::join!(
+        cggmp21::signing(eid, 0, participants, &shares[0]).sign(
+            &mut party0_rng,
+            party0,
+            message_to_sign
+        ),
+        cggmp21::signing(eid, 1, participants, &shares[1]).sign(
+            &mut party1_rng,
+            party1,
+            message_to_sign
+        ),
+    );
+    let sig0 = sig0.expect("signing failed");
+    sig1.expect("signing failed");
+
+    let log = recorder.log().unwrap();
+    assert!(!log.incomings.is_empty());
+    assert!(!log.outgoings.is_empty());
+    assert!(!log.randomness.is_empty());
+    let log: debug_replay::Log =
+        serde_json::from_slice(&serde_json::to_vec(&log).unwrap()).unwrap();
+
+    // Replay with the same inputs reproduces the execution
+    let (party, mut replay_rng, replay) =
+        debug_replay::replay::<Msg<Secp256k1, Sha256>>(&log).unwrap();
+    let replayed = cggmp21::signing(eid, 0, participants, &shares[0])
+        .sign(&mut replay_rng, party, message_to_sign)
+        .await
+        .expect("replayed signing failed");
+    replay.verify().unwrap();
+    assert_eq!(sig0.r, replayed.r);
+    assert_eq!(sig0.s, replayed.s);
+
+    // Replay with another message to sign diverges
+    let another_message = DataToSign::digest::<Sha256>(b"another message");
+    let (party, mut replay_rng, replay) =
+        debug_replay::replay::<Msg<Secp256k1, Sha256>>(&log).unwrap();
+    let _ = cggmp21::signing(eid, 0, participants, &shares[0])
+        .sign(&mut replay_rng, party, another_message)
+        .await;
+    let err = replay.verify().unwrap_err();
+    assert!(err.divergence().is_some(), "{err}");
+}
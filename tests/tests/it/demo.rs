@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use cggmp21_demo::{Config, Network, Transport};
+use futures::future;
+use rand::Rng;
+use rand_dev::DevRng;
+use tokio::net::TcpListener;
+
+#[test_case::case(Transport::Tcp; "tcp")]
+#[test_case::case(Transport::WebSocket; "websocket")]
+#[tokio::test]
+async fn demo_runs_over_localhost(transport: Transport) {
+    let mut rng = DevRng::new();
+    let n = 3;
+    let config = Config {
+        t: 2,
+        signers: vec![0, 2],
+        session: format!("demo-test-{}", hex::encode(rng.gen::<[u8; 16]>())),
+        message: b"message sent over localhost".to_vec(),
+        timeout: Duration::from_secs(300),
+        // Cached primes are Blum primes
+        dangerous_allow_blum_primes: true,
+    };
+
+    let mut listeners = vec![];
+    for _ in 0..n {
+        listeners.push(TcpListener::bind("127.0.0.1:0").await.unwrap());
+    }
+    let addrs = listeners
+        .iter()
+        .map(|listener| listener.local_addr().unwrap())
+        .collect::<Vec<_>>();
+    let mut primes = cggmp21_tests::CACHED_PRIMES.iter();
+
+    let outputs = (0..).zip(listeners).map(|(i, listener)| {
+        let mut party_rng = rng.fork();
+        let party_primes = primes.next().expect("not enough primes");
+        let (addrs, config) = (&addrs, &config);
+        async move {
+            let network = Network::connect(transport, i, listener, addrs, config.timeout).await?;
+            let output = cggmp21_demo::run(&mut party_rng, &network, config, party_primes).await?;
+            network.shutdown().await;
+            Ok::<_, anyhow::Error>(output)
+        }
+    });
+    let outputs = future::try_join_all(outputs).await.unwrap();
+
+    let public_key = outputs[0].key_share.shared_public_key;
+    for output in &outputs {
+        assert_eq!(output.key_share.shared_public_key, public_key);
+    }
+    let signers = outputs
+        .iter()
+        .filter(|output| output.signature.is_some())
+        .count();
+    assert_eq!(signers, config.signers.len());
+}
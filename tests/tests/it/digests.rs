@@ -0,0 +1,160 @@
+#[generic_tests::define(attrs(tokio::test))]
+mod generic {
+    use cggmp21::{
+        digests::{blake2s_256, sha2_256, sha3_256},
+        key_share::{AnyKeyShare, KeyShare},
+        keygen::ThresholdMsg,
+        security_level::SecurityLevel128,
+        signing::{msg::Msg, DataToSign},
+        ExecutionId,
+    };
+    use generic_ec::{coords::HasAffineX, Curve, Point};
+    use rand::{Rng, RngCore};
+    use rand_dev::DevRng;
+    use round_based::simulation::Simulation;
+    use sha2::{Digest, Sha256, Sha512};
+
+    #[tokio::test]
+    async fn full_pipeline_with_sha512<E: Curve>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        run_pipeline::<E, Sha512, Sha512>(&mut DevRng::new(), false).await
+    }
+
+    #[tokio::test]
+    async fn full_pipeline_with_sha3<E: Curve>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        run_pipeline::<E, sha3_256::Digest, sha3_256::Digest>(&mut DevRng::new(), false).await
+    }
+
+    #[tokio::test]
+    async fn full_pipeline_with_blake2<E: Curve>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        run_pipeline::<E, blake2s_256::Digest, blake2s_256::Digest>(&mut DevRng::new(), false).await
+    }
+
+    #[tokio::test]
+    async fn full_pipeline_with_separate_reliability_digest<E: Curve>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        run_pipeline::<E, Sha256, blake2s_256::Digest>(&mut DevRng::new(), true).await
+    }
+
+    #[tokio::test]
+    async fn signatures_depend_on_digest<E: Curve>() {
+        let message = b"same message";
+        let hashes = [
+            sha2_256::data_to_sign::<E>(message).to_scalar(),
+            sha3_256::data_to_sign::<E>(message).to_scalar(),
+            blake2s_256::data_to_sign::<E>(message).to_scalar(),
+        ];
+        assert_ne!(hashes[0], hashes[1]);
+        assert_ne!(hashes[0], hashes[2]);
+        assert_ne!(hashes[1], hashes[2]);
+    }
+
+    /// Runs keygen, aux info generation and signing with digest `D`, broadcast messages are
+    /// checked for reliability with digest `H`
+    async fn run_pipeline<E, D, H>(rng: &mut DevRng, echo_broadcast: bool)
+    where
+        E: Curve,
+        Point<E>: HasAffineX<E>,
+        D: Digest + Clone + Send + Sync + 'static,
+        H: Digest<OutputSize = D::OutputSize>,
+    {
+        let t = 2;
+        let n = 3;
+
+        let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, D>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let outputs = (0..n).map(|i| {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_digest::<D>()
+                    .set_reliability_digest::<H>()
+                    .enforce_echo_broadcast(echo_broadcast)
+                    .set_threshold(t)
+                    .start(&mut party_rng, party)
+                    .await
+            }
+        });
+        let incomplete_shares = futures::future::try_join_all(outputs)
+            .await
+            .expect("keygen failed");
+
+        let mut primes = cggmp21_tests::CACHED_PRIMES.iter();
+        let mut simulation =
+            Simulation::<cggmp21::key_refresh::AuxOnlyMsg<D, SecurityLevel128>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let outputs = (0..n).map(|i| {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let pregenerated_primes = primes.next().expect("can't fetch primes");
+            async move {
+                cggmp21::aux_info_gen(eid, i, n, pregenerated_primes)
+                    .dangerous_allow_blum_primes(true)
+                    .set_digest::<D>()
+                    .set_reliability_digest::<H>()
+                    .enforce_echo_broadcast(echo_broadcast)
+                    .start(&mut party_rng, party)
+                    .await
+            }
+        });
+        let aux_infos = futures::future::try_join_all(outputs)
+            .await
+            .expect("aux info generation failed");
+
+        let shares = incomplete_shares
+            .into_iter()
+            .zip(aux_infos)
+            .map(|(core, aux)| KeyShare::from_parts((core, aux)).expect("invalid key share"))
+            .collect::<Vec<_>>();
+        assert_eq!(shares[0].min_signers(), t);
+
+        let mut simulation = Simulation::<Msg<E, D>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let mut message = [0u8; 100];
+        rng.fill_bytes(&mut message);
+        let message_to_sign = DataToSign::digest::<D>(&message);
+        let participants = &[0, 2];
+        let outputs = (0..).zip(participants).map(|(i, &j)| {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let share = &shares[usize::from(j)];
+            async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .set_digest::<D>()
+                    .set_reliability_digest::<H>()
+                    .enforce_echo_broadcast(echo_broadcast)
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            }
+        });
+        let signatures = futures::future::try_join_all(outputs)
+            .await
+            .expect("signing failed");
+
+        signatures[0]
+            .verify(&shares[0].shared_public_key, &message_to_sign)
+            .expect("signature is not valid");
+        assert!(signatures.iter().all(|s_i| signatures[0] == *s_i));
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
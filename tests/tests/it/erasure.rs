@@ -0,0 +1,76 @@
+use cggmp21::{
+    key_refresh::{attest_erasure, verify_erasure_attestations, NonThresholdMsg},
+    security_level::SecurityLevel128,
+    supported_curves::Secp256k1,
+    ExecutionId,
+};
+use rand::Rng;
+use round_based::simulation::Simulation;
+use sha2::Sha256;
+
+#[tokio::test]
+async fn erasure_is_attested_after_refresh() {
+    let mut rng = rand_dev::DevRng::new();
+    let n = 3;
+
+    let old_shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, n, false)
+        .expect("retrieve cached shares");
+    let mut primes = cggmp21_tests::CACHED_PRIMES.iter();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let mut simulation = Simulation::<NonThresholdMsg<Secp256k1, Sha256, SecurityLevel128>>::new();
+    let outputs = old_shares.iter().map(|share| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        let pregenerated_data = primes.next().expect("Can't fetch primes");
+        async move {
+            cggmp21::key_refresh(eid, share, pregenerated_data)
+                .dangerous_allow_blum_primes(true)
+                .start(&mut party_rng, party)
+                .await
+        }
+    });
+    let new_shares = futures::future::try_join_all(outputs)
+        .await
+        .expect("refresh failed");
+
+    let old_key_info = old_shares[0].core.key_info.clone();
+    let attestations = old_shares
+        .into_iter()
+        .zip(&new_shares)
+        .map(|(old, new)| attest_erasure::<_, Sha256, _>(&mut rng, eid, old, new).unwrap())
+        .collect::<Vec<_>>();
+
+    verify_erasure_attestations::<_, Sha256>(eid, &old_key_info, &new_shares[0], &attestations)
+        .unwrap();
+
+    // Attestation is bound to the refresh execution
+    let another_eid = ExecutionId::new(b"another refresh");
+    let err = verify_erasure_attestations::<_, Sha256>(
+        another_eid,
+        &old_key_info,
+        &new_shares[0],
+        &attestations,
+    )
+    .unwrap_err();
+    assert_eq!(err.blame(), [0, 1, 2]);
+
+    // Missing attestation is detected
+    let err = verify_erasure_attestations::<_, Sha256>(
+        eid,
+        &old_key_info,
+        &new_shares[0],
+        &attestations[..2],
+    )
+    .unwrap_err();
+    assert_eq!(err.blame(), [2]);
+
+    // Proof can't be forged for another party
+    let mut forged = attestations.clone();
+    forged[1].proof = forged[0].proof.clone();
+    let err = verify_erasure_attestations::<_, Sha256>(eid, &old_key_info, &new_shares[0], &forged)
+        .unwrap_err();
+    assert_eq!(err.blame(), [1]);
+}
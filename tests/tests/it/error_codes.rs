@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+
+use cggmp21::error_code::ErrorCode;
+
+#[test]
+fn error_codes_are_stable() {
+    let numeric = ErrorCode::ALL
+        .iter()
+        .map(|code| code.code())
+        .collect::<HashSet<_>>();
+    assert_eq!(numeric.len(), ErrorCode::ALL.len(), "codes are not unique");
+    let names = ErrorCode::ALL
+        .iter()
+        .map(|code| code.as_str())
+        .collect::<HashSet<_>>();
+    assert_eq!(names.len(), ErrorCode::ALL.len(), "names are not unique");
+
+    for &code in ErrorCode::ALL {
+        assert_eq!(ErrorCode::from_code(code.code()), Some(code));
+
+        let serialized = serde_json::to_string(&code).unwrap();
+        assert_eq!(serialized, format!("\"{code}\""));
+        let deserialized: ErrorCode = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, code);
+    }
+    assert_eq!(ErrorCode::from_code(0), None);
+
+    assert_eq!(ErrorCode::PeerAborted.code(), 1004);
+    assert_eq!(ErrorCode::InvalidSchnorrProof.code(), 2001);
+    assert_eq!(ErrorCode::PolicyViolation.code(), 3000);
+    assert!(ErrorCode::InvalidSchnorrProof.is_malicious());
+    assert!(!ErrorCode::PeerAborted.is_malicious());
+}
+
+#[generic_tests::define(attrs(tokio::test))]
+mod generic {
+    use cggmp21::error_code::{ErrorCode, ErrorReport};
+    use cggmp21::signing::{msg::Msg, DataToSign};
+    use cggmp21::{security_level::SecurityLevel128, ExecutionId};
+    use generic_ec::{coords::HasAffineX, Curve, Point};
+    use rand::Rng;
+    use rand_dev::DevRng;
+    use round_based::simulation::Simulation;
+    use sha2::Sha256;
+
+    #[tokio::test]
+    async fn signing_with_invalid_args_is_reported<E: Curve>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        let mut rng = DevRng::new();
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 2, false)
+            .expect("retrieve cached shares");
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let party = simulation.add_party();
+        let message_to_sign = DataToSign::digest::<Sha256>(b"message");
+
+        // Party 0 appears in the set of signers twice
+        let err = cggmp21::signing(eid, 0, &[0, 0], &shares[0])
+            .sign(&mut rng, party, message_to_sign)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), ErrorCode::InvalidArgs);
+
+        let report = err.report();
+        assert_eq!(report.code, ErrorCode::InvalidArgs);
+        assert_eq!(report.numeric_code, ErrorCode::InvalidArgs.code());
+        assert!(report.parties.is_empty());
+        assert!(report.message.starts_with(&err.to_string()));
+
+        let serialized = serde_json::to_string(&report).unwrap();
+        let deserialized: ErrorReport = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, report);
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
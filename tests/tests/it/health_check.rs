@@ -0,0 +1,52 @@
+use cggmp21::{
+    key_share::health::{Check, CheckStatus},
+    security_level::SecurityLevel128,
+    supported_curves::Secp256k1,
+};
+
+#[test]
+fn cached_key_share_is_healthy() {
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(Some(2), 3, false)
+        .expect("retrieve cached shares");
+
+    for share in &shares {
+        let report = share.health_check();
+        assert!(report.is_healthy(), "{report:?}");
+        assert_eq!(
+            report.status(Check::PaillierKey),
+            Some(&CheckStatus::Passed)
+        );
+        assert!(matches!(
+            report.status(Check::AuxProofs),
+            Some(CheckStatus::Skipped(_))
+        ));
+    }
+}
+
+#[test]
+fn corrupted_key_share_is_reported() {
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(Some(2), 3, false)
+        .expect("retrieve cached shares");
+
+    // Copied modulus of another party
+    let mut share = shares[0].clone().into_inner();
+    share.aux.parties[2] = share.aux.parties[1].clone();
+    let report = share.health_check();
+    assert!(!report.is_healthy());
+    let failures = report
+        .failures()
+        .map(|(check, _)| check)
+        .collect::<Vec<_>>();
+    assert_eq!(failures, [Check::PartiesAux]);
+
+    // Swapped Paillier primes of another share
+    let mut share = shares[0].clone().into_inner();
+    share.aux.p = shares[1].aux.p.clone();
+    let report = share.health_check();
+    assert!(matches!(
+        report.status(Check::PaillierKey),
+        Some(CheckStatus::Failed(_))
+    ));
+}
@@ -0,0 +1,53 @@
+use cggmp21_tests::kat::{self, Level, Protocol};
+
+#[tokio::test]
+async fn execution_is_deterministic() {
+    for protocol in [Protocol::Keygen, Protocol::Presign, Protocol::Sign] {
+        let vector = kat::generate(protocol, Some("secp256k1"), Level::L128, 3, Some(2), 1)
+            .await
+            .unwrap();
+        kat::replay(&vector).await.unwrap();
+
+        let other_seed = kat::generate(protocol, Some("secp256k1"), Level::L128, 3, Some(2), 2)
+            .await
+            .unwrap();
+        assert_ne!(vector.answer, other_seed.answer, "{protocol:?}");
+    }
+}
+
+#[tokio::test]
+async fn replay_detects_changed_answer() {
+    let mut vector = kat::generate(Protocol::Sign, Some("stark"), Level::L128, 3, Some(2), 1)
+        .await
+        .unwrap();
+    vector.answer.outputs[0][0] ^= 1;
+    assert!(kat::replay(&vector).await.is_err());
+}
+
+#[tokio::test]
+async fn committed_vectors_match() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../test-data/kat_vectors.json");
+    let vectors = std::fs::read(path).expect("vectors are missing, see `kat_vectors generate`");
+    let vectors: Vec<kat::Vector> = serde_json::from_slice(&vectors).unwrap();
+
+    // Every curve and security level is covered
+    for (protocol, curve, level, n, t) in kat::default_set() {
+        assert!(
+            vectors.iter().any(|v| v.protocol == protocol
+                && v.curve.as_deref() == curve
+                && v.security_level == level
+                && v.n == n
+                && v.t == t),
+            "no vector for {protocol:?} {curve:?} {level:?}"
+        );
+    }
+
+    for vector in &vectors {
+        kat::replay(vector).await.unwrap_or_else(|err| {
+            panic!(
+                "{:?} {:?} {:?}: {err:#}",
+                vector.protocol, vector.curve, vector.security_level
+            )
+        });
+    }
+}
@@ -43,7 +43,9 @@ mod generic {
             let pregenerated_data = primes.next().expect("Can't fetch primes");
             async move {
                 cggmp21::key_refresh(eid, share, pregenerated_data)
+                    .dangerous_allow_blum_primes(true)
                     .enforce_reliable_broadcast(reliable_broadcast)
+                    .enforce_echo_broadcast(reliable_broadcast)
                     .start(&mut party_rng, party)
                     .await
             }
@@ -102,6 +104,7 @@ mod generic {
             async move {
                 cggmp21::signing(eid, share.core.i, participants, share)
                     .enforce_reliable_broadcast(reliable_broadcast)
+                    .enforce_echo_broadcast(reliable_broadcast)
                     .sign(&mut party_rng, party, message_to_sign)
                     .await
             }
@@ -146,7 +149,9 @@ mod generic {
             let pregenerated_data = primes.next().expect("Can't fetch primes");
             async move {
                 cggmp21::aux_info_gen(eid, i, n, pregenerated_data)
+                    .dangerous_allow_blum_primes(true)
                     .enforce_reliable_broadcast(reliable_broadcast)
+                    .enforce_echo_broadcast(reliable_broadcast)
                     .start(&mut party_rng, party)
                     .await
             }
@@ -165,6 +170,7 @@ mod generic {
                 DirtyKeyShare {
                     core: share.into_inner().core,
                     aux: aux.into_inner(),
+                    policy: None,
                 }
                 .validate()
                 .unwrap()
@@ -193,6 +199,7 @@ mod generic {
             async move {
                 cggmp21::signing(eid, i, participants, share)
                     .enforce_reliable_broadcast(reliable_broadcast)
+                    .enforce_echo_broadcast(reliable_broadcast)
                     .sign(&mut party_rng, party, message_to_sign)
                     .await
             }
@@ -208,6 +215,191 @@ mod generic {
         }
     }
 
+    #[tokio::test]
+    async fn key_refresh_rotates_rid<E: generic_ec::Curve>() {
+        let mut rng = rand_dev::DevRng::new();
+        let n = 3;
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, n, false)
+            .expect("retrieve cached shares");
+        let mut primes = cggmp21_tests::CACHED_PRIMES.iter();
+
+        let mut rids = vec![];
+        for _ in 0..2 {
+            let eid: [u8; 32] = rng.gen();
+            let eid = ExecutionId::new(&eid);
+            let mut simulation = Simulation::<
+                cggmp21::key_refresh::NonThresholdMsg<E, Sha256, SecurityLevel128>,
+            >::new();
+            let outputs = shares.iter().map(|share| {
+                let party = simulation.add_party();
+                let mut party_rng = rng.fork();
+                let pregenerated_data = primes.next().expect("Can't fetch primes");
+                async move {
+                    cggmp21::key_refresh(eid, share, pregenerated_data)
+                        .dangerous_allow_blum_primes(true)
+                        .start_with_rid(&mut party_rng, party)
+                        .await
+                }
+            });
+            let outputs = futures::future::try_join_all(outputs)
+                .await
+                .expect("refresh failed");
+
+            // All parties agree on the same rid
+            let rid = outputs[0].1.as_ref().to_vec();
+            for (_, rid_j) in &outputs {
+                assert_eq!(rid, rid_j.as_ref());
+            }
+            rids.push(rid);
+        }
+
+        // Every refresh produces a new rid
+        assert_ne!(rids[0], rids[1]);
+    }
+
+    #[tokio::test]
+    async fn key_refresh_preserves_key_usage_policy<E: generic_ec::Curve>()
+    where
+        Point<E>: generic_ec::coords::HasAffineX<E>,
+    {
+        use cggmp21::policy::KeyUsagePolicy;
+        use cggmp21::signing::{msg::Msg, DataToSign};
+
+        let mut rng = rand_dev::DevRng::new();
+        let n = 3;
+
+        let mut policy = KeyUsagePolicy::default();
+        policy.allowed_prefixes.push(b"allowed:".to_vec());
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, n, false)
+            .expect("retrieve cached shares")
+            .into_iter()
+            .map(|share| {
+                let mut share = share.into_inner();
+                share.policy = Some(policy.clone());
+                share.validate().unwrap()
+            })
+            .collect::<Vec<_>>();
+        let mut primes = cggmp21_tests::CACHED_PRIMES.iter();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let mut simulation =
+            Simulation::<cggmp21::key_refresh::NonThresholdMsg<E, Sha256, SecurityLevel128>>::new();
+        let outputs = shares.iter().map(|share| {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let pregenerated_data = primes.next().expect("Can't fetch primes");
+            async move {
+                cggmp21::key_refresh(eid, share, pregenerated_data)
+                    .dangerous_allow_blum_primes(true)
+                    .start(&mut party_rng, party)
+                    .await
+            }
+        });
+        let key_shares = futures::future::try_join_all(outputs)
+            .await
+            .expect("refresh failed");
+        for key_share in &key_shares {
+            let key_policy = key_share.policy.as_ref().expect("policy is lost");
+            assert_eq!(key_policy.allowed_prefixes, policy.allowed_prefixes);
+        }
+
+        // Refreshed key shares keep enforcing the policy
+        let participants = &[0, 1, 2];
+        let message = b"forbidden: message";
+        let message_to_sign = DataToSign::digest::<Sha256>(message);
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let outputs = (0..).zip(&key_shares).map(|(i, share)| {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .set_message(message)
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            }
+        });
+        for result in futures::future::join_all(outputs).await {
+            let err = result.err().expect("policy violation is not detected");
+            assert!(err.is_policy_violation() || err.aborted_by_peer().is_some());
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[tokio::test]
+    async fn key_refresh_on_dedicated_thread_pool<E: generic_ec::Curve>() {
+        let mut rng = rand_dev::DevRng::new();
+        let n = 3;
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, n, false)
+            .expect("retrieve cached shares");
+        let mut primes = cggmp21_tests::CACHED_PRIMES.iter();
+        let pool = cggmp21::rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let mut simulation =
+            Simulation::<cggmp21::key_refresh::NonThresholdMsg<E, Sha256, SecurityLevel128>>::new();
+        let outputs = shares.iter().map(|share| {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let pregenerated_data = primes.next().expect("Can't fetch primes");
+            let pool = &pool;
+            async move {
+                cggmp21::key_refresh(eid, share, pregenerated_data)
+                    .dangerous_allow_blum_primes(true)
+                    .set_thread_pool(pool)
+                    .start(&mut party_rng, party)
+                    .await
+            }
+        });
+        let key_shares = futures::future::try_join_all(outputs)
+            .await
+            .expect("refresh failed");
+
+        for key_share in &key_shares {
+            assert_eq!(
+                key_share.core.shared_public_key,
+                shares[0].core.shared_public_key
+            );
+            assert_eq!(
+                Point::<E>::generator() * &key_share.core.x,
+                key_share.core.public_shares[usize::from(key_share.core.i)]
+            );
+        }
+
+        // Aux info generation honors the pool as well
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let mut simulation =
+            Simulation::<cggmp21::key_refresh::AuxOnlyMsg<Sha256, SecurityLevel128>>::new();
+        let outputs = (0..n).map(|i| {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let pregenerated_data = primes.next().expect("Can't fetch primes");
+            let pool = &pool;
+            async move {
+                cggmp21::aux_info_gen(eid, i, n, pregenerated_data)
+                    .dangerous_allow_blum_primes(true)
+                    .set_thread_pool(pool)
+                    .start(&mut party_rng, party)
+                    .await
+            }
+        });
+        futures::future::try_join_all(outputs)
+            .await
+            .expect("aux gen failed");
+    }
+
     #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
     mod secp256r1 {}
     #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
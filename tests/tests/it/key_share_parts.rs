@@ -0,0 +1,201 @@
+#[generic_tests::define(attrs(test, tokio::test, test_case::case, cfg_attr))]
+mod generic {
+    use cggmp21::{
+        key_share::{self, Validate},
+        policy::KeyUsagePolicy,
+        security_level::SecurityLevel128,
+        signing::{msg::Msg, DataToSign, SigningBuilder},
+        trusted_dealer, ExecutionId, KeyShare,
+    };
+    use generic_ec::{coords::HasAffineX, Curve, Point};
+    use rand::Rng;
+    use round_based::simulation::Simulation;
+    use sha2::Sha256;
+
+    #[test]
+    fn key_share_is_split_into_parts_and_merged_back<E: Curve>() {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 3, false)
+            .expect("retrieve cached shares");
+        let other_committee = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 2, false)
+            .expect("retrieve cached shares");
+
+        let (cores, auxes): (Vec<_>, Vec<_>) = shares
+            .iter()
+            .cloned()
+            .map(|share| {
+                let (core, aux, _policy) = key_share::split(share);
+                (core, aux)
+            })
+            .unzip();
+
+        for (i, (core, aux)) in cores.iter().zip(&auxes).enumerate() {
+            let key_share = KeyShare::from_parts((core.clone(), aux.clone())).unwrap();
+            assert_eq!(key_share.core.i, shares[i].core.i);
+            assert_eq!(
+                key_share.core.shared_public_key,
+                shares[i].core.shared_public_key
+            );
+            assert!(key_share
+                .aux
+                .parties
+                .iter()
+                .zip(&shares[i].aux.parties)
+                .all(|(a, b)| a.N == b.N));
+
+            // Aux info of another signer doesn't match the key share
+            let err = KeyShare::from_parts((core.clone(), auxes[(i + 1) % 3].clone()))
+                .unwrap_err()
+                .into_error();
+            assert!(err.is_mismatched_parts(), "{err}");
+        }
+
+        // Aux info of another committee doesn't match the key share
+        let (_, other_aux, _) = key_share::split(other_committee[0].clone());
+        let err = KeyShare::from_parts((cores[0].clone(), other_aux))
+            .unwrap_err()
+            .into_error();
+        assert!(err.is_mismatched_parts(), "{err}");
+    }
+
+    #[test]
+    fn aux_info_bound_to_key_is_not_paired_with_another_key<E: Curve>() {
+        let mut rng = rand_dev::DevRng::new();
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 3, false)
+            .expect("retrieve cached shares");
+        let another_key = trusted_dealer::builder::<E, SecurityLevel128>(3)
+            .generate_core_shares(&mut rng)
+            .unwrap();
+
+        let (core, aux, _) = key_share::split(shares[0].clone());
+
+        // Unbound aux info can be paired with any key of the committee
+        assert!(KeyShare::from_parts((another_key[0].clone(), aux.clone())).is_ok());
+
+        let mut aux = aux.into_inner();
+        aux.bind_to_key(&core.key_info, 1);
+        let aux = aux.validate().unwrap();
+
+        assert!(KeyShare::from_parts((core.clone(), aux.clone())).is_ok());
+        assert!(KeyShare::from_parts((core.clone(), aux.clone(), 1_u64)).is_ok());
+
+        let err = KeyShare::from_parts((another_key[0].clone(), aux.clone()))
+            .unwrap_err()
+            .into_error();
+        assert!(err.is_mismatched_parts(), "{err}");
+        let err = KeyShare::from_parts((core, aux, 2_u64))
+            .unwrap_err()
+            .into_error();
+        assert!(err.is_mismatched_parts(), "{err}");
+    }
+
+    #[tokio::test]
+    async fn restricted_key_share_stays_restricted_after_split<E: Curve>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        let mut rng = rand_dev::DevRng::new();
+        let (t, n) = (2, 3);
+
+        let mut policy = KeyUsagePolicy::default();
+        policy.allowed_prefixes.push(b"allowed:".to_vec());
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares")
+            .into_iter()
+            .map(|share| {
+                let mut share = share.into_inner();
+                share.policy = Some(policy.clone());
+                share.validate().unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // Policy is returned alongside the parts and restored when they're merged back
+        let parts = shares
+            .iter()
+            .map(|share| key_share::split(share.clone()))
+            .collect::<Vec<_>>();
+        let merged = parts
+            .iter()
+            .map(|(core, aux, policy)| {
+                assert!(policy.is_some(), "policy is lost on split");
+                KeyShare::from_parts((core.clone(), aux.clone(), policy.clone())).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // Key share merged back from parts enforces the policy
+        let participants = &[0, 1];
+        let message = b"forbidden";
+        let message_to_sign = DataToSign::digest::<Sha256>(message);
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let outputs = (0..).zip(participants).map(|(i, &j)| {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let share = &merged[usize::from(j)];
+            async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .set_message(message)
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            }
+        });
+        for result in futures::future::join_all(outputs).await {
+            let err = result.err().expect("policy violation is not detected");
+            assert!(err.is_policy_violation() || err.aborted_by_peer().is_some());
+        }
+
+        // Signing from parts enforces the policy
+        for (message, allowed) in [(&b"allowed: message"[..], true), (b"forbidden", false)] {
+            let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+            let eid: [u8; 32] = rng.gen();
+            let eid = ExecutionId::new(&eid);
+            let message_to_sign = DataToSign::digest::<Sha256>(message);
+
+            let outputs = (0..).zip(participants).map(|(i, &j)| {
+                let party = simulation.add_party();
+                let mut party_rng = rng.fork();
+                let (core, aux, policy) = &parts[usize::from(j)];
+                async move {
+                    SigningBuilder::<E, SecurityLevel128>::from_parts(
+                        eid,
+                        i,
+                        participants,
+                        core,
+                        aux,
+                        policy.as_ref(),
+                    )
+                    .unwrap()
+                    .set_message(message)
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+                }
+            });
+            let results = futures::future::join_all(outputs).await;
+            for result in results {
+                match result {
+                    Ok(signature) => {
+                        assert!(allowed, "policy violation is not detected");
+                        signature
+                            .verify(&shares[0].shared_public_key, &message_to_sign)
+                            .expect("signature is not valid");
+                    }
+                    Err(err) => {
+                        assert!(!allowed, "signing failed: {err}");
+                        assert!(err.is_policy_violation() || err.aborted_by_peer().is_some());
+                    }
+                }
+            }
+        }
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
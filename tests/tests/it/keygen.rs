@@ -1,15 +1,15 @@
 #[generic_tests::define(attrs(tokio::test, test_case::case, cfg_attr))]
 mod generic {
-    use generic_ec::{Curve, Point};
+    use generic_ec::{Curve, NonZero, Point, Scalar};
     use rand::{seq::SliceRandom, Rng, SeedableRng};
     use rand_chacha::ChaCha20Rng;
     use rand_dev::DevRng;
     use round_based::simulation::Simulation;
     use sha2::Sha256;
 
-    use cggmp21::keygen::{NonThresholdMsg, ThresholdMsg};
+    use cggmp21::keygen::{NonThresholdMsg, ThresholdMsg, ToThresholdMsg, TwoRoundMsg};
     use cggmp21::{
-        key_share::reconstruct_secret_key, security_level::SecurityLevel128, ExecutionId,
+        key_share::reconstruct_secret_key, security_level::SecurityLevel128, DomainTag, ExecutionId,
     };
 
     #[test_case::case(3, false, false; "n3")]
@@ -39,8 +39,9 @@ mod generic {
             let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
 
             outputs.push(async move {
-                let keygen =
-                    cggmp21::keygen(eid, i, n).enforce_reliable_broadcast(reliable_broadcast);
+                let keygen = cggmp21::keygen(eid, i, n)
+                    .enforce_reliable_broadcast(reliable_broadcast)
+                    .enforce_echo_broadcast(reliable_broadcast);
 
                 #[cfg(feature = "hd-wallets")]
                 let keygen = keygen.hd_wallet(hd_wallet);
@@ -80,6 +81,62 @@ mod generic {
         }
     }
 
+    #[test_case::case(2, false; "n2")]
+    #[test_case::case(3, false; "n3")]
+    #[test_case::case(3, true; "n3-reliable")]
+    #[tokio::test]
+    async fn two_round_keygen_works<E: Curve>(n: u16, reliable_broadcast: bool) {
+        use cggmp21::progress::{Progress, ProgressTracer};
+
+        let mut rng = DevRng::new();
+
+        let mut simulation = Simulation::<TwoRoundMsg<E, SecurityLevel128, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut outputs = vec![];
+        for i in 0..n {
+            let party = simulation.add_party();
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+
+            outputs.push(async move {
+                let keygen = cggmp21::keygen::<E>(eid, i, n)
+                    .enforce_reliable_broadcast(reliable_broadcast)
+                    .enforce_echo_broadcast(reliable_broadcast);
+                let total_rounds = keygen.two_round_rounds_count();
+
+                let mut tracer = ProgressTracer::new(total_rounds, |_: Progress| {});
+                let key_share = keygen
+                    .set_progress_tracer(&mut tracer)
+                    .start_two_round(&mut party_rng, party)
+                    .await?;
+                // All rounds begun, i.e. all but the last one are completed
+                assert_eq!(tracer.progress().completed_rounds + 1, total_rounds);
+
+                Ok::<_, cggmp21::keygen::KeygenError>(key_share)
+            })
+        }
+
+        let key_shares = futures::future::try_join_all(outputs)
+            .await
+            .expect("keygen failed");
+
+        for (i, key_share) in (0u16..).zip(&key_shares) {
+            assert_eq!(key_share.i, i);
+            assert_eq!(key_share.shared_public_key, key_shares[0].shared_public_key);
+            assert_eq!(key_share.public_shares, key_shares[0].public_shares);
+            assert_eq!(
+                Point::<E>::generator() * &key_share.x,
+                key_share.public_shares[usize::from(i)]
+            );
+        }
+        assert_eq!(
+            key_shares[0].shared_public_key,
+            key_shares[0].public_shares.iter().sum::<Point<E>>()
+        );
+    }
+
     #[test_case::case(2, 3, false, false; "t2n3")]
     #[test_case::case(3, 5, false, false; "t3n5")]
     #[test_case::case(3, 5, true, false; "t3n5-reliable")]
@@ -110,6 +167,7 @@ mod generic {
             outputs.push(async move {
                 let keygen = cggmp21::keygen(eid, i, n)
                     .enforce_reliable_broadcast(reliable_broadcast)
+                    .enforce_echo_broadcast(reliable_broadcast)
                     .set_threshold(t);
 
                 #[cfg(feature = "hd-wallets")]
@@ -155,6 +213,626 @@ mod generic {
         assert_eq!(Point::generator() * sk, key_shares[0].shared_public_key);
     }
 
+    #[test_case::case(2, 3; "t2n3")]
+    #[test_case::case(3, 5; "t3n5")]
+    #[tokio::test]
+    async fn non_threshold_key_converts_to_threshold<E: Curve>(t: u16, n: u16) {
+        let mut rng = DevRng::new();
+
+        let mut simulation = Simulation::<NonThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let mut outputs = vec![];
+        for i in 0..n {
+            let party = simulation.add_party();
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+            outputs.push(async move {
+                cggmp21::keygen(eid, i, n)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+        let key_shares = futures::future::try_join_all(outputs)
+            .await
+            .expect("keygen failed");
+
+        let mut simulation = Simulation::<ToThresholdMsg<E, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let mut outputs = vec![];
+        for key_share in &key_shares {
+            let party = simulation.add_party();
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+            outputs.push(async move {
+                cggmp21::keygen::to_threshold(eid, key_share, t)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+        let converted = futures::future::try_join_all(outputs)
+            .await
+            .expect("conversion failed");
+
+        for (i, key_share) in (0u16..).zip(&converted) {
+            assert_eq!(key_share.i, i);
+            assert_eq!(key_share.min_signers(), t);
+            assert_eq!(key_share.shared_public_key, key_shares[0].shared_public_key);
+            assert_eq!(key_share.public_shares, converted[0].public_shares);
+            #[cfg(feature = "hd-wallets")]
+            assert_eq!(key_share.chain_code, key_shares[0].chain_code);
+        }
+
+        let mut converted = converted;
+        converted.shuffle(&mut rng);
+        let sk = reconstruct_secret_key(&converted[..usize::from(t)]).unwrap();
+        assert_eq!(Point::generator() * sk, key_shares[0].shared_public_key);
+
+        // Converted key can't be converted again
+        let mut simulation = Simulation::<ToThresholdMsg<E, Sha256>>::new();
+        let result = cggmp21::keygen::to_threshold(eid, &converted[0], t)
+            .start(&mut rng, simulation.add_party())
+            .await;
+        assert!(result.is_err());
+
+        // Invalid threshold is rejected before the protocol starts
+        for t in [0, 1, n + 1] {
+            let mut simulation = Simulation::<ToThresholdMsg<E, Sha256>>::new();
+            let err = cggmp21::keygen::to_threshold(eid, &key_shares[0], t)
+                .start(&mut rng, simulation.add_party())
+                .await
+                .unwrap_err();
+            assert_eq!(
+                err.code(),
+                cggmp21::error_code::ErrorCode::InvalidArgs,
+                "t={t}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn keygen_rejects_reused_eid<E: Curve>() {
+        use cggmp21::EidRegistry;
+
+        let mut rng = DevRng::new();
+        let registry = cggmp21::InMemoryEidRegistry::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        registry.register(eid).expect("eid is not used yet");
+        assert!(registry.contains(eid));
+
+        let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+        let party = simulation.add_party();
+        let result = cggmp21::keygen::<E>(eid, 0, 3)
+            .set_eid_registry(&registry)
+            .set_threshold(2)
+            .start(&mut rng, party)
+            .await;
+        assert!(result.is_err());
+
+        let err = registry.register(eid).unwrap_err();
+        assert!(err.is_reused());
+    }
+
+    #[test_case::case(false; "echo-disabled")]
+    #[test_case::case(true; "echo-enabled")]
+    #[tokio::test]
+    async fn keygen_reports_determinate_progress<E: Curve>(echo_broadcast: bool) {
+        use cggmp21::progress::{Progress, ProgressTracer};
+
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut outputs = vec![];
+        for i in 0..n {
+            let party = simulation.add_party();
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+
+            outputs.push(async move {
+                let keygen = cggmp21::keygen::<E>(eid, i, n)
+                    .enforce_echo_broadcast(echo_broadcast)
+                    .set_threshold(t);
+                let total_rounds = keygen.rounds_count();
+
+                let mut reported = vec![];
+                let mut tracer = ProgressTracer::new(total_rounds, |p: Progress| reported.push(p));
+                keygen
+                    .set_progress_tracer(&mut tracer)
+                    .start(&mut party_rng, party)
+                    .await?;
+                // All rounds begun, i.e. all but the last one are completed
+                assert_eq!(tracer.progress().completed_rounds + 1, total_rounds);
+                drop(tracer);
+
+                Ok::<_, cggmp21::keygen::KeygenError>(reported)
+            })
+        }
+
+        let reports = futures::future::try_join_all(outputs)
+            .await
+            .expect("keygen failed");
+
+        for reported in reports {
+            assert_eq!(reported.first().map(|p| p.completed_rounds), Some(0));
+            assert!(reported
+                .windows(2)
+                .all(|w| w[0].percent() <= w[1].percent()));
+            let last = reported.last().unwrap();
+            assert!(last.is_completed());
+            assert_eq!(last.percent(), 100.);
+        }
+    }
+
+    #[test_case::case(false; "same-beacon")]
+    #[test_case::case(true; "mismatched-beacon")]
+    #[tokio::test]
+    async fn keygen_with_randomness_beacon<E: Curve>(mismatched: bool) {
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let beacon: [u8; 32] = rng.gen();
+        let another_beacon: [u8; 32] = rng.gen();
+
+        let mut outputs = vec![];
+        for i in 0..n {
+            let party = simulation.add_party();
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+            let beacon = if mismatched && i == 0 {
+                another_beacon
+            } else {
+                beacon
+            };
+
+            outputs.push(async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .set_randomness_beacon(&beacon)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+
+        let results = futures::future::join_all(outputs).await;
+        if mismatched {
+            for result in results {
+                assert!(result.is_err(), "keygen must fail on mismatched beacon");
+            }
+        } else {
+            let key_shares = results
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()
+                .expect("keygen failed");
+            for key_share in &key_shares {
+                assert_eq!(key_share.shared_public_key, key_shares[0].shared_public_key);
+            }
+        }
+    }
+
+    #[test_case::case(false; "same-tag")]
+    #[test_case::case(true; "mismatched-tag")]
+    #[tokio::test]
+    async fn keygen_with_domain_tag<E: Curve>(mismatched: bool) {
+        for invalid_tag in [
+            "",
+            "dfns.cggmp21.keygen",
+            "bad tag!",
+            "a".repeat(65).as_str(),
+        ] {
+            assert!(
+                DomainTag::new(invalid_tag).is_err(),
+                "tag {invalid_tag:?} must be rejected"
+            );
+        }
+
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let tag = DomainTag::new("com.example.wallet/v1").unwrap();
+        let another_tag = DomainTag::new("com.example.exchange/v1").unwrap();
+
+        let mut outputs = vec![];
+        for i in 0..n {
+            let party = simulation.add_party();
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+            let tag = if mismatched && i == 0 {
+                another_tag
+            } else {
+                tag
+            };
+
+            outputs.push(async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .set_domain_tag(tag)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+
+        let results = futures::future::join_all(outputs).await;
+        if mismatched {
+            for result in results {
+                assert!(result.is_err(), "keygen must fail on mismatched domain tag");
+            }
+        } else {
+            let key_shares = results
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()
+                .expect("keygen failed");
+            for key_share in &key_shares {
+                assert_eq!(key_share.shared_public_key, key_shares[0].shared_public_key);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn threshold_keygen_with_batch_verification<E: Curve>() {
+        let mut rng = DevRng::new();
+        let (t, n) = (5, 20);
+
+        let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut outputs = vec![];
+        for i in 0..n {
+            let party = simulation.add_party();
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+
+            outputs.push(async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .batch_verification(true)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+
+        let key_shares = futures::future::try_join_all(outputs)
+            .await
+            .expect("keygen failed");
+        for key_share in &key_shares {
+            assert_eq!(key_share.shared_public_key, key_shares[0].shared_public_key);
+            assert_eq!(key_share.public_shares, key_shares[0].public_shares);
+        }
+
+        let mut key_shares = key_shares;
+        key_shares.shuffle(&mut rng);
+        let sk = reconstruct_secret_key(&key_shares[..usize::from(t)]).unwrap();
+        assert_eq!(Point::generator() * sk, key_shares[0].shared_public_key);
+    }
+
+    #[tokio::test]
+    async fn threshold_keygen_with_custom_share_indexes<E: Curve>() {
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let share_indexes = (0..n)
+            .map(|_| NonZero::<Scalar<E>>::random(&mut rng))
+            .collect::<Vec<_>>();
+
+        let mut outputs = vec![];
+        for i in 0..n {
+            let party = simulation.add_party();
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+            let share_indexes = &share_indexes;
+
+            outputs.push(async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .set_share_indexes(share_indexes)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+
+        let key_shares = futures::future::try_join_all(outputs)
+            .await
+            .expect("keygen failed");
+        for key_share in &key_shares {
+            assert_eq!(key_share.shared_public_key, key_shares[0].shared_public_key);
+            assert_eq!(
+                key_share.vss_setup.as_ref().map(|setup| &setup.I),
+                Some(&share_indexes)
+            );
+        }
+
+        let sk = reconstruct_secret_key(&key_shares[1..]).unwrap();
+        assert_eq!(Point::generator() * sk, key_shares[0].shared_public_key);
+
+        // Indexes must be distinct
+        let duplicated = [share_indexes[0], share_indexes[1], share_indexes[0]];
+        let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+        let result = cggmp21::keygen::<E>(eid, 0, n)
+            .set_threshold(t)
+            .set_share_indexes(&duplicated)
+            .start(&mut rng, simulation.add_party())
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn threshold_keygen_stores_vss_commitments<E: Curve>() {
+        let mut rng = DevRng::new();
+        let (t, n) = (3, 5);
+
+        let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut outputs = vec![];
+        for i in 0..n {
+            let party = simulation.add_party();
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+
+            outputs.push(async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+
+        let key_shares = futures::future::try_join_all(outputs)
+            .await
+            .expect("keygen failed");
+        for key_share in &key_shares {
+            let vss_setup = key_share.vss_setup.as_ref().unwrap();
+            let commitments = vss_setup
+                .commitments
+                .as_ref()
+                .expect("commitments are missing");
+            assert_eq!(commitments.len(), usize::from(t));
+            assert_eq!(commitments[0], *key_share.shared_public_key);
+            assert_eq!(
+                Some(vss_setup),
+                key_shares[0].vss_setup.as_ref(),
+                "all parties must output the same vss setup"
+            );
+
+            // Any party's share can be verified against the commitments
+            for j in 0..n {
+                assert_eq!(
+                    vss_setup.verify_public_share(j, &key_share.public_shares[usize::from(j)]),
+                    Some(true)
+                );
+            }
+            assert_eq!(
+                vss_setup.verify_secret_share(key_share.i, &key_share.x),
+                Some(true)
+            );
+            assert_eq!(
+                vss_setup.verify_public_share(0, &key_share.public_shares[1]),
+                Some(false)
+            );
+            assert_eq!(
+                vss_setup.verify_public_share(n, &key_share.public_shares[0]),
+                None
+            );
+        }
+
+        // Key share with tampered commitments doesn't pass validation
+        let mut key_share = key_shares[0].clone().into_inner();
+        let commitments = key_share
+            .key_info
+            .vss_setup
+            .as_mut()
+            .and_then(|vss_setup| vss_setup.commitments.as_mut())
+            .unwrap();
+        commitments[1] = commitments[1] + Point::generator();
+        assert!(cggmp21::key_share::Validate::validate(key_share).is_err());
+    }
+
+    #[tokio::test]
+    async fn threshold_keygen_with_weights<E: Curve>() {
+        let mut rng = DevRng::new();
+        let (t, n) = (3, 3);
+        let weights = [2, 1, 1];
+
+        let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut outputs = vec![];
+        for i in 0..n {
+            let party = simulation.add_party();
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+            let weights = &weights;
+
+            outputs.push(async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .set_weights(weights)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+
+        let key_shares = futures::future::try_join_all(outputs)
+            .await
+            .expect("keygen failed");
+        for (key_share, weight) in key_shares.iter().zip(weights) {
+            assert_eq!(key_share.shared_public_key, key_shares[0].shared_public_key);
+            let vss_setup = key_share.vss_setup.as_ref().unwrap();
+            assert_eq!(vss_setup.weight(key_share.i), Some(weight));
+            assert_eq!(key_share.extra_x.len(), usize::from(weight - 1));
+        }
+
+        // Parties 0 and 2 have total weight 3, which is enough to reconstruct the key
+        let sk = reconstruct_secret_key(&[&key_shares[0], &key_shares[2]]).unwrap();
+        assert_eq!(Point::generator() * sk, key_shares[0].shared_public_key);
+
+        // Parties 1 and 2 have total weight 2, which is less than threshold
+        assert!(reconstruct_secret_key(&[&key_shares[1], &key_shares[2]]).is_err());
+
+        // Weights must be non-zero
+        let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+        let result = cggmp21::keygen::<E>(eid, 0, n)
+            .set_threshold(t)
+            .set_weights(&[2, 0, 1])
+            .start(&mut rng, simulation.add_party())
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn keygen_outputs_proof_of_possession<E: Curve>() {
+        let mut rng = DevRng::new();
+        let n = 3;
+        let challenge = b"enrollment challenge";
+
+        // Non-threshold keygen
+        let mut simulation = Simulation::<NonThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let mut outputs = vec![];
+        for i in 0..n {
+            let party = simulation.add_party();
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+            outputs.push(async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .start_with_proof_of_possession(&mut party_rng, party, challenge)
+                    .await
+            })
+        }
+        let outputs = futures::future::try_join_all(outputs)
+            .await
+            .expect("keygen failed");
+        for (key_share, pop) in &outputs {
+            assert_eq!(*pop, outputs[0].1);
+            pop.verify::<Sha256>(&key_share.shared_public_key, challenge)
+                .expect("invalid proof of possession");
+            assert!(pop
+                .verify::<Sha256>(&key_share.shared_public_key, b"another challenge")
+                .is_err());
+        }
+
+        // Threshold keygen
+        let t = 2;
+        let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let mut outputs = vec![];
+        for i in 0..n {
+            let party = simulation.add_party();
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+            outputs.push(async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .start_with_proof_of_possession(&mut party_rng, party, challenge)
+                    .await
+            })
+        }
+        let outputs = futures::future::try_join_all(outputs)
+            .await
+            .expect("keygen failed");
+        for (key_share, pop) in &outputs {
+            assert_eq!(*pop, outputs[0].1);
+            pop.verify::<Sha256>(&key_share.shared_public_key, challenge)
+                .expect("invalid proof of possession");
+        }
+    }
+
+    #[tokio::test]
+    async fn keygen_is_reproducible_from_seed<E: Curve>() {
+        use cggmp21::test_utils::SeededRng;
+
+        let (t, n) = (2, 3);
+        let eid = ExecutionId::new(b"reproducible keygen");
+
+        let run_keygen = |seed: &'static [u8]| async move {
+            let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+            let mut outputs = vec![];
+            for i in 0..n {
+                let party = simulation.add_party();
+                let mut party_rng = SeededRng::for_party(seed, i);
+                outputs.push(async move {
+                    cggmp21::keygen::<E>(eid, i, n)
+                        .set_threshold(t)
+                        .start(&mut party_rng, party)
+                        .await
+                })
+            }
+            futures::future::try_join_all(outputs)
+                .await
+                .expect("keygen failed")
+        };
+
+        let shares1 = run_keygen(b"seed").await;
+        let shares2 = run_keygen(b"seed").await;
+        let shares3 = run_keygen(b"another seed").await;
+
+        for (share1, share2) in shares1.iter().zip(&shares2) {
+            assert_eq!(
+                serde_json::to_string(share1).unwrap(),
+                serde_json::to_string(share2).unwrap()
+            );
+        }
+        assert_ne!(shares1[0].shared_public_key, shares3[0].shared_public_key);
+    }
+
+    #[tokio::test]
+    async fn keygen_reports_peer_abort<E: Curve>() {
+        use cggmp21::abort::MsgAbort;
+        use futures::SinkExt;
+        use round_based::{Delivery, Mpc, MpcParty};
+
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut outputs = vec![];
+        for i in 0..n - 1 {
+            let party = simulation.add_party();
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+
+            outputs.push(async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+
+        // Last party aborts the protocol instead of participating
+        let MpcParty { delivery, .. } = simulation.add_party().into_party();
+        let (_incomings, mut outgoings) = delivery.split();
+        outgoings.send(MsgAbort::broadcast()).await.unwrap();
+
+        for result in futures::future::join_all(outputs).await {
+            let err = result.expect_err("protocol must be aborted");
+            assert_eq!(err.aborted_by_peer(), Some(n - 1));
+
+            let report = err.report();
+            assert_eq!(report.code, cggmp21::error_code::ErrorCode::PeerAborted);
+            assert_eq!(report.parties, [n - 1]);
+        }
+    }
+
     #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
     mod secp256k1 {}
     #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
@@ -1,7 +1,39 @@
+mod adversarial;
+mod aux_proofs;
+mod blocking;
+mod co_signer;
+mod debug_replay;
+mod demo;
+mod digests;
+mod erasure;
+mod error_codes;
+mod health_check;
+mod kat;
 mod key_refresh;
+mod key_share_parts;
 mod keygen;
+mod network;
 mod old_shares;
 mod pipeline;
+mod poly;
+mod pregenerated_primes;
+mod progress;
+mod pvss;
+mod registry;
+mod rekey;
+mod ring_pedersen;
+mod roster;
+mod secp384r1;
+mod secret_storage;
+mod share_recovery;
+mod shared_aux;
+#[cfg(feature = "side-channel")]
+mod side_channel;
 mod signing;
 mod stark_prehashed;
+mod transcript;
+mod transport;
 mod trusted_dealer;
+mod tss_lib;
+mod upgrade;
+mod version;
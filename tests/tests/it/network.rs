@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use cggmp21::{
+    security_level::SecurityLevel128,
+    signing::{msg::Msg, DataToSign},
+    supported_curves::Secp256k1,
+    ExecutionId,
+};
+use cggmp21_tests::network::{LinkConditions, NetworkSimulation};
+use rand::Rng;
+use sha2::Sha256;
+
+#[tokio::test]
+async fn signing_over_unreliable_network() {
+    let mut rng = rand_dev::DevRng::new();
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(Some(2), 3, false)
+        .expect("retrieve cached shares");
+
+    let mut simulation = NetworkSimulation::<Msg<Secp256k1, Sha256>>::new(rng.gen());
+    simulation.set_default_link(LinkConditions {
+        latency: Duration::from_millis(5),
+        jitter: Duration::from_millis(10),
+        reorder_rate: 0.3,
+        drop_rate: 0.,
+    });
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message over slow network");
+    let participants = &[0, 2];
+
+    let outputs = (0..).zip(participants).map(|(i, &j)| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        let share = &shares[usize::from(j)];
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    });
+    let signatures = futures::future::try_join_all(outputs)
+        .await
+        .expect("signing failed");
+    signatures[0]
+        .verify(&shares[0].shared_public_key, &message_to_sign)
+        .expect("signature is not valid");
+    assert!(signatures.iter().all(|s| *s == signatures[0]));
+
+    let stats = simulation.stats();
+    assert!(stats.delivered > 0);
+    assert_eq!(stats.dropped, 0);
+}
+
+#[tokio::test]
+async fn signing_stalls_if_link_drops_messages() {
+    let mut rng = rand_dev::DevRng::new();
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(Some(2), 3, false)
+        .expect("retrieve cached shares");
+
+    let mut simulation = NetworkSimulation::<Msg<Secp256k1, Sha256>>::new(rng.gen());
+    simulation.set_link(
+        1,
+        0,
+        LinkConditions {
+            drop_rate: 1.,
+            ..LinkConditions::perfect()
+        },
+    );
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message over broken network");
+    let participants = &[0, 2];
+
+    let outputs = (0..).zip(participants).map(|(i, &j)| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        let share = &shares[usize::from(j)];
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    });
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        futures::future::try_join_all(outputs),
+    )
+    .await;
+    assert!(result.is_err(), "signing must not complete");
+    assert!(simulation.stats().dropped > 0);
+}
@@ -86,6 +86,7 @@ mod generic {
             let pregenerated_data = primes.next().expect("Can't fetch primes");
             async move {
                 cggmp21::aux_info_gen(eid, i, n, pregenerated_data)
+                    .dangerous_allow_blum_primes(true)
                     .start(&mut party_rng, party)
                     .await
             }
@@ -0,0 +1,103 @@
+#[generic_tests::define]
+mod test {
+    use cggmp21::{poly, security_level::SecurityLevel128, trusted_dealer};
+    use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+    use rand::seq::SliceRandom;
+    use rand_dev::DevRng;
+
+    #[test]
+    fn interpolation<E: Curve>() {
+        let mut rng = DevRng::new();
+        let (t, n) = (3, 5);
+
+        let sk = NonZero::<SecretScalar<E>>::random(&mut rng);
+        let shares = trusted_dealer::builder::<E, SecurityLevel128>(n)
+            .set_threshold(Some(t))
+            .set_shared_secret_key(sk.clone())
+            .generate_core_shares(&mut rng)
+            .unwrap();
+        let indexes = &shares[0].vss_setup.as_ref().unwrap().I;
+        let public_shares = &shares[0].public_shares;
+
+        let mut signers = (0..usize::from(n)).collect::<Vec<_>>();
+        signers.shuffle(&mut rng);
+        signers.truncate(t.into());
+        let signers_indexes = poly::subset(&signers, indexes).unwrap();
+        let signers_public_shares = poly::subset(&signers, public_shares).unwrap();
+        let signers_shares = signers
+            .iter()
+            .map(|&j| shares[j].x.clone())
+            .collect::<Vec<_>>();
+
+        // Secret key and public key are interpolated at zero
+        let sk_interpolated: Scalar<E> =
+            poly::interpolate(Scalar::zero(), &signers_indexes, &signers_shares).unwrap();
+        let sk: &Scalar<E> = sk.as_ref();
+        assert_eq!(sk_interpolated, *sk);
+        let pk: Point<E> =
+            poly::interpolate(Scalar::zero(), &signers_indexes, &signers_public_shares).unwrap();
+        assert_eq!(pk, *shares[0].shared_public_key);
+
+        // Public shares of other signers can be derived
+        for (index, public_share) in indexes.iter().zip(public_shares) {
+            let derived: Point<E> =
+                poly::interpolate(**index, &signers_indexes, &signers_public_shares).unwrap();
+            assert_eq!(derived, **public_share);
+        }
+
+        // Additive shares sum up to the secret key and public key
+        let additive = (0..signers_shares.len())
+            .map(|k| poly::to_additive_share(k, &signers_indexes, &signers_shares[k]).unwrap())
+            .sum::<Scalar<E>>();
+        assert_eq!(additive, sk_interpolated);
+        let additive = (0..signers_public_shares.len())
+            .map(|k| {
+                poly::to_additive_share(k, &signers_indexes, &signers_public_shares[k]).unwrap()
+            })
+            .sum::<Point<E>>();
+        assert_eq!(additive, pk);
+
+        let lambda = poly::lagrange_coefficients(Scalar::zero(), &signers_indexes).unwrap();
+        assert_eq!(lambda.len(), signers_indexes.len());
+        assert!(poly::lagrange_coefficients(*signers_indexes[0], &signers_indexes).is_none());
+
+        // Invalid inputs
+        assert!(poly::interpolate::<E, _, Point<E>>(
+            Scalar::zero(),
+            &signers_indexes,
+            &signers_public_shares[1..]
+        )
+        .is_none());
+        let duplicated = [signers_indexes[0], signers_indexes[0], signers_indexes[1]];
+        assert!(poly::interpolate::<E, _, Point<E>>(
+            Scalar::zero(),
+            &duplicated,
+            &signers_public_shares
+        )
+        .is_none());
+        assert!(poly::subset(&[n], public_shares).is_none());
+    }
+
+    #[test]
+    fn evaluate_at_consecutive_points<E: Curve>() {
+        let mut rng = DevRng::new();
+
+        for degree in [0, 1, 2, 5] {
+            let f = poly::Polynomial::<Scalar<E>>::sample(&mut rng, degree);
+            let commitment = &f * &Point::generator();
+
+            let values = poly::evaluate_at_consecutive_points(&commitment, 10);
+            assert_eq!(values.len(), 10);
+            for (x, value) in (1u16..).zip(values) {
+                assert_eq!(value, commitment.value::<_, Point<E>>(&Scalar::from(x)));
+            }
+        }
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
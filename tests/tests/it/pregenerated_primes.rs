@@ -0,0 +1,231 @@
+use std::num::NonZeroUsize;
+
+use cggmp21::{define_security_level, rug::integer::IsPrime, PregeneratedPrimes};
+use rand::{Rng, SeedableRng};
+use rand_dev::DevRng;
+
+/// Dummy security level that enables fast primes generation
+#[derive(Clone)]
+struct DummyLevel;
+define_security_level!(DummyLevel {
+    security_bits = 128,
+    epsilon = 64,
+    ell = 128,
+    ell_prime = 128,
+    m = 128,
+    q = (cggmp21::rug::Integer::ONE.clone() << 128) - 1,
+});
+
+fn assert_safe_prime(x: &cggmp21::rug::Integer) {
+    assert_eq!(x.significant_bits(), 4 * 128);
+    assert_ne!(x.is_probably_prime(25), IsPrime::No);
+    let half = (x.clone() - 1u8) >> 1;
+    assert_ne!(half.is_probably_prime(25), IsPrime::No);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_generation_outputs_safe_primes() {
+    let mut rng = DevRng::new();
+    let pool = cggmp21::rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap();
+    let (p, q) = PregeneratedPrimes::<DummyLevel>::generate_parallel(&mut rng, Some(&pool)).split();
+    assert_safe_prime(&p);
+    assert_safe_prime(&q);
+    assert_ne!(p, q);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn single_threaded_pool_is_honored() {
+    let seed: [u8; 32] = DevRng::new().gen();
+    let pool = cggmp21::rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+
+    // Generation on a single-threaded pool is deterministic, while global pool
+    // may have many threads
+    let (p, q) = PregeneratedPrimes::<DummyLevel>::generate_parallel(
+        &mut DevRng::from_seed(seed),
+        Some(&pool),
+    )
+    .split();
+    let expected = PregeneratedPrimes::<DummyLevel>::generate(&mut DevRng::from_seed(seed)).split();
+    assert_eq!((p, q), expected);
+
+    // Same when generation is invoked from within the pool
+    let (p, q) = pool
+        .install(|| {
+            PregeneratedPrimes::<DummyLevel>::generate_parallel(&mut DevRng::from_seed(seed), None)
+        })
+        .split();
+    assert_eq!((p, q), expected);
+}
+
+#[test]
+fn primes_pool_hands_out_primes() {
+    use cggmp21::key_refresh::PrimesPool;
+
+    let pool = PrimesPool::<DummyLevel>::start(
+        DevRng::new(),
+        NonZeroUsize::new(2).unwrap(),
+        NonZeroUsize::new(2).unwrap(),
+    );
+    assert_eq!(pool.depth(), 2);
+
+    let mut taken = vec![];
+    for _ in 0..3 {
+        let (p, q) = pool.take().split();
+        assert_safe_prime(&p);
+        assert_safe_prime(&q);
+        taken.push(p);
+        taken.push(q);
+    }
+    taken.sort();
+    taken.dedup();
+    assert_eq!(taken.len(), 6);
+
+    // Pool is refilled in the background
+    while pool.ready() < pool.depth() {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert!(pool.try_take().is_some());
+}
+
+#[test]
+fn validation() {
+    use cggmp21::rug::{Complete, Integer};
+
+    let mut rng = DevRng::new();
+    let valid = PregeneratedPrimes::<DummyLevel>::generate(&mut rng);
+    valid.validate().unwrap();
+    let (p, q) = valid.clone().split();
+
+    // Blum prime which is not a safe prime
+    let mut blum = Integer::from(1u8) << (4 * 128 - 1);
+    loop {
+        blum.next_prime_mut();
+        if blum.mod_u(4) == 3 && (blum.clone() >> 1).is_probably_prime(25) == IsPrime::No {
+            break;
+        }
+    }
+    // Composite number which is 3 modulo 4
+    let composite = (&p * Integer::from(5)).complete();
+
+    for (p, q) in [(p.clone(), p.clone()), (p.clone(), blum), (composite, q)] {
+        let primes = PregeneratedPrimes::<DummyLevel>::new(p, q).unwrap();
+        assert!(primes.validate().is_err());
+    }
+
+    // Primes which are too small can only be obtained via deserialization
+    let mut serialized = serde_json::to_value(&valid).unwrap();
+    serialized["q"] = serde_json::to_value(Integer::from(23)).unwrap();
+    let primes: PregeneratedPrimes<DummyLevel> = serde_json::from_value(serialized).unwrap();
+    assert!(primes.validate().is_err());
+}
+
+#[tokio::test]
+async fn blum_primes_are_rejected_unless_allowed() {
+    use cggmp21::{
+        key_refresh::{AuxOnlyMsg, PrimesKind},
+        security_level::SecurityLevel128,
+        ExecutionId,
+    };
+    use round_based::simulation::Simulation;
+    use sha2::Sha256;
+
+    let mut rng = DevRng::new();
+    let n = 2;
+    let primes = std::iter::repeat_with(|| {
+        PregeneratedPrimes::<SecurityLevel128>::generate_with_kind(&mut rng, PrimesKind::Blum)
+    })
+    .take(n.into())
+    .collect::<Vec<_>>();
+    for primes in &primes {
+        assert_eq!(primes.kind(), PrimesKind::Blum);
+        primes.validate().unwrap();
+    }
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    // Rejected by default
+    let mut simulation = Simulation::<AuxOnlyMsg<Sha256, SecurityLevel128>>::new();
+    let result = cggmp21::aux_info_gen(eid, 0, n, primes[0].clone())
+        .start(&mut rng, simulation.add_party())
+        .await;
+    assert!(result.is_err());
+
+    // Blum primes declared as safe are rejected as well
+    let (p, q) = primes[0].clone().split();
+    let mislabeled = PregeneratedPrimes::<SecurityLevel128>::new(p, q).unwrap();
+    assert_eq!(mislabeled.kind(), PrimesKind::Safe);
+    let mut simulation = Simulation::<AuxOnlyMsg<Sha256, SecurityLevel128>>::new();
+    let err = cggmp21::aux_info_gen(eid, 0, n, mislabeled)
+        .start(&mut rng, simulation.add_party())
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.code(),
+        cggmp21::error_code::ErrorCode::BlumPrimesNotAllowed
+    );
+
+    // Accepted when explicitly allowed
+    let mut simulation = Simulation::<AuxOnlyMsg<Sha256, SecurityLevel128>>::new();
+    let outputs = (0..).zip(primes).map(|(i, primes)| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::aux_info_gen(eid, i, n, primes)
+                .dangerous_allow_blum_primes(true)
+                .start(&mut party_rng, party)
+                .await
+        }
+    });
+    futures::future::try_join_all(outputs)
+        .await
+        .expect("aux gen failed");
+}
+
+#[test]
+fn generation_with_progress_can_be_cancelled() {
+    use cggmp21::key_refresh::CancellationToken;
+
+    let mut rng = DevRng::new();
+
+    // Progress is reported until both primes are found
+    let mut last_progress = None;
+    let (p, q) = PregeneratedPrimes::<DummyLevel>::generate_with_progress(
+        &mut rng,
+        &CancellationToken::new(),
+        |progress| last_progress = Some(progress.clone()),
+    )
+    .unwrap()
+    .split();
+    assert_safe_prime(&p);
+    assert_safe_prime(&q);
+    let last_progress = last_progress.unwrap();
+    assert_eq!(last_progress.primes_found, 2);
+    assert!(last_progress.candidates_tried >= 2);
+
+    // Generation stops once cancelled
+    let cancel = CancellationToken::new();
+    let mut candidates_tried = 0;
+    let result =
+        PregeneratedPrimes::<DummyLevel>::generate_with_progress(&mut rng, &cancel, |progress| {
+            candidates_tried = progress.candidates_tried;
+            if candidates_tried == 10 {
+                cancel.cancel()
+            }
+        });
+    assert!(result.is_err());
+    assert_eq!(candidates_tried, 10);
+
+    // Cancelled token prevents generation from starting
+    let result =
+        PregeneratedPrimes::<DummyLevel>::generate_with_progress(&mut rng, &cancel, |_| {});
+    assert!(result.is_err());
+}
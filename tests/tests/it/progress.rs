@@ -0,0 +1,492 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use cggmp21::{
+    progress::{BandwidthTracer, DurationStats, Event, EventLog, PerfProfiler, PerfReport, Tracer},
+    security_level::SecurityLevel128,
+    supported_curves::Secp256k1,
+    ExecutionId,
+};
+use futures::{Sink, SinkExt, StreamExt};
+use rand::Rng;
+use rand_dev::DevRng;
+use round_based::{
+    simulation::{MockedDelivery, MockedOutgoing, Simulation},
+    Delivery, MessageDestination, MpcParty, Outgoing,
+};
+use serde::Serialize;
+use sha2::Sha256;
+
+type E = Secp256k1;
+type L = SecurityLevel128;
+
+/// Checks that party `i` reported amount of received messages and processed every peer
+fn assert_peers_traced(log: &EventLog, i: u16, n: u16) {
+    let counts = log
+        .events()
+        .iter()
+        .filter_map(|e| match e.event {
+            Event::MsgsReceived { count } => Some(count),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert!(!counts.is_empty());
+    assert!(
+        counts.iter().all(|&c| c == Some(usize::from(n - 1))),
+        "{counts:?}"
+    );
+
+    let mut peers = log
+        .events()
+        .iter()
+        .filter_map(|e| match e.event {
+            Event::ProcessPeer { peer } => Some(peer),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    peers.sort();
+    peers.dedup();
+    assert_eq!(peers, (0..n).filter(|&j| j != i).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn keygen_traces_peers_and_received_msgs() {
+    let mut rng = DevRng::new();
+    let n = 3;
+
+    let mut simulation = Simulation::<cggmp21::keygen::NonThresholdMsg<E, L, Sha256>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let outputs = (0..n).map(|i| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        async move {
+            let mut log = EventLog::new();
+            cggmp21::keygen::<E>(eid, i, n)
+                .set_progress_tracer(&mut log)
+                .start(&mut party_rng, party)
+                .await?;
+            Ok::<_, cggmp21::keygen::KeygenError>(log)
+        }
+    });
+    let logs = futures::future::try_join_all(outputs)
+        .await
+        .expect("keygen failed");
+
+    for (i, log) in (0..).zip(&logs) {
+        assert_peers_traced(log, i, n);
+    }
+}
+
+#[tokio::test]
+async fn aux_gen_traces_peers_and_received_msgs() {
+    let mut rng = DevRng::new();
+    let n = 3;
+
+    let mut primes = cggmp21_tests::CACHED_PRIMES.iter::<L>();
+    let mut simulation = Simulation::<cggmp21::key_refresh::AuxOnlyMsg<Sha256, L>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let outputs = (0..n).map(|i| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        let pregenerated_primes = primes.next().expect("can't fetch primes");
+        async move {
+            let mut log = EventLog::new();
+            cggmp21::aux_info_gen(eid, i, n, pregenerated_primes)
+                .dangerous_allow_blum_primes(true)
+                .set_progress_tracer(&mut log)
+                .start(&mut party_rng, party)
+                .await?;
+            Ok::<_, cggmp21::key_refresh::KeyRefreshError>(log)
+        }
+    });
+    let logs = futures::future::try_join_all(outputs)
+        .await
+        .expect("aux gen failed");
+
+    for (i, log) in (0..).zip(&logs) {
+        assert_peers_traced(log, i, n);
+    }
+}
+
+#[tokio::test]
+async fn key_refresh_traces_peers_and_received_msgs() {
+    let mut rng = DevRng::new();
+    let n = 3;
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, L>(None, n, false)
+        .expect("retrieve cached shares");
+    let mut primes = cggmp21_tests::CACHED_PRIMES.iter::<L>();
+    let mut simulation = Simulation::<cggmp21::key_refresh::NonThresholdMsg<E, Sha256, L>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let outputs = shares.iter().map(|share| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        let pregenerated_primes = primes.next().expect("can't fetch primes");
+        async move {
+            let mut log = EventLog::new();
+            cggmp21::key_refresh(eid, share, pregenerated_primes)
+                .dangerous_allow_blum_primes(true)
+                .set_progress_tracer(&mut log)
+                .start(&mut party_rng, party)
+                .await?;
+            Ok::<_, cggmp21::key_refresh::KeyRefreshError>(log)
+        }
+    });
+    let logs = futures::future::try_join_all(outputs)
+        .await
+        .expect("key refresh failed");
+
+    for (i, log) in (0..).zip(&logs) {
+        assert_peers_traced(log, i, n);
+    }
+}
+
+type SendError<M> = <MockedOutgoing<M> as Sink<Outgoing<M>>>::Error;
+
+/// Reports sizes of serialized messages that party sends and receives to the bandwidth tracer
+///
+/// Returns sizes of sent messages in order they were sent
+fn metered<M>(
+    party: MpcParty<M, MockedDelivery<M>>,
+    bandwidth: BandwidthTracer,
+) -> (MpcParty<M, impl Delivery<M>>, Arc<Mutex<Vec<u64>>>)
+where
+    M: Serialize + Clone + Send + Unpin + 'static,
+{
+    let size = |msg: &M| serde_json::to_vec(msg).expect("serialize message").len();
+    let sent = Arc::new(Mutex::new(vec![]));
+    let (incomings, outgoings) = party.into_party().delivery.split();
+
+    let incomings = {
+        let bandwidth = bandwidth.clone();
+        incomings.inspect(move |incoming| {
+            if let Ok(incoming) = incoming {
+                bandwidth.record_received(incoming.sender, size(&incoming.msg));
+            }
+        })
+    };
+    let outgoings = {
+        let sent = sent.clone();
+        outgoings.with(move |outgoing: Outgoing<M>| {
+            let bytes = size(&outgoing.msg);
+            sent.lock().unwrap().push(bytes as u64);
+            let recipient = match outgoing.recipient {
+                MessageDestination::AllParties => None,
+                MessageDestination::OneParty(j) => Some(j),
+            };
+            bandwidth.record_sent(recipient, bytes);
+            futures::future::ready(Ok::<_, SendError<M>>(outgoing))
+        })
+    };
+    (MpcParty::connected((incomings, outgoings)), sent)
+}
+
+#[tokio::test]
+async fn bandwidth_tracer_accounts_serialized_msgs() {
+    let mut rng = DevRng::new();
+    let n: u16 = 3;
+
+    let mut simulation = Simulation::<cggmp21::keygen::NonThresholdMsg<E, L, Sha256>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let outputs = (0..n).map(|i| {
+        let bandwidth = BandwidthTracer::new();
+        let (party, sent) = metered(simulation.add_party(), bandwidth.clone());
+        let mut party_rng = rng.fork();
+        async move {
+            let keygen = cggmp21::keygen::<E>(eid, i, n);
+            let rounds_count = keygen.rounds_count();
+            let mut tracer = bandwidth.clone();
+            keygen
+                .set_progress_tracer(&mut tracer)
+                .start(&mut party_rng, party)
+                .await?;
+            let sent = sent.lock().unwrap().clone();
+            Ok::<_, cggmp21::keygen::KeygenError>((bandwidth.get_report(), sent, rounds_count))
+        }
+    });
+    let outputs = futures::future::try_join_all(outputs)
+        .await
+        .expect("keygen failed");
+
+    for (report, sent, rounds_count) in &outputs {
+        assert_eq!(report.rounds.len(), usize::from(*rounds_count));
+        assert_eq!(report.setup.total_sent(), 0);
+        assert_eq!(report.setup.total_received(), 0);
+
+        // Keygen broadcasts at most one message per round
+        assert!(report.rounds.iter().all(|r| r.sent_p2p.is_empty()));
+        let sent_per_round = report
+            .rounds
+            .iter()
+            .map(|r| r.sent_broadcast)
+            .filter(|&bytes| bytes > 0)
+            .collect::<Vec<_>>();
+        assert_eq!(&sent_per_round, sent);
+        assert_eq!(report.total_sent(), sent.iter().sum::<u64>());
+    }
+
+    // Messages sent by party `j` within a round are received by the others by the end of the
+    // next round, and not earlier than in the same round
+    for (i, (report_i, _, _)) in (0..n).zip(&outputs) {
+        for (j, (report_j, sent_j, _)) in (0..n).zip(&outputs) {
+            if i == j {
+                continue;
+            }
+            let mut received = 0;
+            let mut sent = 0;
+            for (round_i, round_j) in report_i.rounds.iter().zip(&report_j.rounds) {
+                let sent_before = sent;
+                received += round_i.received.get(&j).copied().unwrap_or(0);
+                sent += round_j.total_sent();
+                assert!(sent_before <= received && received <= sent, "i={i} j={j}");
+            }
+            assert_eq!(
+                report_i.received_per_peer().get(&j).copied(),
+                Some(sent_j.iter().sum::<u64>())
+            );
+        }
+    }
+}
+
+/// Reports arrivals of messages to the profiler, and delays each message sent by the party
+fn delayed<M>(
+    party: MpcParty<M, MockedDelivery<M>>,
+    arrivals: MsgArrivals,
+    delay: Duration,
+) -> MpcParty<M, impl Delivery<M>>
+where
+    M: Clone + Send + Unpin + 'static,
+{
+    let (incomings, outgoings) = party.into_party().delivery.split();
+    let incomings = incomings.inspect(move |incoming| {
+        if let Ok(incoming) = incoming {
+            arrivals.record(incoming.sender);
+        }
+    });
+    let outgoings = outgoings.with(move |outgoing: Outgoing<M>| {
+        Box::pin(async move {
+            tokio::time::sleep(delay).await;
+            Ok::<_, SendError<M>>(outgoing)
+        })
+    });
+    MpcParty::connected((incomings, outgoings))
+}
+
+#[tokio::test]
+async fn profiler_reports_delayed_party_as_slowest() {
+    let mut rng = DevRng::new();
+    let n: u16 = 3;
+    let slow_party: u16 = 2;
+    let delay = Duration::from_millis(100);
+
+    let mut simulation = Simulation::<cggmp21::keygen::NonThresholdMsg<E, L, Sha256>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let outputs = (0..n).map(|i| {
+        let mut profiler = PerfProfiler::new();
+        let delay = if i == slow_party {
+            delay
+        } else {
+            Duration::ZERO
+        };
+        let party = delayed(simulation.add_party(), profiler.track_msg_arrivals(), delay);
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::keygen::<E>(eid, i, n)
+                .set_progress_tracer(&mut profiler)
+                .start(&mut party_rng, party)
+                .await?;
+            Ok::<_, cggmp21::keygen::KeygenError>(profiler.get_report().expect("profiler failed"))
+        }
+    });
+    let reports = futures::future::try_join_all(outputs)
+        .await
+        .expect("keygen failed");
+
+    for (i, report) in (0..n).zip(&reports) {
+        if i == slow_party {
+            continue;
+        }
+        // Each round, we wait for the slow party to send its message
+        for round in &report.rounds {
+            assert_eq!(round.slowest_peer, Some(slow_party), "i={i} {round:?}");
+        }
+
+        let peers_wait = report.peers_wait();
+        let slow_party_wait = peers_wait[&slow_party];
+        assert!(
+            slow_party_wait >= delay * 3 / 4 * report.rounds.len() as u32,
+            "i={i} {peers_wait:?}"
+        );
+        assert!(
+            peers_wait
+                .iter()
+                .all(|(j, wait)| *j == slow_party || *wait < slow_party_wait),
+            "i={i} {peers_wait:?}"
+        );
+    }
+}
+
+/// Builds a report with two rounds, durations of which are then overwritten with known values
+fn report(setup: u64, rounds: [(u64, u64, u64); 2]) -> PerfReport {
+    let mut profiler = PerfProfiler::new();
+    profiler.protocol_begins();
+    profiler.stage("load");
+    profiler.named_round_begins("Commit");
+    profiler.stage("prove");
+    profiler.send_msg();
+    profiler.msg_sent();
+    profiler.receive_msgs();
+    profiler.msgs_received();
+    profiler.named_round_begins("Decommit, prove");
+    profiler.send_msg();
+    profiler.msg_sent();
+    profiler.protocol_ends();
+    let mut report = profiler.get_report().expect("profiler failed");
+
+    let ms = Duration::from_millis;
+    report.setup = ms(setup);
+    report.setup_stages[0].duration = ms(setup - 1);
+    for (round, (computation, sending, receiving)) in report.rounds.iter_mut().zip(rounds) {
+        round.computation = ms(computation);
+        round.sending = ms(sending);
+        round.receiving = ms(receiving);
+        for stage in &mut round.stages {
+            stage.duration = ms(computation - 3);
+        }
+    }
+    report
+}
+
+#[test]
+fn perf_report_to_csv() {
+    let report = report(5, [(10, 1, 2), (3, 1, 0)]);
+    assert_eq!(
+        report.to_csv(),
+        "round,round_name,kind,name,duration_ns\n\
+         0,setup,total,,5000000\n\
+         0,setup,stage,load,4000000\n\
+         1,Commit,computation,,10000000\n\
+         1,Commit,sending,,1000000\n\
+         1,Commit,receiving,,2000000\n\
+         1,Commit,stage,prove,7000000\n\
+         2,\"Decommit, prove\",computation,,3000000\n\
+         2,\"Decommit, prove\",sending,,1000000\n\
+         2,\"Decommit, prove\",receiving,,0\n"
+    );
+}
+
+#[test]
+fn perf_reports_merge() {
+    let ms = Duration::from_millis;
+    let reports = [
+        report(5, [(10, 1, 2), (3, 1, 0)]),
+        report(7, [(20, 1, 4), (5, 3, 0)]),
+        report(3, [(30, 1, 6), (4, 2, 0)]),
+    ];
+
+    let stats = PerfReport::merge(&reports).expect("reports are compatible");
+    assert_eq!(stats.runs, 3);
+    assert_eq!(
+        stats.setup,
+        DurationStats {
+            mean: ms(5),
+            min: ms(3),
+            max: ms(7),
+            p50: ms(5),
+            p90: ms(7),
+            p99: ms(7),
+        }
+    );
+    assert_eq!(
+        stats.total,
+        DurationStats::new(reports.iter().map(PerfReport::total).collect())
+    );
+    assert_eq!(stats.total.mean, ms(36));
+
+    assert_eq!(stats.rounds.len(), 2);
+    assert_eq!(stats.rounds[0].round_name.as_deref(), Some("Commit"));
+    assert_eq!(
+        stats.rounds[1].round_name.as_deref(),
+        Some("Decommit, prove")
+    );
+    assert_eq!(stats.rounds[0].computation.mean, ms(20));
+    assert_eq!(stats.rounds[0].computation.p50, ms(20));
+    assert_eq!(stats.rounds[0].receiving.max, ms(6));
+    assert_eq!(stats.rounds[1].sending.min, ms(1));
+    assert_eq!(stats.rounds[1].receiving, DurationStats::new(vec![]));
+
+    let csv = stats.to_csv();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("round,round_name,kind,mean_ns,min_ns,max_ns,p50_ns,p90_ns,p99_ns")
+    );
+    assert_eq!(
+        lines.nth(1),
+        Some("0,setup,computation,5000000,3000000,7000000,5000000,7000000,7000000")
+    );
+    assert_eq!(csv.lines().count(), 1 + 2 + 3 * 2);
+
+    // Reports of different protocols can't be merged
+    let mut other = report(5, [(10, 1, 2), (3, 1, 0)]);
+    other.rounds.pop();
+    assert!(PerfReport::merge([&reports[0], &other]).is_none());
+    assert!(PerfReport::merge([]).is_none());
+}
+
+#[test]
+fn duration_stats() {
+    let ms = Duration::from_millis;
+
+    // Order of measurements doesn't matter
+    let measurements = (1..=100).rev().map(ms).collect();
+    assert_eq!(
+        DurationStats::new(measurements),
+        DurationStats {
+            mean: Duration::from_micros(50_500),
+            min: ms(1),
+            max: ms(100),
+            p50: ms(50),
+            p90: ms(90),
+            p99: ms(99),
+        }
+    );
+
+    // Nearest-rank percentiles of small samples
+    let stats = DurationStats::new(vec![ms(4), ms(1), ms(2), ms(3)]);
+    assert_eq!(stats.mean, Duration::from_micros(2_500));
+    assert_eq!((stats.min, stats.max), (ms(1), ms(4)));
+    assert_eq!((stats.p50, stats.p90, stats.p99), (ms(2), ms(4), ms(4)));
+
+    let stats = DurationStats::new(vec![ms(7)]);
+    assert_eq!(
+        stats,
+        DurationStats {
+            mean: ms(7),
+            min: ms(7),
+            max: ms(7),
+            p50: ms(7),
+            p90: ms(7),
+            p99: ms(7),
+        }
+    );
+
+    let zero = Duration::ZERO;
+    assert_eq!(
+        DurationStats::new(vec![]),
+        DurationStats {
+            mean: zero,
+            min: zero,
+            max: zero,
+            p50: zero,
+            p90: zero,
+            p99: zero,
+        }
+    );
+}
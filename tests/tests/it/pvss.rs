@@ -0,0 +1,72 @@
+use cggmp21::{
+    generic_ec::Point,
+    key_share::{reconstruct_secret_key, AuxInfo, Validate},
+    pvss::{self, Dealing},
+    security_level::SecurityLevel128,
+    supported_curves::Secp256k1,
+    ExecutionId,
+};
+use rand::Rng;
+use sha2::Sha256;
+
+type E = Secp256k1;
+type L = SecurityLevel128;
+
+#[test]
+fn dealings_are_publicly_verifiable() {
+    let mut rng = rand_dev::DevRng::new();
+    let n = 3;
+    let t = 2;
+
+    let aux_infos = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, L>(None, n, false)
+        .expect("retrieve cached shares")
+        .into_iter()
+        .map(|share| share.into_inner().aux.validate().unwrap())
+        .collect::<Vec<AuxInfo<L>>>();
+    let parties = &aux_infos[0].parties;
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let dealings = (0..n)
+        .map(|i| pvss::deal::<E, L, Sha256, _>(&mut rng, eid, i, t, parties).unwrap())
+        .collect::<Vec<_>>();
+
+    // Dealings survive serialization
+    let serialized = serde_json::to_vec(&dealings).unwrap();
+    let dealings: Vec<Dealing<E>> = serde_json::from_slice(&serialized).unwrap();
+
+    // Observer verifies dealings knowing only public data
+    let key_info = pvss::verify_dealings::<E, L, Sha256>(eid, t, parties, &dealings).unwrap();
+
+    let key_shares = (0..)
+        .zip(aux_infos)
+        .map(|(i, aux)| pvss::complete::<E, L, Sha256>(eid, t, i, aux, &dealings).unwrap())
+        .collect::<Vec<_>>();
+    for key_share in &key_shares {
+        assert_eq!(key_share.shared_public_key, key_info.shared_public_key);
+        assert_eq!(key_share.public_shares, key_info.public_shares);
+    }
+
+    let sk = reconstruct_secret_key(&key_shares[..usize::from(t)]).unwrap();
+    assert_eq!(Point::generator() * sk, *key_info.shared_public_key);
+
+    // Dealings are bound to the execution id
+    let another_eid = ExecutionId::new(b"another execution");
+    let err =
+        pvss::verify_dealings::<E, L, Sha256>(another_eid, t, parties, &dealings).unwrap_err();
+    assert_eq!(err.blame(), [0, 1, 2]);
+
+    // Tampered ciphertext is detected and attributed to the dealer
+    let mut tampered = dealings.clone();
+    tampered[1].ciphertexts.swap(0, 2);
+    let err = pvss::verify_dealings::<E, L, Sha256>(eid, t, parties, &tampered).unwrap_err();
+    assert_eq!(err.blame(), [1]);
+
+    // Dealings must be ordered by party index
+    let mut reordered = dealings;
+    reordered.swap(0, 2);
+    let err = pvss::verify_dealings::<E, L, Sha256>(eid, t, parties, &reordered).unwrap_err();
+    assert_eq!(err.blame(), [0, 2]);
+}
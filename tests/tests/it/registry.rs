@@ -0,0 +1,51 @@
+use cggmp21::{
+    registry::KeyRegistry, security_level::SecurityLevel128,
+    signing::presignature_store::PresignatureStore, supported_curves::Secp256k1,
+};
+
+#[test]
+fn registry_tracks_keys_and_epochs() {
+    let shares_a = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, 3, false)
+        .expect("retrieve cached shares");
+    let shares_b = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(Some(2), 3, false)
+        .expect("retrieve cached shares");
+
+    let registry = KeyRegistry::<Secp256k1, SecurityLevel128>::new();
+    let ids = registry
+        .insert_many([shares_a[0].clone(), shares_b[0].clone()])
+        .unwrap();
+    assert_eq!(ids.len(), 2);
+    assert_eq!(registry.len().unwrap(), 2);
+
+    // Key can't be registered twice
+    assert!(registry.insert(shares_a[0].clone()).is_err());
+    assert!(registry
+        .insert_many([shares_a[1].clone(), shares_a[1].clone()])
+        .is_err());
+    assert_eq!(registry.len().unwrap(), 2);
+
+    let id_a = cggmp21::registry::KeyId::of(&shares_a[0].core.key_info);
+    let entry = registry.get(&id_a).unwrap();
+    assert_eq!(entry.epoch, 0);
+    assert_eq!(
+        entry.key_share.core.shared_public_key,
+        shares_a[0].core.shared_public_key
+    );
+    assert!(registry.presignatures(&id_a).unwrap().is_empty().unwrap());
+
+    // Key share of another key is not accepted as refreshed
+    assert!(registry.refresh(&id_a, shares_b[0].clone()).is_err());
+    assert_eq!(registry.refresh(&id_a, shares_a[0].clone()).unwrap(), 1);
+    assert_eq!(registry.get(&id_a).unwrap().epoch, 1);
+
+    let stale = registry.stale_keys(1).unwrap();
+    assert_eq!(stale.len(), 1);
+    assert_ne!(stale[0], id_a);
+
+    registry.retain(|entry| entry.epoch > 0).unwrap();
+    assert_eq!(registry.ids().unwrap(), [id_a]);
+    assert!(registry.remove(&id_a).is_some());
+    assert!(registry.is_empty().unwrap());
+}
@@ -0,0 +1,86 @@
+use cggmp21::{
+    key_share::{KeyShare, SharedAuxInfo, Validate},
+    rekey::{HandoverStatement, SignedHandover},
+    security_level::SecurityLevel128,
+    signing::{DataToSign, Signature},
+    supported_curves::Secp256k1,
+    trusted_dealer, ExecutionId,
+};
+use rand::Rng;
+use round_based::simulation::Simulation;
+use sha2::Sha256;
+
+#[tokio::test]
+async fn handover_is_signed_by_old_and_new_keys() {
+    let mut rng = rand_dev::DevRng::new();
+    let n = 3;
+
+    let old_shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, n, false)
+        .expect("retrieve cached shares");
+
+    // New key reuses aux info of the old key
+    let new_cores = trusted_dealer::builder::<Secp256k1, SecurityLevel128>(n)
+        .generate_core_shares(&mut rng)
+        .unwrap();
+    let new_shares = old_shares
+        .iter()
+        .zip(new_cores)
+        .map(|(old, core)| {
+            SharedAuxInfo::new(old.aux.clone().validate().unwrap())
+                .bind(core)
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let statement = HandoverStatement::new(b"rekey", &old_shares[0], &new_shares[0]);
+    let data_to_sign = statement.data_to_sign::<Sha256>();
+    let handover = SignedHandover {
+        old_key_signature: sign(&mut rng, &old_shares, data_to_sign).await,
+        new_key_signature: sign(&mut rng, &new_shares, data_to_sign).await,
+        statement,
+    };
+    handover.verify::<Sha256>().expect("handover is not valid");
+
+    // Handover survives serialization
+    let serialized = serde_json::to_vec(&handover).unwrap();
+    let deserialized: SignedHandover<Secp256k1> = serde_json::from_slice(&serialized).unwrap();
+    deserialized.verify::<Sha256>().unwrap();
+
+    // Signatures are bound to the statement
+    let mut tampered = handover.clone();
+    tampered.statement.ceremony_id = b"another rekey".to_vec();
+    assert!(tampered.verify::<Sha256>().is_err());
+
+    let mut swapped = handover;
+    std::mem::swap(
+        &mut swapped.old_key_signature,
+        &mut swapped.new_key_signature,
+    );
+    assert!(swapped.verify::<Sha256>().is_err());
+}
+
+async fn sign(
+    rng: &mut rand_dev::DevRng,
+    shares: &[KeyShare<Secp256k1, SecurityLevel128>],
+    data_to_sign: DataToSign<Secp256k1>,
+) -> Signature<Secp256k1> {
+    let mut simulation = Simulation::<cggmp21::signing::msg::Msg<Secp256k1, Sha256>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let participants = &(0..u16::try_from(shares.len()).unwrap()).collect::<Vec<_>>();
+
+    let outputs = (0..).zip(shares).map(|(i, share)| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .sign(&mut party_rng, party, data_to_sign)
+                .await
+        }
+    });
+    let signatures = futures::future::try_join_all(outputs)
+        .await
+        .expect("signing failed");
+    signatures[0]
+}
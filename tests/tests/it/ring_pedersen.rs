@@ -0,0 +1,145 @@
+use cggmp21::{
+    key_refresh::{apply_ring_pedersen_update, regenerate_ring_pedersen_params},
+    key_share::{DirtyKeyShare, Validate},
+    security_level::SecurityLevel128,
+    supported_curves::Secp256k1,
+    ExecutionId,
+};
+use rand::Rng;
+use round_based::simulation::Simulation;
+use sha2::Sha256;
+
+#[tokio::test]
+async fn regenerated_ring_pedersen_params_are_usable() {
+    let mut rng = rand_dev::DevRng::new();
+    let n = 3;
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, n, false)
+        .expect("retrieve cached shares");
+    let auxes = shares
+        .iter()
+        .map(|share| share.aux.clone().validate().unwrap())
+        .collect::<Vec<_>>();
+
+    // Party 1 regenerates its parameters
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let (new_aux_1, update) =
+        regenerate_ring_pedersen_params::<_, Sha256, _>(&mut rng, eid, &auxes[1]).unwrap();
+    assert_eq!(update.i, 1);
+    assert_ne!(new_aux_1.parties[1].s, auxes[1].parties[1].s);
+
+    // Update is rejected if it's verified with another execution id
+    let other_eid = ExecutionId::new(b"another eid");
+    assert!(apply_ring_pedersen_update::<_, Sha256>(other_eid, &auxes[0], &update).is_err());
+
+    let new_auxes = auxes
+        .iter()
+        .enumerate()
+        .map(|(j, aux)| {
+            if j == 1 {
+                new_aux_1.clone()
+            } else {
+                apply_ring_pedersen_update::<_, Sha256>(eid, aux, &update).unwrap()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Sign with updated aux info
+    let key_shares = shares
+        .into_iter()
+        .zip(new_auxes)
+        .map(|(share, aux)| {
+            DirtyKeyShare {
+                core: share.into_inner().core,
+                aux: aux.into_inner(),
+                policy: None,
+            }
+            .validate()
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let mut simulation = Simulation::<cggmp21::signing::msg::Msg<Secp256k1, Sha256>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let message_to_sign = cggmp21::signing::DataToSign::digest::<Sha256>(b"message");
+    let participants = &(0..n).collect::<Vec<_>>();
+
+    let outputs = (0..).zip(&key_shares).map(|(i, share)| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    });
+    let signatures = futures::future::try_join_all(outputs)
+        .await
+        .expect("signing failed");
+    signatures[0]
+        .verify(&key_shares[0].core.shared_public_key, &message_to_sign)
+        .expect("signature is not valid");
+}
+
+#[tokio::test]
+async fn aux_gen_with_external_ring_pedersen_params() {
+    use cggmp21::key_refresh::{AuxOnlyMsg, RingPedersenParams};
+
+    let mut rng = rand_dev::DevRng::new();
+    let n = 3;
+    let primes = cggmp21_tests::CACHED_PRIMES
+        .iter::<SecurityLevel128>()
+        .take(n.into())
+        .collect::<Vec<_>>();
+
+    // Parameters are produced externally, e.g. in HSM
+    let params = primes
+        .iter()
+        .map(|primes| {
+            let (p, q) = primes.clone().split();
+            RingPedersenParams::generate(&mut rng, &p, &q).unwrap()
+        })
+        .collect::<Vec<_>>();
+    let expected_s = params.iter().map(|p| p.s.clone()).collect::<Vec<_>>();
+
+    // Parameters that don't match the primes are rejected
+    {
+        let (p, q) = primes[1].clone().split();
+        let foreign = RingPedersenParams::generate(&mut rng, &p, &q).unwrap();
+        let mut simulation = Simulation::<AuxOnlyMsg<Sha256, SecurityLevel128>>::new();
+        let result = cggmp21::aux_info_gen(ExecutionId::new(b"eid"), 0, n, primes[0].clone())
+            .dangerous_allow_blum_primes(true)
+            .set_ring_pedersen_params(foreign)
+            .start(&mut rng, simulation.add_party())
+            .await;
+        assert!(result.is_err());
+    }
+
+    let mut simulation = Simulation::<AuxOnlyMsg<Sha256, SecurityLevel128>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let outputs = (0..)
+        .zip(primes.into_iter().zip(params))
+        .map(|(i, (primes, params))| {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::aux_info_gen(eid, i, n, primes)
+                    .dangerous_allow_blum_primes(true)
+                    .set_ring_pedersen_params(params)
+                    .start(&mut party_rng, party)
+                    .await
+            }
+        });
+    let auxes = futures::future::try_join_all(outputs)
+        .await
+        .expect("aux gen failed");
+
+    for aux in &auxes {
+        let s = aux.parties.iter().map(|p| p.s.clone()).collect::<Vec<_>>();
+        assert_eq!(s, expected_s);
+    }
+}
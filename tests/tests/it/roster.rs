@@ -0,0 +1,83 @@
+use cggmp21::{
+    keygen::ThresholdMsg, roster::Roster, security_level::SecurityLevel128,
+    supported_curves::Secp256k1, ExecutionId,
+};
+use generic_ec::{NonZero, Point, SecretScalar};
+use rand::Rng;
+use round_based::simulation::Simulation;
+use sha2::Sha256;
+
+#[tokio::test]
+async fn key_is_bound_to_roster() {
+    let mut rng = rand_dev::DevRng::new();
+    let (t, n) = (2, 3);
+
+    let identity_keys = (0..n)
+        .map(|_| SecretScalar::<Secp256k1>::random(&mut rng))
+        .collect::<Vec<_>>();
+    let identities = identity_keys
+        .iter()
+        .map(|sk| NonZero::from_point(Point::generator() * sk).unwrap())
+        .collect::<Vec<_>>();
+    let roster = Roster::new(identities.clone()).unwrap();
+
+    // Roster can't contain the same identity twice
+    assert!(Roster::new(vec![identities[0], identities[0]]).is_err());
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = roster.bind_execution_id(ExecutionId::new(&eid));
+    let eid = ExecutionId::new(&eid);
+
+    let mut simulation = Simulation::<ThresholdMsg<Secp256k1, SecurityLevel128, Sha256>>::new();
+    let outputs = (0..n).map(|i| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::keygen::<Secp256k1>(eid, i, n)
+                .set_threshold(t)
+                .start(&mut party_rng, party)
+                .await
+        }
+    });
+    let key_shares = futures::future::try_join_all(outputs)
+        .await
+        .expect("keygen failed");
+
+    let attestations = key_shares
+        .iter()
+        .zip(&identity_keys)
+        .map(|(key_share, sk)| {
+            roster
+                .attest::<Sha256, _>(&mut rng, eid, sk, key_share)
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    roster
+        .verify_attestations::<Sha256>(eid, &key_shares[0], &attestations)
+        .unwrap();
+
+    // Party can't attest with identity key of another party
+    assert!(roster
+        .attest::<Sha256, _>(&mut rng, eid, &identity_keys[1], &key_shares[0])
+        .is_err());
+
+    // Attestations don't verify against another roster
+    let mut reordered = identities.clone();
+    reordered.swap(0, 1);
+    let reordered = Roster::new(reordered).unwrap();
+    let err = reordered
+        .verify_attestations::<Sha256>(eid, &key_shares[0], &attestations)
+        .unwrap_err();
+    assert_eq!(err.blame(), [0, 1, 2]);
+
+    // Missing attestation is detected
+    let err = roster
+        .verify_attestations::<Sha256>(eid, &key_shares[0], &attestations[1..])
+        .unwrap_err();
+    assert_eq!(err.blame(), [0]);
+
+    // Roster survives serialization
+    let serialized = serde_json::to_vec(&roster).unwrap();
+    let deserialized: Roster<Secp256k1> = serde_json::from_slice(&serialized).unwrap();
+    assert_eq!(roster, deserialized);
+}
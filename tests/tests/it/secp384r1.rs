@@ -0,0 +1,149 @@
+use cggmp21::{
+    key_share::KeyShare,
+    security_level::SecurityLevel128Wide,
+    signing::{msg::Msg, DataToSign},
+    supported_curves::Secp384r1,
+    ExecutionId,
+};
+use cggmp21_tests::external_verifier::{rustcrypto::P384, ExternalVerifier};
+use rand::{Rng, RngCore};
+use rand_dev::DevRng;
+use round_based::simulation::Simulation;
+use sha2::Sha256;
+
+type E = Secp384r1;
+type L = SecurityLevel128Wide;
+type D = Sha256;
+
+#[tokio::test]
+async fn keygen_refresh_and_signing() {
+    let mut rng = DevRng::new();
+    let n = 3;
+
+    let mut simulation = Simulation::<cggmp21::keygen::NonThresholdMsg<E, L, D>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let outputs = (0..n).map(|i| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::keygen::<E>(eid, i, n)
+                .set_security_level::<L>()
+                .start(&mut party_rng, party)
+                .await
+        }
+    });
+    let incomplete_shares = futures::future::try_join_all(outputs)
+        .await
+        .expect("keygen failed");
+
+    let mut primes = cggmp21_tests::CACHED_PRIMES.iter::<L>();
+    let mut simulation = Simulation::<cggmp21::key_refresh::NonThresholdMsg<E, D, L>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let outputs = incomplete_shares.iter().map(|share| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        let pregenerated_primes = primes.next().expect("can't fetch primes");
+        async move {
+            cggmp21::key_refresh(eid, share, pregenerated_primes)
+                .dangerous_allow_blum_primes(true)
+                .start(&mut party_rng, party)
+                .await
+        }
+    });
+    let shares = futures::future::try_join_all(outputs)
+        .await
+        .expect("key refresh failed");
+    for share in &shares {
+        assert_eq!(
+            share.shared_public_key,
+            incomplete_shares[0].shared_public_key
+        );
+    }
+
+    sign_and_verify(&mut rng, &shares, &[0, 1, 2]).await;
+}
+
+#[tokio::test]
+async fn threshold_keygen_aux_gen_and_signing() {
+    let mut rng = DevRng::new();
+    let t = 2;
+    let n = 3;
+
+    let mut simulation = Simulation::<cggmp21::keygen::ThresholdMsg<E, L, D>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let outputs = (0..n).map(|i| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::keygen::<E>(eid, i, n)
+                .set_security_level::<L>()
+                .set_threshold(t)
+                .start(&mut party_rng, party)
+                .await
+        }
+    });
+    let incomplete_shares = futures::future::try_join_all(outputs)
+        .await
+        .expect("keygen failed");
+
+    let mut primes = cggmp21_tests::CACHED_PRIMES.iter::<L>();
+    let mut simulation = Simulation::<cggmp21::key_refresh::AuxOnlyMsg<D, L>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let outputs = (0..n).map(|i| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        let pregenerated_primes = primes.next().expect("can't fetch primes");
+        async move {
+            cggmp21::aux_info_gen(eid, i, n, pregenerated_primes)
+                .dangerous_allow_blum_primes(true)
+                .start(&mut party_rng, party)
+                .await
+        }
+    });
+    let aux_infos = futures::future::try_join_all(outputs)
+        .await
+        .expect("aux info generation failed");
+
+    let shares = incomplete_shares
+        .into_iter()
+        .zip(aux_infos)
+        .map(|(core, aux)| KeyShare::from_parts((core, aux)).expect("invalid key share"))
+        .collect::<Vec<_>>();
+
+    sign_and_verify(&mut rng, &shares, &[0, 2]).await;
+}
+
+/// Signs random message with `participants` and verifies the signature with external library
+async fn sign_and_verify(rng: &mut DevRng, shares: &[KeyShare<E, L>], participants: &[u16]) {
+    let mut simulation = Simulation::<Msg<E, D>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let mut message = [0u8; 100];
+    rng.fill_bytes(&mut message);
+    let message_to_sign = DataToSign::digest::<D>(&message);
+    let outputs = (0..).zip(participants).map(|(i, &j)| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        let share = &shares[usize::from(j)];
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    });
+    let signatures = futures::future::try_join_all(outputs)
+        .await
+        .expect("signing failed");
+
+    signatures[0]
+        .verify(&shares[0].shared_public_key, &message_to_sign)
+        .expect("signature is not valid");
+    assert!(signatures.iter().all(|s_i| signatures[0] == *s_i));
+
+    P384::verify(&shares[0].shared_public_key, &signatures[0], &message)
+        .expect("external verification failed");
+}
@@ -0,0 +1,30 @@
+use cggmp21::{
+    secret_storage::{InMemorySecretStorage, SealedKeyShare, SecretStorage},
+    security_level::SecurityLevel128,
+    supported_curves::Secp256k1,
+};
+
+#[test]
+fn key_share_is_sealed_and_unsealed() {
+    let key_share = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(Some(2), 3, false)
+        .expect("retrieve cached shares")
+        .remove(0);
+    let original = serde_json::to_vec(&key_share).unwrap();
+
+    let storage = InMemorySecretStorage::new();
+    let sealed = SealedKeyShare::seal(key_share, &storage).unwrap();
+
+    // Sealed key share can be persisted without secrets
+    let sealed_bytes = serde_json::to_vec(&sealed).unwrap();
+    let sealed: SealedKeyShare<Secp256k1, u64, SecurityLevel128> =
+        serde_json::from_slice(&sealed_bytes).unwrap();
+
+    let unsealed = sealed.unseal(&storage).unwrap();
+    assert_eq!(serde_json::to_vec(&unsealed).unwrap(), original);
+
+    // Secrets can't be loaded once erased
+    storage.erase(&sealed.handle).unwrap();
+    let err = sealed.unseal(&storage).err().unwrap();
+    assert!(err.is_unknown_handle());
+}
@@ -0,0 +1,42 @@
+use cggmp21::{
+    security_level::SecurityLevel128, share_recovery, supported_curves::Secp256k1, KeyShare,
+};
+use rand::seq::SliceRandom;
+
+#[test]
+fn key_share_is_restored_from_shards() {
+    let mut rng = rand_dev::DevRng::new();
+    let (m, k) = (3, 5);
+
+    let key_share = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(Some(2), 3, false)
+        .expect("retrieve cached shares")
+        .remove(0);
+
+    let mut shards = share_recovery::split(&mut rng, &key_share, m, k).unwrap();
+    shards.shuffle(&mut rng);
+
+    let restored: KeyShare<Secp256k1, SecurityLevel128> =
+        share_recovery::restore(&shards[..usize::from(m)]).unwrap();
+    assert_eq!(
+        serde_json::to_vec(&restored).unwrap(),
+        serde_json::to_vec(&key_share).unwrap()
+    );
+
+    // Not enough shards
+    assert!(share_recovery::restore::<_, SecurityLevel128>(&shards[..usize::from(m - 1)]).is_err());
+
+    // Corrupted shard
+    let mut corrupted = shards[..usize::from(m)].to_vec();
+    corrupted[0].share += generic_ec::Scalar::one();
+    assert!(share_recovery::restore::<_, SecurityLevel128>(&corrupted).is_err());
+
+    // Shards of another backup can't be mixed in
+    let another_shards = share_recovery::split(&mut rng, &key_share, m, k).unwrap();
+    let mut mixed = shards[..usize::from(m - 1)].to_vec();
+    mixed.push(another_shards[usize::from(k - 1)].clone());
+    assert!(share_recovery::restore::<_, SecurityLevel128>(&mixed).is_err());
+
+    // Invalid threshold
+    assert!(share_recovery::split(&mut rng, &key_share, k + 1, k).is_err());
+}
@@ -0,0 +1,61 @@
+use cggmp21::{
+    key_share::{SharedAuxInfo, Validate},
+    security_level::SecurityLevel128,
+    supported_curves::Secp256k1,
+    trusted_dealer, ExecutionId,
+};
+use rand::Rng;
+use round_based::simulation::Simulation;
+use sha2::Sha256;
+
+#[tokio::test]
+async fn one_aux_info_is_bound_to_many_keys() {
+    let mut rng = rand_dev::DevRng::new();
+    let n = 3;
+
+    // Aux info is generated once per committee
+    let auxes = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, n, false)
+        .expect("retrieve cached shares")
+        .into_iter()
+        .map(|share| SharedAuxInfo::new(share.into_inner().aux.validate().unwrap()))
+        .collect::<Vec<_>>();
+
+    for _ in 0..2 {
+        let cores = trusted_dealer::builder::<Secp256k1, SecurityLevel128>(n)
+            .generate_core_shares(&mut rng)
+            .unwrap();
+
+        // Aux info of one party can't be bound to key share of another party
+        assert!(auxes[0].check(&cores[1]).is_err());
+        assert!(auxes[0].bind(cores[1].clone()).is_err());
+
+        let key_shares = auxes
+            .iter()
+            .zip(cores)
+            .map(|(aux, core)| aux.bind(core).unwrap())
+            .collect::<Vec<_>>();
+
+        let mut simulation = Simulation::<cggmp21::signing::msg::Msg<Secp256k1, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let message_to_sign = cggmp21::signing::DataToSign::digest::<Sha256>(b"message");
+        let participants = &(0..n).collect::<Vec<_>>();
+
+        let outputs = (0..).zip(&key_shares).map(|(i, share)| {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            }
+        });
+        let signatures = futures::future::try_join_all(outputs)
+            .await
+            .expect("signing failed");
+        signatures[0]
+            .verify(&key_shares[0].core.shared_public_key, &message_to_sign)
+            .expect("signature is not valid");
+    }
+}
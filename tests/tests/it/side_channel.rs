@@ -0,0 +1,73 @@
+use std::hint::black_box;
+
+use cggmp21_tests::side_channel::{self, Class, WelchTTest, T_THRESHOLD};
+use rand_dev::DevRng;
+
+#[test]
+fn same_distributions_are_not_distinguishable() {
+    let measurements = (0..10_000)
+        .map(|i| {
+            let class = if i % 2 == 0 {
+                Class::Fixed
+            } else {
+                Class::Random
+            };
+            (class, 100. + ((i / 2) % 7) as f64)
+        })
+        .collect::<Vec<_>>();
+    let report = side_channel::analyze("same", &measurements);
+    assert_eq!(report.samples, 10_000);
+    assert!(!report.leaks(), "t = {}", report.max_t);
+}
+
+#[test]
+fn shifted_distributions_are_distinguishable() {
+    let measurements = (0..10_000)
+        .map(|i| {
+            let noise = ((i / 2) % 7) as f64;
+            if i % 2 == 0 {
+                (Class::Fixed, 100. + noise)
+            } else {
+                (Class::Random, 103. + noise)
+            }
+        })
+        .collect::<Vec<_>>();
+    let report = side_channel::analyze("shifted", &measurements);
+    assert!(report.leaks(), "t = {}", report.max_t);
+    assert!(report.max_t < 0.);
+}
+
+#[test]
+fn t_is_zero_when_not_enough_measurements() {
+    let mut test = WelchTTest::default();
+    assert_eq!(test.t(), 0.);
+    test.push(Class::Fixed, 1.);
+    test.push(Class::Fixed, 2.);
+    test.push(Class::Random, 100.);
+    assert_eq!(test.t(), 0.);
+    test.push(Class::Random, 101.);
+    assert!(test.t().abs() > T_THRESHOLD);
+}
+
+#[test]
+fn detects_data_dependent_running_time() {
+    let mut rng = DevRng::new();
+    let report = side_channel::run(
+        "leaky",
+        &mut rng,
+        2_000,
+        |_rng, class| match class {
+            Class::Fixed => 0_u64,
+            Class::Random => 100_000,
+        },
+        |&iterations| {
+            let mut acc = 0_u64;
+            for i in 0..iterations {
+                acc = black_box(acc.wrapping_add(i));
+            }
+            black_box(acc);
+        },
+    );
+    assert_eq!(report.samples, 2_000);
+    assert!(report.leaks(), "t = {}", report.max_t);
+}
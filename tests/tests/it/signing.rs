@@ -74,7 +74,8 @@ mod generic {
 
             outputs.push(async move {
                 let signing = cggmp21::signing(eid, i, participants, share)
-                    .enforce_reliable_broadcast(reliable_broadcast);
+                    .enforce_reliable_broadcast(reliable_broadcast)
+                    .enforce_echo_broadcast(reliable_broadcast);
 
                 #[cfg(feature = "hd-wallets")]
                 let signing = if let Some(derivation_path) = derivation_path {
@@ -116,6 +117,76 @@ mod generic {
             .expect("external verification failed")
     }
 
+    #[test_case::case(None, 3; "n3")]
+    #[test_case::case(Some(2), 3; "t2n3")]
+    #[tokio::test]
+    async fn round_optimized_signing_works<E: Curve, V>(t: Option<u16>, n: u16)
+    where
+        Point<E>: HasAffineX<E>,
+        V: ExternalVerifier<E>,
+    {
+        use cggmp21::signing::SigningVariant;
+
+        let mut rng = DevRng::new();
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(t, n, false)
+            .expect("retrieve cached shares");
+
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut original_message_to_sign = [0u8; 100];
+        rng.fill_bytes(&mut original_message_to_sign);
+        let message_to_sign = DataToSign::digest::<Sha256>(&original_message_to_sign);
+
+        let t = shares[0].min_signers();
+        let mut participants = (0..n).collect::<Vec<_>>();
+        participants.shuffle(&mut rng);
+        let participants = &participants[..usize::from(t)];
+        println!("Signers: {participants:?}");
+
+        // Round-optimized variant saves one round
+        let standard = cggmp21::signing::<E, SecurityLevel128>(
+            eid,
+            0,
+            participants,
+            &shares[usize::from(participants[0])],
+        );
+        let standard_rounds = standard.signing_rounds_count();
+        let optimized = standard.set_variant(SigningVariant::RoundOptimized);
+        assert_eq!(optimized.signing_rounds_count() + 1, standard_rounds);
+
+        let mut outputs = vec![];
+        for (i, &j) in (0..).zip(participants) {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let share = &shares[usize::from(j)];
+
+            outputs.push(async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .set_variant(SigningVariant::RoundOptimized)
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            });
+        }
+
+        let signatures = futures::future::try_join_all(outputs)
+            .await
+            .expect("signing failed");
+
+        let public_key = shares[0].shared_public_key;
+        signatures[0]
+            .verify(&public_key, &message_to_sign)
+            .expect("signature is not valid");
+        assert!(signatures.iter().all(|s_i| signatures[0] == *s_i));
+
+        V::verify(&public_key, &signatures[0], &original_message_to_sign)
+            .expect("external verification failed")
+    }
+
     #[test_case::case(Some(3), 5, false; "t3n5")]
     #[cfg_attr(feature = "hd-wallets", test_case::case(Some(3), 5, true; "t3n5-hd"))]
     #[tokio::test]
@@ -188,7 +259,9 @@ mod generic {
                 } else {
                     presig
                 };
-                presig.issue_partial_signature(message_to_sign)
+                presig
+                    .issue_partial_signature(message_to_sign)
+                    .expect("presignature is used only once")
             })
             .collect::<Vec<_>>();
 
@@ -218,6 +291,1090 @@ mod generic {
             .expect("external verification failed")
     }
 
+    #[tokio::test]
+    async fn signing_with_signers_set<E: Curve, V>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        use cggmp21::signing::SignersSet;
+
+        let mut rng = DevRng::new();
+        let (t, n) = (3, 5);
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares");
+
+        // Invalid sets are rejected
+        assert!(SignersSet::new(&shares[0], [0, 1]).is_err());
+        assert!(SignersSet::new(&shares[0], [0, 1, 1]).is_err());
+        assert!(SignersSet::new(&shares[0], [0, 1, n]).is_err());
+
+        let mut signers = (0..n).collect::<Vec<_>>();
+        signers.shuffle(&mut rng);
+        signers.truncate(usize::from(t));
+        println!("Signers: {signers:?}");
+
+        let set = SignersSet::new(&shares[0], signers.iter().copied()).unwrap();
+        let non_signer = (0..n).find(|j| !signers.contains(j)).unwrap();
+        assert_eq!(set.position_of(non_signer), None);
+        assert!(
+            cggmp21::signing::SigningBuilder::<E, SecurityLevel128, Sha256>::with_signers(
+                ExecutionId::new(b"eid"),
+                &set,
+                &shares[usize::from(non_signer)],
+            )
+            .is_err()
+        );
+
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let message_to_sign = DataToSign::digest::<Sha256>(b"signing with signers set");
+
+        // Parties must be added to the simulation in order of their position in S
+        let mut outputs = vec![];
+        for &j in set.as_slice() {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let share = &shares[usize::from(j)];
+
+            // Each signer lists the signers in its own order
+            let mut signers = signers.clone();
+            signers.shuffle(&mut rng);
+
+            outputs.push(async move {
+                let set = SignersSet::new(share, signers).unwrap();
+                cggmp21::signing::SigningBuilder::with_signers(eid, &set, share)
+                    .unwrap()
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            });
+        }
+
+        let signatures = futures::future::try_join_all(outputs)
+            .await
+            .expect("signing failed");
+
+        signatures[0]
+            .verify(&shares[0].shared_public_key, &message_to_sign)
+            .expect("signature is not valid");
+        assert!(signatures.iter().all(|s_i| signatures[0] == *s_i));
+    }
+
+    #[tokio::test]
+    async fn signing_with_weighted_shares<E: Curve, V>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        use cggmp21::key_share::{KeyShare, Validate};
+        use cggmp21::signing::SignersSet;
+        use rand::SeedableRng;
+
+        let mut rng = DevRng::new();
+        let (t, n) = (3, 3);
+        let weights = [2, 1, 1];
+
+        // Aux info is not affected by weights, so we reuse it from cached shares
+        let cached_shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, n, false)
+            .expect("retrieve cached shares");
+
+        let mut simulation =
+            Simulation::<cggmp21::keygen::ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let mut outputs = vec![];
+        for i in 0..n {
+            let party = simulation.add_party();
+            let mut party_rng = rand_chacha::ChaCha20Rng::from_seed(rng.gen());
+            let weights = &weights;
+            outputs.push(async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .set_weights(weights)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+        let shares = futures::future::try_join_all(outputs)
+            .await
+            .expect("keygen failed")
+            .into_iter()
+            .zip(&cached_shares)
+            .map(|(core, cached)| {
+                let aux = cached.aux.clone().validate().expect("invalid aux info");
+                KeyShare::from_parts((core, aux)).expect("couldn't make share from parts")
+            })
+            .collect::<Vec<_>>();
+
+        // Parties 1 and 2 don't have enough weight to sign
+        assert!(SignersSet::new(&shares[1], [1, 2]).is_err());
+        // Party 0 alone doesn't have enough weight either
+        assert!(SignersSet::new(&shares[0], [0]).is_err());
+
+        // Parties 0 and 2 have total weight 3
+        let signers = [0, 2];
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let message_to_sign = DataToSign::digest::<Sha256>(b"signing with weighted shares");
+
+        let mut outputs = vec![];
+        for j in signers {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let share = &shares[usize::from(j)];
+
+            outputs.push(async move {
+                let set = SignersSet::new(share, signers).unwrap();
+                cggmp21::signing::SigningBuilder::with_signers(eid, &set, share)
+                    .unwrap()
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            });
+        }
+
+        let signatures = futures::future::try_join_all(outputs)
+            .await
+            .expect("signing failed");
+
+        signatures[0]
+            .verify(&shares[0].shared_public_key, &message_to_sign)
+            .expect("signature is not valid");
+        assert!(signatures.iter().all(|s_i| signatures[0] == *s_i));
+    }
+
+    #[tokio::test]
+    async fn signing_with_signer_set_context<E: Curve, V>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        use cggmp21::signing::{SignerSetContext, SignersSet};
+
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares");
+
+        let set = SignersSet::new(&shares[0], [0, 2]).unwrap();
+        let contexts = set
+            .as_slice()
+            .iter()
+            .map(|&j| SignerSetContext::new(&shares[usize::from(j)], &set).unwrap())
+            .collect::<Vec<_>>();
+        assert!(SignerSetContext::new(&shares[1], &set).is_err());
+        assert_eq!(
+            contexts[0].public_shares().iter().sum::<Point<E>>(),
+            *shares[0].shared_public_key
+        );
+        assert_eq!(
+            contexts[0].public_shares(),
+            shares[0]
+                .core
+                .signers_public_shares(set.as_slice())
+                .unwrap()
+        );
+
+        // The same contexts are reused across several signings
+        for message in [&b"first message"[..], b"second message"] {
+            let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+            let eid: [u8; 32] = rng.gen();
+            let eid = ExecutionId::new(&eid);
+            let message_to_sign = DataToSign::digest::<Sha256>(message);
+
+            let mut outputs = vec![];
+            for context in &contexts {
+                let party = simulation.add_party();
+                let mut party_rng = rng.fork();
+                let share = &shares[usize::from(set.as_slice()[usize::from(context.i())])];
+
+                outputs.push(async move {
+                    cggmp21::signing::SigningBuilder::with_signer_set_context(eid, context, share)
+                        .sign(&mut party_rng, party, message_to_sign)
+                        .await
+                });
+            }
+
+            let signatures = futures::future::try_join_all(outputs)
+                .await
+                .expect("signing failed");
+            signatures[0]
+                .verify(&shares[0].shared_public_key, &message_to_sign)
+                .expect("signature is not valid");
+            assert!(signatures.iter().all(|s_i| signatures[0] == *s_i));
+        }
+
+        // Context derived from another key share is rejected
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let party = simulation.add_party();
+        let result = cggmp21::signing::SigningBuilder::<E, SecurityLevel128, Sha256>::with_signer_set_context(
+            ExecutionId::new(b"eid"),
+            &contexts[0],
+            &shares[2],
+        )
+        .sign(&mut rng, party, DataToSign::digest::<Sha256>(b"message"))
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[tokio::test]
+    async fn signing_on_dedicated_thread_pool<E: Curve, V>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares");
+        let pool = cggmp21::rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let message_to_sign = DataToSign::digest::<Sha256>(b"signing on dedicated pool");
+
+        let participants = &[0, 2];
+        let mut outputs = vec![];
+        for (i, &j) in (0..).zip(participants) {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let share = &shares[usize::from(j)];
+            let pool = &pool;
+
+            outputs.push(async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .set_thread_pool(pool)
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            });
+        }
+
+        let signatures = futures::future::try_join_all(outputs)
+            .await
+            .expect("signing failed");
+        signatures[0]
+            .verify(&shares[0].shared_public_key, &message_to_sign)
+            .expect("signature is not valid");
+        assert!(signatures.iter().all(|s_i| signatures[0] == *s_i));
+    }
+
+    #[tokio::test]
+    async fn signing_with_separately_stored_aux_info<E: Curve, V>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares");
+        let parts = shares
+            .iter()
+            .map(|share| {
+                let core: &cggmp21::IncompleteKeyShare<E> = share.as_ref();
+                let aux: &cggmp21::key_share::AuxInfo<SecurityLevel128> = share.as_ref();
+                (core, aux)
+            })
+            .collect::<Vec<_>>();
+
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let message_to_sign = DataToSign::digest::<Sha256>(b"signing with separate aux info");
+
+        // Key share must be consistent with aux info
+        assert!(
+            cggmp21::signing::SigningBuilder::<E, SecurityLevel128>::from_parts(
+                eid,
+                0,
+                &[0, 2],
+                parts[0].0,
+                parts[2].1,
+                None,
+            )
+            .is_err()
+        );
+
+        let participants = &[0, 2];
+        let mut outputs = vec![];
+        for (i, &j) in (0..).zip(participants) {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let (core, aux) = parts[usize::from(j)];
+
+            outputs.push(async move {
+                cggmp21::signing::SigningBuilder::<E, SecurityLevel128>::from_parts(
+                    eid,
+                    i,
+                    participants,
+                    core,
+                    aux,
+                    None,
+                )
+                .unwrap()
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+            });
+        }
+
+        let signatures = futures::future::try_join_all(outputs)
+            .await
+            .expect("signing failed");
+        signatures[0]
+            .verify(&shares[0].shared_public_key, &message_to_sign)
+            .expect("signature is not valid");
+        assert!(signatures.iter().all(|s_i| signatures[0] == *s_i));
+    }
+
+    #[tokio::test]
+    async fn signing_with_paillier_decryptor<E: Curve, V>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use cggmp21::paillier_backend::{DecryptionError, PaillierDecryptor};
+        use cggmp21::rug::Integer;
+
+        struct CountingDecryptor {
+            key: cggmp21::fast_paillier::DecryptionKey,
+            calls: AtomicUsize,
+        }
+        impl PaillierDecryptor for CountingDecryptor {
+            fn n(&self) -> &Integer {
+                self.key.n()
+            }
+            fn decrypt(&self, ciphertext: &Integer) -> Result<Integer, DecryptionError> {
+                self.calls.fetch_add(1, Ordering::Relaxed);
+                PaillierDecryptor::decrypt(&self.key, ciphertext)
+            }
+        }
+
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares");
+        let participants = &[0, 2];
+        let decryptors = participants
+            .iter()
+            .map(|&j| CountingDecryptor {
+                key: shares[usize::from(j)]
+                    .aux
+                    .decryption_key()
+                    .unwrap()
+                    .into_owned(),
+                calls: AtomicUsize::new(0),
+            })
+            .collect::<Vec<_>>();
+
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let message_to_sign = DataToSign::digest::<Sha256>(b"signing with paillier decryptor");
+
+        let mut outputs = vec![];
+        for ((i, &j), decryptor) in (0..).zip(participants).zip(&decryptors) {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let share = &shares[usize::from(j)];
+
+            outputs.push(async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .set_paillier_decryptor(decryptor)
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            });
+        }
+
+        let signatures = futures::future::try_join_all(outputs)
+            .await
+            .expect("signing failed");
+        signatures[0]
+            .verify(&shares[0].shared_public_key, &message_to_sign)
+            .expect("signature is not valid");
+        assert!(signatures.iter().all(|s_i| signatures[0] == *s_i));
+
+        // Each signer decrypts D and hat D received from the other signer
+        for decryptor in &decryptors {
+            assert_eq!(decryptor.calls.load(Ordering::Relaxed), 2);
+        }
+
+        // Decryptor holding a key of another party is rejected
+        let party = Simulation::<Msg<E, Sha256>>::new().add_party();
+        let result = cggmp21::signing(eid, 0, participants, &shares[0])
+            .set_paillier_decryptor(&decryptors[1])
+            .sign(&mut rng, party, message_to_sign)
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn signing_enforces_key_usage_policy<E: Curve, V>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        use cggmp21::key_share::Validate;
+        use cggmp21::policy::KeyUsagePolicy;
+
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let mut policy = KeyUsagePolicy::default();
+        policy.allowed_curves.push(E::CURVE_NAME.to_owned());
+        policy.allowed_prefixes.push(b"allowed:".to_vec());
+        policy.set_message_check(|msg| msg.len() < 32);
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares")
+            .into_iter()
+            .map(|share| {
+                let mut share = share.into_inner();
+                share.policy = Some(policy.clone());
+                share.validate().unwrap()
+            })
+            .collect::<Vec<_>>();
+        let participants = &[0, 2];
+
+        for (message, allowed) in [
+            (&b"allowed: message"[..], true),
+            (b"forbidden: message", false),
+            (b"allowed: message that is too long", false),
+        ] {
+            let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+            let eid: [u8; 32] = rng.gen();
+            let eid = ExecutionId::new(&eid);
+            let message_to_sign = DataToSign::digest::<Sha256>(message);
+
+            let mut outputs = vec![];
+            for (i, &j) in (0..).zip(participants) {
+                let party = simulation.add_party();
+                let mut party_rng = rng.fork();
+                let share = &shares[usize::from(j)];
+
+                outputs.push(async move {
+                    cggmp21::signing(eid, i, participants, share)
+                        .set_message(message)
+                        .sign(&mut party_rng, party, message_to_sign)
+                        .await
+                });
+            }
+
+            let results = futures::future::join_all(outputs).await;
+            if allowed {
+                for result in results {
+                    result
+                        .expect("signing failed")
+                        .verify(&shares[0].shared_public_key, &message_to_sign)
+                        .expect("signature is not valid");
+                }
+            } else {
+                for result in results {
+                    let err = result.err().expect("policy violation is not detected");
+                    assert!(err.is_policy_violation() || err.aborted_by_peer().is_some());
+                }
+            }
+        }
+
+        // Policy restricts messages, so signing fails if message is not provided
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let message_to_sign = DataToSign::digest::<Sha256>(b"allowed: message");
+        let mut outputs = vec![];
+        for (i, &j) in (0..).zip(participants) {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let share = &shares[usize::from(j)];
+            outputs.push(async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            });
+        }
+        let results = futures::future::join_all(outputs).await;
+        assert!(results
+            .iter()
+            .any(|r| r.as_ref().is_err_and(|err| err.is_policy_violation())));
+    }
+
+    #[tokio::test]
+    async fn signing_invokes_approval_hook<E: Curve, V>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        use std::sync::Mutex;
+
+        use cggmp21::signing::approval::{ApprovalRequest, Rejected, SigningApproval, Stage};
+
+        /// Approves signing of `allowed` data only, records stages it was invoked at
+        struct Approver<E: Curve> {
+            allowed: DataToSign<E>,
+            stages: Mutex<Vec<Stage>>,
+        }
+        impl<E: Curve> SigningApproval<E> for Approver<E> {
+            fn approve(&self, request: &ApprovalRequest<E>) -> Result<(), Rejected> {
+                self.stages.lock().unwrap().push(request.stage);
+                assert_eq!(request.signers, [0, 2]);
+                match request.data_to_sign {
+                    Some(data) if data.to_scalar() != self.allowed.to_scalar() => {
+                        Err(Rejected::new("data is not allowed"))
+                    }
+                    _ => Ok(()),
+                }
+            }
+        }
+
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares");
+        let participants = &[0, 2];
+        let allowed = DataToSign::digest::<Sha256>(b"approved message");
+
+        for (message_to_sign, approved) in [
+            (allowed, true),
+            (DataToSign::digest::<Sha256>(b"unapproved message"), false),
+        ] {
+            let approvers = participants
+                .iter()
+                .map(|_| Approver {
+                    allowed,
+                    stages: Mutex::new(vec![]),
+                })
+                .collect::<Vec<_>>();
+
+            let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+            let eid: [u8; 32] = rng.gen();
+            let eid = ExecutionId::new(&eid);
+
+            let mut outputs = vec![];
+            for ((i, &j), approver) in (0..).zip(participants).zip(&approvers) {
+                let party = simulation.add_party();
+                let mut party_rng = rng.fork();
+                let share = &shares[usize::from(j)];
+
+                outputs.push(async move {
+                    cggmp21::signing(eid, i, participants, share)
+                        .set_approval_hook(approver)
+                        .sign(&mut party_rng, party, message_to_sign)
+                        .await
+                });
+            }
+
+            let results = futures::future::join_all(outputs).await;
+            for (result, approver) in results.into_iter().zip(&approvers) {
+                if approved {
+                    result
+                        .expect("signing failed")
+                        .verify(&shares[0].shared_public_key, &message_to_sign)
+                        .expect("signature is not valid");
+                } else {
+                    let err = result.err().expect("rejection is not detected");
+                    assert!(err.is_rejected() || err.aborted_by_peer().is_some());
+                }
+                let stages = approver.stages.lock().unwrap();
+                assert_eq!(stages[0], Stage::BeforeRound1);
+                assert!(stages.len() <= 2);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn signing_is_rate_limited<E: Curve, V>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        use cggmp21::signing::rate_limit::{InMemoryUsageStore, RateLimit, RateLimiter};
+
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares");
+        let participants = &[0, 2];
+        let limiters = participants
+            .iter()
+            .map(|_| {
+                RateLimiter::new(
+                    InMemoryUsageStore::new(),
+                    RateLimit {
+                        per_period: None,
+                        total: Some(1),
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for attempt in 0..2 {
+            let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+            let eid: [u8; 32] = rng.gen();
+            let eid = ExecutionId::new(&eid);
+            let message_to_sign = DataToSign::digest::<Sha256>(b"rate limited signing");
+
+            let mut outputs = vec![];
+            for ((i, &j), limiter) in (0..).zip(participants).zip(&limiters) {
+                let party = simulation.add_party();
+                let mut party_rng = rng.fork();
+                let share = &shares[usize::from(j)];
+
+                outputs.push(async move {
+                    cggmp21::signing(eid, i, participants, share)
+                        .set_rate_limiter(limiter)
+                        .sign(&mut party_rng, party, message_to_sign)
+                        .await
+                });
+            }
+
+            let results = futures::future::join_all(outputs).await;
+            for result in results {
+                if attempt == 0 {
+                    result.expect("signing failed");
+                } else {
+                    let err = result.err().expect("rate limit is not enforced");
+                    assert!(err.is_rate_limited() || err.aborted_by_peer().is_some());
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn signing_with_keccak_digest<E: Curve, V>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        use sha3::Keccak256;
+
+        let mut rng = DevRng::new();
+        let n = 3;
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, n, false)
+            .expect("retrieve cached shares");
+
+        let mut simulation = Simulation::<Msg<E, Keccak256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let message_to_sign = DataToSign::keccak256(b"signing with keccak");
+        assert_eq!(
+            message_to_sign.to_scalar(),
+            DataToSign::<E>::digest::<Keccak256>(b"signing with keccak").to_scalar()
+        );
+
+        let participants = &(0..n).collect::<Vec<_>>();
+        let mut outputs = vec![];
+        for (i, share) in (0..).zip(&shares) {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            outputs.push(async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .set_digest::<Keccak256>()
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            });
+        }
+
+        let signatures = futures::future::try_join_all(outputs)
+            .await
+            .expect("signing failed");
+
+        signatures[0]
+            .verify(&shares[0].shared_public_key, &message_to_sign)
+            .expect("signature is not valid");
+        assert!(signatures.iter().all(|s_i| signatures[0] == *s_i));
+    }
+
+    #[tokio::test]
+    async fn presignature_store_never_returns_presignature_twice<E: Curve, V>() {
+        use cggmp21::signing::presignature_store::{InMemoryPresignatureStore, PresignatureStore};
+        use generic_ec::{NonZero, SecretScalar};
+
+        let random_point = |rng: &mut DevRng| {
+            NonZero::from_point(Point::<E>::generator() * &SecretScalar::random(rng)).unwrap()
+        };
+
+        let mut rng = DevRng::new();
+        let store = InMemoryPresignatureStore::<E>::new();
+
+        let mut expected = vec![];
+        for _ in 0..100 {
+            let presig = cggmp21::Presignature::from_parts(
+                random_point(&mut rng),
+                SecretScalar::random(&mut rng),
+                SecretScalar::random(&mut rng),
+            );
+            expected.push(presig.R);
+            store.put(presig).unwrap();
+        }
+        assert_eq!(store.len().unwrap(), expected.len());
+
+        let consumed = std::thread::scope(|s| {
+            let threads = (0..4)
+                .map(|_| {
+                    s.spawn(|| {
+                        let mut consumed = vec![];
+                        while let Some(presig) = store.consume_one().unwrap() {
+                            consumed.push(presig.R);
+                        }
+                        consumed
+                    })
+                })
+                .collect::<Vec<_>>();
+            threads
+                .into_iter()
+                .flat_map(|t| t.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        assert!(store.is_empty().unwrap());
+        assert_eq!(consumed.len(), expected.len());
+        for R in &expected {
+            assert_eq!(consumed.iter().filter(|R_j| *R_j == R).count(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn signing_with_deferred_message<E: Curve, V>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        use cggmp21::signing::deferred_message;
+
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares");
+        let participants = &[0, 2];
+        let message_to_sign = DataToSign::digest::<Sha256>(b"message");
+
+        // Message is supplied after protocol is started
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let mut outputs = vec![];
+        let mut senders = vec![];
+        for (i, &j) in (0..).zip(participants) {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let share = &shares[usize::from(j)];
+            let (sender, message) = deferred_message();
+            senders.push(sender);
+            outputs.push(async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .sign_with_deferred_message(&mut party_rng, party, message)
+                    .await
+            });
+        }
+        let provide_message = async {
+            for sender in senders {
+                sender.send(message_to_sign).unwrap();
+            }
+        };
+        let (signatures, ()) =
+            futures::join!(futures::future::try_join_all(outputs), provide_message);
+        let signatures = signatures.expect("signing failed");
+        signatures[0]
+            .verify(&shares[0].shared_public_key, &message_to_sign)
+            .expect("signature is not valid");
+        assert!(signatures.iter().all(|s_i| signatures[0] == *s_i));
+
+        // Protocol is aborted if message is never supplied
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let mut outputs = vec![];
+        for (i, &j) in (0..).zip(participants) {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let share = &shares[usize::from(j)];
+            let (sender, message) = deferred_message();
+            if i == 0 {
+                drop(sender)
+            } else {
+                sender.send(message_to_sign).unwrap();
+            }
+            outputs.push(async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .sign_with_deferred_message(&mut party_rng, party, message)
+                    .await
+            });
+        }
+        let results = futures::future::join_all(outputs).await;
+        assert!(results[0].is_err());
+        assert_eq!(results[1].as_ref().unwrap_err().aborted_by_peer(), Some(0));
+    }
+
+    #[tokio::test]
+    async fn deferred_message_is_checked_against_policy<E: Curve, V>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        use cggmp21::key_share::Validate;
+        use cggmp21::policy::KeyUsagePolicy;
+        use cggmp21::signing::deferred_message;
+
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let mut policy = KeyUsagePolicy::default();
+        policy.allowed_prefixes.push(b"allowed:".to_vec());
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares")
+            .into_iter()
+            .map(|share| {
+                let mut share = share.into_inner();
+                share.policy = Some(policy.clone());
+                share.validate().unwrap()
+            })
+            .collect::<Vec<_>>();
+        let participants = &[0, 2];
+
+        for (message, allowed) in [(&b"allowed: message"[..], true), (b"forbidden", false)] {
+            let message_to_sign = DataToSign::digest::<Sha256>(message);
+
+            // Message is supplied after protocol is started, along with data to sign
+            let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+            let eid: [u8; 32] = rng.gen();
+            let eid = ExecutionId::new(&eid);
+            let mut outputs = vec![];
+            let mut senders = vec![];
+            for (i, &j) in (0..).zip(participants) {
+                let party = simulation.add_party();
+                let mut party_rng = rng.fork();
+                let share = &shares[usize::from(j)];
+                let (sender, deferred) = deferred_message();
+                senders.push(sender);
+                outputs.push(async move {
+                    cggmp21::signing(eid, i, participants, share)
+                        .sign_with_deferred_message(&mut party_rng, party, deferred)
+                        .await
+                });
+            }
+            let provide_message = async {
+                for sender in senders {
+                    sender
+                        .send_with_message(message_to_sign, message.to_vec())
+                        .unwrap();
+                }
+            };
+            let (results, ()) = futures::join!(futures::future::join_all(outputs), provide_message);
+
+            for result in results {
+                if allowed {
+                    result
+                        .expect("signing failed")
+                        .verify(&shares[0].shared_public_key, &message_to_sign)
+                        .expect("signature is not valid");
+                } else {
+                    let err = result.err().expect("policy violation is not detected");
+                    assert!(err.is_policy_violation() || err.aborted_by_peer().is_some());
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn presignature_copies_sign_only_one_message<E: Curve, V>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        use generic_ec::{NonZero, SecretScalar};
+
+        let mut rng = DevRng::new();
+        let presig = cggmp21::Presignature::<E>::from_parts(
+            NonZero::from_point(Point::generator() * &SecretScalar::random(&mut rng)).unwrap(),
+            SecretScalar::random(&mut rng),
+            SecretScalar::random(&mut rng),
+        );
+        let copy1 = presig.dangerously_clone();
+        let copy2 = presig.dangerously_clone();
+
+        let message1 = DataToSign::digest::<Sha256>(b"message 1");
+        let message2 = DataToSign::digest::<Sha256>(b"message 2");
+
+        let partial_sig = copy1.issue_partial_signature(message1).unwrap();
+        // Signing a different message with a copy is refused
+        assert!(copy2.issue_partial_signature(message2).is_err());
+        // Signing the same message is harmless
+        let partial_sig2 = presig.issue_partial_signature(message1).unwrap();
+        assert_eq!(partial_sig.sigma, partial_sig2.sigma);
+    }
+
+    #[tokio::test]
+    async fn combine_identifies_faulty_partial_signature<E: Curve, V>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares");
+        let public_key = shares[0].shared_public_key;
+
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let participants = &[0, 2];
+
+        let mut outputs = vec![];
+        for (i, &j) in (0..).zip(participants) {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let share = &shares[usize::from(j)];
+            outputs.push(async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .generate_presignature(&mut party_rng, party)
+                    .await
+            });
+        }
+        let presignatures = futures::future::try_join_all(outputs)
+            .await
+            .expect("signing failed");
+
+        let public_data = presignatures
+            .iter()
+            .map(|presig| presig.public_data())
+            .collect::<Vec<_>>();
+        let message_to_sign = DataToSign::digest::<Sha256>(b"message");
+        let mut partial_signatures = presignatures
+            .into_iter()
+            .map(|presig| presig.issue_partial_signature(message_to_sign).unwrap())
+            .collect::<Vec<_>>();
+
+        let signature = cggmp21::PartialSignature::combine_and_identify(
+            &partial_signatures,
+            &public_data,
+            &public_key,
+            &message_to_sign,
+        )
+        .expect("partial signatures are valid");
+        signature
+            .verify(&public_key, &message_to_sign)
+            .expect("signature is not valid");
+
+        // Tamper with the second partial signature
+        partial_signatures[1].sigma += generic_ec::Scalar::one();
+        let err = cggmp21::PartialSignature::combine_and_identify(
+            &partial_signatures,
+            &public_data,
+            &public_key,
+            &message_to_sign,
+        )
+        .unwrap_err();
+        assert_eq!(err.faulty_partial_signatures(), Some(&[1][..]));
+
+        // Public data that doesn't correspond to the public key is rejected
+        let err = cggmp21::PartialSignature::combine_and_identify(
+            &partial_signatures[..1],
+            &public_data[..1],
+            &public_key,
+            &message_to_sign,
+        )
+        .unwrap_err();
+        assert_eq!(err.faulty_partial_signatures(), None);
+    }
+
+    #[tokio::test]
+    async fn coordinator_blames_faulty_signer<E: Curve, V>()
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        use cggmp21::signing::coordinator::Coordinator;
+
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares");
+        let key_info: &cggmp21::key_share::KeyInfo<E> = shares[0].as_ref();
+
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let participants = &[0, 2];
+
+        let mut outputs = vec![];
+        for (i, &j) in (0..).zip(participants) {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let share = &shares[usize::from(j)];
+            outputs.push(async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .generate_presignature(&mut party_rng, party)
+                    .await
+            });
+        }
+        let presignatures = futures::future::try_join_all(outputs)
+            .await
+            .expect("signing failed");
+
+        let message_to_sign = DataToSign::digest::<Sha256>(b"message");
+        let submissions = participants
+            .iter()
+            .zip(presignatures)
+            .map(|(&j, presig)| {
+                let public_data = presig.public_data();
+                let partial_sig = presig.issue_partial_signature(message_to_sign).unwrap();
+                (j, public_data, partial_sig)
+            })
+            .collect::<Vec<_>>();
+
+        // Invalid set of signers is rejected
+        assert!(Coordinator::new(key_info, [0, 1, 2], message_to_sign).is_err());
+
+        let mut coordinator = Coordinator::new(key_info, [2, 0], message_to_sign).unwrap();
+        for (j, public_data, partial_sig) in submissions.iter().cloned() {
+            assert!(!coordinator.is_complete());
+            coordinator.submit(j, public_data, partial_sig).unwrap();
+        }
+        assert!(coordinator.is_complete());
+        // Signer can't submit twice, and non-signer can't submit at all
+        let (_, public_data, partial_sig) = submissions[0].clone();
+        assert!(coordinator
+            .submit(0, public_data, partial_sig.clone())
+            .is_err());
+        assert!(coordinator.submit(1, public_data, partial_sig).is_err());
+
+        let signature = coordinator.finish().expect("signing failed");
+        signature
+            .verify(&shares[0].shared_public_key, &message_to_sign)
+            .expect("signature is not valid");
+
+        // Signer 2 submits faulty partial signature
+        let mut coordinator = Coordinator::new(key_info, [0, 2], message_to_sign).unwrap();
+        let (j, public_data, partial_sig) = submissions[0].clone();
+        coordinator.submit(j, public_data, partial_sig).unwrap();
+        assert_eq!(coordinator.missing_signers(), [2]);
+        let (j, public_data, mut partial_sig) = submissions[1].clone();
+        partial_sig.sigma += generic_ec::Scalar::one();
+        coordinator.submit(j, public_data, partial_sig).unwrap();
+        let err = coordinator.finish().unwrap_err();
+        assert_eq!(err.blame(), [2]);
+    }
+
     #[instantiate_tests(<cggmp21::supported_curves::Secp256k1, cggmp21_tests::external_verifier::blockchains::Bitcoin>)]
     mod secp256k1 {}
     #[instantiate_tests(<cggmp21::supported_curves::Secp256r1, cggmp21_tests::external_verifier::Noop>)]
@@ -0,0 +1,121 @@
+use cggmp21::{
+    key_refresh::AuxOnlyMsg,
+    keygen::ThresholdMsg,
+    roster::{Roster, SignedTranscript},
+    security_level::SecurityLevel128,
+    supported_curves::Secp256k1,
+    transcript::ThresholdRounds,
+    ExecutionId,
+};
+use generic_ec::{NonZero, Point, SecretScalar};
+use rand::Rng;
+use round_based::simulation::Simulation;
+use sha2::Sha256;
+
+#[tokio::test]
+async fn keygen_transcript_is_signed_and_verified() {
+    let mut rng = rand_dev::DevRng::new();
+    let (t, n) = (2, 3);
+
+    let identity_keys = (0..n)
+        .map(|_| SecretScalar::<Secp256k1>::random(&mut rng))
+        .collect::<Vec<_>>();
+    let roster = Roster::new(
+        identity_keys
+            .iter()
+            .map(|sk| NonZero::from_point(Point::generator() * sk).unwrap())
+            .collect(),
+    )
+    .unwrap();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let mut simulation = Simulation::<ThresholdMsg<Secp256k1, SecurityLevel128, Sha256>>::new();
+    let outputs = (0..n).map(|i| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::keygen::<Secp256k1>(eid, i, n)
+                .set_threshold(t)
+                .start_with_transcript(&mut party_rng, party)
+                .await
+        }
+    });
+    let outputs = futures::future::try_join_all(outputs)
+        .await
+        .expect("keygen failed");
+
+    let signed = outputs
+        .into_iter()
+        .zip(&identity_keys)
+        .map(|((_, transcript), sk)| {
+            roster
+                .sign_transcript::<Sha256, _, _>(&mut rng, sk, transcript)
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    // All parties observed the same ceremony
+    let hashes = signed
+        .iter()
+        .map(|signed| roster.verify_transcript::<Sha256, _>(signed).unwrap())
+        .collect::<Vec<_>>();
+    assert!(hashes.iter().all(|h| *h == hashes[0]));
+
+    // Transcript survives serialization
+    let serialized = serde_json::to_vec(&signed[0]).unwrap();
+    let deserialized: SignedTranscript<
+        Secp256k1,
+        ThresholdRounds<Secp256k1, SecurityLevel128, Sha256>,
+    > = serde_json::from_slice(&serialized).unwrap();
+    assert_eq!(
+        roster
+            .verify_transcript::<Sha256, _>(&deserialized)
+            .unwrap(),
+        hashes[0]
+    );
+
+    // Tampered transcript is detected
+    let mut tampered = signed[0].clone();
+    tampered.transcript.rounds.round3.swap(0, 1);
+    assert!(roster.verify_transcript::<Sha256, _>(&tampered).is_err());
+
+    // Transcript can't be signed with identity key of another party
+    let transcript = signed[0].transcript.clone();
+    assert!(roster
+        .sign_transcript::<Sha256, _, _>(&mut rng, &identity_keys[1], transcript)
+        .is_err());
+}
+
+#[tokio::test]
+async fn aux_gen_transcripts_match() {
+    let mut rng = rand_dev::DevRng::new();
+    let n = 3;
+    let mut primes = cggmp21_tests::CACHED_PRIMES.iter();
+
+    let mut simulation = Simulation::<AuxOnlyMsg<Sha256, SecurityLevel128>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let outputs = (0..n).map(|i| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        let pregenerated_data = primes.next().expect("Can't fetch primes");
+        async move {
+            cggmp21::aux_info_gen(eid, i, n, pregenerated_data)
+                .dangerous_allow_blum_primes(true)
+                .start_with_transcript(&mut party_rng, party)
+                .await
+        }
+    });
+    let outputs = futures::future::try_join_all(outputs)
+        .await
+        .expect("aux gen failed");
+
+    let hash = outputs[0].1.hash::<Sha256>();
+    for (i, (_, transcript)) in (0u16..).zip(&outputs) {
+        assert_eq!(transcript.i, i);
+        assert_eq!(transcript.rounds.round2.len(), usize::from(n));
+        assert_eq!(transcript.hash::<Sha256>(), hash);
+    }
+}
@@ -0,0 +1,223 @@
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use cggmp21::{
+    security_level::SecurityLevel128,
+    signing::{msg::Msg, DataToSign},
+    supported_curves::Secp256k1,
+    transport::{default_max_frame_size, tcp::TcpLink, Channel, Config, Link, Network},
+    ExecutionId,
+};
+use futures::future::{self, BoxFuture};
+use futures::{SinkExt, StreamExt};
+use rand::Rng;
+use round_based::PartyIndex;
+use sha2::Sha256;
+use tokio::net::TcpListener;
+
+/// Link that breaks first `breaks` dialed connections after a few frames were sent over them
+struct FlakyLink {
+    inner: TcpLink,
+    breaks: AtomicUsize,
+}
+
+impl Link for FlakyLink {
+    fn dial(&self, j: PartyIndex) -> BoxFuture<'_, io::Result<Channel>> {
+        Box::pin(async move {
+            let mut channel = self.inner.dial(j).await?;
+            let broken = self
+                .breaks
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |b| b.checked_sub(1))
+                .is_ok();
+            if broken {
+                let mut frames_left = 3_usize;
+                channel.sink = Box::pin(channel.sink.with(move |frame: Vec<u8>| {
+                    future::ready(match frames_left.checked_sub(1) {
+                        Some(left) => {
+                            frames_left = left;
+                            Ok(frame)
+                        }
+                        None => Err(io::Error::from(io::ErrorKind::ConnectionReset)),
+                    })
+                }));
+            }
+            Ok(channel)
+        })
+    }
+
+    fn accept(&self) -> BoxFuture<'_, io::Result<Channel>> {
+        self.inner.accept()
+    }
+}
+
+/// Link that authenticates parties by the index they send in the first frame, a stand-in for
+/// client certificates
+struct IdentityLink {
+    inner: TcpLink,
+    i: PartyIndex,
+}
+
+impl Link for IdentityLink {
+    fn dial(&self, j: PartyIndex) -> BoxFuture<'_, io::Result<Channel>> {
+        Box::pin(async move {
+            let mut channel = self.inner.dial(j).await?;
+            channel.sink.send(self.i.to_be_bytes().to_vec()).await?;
+            Ok(channel.authenticated(j))
+        })
+    }
+
+    fn accept(&self) -> BoxFuture<'_, io::Result<Channel>> {
+        Box::pin(async move {
+            let mut channel = self.inner.accept().await?;
+            let identity = channel
+                .stream
+                .next()
+                .await
+                .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))??;
+            let identity = identity
+                .try_into()
+                .map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+            Ok(channel.authenticated(PartyIndex::from_be_bytes(identity)))
+        })
+    }
+}
+
+async fn bind(n: u16) -> (Vec<TcpListener>, Vec<std::net::SocketAddr>) {
+    let mut listeners = vec![];
+    for _ in 0..n {
+        listeners.push(TcpListener::bind("127.0.0.1:0").await.unwrap());
+    }
+    let addrs = listeners
+        .iter()
+        .map(|listener| listener.local_addr().unwrap())
+        .collect();
+    (listeners, addrs)
+}
+
+async fn connect(n: u16, breaks: usize) -> Vec<Network> {
+    let (listeners, addrs) = bind(n).await;
+    future::try_join_all(listeners.into_iter().zip(0..).map(|(listener, i)| {
+        let link = FlakyLink {
+            inner: TcpLink::new(listener, addrs.clone()),
+            breaks: AtomicUsize::new(breaks),
+        };
+        Network::connect(link, i, n, Config::insecure_unauthenticated())
+    }))
+    .await
+    .expect("connect parties")
+}
+
+async fn sign(networks: &[Network]) {
+    let mut rng = rand_dev::DevRng::new();
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, 3, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let message_to_sign = DataToSign::digest::<Sha256>(b"signed over tcp");
+    let participants = &[0, 1, 2];
+
+    let signatures = future::try_join_all(networks.iter().zip(0..).map(|(network, i)| {
+        let party = network.party::<Msg<Secp256k1, Sha256>>("signing").unwrap();
+        let mut party_rng = rng.fork();
+        let share = &shares[usize::from(i)];
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    }))
+    .await
+    .expect("signing failed");
+
+    let public_key = shares[0].shared_public_key;
+    for signature in &signatures {
+        signature
+            .verify(&public_key, &message_to_sign)
+            .expect("signature is not valid");
+    }
+
+    // Phase can't be started twice
+    assert!(networks[0]
+        .party::<Msg<Secp256k1, Sha256>>("signing")
+        .is_err());
+}
+
+async fn signing_over_tcp(breaks: usize) {
+    let networks = connect(3, breaks).await;
+    sign(&networks).await;
+    for network in networks {
+        network.shutdown().await;
+    }
+}
+
+#[tokio::test]
+async fn signing_works_over_tcp() {
+    signing_over_tcp(0).await
+}
+
+#[tokio::test]
+async fn signing_survives_lost_connection() {
+    signing_over_tcp(1).await
+}
+
+#[tokio::test]
+async fn party_cannot_claim_index_of_another_party() {
+    let n = 3;
+    let (listeners, addrs) = bind(n).await;
+    let networks = future::try_join_all(listeners.into_iter().zip(0..).map(|(listener, i)| {
+        let link = IdentityLink {
+            inner: TcpLink::new(listener, addrs.clone()),
+            i,
+        };
+        Network::connect(link, i, n, Config::default())
+    }));
+
+    // Party 2 connects to party 0 pretending to be party 1
+    let impersonate = async {
+        let stream = tokio::net::TcpStream::connect(addrs[0]).await.unwrap();
+        let mut channel = Channel::length_delimited(stream, default_max_frame_size(n.into()));
+        channel
+            .sink
+            .send(2u16.to_be_bytes().to_vec())
+            .await
+            .unwrap();
+        let hello = serde_json::json!({"Hello": {"party": 1, "received": 0}});
+        channel
+            .sink
+            .send(serde_json::to_vec(&hello).unwrap())
+            .await
+            .unwrap();
+        // Connection is dropped without a reply
+        let reply = tokio::time::timeout(std::time::Duration::from_secs(10), channel.stream.next())
+            .await
+            .expect("connection wasn't dropped");
+        assert!(!matches!(reply, Some(Ok(_))), "impersonator got a reply");
+    };
+
+    let (networks, ()) = future::join(networks, impersonate).await;
+    let networks = networks.expect("connect parties");
+    sign(&networks).await;
+    for network in networks {
+        network.shutdown().await;
+    }
+}
+
+#[tokio::test]
+async fn unauthenticated_connections_are_refused_by_default() {
+    let n = 2;
+    let (listeners, addrs) = bind(n).await;
+    let config = Config {
+        connect_timeout: std::time::Duration::from_secs(1),
+        ..Default::default()
+    };
+    let results = future::join_all(listeners.into_iter().zip(0..).map(|(listener, i)| {
+        Network::connect(TcpLink::new(listener, addrs.clone()), i, n, config.clone())
+    }))
+    .await;
+    for result in results {
+        let err = result.err().expect("unauthenticated parties got connected");
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+}
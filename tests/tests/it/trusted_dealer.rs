@@ -69,6 +69,127 @@ mod test {
         }
     }
 
+    #[test]
+    fn trusted_dealer_deals_large_committee<E: Curve>() {
+        let mut rng = DevRng::new();
+        let (t, n) = (67, 100);
+
+        let sk = NonZero::<SecretScalar<_>>::random(&mut rng);
+        let shares = trusted_dealer::builder::<E, DummyLevel>(n)
+            .set_threshold(Some(t))
+            .set_shared_secret_key(sk.clone())
+            .generate_shares(&mut rng)
+            .unwrap();
+        assert_eq!(shares.len(), usize::from(n));
+
+        let t_shares = shares
+            .choose_multiple(&mut rng, t.into())
+            .cloned()
+            .collect::<Vec<_>>();
+        let sk_reconstructed = reconstruct_secret_key(&t_shares).unwrap();
+        assert_eq!(
+            Point::generator() * &sk_reconstructed,
+            Point::generator() * &sk
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn trusted_dealer_on_dedicated_thread_pool<E: Curve>() {
+        use rand::SeedableRng;
+
+        let seed: [u8; 32] = DevRng::new().gen();
+        let n = 5;
+        let pool = cggmp21::rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+
+        let shares = trusted_dealer::builder::<E, DummyLevel>(n)
+            .set_thread_pool(&pool)
+            .generate_shares(&mut DevRng::from_seed(seed))
+            .unwrap();
+        assert_eq!(shares.len(), usize::from(n));
+
+        // Output is determined by the rng regardless of the pool
+        let expected = trusted_dealer::builder::<E, DummyLevel>(n)
+            .generate_shares(&mut DevRng::from_seed(seed))
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&shares).unwrap(),
+            serde_json::to_value(&expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn signers_public_shares_sum_up_to_public_key<E: Curve>() {
+        let mut rng = DevRng::new();
+        let n = 5;
+
+        for t in [None, Some(2), Some(3), Some(5)] {
+            let shares = trusted_dealer::builder::<E, DummyLevel>(n)
+                .set_threshold(t)
+                .generate_core_shares(&mut rng)
+                .unwrap();
+            let key_info = &shares[0].key_info;
+            let t = t.unwrap_or(n);
+
+            for k in t..=n {
+                let mut signers = (0..n).collect::<Vec<_>>();
+                signers.shuffle(&mut rng);
+                signers.truncate(k.into());
+
+                let public_shares = key_info.signers_public_shares(&signers).unwrap();
+                assert_eq!(public_shares.len(), signers.len());
+                assert_eq!(
+                    public_shares.iter().sum::<Point<E>>(),
+                    *key_info.shared_public_key
+                );
+            }
+
+            // Too few signers
+            let signers = (0..t - 1).collect::<Vec<_>>();
+            assert!(key_info.signers_public_shares(&signers).is_err());
+            // Repeated signer
+            let mut signers = (0..t).collect::<Vec<_>>();
+            signers[0] = signers[1];
+            assert!(key_info.signers_public_shares(&signers).is_err());
+            // Signer index is out of bounds
+            let mut signers = (0..t).collect::<Vec<_>>();
+            signers[0] = n;
+            assert!(key_info.signers_public_shares(&signers).is_err());
+        }
+    }
+
+    #[test]
+    fn decryption_key_is_cached<E: Curve>() {
+        let mut rng = DevRng::new();
+        let shares = trusted_dealer::builder::<E, DummyLevel>(3)
+            .generate_shares(&mut rng)
+            .unwrap();
+
+        for share in &shares {
+            let i = usize::from(share.core.i);
+            let dec = share.aux.decryption_key().unwrap();
+            assert!(matches!(dec, std::borrow::Cow::Borrowed(_)));
+            assert_eq!(dec.n(), &share.aux.parties[i].N);
+
+            // Cache is reused by subsequent calls and shared between clones
+            let dec2 = share.aux.decryption_key().unwrap();
+            assert!(std::ptr::eq(&*dec, &*dec2));
+            let cloned = share.clone();
+            assert!(std::ptr::eq(&*dec, &*cloned.aux.decryption_key().unwrap()));
+
+            // Stale cache is not used once primes are modified
+            let mut aux = share.aux.clone();
+            std::mem::swap(&mut aux.p, &mut aux.q);
+            let dec3 = aux.decryption_key().unwrap();
+            assert!(matches!(dec3, std::borrow::Cow::Owned(_)));
+            assert_eq!(dec3.p(), &aux.p);
+            assert_eq!(dec3.n(), &share.aux.parties[i].N);
+        }
+    }
+
     #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
     mod secp256r1 {}
     #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
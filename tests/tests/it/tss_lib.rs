@@ -0,0 +1,119 @@
+use cggmp21::{
+    generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar},
+    key_share::VssSetup,
+    rug::{integer::Order, Integer},
+    security_level::SecurityLevel128,
+    supported_curves::{Secp256k1, Secp256r1},
+    trusted_dealer,
+};
+
+/// Go encodes `big.Int` as JSON number of arbitrary length
+fn big_int(bytes: &[u8]) -> String {
+    Integer::from_digits(bytes, Order::Msf).to_string()
+}
+
+fn scalar<E: Curve>(x: &NonZero<Scalar<E>>) -> String {
+    let x: &Scalar<E> = x.as_ref();
+    big_int(x.to_be_bytes().as_bytes())
+}
+
+fn secret_scalar<E: Curve>(x: &NonZero<SecretScalar<E>>) -> Vec<u8> {
+    let x: &SecretScalar<E> = x.as_ref();
+    x.as_ref().to_be_bytes().as_bytes().to_vec()
+}
+
+fn ec_point<E: Curve>(curve: &str, point: &NonZero<Point<E>>) -> String {
+    let bytes = point.to_bytes(false);
+    let (x, y) = bytes[1..].split_at((bytes.len() - 1) / 2);
+    format!(
+        r#"{{"Curve":"{curve}","Coords":[{},{}]}}"#,
+        big_int(x),
+        big_int(y)
+    )
+}
+
+fn save_data<E: Curve>(
+    curve: &str,
+    x: &NonZero<SecretScalar<E>>,
+    share_id: &str,
+    ks: &[String],
+    public_shares: &[NonZero<Point<E>>],
+    shared_public_key: &NonZero<Point<E>>,
+) -> Vec<u8> {
+    let big_xj = public_shares
+        .iter()
+        .map(|p| ec_point(curve, p))
+        .collect::<Vec<_>>();
+    format!(
+        r#"{{"Xi":{},"ShareID":{share_id},"Ks":[{}],"BigXj":[{}],"ECDSAPub":{}}}"#,
+        big_int(&secret_scalar(x)),
+        ks.join(","),
+        big_xj.join(","),
+        ec_point(curve, shared_public_key),
+    )
+    .into_bytes()
+}
+
+fn import_key_shares<E: Curve>(curve: &str) {
+    let mut rng = rand_dev::DevRng::new();
+    let (t, n) = (2, 3);
+    let shares = trusted_dealer::builder::<E, SecurityLevel128>(n)
+        .set_threshold(Some(t))
+        .generate_core_shares(&mut rng)
+        .unwrap();
+
+    for share in &shares {
+        let vss = share.vss_setup.as_ref().unwrap();
+        let ks = vss.I.iter().map(scalar).collect::<Vec<_>>();
+        let save_data = save_data(
+            curve,
+            &share.x,
+            &ks[usize::from(share.i)],
+            &ks,
+            &share.public_shares,
+            &share.shared_public_key,
+        );
+
+        let imported =
+            cggmp21::tss_lib::import_key_share::<E>(&save_data, t - 1).expect("import failed");
+        assert_eq!(imported.i, share.i);
+        assert_eq!(imported.shared_public_key, share.shared_public_key);
+        assert_eq!(imported.public_shares, share.public_shares);
+        // tss-lib doesn't store VSS commitments, so they can't be recovered on import
+        let expected_vss = VssSetup {
+            commitments: None,
+            ..vss.clone()
+        };
+        assert_eq!(imported.vss_setup, Some(expected_vss));
+        assert_eq!(secret_scalar(&imported.x), secret_scalar(&share.x));
+    }
+}
+
+#[test]
+fn import_secp256k1_key_shares() {
+    import_key_shares::<Secp256k1>("secp256k1")
+}
+
+#[test]
+fn import_secp256r1_key_shares() {
+    import_key_shares::<Secp256r1>("nist256p1")
+}
+
+#[test]
+fn key_share_on_another_curve_is_rejected() {
+    let mut rng = rand_dev::DevRng::new();
+    let shares = trusted_dealer::builder::<Secp256k1, SecurityLevel128>(2)
+        .set_threshold(Some(2))
+        .generate_core_shares(&mut rng)
+        .unwrap();
+    let share = &shares[0];
+    let save_data = save_data(
+        "secp256k1",
+        &share.x,
+        "1",
+        &["1".to_owned(), "2".to_owned()],
+        &share.public_shares,
+        &share.shared_public_key,
+    );
+    assert!(cggmp21::tss_lib::import_key_share::<Secp256r1>(&save_data, 1).is_err());
+}
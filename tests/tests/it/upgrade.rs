@@ -0,0 +1,111 @@
+use cggmp21::{
+    define_security_level,
+    key_refresh::AuxOnlyMsg,
+    key_share::{ensure_same_key, upgrade_security_level, Validate},
+    policy::KeyUsagePolicy,
+    security_level::SecurityLevel128,
+    supported_curves::Secp256k1,
+    trusted_dealer, ExecutionId,
+};
+use rand::Rng;
+use round_based::simulation::Simulation;
+use sha2::Sha256;
+
+/// Low security level which key shares are upgraded from
+#[derive(Clone)]
+struct LowLevel;
+define_security_level!(LowLevel {
+    security_bits = 64,
+    epsilon = 128,
+    ell = 128,
+    ell_prime = 128,
+    m = 128,
+    q = (cggmp21::rug::Integer::ONE.clone() << 128) - 1,
+});
+
+#[tokio::test]
+async fn key_shares_are_upgraded_to_higher_security_level() {
+    let mut rng = rand_dev::DevRng::new();
+    let (t, n) = (2, 3);
+
+    let mut policy = KeyUsagePolicy::default();
+    policy
+        .allowed_curves
+        .push(<Secp256k1 as generic_ec::Curve>::CURVE_NAME.to_owned());
+    let old_shares = trusted_dealer::builder::<Secp256k1, LowLevel>(n)
+        .set_threshold(Some(t))
+        .generate_shares(&mut rng)
+        .unwrap()
+        .into_iter()
+        .map(|share| {
+            let mut share = share.into_inner();
+            share.policy = Some(policy.clone());
+            share.validate().unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    // Aux info generation at the new security level
+    let mut primes = cggmp21_tests::CACHED_PRIMES.iter::<SecurityLevel128>();
+    let mut simulation = Simulation::<AuxOnlyMsg<Sha256, SecurityLevel128>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let outputs = (0..n).map(|i| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        let pregenerated_primes = primes.next().expect("Can't fetch primes");
+        async move {
+            cggmp21::aux_info_gen(eid, i, n, pregenerated_primes)
+                .dangerous_allow_blum_primes(true)
+                .start(&mut party_rng, party)
+                .await
+        }
+    });
+    let auxes = futures::future::try_join_all(outputs)
+        .await
+        .expect("aux gen failed");
+
+    // Aux info of another party can't be bound
+    assert!(upgrade_security_level(old_shares[0].clone(), auxes[1].clone()).is_err());
+
+    let new_shares = old_shares
+        .iter()
+        .zip(auxes)
+        .map(|(old, aux)| {
+            let new = upgrade_security_level(old.clone(), aux).unwrap();
+            ensure_same_key(old, &new).unwrap();
+            let new_policy = new.policy.as_ref().expect("policy is lost on upgrade");
+            assert_eq!(new_policy.allowed_curves, policy.allowed_curves);
+            new
+        })
+        .collect::<Vec<_>>();
+    assert!(ensure_same_key(&old_shares[0], &new_shares[1]).is_err());
+
+    // Security level can't be downgraded
+    let low_aux = old_shares[0].aux.clone();
+    let low_aux = Validate::validate(low_aux).unwrap();
+    assert!(upgrade_security_level(new_shares[0].clone(), low_aux).is_err());
+
+    // Upgraded key shares can be used for signing
+    let mut simulation = Simulation::<cggmp21::signing::msg::Msg<Secp256k1, Sha256>>::new();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let message_to_sign = cggmp21::signing::DataToSign::digest::<Sha256>(b"message");
+    let participants = &[0, 2];
+
+    let outputs = (0..).zip(participants).map(|(i, &j)| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        let share = &new_shares[usize::from(j)];
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    });
+    let signatures = futures::future::try_join_all(outputs)
+        .await
+        .expect("signing failed");
+    signatures[0]
+        .verify(&old_shares[0].core.shared_public_key, &message_to_sign)
+        .expect("signature is not valid");
+}
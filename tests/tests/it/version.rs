@@ -0,0 +1,108 @@
+#[generic_tests::define(attrs(tokio::test))]
+mod generic {
+    use futures::SinkExt;
+    use generic_ec::Curve;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+    use rand_dev::DevRng;
+    use round_based::simulation::Simulation;
+    use round_based::{Delivery, Mpc, MpcParty, Outgoing};
+    use sha2::Sha256;
+
+    use cggmp21::error_code::ErrorCode;
+    use cggmp21::keygen::NonThresholdMsg;
+    use cggmp21::version::{self, Envelope, Payload, ProtocolVersion};
+    use cggmp21::{security_level::SecurityLevel128, ExecutionId};
+
+    #[tokio::test]
+    async fn keygen_with_negotiation<E: Curve>() {
+        let mut rng = DevRng::new();
+        let n = 3;
+
+        let mut simulation =
+            Simulation::<Envelope<NonThresholdMsg<E, SecurityLevel128, Sha256>>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut outputs = vec![];
+        for i in 0..n {
+            let party = simulation.add_party();
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+
+            outputs.push(async move {
+                let (party, negotiated) = version::negotiate(party, i, n)
+                    .await
+                    .expect("negotiation failed");
+                assert_eq!(negotiated, ProtocolVersion::CURRENT);
+
+                cggmp21::keygen::<E>(eid, i, n)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+
+        let key_shares = futures::future::try_join_all(outputs)
+            .await
+            .expect("keygen failed");
+        for key_share in &key_shares {
+            assert_eq!(key_share.shared_public_key, key_shares[0].shared_public_key);
+        }
+    }
+
+    #[tokio::test]
+    async fn incompatible_version_is_detected<E: Curve>() {
+        let mut rng = DevRng::new();
+        let n = 3;
+
+        let mut simulation =
+            Simulation::<Envelope<NonThresholdMsg<E, SecurityLevel128, Sha256>>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut outputs = vec![];
+        for i in 0..n - 1 {
+            let party = simulation.add_party();
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+
+            outputs.push(async move {
+                let (party, _) = version::negotiate(party, i, n).await?;
+                let _ = cggmp21::keygen::<E>(eid, i, n)
+                    .start(&mut party_rng, party)
+                    .await;
+                Ok::<_, version::VersionError>(())
+            })
+        }
+
+        // Last party runs next major version
+        let incompatible = ProtocolVersion {
+            major: ProtocolVersion::CURRENT.major + 1,
+            minor: 0,
+        };
+        assert!(!ProtocolVersion::CURRENT.is_compatible_with(&incompatible));
+        let MpcParty { delivery, .. } = simulation.add_party().into_party();
+        let (_incomings, mut outgoings) = delivery.split();
+        outgoings
+            .send(Outgoing::broadcast(Envelope {
+                version: incompatible,
+                payload: Payload::Hello,
+            }))
+            .await
+            .unwrap();
+
+        for result in futures::future::join_all(outputs).await {
+            let err = result.expect_err("negotiation must fail");
+            assert_eq!(err.incompatible_party(), Some((n - 1, incompatible)));
+            assert_eq!(err.code(), ErrorCode::IncompatibleVersion);
+            assert_eq!(err.report().parties, [n - 1]);
+        }
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}